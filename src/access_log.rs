@@ -0,0 +1,335 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use tokio::spawn;
+
+use crate::{
+    error::{AppError, Result},
+    event::{AppEventType, Sender},
+    storage::Storage,
+};
+
+// Only the latest log files are analyzed, as the log prefixes can have a huge number of small files
+const MAX_ACCESS_LOG_FILES: usize = 1000;
+
+const TOP_ENTRIES_LIMIT: usize = 20;
+
+// A record of S3 server access logs, only the fields used for the summary
+// https://docs.aws.amazon.com/AmazonS3/latest/userguide/LogFormat.html
+#[derive(Debug, PartialEq, Eq)]
+struct AccessLogRecord<'a> {
+    time: &'a str,
+    requester: &'a str,
+    operation: &'a str,
+    key: &'a str,
+    http_status: &'a str,
+    error_code: &'a str,
+    bytes_sent: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessLogSection {
+    Operations,
+    Keys,
+    Requesters,
+    Errors,
+}
+
+impl AccessLogSection {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Operations => "Operations",
+            Self::Keys => "Top keys",
+            Self::Requesters => "Top requesters",
+            Self::Errors => "Errors",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccessLogSummary {
+    pub file_count: usize,
+    pub request_count: usize,
+    pub invalid_line_count: usize,
+    pub bytes_sent: usize,
+    pub first_time: Option<String>,
+    pub last_time: Option<String>,
+    // (section, [(name, count)]) sorted by count in descending order
+    pub sections: Vec<(AccessLogSection, Vec<(String, usize)>)>,
+}
+
+impl AccessLogSummary {
+    pub fn error_count(&self) -> usize {
+        self.sections
+            .iter()
+            .filter(|(section, _)| *section == AccessLogSection::Errors)
+            .flat_map(|(_, entries)| entries.iter().map(|(_, count)| count))
+            .sum()
+    }
+}
+
+// Aggregates the log files one by one, so that the whole logs are not kept in memory
+#[derive(Debug, Default)]
+pub struct AccessLogAggregator {
+    file_count: usize,
+    request_count: usize,
+    invalid_line_count: usize,
+    bytes_sent: usize,
+    first_time: Option<String>,
+    last_time: Option<String>,
+    counts: HashMap<AccessLogSection, HashMap<String, usize>>,
+}
+
+impl AccessLogAggregator {
+    pub fn add_file(&mut self, bytes: &[u8]) {
+        self.file_count += 1;
+        for line in String::from_utf8_lossy(bytes).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_record(line) {
+                Some(record) => self.add_record(record),
+                None => self.invalid_line_count += 1,
+            }
+        }
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.file_count
+    }
+
+    fn add_record(&mut self, record: AccessLogRecord) {
+        self.request_count += 1;
+        self.bytes_sent += record.bytes_sent;
+
+        // the timestamps are compared as is, the log files are written in UTC
+        if let Some(time) = parse_time(record.time) {
+            if self.first_time.as_ref().map_or(true, |t| time < *t) {
+                self.first_time = Some(time.clone());
+            }
+            if self.last_time.as_ref().map_or(true, |t| time > *t) {
+                self.last_time = Some(time);
+            }
+        }
+
+        self.count(AccessLogSection::Operations, record.operation);
+        if record.key != "-" {
+            self.count(AccessLogSection::Keys, record.key);
+        }
+        self.count(AccessLogSection::Requesters, record.requester);
+        if record.http_status.starts_with(['4', '5']) {
+            let error = format!("{} {}", record.http_status, record.error_code);
+            self.count(AccessLogSection::Errors, &error);
+        }
+    }
+
+    fn count(&mut self, section: AccessLogSection, name: &str) {
+        *self
+            .counts
+            .entry(section)
+            .or_default()
+            .entry(name.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn summary(mut self) -> AccessLogSummary {
+        let sections = [
+            AccessLogSection::Operations,
+            AccessLogSection::Keys,
+            AccessLogSection::Requesters,
+            AccessLogSection::Errors,
+        ]
+        .into_iter()
+        .map(|section| {
+            let counts = self.counts.remove(&section).unwrap_or_default();
+            (section, top_entries(counts))
+        })
+        .collect();
+        AccessLogSummary {
+            file_count: self.file_count,
+            request_count: self.request_count,
+            invalid_line_count: self.invalid_line_count,
+            bytes_sent: self.bytes_sent,
+            first_time: self.first_time,
+            last_time: self.last_time,
+            sections,
+        }
+    }
+}
+
+// The log files are downloaded concurrently, and each file is aggregated and dropped as soon as it is downloaded.
+pub async fn analyze_access_logs(
+    client: Arc<dyn Storage>,
+    tx: &Sender,
+    bucket: &str,
+    prefix: &str,
+    max_concurrent_requests: usize,
+) -> Result<AccessLogSummary> {
+    let mut files = client.list_all_object_summaries(bucket, prefix).await?;
+    files.retain(|obj| !obj.key.ends_with('/'));
+    // the log file names start with the date, so the latest files are the last ones
+    files.sort_by(|a, b| a.key.cmp(&b.key));
+    let files = &files[files.len().saturating_sub(MAX_ACCESS_LOG_FILES)..];
+
+    let total = files.len();
+    let mut targets = files.iter();
+    let mut in_flight = VecDeque::new();
+    let mut aggregator = AccessLogAggregator::default();
+    loop {
+        while in_flight.len() < max_concurrent_requests {
+            let Some(file) = targets.next() else {
+                break;
+            };
+            let client = client.clone();
+            let bucket = bucket.to_string();
+            let key = file.key.clone();
+            let size_byte = file.size_byte;
+            let handle = spawn(async move {
+                client
+                    .download_object(&bucket, &key, None, None, size_byte, Box::new(|_| {}))
+                    .await
+            });
+            in_flight.push_back(handle);
+        }
+        let Some(handle) = in_flight.pop_front() else {
+            break;
+        };
+
+        let msg = format!(
+            "Analyzing access logs ({} out of {}, {} concurrent requests)",
+            aggregator.file_count() + 1,
+            total,
+            in_flight.len() + 1,
+        );
+        tx.send(AppEventType::NotifyInfo(msg));
+
+        let obj = handle
+            .await
+            .unwrap_or_else(|e| Err(AppError::new("Failed to download access log", e)))?;
+        aggregator.add_file(&obj.bytes);
+    }
+    Ok(aggregator.summary())
+}
+
+fn top_entries(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut entries: Vec<(String, usize)> = counts.into_iter().collect();
+    entries.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+    entries.truncate(TOP_ENTRIES_LIMIT);
+    entries
+}
+
+// The fields are separated by spaces, the time is enclosed in `[]` and some fields are quoted
+fn split_fields(line: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let end = match rest.as_bytes()[0] {
+            b'[' => rest.find(']').map(|i| i + 1),
+            b'"' => rest[1..].find('"').map(|i| i + 2),
+            _ => rest.find(' '),
+        }
+        .unwrap_or(rest.len());
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    fields
+}
+
+fn parse_record(line: &str) -> Option<AccessLogRecord<'_>> {
+    let fields = split_fields(line);
+    if fields.len() < 12 {
+        return None;
+    }
+    let time = fields[2];
+    if !time.starts_with('[') {
+        return None;
+    }
+    Some(AccessLogRecord {
+        time,
+        requester: fields[4],
+        operation: fields[6],
+        key: fields[7],
+        http_status: fields[9],
+        error_code: fields[10],
+        bytes_sent: fields[11].parse().unwrap_or_default(),
+    })
+}
+
+// [06/Feb/2019:00:00:38 +0000] -> 2019-02-06 00:00:38
+fn parse_time(s: &str) -> Option<String> {
+    let s = s.trim_start_matches('[').trim_end_matches(']');
+    let (datetime, _) = s.split_once(' ').unwrap_or((s, ""));
+    chrono::NaiveDateTime::parse_from_str(datetime, "%d/%b/%Y:%H:%M:%S")
+        .ok()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = r#"79a5 bucket1 [06/Feb/2019:00:00:38 +0000] 192.0.2.3 arn:aws:iam::123456789012:user/alice 3E57427F3EXAMPLE REST.GET.OBJECT a/b.txt "GET /bucket1/a/b.txt HTTP/1.1" 200 - 113 113 7 - "-" "S3Console/0.4" - s9lz= SigV4 ECDHE-RSA-AES128-GCM-SHA256 AuthHeader bucket1.s3.us-west-1.amazonaws.com TLSv1.2 - -
+79a5 bucket1 [06/Feb/2019:00:01:00 +0000] 192.0.2.3 arn:aws:iam::123456789012:user/bob 891CE47D2EXAMPLE REST.GET.OBJECT a/b.txt "GET /bucket1/a/b.txt HTTP/1.1" 403 AccessDenied 243 - 20 - "-" "aws-cli/2.0" - s9lz= SigV4 ECDHE-RSA-AES128-GCM-SHA256 AuthHeader bucket1.s3.us-west-1.amazonaws.com TLSv1.2 - -
+79a5 bucket1 [05/Feb/2019:23:59:59 +0000] 192.0.2.3 arn:aws:iam::123456789012:user/alice A1206F460EXAMPLE REST.GET.BUCKET - "GET /bucket1?list-type=2 HTTP/1.1" 200 - - - 11 10 "-" "aws-cli/2.0" - s9lz= SigV4 ECDHE-RSA-AES128-GCM-SHA256 AuthHeader bucket1.s3.us-west-1.amazonaws.com TLSv1.2 - -
+invalid line
+"#;
+
+    #[test]
+    fn test_split_fields() {
+        let line = r#"a b [06/Feb/2019:00:00:38 +0000] "GET /b HTTP/1.1" - "x y""#;
+        let actual = split_fields(line);
+        let expected = vec![
+            "a",
+            "b",
+            "[06/Feb/2019:00:00:38 +0000]",
+            "\"GET /b HTTP/1.1\"",
+            "-",
+            "\"x y\"",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_access_log_aggregator() {
+        let mut aggregator = AccessLogAggregator::default();
+        aggregator.add_file(LOG.as_bytes());
+        let summary = aggregator.summary();
+
+        assert_eq!(summary.file_count, 1);
+        assert_eq!(summary.request_count, 3);
+        assert_eq!(summary.invalid_line_count, 1);
+        assert_eq!(summary.bytes_sent, 113 + 243);
+        assert_eq!(summary.first_time.as_deref(), Some("2019-02-05 23:59:59"));
+        assert_eq!(summary.last_time.as_deref(), Some("2019-02-06 00:01:00"));
+        assert_eq!(summary.error_count(), 1);
+
+        let entries = |section: AccessLogSection| -> Vec<(&str, usize)> {
+            let (_, entries) = summary
+                .sections
+                .iter()
+                .find(|(s, _)| *s == section)
+                .unwrap();
+            entries.iter().map(|(n, c)| (n.as_str(), *c)).collect()
+        };
+        assert_eq!(
+            entries(AccessLogSection::Operations),
+            vec![("REST.GET.OBJECT", 2), ("REST.GET.BUCKET", 1)]
+        );
+        assert_eq!(entries(AccessLogSection::Keys), vec![("a/b.txt", 2)]);
+        assert_eq!(
+            entries(AccessLogSection::Requesters),
+            vec![
+                ("arn:aws:iam::123456789012:user/alice", 2),
+                ("arn:aws:iam::123456789012:user/bob", 1)
+            ]
+        );
+        assert_eq!(
+            entries(AccessLogSection::Errors),
+            vec![("403 AccessDenied", 1)]
+        );
+    }
+}
@@ -1,28 +1,90 @@
+use chrono::{DateTime, Local};
+use laurier::{key_code, key_code_char};
+use md5::{Digest, Md5};
 use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
     layout::{Constraint, Layout, Rect},
     style::Stylize,
-    widgets::Block,
+    widgets::{Block, Padding, Paragraph},
     Frame,
 };
-use std::{path::PathBuf, rc::Rc, sync::Arc};
-use tokio::spawn;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{spawn, task::AbortHandle};
 
 use crate::{
-    client::Client,
+    access_log::analyze_access_logs,
+    aws_profile::load_profile_names,
+    bucket_pin::PinnedBuckets,
     color::ColorTheme,
-    config::Config,
+    config::{Config, DownloadVerifyType},
+    download_list::DownloadObjectList,
     environment::Environment,
     error::{AppError, Result},
+    etag_index::ETagIndex,
     event::{
-        AppEventType, CompleteDownloadObjectResult, CompleteInitializeResult,
-        CompleteLoadObjectDetailResult, CompleteLoadObjectVersionsResult,
-        CompleteLoadObjectsResult, CompletePreviewObjectResult, CompleteReloadBucketsResult,
-        CompleteReloadObjectsResult, Sender,
+        AppEventType, CompleteAnalyzeAccessLogsResult, CompleteApplyObjectTagsResult,
+        CompleteChangeStorageClassResult, CompleteCheckObjectLockResult,
+        CompleteCompareObjectsResult, CompleteCompareVersionPreviewResult,
+        CompleteComputeObjectMd5Result, CompleteCopyObjectResult, CompleteCreateBucketResult,
+        CompleteCreateFolderResult, CompleteDeleteBucketResult, CompleteDeleteObjectsResult,
+        CompleteDownloadObjectResult, CompleteDownloadObjectsResult,
+        CompleteFindCleanupObjectsResult, CompleteFindDuplicateObjectsResult,
+        CompleteFindMultipartUploadResult, CompleteInitializeResult,
+        CompleteLoadAllDownloadObjectListResult, CompleteLoadBucketMetricsResult,
+        CompleteLoadInventoryResult, CompleteLoadObjectDetailResult,
+        CompleteLoadObjectLifecycleResult, CompleteLoadObjectVersionsResult,
+        CompleteLoadObjectsResult, CompleteOpenLocationResult, CompletePreviewObjectResult,
+        CompleteReloadBucketsResult, CompleteReloadObjectsResult, CompleteRemoveDeleteMarkerResult,
+        CompleteRenameObjectsResult, CompleteSetObjectLegalHoldResult, CompleteSyncObjectsResult,
+        CompleteUpdateObjectMetadataResult, CompleteUploadClipboardImageResult,
+        CompleteUploadObjectResult, Sender,
+    },
+    file::{
+        clipboard_image_file_name, copy_to_clipboard, download_temp_path, file_md5_hex,
+        local_file_info, read_clipboard, read_clipboard_image, save_binary, save_error_log,
+        unique_file_path, ArchiveWriter, TempFile,
+    },
+    format::{format_count, format_remaining},
+    inventory::load_inventory,
+    jobs::{AbortOnDrop, JobId, JobProgress, JobQueue, JobSpec, JobStatus},
+    key_macro::KeyMacro,
+    lifecycle::evaluate_lifecycle_rules,
+    location::{parse_s3_location, S3Location},
+    multipart::{
+        download_in_parts, plan_parts, reusable_parts, stream_part_size, upload_in_parts,
+        upload_stream, MultipartUpload, MultipartUploadTarget,
+    },
+    object::{
+        compare_objects, find_cleanup_objects, find_duplicate_objects, insert_dir_item, AppObjects,
+        BucketItem, CopyFailure, DownloadFailure, DownloadLayoutType, DownloadObjectInfo,
+        DownloadObjectsOptions, DownloadObjectsOutcome, DownloadOutputType, DownloadSkipType,
+        FileDetail, FileVersion, LockedObject, ObjectItem, ObjectKey, ObjectMetadata, RawObject,
+        StorageClassType, TagFailure,
+    },
+    pages::{
+        bucket_list::{BucketListPage, BUCKET_REGIONS},
+        object_detail::ObjectDetailPage,
+        object_list::{ObjectListPage, FILTER_DEBOUNCE_DELAY},
+        object_preview::ObjectPreviewPage,
+        page::{Page, PageId, PageStack},
+    },
+    rename::RenameTarget,
+    report::BulkOperationReport,
+    schedule::{ScheduledTaskOutcome, ScheduledTaskType, Scheduler},
+    sso::{is_sso_session_expired, sso_login, SsoLoginPrompt},
+    storage::{Progress, Storage, StorageFactory, StorageTarget},
+    tagging::merge_tags,
+    watch::{WatchSession, WATCH_UPLOAD_DELAY},
+    widget::{
+        BucketSwitcherDialog, BucketSwitcherDialogState, ConfirmDialog, Header, LoadingDialog,
+        ProgressBar, Status, StatusType, SwitcherDialog, SwitcherDialogState,
     },
-    file::{copy_to_clipboard, save_binary, save_error_log},
-    object::{AppObjects, FileDetail, ObjectItem, RawObject},
-    pages::page::{Page, PageStack},
-    widget::{Header, LoadingDialog, Status, StatusType},
 };
 
 #[derive(Debug)]
@@ -34,6 +96,45 @@ pub enum Notification {
     Error(String),
 }
 
+// Shown before deleting the objects when some of them are locked
+#[derive(Debug)]
+struct ObjectLockWarning {
+    bucket: String,
+    // keys of the objects that are not locked, which are deleted if confirmed
+    keys: Vec<String>,
+    locked: Vec<LockedObject>,
+}
+
+// The operation which blocks the app while it is running, shown in the loading dialog
+#[derive(Debug)]
+struct LoadingOperation {
+    name: &'static str,
+    started_at: Instant,
+    // the transferred and the total size in bytes, shown in the footer
+    transferred: Option<(usize, usize)>,
+}
+
+impl LoadingOperation {
+    fn new(name: &'static str) -> LoadingOperation {
+        LoadingOperation {
+            name,
+            started_at: Instant::now(),
+            transferred: None,
+        }
+    }
+}
+
+// The page to open first, instead of the default behavior at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartPage {
+    // stay on the bucket list even if only one bucket is listed or a location is specified
+    Buckets,
+    // do not open the object detail even if the location points to an object
+    ObjectList,
+    // open the preview of the object which the location points to
+    Preview,
+}
+
 #[derive(Debug, Default)]
 pub struct AppContext {
     pub config: Config,
@@ -51,39 +152,110 @@ impl AppContext {
 pub struct App {
     pub page_stack: PageStack,
     app_objects: AppObjects,
-    client: Option<Arc<Client>>,
+    client: Option<Arc<dyn Storage>>,
+    initial_location: Option<S3Location>,
+    // cleared once the initial location is opened
+    start_page: Option<StartPage>,
     ctx: Rc<AppContext>,
     tx: Sender,
 
     notification: Notification,
-    is_loading: bool,
+    loading: Option<LoadingOperation>,
+    // the loading task that can be cancelled with Esc, and its progress
+    cancellable_task: Option<AbortHandle>,
+    loading_progress: Option<String>,
+    // the number of the storage requests running longer than `client.slow_request_threshold`
+    slow_requests: usize,
+    key_macro: KeyMacro,
+    bucket_switcher: Option<BucketSwitcherDialogState>,
+    // the profile and the region of the client, and the factory to rebuild the client with others (S3 only)
+    storage_target: StorageTarget,
+    storage_factory: Option<StorageFactory>,
+    profile_switcher: Option<SwitcherDialogState>,
+    region_switcher: Option<SwitcherDialogState>,
+    // the error caused by the expired SSO session, kept while asking whether to log in again
+    sso_login_confirm: Option<AppError>,
+    sso_login_prompt: Option<SsoLoginPrompt>,
+    object_lock_warning: Option<ObjectLockWarning>,
+    etag_index: ETagIndex,
+    pinned_buckets: PinnedBuckets,
+    // whether the key names are shown decoded, shared by the object list and detail pages
+    decode_key_names: bool,
+    // whether the buckets and objects matching `filters.hidden` are shown, shared by the list pages
+    show_hidden: bool,
+    watch_session: Option<WatchSession>,
+    scheduler: Scheduler,
+    jobs: JobQueue,
     width: usize,
     height: usize,
 }
 
 impl App {
     pub fn new(ctx: AppContext, tx: Sender, width: usize, height: usize) -> App {
+        let etag_index = match Config::etag_index_file_path() {
+            Ok(path) if ctx.config.ui.object_list.show_changes => ETagIndex::load(path),
+            _ => ETagIndex::default(),
+        };
+        let pinned_buckets = match Config::pinned_buckets_file_path() {
+            Ok(path) => PinnedBuckets::load(path),
+            Err(_) => PinnedBuckets::default(),
+        };
+        let decode_key_names = ctx.config.ui.object_list.decode_key_names;
         let ctx = Rc::new(ctx);
         App {
             app_objects: AppObjects::default(),
             page_stack: PageStack::new(Rc::clone(&ctx), tx.clone()),
             client: None,
+            initial_location: None,
+            start_page: None,
             ctx,
             tx,
             notification: Notification::None,
-            is_loading: true,
+            loading: Some(LoadingOperation::new("Loading buckets")),
+            cancellable_task: None,
+            loading_progress: None,
+            slow_requests: 0,
+            key_macro: KeyMacro::default(),
+            bucket_switcher: None,
+            storage_target: StorageTarget::default(),
+            storage_factory: None,
+            profile_switcher: None,
+            region_switcher: None,
+            sso_login_confirm: None,
+            sso_login_prompt: None,
+            object_lock_warning: None,
+            etag_index,
+            pinned_buckets,
+            decode_key_names,
+            show_hidden: false,
+            watch_session: None,
+            scheduler: Scheduler::default(),
+            jobs: JobQueue::default(),
             width,
             height,
         }
     }
 
+    pub fn set_storage_factory(&mut self, target: StorageTarget, factory: StorageFactory) {
+        self.storage_target = target;
+        self.storage_factory = Some(factory);
+    }
+
     pub fn resize(&mut self, width: usize, height: usize) {
         self.width = width;
         self.height = height;
     }
 
-    pub fn initialize(&mut self, client: Client, bucket: Option<String>) {
-        self.client = Some(Arc::new(client));
+    pub fn initialize(
+        &mut self,
+        client: Arc<dyn Storage>,
+        bucket: Option<String>,
+        location: Option<S3Location>,
+        start_page: Option<StartPage>,
+    ) {
+        self.client = Some(client);
+        self.initial_location = location;
+        self.start_page = start_page;
 
         let (client, tx) = self.unwrap_client_tx();
         spawn(async move {
@@ -101,11 +273,17 @@ impl App {
             Ok(CompleteInitializeResult { buckets }) => {
                 self.app_objects.set_bucket_items(buckets);
 
-                let bucket_list_page = Page::of_bucket_list(
+                let (client, _) = self.unwrap_client_tx();
+                let mut bucket_list_page = Page::of_bucket_list(
                     self.app_objects.get_bucket_items(),
+                    client.region(),
                     Rc::clone(&self.ctx),
                     self.tx.clone(),
                 );
+                if let Some(page) = bucket_list_page.get_mut::<BucketListPage>() {
+                    page.set_pinned_buckets(self.pinned_buckets.names());
+                    page.set_show_hidden(self.show_hidden);
+                }
                 self.page_stack.pop(); // remove initializing page
                 self.page_stack.push(bucket_list_page);
             }
@@ -116,18 +294,28 @@ impl App {
 
         let bucket_items_len = self.app_objects.get_bucket_items().len();
 
-        if bucket_items_len == 1 {
+        if let Some(location) = self.initial_location.take() {
+            // the location is opened only once at startup, not when reloading buckets
+            if bucket_items_len > 0 && self.start_page == Some(StartPage::Buckets) {
+                if let Some(page) = self.page_stack.current_mut::<BucketListPage>() {
+                    page.select_bucket(&location.bucket);
+                }
+            } else if bucket_items_len > 0 {
+                self.tx.send(AppEventType::OpenLocation(location));
+                return;
+            }
+        }
+
+        let start_page = self.start_page.take();
+        if bucket_items_len == 1 && start_page != Some(StartPage::Buckets) {
             // bucket name is specified, or if there is only one bucket, open it.
-            // since continues to load object, is_loading is not reset.
             self.bucket_list_move_down();
-        } else {
-            if bucket_items_len == 0 {
-                let (client, _) = self.unwrap_client_tx();
-                let msg = format!("No bucket found (region: {})", client.region());
-                self.tx.send(AppEventType::NotifyWarn(msg));
-            }
-            self.is_loading = false;
+        } else if bucket_items_len == 0 {
+            let (client, _) = self.unwrap_client_tx();
+            let msg = format!("No bucket found (region: {})", client.region());
+            self.tx.send(AppEventType::NotifyWarn(msg));
         }
+        self.finish_loading();
     }
 
     pub fn reload_buckets(&self) {
@@ -145,21 +333,17 @@ impl App {
     }
 
     pub fn bucket_list_move_down(&mut self) {
-        let bucket_page = self.page_stack.current_page().as_bucket_list();
+        let Some(bucket_page) = self.page_stack.current::<BucketListPage>() else {
+            return;
+        };
         let object_key = bucket_page.current_selected_object_key();
 
         if let Some(current_object_items) = self.app_objects.get_object_items(&object_key) {
             // object list has been already loaded
-            let object_list_page = Page::of_object_list(
-                current_object_items,
-                object_key,
-                Rc::clone(&self.ctx),
-                self.tx.clone(),
-            );
+            let object_list_page = self.new_object_list_page(current_object_items, object_key);
             self.page_stack.push(object_list_page);
         } else {
             self.tx.send(AppEventType::LoadObjects);
-            self.is_loading = true;
         }
     }
 
@@ -167,21 +351,28 @@ impl App {
         self.app_objects.clear_all();
 
         self.tx.send(AppEventType::ReloadBuckets);
-        self.is_loading = true;
+        self.start_loading("Loading buckets");
     }
 
     pub fn object_list_move_down(&mut self) {
-        let object_list_page = self.page_stack.current_page().as_object_list();
-        let selected = object_list_page.current_selected_item().to_owned();
+        let Some(object_list_page) = self.page_stack.current::<ObjectListPage>() else {
+            return;
+        };
+        // nothing to open on a group header
+        let (Some(selected), Some(current_object_key)) = (
+            object_list_page.current_selected_item(),
+            object_list_page.current_selected_object_key(),
+        ) else {
+            return;
+        };
 
         match selected {
             ObjectItem::File { .. } => {
-                let current_object_key = object_list_page.current_selected_object_key();
                 let detail = self.app_objects.get_object_detail(&current_object_key);
 
                 if let Some(detail) = detail {
                     // object detail has been already loaded
-                    let object_detail_page = Page::of_object_detail(
+                    let mut object_detail_page = Page::of_object_detail(
                         detail.clone(),
                         object_list_page.object_list(),
                         current_object_key,
@@ -189,26 +380,25 @@ impl App {
                         Rc::clone(&self.ctx),
                         self.tx.clone(),
                     );
+                    if let Some(page) = object_detail_page.get_mut::<ObjectDetailPage>() {
+                        page.set_decode_key_names(self.decode_key_names);
+                    }
                     self.page_stack.push(object_detail_page);
+                    self.tx.send(AppEventType::LoadObjectLifecycle);
                 } else {
                     self.tx.send(AppEventType::LoadObjectDetail);
-                    self.is_loading = true;
                 }
             }
             ObjectItem::Dir { .. } => {
-                let object_key = object_list_page.current_selected_object_key();
-                if let Some(current_object_items) = self.app_objects.get_object_items(&object_key) {
+                if let Some(current_object_items) =
+                    self.app_objects.get_object_items(&current_object_key)
+                {
                     // object list has been already loaded
-                    let new_object_list_page = Page::of_object_list(
-                        current_object_items,
-                        object_key,
-                        Rc::clone(&self.ctx),
-                        self.tx.clone(),
-                    );
+                    let new_object_list_page =
+                        self.new_object_list_page(current_object_items, current_object_key);
                     self.page_stack.push(new_object_list_page);
                 } else {
                     self.tx.send(AppEventType::LoadObjects);
-                    self.is_loading = true;
                 }
             }
         }
@@ -223,12 +413,28 @@ impl App {
     }
 
     pub fn object_list_refresh(&mut self) {
-        let object_list_page = self.page_stack.current_page().as_object_list();
+        let Some(object_list_page) = self.page_stack.current::<ObjectListPage>() else {
+            return;
+        };
         let object_key = object_list_page.current_dir_object_key();
         self.app_objects.clear_object_items_under(object_key);
 
         self.tx.send(AppEventType::ReloadObjects);
-        self.is_loading = true;
+    }
+
+    pub fn object_list_debounce_filter(&self, generation: usize) {
+        let tx = self.tx.clone();
+        spawn(async move {
+            tokio::time::sleep(FILTER_DEBOUNCE_DELAY).await;
+            tx.send(AppEventType::ObjectListApplyDebouncedFilter(generation));
+        });
+    }
+
+    pub fn object_list_apply_debounced_filter(&mut self, generation: usize) {
+        // the page may have been closed while waiting
+        if let Page::ObjectList(page) = self.page_stack.current_page_mut() {
+            page.apply_debounced_filter(generation);
+        }
     }
 
     pub fn back_to_bucket_list(&mut self) {
@@ -238,101 +444,300 @@ impl App {
         self.page_stack.clear();
     }
 
-    pub fn load_objects(&self) {
-        let current_object_key = match self.page_stack.current_page() {
-            page @ Page::BucketList(_) => page.as_bucket_list().current_selected_object_key(),
-            page @ Page::ObjectList(_) => page.as_object_list().current_selected_object_key(),
-            page => panic!("Invalid page: {:?}", page),
+    pub fn load_objects(&mut self) {
+        let current_object_key = if let Some(page) = self.page_stack.current::<BucketListPage>() {
+            page.current_selected_object_key()
+        } else if let Some(page) = self.page_stack.current::<ObjectListPage>() {
+            // nothing to load on a group header
+            let Some(object_key) = page.current_selected_object_key() else {
+                return;
+            };
+            object_key
+        } else {
+            tracing::warn!("objects cannot be loaded from the current page");
+            return;
         };
         let bucket = current_object_key.bucket_name.clone();
         let prefix = current_object_key.joined_object_path(false);
+
+        let page_id = self.page_stack.current_page_id();
+        self.page_stack.start_loading(page_id);
+
         let (client, tx) = self.unwrap_client_tx();
         spawn(async move {
-            let items = client.load_objects(&bucket, &prefix).await;
-            let result = CompleteLoadObjectsResult::new(items);
-            tx.send(AppEventType::CompleteLoadObjects(result));
+            let page = client.load_objects_page(&bucket, &prefix, None).await;
+            let result = CompleteLoadObjectsResult::new(page, current_object_key);
+            tx.send(AppEventType::CompleteLoadObjects(page_id, result));
+        });
+    }
+
+    pub fn complete_load_objects(
+        &mut self,
+        page_id: PageId,
+        result: Result<CompleteLoadObjectsResult>,
+    ) {
+        self.page_stack.finish_loading(page_id);
+
+        match result {
+            Ok(CompleteLoadObjectsResult {
+                items,
+                object_key,
+                next_token,
+            }) => {
+                // a partial list is not cached, it is cached when all pages have been loaded
+                if next_token.is_none() {
+                    self.app_objects
+                        .set_object_items(object_key.clone(), items.clone());
+                    self.update_etag_index(&object_key, &items);
+                }
+
+                // if the user has moved on, the list is just cached and shown when it is opened again
+                if self.is_selected_on_page(page_id, &object_key) {
+                    let mut object_list_page = self.new_object_list_page(items, object_key.clone());
+                    let loading_more = next_token.is_some();
+                    if let Some(page) = object_list_page.get_mut::<ObjectListPage>() {
+                        page.set_loading_more(loading_more);
+                    }
+                    self.page_stack.push(object_list_page);
+
+                    if let Some(token) = next_token {
+                        let list_page_id = self.page_stack.current_page_id();
+                        self.load_more_objects(list_page_id, object_key, token);
+                    }
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    // Loads the following pages one by one while the object list page is open,
+    // so that the page can be used before all objects of a huge prefix are listed.
+    fn load_more_objects(&mut self, page_id: PageId, object_key: ObjectKey, token: String) {
+        let bucket = object_key.bucket_name.clone();
+        let prefix = object_key.joined_object_path(false);
+
+        self.page_stack.start_loading(page_id);
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let page = client
+                .load_objects_page(&bucket, &prefix, Some(token))
+                .await;
+            let result = CompleteLoadObjectsResult::new(page, object_key);
+            tx.send(AppEventType::AppendLoadedObjects(page_id, result));
         });
     }
 
-    pub fn complete_load_objects(&mut self, result: Result<CompleteLoadObjectsResult>) {
-        let current_object_key = match self.page_stack.current_page() {
-            page @ Page::BucketList(_) => page.as_bucket_list().current_selected_object_key(),
-            page @ Page::ObjectList(_) => page.as_object_list().current_selected_object_key(),
-            page => panic!("Invalid page: {:?}", page),
+    pub fn append_loaded_objects(
+        &mut self,
+        page_id: PageId,
+        result: Result<CompleteLoadObjectsResult>,
+    ) {
+        self.page_stack.finish_loading(page_id);
+
+        // stop loading if the page has been closed, or replaced by a refresh
+        let Some(Page::ObjectList(page)) = self.page_stack.get_mut(page_id) else {
+            return;
         };
+        if !page.is_loading_more() {
+            return;
+        }
 
         match result {
-            Ok(CompleteLoadObjectsResult { items }) => {
-                self.app_objects
-                    .set_object_items(current_object_key.clone(), items.clone());
+            Ok(CompleteLoadObjectsResult {
+                items,
+                object_key,
+                next_token,
+            }) => {
+                page.append_objects(items);
+                if let Some(token) = next_token {
+                    self.load_more_objects(page_id, object_key, token);
+                    return;
+                }
 
-                let object_list_page = Page::of_object_list(
-                    items,
-                    current_object_key,
-                    Rc::clone(&self.ctx),
-                    self.tx.clone(),
-                );
-                self.page_stack.push(object_list_page);
+                page.set_loading_more(false);
+                let items = page.all_object_items().to_vec();
+                let changes = self.etag_index.detect_changes(&object_key, &items);
+                page.set_object_changes(changes);
+                self.update_etag_index(&object_key, &items);
+                self.app_objects.set_object_items(object_key, items);
             }
             Err(e) => {
+                page.set_loading_more(false);
                 self.tx.send(AppEventType::NotifyError(e));
             }
         }
-        self.is_loading = false;
     }
 
-    pub fn reload_objects(&self) {
-        let object_list_page = self.page_stack.current_page().as_object_list();
-        let object_key = object_list_page.current_dir_object_key();
+    // Returns true if the page is still the current page and the object key is selected on it
+    fn is_selected_on_page(&self, page_id: PageId, object_key: &ObjectKey) -> bool {
+        if !self.page_stack.is_current_page(page_id) {
+            return false;
+        }
+        let selected = match self.page_stack.current_page() {
+            Page::BucketList(page) if page.non_empty() => page.current_selected_object_key(),
+            Page::ObjectList(page) => match page.current_selected_object_key() {
+                Some(object_key) => object_key,
+                None => return false,
+            },
+            _ => return false,
+        };
+        &selected == object_key
+    }
+
+    fn new_object_list_page(&self, items: Vec<ObjectItem>, object_key: ObjectKey) -> Page {
+        let changes = self.etag_index.detect_changes(&object_key, &items);
+        let mut page =
+            Page::of_object_list(items, object_key, Rc::clone(&self.ctx), self.tx.clone());
+        if let Some(object_list_page) = page.get_mut::<ObjectListPage>() {
+            object_list_page.set_object_changes(changes);
+            object_list_page.set_decode_key_names(self.decode_key_names);
+            object_list_page.set_show_hidden(self.show_hidden);
+        }
+        page
+    }
+
+    pub fn toggle_decode_key_names(&mut self) {
+        self.decode_key_names = !self.decode_key_names;
+        for page in self.page_stack.iter_mut() {
+            match page {
+                Page::ObjectList(page) => page.set_decode_key_names(self.decode_key_names),
+                Page::ObjectDetail(page) => page.set_decode_key_names(self.decode_key_names),
+                _ => {}
+            }
+        }
+        let msg = if self.decode_key_names {
+            "Showing decoded key names"
+        } else {
+            "Showing raw key names"
+        };
+        self.tx.send(AppEventType::NotifyInfo(msg.into()));
+    }
+
+    pub fn toggle_show_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        for page in self.page_stack.iter_mut() {
+            match page {
+                Page::BucketList(page) => page.set_show_hidden(self.show_hidden),
+                Page::ObjectList(page) => page.set_show_hidden(self.show_hidden),
+                _ => {}
+            }
+        }
+        let msg = if self.show_hidden {
+            "Showing hidden buckets and objects"
+        } else {
+            "Hiding buckets and objects matching filters.hidden"
+        };
+        self.tx.send(AppEventType::NotifyInfo(msg.into()));
+    }
+
+    // called only when the objects are loaded from S3, not when the cached list is shown
+    fn update_etag_index(&mut self, object_key: &ObjectKey, items: &[ObjectItem]) {
+        if !self.ctx.config.ui.object_list.show_changes {
+            return;
+        }
+        if let Err(e) = self.etag_index.update(object_key, items) {
+            tracing::warn!("failed to update etag index: {}", e);
+        }
+    }
+
+    pub fn reload_objects(&mut self) {
+        let Some(object_list_page) = self.page_stack.current::<ObjectListPage>() else {
+            return;
+        };
+        let object_key = object_list_page.current_dir_object_key().clone();
         let bucket = object_key.bucket_name.clone();
         let prefix = object_key.joined_object_path(false);
+
+        let page_id = self.page_stack.current_page_id();
+        self.page_stack.start_loading(page_id);
+
         let (client, tx) = self.unwrap_client_tx();
         spawn(async move {
             let items = client.load_objects(&bucket, &prefix).await;
-            let result = CompleteReloadObjectsResult::new(items);
-            tx.send(AppEventType::CompleteReloadObjects(result));
+            let result = CompleteReloadObjectsResult::new(items, object_key);
+            tx.send(AppEventType::CompleteReloadObjects(page_id, result));
         });
     }
 
-    pub fn complete_reload_objects(&mut self, result: Result<CompleteReloadObjectsResult>) {
-        self.page_stack.pop();
-        self.complete_load_objects(result.map(|r| r.into()));
+    pub fn complete_reload_objects(
+        &mut self,
+        page_id: PageId,
+        result: Result<CompleteReloadObjectsResult>,
+    ) {
+        self.page_stack.finish_loading(page_id);
+
+        match result {
+            Ok(CompleteReloadObjectsResult { items, object_key }) => {
+                self.app_objects
+                    .set_object_items(object_key.clone(), items.clone());
+                self.update_etag_index(&object_key, &items);
+
+                // the page is refreshed in place even if other pages have been opened on top of it
+                let object_list_page = self.new_object_list_page(items, object_key);
+                self.page_stack.replace(page_id, object_list_page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
     }
 
-    pub fn load_object_detail(&self) {
-        let object_list_page = self.page_stack.current_page().as_object_list();
+    pub fn load_object_detail(&mut self) {
+        let Some(object_list_page) = self.page_stack.current::<ObjectListPage>() else {
+            return;
+        };
 
-        if let ObjectItem::File {
+        let Some(ObjectItem::File {
             name, size_byte, ..
-        } = object_list_page.current_selected_item()
-        {
-            let name = name.clone();
-            let size_byte = *size_byte;
+        }) = object_list_page.current_selected_item()
+        else {
+            return;
+        };
+        let name = name.clone();
+        let size_byte = *size_byte;
+
+        let Some(map_key) = object_list_page.current_selected_object_key() else {
+            return;
+        };
+        let bucket = map_key.bucket_name.clone();
+        let key = map_key.joined_object_path(true);
 
-            let map_key = object_list_page.current_selected_object_key().clone();
-            let bucket = map_key.bucket_name.clone();
-            let key = map_key.joined_object_path(true);
+        let page_id = self.page_stack.current_page_id();
+        self.page_stack.start_loading(page_id);
 
-            let (client, tx) = self.unwrap_client_tx();
-            spawn(async move {
-                let detail = client
-                    .load_object_detail(&bucket, &key, &name, size_byte)
-                    .await;
-                let result = CompleteLoadObjectDetailResult::new(detail, map_key);
-                tx.send(AppEventType::CompleteLoadObjectDetail(result));
-            });
-        }
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let detail = client
+                .load_object_detail(&bucket, &key, &name, size_byte)
+                .await;
+            let result = CompleteLoadObjectDetailResult::new(detail, map_key);
+            tx.send(AppEventType::CompleteLoadObjectDetail(page_id, result));
+        });
     }
 
-    pub fn complete_load_object_detail(&mut self, result: Result<CompleteLoadObjectDetailResult>) {
+    pub fn complete_load_object_detail(
+        &mut self,
+        page_id: PageId,
+        result: Result<CompleteLoadObjectDetailResult>,
+    ) {
+        self.page_stack.finish_loading(page_id);
+
         match result {
             Ok(CompleteLoadObjectDetailResult { detail, map_key }) => {
                 self.app_objects
                     .set_object_detail(map_key.clone(), *detail.clone());
 
-                let object_page = self.page_stack.current_page().as_object_list();
+                if !self.is_selected_on_page(page_id, &map_key) {
+                    return;
+                }
+                let Some(object_page) = self.page_stack.current::<ObjectListPage>() else {
+                    return;
+                };
 
-                let object_detail_page = Page::of_object_detail(
+                let mut object_detail_page = Page::of_object_detail(
                     *detail.clone(),
                     object_page.object_list(),
                     map_key,
@@ -340,17 +745,129 @@ impl App {
                     Rc::clone(&self.ctx),
                     self.tx.clone(),
                 );
+                if let Some(page) = object_detail_page.get_mut::<ObjectDetailPage>() {
+                    page.set_decode_key_names(self.decode_key_names);
+                }
                 self.page_stack.push(object_detail_page);
+                self.tx.send(AppEventType::LoadObjectLifecycle);
+                if self.start_page.take() == Some(StartPage::Preview) {
+                    self.tx.send(AppEventType::OpenPreview(*detail, None));
+                }
+            }
+            Err(e) => {
+                self.start_page = None;
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn load_object_lifecycle(&self) {
+        let Page::ObjectDetail(object_detail_page) = self.page_stack.current_page() else {
+            return;
+        };
+        let map_key = object_detail_page.current_object_key().clone();
+        let Some(detail) = self.app_objects.get_object_detail(&map_key).cloned() else {
+            return;
+        };
+        let bucket = map_key.bucket_name.clone();
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let events = async {
+                let rules = client.load_lifecycle_rules(&bucket).await?;
+                // tags are fetched only when needed since GetObjectTagging requires another permission
+                let tags = if rules.iter().any(|r| r.has_tag_filter()) {
+                    client.load_object_tags(&bucket, &detail.key).await?
+                } else {
+                    Vec::new()
+                };
+                Ok(evaluate_lifecycle_rules(
+                    &rules,
+                    &detail.key,
+                    &tags,
+                    detail.size_byte,
+                    detail.last_modified,
+                    &detail.storage_class,
+                ))
+            }
+            .await;
+            let result = CompleteLoadObjectLifecycleResult::new(events, map_key);
+            tx.send(AppEventType::CompleteLoadObjectLifecycle(result));
+        });
+    }
+
+    pub fn complete_load_object_lifecycle(
+        &mut self,
+        result: Result<CompleteLoadObjectLifecycleResult>,
+    ) {
+        match result {
+            Ok(CompleteLoadObjectLifecycleResult { events, map_key }) => {
+                if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+                    if page.current_object_key() == &map_key {
+                        page.set_lifecycle_events(events);
+                    }
+                }
+            }
+            Err(e) => {
+                // lifecycle info is supplementary, so do not bother the user (e.g. missing permission)
+                tracing::warn!("Failed to load lifecycle: {:?}", e);
+            }
+        }
+    }
+
+    pub fn start_compute_object_md5(&mut self, file_detail: FileDetail, map_key: ObjectKey) {
+        self.start_loading("Computing MD5");
+
+        let bucket = map_key.bucket_name.clone();
+        let key = map_key.joined_object_path(true);
+        let if_match = if_match_e_tag(&file_detail, &None);
+        let (client, tx) = self.unwrap_client_tx();
+        let loading = self.handle_loading_size(file_detail.size_byte, tx.clone());
+        let handle = spawn(async move {
+            let md5 = client
+                .download_object(
+                    &bucket,
+                    &key,
+                    None,
+                    if_match,
+                    file_detail.size_byte,
+                    loading,
+                )
+                .await
+                .map(|obj| format!("{:x}", Md5::digest(&obj.bytes)));
+            let result = CompleteComputeObjectMd5Result::new(md5, map_key);
+            tx.send(AppEventType::CompleteComputeObjectMd5(result));
+        });
+        self.cancellable_task = Some(handle.abort_handle());
+    }
+
+    pub fn complete_compute_object_md5(&mut self, result: Result<CompleteComputeObjectMd5Result>) {
+        // the computation may complete just before it is cancelled
+        if self.cancellable_task.take().is_none() {
+            return;
+        }
+
+        match result {
+            Ok(CompleteComputeObjectMd5Result { md5, map_key }) => {
+                if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+                    if page.current_object_key() == &map_key {
+                        page.set_content_md5(md5.clone());
+                    }
+                }
+                let msg = format!("MD5 of the content: {}", md5);
+                self.tx.send(AppEventType::NotifySuccess(msg));
             }
             Err(e) => {
                 self.tx.send(AppEventType::NotifyError(e));
             }
         }
-        self.is_loading = false;
+        self.finish_loading();
     }
 
     pub fn open_object_versions_tab(&mut self) {
-        let object_detail_page = self.page_stack.current_page().as_object_detail();
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            return;
+        };
 
         let current_object_key = object_detail_page.current_object_key().clone();
         let versions = self.app_objects.get_object_versions(&current_object_key);
@@ -359,47 +876,122 @@ impl App {
             // object versions has been already loaded
             let result =
                 CompleteLoadObjectVersionsResult::new(Ok(versions.clone()), current_object_key);
+            let page_id = self.page_stack.current_page_id();
+            self.page_stack.start_loading(page_id);
             self.tx
-                .send(AppEventType::CompleteLoadObjectVersions(result));
+                .send(AppEventType::CompleteLoadObjectVersions(page_id, result));
         } else {
             self.tx.send(AppEventType::LoadObjectVersions);
-            self.is_loading = true;
         }
     }
 
-    pub fn load_object_versions(&self) {
-        let object_detail_page = self.page_stack.current_page().as_object_detail();
+    pub fn load_object_versions(&mut self) {
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            return;
+        };
 
         let map_key = object_detail_page.current_object_key().clone();
         let bucket = map_key.bucket_name.clone();
         let key = map_key.joined_object_path(true);
 
+        let page_id = self.page_stack.current_page_id();
+        self.page_stack.start_loading(page_id);
+
         let (client, tx) = self.unwrap_client_tx();
         spawn(async move {
-            let versions = client.load_object_versions(&bucket, &key).await;
+            let versions = client.load_object_versions(&bucket, &key, None).await;
             let result = CompleteLoadObjectVersionsResult::new(versions, map_key);
-            tx.send(AppEventType::CompleteLoadObjectVersions(result));
+            tx.send(AppEventType::CompleteLoadObjectVersions(page_id, result));
         });
     }
 
     pub fn complete_load_object_versions(
         &mut self,
+        page_id: PageId,
         result: Result<CompleteLoadObjectVersionsResult>,
     ) {
+        self.page_stack.finish_loading(page_id);
+
         match result {
             Ok(CompleteLoadObjectVersionsResult { versions, map_key }) => {
                 self.app_objects
                     .set_object_versions(map_key, versions.clone());
 
-                let object_detail_page = self.page_stack.current_page_mut().as_mut_object_detail();
-                object_detail_page.set_versions(versions);
+                // the page may have been closed while loading
+                if !self.page_stack.is_current_page(page_id) {
+                    return;
+                }
+                let Some(object_detail_page) = self.page_stack.current_mut::<ObjectDetailPage>()
+                else {
+                    return;
+                };
+                object_detail_page.set_versions(versions.versions);
+                object_detail_page.set_has_more_versions(versions.next_marker.is_some());
                 object_detail_page.select_versions_tab();
             }
             Err(e) => {
                 self.tx.send(AppEventType::NotifyError(e));
             }
         }
-        self.is_loading = false;
+    }
+
+    pub fn load_more_object_versions(&mut self) {
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            return;
+        };
+
+        let map_key = object_detail_page.current_object_key().clone();
+        let marker = self
+            .app_objects
+            .get_object_versions(&map_key)
+            .and_then(|versions| versions.next_marker.clone());
+        let Some(marker) = marker else {
+            return;
+        };
+        let bucket = map_key.bucket_name.clone();
+        let key = map_key.joined_object_path(true);
+
+        let page_id = self.page_stack.current_page_id();
+        self.page_stack.start_loading(page_id);
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let versions = client
+                .load_object_versions(&bucket, &key, Some(marker))
+                .await;
+            let result = CompleteLoadObjectVersionsResult::new(versions, map_key);
+            tx.send(AppEventType::CompleteLoadMoreObjectVersions(
+                page_id, result,
+            ));
+        });
+    }
+
+    pub fn complete_load_more_object_versions(
+        &mut self,
+        page_id: PageId,
+        result: Result<CompleteLoadObjectVersionsResult>,
+    ) {
+        self.page_stack.finish_loading(page_id);
+
+        match result {
+            Ok(CompleteLoadObjectVersionsResult { versions, map_key }) => {
+                self.app_objects
+                    .append_object_versions(map_key, versions.clone());
+
+                // the page may have been closed while loading
+                if !self.page_stack.is_current_page(page_id) {
+                    return;
+                }
+                let Some(page) = self.page_stack.current_mut::<ObjectDetailPage>() else {
+                    return;
+                };
+                page.append_versions(versions.versions);
+                page.set_has_more_versions(versions.next_marker.is_some());
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
     }
 
     pub fn open_help(&mut self) {
@@ -418,64 +1010,216 @@ impl App {
     pub fn detail_download_object(&mut self, file_detail: FileDetail, version_id: Option<String>) {
         self.tx
             .send(AppEventType::DownloadObject(file_detail, version_id));
-        self.is_loading = true;
     }
 
+    // The object has already been loaded for the preview, so it is saved without a job
     pub fn preview_download_object(&self, obj: RawObject, path: String) {
-        let result = CompleteDownloadObjectResult::new(Ok(obj), PathBuf::from(path));
-        self.tx.send(AppEventType::CompleteDownloadObject(result));
+        let path = self.resolve_download_path(PathBuf::from(path));
+        let result = save_binary(&path, &obj.bytes).map(|_| path);
+        self.notify_download_result(result);
     }
 
-    pub fn open_preview(&mut self, file_detail: FileDetail, version_id: Option<String>) {
-        self.tx
-            .send(AppEventType::PreviewObject(file_detail, version_id));
-        self.is_loading = true;
+    pub fn preview_export_html(&self, html: String, path: PathBuf) {
+        let path = self.resolve_download_path(path);
+        match save_binary(&path, html.as_bytes()) {
+            Ok(_) => {
+                let msg = format!("Exported preview as HTML: {}", path.to_string_lossy());
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
     }
 
-    pub fn download_object(&self, file_detail: FileDetail, version_id: Option<String>) {
-        let object_name = file_detail.name;
-        let size_byte = file_detail.size_byte;
+    pub fn preview_compare_local_file(
+        &mut self,
+        file_detail: FileDetail,
+        obj: RawObject,
+        input: String,
+    ) {
+        let path = PathBuf::from(input);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let e = AppError::new("Failed to read file", e);
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        if infer::is_image(&bytes) {
+            let msg = "Only text objects can be compared".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+            return;
+        }
 
-        self.download_object_and(
-            &object_name,
-            size_byte,
-            None,
-            version_id,
-            |tx, obj, path| {
-                let result = CompleteDownloadObjectResult::new(obj, path);
-                tx.send(AppEventType::CompleteDownloadObject(result));
-            },
-        )
+        let local_obj = RawObject { bytes };
+        let page = Page::of_object_local_compare(
+            file_detail,
+            obj,
+            path,
+            local_obj,
+            Rc::clone(&self.ctx),
+            self.tx.clone(),
+        );
+        self.page_stack.push(page);
+    }
+
+    pub fn open_preview(&mut self, file_detail: FileDetail, version_id: Option<String>) {
+        self.tx
+            .send(AppEventType::PreviewObject(file_detail, version_id));
+        self.start_loading("Loading preview");
+    }
+
+    pub fn open_compare_version_preview(&mut self, file_detail: FileDetail, version: FileVersion) {
+        self.tx
+            .send(AppEventType::CompareVersionPreview(file_detail, version));
+        self.start_loading("Loading versions to compare");
+    }
+
+    pub fn download_object(&mut self, file_detail: FileDetail, version_id: Option<String>) {
+        self.start_download_object(file_detail, None, version_id);
     }
 
     pub fn download_object_as(
-        &self,
+        &mut self,
         file_detail: FileDetail,
         input: String,
         version_id: Option<String>,
     ) {
-        let object_name = file_detail.name;
-        let size_byte = file_detail.size_byte;
+        self.start_download_object(file_detail, Some(input), version_id);
+    }
 
-        self.download_object_and(
-            &object_name,
-            size_byte,
-            Some(&input),
+    // The object is downloaded in the background as a job, so that other pages can be browsed meanwhile
+    fn start_download_object(
+        &mut self,
+        file_detail: FileDetail,
+        save_file_name: Option<String>,
+        version_id: Option<String>,
+    ) {
+        let if_match = if_match_e_tag(&file_detail, &version_id);
+        let Some((bucket, key, path)) = self.current_object_download_target(
+            &file_detail.name,
+            save_file_name.as_deref(),
+            version_id.as_deref(),
+        ) else {
+            return;
+        };
+        let spec = JobSpec::DownloadObject {
+            bucket,
+            key,
             version_id,
-            |tx, obj, path| {
-                let result = CompleteDownloadObjectResult::new(obj, path);
-                tx.send(AppEventType::CompleteDownloadObject(result));
-            },
-        )
+            if_match,
+            size_byte: file_detail.size_byte,
+            path,
+        };
+        let id = self.jobs.add(spec.clone(), Local::now());
+        self.run_job(id, spec);
+        self.update_transfer_list_pages();
+
+        let msg = format!(
+            "Downloading {} in the background (Ctrl-t to show transfers)",
+            file_detail.name
+        );
+        self.tx.send(AppEventType::NotifyInfo(msg));
     }
 
-    pub fn complete_download_object(&mut self, result: Result<CompleteDownloadObjectResult>) {
+    fn spawn_download_object(
+        &self,
+        id: JobId,
+        bucket: String,
+        key: String,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        path: PathBuf,
+    ) -> AbortHandle {
+        let tx = self.tx.clone();
+        let progress_tx = self.tx.clone();
+        let progress: Progress = Box::new(move |current| {
+            let done = JobProgress {
+                count: 0,
+                size_byte: current,
+            };
+            progress_tx.send(AppEventType::JobProgress(id, done));
+        });
+        let config = &self.ctx.config.download;
+        let parts = config
+            .should_download_in_parts(size_byte)
+            .then(|| (config.part_size_byte(), config.max_concurrent_requests));
+        let (client, _) = self.unwrap_client_tx();
+        // removed if the download fails or is cancelled (the task is aborted)
+        let temp = TempFile::new(download_temp_path(&path, id));
+        let handle = spawn(async move {
+            let result = match parts {
+                Some((part_size, max_concurrent_requests)) => download_in_parts(
+                    client,
+                    &bucket,
+                    &key,
+                    version_id,
+                    if_match,
+                    size_byte,
+                    part_size,
+                    max_concurrent_requests,
+                    temp.path().to_path_buf(),
+                    progress,
+                )
+                .await
+                .map(|_| ()),
+                None => client
+                    .download_object(&bucket, &key, version_id, if_match, size_byte, progress)
+                    .await
+                    .and_then(|obj| save_binary(temp.path(), &obj.bytes)),
+            };
+            let result = result.map(|_| temp.keep());
+            let result = CompleteDownloadObjectResult::new(result, path);
+            tx.send(AppEventType::CompleteDownloadObject(id, result));
+        });
+        handle.abort_handle()
+    }
+
+    pub fn complete_download_object(
+        &mut self,
+        id: JobId,
+        result: Result<CompleteDownloadObjectResult>,
+    ) {
+        if !self.jobs.is_running(id) {
+            // the job has been cancelled, the object is not saved
+            if let Ok(result) = result {
+                let _ = std::fs::remove_file(result.downloaded_path);
+            }
+            return;
+        }
         let result = match result {
-            Ok(CompleteDownloadObjectResult { obj, path }) => {
-                save_binary(&path, &obj.bytes).map(|_| path)
+            Ok(CompleteDownloadObjectResult {
+                downloaded_path,
+                path,
+            }) => {
+                let temp = TempFile::new(downloaded_path);
+                let path = self.resolve_download_path(path);
+                std::fs::rename(temp.path(), &path)
+                    .map(|_| {
+                        temp.keep();
+                        path
+                    })
+                    .map_err(|e| AppError::new("Failed to save file", e))
             }
             Err(e) => Err(e),
         };
+        let status = match &result {
+            Ok(_) => JobStatus::Completed,
+            Err(e) => JobStatus::Failed(e.msg.clone()),
+        };
+        if let Some(job) = self.jobs.finish(id, status, Local::now()) {
+            if result.is_ok() {
+                job.done = job.total;
+            }
+        }
+        self.update_transfer_list_pages();
+        self.notify_download_result(result);
+    }
+
+    fn notify_download_result(&self, result: Result<PathBuf>) {
         match result {
             Ok(path) => {
                 let msg = format!(
@@ -484,194 +1228,2768 @@ impl App {
                 );
                 self.tx.send(AppEventType::NotifySuccess(msg));
             }
-            Err(e) => {
-                self.tx.send(AppEventType::NotifyError(e));
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+
+        if let Page::ObjectPreview(page) = self.page_stack.current_page() {
+            if page.is_image_preview() {
+                self.tx.send(AppEventType::PreviewRerenderImage);
+            }
+        }
+    }
+
+    pub fn start_load_all_download_object_list(&mut self, bucket: String, key: String) {
+        self.start_loading("Listing objects");
+
+        let (client, tx) = self.unwrap_client_tx();
+        let handle = spawn(async move {
+            let progress_tx = tx.clone();
+            let objs = client
+                .list_all_download_objects(
+                    &bucket,
+                    &key,
+                    Box::new(move |count| {
+                        progress_tx.send(AppEventType::LoadAllDownloadObjectListProgress(count));
+                    }),
+                )
+                .await;
+            let result = CompleteLoadAllDownloadObjectListResult::new(objs, key);
+            tx.send(AppEventType::CompleteLoadAllDownloadObjectList(result));
+        });
+        self.cancellable_task = Some(handle.abort_handle());
+        self.loading_progress = Some("Listing objects...".into());
+    }
+
+    pub fn load_all_download_object_list_progress(&mut self, count: usize) {
+        // the progress may arrive after the listing is cancelled
+        if self.cancellable_task.is_some() {
+            let msg = format!("{} objects found so far...", format_count(count));
+            self.loading_progress = Some(msg);
+        }
+    }
+
+    // Aborts the task of the current loading operation, which also drops the requests in flight
+    pub fn cancel_loading(&mut self) {
+        if let Some(handle) = self.cancellable_task.take() {
+            handle.abort();
+            let name = self.loading.as_ref().map(|op| op.name).unwrap_or_default();
+            self.loading_progress = None;
+            self.sso_login_prompt = None;
+            self.finish_loading();
+            let msg = format!("{} cancelled", name);
+            self.tx.send(AppEventType::NotifyWarn(msg));
+        }
+    }
+
+    pub fn complete_load_all_download_object_list(
+        &mut self,
+        result: Result<CompleteLoadAllDownloadObjectListResult>,
+    ) {
+        // the listing may complete just before it is cancelled
+        if self.cancellable_task.take().is_none() {
+            return;
+        }
+        self.loading_progress = None;
+
+        match result {
+            Ok(CompleteLoadAllDownloadObjectListResult { objs, key }) => {
+                if objs.is_empty() {
+                    let msg = format!("No objects found under '{}'", key);
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                } else {
+                    // the key may be a partial prefix like "logs/2024-",
+                    // so the folder containing it is used as the base of the relative paths
+                    let base_key = match key.rfind('/') {
+                        Some(i) => key[..=i].to_string(),
+                        None => String::new(),
+                    };
+                    if let Some(page) = self.page_stack.current_mut::<ObjectListPage>() {
+                        page.open_download_confirm_dialog(base_key, objs);
+                    }
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_download_objects(
+        &mut self,
+        bucket: String,
+        key: String,
+        objs: DownloadObjectList,
+        options: DownloadObjectsOptions,
+        path: PathBuf,
+    ) {
+        if let Some(page) = self.page_stack.current_mut::<ObjectListPage>() {
+            page.close_download_confirm_dialog();
+        }
+
+        // the policy is applied to each file when it is saved, after the skip type
+        let path = match options.output_type {
+            DownloadOutputType::Files => path,
+            DownloadOutputType::TarGz | DownloadOutputType::Zip => self.resolve_download_path(path),
+        };
+
+        let count = objs.len();
+        let spec = JobSpec::DownloadObjects {
+            bucket,
+            key,
+            objs: Arc::new(objs),
+            options,
+            path,
+        };
+        let id = self.jobs.add(spec.clone(), Local::now());
+        self.run_job(id, spec);
+        self.update_transfer_list_pages();
+
+        let msg = format!(
+            "Downloading {} objects in the background (Ctrl-t to show transfers)",
+            count
+        );
+        self.tx.send(AppEventType::NotifyInfo(msg));
+    }
+
+    fn run_job(&mut self, id: JobId, spec: JobSpec) {
+        let handle = match spec {
+            JobSpec::DownloadObjects {
+                bucket,
+                key,
+                objs,
+                options,
+                path,
+            } => self.spawn_download_objects(id, bucket, key, objs, options, path),
+            JobSpec::DownloadObject {
+                bucket,
+                key,
+                version_id,
+                if_match,
+                size_byte,
+                path,
+            } => self.spawn_download_object(id, bucket, key, version_id, if_match, size_byte, path),
+        };
+        self.jobs.set_handle(id, handle);
+    }
+
+    fn spawn_download_objects(
+        &self,
+        id: JobId,
+        bucket: String,
+        key: String,
+        objs: Arc<DownloadObjectList>,
+        options: DownloadObjectsOptions,
+        path: PathBuf,
+    ) -> AbortHandle {
+        // archives are not verified since the entries cannot be read back individually
+        let verify_type = match options.output_type {
+            DownloadOutputType::Files => self.ctx.config.download.verify,
+            DownloadOutputType::TarGz | DownloadOutputType::Zip => DownloadVerifyType::None,
+        };
+
+        let conflict_policy = self.ctx.config.download.conflict_policy;
+
+        let (client, tx) = self.unwrap_client_tx();
+        let handle = spawn(async move {
+            // the objects are read from the list one by one
+            let started = objs.iter().and_then(|targets| {
+                let archive = match options.output_type {
+                    DownloadOutputType::Files => None,
+                    DownloadOutputType::TarGz => Some(ArchiveWriter::create_tar_gz(&path)?),
+                    DownloadOutputType::Zip => Some(ArchiveWriter::create_zip(&path)?),
+                };
+                Ok((targets, archive))
+            });
+            let (mut targets, mut archive) = match started {
+                Ok(started) => started,
+                Err(e) => {
+                    let result =
+                        CompleteDownloadObjectsResult::new(Err(e), bucket, key, options, path);
+                    tx.send(AppEventType::CompleteDownloadObjects(id, result));
+                    return;
+                }
+            };
+
+            let max_concurrent_requests = options.max_concurrent_requests.max(1);
+            let mut used_paths = HashSet::new();
+            // downloads run concurrently, but the results are written in the original order.
+            // the downloads in flight are aborted when the job is cancelled
+            let mut in_flight = VecDeque::new();
+            let mut done = JobProgress::default();
+            // failed objects are reported at the end so that they can be retried
+            let mut outcome = DownloadObjectsOutcome::default();
+            // files to be verified after all downloads are completed
+            let mut written = Vec::new();
+            let mut result = Ok(());
+            loop {
+                while in_flight.len() < max_concurrent_requests && result.is_ok() {
+                    let obj = match targets.next() {
+                        Some(Ok(obj)) => obj,
+                        Some(Err(e)) => {
+                            result = Err(e);
+                            break;
+                        }
+                        None => break,
+                    };
+                    let relative_path =
+                        options
+                            .layout_type
+                            .relative_path(&key, &obj.key, &mut used_paths);
+                    let obj_path = path.join(&relative_path);
+                    if archive.is_none()
+                        && options
+                            .skip_type
+                            .should_skip(&obj, local_file_info(&obj_path))
+                    {
+                        done.count += 1;
+                        done.size_byte += obj.size_byte;
+                        tx.send(AppEventType::JobProgress(id, done));
+                        outcome.skipped.push(obj.key);
+                        continue;
+                    }
+                    let (client, bucket, key) = (client.clone(), bucket.clone(), obj.key.clone());
+                    let handle = AbortOnDrop(spawn(async move {
+                        client
+                            .download_object(
+                                &bucket,
+                                &key,
+                                None,
+                                None,
+                                obj.size_byte,
+                                Box::new(|_| {}),
+                            )
+                            .await
+                    }));
+                    in_flight.push_back((handle, obj, relative_path, obj_path));
+                }
+                let Some((handle, obj, relative_path, obj_path)) = in_flight.pop_front() else {
+                    break;
+                };
+
+                let downloaded = handle
+                    .await
+                    .unwrap_or_else(|e| Err(AppError::new("Failed to download object", e)));
+                done.count += 1;
+                done.size_byte += obj.size_byte;
+                tx.send(AppEventType::JobProgress(id, done));
+                let raw = match downloaded {
+                    Ok(raw) => raw,
+                    Err(e) => {
+                        let error = e.to_string();
+                        outcome.failed.push(DownloadFailure { obj, error });
+                        continue;
+                    }
+                };
+                match &mut archive {
+                    Some(archive) => {
+                        // the archive is broken after a write error, so stop the whole download
+                        result = archive.append(&relative_path, &raw.bytes, obj.last_modified);
+                        outcome.succeeded.push(obj.key);
+                    }
+                    None => {
+                        // resolved when saved in order, so that the same new name is not used twice
+                        let obj_path = conflict_policy.resolve(obj_path);
+                        match save_binary(&obj_path, &raw.bytes) {
+                            Ok(_) => {
+                                outcome.succeeded.push(obj.key.clone());
+                                if verify_type != DownloadVerifyType::None {
+                                    written.push((obj, obj_path));
+                                }
+                            }
+                            Err(e) => {
+                                let error = e.to_string();
+                                outcome.failed.push(DownloadFailure { obj, error });
+                            }
+                        }
+                    }
+                }
+                if result.is_err() {
+                    break;
+                }
+            }
+            drop(in_flight);
+            if let Some(archive) = archive {
+                if result.is_ok() {
+                    result = archive.finish();
+                }
+            }
+            let total = written.len();
+            for (i, (obj, obj_path)) in written.into_iter().enumerate() {
+                let msg = format!("Verifying downloaded files ({} out of {})", i + 1, total);
+                tx.send(AppEventType::NotifyInfo(msg));
+
+                // mismatched files are reported as failures so that they can be retried
+                if let Some(error) = verify_downloaded_file(&obj, &obj_path, verify_type) {
+                    outcome.succeeded.retain(|key| *key != obj.key);
+                    outcome.failed.push(DownloadFailure { obj, error });
+                }
+            }
+            let result = CompleteDownloadObjectsResult::new(
+                result.map(|_| outcome),
+                bucket,
+                key,
+                options,
+                path,
+            );
+            tx.send(AppEventType::CompleteDownloadObjects(id, result));
+        });
+        handle.abort_handle()
+    }
+
+    pub fn complete_download_objects(
+        &mut self,
+        id: JobId,
+        result: Result<CompleteDownloadObjectsResult>,
+    ) {
+        let status = match &result {
+            Ok(result) if result.failed.is_empty() => JobStatus::Completed,
+            Ok(result) => JobStatus::Failed(format!("{} objects failed", result.failed.len())),
+            Err(e) => JobStatus::Failed(e.msg.clone()),
+        };
+        let Some(job) = self.jobs.finish(id, status, Local::now()) else {
+            // the job has been cancelled, the result is discarded
+            return;
+        };
+        if let Ok(result) = &result {
+            if !result.failed.is_empty() {
+                // only the failed objects are downloaded again by retrying the job,
+                // without overwriting the archive containing the objects downloaded successfully
+                let path = match result.options.output_type {
+                    DownloadOutputType::Files => result.path.clone(),
+                    DownloadOutputType::TarGz | DownloadOutputType::Zip => {
+                        unique_file_path(&result.path)
+                    }
+                };
+                let objs = result.failed.iter().map(|f| f.obj.clone());
+                match DownloadObjectList::from_objs(objs) {
+                    Ok(objs) => {
+                        job.spec = JobSpec::DownloadObjects {
+                            bucket: result.bucket.clone(),
+                            key: result.key.clone(),
+                            objs: Arc::new(objs),
+                            options: result.options,
+                            path,
+                        };
+                    }
+                    Err(e) => {
+                        self.tx.send(AppEventType::NotifyError(e));
+                    }
+                }
+            }
+        }
+        self.update_transfer_list_pages();
+
+        match result {
+            Ok(CompleteDownloadObjectsResult {
+                bucket,
+                key,
+                options,
+                path,
+                succeeded,
+                skipped,
+                failed,
+            }) => {
+                let report =
+                    BulkOperationReport::download(&bucket, &path, &succeeded, &skipped, &failed);
+                let report_path = save_bulk_operation_report(&report);
+
+                if failed.is_empty() {
+                    let mut msg = format!(
+                        "Download completed successfully: {}",
+                        path.to_string_lossy()
+                    );
+                    if !skipped.is_empty() {
+                        msg.push_str(&format!(" ({} skipped)", skipped.len()));
+                    }
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                } else {
+                    let mut msg = format!(
+                        "Download completed with {} failed objects: {}",
+                        failed.len(),
+                        path.to_string_lossy()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+
+                    // the download runs in the background, so the failures are shown
+                    // only if the user is still on an object list, the job can be retried anyway
+                    if let Page::ObjectList(page) = self.page_stack.current_page_mut() {
+                        page.open_download_retry_dialog(bucket, key, failed, options, path);
+                    }
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn open_transfer_list(&mut self) {
+        if matches!(self.page_stack.current_page(), Page::TransferList(_)) {
+            return;
+        }
+        let page = Page::of_transfer_list(
+            self.jobs.jobs().to_vec(),
+            Rc::clone(&self.ctx),
+            self.tx.clone(),
+        );
+        self.page_stack.push(page);
+    }
+
+    pub fn update_job_progress(&mut self, id: JobId, progress: JobProgress) {
+        if self.jobs.update_progress(id, progress, Local::now()) {
+            self.update_transfer_list_pages();
+        }
+    }
+
+    pub fn cancel_job(&mut self, id: JobId) {
+        if self.jobs.cancel(id, Local::now()) {
+            self.update_transfer_list_pages();
+            self.tx
+                .send(AppEventType::NotifyWarn("Transfer cancelled".into()));
+        }
+    }
+
+    pub fn retry_job(&mut self, id: JobId) {
+        let Some(spec) = self.jobs.retry(id, Local::now()) else {
+            let msg = "Only failed or cancelled transfers can be retried".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+            return;
+        };
+        self.run_job(id, spec);
+        self.update_transfer_list_pages();
+    }
+
+    fn update_transfer_list_pages(&mut self) {
+        for page in self.page_stack.iter_mut() {
+            if let Page::TransferList(page) = page {
+                page.set_jobs(self.jobs.jobs().to_vec());
+            }
+        }
+    }
+
+    pub fn start_rename_objects(
+        &mut self,
+        bucket: String,
+        prefix: String,
+        targets: Vec<RenameTarget>,
+    ) {
+        self.start_loading("Renaming objects");
+
+        if let Some(page) = self.page_stack.current_mut::<ObjectListPage>() {
+            page.close_batch_rename_dialog();
+        }
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let total = targets.len();
+            let mut result = Ok(total);
+            for (i, target) in targets.into_iter().enumerate() {
+                let msg = format!("Renaming objects ({} out of {})", i + 1, total);
+                tx.send(AppEventType::NotifyInfo(msg));
+
+                let from = format!("{}{}", prefix, target.from);
+                let to = format!("{}{}", prefix, target.to);
+                // S3 has no rename operation, so copy the object and then delete the original.
+                // The new name is checked again here, since only the loaded objects are checked before.
+                let renamed = async {
+                    if client.object_exists(&bucket, &to).await? {
+                        return Err(AppError::msg(format!("Object already exists: {}", to)));
+                    }
+                    client.copy_object(&bucket, &from, &bucket, &to).await?;
+                    client.delete_object(&bucket, &from).await
+                }
+                .await;
+                if let Err(mut e) = renamed {
+                    e.msg = format!("{} ({} out of {} renamed)", e.msg, i, total);
+                    result = Err(e);
+                    break;
+                }
+            }
+            let result = CompleteRenameObjectsResult::new(result);
+            tx.send(AppEventType::CompleteRenameObjects(result));
+        });
+    }
+
+    pub fn complete_rename_objects(&mut self, result: Result<CompleteRenameObjectsResult>) {
+        match result {
+            Ok(CompleteRenameObjectsResult { count }) => {
+                let msg = format!("Renamed {} objects successfully", count);
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+
+        // reload to reflect the renamed objects, even if some of them failed
+        if let Page::ObjectList(_) = self.page_stack.current_page() {
+            self.object_list_refresh();
+        }
+    }
+
+    pub fn upload_object(
+        &mut self,
+        bucket: String,
+        prefix: String,
+        path: PathBuf,
+        content_type: Option<String>,
+    ) {
+        if let Some(page) = self.page_stack.current_mut::<ObjectListPage>() {
+            page.close_upload_dialog();
+        }
+
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            let e = AppError::msg(format!("Invalid file path: {}", path.display()));
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        };
+        if !path.is_file() {
+            let e = AppError::msg(format!("File not found: {}", path.display()));
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        }
+        let key = format!("{}{}", prefix, name);
+        let size_byte = path.metadata().map(|m| m.len()).unwrap_or_default();
+
+        self.start_loading("Uploading object");
+
+        let (client, tx) = self.unwrap_client_tx();
+        if client.supports_multipart_upload()
+            && size_byte >= self.ctx.config.upload.multipart_threshold_byte()
+        {
+            let target = MultipartUploadTarget {
+                bucket,
+                key,
+                path,
+                size_byte,
+                content_type,
+            };
+            spawn(async move {
+                let result = client
+                    .find_multipart_upload(&target.bucket, &target.key)
+                    .await;
+                let result = CompleteFindMultipartUploadResult::new(result, target);
+                tx.send(AppEventType::CompleteFindMultipartUpload(result));
+            });
+            return;
+        }
+
+        spawn(async move {
+            let result = match tokio::fs::read(&path).await {
+                Ok(bytes) => {
+                    client
+                        .put_object(&bucket, &key, bytes, content_type.as_deref())
+                        .await
+                }
+                Err(e) => Err(AppError::new("Failed to read file", e)),
+            };
+            let result = CompleteUploadObjectResult::new(result, key);
+            tx.send(AppEventType::CompleteUploadObject(result));
+        });
+    }
+
+    // The object URL is copied to share the image, it can be opened if the bucket is public
+    pub fn upload_clipboard_image(&mut self, bucket: String, prefix: String) {
+        let key = format!("{}{}", prefix, clipboard_image_file_name(Local::now()));
+        let url = match self
+            .app_objects
+            .get_bucket_items()
+            .iter()
+            .find(|b| b.name == bucket)
+        {
+            Some(bucket_item) => format!("{}{}", bucket_item.virtual_hosted_url, key),
+            None => format!("s3://{}/{}", bucket, key),
+        };
+
+        self.start_loading("Uploading image");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = match tokio::task::spawn_blocking(read_clipboard_image).await {
+                Ok(Ok(bytes)) => {
+                    client
+                        .put_object(&bucket, &key, bytes, Some("image/png"))
+                        .await
+                }
+                Ok(Err(e)) => Err(e),
+                Err(e) => Err(AppError::new("Failed to read image from clipboard", e)),
+            };
+            let result = CompleteUploadClipboardImageResult::new(result, key, url);
+            tx.send(AppEventType::CompleteUploadClipboardImage(result));
+        });
+    }
+
+    pub fn complete_upload_clipboard_image(
+        &mut self,
+        result: Result<CompleteUploadClipboardImageResult>,
+    ) {
+        match result {
+            Ok(CompleteUploadClipboardImageResult { key, url }) => match copy_to_clipboard(url) {
+                Ok(_) => {
+                    let msg = format!("Uploaded {} and copied the URL to clipboard", key);
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                }
+                Err(e) => {
+                    self.tx.send(AppEventType::NotifyError(e));
+                }
+            },
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+
+        if let Page::ObjectList(_) = self.page_stack.current_page() {
+            self.object_list_refresh();
+        }
+    }
+
+    pub fn complete_find_multipart_upload(
+        &mut self,
+        result: Result<CompleteFindMultipartUploadResult>,
+    ) {
+        match result {
+            Ok(CompleteFindMultipartUploadResult { target, upload }) => {
+                let plan = plan_parts(target.size_byte, self.ctx.config.upload.part_size_byte());
+                match upload {
+                    Some(upload) if !reusable_parts(&upload, &plan).is_empty() => {
+                        if let Some(page) = self.page_stack.current_mut::<ObjectListPage>() {
+                            page.open_resume_upload_dialog(target, upload);
+                        }
+                        self.finish_loading();
+                    }
+                    _ => {
+                        self.start_multipart_upload(target, None);
+                    }
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+            }
+        }
+    }
+
+    pub fn start_multipart_upload(
+        &mut self,
+        target: MultipartUploadTarget,
+        upload: Option<MultipartUpload>,
+    ) {
+        self.start_loading("Uploading object");
+
+        let part_size_byte = self.ctx.config.upload.part_size_byte();
+        let max_retries = self.ctx.config.upload.max_retries;
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let key = target.key.clone();
+            let result =
+                upload_in_parts(client, &tx, target, upload, part_size_byte, max_retries).await;
+            let result = CompleteUploadObjectResult::new(result, key);
+            tx.send(AppEventType::CompleteUploadObject(result));
+        });
+    }
+
+    pub fn complete_upload_object(&mut self, result: Result<CompleteUploadObjectResult>) {
+        match result {
+            Ok(CompleteUploadObjectResult { key }) => {
+                let msg = format!("Upload completed successfully: {}", key);
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+
+        if let Page::ObjectList(_) = self.page_stack.current_page() {
+            self.object_list_refresh();
+        }
+    }
+
+    pub fn start_apply_object_tags(
+        &mut self,
+        bucket: String,
+        keys: Vec<String>,
+        tags: Vec<(String, String)>,
+    ) {
+        self.start_loading("Applying tags");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let mut failed = Vec::new();
+            for (i, key) in keys.iter().enumerate() {
+                let msg = format!("Applying tags ({} out of {})", i + 1, keys.len());
+                tx.send(AppEventType::NotifyInfo(msg));
+
+                // PutObjectTagging replaces all the tags, so the current tags are merged first
+                let result = async {
+                    let current = client.load_object_tags(&bucket, key).await?;
+                    let merged = merge_tags(&current, &tags);
+                    client.put_object_tags(&bucket, key, &merged).await
+                }
+                .await;
+                if let Err(e) = result {
+                    failed.push(TagFailure {
+                        key: key.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+            let result = CompleteApplyObjectTagsResult::new(Ok(failed), bucket, keys);
+            tx.send(AppEventType::CompleteApplyObjectTags(result));
+        });
+    }
+
+    pub fn complete_apply_object_tags(&mut self, result: Result<CompleteApplyObjectTagsResult>) {
+        match result {
+            Ok(CompleteApplyObjectTagsResult {
+                bucket,
+                keys,
+                failed,
+            }) => {
+                let report = BulkOperationReport::tag(&bucket, &keys, &failed);
+                let report_path = save_bulk_operation_report(&report);
+
+                if failed.is_empty() {
+                    let mut msg = format!("Applied tags to {} objects successfully", keys.len());
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                } else {
+                    let mut msg = format!(
+                        "Applied tags to {} objects, failed for {} objects",
+                        keys.len(),
+                        failed.len()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_change_storage_class(
+        &mut self,
+        bucket: String,
+        keys: Vec<String>,
+        storage_class: StorageClassType,
+    ) {
+        self.start_loading("Changing storage class");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let mut failed = Vec::new();
+            for (i, key) in keys.iter().enumerate() {
+                let msg = format!("Changing storage class ({} out of {})", i + 1, keys.len());
+                tx.send(AppEventType::NotifyInfo(msg));
+
+                let result = client
+                    .change_storage_class(&bucket, key, storage_class.str())
+                    .await;
+                if let Err(e) = result {
+                    failed.push(CopyFailure {
+                        key: key.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+            let result =
+                CompleteChangeStorageClassResult::new(Ok(failed), bucket, storage_class, keys);
+            tx.send(AppEventType::CompleteChangeStorageClass(result));
+        });
+    }
+
+    pub fn complete_change_storage_class(
+        &mut self,
+        result: Result<CompleteChangeStorageClassResult>,
+    ) {
+        match result {
+            Ok(CompleteChangeStorageClassResult {
+                bucket,
+                storage_class,
+                keys,
+                failed,
+            }) => {
+                let report = BulkOperationReport::storage_class(&bucket, &keys, &failed);
+                let report_path = save_bulk_operation_report(&report);
+
+                if failed.is_empty() {
+                    let mut msg = format!(
+                        "Changed storage class of {} objects to {} successfully",
+                        keys.len(),
+                        storage_class.str()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                } else {
+                    let mut msg = format!(
+                        "Changed storage class of {} objects to {}, failed for {} objects",
+                        keys.len(),
+                        storage_class.str(),
+                        failed.len()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+
+        // reload so that the details do not show the old storage class
+        if let Page::ObjectList(_) = self.page_stack.current_page() {
+            self.object_list_refresh();
+        }
+    }
+
+    pub fn start_update_object_metadata(&mut self, map_key: ObjectKey, metadata: ObjectMetadata) {
+        let Some(detail) = self.app_objects.get_object_detail(&map_key).cloned() else {
+            return;
+        };
+        self.start_loading("Updating metadata");
+
+        let bucket = map_key.bucket_name.clone();
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            // the detail is loaded again since the ETag and the last modified are also changed by the copy
+            let result = async {
+                client
+                    .update_object_metadata(&bucket, &detail.key, &metadata)
+                    .await?;
+                client
+                    .load_object_detail(&bucket, &detail.key, &detail.name, detail.size_byte)
+                    .await
+            }
+            .await;
+            let result = CompleteUpdateObjectMetadataResult::new(result, map_key);
+            tx.send(AppEventType::CompleteUpdateObjectMetadata(result));
+        });
+    }
+
+    pub fn complete_update_object_metadata(
+        &mut self,
+        result: Result<CompleteUpdateObjectMetadataResult>,
+    ) {
+        match result {
+            Ok(CompleteUpdateObjectMetadataResult { detail, map_key }) => {
+                let msg = format!("Updated metadata of {}", detail.key);
+                self.app_objects
+                    .set_object_detail(map_key.clone(), *detail.clone());
+                if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+                    if *page.current_object_key() == map_key {
+                        page.set_file_detail(*detail);
+                    }
+                }
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_set_object_legal_hold(&mut self, map_key: ObjectKey, on: bool) {
+        let Some(detail) = self.app_objects.get_object_detail(&map_key).cloned() else {
+            return;
+        };
+        self.start_loading("Updating legal hold");
+
+        let bucket = map_key.bucket_name.clone();
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = async {
+                client
+                    .set_object_legal_hold(&bucket, &detail.key, on)
+                    .await?;
+                client
+                    .load_object_detail(&bucket, &detail.key, &detail.name, detail.size_byte)
+                    .await
+            }
+            .await;
+            let result = CompleteSetObjectLegalHoldResult::new(result, map_key);
+            tx.send(AppEventType::CompleteSetObjectLegalHold(result));
+        });
+    }
+
+    pub fn complete_set_object_legal_hold(
+        &mut self,
+        result: Result<CompleteSetObjectLegalHoldResult>,
+    ) {
+        match result {
+            Ok(CompleteSetObjectLegalHoldResult { detail, map_key }) => {
+                let status = if detail.legal_hold == Some(true) {
+                    "on"
+                } else {
+                    "off"
+                };
+                let msg = format!("Turned legal hold {} for {}", status, detail.key);
+                self.app_objects
+                    .set_object_detail(map_key.clone(), *detail.clone());
+                if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+                    if *page.current_object_key() == map_key {
+                        page.set_file_detail(*detail);
+                    }
+                }
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_remove_delete_marker(&mut self, map_key: ObjectKey, version: FileVersion) {
+        self.start_loading("Removing delete marker");
+
+        let bucket = map_key.bucket_name.clone();
+        let key = map_key.joined_object_path(true);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = async {
+                client
+                    .delete_object_version(&bucket, &key, &version.version_id)
+                    .await?;
+                // the versions are reloaded from the first page since the latest one may change
+                client.load_object_versions(&bucket, &key, None).await
+            }
+            .await;
+            let result = CompleteRemoveDeleteMarkerResult::new(result, map_key, version.is_latest);
+            tx.send(AppEventType::CompleteRemoveDeleteMarker(result));
+        });
+    }
+
+    pub fn complete_remove_delete_marker(
+        &mut self,
+        result: Result<CompleteRemoveDeleteMarkerResult>,
+    ) {
+        match result {
+            Ok(CompleteRemoveDeleteMarkerResult {
+                versions,
+                map_key,
+                restored,
+            }) => {
+                self.app_objects
+                    .set_object_versions(map_key.clone(), versions.clone());
+                if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+                    if *page.current_object_key() == map_key {
+                        page.set_versions(versions.versions);
+                        page.set_has_more_versions(versions.next_marker.is_some());
+                        page.select_versions_tab();
+                    }
+                }
+                let msg = if restored {
+                    "Removed the delete marker, the object has been restored"
+                } else {
+                    "Removed the delete marker"
+                };
+                self.tx.send(AppEventType::NotifySuccess(msg.into()));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn create_folder(&mut self, object_key: ObjectKey, name: String) {
+        self.start_loading("Creating folder");
+
+        let bucket = object_key.bucket_name.clone();
+        let key = format!("{}{}/", object_key.joined_object_path(false), name);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let item = client.create_folder(&bucket, &key).await;
+            let result = CompleteCreateFolderResult::new(item, object_key);
+            tx.send(AppEventType::CompleteCreateFolder(result));
+        });
+    }
+
+    pub fn complete_create_folder(&mut self, result: Result<CompleteCreateFolderResult>) {
+        match result {
+            Ok(CompleteCreateFolderResult { object_key, item }) => {
+                let msg = format!("Created folder: {}", item.name());
+                // the list is not reloaded, so the cached list is updated as well
+                if let Some(mut items) = self.app_objects.get_object_items(&object_key) {
+                    insert_dir_item(&mut items, item.clone());
+                    self.app_objects.set_object_items(object_key.clone(), items);
+                }
+                if let Page::ObjectList(page) = self.page_stack.current_page_mut() {
+                    if page.current_dir_object_key() == &object_key {
+                        page.add_dir_item(item);
+                    }
+                }
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_find_duplicate_objects(&mut self, object_key: ObjectKey) {
+        self.start_loading("Finding duplicate objects");
+
+        let bucket = object_key.bucket_name.clone();
+        let prefix = object_key.joined_object_path(false);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let groups = client
+                .list_all_object_summaries(&bucket, &prefix)
+                .await
+                .map(find_duplicate_objects);
+            let result = CompleteFindDuplicateObjectsResult::new(groups, object_key);
+            tx.send(AppEventType::CompleteFindDuplicateObjects(result));
+        });
+    }
+
+    pub fn complete_find_duplicate_objects(
+        &mut self,
+        result: Result<CompleteFindDuplicateObjectsResult>,
+    ) {
+        match result {
+            Ok(CompleteFindDuplicateObjectsResult { groups, .. }) if groups.is_empty() => {
+                let msg = "No duplicate objects found".to_string();
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Ok(CompleteFindDuplicateObjectsResult { object_key, groups }) => {
+                let page = Page::of_duplicate_objects(
+                    object_key,
+                    groups,
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                self.page_stack.push(page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_find_cleanup_objects(&mut self, object_key: ObjectKey) {
+        self.start_loading("Finding cleanup candidates");
+
+        let bucket = object_key.bucket_name.clone();
+        let prefix = object_key.joined_object_path(false);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let objects = client
+                .list_all_object_summaries(&bucket, &prefix)
+                .await
+                .map(|objs| find_cleanup_objects(&objs));
+            let result = CompleteFindCleanupObjectsResult::new(objects, object_key);
+            tx.send(AppEventType::CompleteFindCleanupObjects(result));
+        });
+    }
+
+    pub fn complete_find_cleanup_objects(
+        &mut self,
+        result: Result<CompleteFindCleanupObjectsResult>,
+    ) {
+        match result {
+            Ok(CompleteFindCleanupObjectsResult { objects, .. }) if objects.is_empty() => {
+                let msg = "No empty folders or zero-byte objects found".to_string();
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Ok(CompleteFindCleanupObjectsResult {
+                object_key,
+                objects,
+            }) => {
+                let page = Page::of_cleanup_objects(
+                    object_key,
+                    objects,
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                self.page_stack.push(page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_compare_objects(&mut self, a_key: ObjectKey, b_key: ObjectKey) {
+        self.start_loading("Comparing objects");
+
+        let a_prefix = a_key.joined_object_path(false);
+        let b_prefix = b_key.joined_object_path(false);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let (a_objs, b_objs) = tokio::join!(
+                client.list_all_object_summaries(&a_key.bucket_name, &a_prefix),
+                client.list_all_object_summaries(&b_key.bucket_name, &b_prefix),
+            );
+            let diffs = a_objs.and_then(|a_objs| {
+                b_objs.map(|b_objs| compare_objects(a_objs, &a_prefix, b_objs, &b_prefix))
+            });
+            let result = CompleteCompareObjectsResult::new(diffs, a_key, b_key);
+            tx.send(AppEventType::CompleteCompareObjects(result));
+        });
+    }
+
+    pub fn complete_compare_objects(&mut self, result: Result<CompleteCompareObjectsResult>) {
+        match result {
+            Ok(CompleteCompareObjectsResult { diffs, .. }) if diffs.is_empty() => {
+                let msg = "No differences found".to_string();
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Ok(CompleteCompareObjectsResult {
+                a_key,
+                b_key,
+                diffs,
+            }) => {
+                let page = Page::of_compare_objects(
+                    a_key,
+                    b_key,
+                    diffs,
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                self.page_stack.push(page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    // Copies the objects with the keys relative to the prefixes from `src_key` to `dst_key`
+    pub fn start_copy_object(&mut self, src_key: ObjectKey, dst_key: ObjectKey) {
+        self.start_loading("Copying object");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = client
+                .copy_object(
+                    &src_key.bucket_name,
+                    &src_key.joined_object_path(true),
+                    &dst_key.bucket_name,
+                    &dst_key.joined_object_path(true),
+                )
+                .await;
+            let result = CompleteCopyObjectResult::new(result, dst_key);
+            tx.send(AppEventType::CompleteCopyObject(result));
+        });
+    }
+
+    pub fn complete_copy_object(&mut self, result: Result<CompleteCopyObjectResult>) {
+        match result {
+            Ok(CompleteCopyObjectResult { dst_key }) => {
+                // the cached object list of the destination folder is outdated
+                let dst_dir_key = ObjectKey {
+                    bucket_name: dst_key.bucket_name.clone(),
+                    object_path: dst_key.object_path[..dst_key.object_path.len() - 1].to_vec(),
+                };
+                self.app_objects.clear_object_items_under(&dst_dir_key);
+
+                let msg = format!(
+                    "Copied to s3://{}/{} successfully",
+                    dst_key.bucket_name,
+                    dst_key.joined_object_path(true)
+                );
+                self.tx.send(AppEventType::NotifySuccess(msg));
+                self.finish_loading();
+
+                if let Page::ObjectList(page) = self.page_stack.current_page() {
+                    if *page.current_dir_object_key() == dst_dir_key {
+                        self.object_list_refresh();
+                    }
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+            }
+        }
+    }
+
+    pub fn start_sync_objects(
+        &mut self,
+        src_key: ObjectKey,
+        dst_key: ObjectKey,
+        keys: Vec<String>,
+    ) {
+        self.start_loading("Syncing objects");
+
+        // server-side copies are limited in the same way as downloads
+        let max_concurrent_requests = self.ctx.config.download.max_concurrent_requests.max(1);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let src_prefix = src_key.joined_object_path(false);
+            let dst_prefix = dst_key.joined_object_path(false);
+            let total = keys.len();
+            let mut targets = keys.iter();
+            let mut in_flight = VecDeque::new();
+            let mut done = 0;
+            let mut failed = Vec::new();
+            loop {
+                while in_flight.len() < max_concurrent_requests {
+                    let Some(key) = targets.next() else {
+                        break;
+                    };
+                    let client = client.clone();
+                    let src_bucket = src_key.bucket_name.clone();
+                    let src = format!("{}{}", src_prefix, key);
+                    let dst_bucket = dst_key.bucket_name.clone();
+                    let dst = format!("{}{}", dst_prefix, key);
+                    let handle = spawn(async move {
+                        client
+                            .copy_object(&src_bucket, &src, &dst_bucket, &dst)
+                            .await
+                    });
+                    in_flight.push_back((handle, key));
+                }
+                let Some((handle, key)) = in_flight.pop_front() else {
+                    break;
+                };
+
+                let msg = format!(
+                    "Syncing objects ({} out of {}, {} concurrent requests)",
+                    done + 1,
+                    total,
+                    in_flight.len() + 1,
+                );
+                tx.send(AppEventType::NotifyInfo(msg));
+
+                let copied = handle
+                    .await
+                    .unwrap_or_else(|e| Err(AppError::new("Failed to copy object", e)));
+                done += 1;
+                if let Err(e) = copied {
+                    failed.push(CopyFailure {
+                        key: key.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+            let result = CompleteSyncObjectsResult::new(Ok(failed), dst_key, keys);
+            tx.send(AppEventType::CompleteSyncObjects(result));
+        });
+    }
+
+    pub fn complete_sync_objects(&mut self, result: Result<CompleteSyncObjectsResult>) {
+        match result {
+            Ok(CompleteSyncObjectsResult {
+                dst_key,
+                keys,
+                failed,
+            }) => {
+                if let Page::CompareObjects(page) = self.page_stack.current_page_mut() {
+                    page.remove_diffs(&keys);
+                }
+                // the cached object lists under the destination are outdated
+                self.app_objects.clear_object_items_under(&dst_key);
+
+                // the report has the full keys of the destination
+                let dst_prefix = dst_key.joined_object_path(false);
+                let succeeded: Vec<String> = keys
+                    .iter()
+                    .map(|key| format!("{}{}", dst_prefix, key))
+                    .collect();
+                let failed: Vec<CopyFailure> = failed
+                    .into_iter()
+                    .map(|f| CopyFailure {
+                        key: format!("{}{}", dst_prefix, f.key),
+                        error: f.error,
+                    })
+                    .collect();
+                let report = BulkOperationReport::sync(&dst_key.bucket_name, &succeeded, &failed);
+                let report_path = save_bulk_operation_report(&report);
+
+                if failed.is_empty() {
+                    let mut msg = format!("Copied {} objects successfully", keys.len());
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                } else {
+                    let mut msg = format!(
+                        "Copied {} objects, failed to copy {} objects",
+                        keys.len(),
+                        failed.len()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    // The locked objects are checked in advance so that they are not deleted with a generic error
+    pub fn start_delete_objects(&mut self, bucket: String, keys: Vec<String>) {
+        self.start_loading("Checking Object Lock");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = find_locked_objects(client.as_ref(), &bucket, &keys, &tx).await;
+            let result = CompleteCheckObjectLockResult::new(result, bucket, keys);
+            tx.send(AppEventType::CompleteCheckObjectLock(result));
+        });
+    }
+
+    pub fn complete_check_object_lock(&mut self, result: Result<CompleteCheckObjectLockResult>) {
+        match result {
+            Ok(CompleteCheckObjectLockResult {
+                bucket,
+                keys,
+                locked,
+            }) => {
+                if locked.is_empty() {
+                    self.delete_objects(bucket, keys);
+                    return;
+                }
+                let locked_keys: HashSet<&str> = locked.iter().map(|o| o.key.as_str()).collect();
+                let keys = keys
+                    .iter()
+                    .filter(|key| !locked_keys.contains(key.as_str()))
+                    .cloned()
+                    .collect();
+                self.object_lock_warning = Some(ObjectLockWarning {
+                    bucket,
+                    keys,
+                    locked,
+                });
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn is_object_lock_warning_open(&self) -> bool {
+        self.object_lock_warning.is_some()
+    }
+
+    pub fn handle_object_lock_warning_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code_char!('y') => {
+                if let Some(warning) = self.object_lock_warning.take() {
+                    if !warning.keys.is_empty() {
+                        self.delete_objects(warning.bucket, warning.keys);
+                    }
+                }
+            }
+            key_code_char!('n') | key_code!(KeyCode::Esc) => {
+                self.object_lock_warning = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn delete_objects(&mut self, bucket: String, keys: Vec<String>) {
+        self.start_loading("Deleting objects");
+
+        let (client, tx) = self.unwrap_client_tx();
+        let total = keys.len();
+        let progress_tx = tx.clone();
+        let progress: Progress = Box::new(move |done| {
+            let msg = format!(
+                "Deleting objects... {} / {}",
+                format_count(done),
+                format_count(total)
+            );
+            progress_tx.send(AppEventType::NotifyInfo(msg));
+        });
+        spawn(async move {
+            let result = client.delete_objects(&bucket, &keys, progress).await;
+            let result = CompleteDeleteObjectsResult::new(result, bucket, keys);
+            tx.send(AppEventType::CompleteDeleteObjects(result));
+        });
+    }
+
+    pub fn complete_delete_objects(&mut self, result: Result<CompleteDeleteObjectsResult>) {
+        match result {
+            Ok(CompleteDeleteObjectsResult {
+                bucket,
+                keys,
+                failed,
+            }) => {
+                let object_key = match self.page_stack.current_page_mut() {
+                    Page::DuplicateObjects(page) => {
+                        page.remove_objects(&keys);
+                        Some(page.object_key().clone())
+                    }
+                    Page::CleanupObjects(page) => {
+                        page.remove_objects(&keys);
+                        Some(page.object_key().clone())
+                    }
+                    _ => None,
+                };
+                // the cached object lists under the prefix are outdated
+                if let Some(object_key) = object_key {
+                    self.app_objects.clear_object_items_under(&object_key);
+                }
+                let report = BulkOperationReport::delete(&bucket, &keys, &failed);
+                let report_path = save_bulk_operation_report(&report);
+
+                if failed.is_empty() {
+                    let mut msg = format!("Deleted {} objects successfully", keys.len());
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifySuccess(msg));
+                } else {
+                    let mut msg = format!(
+                        "Deleted {} objects, failed to delete {} objects",
+                        keys.len(),
+                        failed.len()
+                    );
+                    msg.push_str(&report_path_message(report_path));
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+
+        // reload to reflect the deleted objects, even if some of them failed
+        if let Page::ObjectList(_) = self.page_stack.current_page() {
+            self.object_list_refresh();
+        }
+    }
+
+    pub fn preview_object(&mut self, file_detail: FileDetail, version_id: Option<String>) {
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            self.finish_loading();
+            return;
+        };
+        let current_object_key = object_detail_page.current_object_key().clone();
+        let Some((bucket, key, path)) =
+            self.current_object_download_target(&file_detail.name, None, version_id.as_deref())
+        else {
+            self.finish_loading();
+            return;
+        };
+        if let Some(obj) = self.app_objects.get_preview_object(
+            &current_object_key,
+            version_id.as_deref(),
+            &file_detail.e_tag,
+        ) {
+            let mut object_preview_page = Page::of_object_preview(
+                file_detail,
+                version_id,
+                obj,
+                path.to_string_lossy().into(),
+                current_object_key,
+                Rc::clone(&self.ctx),
+                self.tx.clone(),
+            );
+            if let Some(page) = object_preview_page.get_mut::<ObjectPreviewPage>() {
+                page.set_cached();
+            }
+            self.page_stack.push(object_preview_page);
+            self.finish_loading();
+            return;
+        }
+
+        let if_match = if_match_e_tag(&file_detail, &version_id);
+
+        // images cannot be shown partially
+        let range_size = self.ctx.config.preview.range_size_byte();
+        if range_size > 0
+            && file_detail.size_byte > range_size
+            && !file_detail.content_type.starts_with("image/")
+        {
+            let (client, tx) = self.unwrap_client_tx();
+            let handle = spawn(async move {
+                let obj = client
+                    .download_object_range(
+                        &bucket,
+                        &key,
+                        version_id.clone(),
+                        if_match,
+                        0,
+                        range_size,
+                    )
+                    .await;
+                let result =
+                    CompletePreviewObjectResult::new(obj, file_detail, version_id, path, true);
+                tx.send(AppEventType::CompletePreviewObject(result));
+            });
+            self.cancellable_task = Some(handle.abort_handle());
+            return;
+        }
+
+        let size_byte = file_detail.size_byte;
+
+        let handle = self.download_object_and(
+            bucket,
+            key,
+            path,
+            size_byte,
+            version_id.clone(),
+            if_match,
+            |tx, obj, path| {
+                let result =
+                    CompletePreviewObjectResult::new(obj, file_detail, version_id, path, false);
+                tx.send(AppEventType::CompletePreviewObject(result));
+            },
+        );
+        self.cancellable_task = Some(handle);
+    }
+
+    pub fn complete_preview_object(&mut self, result: Result<CompletePreviewObjectResult>) {
+        // the preview may be loaded just before it is cancelled
+        if self.cancellable_task.take().is_none() {
+            return;
+        }
+
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            self.finish_loading();
+            return;
+        };
+        let current_object_key = object_detail_page.current_object_key().clone();
+
+        match result {
+            Ok(CompletePreviewObjectResult {
+                obj,
+                file_detail,
+                file_version_id,
+                path,
+                ranged,
+            }) => {
+                // a partially loaded object is not kept, since the rest is loaded into the page
+                if !ranged {
+                    self.app_objects.set_preview_object(
+                        current_object_key.clone(),
+                        file_version_id.clone(),
+                        file_detail.e_tag.clone(),
+                        obj.clone(),
+                        self.ctx.config.preview.cache_size,
+                    );
+                }
+                let mut object_preview_page = Page::of_object_preview(
+                    file_detail,
+                    file_version_id,
+                    obj,
+                    path.to_string_lossy().into(),
+                    current_object_key,
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                if ranged {
+                    if let Some(page) = object_preview_page.get_mut::<ObjectPreviewPage>() {
+                        page.set_ranged();
+                    }
+                }
+                self.page_stack.push(object_preview_page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        };
+        self.clear_notification();
+        self.finish_loading();
+    }
+
+    pub fn load_more_preview(&mut self) {
+        let page_id = self.page_stack.current_page_id();
+        let Page::ObjectPreview(page) = self.page_stack.current_page() else {
+            return;
+        };
+        let file_detail = page.file_detail();
+        let version_id = page.file_version_id().clone();
+        let if_match = if_match_e_tag(file_detail, &version_id);
+        let object_key = page.current_object_key();
+        let bucket = object_key.bucket_name.clone();
+        let key = object_key.joined_object_path(true);
+        let offset = page.loaded_size();
+        let len = self.ctx.config.preview.range_size_byte();
+
+        self.page_stack.start_loading(page_id);
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let obj = client
+                .download_object_range(&bucket, &key, version_id, if_match, offset, len)
+                .await;
+            tx.send(AppEventType::AppendPreviewObject(page_id, obj));
+        });
+    }
+
+    pub fn append_preview_object(&mut self, page_id: PageId, result: Result<RawObject>) {
+        self.page_stack.finish_loading(page_id);
+
+        // the preview may have been closed while loading
+        let Some(Page::ObjectPreview(page)) = self.page_stack.get_mut(page_id) else {
+            return;
+        };
+        match result {
+            Ok(obj) => {
+                page.append_object(obj);
+            }
+            Err(e) => {
+                // the rest is not loaded anymore to avoid retrying on every scroll
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    // Downloads the object again only if the ETag has been changed, e.g. to poll a status file
+    pub fn preview_refresh(&mut self) {
+        let page_id = self.page_stack.current_page_id();
+        let Page::ObjectPreview(page) = self.page_stack.current_page() else {
+            return;
+        };
+        if page.file_version_id().is_some() {
+            let msg = "The version of the object is never changed".to_string();
+            self.tx.send(AppEventType::NotifyInfo(msg));
+            return;
+        }
+        let file_detail = page.file_detail();
+        let name = file_detail.name.clone();
+        let size_byte = file_detail.size_byte;
+        let object_key = page.current_object_key();
+        let bucket = object_key.bucket_name.clone();
+        let key = object_key.joined_object_path(true);
+
+        self.page_stack.start_loading(page_id);
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let detail = client
+                .load_object_detail(&bucket, &key, &name, size_byte)
+                .await;
+            tx.send(AppEventType::CompletePreviewRefresh(page_id, detail));
+        });
+    }
+
+    pub fn complete_preview_refresh(&mut self, page_id: PageId, result: Result<FileDetail>) {
+        self.page_stack.finish_loading(page_id);
+
+        let detail = match result {
+            Ok(detail) => detail,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        // the preview may have been closed while loading
+        let Some(Page::ObjectPreview(page)) = self.page_stack.get_mut(page_id) else {
+            return;
+        };
+        if page.file_detail().e_tag == detail.e_tag {
+            let msg = format!("{} is unchanged", detail.name);
+            self.tx.send(AppEventType::NotifyInfo(msg));
+            return;
+        }
+
+        let object_key = page.current_object_key().clone();
+        self.app_objects
+            .set_object_detail(object_key, detail.clone());
+        if self.page_stack.current_page_id() != page_id {
+            return;
+        }
+        // reopened from the object detail page, in the same way as the first preview
+        self.page_stack.pop();
+        if let Page::ObjectDetail(page) = self.page_stack.current_page_mut() {
+            page.set_file_detail(detail.clone());
+        }
+        self.open_preview(detail, None);
+    }
+
+    pub fn compare_version_preview(&mut self, file_detail: FileDetail, version: FileVersion) {
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            self.finish_loading();
+            return;
+        };
+        let object_key = object_detail_page.current_object_key();
+        let bucket = object_key.bucket_name.clone();
+        let key = object_key.joined_object_path(true);
+
+        let (client, tx) = self.unwrap_client_tx();
+        let handle = spawn(async move {
+            let version_id = version.version_id;
+            let obj = client
+                .download_object(
+                    &bucket,
+                    &key,
+                    Some(version_id.clone()),
+                    None,
+                    version.size_byte,
+                    Box::new(|_| {}),
+                )
+                .await;
+            let latest_obj = client
+                .download_object(
+                    &bucket,
+                    &key,
+                    None,
+                    None,
+                    file_detail.size_byte,
+                    Box::new(|_| {}),
+                )
+                .await;
+            let result =
+                CompleteCompareVersionPreviewResult::new(obj, latest_obj, file_detail, version_id);
+            tx.send(AppEventType::CompleteCompareVersionPreview(result));
+        });
+        self.cancellable_task = Some(handle.abort_handle());
+    }
+
+    pub fn complete_compare_version_preview(
+        &mut self,
+        result: Result<CompleteCompareVersionPreviewResult>,
+    ) {
+        // the objects may be loaded just before it is cancelled
+        if self.cancellable_task.take().is_none() {
+            return;
+        }
+
+        match result {
+            Ok(CompleteCompareVersionPreviewResult {
+                obj,
+                latest_obj,
+                file_detail,
+                file_version_id,
+            }) => {
+                if infer::is_image(&obj.bytes) || infer::is_image(&latest_obj.bytes) {
+                    let msg = "Only text objects can be compared".to_string();
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                } else {
+                    let page = Page::of_object_version_compare(
+                        file_detail,
+                        file_version_id,
+                        obj,
+                        latest_obj,
+                        Rc::clone(&self.ctx),
+                        self.tx.clone(),
+                    );
+                    self.page_stack.push(page);
+                }
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        };
+        self.finish_loading();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn download_object_and<F>(
+        &self,
+        bucket: String,
+        key: String,
+        path: PathBuf,
+        size_byte: usize,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        f: F,
+    ) -> AbortHandle
+    where
+        F: FnOnce(Sender, Result<RawObject>, PathBuf) + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        let loading = self.handle_loading_size(size_byte, tx.clone());
+        let (client, _) = self.unwrap_client_tx();
+        let handle = spawn(async move {
+            let obj = client
+                .download_object(&bucket, &key, version_id, if_match, size_byte, loading)
+                .await;
+            f(tx, obj, path);
+        });
+        handle.abort_handle()
+    }
+
+    // Returns the bucket and the key of the object in the current page, and the path to save it
+    fn current_object_download_target(
+        &self,
+        object_name: &str,
+        save_file_name: Option<&str>,
+        version_id: Option<&str>,
+    ) -> Option<(String, String, PathBuf)> {
+        let object_key = if let Some(page) = self.page_stack.current::<ObjectDetailPage>() {
+            page.current_object_key()
+        } else if let Some(page) = self.page_stack.current::<ObjectPreviewPage>() {
+            page.current_object_key()
+        } else {
+            tracing::warn!("no object to download on the current page");
+            return None;
+        };
+
+        let bucket = object_key.bucket_name.clone();
+        let key = object_key.joined_object_path(true);
+
+        let file_name = match save_file_name {
+            Some(name) => name.to_string(),
+            None => self.ctx.config.download.file_name(
+                &bucket,
+                &key,
+                object_name,
+                version_id,
+                Local::now(),
+            ),
+        };
+        let path = self.ctx.config.download_file_path(&file_name);
+        Some((bucket, key, path))
+    }
+
+    fn resolve_download_path(&self, path: PathBuf) -> PathBuf {
+        self.ctx.config.download.conflict_policy.resolve(path)
+    }
+
+    fn handle_loading_size(&self, total_size: usize, tx: Sender) -> Progress {
+        if total_size < 10_000_000 {
+            return Box::new(|_| {});
+        }
+        let f = move |current| {
+            tx.send(AppEventType::LoadingTransferProgress(current, total_size));
+        };
+        Box::new(f)
+    }
+
+    pub fn bucket_list_open_management_console(&self) {
+        let (client, _) = self.unwrap_client_tx();
+        let result = client.open_management_console_buckets();
+        if let Err(e) = result {
+            self.tx.send(AppEventType::NotifyError(e));
+        }
+    }
+
+    pub fn bucket_list_toggle_pin(&mut self) {
+        let Some(page) = self.page_stack.current_mut::<BucketListPage>() else {
+            return;
+        };
+        let name = page.current_selected_item().name.clone();
+        match self.pinned_buckets.toggle(&name) {
+            Ok(pinned) => {
+                page.set_pinned_buckets(self.pinned_buckets.names());
+                page.select_bucket(&name);
+                let msg = if pinned {
+                    format!("Pinned bucket '{}'", name)
+                } else {
+                    format!("Unpinned bucket '{}'", name)
+                };
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Err(e) => {
+                let e = AppError::new("Failed to save pinned buckets", e);
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn start_load_bucket_metrics(&mut self, bucket: BucketItem) {
+        self.start_loading("Loading bucket metrics");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let metrics = client.load_bucket_metrics(&bucket.name).await;
+            let result = CompleteLoadBucketMetricsResult::new(metrics, bucket);
+            tx.send(AppEventType::CompleteLoadBucketMetrics(result));
+        });
+    }
+
+    pub fn complete_load_bucket_metrics(
+        &mut self,
+        result: Result<CompleteLoadBucketMetricsResult>,
+    ) {
+        match result {
+            Ok(CompleteLoadBucketMetricsResult { bucket, metrics }) => {
+                if metrics.is_empty() {
+                    let msg = format!("No metrics found for bucket '{}'", bucket.name);
+                    self.tx.send(AppEventType::NotifyWarn(msg));
+                }
+                // reloading replaces the current page
+                if matches!(self.page_stack.current_page(), Page::BucketMetrics(_)) {
+                    self.page_stack.pop();
+                }
+                let page =
+                    Page::of_bucket_metrics(bucket, metrics, Rc::clone(&self.ctx), self.tx.clone());
+                self.page_stack.push(page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn create_bucket(&mut self, name: String, region: String) {
+        self.start_loading("Creating bucket");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = client.create_bucket(&name, &region).await;
+            let result = CompleteCreateBucketResult::new(result, name, region);
+            tx.send(AppEventType::CompleteCreateBucket(result));
+        });
+    }
+
+    pub fn complete_create_bucket(&mut self, result: Result<CompleteCreateBucketResult>) {
+        match result {
+            Ok(CompleteCreateBucketResult { name, region }) => {
+                let msg = format!("Created bucket: {} ({})", name, region);
+                self.tx.send(AppEventType::NotifySuccess(msg));
+                // the cached buckets are reloaded, and the page is replaced with the new list
+                self.bucket_list_refresh();
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+            }
+        }
+    }
+
+    pub fn delete_bucket(&mut self, name: String) {
+        self.start_loading("Deleting bucket");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = client.delete_bucket(&name).await;
+            let result = CompleteDeleteBucketResult::new(result, name);
+            tx.send(AppEventType::CompleteDeleteBucket(result));
+        });
+    }
+
+    pub fn complete_delete_bucket(&mut self, result: Result<CompleteDeleteBucketResult>) {
+        match result {
+            Ok(CompleteDeleteBucketResult { name }) => {
+                let msg = format!("Deleted bucket: {}", name);
+                self.tx.send(AppEventType::NotifySuccess(msg));
+                // the cached buckets are reloaded, and the page is replaced with the new list
+                self.bucket_list_refresh();
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+            }
+        }
+    }
+
+    pub fn object_list_open_management_console(&self) {
+        let Some(object_list_page) = self.page_stack.current::<ObjectListPage>() else {
+            return;
+        };
+        let object_key = object_list_page.current_dir_object_key();
+
+        let (client, _) = self.unwrap_client_tx();
+        let bucket = &object_key.bucket_name;
+        let prefix = &object_key.joined_object_path(false);
+        let result = client.open_management_console_list(bucket, prefix);
+        if let Err(e) = result {
+            self.tx.send(AppEventType::NotifyError(e));
+        }
+    }
+
+    pub fn object_detail_open_management_console(&self) {
+        let Some(object_detail_page) = self.page_stack.current::<ObjectDetailPage>() else {
+            return;
+        };
+        let object_key = object_detail_page.current_object_key();
+
+        let (client, _) = self.unwrap_client_tx();
+        let bucket = &object_key.bucket_name;
+        let prefix = &object_key.joined_object_path(true); // should contains file name
+        let result = client.open_management_console_object(bucket, prefix);
+        if let Err(e) = result {
+            self.tx.send(AppEventType::NotifyError(e));
+        }
+    }
+
+    pub fn detail_download_object_as(
+        &mut self,
+        file_detail: FileDetail,
+        input: String,
+        version_id: Option<String>,
+    ) {
+        self.tx.send(AppEventType::DownloadObjectAs(
+            file_detail,
+            input,
+            version_id,
+        ));
+
+        if let Some(page) = self.page_stack.current_mut::<ObjectDetailPage>() {
+            page.close_save_dialog();
+        }
+    }
+
+    pub fn preview_download_object_as(
+        &mut self,
+        file_detail: FileDetail,
+        input: String,
+        version_id: Option<String>,
+    ) {
+        self.tx.send(AppEventType::DownloadObjectAs(
+            file_detail,
+            input,
+            version_id,
+        ));
+
+        if let Some(page) = self.page_stack.current_mut::<ObjectPreviewPage>() {
+            page.close_save_dialog();
+        }
+    }
+
+    pub fn preview_rerender_image(&mut self) {
+        let Some(object_preview_page) = self.page_stack.current_mut::<ObjectPreviewPage>() else {
+            return;
+        };
+        object_preview_page.enable_image_render();
+    }
+
+    pub fn copy_to_clipboard(&self, name: String, value: String) {
+        match copy_to_clipboard(value) {
+            Ok(_) => {
+                let msg = format!("Copied '{}' to clipboard successfully", name);
+                self.tx.send(AppEventType::NotifySuccess(msg));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn copy_presigned_url(
+        &self,
+        bucket: String,
+        key: String,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) {
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = client
+                .presign_get_object(&bucket, &key, version_id, expires_in)
+                .await;
+            match result {
+                Ok(url) => {
+                    let name = "Presigned URL".to_string();
+                    tx.send(AppEventType::CopyToClipboard(name, url));
+                }
+                Err(e) => {
+                    tx.send(AppEventType::NotifyError(e));
+                }
+            }
+        });
+    }
+
+    pub fn copy_presigned_put_url(&self, bucket: String, key: String, expires_in: Duration) {
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = client.presign_put_object(&bucket, &key, expires_in).await;
+            match result {
+                Ok(url) => {
+                    let name = "Presigned PUT URL".to_string();
+                    tx.send(AppEventType::CopyToClipboard(name, url));
+                }
+                Err(e) => {
+                    tx.send(AppEventType::NotifyError(e));
+                }
+            }
+        });
+    }
+
+    pub fn paste_location(&self) {
+        match read_clipboard().and_then(|s| parse_s3_location(&s)) {
+            Ok(location) => {
+                self.tx.send(AppEventType::OpenLocation(location));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn open_location(&mut self, location: S3Location) {
+        let bucket_exists = self
+            .app_objects
+            .get_bucket_items()
+            .iter()
+            .any(|b| b.name == location.bucket);
+        if !bucket_exists {
+            let msg = format!("Bucket '{}' not found", location.bucket);
+            self.tx.send(AppEventType::NotifyError(AppError::msg(msg)));
+            return;
+        }
+
+        self.start_loading("Opening location");
+
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = load_location_object_items(client.as_ref(), &location).await;
+            let result = CompleteOpenLocationResult::new(result, location);
+            tx.send(AppEventType::CompleteOpenLocation(result));
+        });
+    }
+
+    pub fn complete_open_location(&mut self, result: Result<CompleteOpenLocationResult>) {
+        let CompleteOpenLocationResult {
+            location,
+            object_items_list,
+            file_name,
+        } = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.start_page = None;
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+                return;
+            }
+        };
+
+        self.page_stack.clear();
+        if let Some(page) = self.page_stack.current_mut::<BucketListPage>() {
+            page.select_bucket(&location.bucket);
+        }
+
+        for (object_key, items) in object_items_list {
+            if let (Some(name), Some(page)) = (
+                object_key.object_path.last(),
+                self.page_stack.current_mut::<ObjectListPage>(),
+            ) {
+                page.select_item(name, false);
+            }
+
+            self.app_objects
+                .set_object_items(object_key.clone(), items.clone());
+            self.update_etag_index(&object_key, &items);
+            let object_list_page = self.new_object_list_page(items, object_key);
+            self.page_stack.push(object_list_page);
+        }
+        self.finish_loading();
+
+        let Some(name) = file_name else {
+            self.start_page = None;
+            return;
+        };
+        let Some(object_list_page) = self.page_stack.current_mut::<ObjectListPage>() else {
+            self.start_page = None;
+            return;
+        };
+        object_list_page.select_item(&name, true);
+        if self.start_page == Some(StartPage::ObjectList) {
+            self.start_page = None;
+            return;
+        }
+        // the preview is opened after the object detail is loaded
+        self.object_list_move_down();
+    }
+
+    pub fn start_load_inventory(&mut self, location: S3Location) {
+        self.start_loading("Loading inventory");
+
+        let bucket_items = self.app_objects.get_bucket_items();
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = load_inventory(client.as_ref(), &tx, &location, bucket_items).await;
+            let result = CompleteLoadInventoryResult::new(result);
+            tx.send(AppEventType::CompleteLoadInventory(result));
+        });
+    }
+
+    pub fn complete_load_inventory(&mut self, result: Result<CompleteLoadInventoryResult>) {
+        let CompleteLoadInventoryResult {
+            bucket,
+            object_items_list,
+            count,
+        } = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                self.finish_loading();
+                return;
+            }
+        };
+
+        let bucket_key = ObjectKey {
+            bucket_name: bucket.clone(),
+            object_path: Vec::new(),
+        };
+        // the object lists are cached so that moving between the folders does not call ListObjects,
+        // refreshing the list loads the current objects again
+        self.app_objects.clear_object_items_under(&bucket_key);
+        let mut root_items = Vec::new();
+        for (object_key, items) in object_items_list {
+            if object_key.object_path.is_empty() {
+                root_items = items.clone();
+            }
+            self.app_objects.set_object_items(object_key, items);
+        }
+
+        self.page_stack.clear();
+        if let Some(page) = self.page_stack.current_mut::<BucketListPage>() {
+            page.select_bucket(&bucket);
+        }
+        let object_list_page = self.new_object_list_page(root_items, bucket_key);
+        self.page_stack.push(object_list_page);
+        self.finish_loading();
+
+        let msg = format!("Loaded {} objects from the inventory", format_count(count));
+        self.tx.send(AppEventType::NotifySuccess(msg));
+    }
+
+    pub fn start_analyze_access_logs(&mut self, bucket: String, prefix: String) {
+        self.start_loading("Analyzing access logs");
+
+        let max_concurrent_requests = self.ctx.config.download.max_concurrent_requests.max(1);
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let summary =
+                analyze_access_logs(client, &tx, &bucket, &prefix, max_concurrent_requests).await;
+            let result = CompleteAnalyzeAccessLogsResult::new(summary, bucket, prefix);
+            tx.send(AppEventType::CompleteAnalyzeAccessLogs(result));
+        });
+    }
+
+    pub fn complete_analyze_access_logs(
+        &mut self,
+        result: Result<CompleteAnalyzeAccessLogsResult>,
+    ) {
+        match result {
+            Ok(CompleteAnalyzeAccessLogsResult { summary, .. }) if summary.file_count == 0 => {
+                let msg = "No access log files found".to_string();
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Ok(CompleteAnalyzeAccessLogsResult {
+                bucket,
+                prefix,
+                summary,
+            }) => {
+                let page = Page::of_access_log_summary(
+                    bucket,
+                    prefix,
+                    summary,
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                self.page_stack.push(page);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+        self.finish_loading();
+    }
+
+    pub fn start_watch_dir(&mut self, bucket: String, prefix: String) {
+        if let Some(session) = &self.watch_session {
+            // only one directory is watched at a time, show the current status instead
+            let page = Page::of_watch_status(
+                session.dir.clone(),
+                session.bucket.clone(),
+                session.prefix.clone(),
+                session.uploads().to_vec(),
+                Rc::clone(&self.ctx),
+                self.tx.clone(),
+            );
+            self.page_stack.push(page);
+            return;
+        }
+
+        let Some(dir) = self.ctx.config.upload.watch_dir() else {
+            let e = AppError::msg("Set upload.watch_dir in the config to watch a directory");
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        };
+
+        match WatchSession::start(dir, bucket, prefix, self.tx.clone()) {
+            Ok(session) => {
+                let page = Page::of_watch_status(
+                    session.dir.clone(),
+                    session.bucket.clone(),
+                    session.prefix.clone(),
+                    Vec::new(),
+                    Rc::clone(&self.ctx),
+                    self.tx.clone(),
+                );
+                self.page_stack.push(page);
+
+                let msg = format!(
+                    "Watching {} and uploading to s3://{}/{}",
+                    session.dir.display(),
+                    session.bucket,
+                    session.prefix
+                );
+                self.tx.send(AppEventType::NotifyInfo(msg));
+                self.watch_session = Some(session);
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn stop_watch_dir(&mut self) {
+        let Some(session) = self.watch_session.take() else {
+            return;
+        };
+        if matches!(self.page_stack.current_page(), Page::WatchStatus(_)) {
+            self.page_stack.pop();
+        }
+        let msg = format!("Stopped watching {}", session.dir.display());
+        self.tx.send(AppEventType::NotifyInfo(msg));
+    }
+
+    pub fn watch_file_changed(&mut self, path: PathBuf) {
+        let Some(session) = self.watch_session.as_mut() else {
+            return;
+        };
+        if session.file_changed(&path, Local::now()) {
+            self.schedule_watch_upload(path);
+        }
+        self.update_watch_status_pages();
+    }
+
+    pub fn start_watch_upload(&mut self, path: PathBuf) {
+        let Some(session) = self.watch_session.as_mut() else {
+            return;
+        };
+        let Some(key) = session.start_upload(&path, Local::now()) else {
+            return;
+        };
+        let bucket = session.bucket.clone();
+        self.update_watch_status_pages();
+
+        let part_size = stream_part_size(self.ctx.config.upload.part_size_byte());
+        let max_retries = self.ctx.config.upload.max_retries;
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = match tokio::fs::File::open(&path).await {
+                Ok(file) => {
+                    upload_stream(
+                        client.as_ref(),
+                        file,
+                        &bucket,
+                        &key,
+                        None,
+                        part_size,
+                        max_retries,
+                    )
+                    .await
+                }
+                Err(e) => Err(AppError::new("Failed to read file", e)),
+            };
+            tx.send(AppEventType::CompleteWatchUpload(path, result));
+        });
+    }
+
+    pub fn complete_watch_upload(&mut self, path: PathBuf, result: Result<u64>) {
+        let Some(session) = self.watch_session.as_mut() else {
+            return;
+        };
+        if let Err(e) = &result {
+            let e = AppError::msg(format!("Failed to upload {}: {}", path.display(), e.msg));
+            self.tx.send(AppEventType::NotifyError(e));
+        }
+        if session.complete_upload(&path, result, Local::now()) {
+            self.schedule_watch_upload(path);
+        }
+        self.update_watch_status_pages();
+    }
+
+    fn schedule_watch_upload(&self, path: PathBuf) {
+        let tx = self.tx.clone();
+        spawn(async move {
+            tokio::time::sleep(WATCH_UPLOAD_DELAY).await;
+            tx.send(AppEventType::StartWatchUpload(path));
+        });
+    }
+
+    fn update_watch_status_pages(&mut self) {
+        let Some(session) = &self.watch_session else {
+            return;
+        };
+        for page in self.page_stack.iter_mut() {
+            if let Page::WatchStatus(page) = page {
+                page.set_uploads(session.uploads().to_vec());
+            }
+        }
+    }
+
+    pub fn schedule_task(
+        &mut self,
+        task_type: ScheduledTaskType,
+        object_key: ObjectKey,
+        prefix: String,
+        interval_minutes: u64,
+    ) {
+        let task = self.scheduler.add(
+            task_type,
+            object_key,
+            prefix,
+            interval_minutes,
+            self.tx.clone(),
+        );
+        let msg = format!(
+            "Scheduled {} of {} every {} minutes",
+            task.task_type.str(),
+            task.s3_uri(),
+            task.interval_minutes
+        );
+        self.tx.send(AppEventType::NotifyInfo(msg));
+    }
+
+    pub fn cancel_scheduled_tasks(&mut self) {
+        let msg = match self.scheduler.cancel_all() {
+            0 => "No scheduled tasks".to_string(),
+            n => format!("Cancelled {} scheduled tasks", n),
+        };
+        self.tx.send(AppEventType::NotifyInfo(msg));
+    }
+
+    pub fn run_scheduled_task(&mut self, id: usize) {
+        let download_dir_name = |object_key: &ObjectKey, prefix: &str| {
+            prefix
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .filter(|name| !name.is_empty())
+                .unwrap_or(&object_key.bucket_name)
+                .to_string()
+        };
+
+        let Some(task) = self.scheduler.get_mut(id) else {
+            return;
+        };
+        if task.running {
+            tracing::info!(
+                "skip scheduled task {} since the previous run is not finished",
+                id
+            );
+            return;
+        }
+        task.running = true;
+
+        let task_type = task.task_type;
+        let bucket = task.object_key.bucket_name.clone();
+        let prefix = task.prefix.clone();
+        let dir = self
+            .ctx
+            .config
+            .download_file_path(&download_dir_name(&task.object_key, &prefix));
+        let (client, tx) = self.unwrap_client_tx();
+        spawn(async move {
+            let result = match task_type {
+                ScheduledTaskType::Refresh => client
+                    .load_objects(&bucket, &prefix)
+                    .await
+                    .map(ScheduledTaskOutcome::Refreshed),
+                ScheduledTaskType::Download => {
+                    download_new_objects(client, &bucket, &prefix, &dir).await
+                }
+            };
+            tx.send(AppEventType::CompleteScheduledTask(id, result));
+        });
+    }
+
+    pub fn complete_scheduled_task(&mut self, id: usize, result: Result<ScheduledTaskOutcome>) {
+        // the task may be cancelled while running
+        let Some(task) = self.scheduler.get_mut(id) else {
+            return;
+        };
+        task.running = false;
+        let s3_uri = task.s3_uri();
+        let object_key = task.object_key.clone();
+
+        match result {
+            Ok(ScheduledTaskOutcome::Refreshed(items)) => {
+                let previous = self.app_objects.get_object_items(&object_key);
+                let msg = match previous {
+                    Some(previous) => {
+                        let (new, modified) = count_object_changes(&previous, &items);
+                        format!(
+                            "Refreshed {}: {} new, {} modified objects",
+                            s3_uri, new, modified
+                        )
+                    }
+                    None => format!("Refreshed {}: {} items", s3_uri, items.len()),
+                };
+                self.update_etag_index(&object_key, &items);
+                self.app_objects.set_object_items(object_key, items);
+                self.tx.send(AppEventType::NotifyInfo(msg));
+            }
+            Ok(ScheduledTaskOutcome::Downloaded {
+                downloaded,
+                skipped,
+                failed,
+            }) => {
+                if let Some(failure) = failed.first() {
+                    let msg = format!(
+                        "Scheduled download of {}: {} objects failed ({}: {})",
+                        s3_uri,
+                        failed.len(),
+                        failure.obj.key,
+                        failure.error
+                    );
+                    self.tx.send(AppEventType::NotifyError(AppError::msg(msg)));
+                } else {
+                    let msg = format!(
+                        "Scheduled download of {}: {} downloaded, {} skipped",
+                        s3_uri, downloaded, skipped
+                    );
+                    self.tx.send(AppEventType::NotifyInfo(msg));
+                }
+            }
+            Err(e) => {
+                let msg = format!("Scheduled {} failed: {}", s3_uri, e.msg);
+                self.tx.send(AppEventType::NotifyError(AppError::msg(msg)));
+            }
+        }
+    }
+
+    pub fn is_bucket_switcher_open(&self) -> bool {
+        self.bucket_switcher.is_some()
+    }
+
+    pub fn open_bucket_switcher(&mut self) {
+        let names: Vec<String> = self
+            .app_objects
+            .get_bucket_items()
+            .iter()
+            .filter(|b| self.show_hidden || !self.ctx.config.filters.is_hidden_bucket(&b.name))
+            .map(|b| b.name.clone())
+            .collect();
+        if names.is_empty() {
+            return;
+        }
+        self.bucket_switcher = Some(BucketSwitcherDialogState::new(names));
+    }
+
+    pub fn handle_bucket_switcher_key(&mut self, key: KeyEvent) {
+        let Some(state) = self.bucket_switcher.as_mut() else {
+            return;
+        };
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.bucket_switcher = None;
+            }
+            key_code!(KeyCode::Enter) => {
+                if let Some(name) = state.selected_name() {
+                    let location = S3Location {
+                        bucket: name.to_string(),
+                        key: String::new(),
+                    };
+                    self.tx.send(AppEventType::OpenLocation(location));
+                    self.bucket_switcher = None;
+                }
+            }
+            key_code!(KeyCode::Down) | key_code_char!('n', Ctrl) => {
+                state.select_next();
+            }
+            key_code!(KeyCode::Up) | key_code_char!('p', Ctrl) => {
+                state.select_prev();
+            }
+            _ => {
+                state.handle_key_event(key);
+            }
+        }
+    }
+
+    pub fn is_profile_switcher_open(&self) -> bool {
+        self.profile_switcher.is_some()
+    }
+
+    pub fn open_profile_switcher(&mut self) {
+        if self.storage_factory.is_none() {
+            let msg = "Profiles can be switched only with the S3 backend".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+            return;
+        }
+        let names = load_profile_names();
+        if names.is_empty() {
+            let msg = "No profile found in the AWS config files".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+            return;
+        }
+        self.profile_switcher = Some(SwitcherDialogState::new(
+            names,
+            self.storage_target.profile.as_deref(),
+        ));
+    }
+
+    pub fn handle_profile_switcher_key(&mut self, key: KeyEvent) {
+        let Some(state) = self.profile_switcher.as_mut() else {
+            return;
+        };
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.profile_switcher = None;
+            }
+            key_code!(KeyCode::Enter) => {
+                if let Some(name) = state.selected_name() {
+                    let name = name.to_string();
+                    self.profile_switcher = None;
+                    let target = StorageTarget {
+                        profile: Some(name),
+                        ..self.storage_target.clone()
+                    };
+                    self.switch_storage(target);
+                }
             }
-        }
-        self.is_loading = false;
-
-        if let Page::ObjectPreview(page) = self.page_stack.current_page() {
-            if page.is_image_preview() {
-                self.tx.send(AppEventType::PreviewRerenderImage);
+            key_code!(KeyCode::Down) | key_code_char!('j') => {
+                state.select_next();
             }
+            key_code!(KeyCode::Up) | key_code_char!('k') => {
+                state.select_prev();
+            }
+            _ => {}
         }
     }
 
-    pub fn preview_object(&self, file_detail: FileDetail, version_id: Option<String>) {
-        let object_name = file_detail.name.clone();
-        let size_byte = file_detail.size_byte;
-
-        self.download_object_and(
-            &object_name,
-            size_byte,
-            None,
-            version_id.clone(),
-            |tx, obj, path| {
-                let result = CompletePreviewObjectResult::new(obj, file_detail, version_id, path);
-                tx.send(AppEventType::CompletePreviewObject(result));
-            },
-        )
+    pub fn is_region_switcher_open(&self) -> bool {
+        self.region_switcher.is_some()
     }
 
-    pub fn complete_preview_object(&mut self, result: Result<CompletePreviewObjectResult>) {
-        let object_detail_page = self.page_stack.current_page().as_object_detail();
-        let current_object_key = object_detail_page.current_object_key().clone();
+    pub fn open_region_switcher(&mut self) {
+        if self.storage_factory.is_none() {
+            let msg = "Regions can be switched only with the S3 backend".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+            return;
+        }
+        // the current region may be a custom one of an S3-compatible storage
+        let (client, _) = self.unwrap_client_tx();
+        let current = client.region().to_string();
+        let names = std::iter::once(current.as_str())
+            .chain(BUCKET_REGIONS.iter().copied().filter(|r| *r != current))
+            .map(String::from)
+            .collect();
+        self.region_switcher = Some(SwitcherDialogState::new(names, Some(&current)));
+    }
 
-        match result {
-            Ok(CompletePreviewObjectResult {
-                obj,
-                file_detail,
-                file_version_id,
-                path,
-            }) => {
-                let object_preview_page = Page::of_object_preview(
-                    file_detail,
-                    file_version_id,
-                    obj,
-                    path.to_string_lossy().into(),
-                    current_object_key,
-                    Rc::clone(&self.ctx),
-                    self.tx.clone(),
-                );
-                self.page_stack.push(object_preview_page);
+    pub fn handle_region_switcher_key(&mut self, key: KeyEvent) {
+        let Some(state) = self.region_switcher.as_mut() else {
+            return;
+        };
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.region_switcher = None;
             }
-            Err(e) => {
-                self.tx.send(AppEventType::NotifyError(e));
+            key_code!(KeyCode::Enter) => {
+                if let Some(name) = state.selected_name() {
+                    let name = name.to_string();
+                    self.region_switcher = None;
+                    let target = StorageTarget {
+                        region: Some(name),
+                        ..self.storage_target.clone()
+                    };
+                    self.switch_storage(target);
+                }
             }
-        };
-        self.clear_notification();
-        self.is_loading = false;
+            key_code!(KeyCode::Down) | key_code_char!('j') => {
+                state.select_next();
+            }
+            key_code!(KeyCode::Up) | key_code_char!('k') => {
+                state.select_prev();
+            }
+            _ => {}
+        }
     }
 
-    fn download_object_and<F>(
-        &self,
-        object_name: &str,
-        size_byte: usize,
-        save_file_name: Option<&str>,
-        version_id: Option<String>,
-        f: F,
-    ) where
-        F: FnOnce(Sender, Result<RawObject>, PathBuf) + Send + 'static,
-    {
-        let object_key = match self.page_stack.current_page() {
-            page @ Page::ObjectDetail(_) => page.as_object_detail().current_object_key(),
-            page @ Page::ObjectPreview(_) => page.as_object_preview().current_object_key(),
-            page => panic!("Invalid page: {:?}", page),
+    fn switch_storage(&mut self, target: StorageTarget) {
+        let Some(factory) = self.storage_factory.clone() else {
+            return;
         };
-
-        let bucket = object_key.bucket_name.clone();
-        let key = object_key.joined_object_path(true);
-
-        let path = self
-            .ctx
-            .config
-            .download_file_path(save_file_name.unwrap_or(object_name));
-
-        let (client, tx) = self.unwrap_client_tx();
-        let loading = self.handle_loading_size(size_byte, tx.clone());
+        let tx = self.tx.clone();
         spawn(async move {
-            let obj = client
-                .download_object(&bucket, &key, version_id, size_byte, loading)
-                .await;
-            f(tx, obj, path);
+            let client = factory.build(target.clone()).await;
+            tx.send(AppEventType::CompleteSwitchStorage(target, client));
         });
+        self.start_loading("Switching client");
     }
 
-    fn handle_loading_size(&self, total_size: usize, tx: Sender) -> Box<dyn Fn(usize) + Send> {
-        if total_size < 10_000_000 {
-            return Box::new(|_| {});
-        }
-        let decimal_places = if total_size > 1_000_000_000 { 1 } else { 0 };
-        let opt =
-            humansize::FormatSizeOptions::from(humansize::DECIMAL).decimal_places(decimal_places);
-        let total_s = humansize::format_size_i(total_size, opt);
-        let f = move |current| {
-            let percent = (current * 100) / total_size;
-            let cur_s = humansize::format_size_i(current, opt);
-            let msg = format!("{:3}% downloaded ({} out of {})", percent, cur_s, total_s);
-            tx.send(AppEventType::NotifyInfo(msg));
+    pub fn complete_switch_storage(&mut self, target: StorageTarget, client: Arc<dyn Storage>) {
+        // the buckets and objects loaded with the previous client may not be accessible anymore
+        self.app_objects.clear_all();
+        let msg = if target.profile != self.storage_target.profile {
+            format!(
+                "Switched to profile '{}'",
+                target.profile.as_deref().unwrap_or_default()
+            )
+        } else {
+            format!("Switched to region '{}'", client.region())
         };
-        Box::new(f)
-    }
+        self.tx.send(AppEventType::NotifySuccess(msg));
+        self.storage_target = target;
 
-    pub fn bucket_list_open_management_console(&self) {
-        let (client, _) = self.unwrap_client_tx();
-        let result = client.open_management_console_buckets();
-        if let Err(e) = result {
-            self.tx.send(AppEventType::NotifyError(e));
-        }
+        // the bucket list is reloaded in the same way as at startup
+        self.initialize(client, None, None, None);
     }
 
-    pub fn object_list_open_management_console(&self) {
-        let object_list_page = self.page_stack.current_page().as_object_list();
-        let object_key = object_list_page.current_dir_object_key();
-
-        let (client, _) = self.unwrap_client_tx();
-        let bucket = &object_key.bucket_name;
-        let prefix = &object_key.joined_object_path(false);
-        let result = client.open_management_console_list(bucket, prefix);
-        if let Err(e) = result {
-            self.tx.send(AppEventType::NotifyError(e));
-        }
+    pub fn is_sso_login_confirm_open(&self) -> bool {
+        self.sso_login_confirm.is_some()
     }
 
-    pub fn object_detail_open_management_console(&self) {
-        let object_detail_page = self.page_stack.current_page().as_object_detail();
-        let object_key = object_detail_page.current_object_key();
-
-        let (client, _) = self.unwrap_client_tx();
-        let bucket = &object_key.bucket_name;
-        let prefix = &object_key.joined_object_path(true); // should contains file name
-        let result = client.open_management_console_object(bucket, prefix);
-        if let Err(e) = result {
-            self.tx.send(AppEventType::NotifyError(e));
+    pub fn handle_sso_login_confirm_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code_char!('y') => {
+                self.sso_login_confirm = None;
+                self.sso_login();
+            }
+            key_code_char!('n') | key_code!(KeyCode::Esc) => {
+                if let Some(e) = self.sso_login_confirm.take() {
+                    self.notification = Notification::Error(e.msg);
+                }
+            }
+            _ => {}
         }
     }
 
-    pub fn detail_download_object_as(
-        &mut self,
-        file_detail: FileDetail,
-        input: String,
-        version_id: Option<String>,
-    ) {
-        self.tx.send(AppEventType::DownloadObjectAs(
-            file_detail,
-            input,
-            version_id,
-        ));
-        self.is_loading = true;
-
-        let page = self.page_stack.current_page_mut().as_mut_object_detail();
-        page.close_save_dialog();
+    fn sso_login(&mut self) {
+        let profile = self.storage_target.profile.clone();
+        let tx = self.tx.clone();
+        let handle = spawn(async move {
+            let prompt_tx = tx.clone();
+            let result = sso_login(profile, |prompt| {
+                prompt_tx.send(AppEventType::SsoLoginPrompt(prompt));
+            })
+            .await;
+            tx.send(AppEventType::CompleteSsoLogin(result));
+        });
+        self.start_loading("Waiting for SSO login");
+        self.cancellable_task = Some(handle.abort_handle());
+        self.sso_login_prompt = Some(SsoLoginPrompt::default());
+        self.loading_progress = Some("Complete the login in the browser".into());
     }
 
-    pub fn preview_download_object_as(
-        &mut self,
-        file_detail: FileDetail,
-        input: String,
-        version_id: Option<String>,
-    ) {
-        self.tx.send(AppEventType::DownloadObjectAs(
-            file_detail,
-            input,
-            version_id,
-        ));
-        self.is_loading = true;
-
-        let page = self.page_stack.current_page_mut().as_mut_object_preview();
-        page.close_save_dialog();
+    pub fn sso_login_prompt(&mut self, prompt: SsoLoginPrompt) {
+        // the prompt may arrive after the login is cancelled
+        if self.cancellable_task.is_some() {
+            self.sso_login_prompt = Some(prompt);
+        }
     }
 
-    pub fn preview_rerender_image(&mut self) {
-        let object_preview_page = self.page_stack.current_page_mut().as_mut_object_preview();
-        object_preview_page.enable_image_render();
-    }
+    pub fn complete_sso_login(&mut self, result: Result<()>) {
+        // the login may complete just before it is cancelled
+        if self.cancellable_task.take().is_none() {
+            return;
+        }
+        self.sso_login_prompt = None;
+        self.loading_progress = None;
+        self.finish_loading();
 
-    pub fn copy_to_clipboard(&self, name: String, value: String) {
-        match copy_to_clipboard(value) {
-            Ok(_) => {
-                let msg = format!("Copied '{}' to clipboard successfully", name);
+        match result {
+            Ok(()) => {
+                let msg = "SSO login completed".to_string();
                 self.tx.send(AppEventType::NotifySuccess(msg));
+                // the buckets can be loaded again without leaving the current page
+                if matches!(
+                    self.page_stack.current_page(),
+                    Page::Initializing(_) | Page::BucketList(_)
+                ) {
+                    self.reload_buckets();
+                }
             }
             Err(e) => {
                 self.tx.send(AppEventType::NotifyError(e));
@@ -680,7 +3998,26 @@ impl App {
     }
 
     pub fn loading(&self) -> bool {
-        self.is_loading
+        self.loading.is_some()
+    }
+
+    fn start_loading(&mut self, name: &'static str) {
+        self.loading = Some(LoadingOperation::new(name));
+    }
+
+    pub fn update_loading_transfer_progress(&mut self, current: usize, total: usize) {
+        // the progress may arrive after the loading has finished
+        if let Some(operation) = self.loading.as_mut() {
+            operation.transferred = Some((current, total));
+        }
+    }
+
+    pub fn set_slow_requests(&mut self, count: usize) {
+        self.slow_requests = count;
+    }
+
+    fn finish_loading(&mut self) {
+        self.loading = None;
     }
 
     pub fn current_notification(&self) -> &Notification {
@@ -705,6 +4042,15 @@ impl App {
 
     pub fn error_notification(&mut self, e: AppError) {
         self.handle_error(&e);
+        // the expired session can be refreshed with the AWS CLI (S3 only)
+        if self.storage_factory.is_some()
+            && self.sso_login_confirm.is_none()
+            && self.sso_login_prompt.is_none()
+            && is_sso_session_expired(&e)
+        {
+            self.sso_login_confirm = Some(e);
+            return;
+        }
         self.notification = Notification::Error(e.msg);
     }
 
@@ -716,11 +4062,50 @@ impl App {
         save_error_log(path, e).unwrap();
     }
 
+    pub fn toggle_macro_recording(&mut self) {
+        if self.key_macro.is_recording() {
+            let n = self.key_macro.stop_recording();
+            let msg = format!("Macro recorded ({} keys)", n);
+            self.tx.send(AppEventType::NotifySuccess(msg));
+        } else {
+            self.key_macro.start_recording();
+        }
+    }
+
+    pub fn replay_macro(&mut self) {
+        if !self.key_macro.replay() {
+            let msg = if self.key_macro.is_recording() {
+                "Cannot replay macro while recording"
+            } else {
+                "No macro recorded"
+            };
+            self.tx.send(AppEventType::NotifyWarn(msg.into()));
+        }
+    }
+
+    pub fn record_macro_key(&mut self, key: KeyEvent) {
+        self.key_macro.record(key);
+    }
+
+    // Returns the next key to replay if the previous one has been completely processed
+    pub fn next_macro_key(&mut self) -> Option<KeyEvent> {
+        if !self.key_macro.is_replaying() || self.loading() || self.page_stack.is_any_page_loading()
+        {
+            return None;
+        }
+        if matches!(self.notification, Notification::Error(_)) {
+            // stop replaying so that the error is not cleared by the replayed keys
+            self.key_macro.cancel_replay();
+            return None;
+        }
+        self.key_macro.next_key()
+    }
+
     pub fn dump_app(&self) {
         tracing::debug!("{:?}", self);
     }
 
-    fn unwrap_client_tx(&self) -> (Arc<Client>, Sender) {
+    fn unwrap_client_tx(&self) -> (Arc<dyn Storage>, Sender) {
         (self.client.as_ref().unwrap().clone(), self.tx.clone())
     }
 }
@@ -738,6 +4123,11 @@ impl App {
         self.render_header(f, chunks[0]);
         self.render_content(f, chunks[1]);
         self.render_footer(f, chunks[2]);
+        self.render_bucket_switcher_dialog(f);
+        self.render_profile_switcher_dialog(f);
+        self.render_region_switcher_dialog(f);
+        self.render_sso_login_confirm_dialog(f);
+        self.render_object_lock_warning_dialog(f);
         self.render_loading_dialog(f);
     }
 
@@ -755,7 +4145,10 @@ impl App {
 
     fn render_header(&self, f: &mut Frame, area: Rect) {
         if !area.is_empty() {
-            let header = Header::new(self.breadcrumb()).theme(&self.ctx.theme);
+            let header = Header::new(self.breadcrumb())
+                .loading(self.page_stack.is_current_page_loading())
+                .insecure(self.ctx.config.client.insecure_skip_tls_verify)
+                .theme(&self.ctx.theme);
             f.render_widget(header, area);
         }
     }
@@ -765,20 +4158,117 @@ impl App {
     }
 
     fn render_footer(&self, f: &mut Frame, area: Rect) {
+        let area = self.render_slow_request_indicator(f, area);
+
+        if let Some(operation) = &self.loading {
+            if let Some((current, total)) = operation.transferred {
+                let elapsed = operation.started_at.elapsed();
+                let progress = ProgressBar::new(operation.name, current, total, elapsed)
+                    .theme(&self.ctx.theme);
+                f.render_widget(progress, area);
+                return;
+            }
+        }
+
         let status_type = match self.current_notification() {
             Notification::Info(msg) => StatusType::Info(msg.into()),
             Notification::Success(msg) => StatusType::Success(msg.into()),
             Notification::Warn(msg) => StatusType::Warn(msg.into()),
             Notification::Error(msg) => StatusType::Error(msg.into()),
+            Notification::None if self.key_macro.is_recording() => {
+                StatusType::Info("Recording macro... (Ctrl-q to stop)".into())
+            }
             Notification::None => StatusType::Help(self.page_stack.current_page().short_helps()),
         };
         let status = Status::new(status_type).theme(&self.ctx.theme);
         f.render_widget(status, area);
     }
 
+    // Returns the rest of the area
+    fn render_slow_request_indicator(&self, f: &mut Frame, area: Rect) -> Rect {
+        if self.slow_requests == 0 {
+            return area;
+        }
+        let s = if self.slow_requests > 1 { "s" } else { "" };
+        let msg = format!("{} slow request{}...", self.slow_requests, s);
+        let [rest, indicator_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(msg.len() as u16 + 2),
+        ])
+        .areas(area);
+        let indicator = Paragraph::new(msg.fg(self.ctx.theme.status_warn))
+            .block(Block::default().padding(Padding::right(2)));
+        f.render_widget(indicator, indicator_area);
+        rest
+    }
+
+    fn render_bucket_switcher_dialog(&mut self, f: &mut Frame) {
+        if let Some(state) = self.bucket_switcher.as_mut() {
+            let dialog = BucketSwitcherDialog::default().theme(&self.ctx.theme);
+            f.render_stateful_widget(dialog, f.area(), state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+    }
+
+    fn render_profile_switcher_dialog(&mut self, f: &mut Frame) {
+        if let Some(state) = self.profile_switcher.as_mut() {
+            let dialog = SwitcherDialog::new("Switch profile").theme(&self.ctx.theme);
+            f.render_stateful_widget(dialog, f.area(), state);
+        }
+    }
+
+    fn render_region_switcher_dialog(&mut self, f: &mut Frame) {
+        if let Some(state) = self.region_switcher.as_mut() {
+            let dialog = SwitcherDialog::new("Switch region").theme(&self.ctx.theme);
+            f.render_stateful_widget(dialog, f.area(), state);
+        }
+    }
+
+    fn render_sso_login_confirm_dialog(&self, f: &mut Frame) {
+        if self.sso_login_confirm.is_some() {
+            let command = match &self.storage_target.profile {
+                Some(profile) => format!("aws sso login --profile {}", profile),
+                None => "aws sso login".into(),
+            };
+            let lines = vec![
+                "The SSO session has expired.".into(),
+                format!("Run `{}` to log in again?", command),
+            ];
+            let dialog = ConfirmDialog::new("SSO login", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, f.area());
+        }
+    }
+
+    fn render_object_lock_warning_dialog(&self, f: &mut Frame) {
+        if let Some(warning) = &self.object_lock_warning {
+            let lines = build_object_lock_warning_lines(warning, Local::now());
+            let dialog = ConfirmDialog::new("Object Lock", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, f.area());
+        }
+    }
+
     fn render_loading_dialog(&self, f: &mut Frame) {
-        if self.loading() {
-            let dialog = LoadingDialog::default().theme(&self.ctx.theme);
+        if let Some(operation) = &self.loading {
+            let mut details = Vec::new();
+            if let Some(progress) = &self.loading_progress {
+                details.push(progress.clone());
+            }
+            if let Some(prompt) = &self.sso_login_prompt {
+                if let Some(url) = &prompt.url {
+                    details.push(format!("URL: {}", url));
+                }
+                if let Some(code) = &prompt.code {
+                    details.push(format!("Code: {}", code));
+                }
+            }
+            if self.cancellable_task.is_some() {
+                details.push("Press Esc to cancel".into());
+            }
+            let dialog = LoadingDialog::new(operation.name, operation.started_at.elapsed())
+                .details(details)
+                .theme(&self.ctx.theme);
             f.render_widget(dialog, f.area());
         }
     }
@@ -793,11 +4283,261 @@ impl App {
 
         target_pages
             .iter()
-            .map(|page| match page {
-                Page::BucketList(page) => page.current_selected_item().name.clone(),
-                Page::ObjectList(page) => page.current_selected_item().name().into(),
+            .filter_map(|page| match page {
+                Page::BucketList(page) => Some(page.current_selected_item().name.clone()),
+                Page::ObjectList(page) => page.current_selected_item().map(|i| i.name().into()),
                 _ => unreachable!(),
             })
             .collect()
     }
 }
+
+// Reads the downloaded file again to detect truncated writes.
+fn verify_downloaded_file(
+    obj: &DownloadObjectInfo,
+    path: &Path,
+    verify_type: DownloadVerifyType,
+) -> Option<String> {
+    let Some((size_byte, _)) = local_file_info(path) else {
+        return Some("File not found after download".into());
+    };
+    let md5 = match (verify_type, obj.md5_e_tag()) {
+        (DownloadVerifyType::Etag, Some(_)) => match file_md5_hex(path) {
+            Ok(md5) => Some(md5),
+            Err(e) => return Some(e.to_string()),
+        },
+        _ => None,
+    };
+    obj.verify_local_file(size_byte, md5.as_deref())
+}
+
+// Only the objects in the buckets with Object Lock enabled are checked one by one.
+// If the configuration cannot be read, the objects are checked anyway to be on the safe side,
+// and the objects whose lock status cannot be read are treated as not locked.
+async fn find_locked_objects(
+    client: &dyn Storage,
+    bucket: &str,
+    keys: &[String],
+    tx: &Sender,
+) -> Result<Vec<LockedObject>> {
+    if let Ok(false) = client.is_object_lock_enabled(bucket).await {
+        return Ok(Vec::new());
+    }
+
+    let now = Local::now();
+    let mut locked = Vec::new();
+    for (i, key) in keys.iter().enumerate() {
+        let name = key.rsplit('/').next().unwrap_or_default();
+        if let Ok(detail) = client.load_object_detail(bucket, key, name, 0).await {
+            locked.extend(detail.locked_object(now));
+        }
+        let msg = format!(
+            "Checking Object Lock... {} / {}",
+            format_count(i + 1),
+            format_count(keys.len())
+        );
+        tx.send(AppEventType::NotifyInfo(msg));
+    }
+    Ok(locked)
+}
+
+const MAX_OBJECT_LOCK_WARNING_KEYS: usize = 10;
+
+fn build_object_lock_warning_lines(
+    warning: &ObjectLockWarning,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "{} objects are locked and cannot be deleted:",
+            format_count(warning.locked.len())
+        ),
+        "".into(),
+    ];
+    for locked in warning.locked.iter().take(MAX_OBJECT_LOCK_WARNING_KEYS) {
+        let mut reasons = Vec::new();
+        if let Some(retain_until) = locked.retain_until {
+            let remaining = (retain_until - now).to_std().unwrap_or_default();
+            let mode = if locked.mode.is_empty() {
+                "Retention"
+            } else {
+                locked.mode.as_str()
+            };
+            reasons.push(format!("{} ({} left)", mode, format_remaining(remaining)));
+        }
+        if locked.legal_hold {
+            reasons.push("Legal hold".into());
+        }
+        lines.push(format!("  {}: {}", locked.key, reasons.join(", ")));
+    }
+    if warning.locked.len() > MAX_OBJECT_LOCK_WARNING_KEYS {
+        let more = warning.locked.len() - MAX_OBJECT_LOCK_WARNING_KEYS;
+        lines.push(format!("  ... and {} more", format_count(more)));
+    }
+    lines.push("".into());
+    if warning.keys.is_empty() {
+        lines.push("No objects will be deleted.".into());
+    } else {
+        lines.push(format!(
+            "Delete the other {} objects?",
+            format_count(warning.keys.len())
+        ));
+    }
+    lines
+}
+
+// the report is optional, so a failure to save it does not fail the operation itself
+fn save_bulk_operation_report(report: &BulkOperationReport) -> Option<PathBuf> {
+    let result = Config::report_dir_path()
+        .map_err(|e| AppError::msg(e.to_string()))
+        .and_then(|dir| report.save(&dir));
+    match result {
+        Ok(path) => Some(path),
+        Err(e) => {
+            tracing::warn!("failed to save report: {}", e);
+            None
+        }
+    }
+}
+
+fn report_path_message(report_path: Option<PathBuf>) -> String {
+    report_path
+        .map(|path| format!(" (report: {})", path.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+// The ETag of the object shown in the detail is used to detect changes after viewing it.
+// Versions are immutable, so it is only checked for the latest object.
+fn if_match_e_tag(file_detail: &FileDetail, version_id: &Option<String>) -> Option<String> {
+    version_id
+        .is_none()
+        .then(|| format!("\"{}\"", file_detail.e_tag))
+}
+
+// Downloads the objects under the prefix that do not exist locally with the same size,
+// the folder structure under the folder containing the prefix is preserved
+async fn download_new_objects(
+    client: Arc<dyn Storage>,
+    bucket: &str,
+    prefix: &str,
+    dir: &Path,
+) -> Result<ScheduledTaskOutcome> {
+    let objs = client
+        .list_all_download_objects(bucket, prefix, Box::new(|_| {}))
+        .await?;
+    let base_key = match prefix.rfind('/') {
+        Some(i) => &prefix[..=i],
+        None => "",
+    };
+    let mut used_paths = HashSet::new();
+
+    let mut downloaded = 0;
+    let mut skipped = 0;
+    let mut failed = Vec::new();
+    for obj in objs.iter()? {
+        let obj = obj?;
+        let relative_path = DownloadLayoutType::PreserveStructure.relative_path(
+            base_key,
+            &obj.key,
+            &mut used_paths,
+        );
+        let path = dir.join(relative_path);
+        if DownloadSkipType::SkipSameSize.should_skip(&obj, local_file_info(&path)) {
+            skipped += 1;
+            continue;
+        }
+        let result = client
+            .download_object(
+                bucket,
+                &obj.key,
+                None,
+                None,
+                obj.size_byte,
+                Box::new(|_| {}),
+            )
+            .await
+            .and_then(|raw| save_binary(&path, &raw.bytes));
+        match result {
+            Ok(()) => downloaded += 1,
+            Err(e) => {
+                let error = e.to_string();
+                failed.push(DownloadFailure { obj, error });
+            }
+        }
+    }
+    Ok(ScheduledTaskOutcome::Downloaded {
+        downloaded,
+        skipped,
+        failed,
+    })
+}
+
+// Returns the numbers of the new and modified files
+fn count_object_changes(previous: &[ObjectItem], current: &[ObjectItem]) -> (usize, usize) {
+    let previous: HashMap<&str, &str> = previous
+        .iter()
+        .filter_map(|item| match item {
+            ObjectItem::File { s3_uri, e_tag, .. } => Some((s3_uri.as_str(), e_tag.as_str())),
+            ObjectItem::Dir { .. } => None,
+        })
+        .collect();
+    current
+        .iter()
+        .fold((0, 0), |(new, modified), item| match item {
+            ObjectItem::File { s3_uri, e_tag, .. } => match previous.get(s3_uri.as_str()) {
+                None => (new + 1, modified),
+                Some(prev) if prev != e_tag => (new, modified + 1),
+                Some(_) => (new, modified),
+            },
+            ObjectItem::Dir { .. } => (new, modified),
+        })
+}
+
+// Loads the object lists from the bucket root to the location.
+// If the location points to a file, the object list of the directory containing it is the last.
+async fn load_location_object_items(
+    client: &dyn Storage,
+    location: &S3Location,
+) -> Result<Vec<(ObjectKey, Vec<ObjectItem>)>> {
+    let paths = location.key_paths();
+    let mut object_items_list = Vec::new();
+    let mut object_path = Vec::new();
+    for (i, name) in paths.iter().enumerate() {
+        let object_key = ObjectKey {
+            bucket_name: location.bucket.clone(),
+            object_path: object_path.clone(),
+        };
+        let prefix = object_key.joined_object_path(false);
+        let items = client.load_objects(&location.bucket, &prefix).await?;
+
+        let is_last = i == paths.len() - 1;
+        let file_exists = items
+            .iter()
+            .any(|item| matches!(item, ObjectItem::File { name: n, .. } if n == name));
+        let dir_exists = items
+            .iter()
+            .any(|item| matches!(item, ObjectItem::Dir { name: n, .. } if n == name));
+        object_items_list.push((object_key, items));
+
+        if is_last && file_exists && !location.is_dir() {
+            return Ok(object_items_list);
+        }
+        if !dir_exists {
+            let msg = format!(
+                "'{}' not found in bucket '{}'",
+                location.key, location.bucket
+            );
+            return Err(AppError::msg(msg));
+        }
+        object_path.push(name.clone());
+    }
+
+    let object_key = ObjectKey {
+        bucket_name: location.bucket.clone(),
+        object_path,
+    };
+    let prefix = object_key.joined_object_path(false);
+    let items = client.load_objects(&location.bucket, &prefix).await?;
+    object_items_list.push((object_key, items));
+    Ok(object_items_list)
+}
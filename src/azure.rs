@@ -0,0 +1,1662 @@
+use std::{fmt, time::Duration};
+
+use aws_smithy_types::base64;
+use chrono::{DateTime, Local, Utc};
+use hmac::{Hmac, Mac};
+use hyper::{
+    body::HttpBody, client::HttpConnector, Body, Client as HttpClient, Method, Request, Response,
+    StatusCode,
+};
+use hyper_rustls::HttpsConnector;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use sha2::Sha256;
+
+use crate::{
+    bucket_metrics::BucketMetrics,
+    config::ClientConfig,
+    download_list::DownloadObjectList,
+    error::{AppError, Result},
+    lifecycle::LifecycleRule,
+    multipart::{MultipartUpload, UploadedPart},
+    object::{
+        BucketItem, DeleteFailure, DownloadObjectInfo, FileDetail, FileVersion, FileVersions,
+        FileVersionsMarker, ObjectItem, ObjectMetadata, ObjectSummary, ObjectsPage, RawObject,
+    },
+    storage::{
+        load_objects_in_one_page, multipart_upload_not_supported, BoxFuture, BucketAdmin,
+        ManagementConsole, MultipartUploader, ObjectStore, Presigner, Progress,
+    },
+    tls,
+};
+
+const DELIMITER: &str = "/";
+const API_VERSION: &str = "2021-08-06";
+const METADATA_HEADER_PREFIX: &str = "x-ms-meta-";
+const CONNECTION_STRING_ENV: &str = "AZURE_STORAGE_CONNECTION_STRING";
+const ACCOUNT_ENV: &str = "AZURE_STORAGE_ACCOUNT";
+const ACCOUNT_KEY_ENV: &str = "AZURE_STORAGE_KEY";
+
+// The base blob is listed with the snapshots as the latest "version"
+const CURRENT_VERSION_ID: &str = "current";
+
+// Everything except the unreserved characters and the `/` separators
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+// Shared key credentials of a storage account
+#[derive(Clone, PartialEq, Eq)]
+pub struct AzureCredentials {
+    account: String,
+    key: Vec<u8>,
+    // e.g. https://account.blob.core.windows.net or http://127.0.0.1:10000/devstoreaccount1 (Azurite)
+    endpoint: String,
+}
+
+impl fmt::Debug for AzureCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureCredentials")
+            .field("account", &self.account)
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+impl AzureCredentials {
+    // Reads the connection string, or the account name and key if it is not set.
+    pub fn from_env() -> Result<AzureCredentials> {
+        if let Ok(s) = std::env::var(CONNECTION_STRING_ENV) {
+            return AzureCredentials::from_connection_string(&s);
+        }
+        match (std::env::var(ACCOUNT_ENV), std::env::var(ACCOUNT_KEY_ENV)) {
+            (Ok(account), Ok(key)) => {
+                let endpoint = format!("https://{}.blob.core.windows.net", account);
+                AzureCredentials::new(account, &key, endpoint)
+            }
+            _ => Err(AppError::msg(format!(
+                "Either {} or {} and {} must be set",
+                CONNECTION_STRING_ENV, ACCOUNT_ENV, ACCOUNT_KEY_ENV
+            ))),
+        }
+    }
+
+    fn from_connection_string(s: &str) -> Result<AzureCredentials> {
+        let mut protocol = "https";
+        let mut suffix = "core.windows.net";
+        let (mut account, mut key, mut endpoint) = (None, None, None);
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let Some((name, value)) = part.split_once('=') else {
+                return Err(AppError::msg("Invalid connection string"));
+            };
+            match name {
+                "DefaultEndpointsProtocol" => protocol = value,
+                "EndpointSuffix" => suffix = value,
+                "AccountName" => account = Some(value),
+                "AccountKey" => key = Some(value),
+                "BlobEndpoint" => endpoint = Some(value.trim_end_matches('/').to_string()),
+                _ => {}
+            }
+        }
+        let (Some(account), Some(key)) = (account, key) else {
+            return Err(AppError::msg(
+                "AccountName and AccountKey are required in the connection string",
+            ));
+        };
+        let endpoint =
+            endpoint.unwrap_or_else(|| format!("{}://{}.blob.{}", protocol, account, suffix));
+        AzureCredentials::new(account.to_string(), key, endpoint)
+    }
+
+    fn new(account: String, key: &str, endpoint: String) -> Result<AzureCredentials> {
+        let key =
+            base64::decode(key).map_err(|e| AppError::new("Failed to decode account key", e))?;
+        Ok(AzureCredentials {
+            account,
+            key,
+            endpoint,
+        })
+    }
+
+    fn sign(&self, string_to_sign: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).unwrap();
+        mac.update(string_to_sign.as_bytes());
+        base64::encode(mac.finalize().into_bytes())
+    }
+
+    // {endpoint}/{container}/{blob}
+    fn url(&self, container: &str, blob: &str) -> String {
+        if blob.is_empty() {
+            format!("{}/{}", self.endpoint, container)
+        } else {
+            format!("{}/{}/{}", self.endpoint, container, blob)
+        }
+    }
+
+    // path of the endpoint (not empty for the path style endpoints like Azurite)
+    fn endpoint_path(&self) -> &str {
+        let rest = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, rest)| rest);
+        rest.find('/').map_or("", |i| &rest[i..])
+    }
+}
+
+#[derive(Debug)]
+enum AzureError {
+    Service {
+        status: StatusCode,
+        code: String,
+        message: String,
+    },
+    Http(hyper::Error),
+    Request(hyper::http::Error),
+    Xml(quick_xml::DeError),
+}
+
+impl fmt::Display for AzureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AzureError::Service {
+                status,
+                code,
+                message,
+            } if message.is_empty() => write!(f, "{} ({})", code, status),
+            AzureError::Service {
+                status,
+                code,
+                message,
+            } => write!(f, "{} ({}): {}", code, status, message),
+            AzureError::Http(e) => e.fmt(f),
+            AzureError::Request(e) => e.fmt(f),
+            AzureError::Xml(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AzureError {}
+
+impl From<hyper::Error> for AzureError {
+    fn from(e: hyper::Error) -> Self {
+        AzureError::Http(e)
+    }
+}
+
+impl From<hyper::http::Error> for AzureError {
+    fn from(e: hyper::http::Error) -> Self {
+        AzureError::Request(e)
+    }
+}
+
+impl From<quick_xml::DeError> for AzureError {
+    fn from(e: quick_xml::DeError) -> Self {
+        AzureError::Xml(e)
+    }
+}
+
+// Client for Azure Blob Storage, which maps containers to buckets, blobs to objects
+// and snapshots to versions.
+pub struct AzureClient {
+    http: HttpClient<HttpsConnector<HttpConnector>>,
+    credentials: AzureCredentials,
+}
+
+impl fmt::Debug for AzureClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureClient")
+            .field("credentials", &self.credentials)
+            .finish()
+    }
+}
+
+impl AzureClient {
+    pub fn new(credentials: AzureCredentials, client_config: &ClientConfig) -> AzureClient {
+        let connector = tls::https_connector(client_config);
+        let http = HttpClient::builder().build(connector);
+        AzureClient { http, credentials }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        container: &str,
+        blob: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, String)],
+    ) -> std::result::Result<Response<Body>, AzureError> {
+        self.send_with_body(method, container, blob, query, headers, Vec::new())
+            .await
+    }
+
+    async fn send_with_body(
+        &self,
+        method: Method,
+        container: &str,
+        blob: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> std::result::Result<Response<Body>, AzureError> {
+        let path = encode_path(&format!("/{}/{}", container, blob));
+        let path = if blob.is_empty() {
+            path.trim_end_matches('/').to_string()
+        } else {
+            path
+        };
+        let path = if path.is_empty() { "/".into() } else { path };
+
+        let mut headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+            .collect();
+        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        headers.push(("x-ms-date".into(), date));
+        headers.push(("x-ms-version".into(), API_VERSION.into()));
+        if !body.is_empty() {
+            headers.push(("content-length".into(), body.len().to_string()));
+        }
+
+        let resource_path = format!("{}{}", self.credentials.endpoint_path(), path);
+        let string_to_sign = string_to_sign(
+            method.as_str(),
+            &headers,
+            &self.credentials.account,
+            &resource_path,
+            query,
+        );
+        let authorization = format!(
+            "SharedKey {}:{}",
+            self.credentials.account,
+            self.credentials.sign(&string_to_sign)
+        );
+
+        let mut uri = format!("{}{}", self.credentials.endpoint, path);
+        if !query.is_empty() {
+            let query: Vec<String> = query
+                .iter()
+                .map(|(name, value)| {
+                    format!("{}={}", name, utf8_percent_encode(value, QUERY_ENCODE_SET))
+                })
+                .collect();
+            uri = format!("{}?{}", uri, query.join("&"));
+        }
+
+        let mut builder = Request::builder().method(method).uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        let request = builder
+            .header("authorization", authorization)
+            .body(Body::from(body))?;
+
+        let response = self.http.request(request).await?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let header_code = response
+            .headers()
+            .get("x-ms-error-code")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let error = quick_xml::de::from_reader::<_, ErrorResponse>(body.as_ref()).ok();
+        Err(AzureError::Service {
+            status,
+            code: error.as_ref().map_or(header_code, |e| e.code.clone()),
+            message: error.map(|e| e.message).unwrap_or_default(),
+        })
+    }
+
+    async fn send_xml<T: for<'de> Deserialize<'de>>(
+        &self,
+        container: &str,
+        query: &[(&str, &str)],
+    ) -> std::result::Result<T, AzureError> {
+        let response = self.send(Method::GET, container, "", query, &[]).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok(quick_xml::de::from_reader(body.as_ref())?)
+    }
+
+    async fn list_blobs(
+        &self,
+        container: &str,
+        prefix: &str,
+        delimiter: Option<&str>,
+        include: Option<&str>,
+        marker: Option<&str>,
+    ) -> std::result::Result<ListBlobsResult, AzureError> {
+        let mut query = vec![
+            ("comp", "list"),
+            ("prefix", prefix),
+            ("restype", "container"),
+        ];
+        if let Some(delimiter) = delimiter {
+            query.push(("delimiter", delimiter));
+        }
+        if let Some(include) = include {
+            query.push(("include", include));
+        }
+        if let Some(marker) = marker {
+            query.push(("marker", marker));
+        }
+        self.send_xml(container, &query).await
+    }
+
+    // Lists all the blobs under the prefix, "directory" blobs included.
+    async fn list_all_blobs<F>(&self, container: &str, prefix: &str, f: F) -> Result<Vec<Blob>>
+    where
+        F: Fn(usize),
+    {
+        let mut blobs = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let result = self
+                .list_blobs(container, prefix, None, None, marker.as_deref())
+                .await;
+            let output = result.map_err(|e| AppError::new("Failed to list objects", e))?;
+            blobs.extend(output.blobs.entries.into_iter().filter_map(|e| match e {
+                BlobEntry::Blob(blob) => Some(blob),
+                BlobEntry::BlobPrefix(_) => None,
+            }));
+            f(blobs.len());
+
+            marker = output.next_marker.filter(|m| !m.is_empty());
+            if marker.is_none() {
+                break;
+            }
+        }
+        Ok(blobs)
+    }
+
+    fn bucket_item(&self, name: &str) -> BucketItem {
+        let url = self.credentials.url(name, "");
+        BucketItem {
+            name: name.to_string(),
+            s3_uri: build_az_uri(name, ""),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        }
+    }
+
+    fn blob_to_object_item(&self, container: &str, blob: Blob) -> ObjectItem {
+        let name = blob.name.rsplit(DELIMITER).next().unwrap().to_string();
+        let url = self.credentials.url(container, &blob.name);
+        ObjectItem::File {
+            name,
+            size_byte: blob.properties.content_length,
+            last_modified: parse_http_date(&blob.properties.last_modified),
+            s3_uri: build_az_uri(container, &blob.name),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+            e_tag: blob.properties.e_tag.trim_matches('"').to_string(),
+            key: blob.name,
+        }
+    }
+
+    fn blob_prefix_to_object_item(&self, container: &str, prefix: BlobPrefix) -> ObjectItem {
+        let name = prefix
+            .name
+            .trim_end_matches(DELIMITER)
+            .rsplit(DELIMITER)
+            .next()
+            .unwrap()
+            .to_string();
+        let url = self.credentials.url(container, &prefix.name);
+        ObjectItem::Dir {
+            name,
+            s3_uri: build_az_uri(container, &prefix.name),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+            key: prefix.name,
+        }
+    }
+
+    pub async fn load_all_buckets(&self) -> Result<Vec<BucketItem>> {
+        let mut names = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let mut query = vec![("comp", "list")];
+            if let Some(marker) = &marker {
+                query.push(("marker", marker));
+            }
+            let result = self.send_xml::<ListContainersResult>("", &query).await;
+            let output = result.map_err(|e| AppError::new("Failed to load buckets", e))?;
+            names.extend(output.containers.containers.into_iter().map(|c| c.name));
+
+            marker = output.next_marker.filter(|m| !m.is_empty());
+            if marker.is_none() {
+                break;
+            }
+        }
+
+        if names.is_empty() {
+            return Err(AppError::msg("No buckets found"));
+        }
+        Ok(names.iter().map(|name| self.bucket_item(name)).collect())
+    }
+
+    pub async fn load_bucket(&self, name: &str) -> Result<BucketItem> {
+        let query = [("restype", "container")];
+        let result = self.send(Method::HEAD, name, "", &query, &[]).await;
+        result.map_err(|e| AppError::new(format!("Failed to load bucket '{}'", name), e))?;
+        Ok(self.bucket_item(name))
+    }
+
+    pub async fn load_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectItem>> {
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let result = self
+                .list_blobs(bucket, prefix, Some(DELIMITER), None, marker.as_deref())
+                .await;
+            let output = result.map_err(|e| AppError::new("Failed to load objects", e))?;
+            for entry in output.blobs.entries {
+                match entry {
+                    BlobEntry::Blob(blob) => files.push(self.blob_to_object_item(bucket, blob)),
+                    BlobEntry::BlobPrefix(prefix) => {
+                        dirs.push(self.blob_prefix_to_object_item(bucket, prefix))
+                    }
+                }
+            }
+
+            marker = output.next_marker.filter(|m| !m.is_empty());
+            if marker.is_none() {
+                break;
+            }
+        }
+        dirs.extend(files);
+        Ok(dirs)
+    }
+
+    pub async fn list_all_download_objects<F>(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        f: F,
+    ) -> Result<DownloadObjectList>
+    where
+        F: Fn(usize),
+    {
+        let blobs = self.list_all_blobs(bucket, prefix, f).await?;
+        let mut objs: Vec<DownloadObjectInfo> = blobs
+            .into_iter()
+            // skip "directory" blobs
+            .filter(|blob| !blob.name.ends_with(DELIMITER))
+            .map(|blob| DownloadObjectInfo {
+                size_byte: blob.properties.content_length,
+                last_modified: parse_http_date(&blob.properties.last_modified),
+                e_tag: blob.properties.e_tag,
+                key: blob.name,
+            })
+            .collect();
+        objs.sort_by(|a, b| a.key.cmp(&b.key));
+        DownloadObjectList::from_objs(objs)
+    }
+
+    pub async fn list_all_object_summaries(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<Vec<ObjectSummary>> {
+        let blobs = self.list_all_blobs(bucket, prefix, |_| {}).await?;
+        let objs = blobs
+            .into_iter()
+            .map(|blob| ObjectSummary {
+                size_byte: blob.properties.content_length,
+                last_modified: parse_http_date(&blob.properties.last_modified),
+                e_tag: blob.properties.e_tag.trim_matches('"').to_string(),
+                key: blob.name,
+            })
+            .collect();
+        Ok(objs)
+    }
+
+    pub async fn object_exists(&self, bucket: &str, key: &str) -> Result<bool> {
+        match self.send(Method::HEAD, bucket, key, &[], &[]).await {
+            Ok(_) => Ok(true),
+            Err(AzureError::Service { status, .. }) if status == StatusCode::NOT_FOUND => Ok(false),
+            Err(e) => Err(AppError::new("Failed to check object", e)),
+        }
+    }
+
+    pub async fn load_object_detail(
+        &self,
+        bucket: &str,
+        key: &str,
+        name: &str,
+        size_byte: usize,
+    ) -> Result<FileDetail> {
+        let result = self.send(Method::HEAD, bucket, key, &[], &[]).await;
+        let output = result.map_err(|e| AppError::new("Failed to load object detail", e))?;
+
+        let header = |name: &str| {
+            output
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let mut user_metadata: Vec<(String, String)> = output
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.as_str().strip_prefix(METADATA_HEADER_PREFIX)?;
+                Some((name.to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        user_metadata.sort();
+        let url = self.credentials.url(bucket, key);
+        Ok(FileDetail {
+            name: name.to_string(),
+            size_byte,
+            last_modified: parse_http_date(&header("last-modified")),
+            e_tag: header("etag").trim_matches('"').to_string(),
+            content_type: header("content-type"),
+            storage_class: header("x-ms-access-tier"),
+            cache_control: header("cache-control"),
+            content_disposition: header("content-disposition"),
+            user_metadata,
+            // immutability policies of the blob are shown as Object Lock
+            object_lock_mode: header("x-ms-immutability-policy-mode"),
+            object_lock_retain_until: Some(header("x-ms-immutability-policy-until-date"))
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_http_date(&s)),
+            legal_hold: Some(header("x-ms-legal-hold"))
+                .filter(|s| !s.is_empty())
+                .map(|s| s == "true"),
+            key: key.to_string(),
+            s3_uri: build_az_uri(bucket, key),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        })
+    }
+
+    // The snapshots of the blob are listed as the versions, newest first.
+    pub async fn load_object_versions(
+        &self,
+        bucket: &str,
+        key: &str,
+        marker: Option<FileVersionsMarker>,
+    ) -> Result<FileVersions> {
+        let marker = marker.map(|m| m.key_marker);
+        let result = self
+            .list_blobs(bucket, key, None, Some("snapshots"), marker.as_deref())
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to load object versions", e))?;
+
+        let blobs: Vec<Blob> = output
+            .blobs
+            .entries
+            .into_iter()
+            .filter_map(|e| match e {
+                BlobEntry::Blob(blob) => Some(blob),
+                BlobEntry::BlobPrefix(_) => None,
+            })
+            .collect();
+
+        // blobs are listed in the order of the names,
+        // so other blobs that start with the key (e.g. `a.txt.bak`) always come after the key itself
+        let other_key_listed = blobs.iter().any(|b| b.name != key);
+        let next_marker = output
+            .next_marker
+            .filter(|m| !m.is_empty() && !other_key_listed)
+            .map(|m| FileVersionsMarker {
+                key_marker: m,
+                version_id_marker: "".to_string(),
+            });
+
+        // snapshots are listed oldest first, followed by the base blob
+        let versions = blobs
+            .into_iter()
+            .rev()
+            .filter(|b| b.name == key)
+            .map(|b| {
+                let is_latest = b.snapshot.is_none();
+                FileVersion {
+                    version_id: b.snapshot.unwrap_or_else(|| CURRENT_VERSION_ID.to_string()),
+                    size_byte: b.properties.content_length,
+                    last_modified: parse_http_date(&b.properties.last_modified),
+                    e_tag: b.properties.e_tag.trim_matches('"').to_string(),
+                    is_latest,
+                    is_delete_marker: false,
+                }
+            })
+            .collect();
+        Ok(FileVersions {
+            versions,
+            next_marker,
+        })
+    }
+
+    pub async fn load_object_tags(&self, bucket: &str, key: &str) -> Result<Vec<(String, String)>> {
+        let result = self
+            .send(Method::GET, bucket, key, &[("comp", "tags")], &[])
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to load object tags", e))?;
+        let body = hyper::body::to_bytes(output.into_body())
+            .await
+            .map_err(|e| AppError::new("Failed to load object tags", e))?;
+        let tags: TagsResult = quick_xml::de::from_reader(body.as_ref())
+            .map_err(|e| AppError::new("Failed to parse object tags", e))?;
+        Ok(tags
+            .tag_set
+            .tags
+            .into_iter()
+            .map(|t| (t.key, t.value))
+            .collect())
+    }
+
+    pub async fn download_object<F>(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        f: F,
+    ) -> Result<RawObject>
+    where
+        F: Fn(usize),
+    {
+        let snapshot = version_id.filter(|v| v != CURRENT_VERSION_ID);
+        let query: Vec<(&str, &str)> = snapshot
+            .as_deref()
+            .map(|s| ("snapshot", s))
+            .into_iter()
+            .collect();
+        let headers: Vec<(&str, String)> = if_match.map(|e| ("If-Match", e)).into_iter().collect();
+
+        let result = self.send(Method::GET, bucket, key, &query, &headers).await;
+        let output = result.map_err(convert_download_error)?;
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(size_byte);
+        let mut body = output.into_body();
+        let mut reported = 0;
+        while let Some(buf) = body.data().await {
+            let buf = buf.map_err(|e| AppError::new("Failed to collect body", e))?;
+            bytes.extend_from_slice(&buf);
+
+            // suppress too many calls
+            if bytes.len() - reported >= 1024 * 1024 {
+                f(bytes.len());
+                reported = bytes.len();
+            }
+        }
+
+        Ok(RawObject { bytes })
+    }
+
+    pub async fn download_object_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> Result<RawObject> {
+        let snapshot = version_id.filter(|v| v != CURRENT_VERSION_ID);
+        let query: Vec<(&str, &str)> = snapshot
+            .as_deref()
+            .map(|s| ("snapshot", s))
+            .into_iter()
+            .collect();
+        let range = format!("bytes={}-{}", offset, offset + len.max(1) - 1);
+        let mut headers = vec![("x-ms-range", range)];
+        headers.extend(if_match.map(|e| ("If-Match", e)));
+
+        let result = self.send(Method::GET, bucket, key, &query, &headers).await;
+        let output = result.map_err(convert_download_error)?;
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(len);
+        let mut body = output.into_body();
+        while let Some(buf) = body.data().await {
+            let buf = buf.map_err(|e| AppError::new("Failed to collect body", e))?;
+            bytes.extend_from_slice(&buf);
+        }
+
+        Ok(RawObject { bytes })
+    }
+
+    // Generates a read-only service SAS URL
+    pub async fn presign_get_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let snapshot = version_id
+            .filter(|v| v != CURRENT_VERSION_ID)
+            .unwrap_or_default();
+        self.blob_sas_url(bucket, key, "r", &snapshot, expires_in)
+    }
+
+    // The uploader must send the `x-ms-blob-type: BlockBlob` header with the request
+    pub async fn presign_put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<String> {
+        self.blob_sas_url(bucket, key, "cw", "", expires_in)
+    }
+
+    // Builds a URL with a service SAS for the blob, or the snapshot if specified
+    fn blob_sas_url(
+        &self,
+        bucket: &str,
+        key: &str,
+        permissions: &str,
+        snapshot: &str,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let expires_in = chrono::Duration::from_std(expires_in)
+            .map_err(|e| AppError::new("Failed to build presigning config", e))?;
+        let expiry = (Utc::now() + expires_in)
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        let resource = if snapshot.is_empty() { "b" } else { "bs" };
+
+        let string_to_sign = [
+            permissions,
+            "",
+            &expiry,
+            &format!("/blob/{}/{}/{}", self.credentials.account, bucket, key),
+            "",
+            "",
+            "https,http",
+            API_VERSION,
+            resource,
+            snapshot,
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ]
+        .join("\n");
+        let signature = self.credentials.sign(&string_to_sign);
+
+        let mut params = vec![
+            ("sp", permissions),
+            ("se", &expiry),
+            ("spr", "https,http"),
+            ("sv", API_VERSION),
+            ("sr", resource),
+            ("sig", &signature),
+        ];
+        if !snapshot.is_empty() {
+            params.push(("snapshot", snapshot));
+        }
+        let query: Vec<String> = params
+            .iter()
+            .map(|(name, value)| {
+                format!("{}={}", name, utf8_percent_encode(value, QUERY_ENCODE_SET))
+            })
+            .collect();
+        Ok(format!(
+            "{}?{}",
+            encode_url(&self.credentials.url(bucket, key)),
+            query.join("&")
+        ))
+    }
+
+    pub async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<()> {
+        let headers = [
+            ("Content-Length", "0".to_string()),
+            (
+                "x-ms-copy-source",
+                encode_url(&self.credentials.url(src_bucket, src_key)),
+            ),
+        ];
+        let result = self
+            .send(Method::PUT, dst_bucket, dst_key, &[], &headers)
+            .await;
+        result.map_err(|e| AppError::new("Failed to copy object", e))?;
+        Ok(())
+    }
+
+    pub async fn put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<()> {
+        let mut headers = vec![("x-ms-blob-type", "BlockBlob".to_string())];
+        if bytes.is_empty() {
+            headers.push(("Content-Length", "0".to_string()));
+        }
+        let content_type = content_type
+            .map(String::from)
+            .or_else(|| infer::get(&bytes).map(|t| t.mime_type().to_string()));
+        if let Some(t) = content_type {
+            headers.push(("Content-Type", t));
+        }
+        let result = self
+            .send_with_body(Method::PUT, bucket, key, &[], &headers, bytes)
+            .await;
+        result.map_err(|e| AppError::new("Failed to upload object", e))?;
+        Ok(())
+    }
+
+    // Blob Storage has a flat namespace, so a zero-byte blob is put like S3
+    pub async fn create_folder(&self, bucket: &str, key: &str) -> Result<ObjectItem> {
+        self.put_object(bucket, key, Vec::new(), None).await?;
+        let prefix = BlobPrefix {
+            name: key.to_string(),
+        };
+        Ok(self.blob_prefix_to_object_item(bucket, prefix))
+    }
+
+    // The content headers and the metadata are set by separate requests.
+    // Set Blob Properties clears the headers which are not specified, so the others are taken over.
+    pub async fn update_object_metadata(
+        &self,
+        bucket: &str,
+        key: &str,
+        metadata: &ObjectMetadata,
+    ) -> Result<()> {
+        let result = async {
+            let current = self.send(Method::HEAD, bucket, key, &[], &[]).await?;
+            let header = |name: &str| {
+                current
+                    .headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let properties = [
+                ("x-ms-blob-content-type", metadata.content_type.clone()),
+                ("x-ms-blob-cache-control", metadata.cache_control.clone()),
+                (
+                    "x-ms-blob-content-disposition",
+                    metadata.content_disposition.clone(),
+                ),
+                ("x-ms-blob-content-encoding", header("content-encoding")),
+                ("x-ms-blob-content-language", header("content-language")),
+                ("x-ms-blob-content-md5", header("content-md5")),
+            ];
+            let mut headers = vec![("Content-Length", "0".to_string())];
+            headers.extend(properties.into_iter().filter(|(_, v)| !v.is_empty()));
+            let query = [("comp", "properties")];
+            self.send(Method::PUT, bucket, key, &query, &headers)
+                .await?;
+
+            let names: Vec<String> = metadata
+                .user_metadata
+                .iter()
+                .map(|(name, _)| format!("{}{}", METADATA_HEADER_PREFIX, name))
+                .collect();
+            let mut headers = vec![("Content-Length", "0".to_string())];
+            headers.extend(
+                names
+                    .iter()
+                    .zip(&metadata.user_metadata)
+                    .map(|(name, (_, value))| (name.as_str(), value.clone())),
+            );
+            let query = [("comp", "metadata")];
+            self.send(Method::PUT, bucket, key, &query, &headers).await
+        }
+        .await;
+        result.map_err(|e| AppError::new("Failed to update object metadata", e))?;
+        Ok(())
+    }
+
+    // Fails if version-level immutability is not enabled on the container
+    pub async fn set_object_legal_hold(&self, bucket: &str, key: &str, on: bool) -> Result<()> {
+        let headers = [
+            ("Content-Length", "0".to_string()),
+            ("x-ms-legal-hold", on.to_string()),
+        ];
+        let result = self
+            .send(Method::PUT, bucket, key, &[("comp", "legalhold")], &headers)
+            .await;
+        result.map_err(|e| AppError::new("Failed to set legal hold", e))?;
+        Ok(())
+    }
+
+    pub async fn put_object_tags(
+        &self,
+        bucket: &str,
+        key: &str,
+        tags: &[(String, String)],
+    ) -> Result<()> {
+        let body = build_tags_xml(tags).into_bytes();
+        let headers = [("Content-Type", "application/xml".to_string())];
+        let result = self
+            .send_with_body(
+                Method::PUT,
+                bucket,
+                key,
+                &[("comp", "tags")],
+                &headers,
+                body,
+            )
+            .await;
+        result.map_err(|e| AppError::new("Failed to put object tags", e))?;
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        // a blob that has snapshots cannot be deleted without them
+        let headers = [("x-ms-delete-snapshots", "include".to_string())];
+        let result = self.send(Method::DELETE, bucket, key, &[], &headers).await;
+        result.map_err(|e| AppError::new("Failed to delete object", e))?;
+        Ok(())
+    }
+
+    // Blobs are deleted one by one, a failure does not stop the deletion of the remaining keys.
+    pub async fn delete_objects<F>(
+        &self,
+        bucket: &str,
+        keys: &[String],
+        f: F,
+    ) -> Result<Vec<DeleteFailure>>
+    where
+        F: Fn(usize),
+    {
+        let mut failed = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            if let Err(e) = self.delete_object(bucket, key).await {
+                failed.push(DeleteFailure {
+                    key: key.clone(),
+                    error: e.to_string(),
+                });
+            }
+            f(i + 1);
+        }
+        Ok(failed)
+    }
+}
+
+fn build_tags_xml(tags: &[(String, String)]) -> String {
+    let tags: String = tags
+        .iter()
+        .map(|(k, v)| {
+            let (k, v) = (quick_xml::escape::escape(k), quick_xml::escape::escape(v));
+            format!("<Tag><Key>{}</Key><Value>{}</Value></Tag>", k, v)
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?><Tags><TagSet>{}</TagSet></Tags>",
+        tags
+    )
+}
+
+fn build_az_uri(container: &str, blob: &str) -> String {
+    format!("az://{}/{}", container, blob)
+}
+
+fn encode_path(path: &str) -> String {
+    utf8_percent_encode(path, PATH_ENCODE_SET).to_string()
+}
+
+fn encode_url(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => match rest.split_once('/') {
+            Some((host, path)) => format!("{}://{}/{}", scheme, host, encode_path(path)),
+            None => url.to_string(),
+        },
+        None => url.to_string(),
+    }
+}
+
+fn parse_http_date(s: &str) -> DateTime<Local> {
+    DateTime::parse_from_rfc2822(s)
+        .map(|dt| dt.with_timezone(&Local))
+        .unwrap_or_default()
+}
+
+// https://learn.microsoft.com/en-us/rest/api/storageservices/authorize-with-shared-key
+// `headers` must have lowercase names, `path` must be encoded.
+fn string_to_sign(
+    method: &str,
+    headers: &[(String, String)],
+    account: &str,
+    path: &str,
+    query: &[(&str, &str)],
+) -> String {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or("", |(_, v)| v.as_str())
+    };
+    let content_length = match header("content-length") {
+        "0" => "",
+        len => len,
+    };
+
+    let mut ms_headers: Vec<&(String, String)> = headers
+        .iter()
+        .filter(|(n, _)| n.starts_with("x-ms-"))
+        .collect();
+    ms_headers.sort();
+    let canonicalized_headers: String = ms_headers
+        .iter()
+        .map(|(n, v)| format!("{}:{}\n", n, v.trim()))
+        .collect();
+
+    let mut query: Vec<(String, &str)> = query
+        .iter()
+        .map(|(n, v)| (n.to_ascii_lowercase(), *v))
+        .collect();
+    query.sort();
+    let canonicalized_query: String = query
+        .iter()
+        .map(|(n, v)| format!("\n{}:{}", n, v))
+        .collect();
+
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}/{}{}{}",
+        method,
+        header("content-encoding"),
+        header("content-language"),
+        content_length,
+        header("content-md5"),
+        header("content-type"),
+        header("date"),
+        header("if-modified-since"),
+        header("if-match"),
+        header("if-none-match"),
+        header("if-unmodified-since"),
+        header("range"),
+        canonicalized_headers,
+        account,
+        path,
+        canonicalized_query,
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ErrorResponse {
+    code: String,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListContainersResult {
+    #[serde(default)]
+    containers: Containers,
+    next_marker: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Containers {
+    #[serde(rename = "Container", default)]
+    containers: Vec<Container>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Container {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListBlobsResult {
+    #[serde(default)]
+    blobs: Blobs,
+    next_marker: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Blobs {
+    #[serde(rename = "$value", default)]
+    entries: Vec<BlobEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+enum BlobEntry {
+    Blob(Blob),
+    BlobPrefix(BlobPrefix),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Blob {
+    name: String,
+    snapshot: Option<String>,
+    properties: BlobProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobProperties {
+    #[serde(rename = "Last-Modified")]
+    last_modified: String,
+    #[serde(rename = "Etag")]
+    e_tag: String,
+    #[serde(rename = "Content-Length")]
+    content_length: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlobPrefix {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TagsResult {
+    #[serde(default)]
+    tag_set: TagSet,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TagSet {
+    #[serde(rename = "Tag", default)]
+    tags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Tag {
+    key: String,
+    value: String,
+}
+
+fn convert_download_error(e: AzureError) -> AppError {
+    if matches!(e, AzureError::Service { status, .. } if status == StatusCode::PRECONDITION_FAILED)
+    {
+        AppError::msg("Object has changed since you viewed it, please reload and try again")
+    } else {
+        AppError::new("Failed to download object", e)
+    }
+}
+
+impl ObjectStore for AzureClient {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>> {
+        Box::pin(AzureClient::load_objects(self, bucket, prefix))
+    }
+
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        _token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>> {
+        load_objects_in_one_page(Box::pin(AzureClient::load_objects(self, bucket, prefix)))
+    }
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>> {
+        Box::pin(AzureClient::list_all_download_objects(
+            self, bucket, prefix, progress,
+        ))
+    }
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>> {
+        Box::pin(AzureClient::list_all_object_summaries(self, bucket, prefix))
+    }
+
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(AzureClient::object_exists(self, bucket, key))
+    }
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>> {
+        Box::pin(AzureClient::load_object_detail(
+            self, bucket, key, name, size_byte,
+        ))
+    }
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>> {
+        Box::pin(AzureClient::load_object_versions(self, bucket, key, marker))
+    }
+
+    fn load_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(AzureClient::load_object_tags(self, bucket, key))
+    }
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(AzureClient::download_object(
+            self, bucket, key, version_id, if_match, size_byte, progress,
+        ))
+    }
+
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(AzureClient::download_object_range(
+            self, bucket, key, version_id, if_match, offset, len,
+        ))
+    }
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::copy_object(
+            self, src_bucket, src_key, dst_bucket, dst_key,
+        ))
+    }
+
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::put_object(
+            self,
+            bucket,
+            key,
+            bytes,
+            content_type,
+        ))
+    }
+
+    fn put_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::put_object_tags(self, bucket, key, tags))
+    }
+
+    fn change_storage_class<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Storage classes are not supported for Azure Blob Storage",
+            ))
+        })
+    }
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::update_object_metadata(
+            self, bucket, key, metadata,
+        ))
+    }
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        on: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::set_object_legal_hold(self, bucket, key, on))
+    }
+
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>> {
+        Box::pin(AzureClient::create_folder(self, bucket, key))
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(AzureClient::delete_object(self, bucket, key))
+    }
+
+    fn delete_object_version<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Deleting a version is not supported for Azure Blob Storage",
+            ))
+        })
+    }
+
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>> {
+        Box::pin(AzureClient::delete_objects(self, bucket, keys, progress))
+    }
+}
+
+impl BucketAdmin for AzureClient {
+    fn region(&self) -> &str {
+        &self.credentials.account
+    }
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>> {
+        Box::pin(AzureClient::load_all_buckets(self))
+    }
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>> {
+        Box::pin(AzureClient::load_bucket(self, name))
+    }
+
+    // Lifecycle management policies can only be read through the Azure Resource Manager API
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        _bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Lifecycle rules are not supported for Azure Blob Storage",
+            ))
+        })
+    }
+
+    // Legal holds and immutability policies can be set on any blob, so the blobs are always checked
+    fn is_object_lock_enabled<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async { Ok(true) })
+    }
+
+    fn load_bucket_metrics<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Bucket metrics are not supported for Azure Blob Storage",
+            ))
+        })
+    }
+
+    fn create_bucket<'a>(&'a self, _name: &'a str, _region: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Creating containers is not supported for Azure Blob Storage",
+            ))
+        })
+    }
+
+    fn delete_bucket<'a>(&'a self, _name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Deleting containers is not supported for Azure Blob Storage",
+            ))
+        })
+    }
+}
+
+impl Presigner for AzureClient {
+    fn presign_get_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(AzureClient::presign_get_object(
+            self, bucket, key, version_id, expires_in,
+        ))
+    }
+
+    fn presign_put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(AzureClient::presign_put_object(
+            self, bucket, key, expires_in,
+        ))
+    }
+}
+
+impl MultipartUploader for AzureClient {
+    fn supports_multipart_upload(&self) -> bool {
+        false
+    }
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn create_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn upload_part<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _part_number: i32,
+        _bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+}
+
+impl ManagementConsole for AzureClient {
+    fn open_management_console_buckets(&self) -> Result<()> {
+        Err(management_console_not_supported())
+    }
+
+    fn open_management_console_list(&self, _bucket: &str, _prefix: &str) -> Result<()> {
+        Err(management_console_not_supported())
+    }
+
+    fn open_management_console_object(&self, _bucket: &str, _prefix: &str) -> Result<()> {
+        Err(management_console_not_supported())
+    }
+}
+
+fn management_console_not_supported() -> AppError {
+    AppError::msg("Management console is not supported for Azure Blob Storage")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the well-known key of Azurite
+    const DEV_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    #[test]
+    fn test_credentials_from_connection_string() {
+        let s = format!(
+            "DefaultEndpointsProtocol=https;AccountName=foo;AccountKey={};EndpointSuffix=core.windows.net",
+            DEV_KEY
+        );
+        let credentials = AzureCredentials::from_connection_string(&s).unwrap();
+        assert_eq!(credentials.account, "foo");
+        assert_eq!(credentials.endpoint, "https://foo.blob.core.windows.net");
+        assert_eq!(credentials.endpoint_path(), "");
+
+        let s = format!(
+            "AccountName=devstoreaccount1;AccountKey={};BlobEndpoint=http://127.0.0.1:10000/devstoreaccount1/;",
+            DEV_KEY
+        );
+        let credentials = AzureCredentials::from_connection_string(&s).unwrap();
+        assert_eq!(
+            credentials.endpoint,
+            "http://127.0.0.1:10000/devstoreaccount1"
+        );
+        assert_eq!(credentials.endpoint_path(), "/devstoreaccount1");
+
+        assert!(AzureCredentials::from_connection_string("AccountName=foo").is_err());
+    }
+
+    #[test]
+    fn test_string_to_sign() {
+        let headers = vec![
+            ("x-ms-version".to_string(), API_VERSION.to_string()),
+            ("if-match".to_string(), "\"0x1\"".to_string()),
+            (
+                "x-ms-date".to_string(),
+                "Mon, 27 Jan 2025 10:00:00 GMT".to_string(),
+            ),
+            ("content-length".to_string(), "0".to_string()),
+        ];
+        let query = [
+            ("restype", "container"),
+            ("comp", "list"),
+            ("prefix", "a b/"),
+        ];
+        let actual = string_to_sign("GET", &headers, "foo", "/bar", &query);
+        let expected = [
+            "GET",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "\"0x1\"",
+            "",
+            "",
+            "",
+            "x-ms-date:Mon, 27 Jan 2025 10:00:00 GMT",
+            "x-ms-version:2021-08-06",
+            "/foo/bar",
+            "comp:list",
+            "prefix:a b/",
+            "restype:container",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_list_blobs_result() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://foo.blob.core.windows.net/" ContainerName="bar">
+  <Prefix>dir/</Prefix>
+  <Delimiter>/</Delimiter>
+  <Blobs>
+    <BlobPrefix><Name>dir/sub/</Name></BlobPrefix>
+    <Blob>
+      <Name>dir/a.txt</Name>
+      <Properties>
+        <Last-Modified>Mon, 27 Jan 2025 10:00:00 GMT</Last-Modified>
+        <Etag>0x8DD3EB0A5E0F3C1</Etag>
+        <Content-Length>123</Content-Length>
+        <Content-Type>text/plain</Content-Type>
+        <AccessTier>Hot</AccessTier>
+      </Properties>
+    </Blob>
+  </Blobs>
+  <NextMarker />
+</EnumerationResults>"#;
+        let result: ListBlobsResult = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(result.blobs.entries.len(), 2);
+        assert!(
+            matches!(&result.blobs.entries[0], BlobEntry::BlobPrefix(p) if p.name == "dir/sub/")
+        );
+        let BlobEntry::Blob(blob) = &result.blobs.entries[1] else {
+            panic!("expected a blob");
+        };
+        assert_eq!(blob.name, "dir/a.txt");
+        assert_eq!(blob.snapshot, None);
+        assert_eq!(blob.properties.content_length, 123);
+        assert_eq!(blob.properties.e_tag, "0x8DD3EB0A5E0F3C1");
+        assert!(result.next_marker.filter(|m| !m.is_empty()).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+
+// S3 storage metrics are reported to CloudWatch once a day
+pub const BUCKET_METRICS_PERIOD_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricPoint {
+    pub timestamp: DateTime<Local>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BucketMetrics {
+    pub size_bytes: Vec<MetricPoint>,
+    pub object_count: Vec<MetricPoint>,
+}
+
+impl BucketMetrics {
+    pub fn is_empty(&self) -> bool {
+        self.size_bytes.is_empty() && self.object_count.is_empty()
+    }
+}
+
+// BucketSizeBytes is reported for each storage type, so the series are summed up into one.
+// The returned points are sorted by timestamp.
+#[cfg_attr(not(feature = "cloudwatch"), allow(dead_code))]
+pub fn sum_metric_series(series: Vec<Vec<MetricPoint>>) -> Vec<MetricPoint> {
+    let mut sums: BTreeMap<DateTime<Local>, f64> = BTreeMap::new();
+    for point in series.into_iter().flatten() {
+        *sums.entry(point.timestamp).or_default() += point.value;
+    }
+    sums.into_iter()
+        .map(|(timestamp, value)| MetricPoint { timestamp, value })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn point(day: u32, value: f64) -> MetricPoint {
+        MetricPoint {
+            timestamp: Local.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_sum_metric_series() {
+        let series = vec![
+            vec![point(2, 10.0), point(1, 5.0)],
+            vec![point(1, 1.0), point(3, 2.0)],
+            vec![],
+        ];
+        let actual = sum_metric_series(series);
+        let expected = vec![point(1, 6.0), point(2, 10.0), point(3, 2.0)];
+        assert_eq!(actual, expected);
+    }
+}
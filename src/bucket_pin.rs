@@ -0,0 +1,96 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+// Remembers the buckets pinned to the top of the bucket list across sessions.
+//
+// The file has one bucket name per line, in the order they were pinned.
+#[derive(Debug, Default)]
+pub struct PinnedBuckets {
+    file_path: Option<PathBuf>,
+    names: Vec<String>,
+}
+
+impl PinnedBuckets {
+    pub fn load(file_path: PathBuf) -> PinnedBuckets {
+        let contents = match fs::read_to_string(&file_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                tracing::warn!("failed to read pinned buckets: {}", e);
+                String::new()
+            }
+        };
+        PinnedBuckets {
+            file_path: Some(file_path),
+            ..PinnedBuckets::parse(&contents)
+        }
+    }
+
+    fn parse(contents: &str) -> PinnedBuckets {
+        let mut names: Vec<String> = Vec::new();
+        for line in contents.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if !names.iter().any(|n| n == line) {
+                names.push(line.to_string());
+            }
+        }
+        PinnedBuckets {
+            file_path: None,
+            names,
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    // Returns whether the bucket is pinned after toggling
+    pub fn toggle(&mut self, name: &str) -> io::Result<bool> {
+        let pinned = if let Some(i) = self.names.iter().position(|n| n == name) {
+            self.names.remove(i);
+            false
+        } else {
+            self.names.push(name.to_string());
+            true
+        };
+        self.write()?;
+        Ok(pinned)
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+        let temp_file_path = file_path.with_extension("tmp");
+        let mut file = fs::File::create(&temp_file_path)?;
+        file.write_all(self.contents().as_bytes())?;
+        fs::rename(temp_file_path, file_path)
+    }
+
+    fn contents(&self) -> String {
+        self.names.iter().map(|n| format!("{}\n", n)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_buckets() {
+        let mut pinned = PinnedBuckets::default();
+        assert!(pinned.toggle("foo").unwrap());
+        assert!(pinned.toggle("bar").unwrap());
+        assert!(pinned.toggle("baz").unwrap());
+        assert!(!pinned.toggle("bar").unwrap());
+        assert_eq!(pinned.names(), ["foo", "baz"]);
+
+        let pinned = PinnedBuckets::parse(&pinned.contents());
+        assert_eq!(pinned.names(), ["foo", "baz"]);
+
+        let pinned = PinnedBuckets::parse("foo\n\nbar\nfoo\n");
+        assert_eq!(pinned.names(), ["foo", "bar"]);
+    }
+}
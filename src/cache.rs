@@ -43,6 +43,11 @@ impl SimpleStringCache {
         Ok(())
     }
 
+    pub fn remove(&self, key: &str) {
+        let mut cache = self.cache.write().unwrap();
+        cache.remove(key);
+    }
+
     pub fn get(&self, key: &str) -> Option<String> {
         let cache = self.cache.read().unwrap();
         cache.get(key).cloned()
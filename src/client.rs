@@ -1,17 +1,66 @@
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
 use aws_config::{default_provider::region, meta::region::RegionProviderChain, BehaviorVersion};
-use aws_sdk_s3::{config::Region, operation::list_objects_v2::ListObjectsV2Output};
+#[cfg(feature = "cloudwatch")]
+use aws_sdk_cloudwatch::{
+    primitives::DateTime as CloudWatchDateTime,
+    types::{Dimension, DimensionFilter, Statistic},
+};
+use aws_sdk_s3::{
+    config::{
+        http::HttpResponse, interceptors::BeforeTransmitInterceptorContextRef, ConfigBag,
+        Intercept, Region, RuntimeComponents,
+    },
+    error::{BoxError, ProvideErrorMetadata, SdkError},
+    operation::{
+        copy_object::builders::CopyObjectFluentBuilder,
+        get_object::{builders::GetObjectFluentBuilder, GetObjectError},
+        head_object::{builders::HeadObjectFluentBuilder, HeadObjectOutput},
+        list_objects_v2::ListObjectsV2Output,
+    },
+    presigning::PresigningConfig,
+    primitives::ByteStream,
+    types::{
+        BucketLocationConstraint, CompletedMultipartUpload, CompletedPart,
+        CreateBucketConfiguration, Delete, ExpirationStatus, MetadataDirective, ObjectIdentifier,
+        ObjectLockEnabled, ObjectLockLegalHold, ObjectLockLegalHoldStatus, ServerSideEncryption,
+        StorageClass, Tag, Tagging,
+    },
+};
+use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
+use aws_smithy_types::{
+    base64,
+    config_bag::{Storable, StoreReplace},
+    retry::RetryConfig as SdkRetryConfig,
+    timeout::TimeoutConfig,
+};
 use chrono::TimeZone;
+use md5::{Digest, Md5};
 
+#[cfg(feature = "cloudwatch")]
+use crate::bucket_metrics::{sum_metric_series, MetricPoint, BUCKET_METRICS_PERIOD_DAYS};
 use crate::{
+    bucket_metrics::BucketMetrics,
     cache::SimpleStringCache,
-    config::Config,
+    config::{ClientConfig, Config, RetryConfig, RetryMode},
+    download_list::{DownloadObjectList, DownloadObjectListWriter},
     error::{AppError, Result},
-    object::{BucketItem, FileDetail, FileVersion, ObjectItem, RawObject},
+    lifecycle::{LifecycleRule, LifecycleSchedule, LifecycleTransition},
+    multipart::{MultipartUpload, UploadedPart},
+    object::{
+        BucketItem, DeleteFailure, DownloadObjectInfo, FileDetail, FileVersion, FileVersions,
+        FileVersionsMarker, ObjectItem, ObjectMetadata, ObjectSummary, ObjectsPage, RawObject,
+    },
+    tls,
 };
 
 const DELIMITER: &str = "/";
+const SSE_CUSTOMER_ALGORITHM: &str = "AES256";
+const VERSIONS_PAGE_SIZE: i32 = 1000;
+pub const DELETE_OBJECTS_MAX_KEYS: usize = 1000;
+
+// Called with the attempt number and the max attempts before a request is retried
+pub type RetryCallback = Arc<dyn Fn(u32, u32) + Send + Sync>;
 
 pub enum AddressingStyle {
     Auto,
@@ -29,10 +78,121 @@ impl AddressingStyle {
     }
 }
 
+// Base of the object URLs, derived from the active endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UrlBase {
+    scheme: String,
+    host: String,
+    path: String,
+}
+
+impl UrlBase {
+    fn new(endpoint_url: Option<&str>, region: &str) -> UrlBase {
+        let Some(url) = endpoint_url else {
+            return UrlBase {
+                scheme: "https".into(),
+                host: format!("s3.{}.amazonaws.com", region),
+                path: "".into(),
+            };
+        };
+        let (scheme, rest) = url.split_once("://").unwrap_or(("https", url));
+        let rest = rest.trim_end_matches('/');
+        let (host, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        UrlBase {
+            scheme: scheme.into(),
+            host: host.into(),
+            path: path.into(),
+        }
+    }
+
+    // {scheme}://{bucket}.{host}/{key}
+    fn virtual_hosted_url(&self, bucket: &str, key: &str) -> String {
+        format!(
+            "{}://{}.{}{}/{}",
+            self.scheme, bucket, self.host, self.path, key
+        )
+    }
+
+    // {scheme}://{host}/{bucket}/{key}
+    fn path_style_url(&self, bucket: &str, key: &str) -> String {
+        format!(
+            "{}://{}{}/{}/{}",
+            self.scheme, self.host, self.path, bucket, key
+        )
+    }
+}
+
+// Customer-provided key for SSE-C, which must not be written to logs
+#[derive(Clone)]
+pub struct SseCustomerKey {
+    key: String,
+    key_md5: String,
+}
+
+impl SseCustomerKey {
+    pub fn from_base64(s: &str) -> Result<SseCustomerKey> {
+        let bytes =
+            base64::decode(s.trim()).map_err(|e| AppError::new("Failed to decode SSE-C key", e))?;
+        if bytes.len() != 32 {
+            return Err(AppError::msg(
+                "SSE-C key must be a 256-bit key encoded in base64",
+            ));
+        }
+        Ok(SseCustomerKey {
+            key: base64::encode(&bytes),
+            key_md5: base64::encode(Md5::digest(&bytes)),
+        })
+    }
+}
+
+impl Debug for SseCustomerKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SseCustomerKey {{ key: ** redacted ** }}")
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SseCustomerKeys {
+    default_key: Option<SseCustomerKey>,
+    bucket_keys: HashMap<String, SseCustomerKey>,
+}
+
+impl SseCustomerKeys {
+    pub fn new(
+        default_key: Option<SseCustomerKey>,
+        bucket_keys: &HashMap<String, String>,
+    ) -> Result<SseCustomerKeys> {
+        let bucket_keys = bucket_keys
+            .iter()
+            .map(|(bucket, key)| {
+                SseCustomerKey::from_base64(key)
+                    .map(|key| (bucket.clone(), key))
+                    .map_err(|e| AppError::msg(format!("{} (bucket: {})", e.msg, bucket)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(SseCustomerKeys {
+            default_key,
+            bucket_keys,
+        })
+    }
+
+    // The key for the bucket takes precedence over the default key
+    fn get(&self, bucket: &str) -> Option<&SseCustomerKey> {
+        self.bucket_keys.get(bucket).or(self.default_key.as_ref())
+    }
+}
+
 pub struct Client {
     client: aws_sdk_s3::Client,
     region: String,
+    url_base: UrlBase,
+    sse_customer_keys: SseCustomerKeys,
     bucket_region_cache: SimpleStringCache,
+    #[cfg(feature = "cloudwatch")]
+    cloudwatch_client: aws_sdk_cloudwatch::Client,
 }
 
 impl Debug for Client {
@@ -48,6 +208,10 @@ impl Client {
         profile: Option<String>,
         default_region_fallback: String,
         addressing_style: AddressingStyle,
+        sse_customer_keys: SseCustomerKeys,
+        client_config: &ClientConfig,
+        retry_config: &RetryConfig,
+        on_retry: Option<RetryCallback>,
     ) -> Client {
         let mut region_builder = region::Builder::default();
         if let Some(profile) = &profile {
@@ -57,29 +221,53 @@ impl Client {
             .or_else(region_builder.build())
             .or_else(Region::new(default_region_fallback));
 
-        let mut config_loader =
-            aws_config::defaults(BehaviorVersion::latest()).region(region_provider);
+        let mut config_loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(region_provider)
+            .retry_config(build_sdk_retry_config(retry_config))
+            .timeout_config(build_timeout_config(client_config));
         if let Some(url) = &endpoint_url {
             config_loader = config_loader.endpoint_url(url);
         }
         if let Some(profile) = &profile {
             config_loader = config_loader.profile_name(profile);
         }
+        // the default client of the SDK is used unless a custom CA or skipping verification is configured
+        if tls::is_customized(client_config) {
+            let connector = tls::https_connector(client_config);
+            config_loader = config_loader.http_client(HyperClientBuilder::new().build(connector));
+        }
         let sdk_config = config_loader.load().await;
 
-        let config_builder = aws_sdk_s3::config::Builder::from(&sdk_config)
+        let mut config_builder = aws_sdk_s3::config::Builder::from(&sdk_config)
             .force_path_style(addressing_style.to_force_path_style(&endpoint_url));
+        if let Some(callback) = on_retry {
+            config_builder = config_builder.interceptor(RetryNotifyInterceptor {
+                max_attempts: retry_config.max_attempts,
+                callback,
+            });
+        }
+        #[cfg(feature = "otel")]
+        let config_builder = config_builder.interceptor(crate::telemetry::TraceContextInterceptor);
         let config = config_builder.build();
 
         let client = aws_sdk_s3::Client::from_conf(config);
         let region = sdk_config.region().unwrap().to_string();
+        let url_base = UrlBase::new(endpoint_url.as_deref(), &region);
 
         let bucket_region_cache = SimpleStringCache::new(Config::cache_file_path().unwrap());
 
+        // only the buckets in the region are listed, so the metrics can be read from the same region
+        #[cfg(feature = "cloudwatch")]
+        let cloudwatch_client = aws_sdk_cloudwatch::Client::new(&sdk_config);
+
         Client {
             client,
             region,
+            url_base,
+            sse_customer_keys,
             bucket_region_cache,
+            #[cfg(feature = "cloudwatch")]
+            cloudwatch_client,
         }
     }
 
@@ -99,12 +287,14 @@ impl Client {
                 let bucket_name = bucket.name().unwrap().to_string();
                 let s3_uri = build_bucket_s3_uri(&bucket_name);
                 let arn = build_bucket_arn(&bucket_name);
-                let object_url = build_bucket_url(&self.region, &bucket_name);
+                let virtual_hosted_url = self.url_base.virtual_hosted_url(&bucket_name, "");
+                let path_style_url = self.url_base.path_style_url(&bucket_name, "");
                 BucketItem {
                     name: bucket_name,
                     s3_uri,
                     arn,
-                    object_url,
+                    virtual_hosted_url,
+                    path_style_url,
                 }
             })
             .collect();
@@ -173,20 +363,115 @@ impl Client {
 
         let s3_uri = build_bucket_s3_uri(name);
         let arn = build_bucket_arn(name);
-        let object_url = build_bucket_url(&self.region, name);
+        let virtual_hosted_url = self.url_base.virtual_hosted_url(name, "");
+        let path_style_url = self.url_base.path_style_url(name, "");
 
         let bucket = BucketItem {
             name: name.to_string(),
             s3_uri,
             arn,
-            object_url,
+            virtual_hosted_url,
+            path_style_url,
         };
         Ok(bucket)
     }
 
+    pub async fn create_bucket(&self, name: &str, region: &str) -> Result<()> {
+        // us-east-1 is the default location and cannot be specified as the constraint
+        let configuration = (region != "us-east-1").then(|| {
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(region))
+                .build()
+        });
+        let result = self
+            .client
+            .create_bucket()
+            .bucket(name)
+            .set_create_bucket_configuration(configuration)
+            .customize()
+            .config_override(
+                aws_sdk_s3::config::Builder::default().region(Region::new(region.to_string())),
+            )
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to create bucket", e))?;
+
+        self.bucket_region_cache
+            .put(name.to_string(), region.to_string())
+            .unwrap();
+        self.bucket_region_cache.write_cache().unwrap();
+        Ok(())
+    }
+
+    // Only empty buckets can be deleted
+    pub async fn delete_bucket(&self, name: &str) -> Result<()> {
+        let result = self.client.delete_bucket().bucket(name).send().await;
+        result.map_err(|e| AppError::new("Failed to delete bucket", e))?;
+
+        // the same name can be used for a bucket in another region later
+        self.bucket_region_cache.remove(name);
+        self.bucket_region_cache.write_cache().unwrap();
+        Ok(())
+    }
+
     pub async fn load_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectItem>> {
-        let mut dirs_vec: Vec<Vec<ObjectItem>> = Vec::new();
-        let mut files_vec: Vec<Vec<ObjectItem>> = Vec::new();
+        let mut dirs: Vec<ObjectItem> = Vec::new();
+        let mut files: Vec<ObjectItem> = Vec::new();
+
+        let mut token: Option<String> = None;
+        loop {
+            let page = self.load_objects_page(bucket, prefix, token).await?;
+            for item in page.items {
+                match item {
+                    ObjectItem::Dir { .. } => dirs.push(item),
+                    ObjectItem::File { .. } => files.push(item),
+                }
+            }
+
+            token = page.next_token;
+            if token.is_none() {
+                break;
+            }
+        }
+
+        dirs.extend(files);
+        Ok(dirs)
+    }
+
+    pub async fn load_objects_page(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        token: Option<String>,
+    ) -> Result<ObjectsPage> {
+        let result = self
+            .client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .delimiter(DELIMITER)
+            .set_continuation_token(token)
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to load objects", e))?;
+
+        let mut items = objects_output_to_dirs(&self.url_base, bucket, &output);
+        items.extend(objects_output_to_files(&self.url_base, bucket, &output));
+        let next_token = output.next_continuation_token().map(String::from);
+        Ok(ObjectsPage { items, next_token })
+    }
+
+    pub async fn list_all_download_objects<F>(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        f: F,
+    ) -> Result<DownloadObjectList>
+    where
+        F: Fn(usize),
+    {
+        // the keys are returned in UTF-8 binary order, so each page is written to the list as it is
+        let mut objs = DownloadObjectListWriter::create()?;
 
         let mut token: Option<String> = None;
         loop {
@@ -195,17 +480,63 @@ impl Client {
                 .list_objects_v2()
                 .bucket(bucket)
                 .prefix(prefix)
-                .delimiter(DELIMITER)
                 .set_continuation_token(token)
                 .send()
                 .await;
-            let output = result.map_err(|e| AppError::new("Failed to load objects", e))?;
+            let output = result.map_err(|e| AppError::new("Failed to list objects", e))?;
+
+            for obj in output.contents() {
+                let key = obj.key().unwrap();
+                if key.ends_with(DELIMITER) {
+                    // skip "directory" objects
+                    continue;
+                }
+                objs.push(&DownloadObjectInfo {
+                    key: key.to_string(),
+                    size_byte: obj.size().unwrap() as usize,
+                    last_modified: convert_datetime(obj.last_modified().unwrap()),
+                    e_tag: obj.e_tag().unwrap_or_default().to_string(),
+                })?;
+            }
+            f(objs.len());
 
-            let dirs = objects_output_to_dirs(&self.region, bucket, &output);
-            dirs_vec.push(dirs);
+            token = output.next_continuation_token().map(String::from);
+            if token.is_none() {
+                break;
+            }
+        }
+        objs.finish()
+    }
 
-            let files = objects_output_to_files(&self.region, bucket, &output);
-            files_vec.push(files);
+    pub async fn list_all_object_summaries(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<Vec<ObjectSummary>> {
+        let mut objs = Vec::new();
+
+        let mut token: Option<String> = None;
+        loop {
+            let result = self
+                .client
+                .list_objects_v2()
+                .bucket(bucket)
+                .prefix(prefix)
+                .set_continuation_token(token)
+                .send()
+                .await;
+            let output = result.map_err(|e| AppError::new("Failed to list objects", e))?;
+
+            // "directory" objects are included
+            for obj in output.contents() {
+                let key = obj.key().unwrap();
+                objs.push(ObjectSummary {
+                    key: key.to_string(),
+                    size_byte: obj.size().unwrap() as usize,
+                    last_modified: convert_datetime(obj.last_modified().unwrap()),
+                    e_tag: obj.e_tag().unwrap().trim_matches('"').to_string(),
+                });
+            }
 
             token = output.next_continuation_token().map(String::from);
             if token.is_none() {
@@ -213,9 +544,15 @@ impl Client {
             }
         }
 
-        let di = dirs_vec.into_iter().flatten();
-        let fi = files_vec.into_iter().flatten();
-        Ok(di.chain(fi).collect())
+        Ok(objs)
+    }
+
+    pub async fn object_exists(&self, bucket: &str, key: &str) -> Result<bool> {
+        match self.head_object(bucket, key).send().await {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) => Err(AppError::new("Failed to check object", e)),
+        }
     }
 
     pub async fn load_object_detail(
@@ -225,14 +562,11 @@ impl Client {
         name: &str,
         size_byte: usize,
     ) -> Result<FileDetail> {
-        let result = self
-            .client
-            .head_object()
-            .bucket(bucket)
-            .key(key)
+        let output = self
+            .head_object(bucket, key)
             .send()
-            .await;
-        let output = result.map_err(|e| AppError::new("Failed to load object detail", e))?;
+            .await
+            .map_err(|e| AppError::new("Failed to load object detail", e))?;
 
         let name = name.to_owned();
         let last_modified = convert_datetime(output.last_modified().unwrap());
@@ -242,10 +576,26 @@ impl Client {
             .storage_class()
             .map_or("", |s| s.as_str())
             .to_string();
+        let cache_control = output.cache_control().unwrap_or_default().to_string();
+        let content_disposition = output.content_disposition().unwrap_or_default().to_string();
+        let mut user_metadata: Vec<(String, String)> = output
+            .metadata()
+            .map(|m| m.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        user_metadata.sort();
+        let object_lock_mode = output
+            .object_lock_mode()
+            .map_or("", |m| m.as_str())
+            .to_string();
+        let object_lock_retain_until = output.object_lock_retain_until_date().map(convert_datetime);
+        let legal_hold = output
+            .object_lock_legal_hold_status()
+            .map(|s| *s == ObjectLockLegalHoldStatus::On);
         let key = key.to_owned();
         let s3_uri = build_object_s3_uri(bucket, &key);
         let arn = build_object_arn(bucket, &key);
-        let object_url = build_object_url(&self.region, bucket, &key);
+        let virtual_hosted_url = self.url_base.virtual_hosted_url(bucket, &key);
+        let path_style_url = self.url_base.path_style_url(bucket, &key);
         Ok(FileDetail {
             name,
             size_byte,
@@ -253,26 +603,73 @@ impl Client {
             e_tag,
             content_type,
             storage_class,
+            cache_control,
+            content_disposition,
+            user_metadata,
+            object_lock_mode,
+            object_lock_retain_until,
+            legal_hold,
             key,
             s3_uri,
             arn,
-            object_url,
+            virtual_hosted_url,
+            path_style_url,
         })
     }
 
-    pub async fn load_object_versions(&self, bucket: &str, key: &str) -> Result<Vec<FileVersion>> {
+    fn head_object(&self, bucket: &str, key: &str) -> HeadObjectFluentBuilder {
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        self.client
+            .head_object()
+            .bucket(bucket)
+            .key(key)
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+    }
+
+    pub async fn load_object_versions(
+        &self,
+        bucket: &str,
+        key: &str,
+        marker: Option<FileVersionsMarker>,
+    ) -> Result<FileVersions> {
+        let (key_marker, version_id_marker) = match marker {
+            Some(m) => (Some(m.key_marker), Some(m.version_id_marker)),
+            None => (None, None),
+        };
         let result = self
             .client
             .list_object_versions()
             .bucket(bucket)
             .prefix(key)
+            .max_keys(VERSIONS_PAGE_SIZE)
+            .set_key_marker(key_marker)
+            .set_version_id_marker(version_id_marker)
             .send()
             .await;
         let output = result.map_err(|e| AppError::new("Failed to load object versions", e))?;
 
+        // versions of other keys that start with the key (e.g. `a.txt` and `a.txt.bak`) are also listed,
+        // but they always come after the versions of the key itself
+        let other_key_listed = output.versions().iter().any(|v| v.key() != Some(key))
+            || output.delete_markers().iter().any(|m| m.key() != Some(key));
+        let next_marker = if output.is_truncated() == Some(true) && !other_key_listed {
+            output
+                .next_key_marker()
+                .zip(output.next_version_id_marker())
+                .map(|(k, v)| FileVersionsMarker {
+                    key_marker: k.to_string(),
+                    version_id_marker: v.to_string(),
+                })
+        } else {
+            None
+        };
+
         let versions = output
             .versions()
             .iter()
+            .filter(|v| v.key() == Some(key))
             .map(|v| {
                 let version_id = v.version_id().unwrap().to_string(); // returns "null" if empty...
                 let size_byte = v.size().unwrap() as usize;
@@ -285,10 +682,221 @@ impl Client {
                     last_modified,
                     e_tag,
                     is_latest,
+                    is_delete_marker: false,
+                }
+            });
+        let delete_markers = output
+            .delete_markers()
+            .iter()
+            .filter(|m| m.key() == Some(key))
+            .map(|m| FileVersion {
+                version_id: m.version_id().unwrap().to_string(),
+                size_byte: 0,
+                last_modified: convert_datetime(m.last_modified().unwrap()),
+                e_tag: "".to_string(),
+                is_latest: m.is_latest().unwrap(),
+                is_delete_marker: true,
+            });
+        // versions and delete markers are returned separately, both newest first
+        let mut versions: Vec<FileVersion> = versions.chain(delete_markers).collect();
+        versions.sort_by(|a, b| {
+            b.is_latest
+                .cmp(&a.is_latest)
+                .then(b.last_modified.cmp(&a.last_modified))
+        });
+        Ok(FileVersions {
+            versions,
+            next_marker,
+        })
+    }
+
+    pub async fn load_lifecycle_rules(&self, bucket: &str) -> Result<Vec<LifecycleRule>> {
+        let result = self
+            .client
+            .get_bucket_lifecycle_configuration()
+            .bucket(bucket)
+            .send()
+            .await;
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                let no_configuration = e
+                    .as_service_error()
+                    .and_then(|e| e.code())
+                    .is_some_and(|code| code == "NoSuchLifecycleConfiguration");
+                if no_configuration {
+                    return Ok(Vec::new());
+                }
+                return Err(AppError::new("Failed to load lifecycle rules", e));
+            }
+        };
+
+        let rules = output
+            .rules()
+            .iter()
+            .filter(|rule| rule.status() == &ExpirationStatus::Enabled)
+            .map(convert_lifecycle_rule)
+            .collect();
+        Ok(rules)
+    }
+
+    pub async fn is_object_lock_enabled(&self, bucket: &str) -> Result<bool> {
+        let result = self
+            .client
+            .get_object_lock_configuration()
+            .bucket(bucket)
+            .send()
+            .await;
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                let no_configuration = e
+                    .as_service_error()
+                    .and_then(|e| e.code())
+                    .is_some_and(|code| code == "ObjectLockConfigurationNotFoundError");
+                if no_configuration {
+                    return Ok(false);
                 }
+                return Err(AppError::new("Failed to load object lock configuration", e));
+            }
+        };
+
+        let enabled = output
+            .object_lock_configuration()
+            .and_then(|c| c.object_lock_enabled())
+            .is_some_and(|e| *e == ObjectLockEnabled::Enabled);
+        Ok(enabled)
+    }
+
+    #[cfg(feature = "cloudwatch")]
+    pub async fn load_bucket_metrics(&self, bucket: &str) -> Result<BucketMetrics> {
+        // BucketSizeBytes is reported for each storage type used in the bucket
+        let mut storage_types = Vec::new();
+        let mut next_token = None;
+        loop {
+            let result = self
+                .cloudwatch_client
+                .list_metrics()
+                .namespace("AWS/S3")
+                .metric_name("BucketSizeBytes")
+                .dimensions(
+                    DimensionFilter::builder()
+                        .name("BucketName")
+                        .value(bucket)
+                        .build(),
+                )
+                .set_next_token(next_token)
+                .send()
+                .await;
+            let output = result.map_err(|e| AppError::new("Failed to load bucket metrics", e))?;
+
+            for metric in output.metrics() {
+                let storage_type = metric
+                    .dimensions()
+                    .iter()
+                    .find(|d| d.name() == Some("StorageType"))
+                    .and_then(|d| d.value());
+                if let Some(storage_type) = storage_type {
+                    storage_types.push(storage_type.to_string());
+                }
+            }
+
+            next_token = output.next_token().map(String::from);
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        let mut size_series = Vec::new();
+        for storage_type in &storage_types {
+            let points = self
+                .get_bucket_metric_statistics(bucket, "BucketSizeBytes", storage_type)
+                .await?;
+            size_series.push(points);
+        }
+        let size_bytes = sum_metric_series(size_series);
+
+        let object_count = self
+            .get_bucket_metric_statistics(bucket, "NumberOfObjects", "AllStorageTypes")
+            .await?;
+        let object_count = sum_metric_series(vec![object_count]);
+
+        Ok(BucketMetrics {
+            size_bytes,
+            object_count,
+        })
+    }
+
+    #[cfg(not(feature = "cloudwatch"))]
+    pub async fn load_bucket_metrics(&self, _bucket: &str) -> Result<BucketMetrics> {
+        Err(AppError::msg(
+            "Bucket metrics are not available (built without the cloudwatch feature)",
+        ))
+    }
+
+    #[cfg(feature = "cloudwatch")]
+    async fn get_bucket_metric_statistics(
+        &self,
+        bucket: &str,
+        metric_name: &str,
+        storage_type: &str,
+    ) -> Result<Vec<MetricPoint>> {
+        let end_time = chrono::Utc::now();
+        let start_time = end_time - chrono::Duration::days(BUCKET_METRICS_PERIOD_DAYS);
+        let result = self
+            .cloudwatch_client
+            .get_metric_statistics()
+            .namespace("AWS/S3")
+            .metric_name(metric_name)
+            .dimensions(
+                Dimension::builder()
+                    .name("BucketName")
+                    .value(bucket)
+                    .build(),
+            )
+            .dimensions(
+                Dimension::builder()
+                    .name("StorageType")
+                    .value(storage_type)
+                    .build(),
+            )
+            .start_time(CloudWatchDateTime::from_secs(start_time.timestamp()))
+            .end_time(CloudWatchDateTime::from_secs(end_time.timestamp()))
+            .period(24 * 60 * 60)
+            .statistics(Statistic::Average)
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to load bucket metrics", e))?;
+
+        let points = output
+            .datapoints()
+            .iter()
+            .filter_map(|p| {
+                let timestamp = p.timestamp()?;
+                let timestamp = chrono::Local.timestamp_opt(timestamp.secs(), 0).unwrap();
+                let value = p.average()?;
+                Some(MetricPoint { timestamp, value })
             })
             .collect();
-        Ok(versions)
+        Ok(points)
+    }
+
+    pub async fn load_object_tags(&self, bucket: &str, key: &str) -> Result<Vec<(String, String)>> {
+        let result = self
+            .client
+            .get_object_tagging()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to load object tags", e))?;
+
+        let tags = output
+            .tag_set()
+            .iter()
+            .map(|t| (t.key().to_string(), t.value().to_string()))
+            .collect();
+        Ok(tags)
     }
 
     pub async fn download_object<F>(
@@ -296,19 +904,18 @@ impl Client {
         bucket: &str,
         key: &str,
         version_id: Option<String>,
+        if_match: Option<String>,
         size_byte: usize,
         f: F,
     ) -> Result<RawObject>
     where
         F: Fn(usize),
     {
-        let mut request = self.client.get_object().bucket(bucket).key(key);
-        if let Some(version_id) = version_id {
-            request = request.version_id(version_id);
-        }
-
-        let result = request.send().await;
-        let output = result.map_err(|e| AppError::new("Failed to download object", e))?;
+        let result = self
+            .get_object(bucket, key, version_id, if_match)
+            .send()
+            .await;
+        let output = result.map_err(convert_get_object_error)?;
 
         let mut bytes: Vec<u8> = Vec::with_capacity(size_byte);
         let mut stream = output.body;
@@ -331,6 +938,533 @@ impl Client {
         Ok(RawObject { bytes })
     }
 
+    pub async fn download_object_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> Result<RawObject> {
+        // the end of the range is inclusive
+        let range = format!("bytes={}-{}", offset, offset + len.max(1) - 1);
+        let result = self
+            .get_object(bucket, key, version_id, if_match)
+            .range(range)
+            .send()
+            .await;
+        let output = result.map_err(convert_get_object_error)?;
+
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| AppError::new("Failed to collect body", e))?
+            .to_vec();
+        Ok(RawObject { bytes })
+    }
+
+    fn get_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+    ) -> GetObjectFluentBuilder {
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        self.client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .set_if_match(if_match)
+            .set_version_id(version_id)
+    }
+
+    pub async fn presign_get_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| AppError::new("Failed to build presigning config", e))?;
+
+        let mut request = self.client.get_object().bucket(bucket).key(key);
+        if let Some(version_id) = version_id {
+            request = request.version_id(version_id);
+        }
+
+        let result = request.presigned(presigning_config).await;
+        let presigned = result.map_err(|e| AppError::new("Failed to generate presigned URL", e))?;
+
+        Ok(presigned.uri().to_string())
+    }
+
+    pub async fn presign_put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| AppError::new("Failed to build presigning config", e))?;
+
+        // SSE-C headers are not signed, they would expose the key in the URL
+        let request = self.client.put_object().bucket(bucket).key(key);
+        let result = request.presigned(presigning_config).await;
+        let presigned = result.map_err(|e| AppError::new("Failed to generate presigned URL", e))?;
+
+        Ok(presigned.uri().to_string())
+    }
+
+    pub async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<()> {
+        let src_sse_c_key = self.sse_customer_keys.get(src_bucket);
+        let sse_c_key = self.sse_customer_keys.get(dst_bucket);
+        let result = self
+            .client
+            .copy_object()
+            .bucket(dst_bucket)
+            .copy_source(build_copy_source(src_bucket, src_key))
+            .key(dst_key)
+            .set_copy_source_sse_customer_algorithm(
+                src_sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()),
+            )
+            .set_copy_source_sse_customer_key(src_sse_c_key.map(|k| k.key.clone()))
+            .set_copy_source_sse_customer_key_md5(src_sse_c_key.map(|k| k.key_md5.clone()))
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to copy object", e))?;
+        Ok(())
+    }
+
+    pub async fn put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<()> {
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let content_type = content_type
+            .map(String::from)
+            .or_else(|| infer::get(&bytes).map(|t| t.mime_type().to_string()));
+        let result = self
+            .client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .set_content_type(content_type)
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .body(ByteStream::from(bytes))
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to upload object", e))?;
+        Ok(())
+    }
+
+    // Returns the most recently initiated upload of the key that has not been completed or aborted
+    pub async fn find_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<MultipartUpload>> {
+        let result = self
+            .client
+            .list_multipart_uploads()
+            .bucket(bucket)
+            .prefix(key)
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to list multipart uploads", e))?;
+        let upload_id = output
+            .uploads()
+            .iter()
+            .filter(|u| u.key() == Some(key))
+            .max_by_key(|u| u.initiated().map(|t| t.secs()))
+            .and_then(|u| u.upload_id())
+            .map(String::from);
+        let Some(upload_id) = upload_id else {
+            return Ok(None);
+        };
+
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let result = self
+                .client
+                .list_parts()
+                .bucket(bucket)
+                .key(key)
+                .upload_id(&upload_id)
+                .set_part_number_marker(marker)
+                .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+                .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+                .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+                .send()
+                .await;
+            let output = result.map_err(|e| AppError::new("Failed to list uploaded parts", e))?;
+            parts.extend(output.parts().iter().map(|p| UploadedPart {
+                part_number: p.part_number().unwrap_or_default(),
+                e_tag: p.e_tag().unwrap_or_default().to_string(),
+                size_byte: p.size().unwrap_or_default() as u64,
+            }));
+            if output.is_truncated() != Some(true) {
+                break;
+            }
+            marker = output.next_part_number_marker().map(String::from);
+        }
+        Ok(Some(MultipartUpload { upload_id, parts }))
+    }
+
+    pub async fn create_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        content_type: Option<&str>,
+    ) -> Result<String> {
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let result = self
+            .client
+            .create_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .set_content_type(content_type.map(String::from))
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to start multipart upload", e))?;
+        output
+            .upload_id()
+            .map(String::from)
+            .ok_or_else(|| AppError::msg("Failed to start multipart upload: no upload id"))
+    }
+
+    pub async fn upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        bytes: Vec<u8>,
+    ) -> Result<String> {
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let result = self
+            .client
+            .upload_part()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .body(ByteStream::from(bytes))
+            .send()
+            .await;
+        let output = result.map_err(|e| AppError::new("Failed to upload part", e))?;
+        Ok(output.e_tag().unwrap_or_default().to_string())
+    }
+
+    pub async fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<()> {
+        let parts = parts
+            .iter()
+            .map(|p| {
+                CompletedPart::builder()
+                    .part_number(p.part_number)
+                    .e_tag(&p.e_tag)
+                    .build()
+            })
+            .collect();
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let result = self
+            .client
+            .complete_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to complete multipart upload", e))?;
+        Ok(())
+    }
+
+    pub async fn abort_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<()> {
+        let result = self
+            .client
+            .abort_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to abort multipart upload", e))?;
+        Ok(())
+    }
+
+    // Puts a zero-byte object with a trailing slash, which is listed as a folder
+    pub async fn create_folder(&self, bucket: &str, key: &str) -> Result<ObjectItem> {
+        let result = self
+            .client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .body(ByteStream::from_static(b""))
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to create folder", e))?;
+        Ok(build_dir_item(&self.url_base, bucket, key.to_owned()))
+    }
+
+    // Copies the object onto itself with the new storage class, keeping the metadata and the tags
+    pub async fn change_storage_class(
+        &self,
+        bucket: &str,
+        key: &str,
+        storage_class: &str,
+    ) -> Result<()> {
+        let current = self
+            .head_object(bucket, key)
+            .send()
+            .await
+            .map_err(|e| AppError::new("Failed to change storage class", e))?;
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let result = build_change_storage_class_request(
+            &self.client,
+            bucket,
+            key,
+            storage_class,
+            &current,
+            sse_c_key,
+        )
+        .send()
+        .await;
+        result.map_err(|e| AppError::new("Failed to change storage class", e))?;
+        Ok(())
+    }
+
+    // Copies the object onto itself replacing the metadata.
+    // The headers which cannot be edited and the storage class are taken over from the current object,
+    // since they are reset by MetadataDirective=REPLACE.
+    pub async fn update_object_metadata(
+        &self,
+        bucket: &str,
+        key: &str,
+        metadata: &ObjectMetadata,
+    ) -> Result<()> {
+        let current = self
+            .head_object(bucket, key)
+            .send()
+            .await
+            .map_err(|e| AppError::new("Failed to update object metadata", e))?;
+        let (server_side_encryption, ssekms_key_id) = kms_encryption(&current);
+
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let user_metadata: HashMap<String, String> =
+            metadata.user_metadata.iter().cloned().collect();
+
+        let sse_c_key = self.sse_customer_keys.get(bucket);
+        let result = self
+            .client
+            .copy_object()
+            .bucket(bucket)
+            .copy_source(build_copy_source(bucket, key))
+            .key(key)
+            .metadata_directive(MetadataDirective::Replace)
+            .set_metadata(Some(user_metadata))
+            .set_content_type(non_empty(&metadata.content_type))
+            .set_cache_control(non_empty(&metadata.cache_control))
+            .set_content_disposition(non_empty(&metadata.content_disposition))
+            .set_content_encoding(current.content_encoding().map(String::from))
+            .set_content_language(current.content_language().map(String::from))
+            .set_storage_class(current.storage_class().cloned())
+            .set_server_side_encryption(server_side_encryption)
+            .set_ssekms_key_id(ssekms_key_id)
+            .set_copy_source_sse_customer_algorithm(
+                sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()),
+            )
+            .set_copy_source_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_copy_source_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+            .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+            .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to update object metadata", e))?;
+        Ok(())
+    }
+
+    // Fails if Object Lock is not enabled on the bucket
+    pub async fn set_object_legal_hold(&self, bucket: &str, key: &str, on: bool) -> Result<()> {
+        let status = if on {
+            ObjectLockLegalHoldStatus::On
+        } else {
+            ObjectLockLegalHoldStatus::Off
+        };
+        let result = self
+            .client
+            .put_object_legal_hold()
+            .bucket(bucket)
+            .key(key)
+            .legal_hold(ObjectLockLegalHold::builder().status(status).build())
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to set legal hold", e))?;
+        Ok(())
+    }
+
+    // Replaces all the tags of the object
+    pub async fn put_object_tags(
+        &self,
+        bucket: &str,
+        key: &str,
+        tags: &[(String, String)],
+    ) -> Result<()> {
+        let tag_set = tags
+            .iter()
+            .map(|(k, v)| Tag::builder().key(k).value(v).build())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| AppError::new("Failed to build tagging request", e))?;
+        let tagging = Tagging::builder()
+            .set_tag_set(Some(tag_set))
+            .build()
+            .map_err(|e| AppError::new("Failed to build tagging request", e))?;
+        let result = self
+            .client
+            .put_object_tagging()
+            .bucket(bucket)
+            .key(key)
+            .tagging(tagging)
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to put object tags", e))?;
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        let result = self
+            .client
+            .delete_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to delete object", e))?;
+        Ok(())
+    }
+
+    // Deletes the version permanently, deleting a delete marker restores the object
+    pub async fn delete_object_version(
+        &self,
+        bucket: &str,
+        key: &str,
+        version_id: &str,
+    ) -> Result<()> {
+        let result = self
+            .client
+            .delete_object()
+            .bucket(bucket)
+            .key(key)
+            .version_id(version_id)
+            .send()
+            .await;
+        result.map_err(|e| AppError::new("Failed to delete object version", e))?;
+        Ok(())
+    }
+
+    // Returns the objects that could not be deleted.
+    // A failed request does not stop the deletion of the remaining keys.
+    pub async fn delete_objects<F>(
+        &self,
+        bucket: &str,
+        keys: &[String],
+        f: F,
+    ) -> Result<Vec<DeleteFailure>>
+    where
+        F: Fn(usize),
+    {
+        let mut failed = Vec::new();
+        let mut done = 0;
+        for keys in keys.chunks(DELETE_OBJECTS_MAX_KEYS) {
+            done += keys.len();
+            let objects = keys
+                .iter()
+                .map(|key| ObjectIdentifier::builder().key(key).build())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::new("Failed to build delete request", e))?;
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()
+                .map_err(|e| AppError::new("Failed to build delete request", e))?;
+
+            let result = self
+                .client
+                .delete_objects()
+                .bucket(bucket)
+                .delete(delete)
+                .send()
+                .await;
+            let output = match result {
+                Ok(output) => output,
+                Err(e) => {
+                    let error = AppError::new("Failed to delete objects", e).to_string();
+                    failed.extend(keys.iter().map(|key| DeleteFailure {
+                        key: key.clone(),
+                        error: error.clone(),
+                    }));
+                    f(done);
+                    continue;
+                }
+            };
+
+            // only errors are returned in quiet mode
+            failed.extend(output.errors().iter().map(|err| DeleteFailure {
+                key: err.key().unwrap_or_default().to_string(),
+                error: err.message().or(err.code()).unwrap_or_default().to_string(),
+            }));
+            f(done);
+        }
+        Ok(failed)
+    }
+
     pub fn open_management_console_buckets(&self) -> Result<()> {
         let path = format!(
             "https://s3.console.aws.amazon.com/s3/buckets?region={}",
@@ -357,34 +1491,36 @@ impl Client {
 }
 
 fn objects_output_to_dirs(
-    region: &str,
+    url_base: &UrlBase,
     bucket: &str,
     output: &ListObjectsV2Output,
 ) -> Vec<ObjectItem> {
     let objects = output.common_prefixes();
     objects
         .iter()
-        .map(|dir| {
-            let path = dir.prefix().unwrap();
-            let paths = parse_path(path, true);
-            let name = paths.last().unwrap().to_owned();
+        .map(|dir| build_dir_item(url_base, bucket, dir.prefix().unwrap().to_owned()))
+        .collect()
+}
 
-            let key = path.to_owned();
-            let s3_uri = build_object_s3_uri(bucket, &key);
-            let object_url = build_object_url(region, bucket, &key);
+fn build_dir_item(url_base: &UrlBase, bucket: &str, key: String) -> ObjectItem {
+    let paths = parse_path(&key, true);
+    let name = paths.last().unwrap().to_owned();
 
-            ObjectItem::Dir {
-                name,
-                key,
-                s3_uri,
-                object_url,
-            }
-        })
-        .collect()
+    let s3_uri = build_object_s3_uri(bucket, &key);
+    let virtual_hosted_url = url_base.virtual_hosted_url(bucket, &key);
+    let path_style_url = url_base.path_style_url(bucket, &key);
+
+    ObjectItem::Dir {
+        name,
+        key,
+        s3_uri,
+        virtual_hosted_url,
+        path_style_url,
+    }
 }
 
 fn objects_output_to_files(
-    region: &str,
+    url_base: &UrlBase,
     bucket: &str,
     output: &ListObjectsV2Output,
 ) -> Vec<ObjectItem> {
@@ -401,7 +1537,8 @@ fn objects_output_to_files(
             let key = file.key().unwrap().to_owned();
             let s3_uri = build_object_s3_uri(bucket, &key);
             let arn = build_object_arn(bucket, &key);
-            let object_url = build_object_url(region, bucket, &key);
+            let virtual_hosted_url = url_base.virtual_hosted_url(bucket, &key);
+            let path_style_url = url_base.path_style_url(bucket, &key);
             let e_tag = file.e_tag().unwrap().trim_matches('"').to_string();
 
             ObjectItem::File {
@@ -411,7 +1548,8 @@ fn objects_output_to_files(
                 key,
                 s3_uri,
                 arn,
-                object_url,
+                virtual_hosted_url,
+                path_style_url,
                 e_tag,
             }
         })
@@ -428,11 +1566,76 @@ fn parse_path(path: &str, dir: bool) -> Vec<String> {
     }
 }
 
+fn convert_get_object_error(e: SdkError<GetObjectError, HttpResponse>) -> AppError {
+    if e.raw_response().map(|r| r.status().as_u16()) == Some(412) {
+        // Precondition Failed, the ETag specified by If-Match does not match
+        AppError::msg("Object has changed since you viewed it, please reload and try again")
+    } else {
+        AppError::new("Failed to download object", e)
+    }
+}
+
 fn convert_datetime(dt: &aws_smithy_types::DateTime) -> chrono::DateTime<chrono::Local> {
     let nanos = dt.as_nanos();
     chrono::Local.timestamp_nanos(nanos as i64)
 }
 
+// The deprecated rule-level prefix is still returned for rules created without a filter
+#[allow(deprecated)]
+fn convert_lifecycle_rule(rule: &aws_sdk_s3::types::LifecycleRule) -> LifecycleRule {
+    let mut lifecycle_rule = LifecycleRule {
+        prefix: rule.prefix().unwrap_or_default().to_string(),
+        ..Default::default()
+    };
+    if let Some(filter) = rule.filter() {
+        let and = filter.and();
+        if let Some(prefix) = filter.prefix().or(and.and_then(|a| a.prefix())) {
+            lifecycle_rule.prefix = prefix.to_string();
+        }
+        lifecycle_rule.tags = filter
+            .tag()
+            .into_iter()
+            .chain(and.map(|a| a.tags()).unwrap_or_default())
+            .map(|t| (t.key().to_string(), t.value().to_string()))
+            .collect();
+        lifecycle_rule.object_size_greater_than = filter
+            .object_size_greater_than()
+            .or(and.and_then(|a| a.object_size_greater_than()))
+            .map(|n| n as usize);
+        lifecycle_rule.object_size_less_than = filter
+            .object_size_less_than()
+            .or(and.and_then(|a| a.object_size_less_than()))
+            .map(|n| n as usize);
+    }
+    lifecycle_rule.transitions = rule
+        .transitions()
+        .iter()
+        .filter_map(|t| {
+            let schedule = convert_lifecycle_schedule(t.days(), t.date())?;
+            let storage_class = t.storage_class()?.as_str().to_string();
+            Some(LifecycleTransition {
+                schedule,
+                storage_class,
+            })
+        })
+        .collect();
+    lifecycle_rule.expiration = rule
+        .expiration()
+        .and_then(|e| convert_lifecycle_schedule(e.days(), e.date()));
+    lifecycle_rule
+}
+
+fn convert_lifecycle_schedule(
+    days: Option<i32>,
+    date: Option<&aws_smithy_types::DateTime>,
+) -> Option<LifecycleSchedule> {
+    match (days, date) {
+        (Some(days), _) => Some(LifecycleSchedule::Days(days as u32)),
+        (None, Some(date)) => Some(LifecycleSchedule::Date(convert_datetime(date))),
+        (None, None) => None,
+    }
+}
+
 fn build_bucket_s3_uri(bucket: &str) -> String {
     format!("s3://{}/", bucket)
 }
@@ -441,10 +1644,6 @@ fn build_bucket_arn(bucket: &str) -> String {
     format!("arn:aws:s3:::{}", bucket)
 }
 
-fn build_bucket_url(region: &str, bucket: &str) -> String {
-    format!("https://{}.s3.{}.amazonaws.com/", bucket, region)
-}
-
 fn build_object_s3_uri(bucket: &str, key: &str) -> String {
     format!("s3://{}/{}", bucket, key)
 }
@@ -453,6 +1652,269 @@ fn build_object_arn(bucket: &str, key: &str) -> String {
     format!("arn:aws:s3:::{}/{}", bucket, key)
 }
 
-fn build_object_url(region: &str, bucket: &str, key: &str) -> String {
-    format!("https://{}.s3.{}.amazonaws.com/{}", bucket, region, key)
+// CopySource must be URL-encoded, except for the `/` separators
+// The storage class is the only thing changed by the copy.
+// The encryption with KMS is taken over from the current object, since CopyObject does not keep it.
+fn build_change_storage_class_request(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    key: &str,
+    storage_class: &str,
+    current: &HeadObjectOutput,
+    sse_c_key: Option<&SseCustomerKey>,
+) -> CopyObjectFluentBuilder {
+    let (server_side_encryption, ssekms_key_id) = kms_encryption(current);
+    client
+        .copy_object()
+        .bucket(bucket)
+        .copy_source(build_copy_source(bucket, key))
+        .key(key)
+        .storage_class(StorageClass::from(storage_class))
+        .set_server_side_encryption(server_side_encryption)
+        .set_ssekms_key_id(ssekms_key_id)
+        .set_copy_source_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+        .set_copy_source_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+        .set_copy_source_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+        .set_sse_customer_algorithm(sse_c_key.map(|_| SSE_CUSTOMER_ALGORITHM.into()))
+        .set_sse_customer_key(sse_c_key.map(|k| k.key.clone()))
+        .set_sse_customer_key_md5(sse_c_key.map(|k| k.key_md5.clone()))
+}
+
+// Returns the server-side encryption and the KMS key of the object to be set on its copy,
+// which are set only if the object is encrypted with KMS
+fn kms_encryption(current: &HeadObjectOutput) -> (Option<ServerSideEncryption>, Option<String>) {
+    let kms = current.server_side_encryption() == Some(&ServerSideEncryption::AwsKms);
+    (
+        kms.then_some(ServerSideEncryption::AwsKms),
+        current.ssekms_key_id().filter(|_| kms).map(String::from),
+    )
+}
+
+fn build_copy_source(bucket: &str, key: &str) -> String {
+    let mut encoded = String::new();
+    for b in format!("{}/{}", bucket, key).bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+// Replaces the default timeouts of the SDK, which has only the connect timeout (3.1 seconds)
+fn build_timeout_config(config: &ClientConfig) -> TimeoutConfig {
+    let timeout = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+    let mut builder = TimeoutConfig::builder();
+    builder
+        .set_connect_timeout(timeout(config.connect_timeout))
+        .set_read_timeout(timeout(config.read_timeout));
+    builder.build()
+}
+
+fn build_sdk_retry_config(config: &RetryConfig) -> SdkRetryConfig {
+    let retry_config = match config.mode {
+        RetryMode::Standard => SdkRetryConfig::standard(),
+        RetryMode::Adaptive => SdkRetryConfig::adaptive(),
+    };
+    retry_config
+        .with_max_attempts(config.max_attempts.max(1))
+        .with_initial_backoff(Duration::from_millis(config.initial_backoff_millis))
+}
+
+#[derive(Debug)]
+struct RequestAttempts(u32);
+
+impl Storable for RequestAttempts {
+    type Storer = StoreReplace<Self>;
+}
+
+// Counts the attempts of each request to notify the retries,
+// which are otherwise invisible until the request finally fails
+struct RetryNotifyInterceptor {
+    max_attempts: u32,
+    callback: RetryCallback,
+}
+
+impl Debug for RetryNotifyInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RetryNotifyInterceptor")
+    }
+}
+
+impl Intercept for RetryNotifyInterceptor {
+    fn name(&self) -> &'static str {
+        "RetryNotifyInterceptor"
+    }
+
+    fn read_before_attempt(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> std::result::Result<(), BoxError> {
+        let attempts = cfg.load::<RequestAttempts>().map_or(0, |a| a.0) + 1;
+        cfg.interceptor_state().store_put(RequestAttempts(attempts));
+        if attempts > 1 {
+            (self.callback)(attempts, self.max_attempts);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        None,
+        "",
+        "https://bucket-1.s3.ap-northeast-1.amazonaws.com/",
+        "https://s3.ap-northeast-1.amazonaws.com/bucket-1/"
+    )]
+    #[case(
+        None,
+        "a/b/file.txt",
+        "https://bucket-1.s3.ap-northeast-1.amazonaws.com/a/b/file.txt",
+        "https://s3.ap-northeast-1.amazonaws.com/bucket-1/a/b/file.txt"
+    )]
+    #[case(
+        Some("http://localhost:9000"),
+        "a/b/file.txt",
+        "http://bucket-1.localhost:9000/a/b/file.txt",
+        "http://localhost:9000/bucket-1/a/b/file.txt"
+    )]
+    #[case(
+        Some("https://example.com/s3/"),
+        "",
+        "https://bucket-1.example.com/s3/",
+        "https://example.com/s3/bucket-1/"
+    )]
+    fn test_url_base(
+        #[case] endpoint_url: Option<&str>,
+        #[case] key: &str,
+        #[case] expected_virtual_hosted_url: &str,
+        #[case] expected_path_style_url: &str,
+    ) {
+        let url_base = UrlBase::new(endpoint_url, "ap-northeast-1");
+        assert_eq!(
+            url_base.virtual_hosted_url("bucket-1", key),
+            expected_virtual_hosted_url
+        );
+        assert_eq!(
+            url_base.path_style_url("bucket-1", key),
+            expected_path_style_url
+        );
+    }
+
+    #[rstest]
+    #[case("a/b/file.txt", "bucket-1/a/b/file.txt")]
+    #[case("a b+c.txt", "bucket-1/a%20b%2Bc.txt")]
+    #[case("日本.txt", "bucket-1/%E6%97%A5%E6%9C%AC.txt")]
+    fn test_build_copy_source(#[case] key: &str, #[case] expected: &str) {
+        assert_eq!(build_copy_source("bucket-1", key), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Some(ServerSideEncryption::AwsKms),
+        Some("arn:aws:kms:ap-northeast-1:123456789012:key/abc"),
+        Some(ServerSideEncryption::AwsKms),
+        Some("arn:aws:kms:ap-northeast-1:123456789012:key/abc")
+    )]
+    #[case(Some(ServerSideEncryption::Aes256), None, None, None)]
+    #[case(None, None, None, None)]
+    fn test_build_change_storage_class_request(
+        #[case] current_sse: Option<ServerSideEncryption>,
+        #[case] current_kms_key_id: Option<&str>,
+        #[case] expected_sse: Option<ServerSideEncryption>,
+        #[case] expected_kms_key_id: Option<&str>,
+    ) {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("ap-northeast-1"))
+            .build();
+        let client = aws_sdk_s3::Client::from_conf(config);
+        let current = HeadObjectOutput::builder()
+            .set_server_side_encryption(current_sse)
+            .set_ssekms_key_id(current_kms_key_id.map(String::from))
+            .storage_class(StorageClass::Standard)
+            .build();
+
+        let request = build_change_storage_class_request(
+            &client,
+            "bucket-1",
+            "a/b.txt",
+            "GLACIER_IR",
+            &current,
+            None,
+        );
+        let input = request.as_input();
+        assert_eq!(input.get_copy_source().as_deref(), Some("bucket-1/a/b.txt"));
+        assert_eq!(input.get_storage_class(), &Some(StorageClass::GlacierIr));
+        assert_eq!(input.get_server_side_encryption(), &expected_sse);
+        assert_eq!(input.get_ssekms_key_id().as_deref(), expected_kms_key_id);
+        assert!(input.get_sse_customer_key().is_none());
+    }
+
+    #[test]
+    fn test_build_sdk_retry_config() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            initial_backoff_millis: 200,
+            mode: RetryMode::Adaptive,
+        };
+        let actual = build_sdk_retry_config(&config);
+        assert_eq!(actual.mode(), aws_smithy_types::retry::RetryMode::Adaptive);
+        assert_eq!(actual.max_attempts(), 5);
+        assert_eq!(actual.initial_backoff(), Duration::from_millis(200));
+
+        // at least the first attempt is made
+        let config = RetryConfig {
+            max_attempts: 0,
+            ..Default::default()
+        };
+        let actual = build_sdk_retry_config(&config);
+        assert_eq!(actual.mode(), aws_smithy_types::retry::RetryMode::Standard);
+        assert_eq!(actual.max_attempts(), 1);
+    }
+
+    #[test]
+    fn test_sse_customer_key_from_base64() {
+        let key =
+            SseCustomerKey::from_base64("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=\n").unwrap();
+        assert_eq!(key.key, "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=");
+        assert_eq!(key.key_md5, "tP/LI3N87DFaSk0aoqYgzg==");
+        assert!(!format!("{:?}", key).contains(&key.key));
+
+        // 16 bytes
+        assert!(SseCustomerKey::from_base64("AAECAwQFBgcICQoLDA0ODw==").is_err());
+        assert!(SseCustomerKey::from_base64("invalid base64").is_err());
+    }
+
+    #[test]
+    fn test_sse_customer_keys_get() {
+        let default_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+        let bucket_key = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+        let bucket_keys = HashMap::from([("bucket-1".to_string(), bucket_key.to_string())]);
+
+        let keys = SseCustomerKeys::new(None, &bucket_keys).unwrap();
+        assert_eq!(keys.get("bucket-1").unwrap().key, bucket_key);
+        assert!(keys.get("bucket-2").is_none());
+
+        let default_key = SseCustomerKey::from_base64(default_key).ok();
+        let keys = SseCustomerKeys::new(default_key, &bucket_keys).unwrap();
+        assert_eq!(keys.get("bucket-1").unwrap().key, bucket_key);
+        assert_eq!(
+            keys.get("bucket-2").unwrap().key,
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8="
+        );
+
+        let bucket_keys = HashMap::from([("bucket-1".to_string(), "invalid".to_string())]);
+        assert!(SseCustomerKeys::new(None, &bucket_keys).is_err());
+    }
 }
@@ -13,12 +13,16 @@ pub struct ColorTheme {
     pub list_selected_inactive_bg: Color,
     pub list_selected_inactive_fg: Color,
     pub list_filter_match: Color,
+    pub list_marked: Color,
+    pub list_changed: Color,
 
     pub detail_selected: Color,
 
     pub dialog_selected: Color,
 
     pub preview_line_number: Color,
+    pub preview_diff_removed_bg: Color,
+    pub preview_diff_added_bg: Color,
 
     pub status_help: Color,
     pub status_info: Color,
@@ -41,12 +45,16 @@ impl Default for ColorTheme {
             list_selected_inactive_bg: Color::DarkGray,
             list_selected_inactive_fg: Color::Black,
             list_filter_match: Color::Red,
+            list_marked: Color::Yellow,
+            list_changed: Color::Green,
 
             detail_selected: Color::Cyan,
 
             dialog_selected: Color::Cyan,
 
             preview_line_number: Color::DarkGray,
+            preview_diff_removed_bg: Color::Indexed(52),
+            preview_diff_added_bg: Color::Indexed(22),
 
             status_help: Color::DarkGray,
             status_info: Color::Blue,
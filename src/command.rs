@@ -0,0 +1,199 @@
+use crate::{
+    error::{AppError, Result},
+    location::{parse_s3_location, S3Location},
+    schedule::ScheduledTaskType,
+    widget::ObjectListSortType,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    // download all objects under the selected folder, or under the current prefix + the argument
+    Download(Option<String>),
+    Delete,
+    Sort(ObjectListSortType),
+    Filter(String),
+    Goto(S3Location),
+    // browse the objects listed in the S3 Inventory manifest at the location
+    Inventory(S3Location),
+    // summarize the S3 server access logs under the current prefix + the argument
+    AccessLog(Option<String>),
+    Refresh,
+    // repeat the refresh or the download (with the prefix argument) every the minutes while the app is running
+    Every(u64, ScheduledTaskType, Option<String>),
+    // cancel all scheduled tasks
+    EveryOff,
+    Quit,
+}
+
+// Parses ex-style commands such as `download logs/2024-`, `sort size desc`, `filter foo`, `goto s3://bucket/key`.
+// The leading `:` is optional.
+pub fn parse_command(s: &str) -> Result<Command> {
+    let s = s.trim();
+    let s = s.strip_prefix(':').unwrap_or(s).trim_start();
+    let (name, args) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+    let args = args.trim();
+
+    match name {
+        "download" | "dl" => Ok(Command::Download(
+            (!args.is_empty()).then(|| args.to_string()),
+        )),
+        "delete" | "rm" => no_args(name, args, Command::Delete),
+        "sort" => parse_sort_args(args).map(Command::Sort),
+        "filter" => Ok(Command::Filter(args.to_string())),
+        "goto" | "go" => {
+            if args.is_empty() {
+                return Err(AppError::msg("Usage: goto <location>"));
+            }
+            parse_s3_location(args).map(Command::Goto)
+        }
+        "inventory" => {
+            if args.is_empty() {
+                return Err(AppError::msg("Usage: inventory <manifest location>"));
+            }
+            parse_s3_location(args).map(Command::Inventory)
+        }
+        "accesslog" | "al" => Ok(Command::AccessLog(
+            (!args.is_empty()).then(|| args.to_string()),
+        )),
+        "refresh" => no_args(name, args, Command::Refresh),
+        "every" => parse_every_args(args),
+        "quit" | "q" => no_args(name, args, Command::Quit),
+        "" => Err(AppError::msg("No command specified")),
+        _ => Err(AppError::msg(format!("Unknown command: {}", name))),
+    }
+}
+
+fn no_args(name: &str, args: &str, cmd: Command) -> Result<Command> {
+    if args.is_empty() {
+        Ok(cmd)
+    } else {
+        Err(AppError::msg(format!("{} does not take arguments", name)))
+    }
+}
+
+fn parse_every_args(args: &str) -> Result<Command> {
+    let usage =
+        || AppError::msg("Usage: every <minutes> refresh|download [<prefix>], or every off");
+
+    if args == "off" {
+        return Ok(Command::EveryOff);
+    }
+    let (minutes, rest) = args.split_once(char::is_whitespace).ok_or_else(usage)?;
+    let minutes = match minutes.parse::<u64>() {
+        Ok(n) if n > 0 => n,
+        _ => return Err(usage()),
+    };
+    let rest = rest.trim();
+    let (name, prefix) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let prefix = prefix.trim();
+    match name {
+        "refresh" if prefix.is_empty() => {
+            Ok(Command::Every(minutes, ScheduledTaskType::Refresh, None))
+        }
+        "download" | "dl" => Ok(Command::Every(
+            minutes,
+            ScheduledTaskType::Download,
+            (!prefix.is_empty()).then(|| prefix.to_string()),
+        )),
+        _ => Err(usage()),
+    }
+}
+
+fn parse_sort_args(args: &str) -> Result<ObjectListSortType> {
+    let usage = || AppError::msg("Usage: sort [name|date|size [asc|desc]]");
+
+    let mut args = args.split_whitespace();
+    let key = args.next().unwrap_or("default");
+    let desc = match args.next() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(_) => return Err(usage()),
+    };
+    if args.next().is_some() {
+        return Err(usage());
+    }
+
+    let sort_type = match (key, desc) {
+        ("default" | "none", false) => ObjectListSortType::Default,
+        ("name", false) => ObjectListSortType::NameAsc,
+        ("name", true) => ObjectListSortType::NameDesc,
+        ("date" | "modified", false) => ObjectListSortType::LastModifiedAsc,
+        ("date" | "modified", true) => ObjectListSortType::LastModifiedDesc,
+        ("size", false) => ObjectListSortType::SizeAsc,
+        ("size", true) => ObjectListSortType::SizeDesc,
+        _ => return Err(usage()),
+    };
+    Ok(sort_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("download", Command::Download(None))]
+    #[case(":download", Command::Download(None))]
+    #[case("  : dl ", Command::Download(None))]
+    #[case("download logs/2024-", Command::Download(Some("logs/2024-".into())))]
+    #[case("delete", Command::Delete)]
+    #[case("sort", Command::Sort(ObjectListSortType::Default))]
+    #[case("sort name", Command::Sort(ObjectListSortType::NameAsc))]
+    #[case("sort name desc", Command::Sort(ObjectListSortType::NameDesc))]
+    #[case("sort date asc", Command::Sort(ObjectListSortType::LastModifiedAsc))]
+    #[case("sort  size   desc", Command::Sort(ObjectListSortType::SizeDesc))]
+    #[case("filter foo", Command::Filter("foo".into()))]
+    #[case("filter foo bar ", Command::Filter("foo bar".into()))]
+    #[case("filter", Command::Filter("".into()))]
+    #[case(
+        "goto s3://bucket/a/b/",
+        Command::Goto(S3Location { bucket: "bucket".into(), key: "a/b/".into() })
+    )]
+    #[case(
+        "inventory s3://inventory/bucket/config/2024-01-02T01-00Z/manifest.json",
+        Command::Inventory(S3Location {
+            bucket: "inventory".into(),
+            key: "bucket/config/2024-01-02T01-00Z/manifest.json".into(),
+        })
+    )]
+    #[case("accesslog", Command::AccessLog(None))]
+    #[case("al logs/2024-01-", Command::AccessLog(Some("logs/2024-01-".into())))]
+    #[case("refresh", Command::Refresh)]
+    #[case("every 5 refresh", Command::Every(5, ScheduledTaskType::Refresh, None))]
+    #[case(
+        "every 10 download",
+        Command::Every(10, ScheduledTaskType::Download, None)
+    )]
+    #[case(
+        "every 1 dl logs/2024-",
+        Command::Every(1, ScheduledTaskType::Download, Some("logs/2024-".into()))
+    )]
+    #[case("every off", Command::EveryOff)]
+    #[case("q", Command::Quit)]
+    fn test_parse_command(#[case] s: &str, #[case] expected: Command) {
+        assert_eq!(parse_command(s).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case(":")]
+    #[case("foo")]
+    #[case("delete foo")]
+    #[case("sort foo")]
+    #[case("sort name up")]
+    #[case("sort name desc foo")]
+    #[case("sort default desc")]
+    #[case("goto")]
+    #[case("goto bucket/key")]
+    #[case("inventory")]
+    #[case("every")]
+    #[case("every 5")]
+    #[case("every 0 refresh")]
+    #[case("every five refresh")]
+    #[case("every 5 refresh foo")]
+    #[case("every 5 delete")]
+    fn test_parse_command_error(#[case] s: &str) {
+        assert!(parse_command(s).is_err());
+    }
+}
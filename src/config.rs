@@ -1,10 +1,13 @@
-use std::{env, path::PathBuf};
+use std::{collections::HashMap, env, path::PathBuf, time::Duration};
 
 use anyhow::Context;
+use chrono::{DateTime, Local};
 use serde::Deserialize;
 use smart_default::SmartDefault;
 use umbra::optional;
 
+use crate::{client::DELETE_OBJECTS_MAX_KEYS, file::unique_file_path, util::glob_match};
+
 const STU_ROOT_DIR_ENV_VAR: &str = "STU_ROOT_DIR";
 
 const APP_BASE_DIR: &str = ".stu";
@@ -15,6 +18,11 @@ const DOWNLOAD_DIR: &str = "download";
 const PREVIEW_THEME_DIR: &str = "preview_theme";
 const PREVIEW_SYNTAX_DIR: &str = "preview_syntax";
 const CACHE_FILE_NAME: &str = "cache.txt";
+const ETAG_INDEX_FILE_NAME: &str = "etag_index.txt";
+const PINNED_BUCKETS_FILE_NAME: &str = "pinned_buckets.txt";
+const PROMETHEUS_METRICS_FILE_NAME: &str = "metrics.prom";
+const JSON_METRICS_FILE_NAME: &str = "metrics.json";
+const REPORT_DIR: &str = "report";
 
 #[optional(derives = [Deserialize])]
 #[derive(Debug, Clone, SmartDefault)]
@@ -24,9 +32,129 @@ pub struct Config {
     #[default = "us-east-1"]
     pub default_region: String,
     #[nested]
+    pub download: DownloadConfig,
+    #[nested]
+    pub upload: UploadConfig,
+    #[nested]
     pub ui: UiConfig,
     #[nested]
     pub preview: PreviewConfig,
+    #[nested]
+    pub cost: CostConfig,
+    #[nested]
+    pub sse_c: SseCConfig,
+    #[nested]
+    pub filters: FiltersConfig,
+    #[nested]
+    pub metrics: MetricsConfig,
+    #[nested]
+    pub retry: RetryConfig,
+    #[nested]
+    pub client: ClientConfig,
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct DownloadConfig {
+    #[default = "{basename}"]
+    pub name_template: String,
+    pub conflict_policy: DownloadConflictPolicy,
+    #[default = 4]
+    pub max_concurrent_requests: usize,
+    pub verify: DownloadVerifyType,
+    #[default = 64]
+    pub multipart_threshold_mib: u64,
+    #[default = 8]
+    pub part_size_mib: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadConflictPolicy {
+    #[default]
+    Overwrite,
+    // add a " (n)" suffix to the file name
+    Rename,
+}
+
+impl DownloadConflictPolicy {
+    // Returns the path to save the file to
+    pub fn resolve(&self, path: PathBuf) -> PathBuf {
+        match self {
+            Self::Overwrite => path,
+            Self::Rename => unique_file_path(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadVerifyType {
+    #[default]
+    None,
+    Size,
+    // the size and the MD5 digest of the content, if the ETag is an MD5 digest
+    Etag,
+}
+
+impl DownloadConfig {
+    // 0 if the objects are always downloaded in a single request
+    pub fn multipart_threshold_byte(&self) -> usize {
+        (self.multipart_threshold_mib * 1024 * 1024) as usize
+    }
+
+    pub fn part_size_byte(&self) -> usize {
+        (self.part_size_mib * 1024 * 1024) as usize
+    }
+
+    pub fn should_download_in_parts(&self, size_byte: usize) -> bool {
+        let threshold = self.multipart_threshold_byte();
+        threshold > 0 && self.part_size_mib > 0 && size_byte >= threshold
+    }
+
+    // Expands the placeholders in the template.
+    // {version_id} is expanded to "latest" if the version is not specified.
+    pub fn file_name(
+        &self,
+        bucket: &str,
+        key: &str,
+        basename: &str,
+        version_id: Option<&str>,
+        now: DateTime<Local>,
+    ) -> String {
+        self.name_template
+            .replace("{bucket}", bucket)
+            .replace("{key}", key)
+            .replace("{basename}", basename)
+            .replace("{date}", &now.format("%Y-%m-%d").to_string())
+            .replace("{version_id}", version_id.unwrap_or("latest"))
+    }
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct UploadConfig {
+    #[default = 64]
+    pub multipart_threshold_mib: u64,
+    #[default = 8]
+    pub part_size_mib: u64,
+    #[default = 3]
+    pub max_retries: usize,
+    pub watch_dir: String,
+}
+
+impl UploadConfig {
+    pub fn multipart_threshold_byte(&self) -> u64 {
+        self.multipart_threshold_mib * 1024 * 1024
+    }
+
+    pub fn part_size_byte(&self) -> u64 {
+        self.part_size_mib * 1024 * 1024
+    }
+
+    pub fn watch_dir(&self) -> Option<PathBuf> {
+        (!self.watch_dir.is_empty()).then(|| PathBuf::from(&self.watch_dir))
+    }
 }
 
 #[optional(derives = [Deserialize])]
@@ -45,6 +173,8 @@ pub struct UiObjectListConfig {
     pub date_format: String,
     #[default = 19] // // "2021-01-01 12:34:56".len()
     pub date_width: usize,
+    pub show_changes: bool,
+    pub decode_key_names: bool,
 }
 
 #[optional(derives = [Deserialize])]
@@ -60,7 +190,159 @@ pub struct PreviewConfig {
     pub highlight: bool,
     #[default = "base16-ocean.dark"]
     pub highlight_theme: String,
-    pub image: bool,
+    #[nested]
+    pub image: PreviewImageConfig,
+    #[default = true]
+    pub word_wrap: bool,
+    pub hanging_indent: bool,
+    #[default = 1]
+    pub range_size_mib: u64,
+    #[default = 5]
+    pub cache_size: usize,
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct PreviewImageConfig {
+    pub enabled: bool,
+}
+
+impl PreviewConfig {
+    // 0 if the whole object is always loaded
+    pub fn range_size_byte(&self) -> usize {
+        (self.range_size_mib * 1024 * 1024) as usize
+    }
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct CostConfig {
+    #[default = 0.0004]
+    pub get_request_price_per_1000: f64,
+    #[default = 0.09]
+    pub transfer_price_per_gb: f64,
+    #[default = 0.005]
+    pub delete_request_price_per_1000: f64,
+    #[default = 0.01]
+    pub display_threshold: f64,
+}
+
+impl CostConfig {
+    // Returns the estimated cost, or None if it is below the threshold
+    pub fn estimate_download_cost(&self, request_count: usize, size_byte: usize) -> Option<f64> {
+        let request_cost = request_count as f64 / 1000.0 * self.get_request_price_per_1000;
+        let transfer_cost = size_byte as f64 / GB as f64 * self.transfer_price_per_gb;
+        let cost = request_cost + transfer_cost;
+        (cost >= self.display_threshold).then_some(cost)
+    }
+
+    // Objects are deleted by DeleteObjects requests, each of which deletes up to 1,000 keys
+    pub fn estimate_delete_cost(&self, key_count: usize) -> Option<f64> {
+        let request_count = key_count.div_ceil(DELETE_OBJECTS_MAX_KEYS);
+        let cost = request_count as f64 / 1000.0 * self.delete_request_price_per_1000;
+        (cost >= self.display_threshold).then_some(cost)
+    }
+}
+
+const GB: usize = 1024 * 1024 * 1024;
+
+#[optional(derives = [Deserialize])]
+#[derive(Clone, SmartDefault)]
+pub struct SseCConfig {
+    pub bucket_keys: HashMap<String, String>,
+}
+
+impl std::fmt::Debug for SseCConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // do not output the keys
+        let buckets: Vec<&String> = self.bucket_keys.keys().collect();
+        write!(f, "SseCConfig {{ buckets: {:?} }}", buckets)
+    }
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct FiltersConfig {
+    pub hidden: Vec<String>,
+}
+
+impl FiltersConfig {
+    // The patterns without '/' are matched against the bucket names
+    pub fn is_hidden_bucket(&self, name: &str) -> bool {
+        self.hidden
+            .iter()
+            .filter(|pattern| !pattern.contains('/'))
+            .any(|pattern| glob_match(pattern, name))
+    }
+
+    // The patterns with '/' are matched against `<bucket>/<key>`, the folder keys end with '/'
+    pub fn is_hidden_object(&self, bucket: &str, key: &str) -> bool {
+        let path = format!("{}/{}", bucket, key);
+        self.hidden
+            .iter()
+            .filter(|pattern| pattern.contains('/'))
+            .any(|pattern| glob_match(pattern, &path))
+    }
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct MetricsConfig {
+    pub format: MetricsFormat,
+    pub file_path: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsFormat {
+    #[default]
+    None,
+    Prometheus,
+    Json,
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct RetryConfig {
+    #[default = 3]
+    pub max_attempts: u32,
+    #[default = 1000]
+    pub initial_backoff_millis: u64,
+    pub mode: RetryMode,
+}
+
+#[optional(derives = [Deserialize])]
+#[derive(Debug, Clone, SmartDefault)]
+pub struct ClientConfig {
+    // seconds, 0 if no timeout
+    #[default = 3]
+    pub connect_timeout: u64,
+    // seconds, 0 if no timeout
+    pub read_timeout: u64,
+    // seconds, 0 if not indicated
+    #[default = 5]
+    pub slow_request_threshold: u64,
+    // PEM file of the root certificates trusted in addition to the native ones
+    pub ca_bundle: String,
+    pub insecure_skip_tls_verify: bool,
+}
+
+impl ClientConfig {
+    pub fn ca_bundle(&self) -> Option<&str> {
+        (!self.ca_bundle.is_empty()).then_some(self.ca_bundle.as_str())
+    }
+
+    pub fn slow_request_threshold(&self) -> Option<Duration> {
+        (self.slow_request_threshold > 0).then(|| Duration::from_secs(self.slow_request_threshold))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryMode {
+    #[default]
+    Standard,
+    Adaptive,
 }
 
 fn default_download_dir() -> String {
@@ -109,6 +391,36 @@ impl Config {
         Ok(dir.join(CACHE_FILE_NAME))
     }
 
+    pub fn etag_index_file_path() -> anyhow::Result<PathBuf> {
+        let dir = Config::get_app_base_dir()?;
+        Ok(dir.join(ETAG_INDEX_FILE_NAME))
+    }
+
+    pub fn pinned_buckets_file_path() -> anyhow::Result<PathBuf> {
+        let dir = Config::get_app_base_dir()?;
+        Ok(dir.join(PINNED_BUCKETS_FILE_NAME))
+    }
+
+    // None if the metrics of the session are not written
+    pub fn metrics_file_path(&self) -> anyhow::Result<Option<PathBuf>> {
+        if !self.metrics.file_path.is_empty() {
+            let enabled = self.metrics.format != MetricsFormat::None;
+            return Ok(enabled.then(|| PathBuf::from(&self.metrics.file_path)));
+        }
+        let name = match self.metrics.format {
+            MetricsFormat::None => return Ok(None),
+            MetricsFormat::Prometheus => PROMETHEUS_METRICS_FILE_NAME,
+            MetricsFormat::Json => JSON_METRICS_FILE_NAME,
+        };
+        let dir = Config::get_app_base_dir()?;
+        Ok(Some(dir.join(name)))
+    }
+
+    pub fn report_dir_path() -> anyhow::Result<PathBuf> {
+        let dir = Config::get_app_base_dir()?;
+        Ok(dir.join(REPORT_DIR))
+    }
+
     pub fn preview_theme_dir_path() -> anyhow::Result<PathBuf> {
         let dir = Config::get_app_base_dir()?;
         Ok(dir.join(PREVIEW_THEME_DIR))
@@ -131,3 +443,119 @@ impl Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_download_conflict_policy_resolve() {
+        let dir = env::temp_dir().join(format!("stu-conflict-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), b"a").unwrap();
+
+        let policy = DownloadConflictPolicy::Overwrite;
+        assert_eq!(policy.resolve(dir.join("a.txt")), dir.join("a.txt"));
+        let policy = DownloadConflictPolicy::Rename;
+        assert_eq!(policy.resolve(dir.join("a.txt")), dir.join("a (1).txt"));
+        assert_eq!(policy.resolve(dir.join("b.txt")), dir.join("b.txt"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_metrics_file_path() {
+        let mut config = Config::default();
+        config.metrics.file_path = "/tmp/stu.prom".to_string();
+        assert_eq!(config.metrics_file_path().unwrap(), None);
+
+        config.metrics.format = MetricsFormat::Prometheus;
+        let expected = Some(PathBuf::from("/tmp/stu.prom"));
+        assert_eq!(config.metrics_file_path().unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("{basename}", None, "file.txt")]
+    #[case("{basename}.{version_id}", Some("v1"), "file.txt.v1")]
+    #[case("{basename}.{version_id}", None, "file.txt.latest")]
+    #[case("{bucket}/{key}", None, "bucket/dir/file.txt")]
+    #[case("{date}_{basename}", None, "2024-01-02_file.txt")]
+    fn test_download_config_file_name(
+        #[case] name_template: &str,
+        #[case] version_id: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let config = DownloadConfig {
+            name_template: name_template.to_string(),
+            ..Default::default()
+        };
+        let now = NaiveDateTime::parse_from_str("2024-01-02 13:01:02", "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+        let actual = config.file_name("bucket", "dir/file.txt", "file.txt", version_id, now);
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(1, 1024, None)]
+    #[case(100_000, 0, Some(0.04))]
+    #[case(1, 10 * GB, Some(0.9))]
+    #[case(10_000, GB, Some(0.094))]
+    fn test_cost_config_estimate_download_cost(
+        #[case] request_count: usize,
+        #[case] size_byte: usize,
+        #[case] expected: Option<f64>,
+    ) {
+        let config = CostConfig::default();
+        let actual = config.estimate_download_cost(request_count, size_byte);
+        match (actual, expected) {
+            (Some(a), Some(e)) => assert!((a - e).abs() < 1e-6, "{} != {}", a, e),
+            (a, e) => assert_eq!(a, e),
+        }
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(1_000_000, None)]
+    #[case(2_000_000, Some(0.01))]
+    #[case(2_000_001, Some(0.010005))]
+    #[case(10_000_000, Some(0.05))]
+    fn test_cost_config_estimate_delete_cost(
+        #[case] key_count: usize,
+        #[case] expected: Option<f64>,
+    ) {
+        let config = CostConfig::default();
+        let actual = config.estimate_delete_cost(key_count);
+        match (actual, expected) {
+            (Some(a), Some(e)) => assert!((a - e).abs() < 1e-6, "{} != {}", a, e),
+            (a, e) => assert_eq!(a, e),
+        }
+    }
+
+    #[test]
+    fn test_filters_config_is_hidden() {
+        let config = FiltersConfig {
+            hidden: vec![
+                "cdk-*".to_string(),
+                "*/_temporary/".to_string(),
+                "logs-bucket/20??/".to_string(),
+            ],
+        };
+        assert!(config.is_hidden_bucket("cdk-hnb659fds-assets"));
+        assert!(!config.is_hidden_bucket("my-cdk-bucket"));
+        // the object patterns are not applied to the buckets
+        assert!(!config.is_hidden_bucket("_temporary"));
+
+        assert!(config.is_hidden_object("bucket", "_temporary/"));
+        assert!(config.is_hidden_object("bucket", "a/b/_temporary/"));
+        assert!(!config.is_hidden_object("bucket", "a/_temporary.txt"));
+        assert!(config.is_hidden_object("logs-bucket", "2024/"));
+        assert!(!config.is_hidden_object("logs-bucket", "2024/01/"));
+        // the bucket patterns are not applied to the objects
+        assert!(!config.is_hidden_object("cdk-bucket", "file.txt"));
+    }
+}
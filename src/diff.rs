@@ -0,0 +1,184 @@
+// The lines between the common prefix and suffix are compared by LCS only if this is not exceeded,
+// otherwise they are just paired in order, since the table grows with the product of the line counts.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRowType {
+    Equal,
+    Changed,
+    Removed,
+    Added,
+}
+
+// A row of the side-by-side diff, which holds the line index of each side.
+// The side without the line is shown as a blank line so that the both sides are aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffRow {
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub row_type: DiffRowType,
+}
+
+impl DiffRow {
+    fn equal(l: usize, r: usize) -> Self {
+        Self {
+            left: Some(l),
+            right: Some(r),
+            row_type: DiffRowType::Equal,
+        }
+    }
+
+    fn changed(l: usize, r: usize) -> Self {
+        Self {
+            left: Some(l),
+            right: Some(r),
+            row_type: DiffRowType::Changed,
+        }
+    }
+
+    fn removed(l: usize) -> Self {
+        Self {
+            left: Some(l),
+            right: None,
+            row_type: DiffRowType::Removed,
+        }
+    }
+
+    fn added(r: usize) -> Self {
+        Self {
+            left: None,
+            right: Some(r),
+            row_type: DiffRowType::Added,
+        }
+    }
+}
+
+pub fn diff_lines(left: &[&str], right: &[&str]) -> Vec<DiffRow> {
+    let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let (l_end, r_end) = (left.len() - suffix, right.len() - suffix);
+
+    let mut rows: Vec<DiffRow> = (0..prefix).map(|i| DiffRow::equal(i, i)).collect();
+    let l_mid = &left[prefix..l_end];
+    let r_mid = &right[prefix..r_end];
+    if l_mid.len() * r_mid.len() <= MAX_LCS_CELLS {
+        push_lcs_rows(&mut rows, l_mid, r_mid, prefix, prefix);
+    } else {
+        push_changed_rows(&mut rows, prefix..l_end, prefix..r_end);
+    }
+    rows.extend((0..suffix).map(|i| DiffRow::equal(l_end + i, r_end + i)));
+    rows
+}
+
+fn push_lcs_rows(
+    rows: &mut Vec<DiffRow>,
+    left: &[&str],
+    right: &[&str],
+    l_offset: usize,
+    r_offset: usize,
+) {
+    let (n, m) = (left.len(), right.len());
+    // lcs[i][j] is the length of the LCS of left[i..] and right[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    // the removed and added lines between the equal lines are paired as changed lines
+    let (mut removed_from, mut added_from) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && left[i] == right[j] {
+            push_changed_rows(
+                rows,
+                l_offset + removed_from..l_offset + i,
+                r_offset + added_from..r_offset + j,
+            );
+            rows.push(DiffRow::equal(l_offset + i, r_offset + j));
+            i += 1;
+            j += 1;
+            (removed_from, added_from) = (i, j);
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    push_changed_rows(
+        rows,
+        l_offset + removed_from..l_offset + n,
+        r_offset + added_from..r_offset + m,
+    );
+}
+
+fn push_changed_rows(
+    rows: &mut Vec<DiffRow>,
+    removed: std::ops::Range<usize>,
+    added: std::ops::Range<usize>,
+) {
+    let mut removed = removed.into_iter();
+    let mut added = added.into_iter();
+    loop {
+        let row = match (removed.next(), added.next()) {
+            (Some(l), Some(r)) => DiffRow::changed(l, r),
+            (Some(l), None) => DiffRow::removed(l),
+            (None, Some(r)) => DiffRow::added(r),
+            (None, None) => break,
+        };
+        rows.push(row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_types(rows: &[DiffRow]) -> Vec<(Option<usize>, Option<usize>, DiffRowType)> {
+        rows.iter().map(|r| (r.left, r.right, r.row_type)).collect()
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        use DiffRowType::*;
+
+        let left = ["a", "b", "c", "d", "e"];
+        let right = ["a", "c", "x", "e", "f"];
+        let rows = diff_lines(&left, &right);
+        assert_eq!(
+            row_types(&rows),
+            vec![
+                (Some(0), Some(0), Equal),
+                (Some(1), None, Removed),
+                (Some(2), Some(1), Equal),
+                (Some(3), Some(2), Changed),
+                (Some(4), Some(3), Equal),
+                (None, Some(4), Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_same_and_empty() {
+        let lines = ["a", "b"];
+        let rows = diff_lines(&lines, &lines);
+        assert!(rows.iter().all(|r| r.row_type == DiffRowType::Equal));
+        assert_eq!(rows.len(), 2);
+
+        let rows = diff_lines(&[], &lines);
+        assert!(rows.iter().all(|r| r.row_type == DiffRowType::Added));
+        assert_eq!(rows.len(), 2);
+
+        assert!(diff_lines(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::{
+    error::{AppError, Result},
+    object::DownloadObjectInfo,
+};
+
+static NEXT_LIST_ID: AtomicUsize = AtomicUsize::new(0);
+
+const CREATE_ATTEMPTS: usize = 16;
+
+// The objects to be downloaded, spooled to a temporary file so that the keys under a large prefix
+// are not held in memory. Only the offsets of the records and the sizes of the objects are kept,
+// and the objects are read from the file when needed.
+//
+// The objects are kept in the order they are written, which is expected to be the order of the keys.
+// The file is removed when the list is dropped.
+// A reader is kept open for the random access by `get`, since it is called for each visible row.
+//
+// Each record is written as:
+//
+// - the length of the key (u32) and the key
+// - the size (u64)
+// - the last modified time as the seconds (i64) and the nanoseconds (u32) since the epoch
+// - the length of the ETag (u32) and the ETag
+#[derive(Debug)]
+pub struct DownloadObjectList {
+    path: PathBuf,
+    offsets: Vec<u64>,
+    size_bytes: Vec<usize>,
+    reader: Mutex<Option<BufReader<File>>>,
+}
+
+impl DownloadObjectList {
+    pub fn from_objs(objs: impl IntoIterator<Item = DownloadObjectInfo>) -> Result<Self> {
+        let mut writer = DownloadObjectListWriter::create()?;
+        for obj in objs {
+            writer.push(&obj)?;
+        }
+        writer.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub fn size_bytes(&self) -> &[usize] {
+        &self.size_bytes
+    }
+
+    pub fn total_size_byte(&self) -> usize {
+        self.size_bytes.iter().sum()
+    }
+
+    pub fn get(&self, index: usize) -> Result<DownloadObjectInfo> {
+        let offset = self.offsets[index];
+        // the reader seeks before every read, so it can be used even after a panic while locked
+        let mut reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+        let reader = match reader.as_mut() {
+            Some(reader) => reader,
+            None => {
+                let file = File::open(&self.path).map_err(read_error)?;
+                reader.insert(BufReader::new(file))
+            }
+        };
+        reader.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+        read_record(reader).map_err(read_error)
+    }
+
+    pub fn iter(&self) -> Result<DownloadObjectListIter> {
+        let file = File::open(&self.path).map_err(read_error)?;
+        Ok(DownloadObjectListIter {
+            reader: BufReader::new(file),
+            remaining: self.len(),
+        })
+    }
+
+    // Returns a new list of the objects whose indices satisfy `f`
+    pub fn filter(&self, f: impl Fn(usize) -> bool) -> Result<Self> {
+        let mut writer = DownloadObjectListWriter::create()?;
+        for (i, obj) in self.iter()?.enumerate() {
+            let obj = obj?;
+            if f(i) {
+                writer.push(&obj)?;
+            }
+        }
+        writer.finish()
+    }
+}
+
+impl Drop for DownloadObjectList {
+    fn drop(&mut self) {
+        // close the reader first, since an open file cannot be removed on some platforms
+        self.reader
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Err(e) = fs::remove_file(&self.path) {
+            tracing::warn!("failed to remove download list: {}", e);
+        }
+    }
+}
+
+// The list is removed if the writer is dropped without finishing
+#[derive(Debug)]
+pub struct DownloadObjectListWriter {
+    writer: BufWriter<File>,
+    list: DownloadObjectList,
+    offset: u64,
+}
+
+impl DownloadObjectListWriter {
+    pub fn create() -> Result<Self> {
+        let (file, path) =
+            create_list_file().map_err(|e| AppError::new("Failed to create download list", e))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            list: DownloadObjectList {
+                path,
+                offsets: Vec::new(),
+                size_bytes: Vec::new(),
+                reader: Mutex::new(None),
+            },
+            offset: 0,
+        })
+    }
+
+    pub fn push(&mut self, obj: &DownloadObjectInfo) -> Result<()> {
+        let record = encode_record(obj);
+        self.writer.write_all(&record).map_err(write_error)?;
+        self.list.offsets.push(self.offset);
+        self.list.size_bytes.push(obj.size_byte);
+        self.offset += record.len() as u64;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn finish(self) -> Result<DownloadObjectList> {
+        let Self {
+            mut writer, list, ..
+        } = self;
+        writer.flush().map_err(write_error)?;
+        Ok(list)
+    }
+}
+
+// The file is created only if it does not exist, so that an existing file or symlink placed at
+// the path is never followed or truncated. Only the owner can read and write it.
+fn create_list_file() -> io::Result<(File, PathBuf)> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut attempts = 0;
+    loop {
+        let id = NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or_default();
+        let name = format!(
+            "stu-download-list-{}-{}-{:08x}",
+            std::process::id(),
+            id,
+            nanos
+        );
+        let path = std::env::temp_dir().join(name);
+        match options.open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < CREATE_ATTEMPTS => {
+                attempts += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+pub struct DownloadObjectListIter {
+    reader: BufReader<File>,
+    remaining: usize,
+}
+
+impl Iterator for DownloadObjectListIter {
+    type Item = Result<DownloadObjectInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = read_record(&mut self.reader).map_err(read_error);
+        // the rest cannot be read after an error
+        self.remaining = if result.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(result)
+    }
+}
+
+fn encode_record(obj: &DownloadObjectInfo) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(obj.key.len() + obj.e_tag.len() + 28);
+    buf.extend((obj.key.len() as u32).to_le_bytes());
+    buf.extend(obj.key.as_bytes());
+    buf.extend((obj.size_byte as u64).to_le_bytes());
+    buf.extend(obj.last_modified.timestamp().to_le_bytes());
+    buf.extend(obj.last_modified.timestamp_subsec_nanos().to_le_bytes());
+    buf.extend((obj.e_tag.len() as u32).to_le_bytes());
+    buf.extend(obj.e_tag.as_bytes());
+    buf
+}
+
+fn read_record(reader: &mut impl Read) -> io::Result<DownloadObjectInfo> {
+    let key = read_string(reader)?;
+    let size_byte = u64::from_le_bytes(read_bytes(reader)?) as usize;
+    let secs = i64::from_le_bytes(read_bytes(reader)?);
+    let nsecs = u32::from_le_bytes(read_bytes(reader)?);
+    let last_modified = DateTime::from_timestamp(secs, nsecs)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid last modified"))?
+        .with_timezone(&Local);
+    let e_tag = read_string(reader)?;
+    Ok(DownloadObjectInfo {
+        key,
+        size_byte,
+        last_modified,
+        e_tag,
+    })
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = u32::from_le_bytes(read_bytes(reader)?) as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn read_error(e: io::Error) -> AppError {
+    AppError::new("Failed to read download list", e)
+}
+
+fn write_error(e: io::Error) -> AppError {
+    AppError::new("Failed to write download list", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn obj(key: &str, size_byte: usize) -> DownloadObjectInfo {
+        DownloadObjectInfo {
+            key: key.to_string(),
+            size_byte,
+            last_modified: Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+            e_tag: format!("\"{}\"", size_byte),
+        }
+    }
+
+    #[test]
+    fn test_download_object_list() {
+        let objs = vec![obj("a/b.txt", 10), obj("a/c\td\n.txt", 0), obj("e", 200)];
+        let list = DownloadObjectList::from_objs(objs.clone()).unwrap();
+        let path = list.path.clone();
+        assert!(path.exists());
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.size_bytes(), &[10, 0, 200]);
+        assert_eq!(list.total_size_byte(), 210);
+
+        let actual = list.get(1).unwrap();
+        assert_eq!(actual.key, "a/c\td\n.txt");
+        assert_eq!(actual.last_modified, objs[1].last_modified);
+        assert_eq!(actual.e_tag, "\"0\"");
+
+        // read again with the kept reader, in any order
+        assert_eq!(list.get(2).unwrap().key, "e");
+        assert_eq!(list.get(0).unwrap().key, "a/b.txt");
+        assert_eq!(list.get(1).unwrap().key, "a/c\td\n.txt");
+
+        let keys: Vec<String> = list.iter().unwrap().map(|obj| obj.unwrap().key).collect();
+        assert_eq!(keys, vec!["a/b.txt", "a/c\td\n.txt", "e"]);
+
+        let filtered = list.filter(|i| i != 1).unwrap();
+        let keys: Vec<String> = filtered
+            .iter()
+            .unwrap()
+            .map(|obj| obj.unwrap().key)
+            .collect();
+        assert_eq!(keys, vec!["a/b.txt", "e"]);
+        assert_eq!(filtered.size_bytes(), &[10, 200]);
+
+        drop(list);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_download_object_list_writer_dropped() {
+        let mut writer = DownloadObjectListWriter::create().unwrap();
+        writer.push(&obj("a", 1)).unwrap();
+        assert_eq!(writer.len(), 1);
+        let path = writer.list.path.clone();
+        assert!(path.exists());
+
+        drop(writer);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_download_object_list_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let writer = DownloadObjectListWriter::create().unwrap();
+        let mode = fs::metadata(&writer.list.path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -1,16 +1,29 @@
-use crate::config::Config;
+use std::cell::OnceCell;
+
+use crate::{config::Config, event};
 
 #[derive(Debug, Default, Clone)]
 pub struct Environment {
-    pub image_picker: ImagePicker,
+    image_preview_enabled: bool,
+    // the terminal is queried only when an image is previewed for the first time,
+    // because the query at startup causes glitches on some terminals
+    image_picker: OnceCell<ImagePicker>,
 }
 
 impl Environment {
     pub fn new(config: &Config) -> Environment {
         Environment {
-            image_picker: build_image_picker(config.preview.image),
+            image_preview_enabled: config.preview.image.enabled,
+            image_picker: OnceCell::new(),
         }
     }
+
+    pub fn image_picker(&self) -> &ImagePicker {
+        self.image_picker.get_or_init(|| {
+            // the response of the query must not be read as key events
+            event::with_input_reader_paused(|| build_image_picker(self.image_preview_enabled))
+        })
+    }
 }
 
 #[allow(dead_code)]
@@ -1,4 +1,4 @@
-use std::error::Error;
+use std::{error::Error, fmt};
 
 pub type Result<T> = std::result::Result<T, AppError>;
 
@@ -30,3 +30,12 @@ impl AppError {
         }
     }
 }
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.cause {
+            Some(cause) => write!(f, "{}: {}", self.msg, cause),
+            None => write!(f, "{}", self.msg),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::object::{ObjectItem, ObjectKey};
+
+const PREFIX_LINE_TAG: &str = "P";
+const OBJECT_LINE_TAG: &str = "O";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectChange {
+    New,
+    Modified,
+}
+
+// Remembers the ETags of the listed objects across sessions.
+//
+// The file has one entry per line:
+//
+// - `P\t<prefix uri>` for the prefixes that have been listed
+// - `O\t<object uri>\t<etag>` for the objects
+//
+// Changes are always compared with the entries loaded at startup,
+// so the indicators stay the same while the app is running.
+#[derive(Debug, Default)]
+pub struct ETagIndex {
+    file_path: Option<PathBuf>,
+    previous_prefixes: HashSet<String>,
+    previous_objects: HashMap<String, String>,
+    prefixes: HashSet<String>,
+    objects: HashMap<String, String>,
+}
+
+impl ETagIndex {
+    pub fn load(file_path: PathBuf) -> ETagIndex {
+        let contents = match fs::read_to_string(&file_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                tracing::warn!("failed to read etag index: {}", e);
+                String::new()
+            }
+        };
+        ETagIndex {
+            file_path: Some(file_path),
+            ..ETagIndex::parse(&contents)
+        }
+    }
+
+    fn parse(contents: &str) -> ETagIndex {
+        let mut prefixes = HashSet::new();
+        let mut objects = HashMap::new();
+        for line in contents.lines() {
+            match line.split_once('\t') {
+                Some((PREFIX_LINE_TAG, uri)) => {
+                    prefixes.insert(uri.to_string());
+                }
+                Some((OBJECT_LINE_TAG, rest)) => {
+                    // etags never contain tabs, but keys may
+                    if let Some((uri, e_tag)) = rest.rsplit_once('\t') {
+                        objects.insert(uri.to_string(), e_tag.to_string());
+                    }
+                }
+                _ => {
+                    tracing::warn!("invalid line in etag index: {}", line);
+                }
+            }
+        }
+
+        ETagIndex {
+            file_path: None,
+            previous_prefixes: prefixes.clone(),
+            previous_objects: objects.clone(),
+            prefixes,
+            objects,
+        }
+    }
+
+    // Returns the changes of the files in `items` since the prefix was listed last time.
+    // Nothing is reported for a prefix listed for the first time.
+    pub fn detect_changes(
+        &self,
+        object_key: &ObjectKey,
+        items: &[ObjectItem],
+    ) -> HashMap<usize, ObjectChange> {
+        if !self.previous_prefixes.contains(&prefix_uri(object_key)) {
+            return HashMap::new();
+        }
+        items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| match item {
+                ObjectItem::File { s3_uri, e_tag, .. } => match self.previous_objects.get(s3_uri) {
+                    None => Some((i, ObjectChange::New)),
+                    Some(prev) if prev != e_tag => Some((i, ObjectChange::Modified)),
+                    Some(_) => None,
+                },
+                ObjectItem::Dir { .. } => None,
+            })
+            .collect()
+    }
+
+    pub fn update(&mut self, object_key: &ObjectKey, items: &[ObjectItem]) -> io::Result<()> {
+        self.prefixes.insert(prefix_uri(object_key));
+        for item in items {
+            if let ObjectItem::File { s3_uri, e_tag, .. } = item {
+                self.objects.insert(s3_uri.clone(), e_tag.clone());
+            }
+        }
+        self.write()
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+        let temp_file_path = file_path.with_extension("tmp");
+        let mut file = fs::File::create(&temp_file_path)?;
+        file.write_all(self.contents().as_bytes())?;
+        fs::rename(temp_file_path, file_path)
+    }
+
+    // keys containing a newline cannot be written in the line-based format
+    fn contents(&self) -> String {
+        let prefix_lines = self
+            .prefixes
+            .iter()
+            .filter(|uri| !uri.contains('\n'))
+            .map(|uri| format!("{}\t{}\n", PREFIX_LINE_TAG, uri));
+        let object_lines = self
+            .objects
+            .iter()
+            .filter(|(uri, _)| !uri.contains('\n'))
+            .map(|(uri, e_tag)| format!("{}\t{}\t{}\n", OBJECT_LINE_TAG, uri, e_tag));
+        prefix_lines.chain(object_lines).collect()
+    }
+}
+
+fn prefix_uri(object_key: &ObjectKey) -> String {
+    format!(
+        "s3://{}/{}",
+        object_key.bucket_name,
+        object_key.joined_object_path(false)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+
+    use super::*;
+
+    fn file_item(name: &str, e_tag: &str) -> ObjectItem {
+        ObjectItem::File {
+            name: name.to_string(),
+            size_byte: 0,
+            last_modified: Local::now(),
+            key: format!("dir/{}", name),
+            s3_uri: format!("s3://bucket/dir/{}", name),
+            arn: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
+            e_tag: e_tag.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_etag_index() {
+        let object_key = ObjectKey {
+            bucket_name: "bucket".to_string(),
+            object_path: vec!["dir".to_string()],
+        };
+
+        let mut index = ETagIndex::default();
+        let items = vec![file_item("a\tb.txt", "\"1\""), file_item("c.txt", "\"2\"")];
+        // the prefix has not been listed yet
+        assert!(index.detect_changes(&object_key, &items).is_empty());
+        index.update(&object_key, &items).unwrap();
+        // changes are compared with the entries at startup
+        assert!(index.detect_changes(&object_key, &items).is_empty());
+
+        let index = ETagIndex::parse(&index.contents());
+        let items = vec![
+            file_item("a\tb.txt", "\"1\""),
+            file_item("c.txt", "\"3\""),
+            file_item("d.txt", "\"4\""),
+        ];
+        let actual = index.detect_changes(&object_key, &items);
+        let expected = HashMap::from([(1, ObjectChange::Modified), (2, ObjectChange::New)]);
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,58 +1,192 @@
 use std::{
+    collections::HashSet,
     fmt::{self, Debug, Formatter},
     path::PathBuf,
-    sync::mpsc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
     thread,
+    time::Duration,
 };
 
 use ratatui::crossterm::event::KeyEvent;
 
 use crate::{
-    client::Client,
+    access_log::AccessLogSummary,
+    app::StartPage,
+    bucket_metrics::BucketMetrics,
+    download_list::DownloadObjectList,
     error::{AppError, Result},
-    object::{BucketItem, FileDetail, FileVersion, ObjectItem, ObjectKey, RawObject},
+    inventory::InventoryObjects,
+    jobs::{JobId, JobProgress},
+    lifecycle::LifecycleEvent,
+    location::S3Location,
+    multipart::{MultipartUpload, MultipartUploadTarget},
+    object::{
+        BucketItem, CleanupObject, CopyFailure, DeleteFailure, DownloadFailure,
+        DownloadObjectsOptions, DownloadObjectsOutcome, DuplicateObjectGroup, FileDetail,
+        FileVersion, FileVersions, LockedObject, ObjectDiff, ObjectItem, ObjectKey, ObjectMetadata,
+        ObjectsPage, RawObject, StorageClassType, TagFailure,
+    },
+    pages::page::PageId,
+    rename::RenameTarget,
+    schedule::{ScheduledTaskOutcome, ScheduledTaskType},
+    sso::SsoLoginPrompt,
+    storage::{Storage, StorageTarget},
 };
 
 #[derive(Debug)]
 pub enum AppEventType {
     Key(KeyEvent),
     Resize(usize, usize),
-    Initialize(Client, Option<String>),
+    // sent periodically while loading to redraw the loading dialog
+    Tick,
+    // the transferred and the total size in bytes of the object loaded by the current loading operation
+    LoadingTransferProgress(usize, usize),
+    // the number of the storage requests running longer than the threshold
+    SlowRequests(usize),
+    Initialize(
+        Arc<dyn Storage>,
+        Option<String>,
+        Option<S3Location>,
+        Option<StartPage>,
+    ),
     CompleteInitialize(Result<CompleteInitializeResult>),
     ReloadBuckets,
     CompleteReloadBuckets(Result<CompleteReloadBucketsResult>),
     LoadObjects,
-    CompleteLoadObjects(Result<CompleteLoadObjectsResult>),
+    // the loads of the pages do not block the app, so the results have the id of the page which started them
+    CompleteLoadObjects(PageId, Result<CompleteLoadObjectsResult>),
+    // the following pages of the object list, the page id is of the object list page
+    AppendLoadedObjects(PageId, Result<CompleteLoadObjectsResult>),
     ReloadObjects,
-    CompleteReloadObjects(Result<CompleteReloadObjectsResult>),
+    CompleteReloadObjects(PageId, Result<CompleteReloadObjectsResult>),
     LoadObjectDetail,
-    CompleteLoadObjectDetail(Result<CompleteLoadObjectDetailResult>),
+    CompleteLoadObjectDetail(PageId, Result<CompleteLoadObjectDetailResult>),
     LoadObjectVersions,
-    CompleteLoadObjectVersions(Result<CompleteLoadObjectVersionsResult>),
+    CompleteLoadObjectVersions(PageId, Result<CompleteLoadObjectVersionsResult>),
+    LoadMoreObjectVersions,
+    CompleteLoadMoreObjectVersions(PageId, Result<CompleteLoadObjectVersionsResult>),
+    LoadObjectLifecycle,
+    CompleteLoadObjectLifecycle(Result<CompleteLoadObjectLifecycleResult>),
+    StartComputeObjectMd5(FileDetail, ObjectKey),
+    CompleteComputeObjectMd5(Result<CompleteComputeObjectMd5Result>),
     DownloadObject(FileDetail, Option<String>),
     DownloadObjectAs(FileDetail, String, Option<String>),
-    CompleteDownloadObject(Result<CompleteDownloadObjectResult>),
+    CompleteDownloadObject(JobId, Result<CompleteDownloadObjectResult>),
+    StartLoadAllDownloadObjectList(String, String),
+    LoadAllDownloadObjectListProgress(usize),
+    CompleteLoadAllDownloadObjectList(Result<CompleteLoadAllDownloadObjectListResult>),
+    StartDownloadObjects(
+        String,
+        String,
+        DownloadObjectList,
+        DownloadObjectsOptions,
+        PathBuf,
+    ),
+    CompleteDownloadObjects(JobId, Result<CompleteDownloadObjectsResult>),
+    JobProgress(JobId, JobProgress),
+    CancelJob(JobId),
+    RetryJob(JobId),
+    StartRenameObjects(String, String, Vec<RenameTarget>),
+    CompleteRenameObjects(Result<CompleteRenameObjectsResult>),
+    UploadObject(String, String, PathBuf, Option<String>),
+    CompleteUploadObject(Result<CompleteUploadObjectResult>),
+    CompleteFindMultipartUpload(Result<CompleteFindMultipartUploadResult>),
+    StartMultipartUpload(MultipartUploadTarget, Option<MultipartUpload>),
+    UploadClipboardImage(String, String),
+    StartWatchDir(String, String),
+    StopWatchDir,
+    WatchFileChanged(PathBuf),
+    StartWatchUpload(PathBuf),
+    CompleteWatchUpload(PathBuf, Result<u64>),
+    CompleteUploadClipboardImage(Result<CompleteUploadClipboardImageResult>),
+    CreateFolder(ObjectKey, String),
+    CompleteCreateFolder(Result<CompleteCreateFolderResult>),
+    StartApplyObjectTags(String, Vec<String>, Vec<(String, String)>),
+    CompleteApplyObjectTags(Result<CompleteApplyObjectTagsResult>),
+    StartChangeStorageClass(String, Vec<String>, StorageClassType),
+    CompleteChangeStorageClass(Result<CompleteChangeStorageClassResult>),
+    StartUpdateObjectMetadata(ObjectKey, ObjectMetadata),
+    CompleteUpdateObjectMetadata(Result<CompleteUpdateObjectMetadataResult>),
+    StartSetObjectLegalHold(ObjectKey, bool),
+    CompleteSetObjectLegalHold(Result<CompleteSetObjectLegalHoldResult>),
+    StartRemoveDeleteMarker(ObjectKey, FileVersion),
+    CompleteRemoveDeleteMarker(Result<CompleteRemoveDeleteMarkerResult>),
+    StartFindDuplicateObjects(ObjectKey),
+    CompleteFindDuplicateObjects(Result<CompleteFindDuplicateObjectsResult>),
+    StartFindCleanupObjects(ObjectKey),
+    CompleteFindCleanupObjects(Result<CompleteFindCleanupObjectsResult>),
+    StartCompareObjects(ObjectKey, ObjectKey),
+    CompleteCompareObjects(Result<CompleteCompareObjectsResult>),
+    StartCopyObject(ObjectKey, ObjectKey),
+    CompleteCopyObject(Result<CompleteCopyObjectResult>),
+    StartSyncObjects(ObjectKey, ObjectKey, Vec<String>),
+    CompleteSyncObjects(Result<CompleteSyncObjectsResult>),
+    StartDeleteObjects(String, Vec<String>),
+    CompleteCheckObjectLock(Result<CompleteCheckObjectLockResult>),
+    CompleteDeleteObjects(Result<CompleteDeleteObjectsResult>),
     PreviewObject(FileDetail, Option<String>),
     CompletePreviewObject(Result<CompletePreviewObjectResult>),
+    LoadMorePreview,
+    AppendPreviewObject(PageId, Result<RawObject>),
+    CompareVersionPreview(FileDetail, FileVersion),
+    CompleteCompareVersionPreview(Result<CompleteCompareVersionPreviewResult>),
     BucketListMoveDown,
     BucketListRefresh,
     ObjectListMoveDown,
     ObjectListMoveUp,
     ObjectListRefresh,
+    ToggleDecodeKeyNames,
+    ToggleShowHidden,
+    ObjectListDebounceFilter(usize),
+    ObjectListApplyDebouncedFilter(usize),
     BackToBucketList,
     OpenObjectVersionsTab,
     OpenPreview(FileDetail, Option<String>),
+    OpenCompareVersionPreview(FileDetail, FileVersion),
     DetailDownloadObject(FileDetail, Option<String>),
     DetailDownloadObjectAs(FileDetail, String, Option<String>),
     PreviewDownloadObject(RawObject, String),
     PreviewDownloadObjectAs(FileDetail, String, Option<String>),
     PreviewRerenderImage,
+    PreviewRefresh,
+    // the detail loaded again to check whether the object has been changed since it was previewed
+    CompletePreviewRefresh(PageId, Result<FileDetail>),
+    PreviewExportHtml(String, PathBuf),
+    PreviewCompareLocalFile(FileDetail, RawObject, String),
     BucketListOpenManagementConsole,
+    BucketListTogglePin,
+    OpenProfileSwitcher,
+    OpenRegionSwitcher,
+    CompleteSwitchStorage(StorageTarget, Arc<dyn Storage>),
+    SsoLoginPrompt(SsoLoginPrompt),
+    CompleteSsoLogin(Result<()>),
+    StartLoadBucketMetrics(BucketItem),
+    CompleteLoadBucketMetrics(Result<CompleteLoadBucketMetricsResult>),
+    CreateBucket(String, String),
+    CompleteCreateBucket(Result<CompleteCreateBucketResult>),
+    DeleteBucket(String),
+    CompleteDeleteBucket(Result<CompleteDeleteBucketResult>),
     ObjectListOpenManagementConsole,
     ObjectDetailOpenManagementConsole,
     CloseCurrentPage,
     OpenHelp,
     CopyToClipboard(String, String),
+    CopyPresignedUrl(String, String, Option<String>, Duration),
+    CopyPresignedPutUrl(String, String, Duration),
+    PasteLocation,
+    OpenLocation(S3Location),
+    CompleteOpenLocation(Result<CompleteOpenLocationResult>),
+    StartLoadInventory(S3Location),
+    CompleteLoadInventory(Result<CompleteLoadInventoryResult>),
+    StartAnalyzeAccessLogs(String, String),
+    CompleteAnalyzeAccessLogs(Result<CompleteAnalyzeAccessLogsResult>),
+    ScheduleTask(ScheduledTaskType, ObjectKey, String, u64),
+    CancelScheduledTasks,
+    RunScheduledTask(usize),
+    CompleteScheduledTask(usize, Result<ScheduledTaskOutcome>),
     NotifyInfo(String),
     NotifySuccess(String),
     NotifyWarn(String),
@@ -60,6 +194,181 @@ pub enum AppEventType {
     Quit,
 }
 
+impl AppEventType {
+    // Events that modify objects in S3, which are rejected in read-only mode.
+    // Add new events here when adding operations that write to S3.
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            AppEventType::StartRenameObjects(..)
+                | AppEventType::UploadObject(..)
+                | AppEventType::StartMultipartUpload(..)
+                | AppEventType::UploadClipboardImage(..)
+                | AppEventType::StartWatchDir(..)
+                | AppEventType::CopyPresignedPutUrl(..)
+                | AppEventType::CreateFolder(..)
+                | AppEventType::StartApplyObjectTags(..)
+                | AppEventType::StartChangeStorageClass(..)
+                | AppEventType::StartUpdateObjectMetadata(..)
+                | AppEventType::StartSetObjectLegalHold(..)
+                | AppEventType::StartRemoveDeleteMarker(..)
+                | AppEventType::StartCopyObject(..)
+                | AppEventType::StartSyncObjects(..)
+                | AppEventType::StartDeleteObjects(..)
+                | AppEventType::CreateBucket(..)
+                | AppEventType::DeleteBucket(..)
+        )
+    }
+
+    // The name of the event, which can be logged without exposing the payload such as the object
+    // contents or the presigned URLs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEventType::Key(..) => "Key",
+            AppEventType::Resize(..) => "Resize",
+            AppEventType::Tick => "Tick",
+            AppEventType::LoadingTransferProgress(..) => "LoadingTransferProgress",
+            AppEventType::SlowRequests(..) => "SlowRequests",
+            AppEventType::Initialize(..) => "Initialize",
+            AppEventType::CompleteInitialize(..) => "CompleteInitialize",
+            AppEventType::ReloadBuckets => "ReloadBuckets",
+            AppEventType::CompleteReloadBuckets(..) => "CompleteReloadBuckets",
+            AppEventType::LoadObjects => "LoadObjects",
+            AppEventType::CompleteLoadObjects(..) => "CompleteLoadObjects",
+            AppEventType::AppendLoadedObjects(..) => "AppendLoadedObjects",
+            AppEventType::ReloadObjects => "ReloadObjects",
+            AppEventType::CompleteReloadObjects(..) => "CompleteReloadObjects",
+            AppEventType::LoadObjectDetail => "LoadObjectDetail",
+            AppEventType::CompleteLoadObjectDetail(..) => "CompleteLoadObjectDetail",
+            AppEventType::LoadObjectVersions => "LoadObjectVersions",
+            AppEventType::CompleteLoadObjectVersions(..) => "CompleteLoadObjectVersions",
+            AppEventType::LoadMoreObjectVersions => "LoadMoreObjectVersions",
+            AppEventType::CompleteLoadMoreObjectVersions(..) => "CompleteLoadMoreObjectVersions",
+            AppEventType::LoadObjectLifecycle => "LoadObjectLifecycle",
+            AppEventType::CompleteLoadObjectLifecycle(..) => "CompleteLoadObjectLifecycle",
+            AppEventType::StartComputeObjectMd5(..) => "StartComputeObjectMd5",
+            AppEventType::CompleteComputeObjectMd5(..) => "CompleteComputeObjectMd5",
+            AppEventType::DownloadObject(..) => "DownloadObject",
+            AppEventType::DownloadObjectAs(..) => "DownloadObjectAs",
+            AppEventType::CompleteDownloadObject(..) => "CompleteDownloadObject",
+            AppEventType::StartLoadAllDownloadObjectList(..) => "StartLoadAllDownloadObjectList",
+            AppEventType::LoadAllDownloadObjectListProgress(..) => {
+                "LoadAllDownloadObjectListProgress"
+            }
+            AppEventType::CompleteLoadAllDownloadObjectList(..) => {
+                "CompleteLoadAllDownloadObjectList"
+            }
+            AppEventType::StartDownloadObjects(..) => "StartDownloadObjects",
+            AppEventType::CompleteDownloadObjects(..) => "CompleteDownloadObjects",
+            AppEventType::JobProgress(..) => "JobProgress",
+            AppEventType::CancelJob(..) => "CancelJob",
+            AppEventType::RetryJob(..) => "RetryJob",
+            AppEventType::StartRenameObjects(..) => "StartRenameObjects",
+            AppEventType::CompleteRenameObjects(..) => "CompleteRenameObjects",
+            AppEventType::UploadObject(..) => "UploadObject",
+            AppEventType::CompleteUploadObject(..) => "CompleteUploadObject",
+            AppEventType::CompleteFindMultipartUpload(..) => "CompleteFindMultipartUpload",
+            AppEventType::StartMultipartUpload(..) => "StartMultipartUpload",
+            AppEventType::UploadClipboardImage(..) => "UploadClipboardImage",
+            AppEventType::StartWatchDir(..) => "StartWatchDir",
+            AppEventType::StopWatchDir => "StopWatchDir",
+            AppEventType::WatchFileChanged(..) => "WatchFileChanged",
+            AppEventType::StartWatchUpload(..) => "StartWatchUpload",
+            AppEventType::CompleteWatchUpload(..) => "CompleteWatchUpload",
+            AppEventType::CompleteUploadClipboardImage(..) => "CompleteUploadClipboardImage",
+            AppEventType::CreateFolder(..) => "CreateFolder",
+            AppEventType::CompleteCreateFolder(..) => "CompleteCreateFolder",
+            AppEventType::StartApplyObjectTags(..) => "StartApplyObjectTags",
+            AppEventType::CompleteApplyObjectTags(..) => "CompleteApplyObjectTags",
+            AppEventType::StartChangeStorageClass(..) => "StartChangeStorageClass",
+            AppEventType::CompleteChangeStorageClass(..) => "CompleteChangeStorageClass",
+            AppEventType::StartUpdateObjectMetadata(..) => "StartUpdateObjectMetadata",
+            AppEventType::CompleteUpdateObjectMetadata(..) => "CompleteUpdateObjectMetadata",
+            AppEventType::StartSetObjectLegalHold(..) => "StartSetObjectLegalHold",
+            AppEventType::CompleteSetObjectLegalHold(..) => "CompleteSetObjectLegalHold",
+            AppEventType::StartRemoveDeleteMarker(..) => "StartRemoveDeleteMarker",
+            AppEventType::CompleteRemoveDeleteMarker(..) => "CompleteRemoveDeleteMarker",
+            AppEventType::StartFindDuplicateObjects(..) => "StartFindDuplicateObjects",
+            AppEventType::CompleteFindDuplicateObjects(..) => "CompleteFindDuplicateObjects",
+            AppEventType::StartFindCleanupObjects(..) => "StartFindCleanupObjects",
+            AppEventType::CompleteFindCleanupObjects(..) => "CompleteFindCleanupObjects",
+            AppEventType::StartCompareObjects(..) => "StartCompareObjects",
+            AppEventType::CompleteCompareObjects(..) => "CompleteCompareObjects",
+            AppEventType::StartCopyObject(..) => "StartCopyObject",
+            AppEventType::CompleteCopyObject(..) => "CompleteCopyObject",
+            AppEventType::StartSyncObjects(..) => "StartSyncObjects",
+            AppEventType::CompleteSyncObjects(..) => "CompleteSyncObjects",
+            AppEventType::StartDeleteObjects(..) => "StartDeleteObjects",
+            AppEventType::CompleteCheckObjectLock(..) => "CompleteCheckObjectLock",
+            AppEventType::CompleteDeleteObjects(..) => "CompleteDeleteObjects",
+            AppEventType::PreviewObject(..) => "PreviewObject",
+            AppEventType::CompletePreviewObject(..) => "CompletePreviewObject",
+            AppEventType::LoadMorePreview => "LoadMorePreview",
+            AppEventType::AppendPreviewObject(..) => "AppendPreviewObject",
+            AppEventType::CompareVersionPreview(..) => "CompareVersionPreview",
+            AppEventType::CompleteCompareVersionPreview(..) => "CompleteCompareVersionPreview",
+            AppEventType::BucketListMoveDown => "BucketListMoveDown",
+            AppEventType::BucketListRefresh => "BucketListRefresh",
+            AppEventType::ObjectListMoveDown => "ObjectListMoveDown",
+            AppEventType::ObjectListMoveUp => "ObjectListMoveUp",
+            AppEventType::ObjectListRefresh => "ObjectListRefresh",
+            AppEventType::ToggleDecodeKeyNames => "ToggleDecodeKeyNames",
+            AppEventType::ToggleShowHidden => "ToggleShowHidden",
+            AppEventType::ObjectListDebounceFilter(..) => "ObjectListDebounceFilter",
+            AppEventType::ObjectListApplyDebouncedFilter(..) => "ObjectListApplyDebouncedFilter",
+            AppEventType::BackToBucketList => "BackToBucketList",
+            AppEventType::OpenObjectVersionsTab => "OpenObjectVersionsTab",
+            AppEventType::OpenPreview(..) => "OpenPreview",
+            AppEventType::OpenCompareVersionPreview(..) => "OpenCompareVersionPreview",
+            AppEventType::DetailDownloadObject(..) => "DetailDownloadObject",
+            AppEventType::DetailDownloadObjectAs(..) => "DetailDownloadObjectAs",
+            AppEventType::PreviewDownloadObject(..) => "PreviewDownloadObject",
+            AppEventType::PreviewDownloadObjectAs(..) => "PreviewDownloadObjectAs",
+            AppEventType::PreviewRerenderImage => "PreviewRerenderImage",
+            AppEventType::PreviewRefresh => "PreviewRefresh",
+            AppEventType::CompletePreviewRefresh(..) => "CompletePreviewRefresh",
+            AppEventType::PreviewExportHtml(..) => "PreviewExportHtml",
+            AppEventType::PreviewCompareLocalFile(..) => "PreviewCompareLocalFile",
+            AppEventType::BucketListOpenManagementConsole => "BucketListOpenManagementConsole",
+            AppEventType::BucketListTogglePin => "BucketListTogglePin",
+            AppEventType::OpenProfileSwitcher => "OpenProfileSwitcher",
+            AppEventType::OpenRegionSwitcher => "OpenRegionSwitcher",
+            AppEventType::CompleteSwitchStorage(..) => "CompleteSwitchStorage",
+            AppEventType::SsoLoginPrompt(..) => "SsoLoginPrompt",
+            AppEventType::CompleteSsoLogin(..) => "CompleteSsoLogin",
+            AppEventType::StartLoadBucketMetrics(..) => "StartLoadBucketMetrics",
+            AppEventType::CompleteLoadBucketMetrics(..) => "CompleteLoadBucketMetrics",
+            AppEventType::CreateBucket(..) => "CreateBucket",
+            AppEventType::CompleteCreateBucket(..) => "CompleteCreateBucket",
+            AppEventType::DeleteBucket(..) => "DeleteBucket",
+            AppEventType::CompleteDeleteBucket(..) => "CompleteDeleteBucket",
+            AppEventType::ObjectListOpenManagementConsole => "ObjectListOpenManagementConsole",
+            AppEventType::ObjectDetailOpenManagementConsole => "ObjectDetailOpenManagementConsole",
+            AppEventType::CloseCurrentPage => "CloseCurrentPage",
+            AppEventType::OpenHelp => "OpenHelp",
+            AppEventType::CopyToClipboard(..) => "CopyToClipboard",
+            AppEventType::CopyPresignedUrl(..) => "CopyPresignedUrl",
+            AppEventType::CopyPresignedPutUrl(..) => "CopyPresignedPutUrl",
+            AppEventType::PasteLocation => "PasteLocation",
+            AppEventType::OpenLocation(..) => "OpenLocation",
+            AppEventType::CompleteOpenLocation(..) => "CompleteOpenLocation",
+            AppEventType::StartLoadInventory(..) => "StartLoadInventory",
+            AppEventType::CompleteLoadInventory(..) => "CompleteLoadInventory",
+            AppEventType::StartAnalyzeAccessLogs(..) => "StartAnalyzeAccessLogs",
+            AppEventType::CompleteAnalyzeAccessLogs(..) => "CompleteAnalyzeAccessLogs",
+            AppEventType::ScheduleTask(..) => "ScheduleTask",
+            AppEventType::CancelScheduledTasks => "CancelScheduledTasks",
+            AppEventType::RunScheduledTask(..) => "RunScheduledTask",
+            AppEventType::CompleteScheduledTask(..) => "CompleteScheduledTask",
+            AppEventType::NotifyInfo(..) => "NotifyInfo",
+            AppEventType::NotifySuccess(..) => "NotifySuccess",
+            AppEventType::NotifyWarn(..) => "NotifyWarn",
+            AppEventType::NotifyError(..) => "NotifyError",
+            AppEventType::Quit => "Quit",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CompleteInitializeResult {
     pub buckets: Vec<BucketItem>,
@@ -95,32 +404,38 @@ impl CompleteReloadBucketsResult {
 #[derive(Debug)]
 pub struct CompleteLoadObjectsResult {
     pub items: Vec<ObjectItem>,
+    pub object_key: ObjectKey,
+    // set if there are more pages to load
+    pub next_token: Option<String>,
 }
 
 impl CompleteLoadObjectsResult {
-    pub fn new(items: Result<Vec<ObjectItem>>) -> Result<CompleteLoadObjectsResult> {
-        let items = items?;
-        Ok(CompleteLoadObjectsResult { items })
-    }
-}
-
-impl From<CompleteReloadObjectsResult> for CompleteLoadObjectsResult {
-    fn from(result: CompleteReloadObjectsResult) -> Self {
-        CompleteLoadObjectsResult {
-            items: result.items,
-        }
+    pub fn new(
+        page: Result<ObjectsPage>,
+        object_key: ObjectKey,
+    ) -> Result<CompleteLoadObjectsResult> {
+        let ObjectsPage { items, next_token } = page?;
+        Ok(CompleteLoadObjectsResult {
+            items,
+            object_key,
+            next_token,
+        })
     }
 }
 
 #[derive(Debug)]
 pub struct CompleteReloadObjectsResult {
     pub items: Vec<ObjectItem>,
+    pub object_key: ObjectKey,
 }
 
 impl CompleteReloadObjectsResult {
-    pub fn new(items: Result<Vec<ObjectItem>>) -> Result<CompleteReloadObjectsResult> {
+    pub fn new(
+        items: Result<Vec<ObjectItem>>,
+        object_key: ObjectKey,
+    ) -> Result<CompleteReloadObjectsResult> {
         let items = items?;
-        Ok(CompleteReloadObjectsResult { items })
+        Ok(CompleteReloadObjectsResult { items, object_key })
     }
 }
 
@@ -142,13 +457,13 @@ impl CompleteLoadObjectDetailResult {
 
 #[derive(Debug)]
 pub struct CompleteLoadObjectVersionsResult {
-    pub versions: Vec<FileVersion>,
+    pub versions: FileVersions,
     pub map_key: ObjectKey,
 }
 
 impl CompleteLoadObjectVersionsResult {
     pub fn new(
-        versions: Result<Vec<FileVersion>>,
+        versions: Result<FileVersions>,
         map_key: ObjectKey,
     ) -> Result<CompleteLoadObjectVersionsResult> {
         let versions = versions?;
@@ -156,16 +471,560 @@ impl CompleteLoadObjectVersionsResult {
     }
 }
 
+#[derive(Debug)]
+pub struct CompleteLoadObjectLifecycleResult {
+    pub events: Vec<LifecycleEvent>,
+    pub map_key: ObjectKey,
+}
+
+impl CompleteLoadObjectLifecycleResult {
+    pub fn new(
+        events: Result<Vec<LifecycleEvent>>,
+        map_key: ObjectKey,
+    ) -> Result<CompleteLoadObjectLifecycleResult> {
+        let events = events?;
+        Ok(CompleteLoadObjectLifecycleResult { events, map_key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteLoadBucketMetricsResult {
+    pub bucket: BucketItem,
+    pub metrics: BucketMetrics,
+}
+
+impl CompleteLoadBucketMetricsResult {
+    pub fn new(
+        metrics: Result<BucketMetrics>,
+        bucket: BucketItem,
+    ) -> Result<CompleteLoadBucketMetricsResult> {
+        let metrics = metrics?;
+        Ok(CompleteLoadBucketMetricsResult { bucket, metrics })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCreateBucketResult {
+    pub name: String,
+    pub region: String,
+}
+
+impl CompleteCreateBucketResult {
+    pub fn new(
+        result: Result<()>,
+        name: String,
+        region: String,
+    ) -> Result<CompleteCreateBucketResult> {
+        result?;
+        Ok(CompleteCreateBucketResult { name, region })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteDeleteBucketResult {
+    pub name: String,
+}
+
+impl CompleteDeleteBucketResult {
+    pub fn new(result: Result<()>, name: String) -> Result<CompleteDeleteBucketResult> {
+        result?;
+        Ok(CompleteDeleteBucketResult { name })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteComputeObjectMd5Result {
+    pub md5: String,
+    pub map_key: ObjectKey,
+}
+
+impl CompleteComputeObjectMd5Result {
+    pub fn new(md5: Result<String>, map_key: ObjectKey) -> Result<CompleteComputeObjectMd5Result> {
+        let md5 = md5?;
+        Ok(CompleteComputeObjectMd5Result { md5, map_key })
+    }
+}
+
 #[derive(Debug)]
 pub struct CompleteDownloadObjectResult {
-    pub obj: RawObject,
+    // the temporary file the object has been downloaded to, which is moved to the path
+    pub downloaded_path: PathBuf,
     pub path: PathBuf,
 }
 
 impl CompleteDownloadObjectResult {
-    pub fn new(obj: Result<RawObject>, path: PathBuf) -> Result<CompleteDownloadObjectResult> {
-        let obj = obj?;
-        Ok(CompleteDownloadObjectResult { obj, path })
+    pub fn new(
+        downloaded_path: Result<PathBuf>,
+        path: PathBuf,
+    ) -> Result<CompleteDownloadObjectResult> {
+        let downloaded_path = downloaded_path?;
+        Ok(CompleteDownloadObjectResult {
+            downloaded_path,
+            path,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteLoadAllDownloadObjectListResult {
+    pub objs: DownloadObjectList,
+    pub key: String,
+}
+
+impl CompleteLoadAllDownloadObjectListResult {
+    pub fn new(
+        objs: Result<DownloadObjectList>,
+        key: String,
+    ) -> Result<CompleteLoadAllDownloadObjectListResult> {
+        let objs = objs?;
+        Ok(CompleteLoadAllDownloadObjectListResult { objs, key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteDownloadObjectsResult {
+    pub bucket: String,
+    pub key: String,
+    pub options: DownloadObjectsOptions,
+    pub path: PathBuf,
+    pub succeeded: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<DownloadFailure>,
+}
+
+impl CompleteDownloadObjectsResult {
+    pub fn new(
+        result: Result<DownloadObjectsOutcome>,
+        bucket: String,
+        key: String,
+        options: DownloadObjectsOptions,
+        path: PathBuf,
+    ) -> Result<CompleteDownloadObjectsResult> {
+        let DownloadObjectsOutcome {
+            succeeded,
+            skipped,
+            failed,
+        } = result?;
+        Ok(CompleteDownloadObjectsResult {
+            bucket,
+            key,
+            options,
+            path,
+            succeeded,
+            skipped,
+            failed,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteRenameObjectsResult {
+    pub count: usize,
+}
+
+impl CompleteRenameObjectsResult {
+    pub fn new(count: Result<usize>) -> Result<CompleteRenameObjectsResult> {
+        let count = count?;
+        Ok(CompleteRenameObjectsResult { count })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteUploadObjectResult {
+    pub key: String,
+}
+
+impl CompleteUploadObjectResult {
+    pub fn new(result: Result<()>, key: String) -> Result<CompleteUploadObjectResult> {
+        result?;
+        Ok(CompleteUploadObjectResult { key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteUploadClipboardImageResult {
+    pub key: String,
+    pub url: String,
+}
+
+impl CompleteUploadClipboardImageResult {
+    pub fn new(
+        result: Result<()>,
+        key: String,
+        url: String,
+    ) -> Result<CompleteUploadClipboardImageResult> {
+        result?;
+        Ok(CompleteUploadClipboardImageResult { key, url })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteFindMultipartUploadResult {
+    pub target: MultipartUploadTarget,
+    pub upload: Option<MultipartUpload>,
+}
+
+impl CompleteFindMultipartUploadResult {
+    pub fn new(
+        result: Result<Option<MultipartUpload>>,
+        target: MultipartUploadTarget,
+    ) -> Result<CompleteFindMultipartUploadResult> {
+        let upload = result?;
+        Ok(CompleteFindMultipartUploadResult { target, upload })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteApplyObjectTagsResult {
+    pub bucket: String,
+    // keys of the objects tagged successfully
+    pub keys: Vec<String>,
+    pub failed: Vec<TagFailure>,
+}
+
+impl CompleteApplyObjectTagsResult {
+    pub fn new(
+        result: Result<Vec<TagFailure>>,
+        bucket: String,
+        keys: Vec<String>,
+    ) -> Result<CompleteApplyObjectTagsResult> {
+        let failed = result?;
+        let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+        let keys = keys
+            .into_iter()
+            .filter(|key| !failed_keys.contains(key.as_str()))
+            .collect();
+        Ok(CompleteApplyObjectTagsResult {
+            bucket,
+            keys,
+            failed,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteChangeStorageClassResult {
+    pub bucket: String,
+    pub storage_class: StorageClassType,
+    // keys of the objects changed successfully
+    pub keys: Vec<String>,
+    pub failed: Vec<CopyFailure>,
+}
+
+impl CompleteChangeStorageClassResult {
+    pub fn new(
+        result: Result<Vec<CopyFailure>>,
+        bucket: String,
+        storage_class: StorageClassType,
+        keys: Vec<String>,
+    ) -> Result<CompleteChangeStorageClassResult> {
+        let failed = result?;
+        let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+        let keys = keys
+            .into_iter()
+            .filter(|key| !failed_keys.contains(key.as_str()))
+            .collect();
+        Ok(CompleteChangeStorageClassResult {
+            bucket,
+            storage_class,
+            keys,
+            failed,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteUpdateObjectMetadataResult {
+    pub detail: Box<FileDetail>,
+    pub map_key: ObjectKey,
+}
+
+impl CompleteUpdateObjectMetadataResult {
+    pub fn new(
+        detail: Result<FileDetail>,
+        map_key: ObjectKey,
+    ) -> Result<CompleteUpdateObjectMetadataResult> {
+        let detail = Box::new(detail?);
+        Ok(CompleteUpdateObjectMetadataResult { detail, map_key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteSetObjectLegalHoldResult {
+    pub detail: Box<FileDetail>,
+    pub map_key: ObjectKey,
+}
+
+impl CompleteSetObjectLegalHoldResult {
+    pub fn new(
+        detail: Result<FileDetail>,
+        map_key: ObjectKey,
+    ) -> Result<CompleteSetObjectLegalHoldResult> {
+        let detail = Box::new(detail?);
+        Ok(CompleteSetObjectLegalHoldResult { detail, map_key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteRemoveDeleteMarkerResult {
+    pub versions: FileVersions,
+    pub map_key: ObjectKey,
+    // the removed delete marker was the latest version, so the object has been restored
+    pub restored: bool,
+}
+
+impl CompleteRemoveDeleteMarkerResult {
+    pub fn new(
+        versions: Result<FileVersions>,
+        map_key: ObjectKey,
+        restored: bool,
+    ) -> Result<CompleteRemoveDeleteMarkerResult> {
+        let versions = versions?;
+        Ok(CompleteRemoveDeleteMarkerResult {
+            versions,
+            map_key,
+            restored,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCreateFolderResult {
+    pub object_key: ObjectKey,
+    pub item: ObjectItem,
+}
+
+impl CompleteCreateFolderResult {
+    pub fn new(
+        item: Result<ObjectItem>,
+        object_key: ObjectKey,
+    ) -> Result<CompleteCreateFolderResult> {
+        let item = item?;
+        Ok(CompleteCreateFolderResult { object_key, item })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteFindDuplicateObjectsResult {
+    pub object_key: ObjectKey,
+    pub groups: Vec<DuplicateObjectGroup>,
+}
+
+impl CompleteFindDuplicateObjectsResult {
+    pub fn new(
+        groups: Result<Vec<DuplicateObjectGroup>>,
+        object_key: ObjectKey,
+    ) -> Result<CompleteFindDuplicateObjectsResult> {
+        let groups = groups?;
+        Ok(CompleteFindDuplicateObjectsResult { object_key, groups })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCompareObjectsResult {
+    pub a_key: ObjectKey,
+    pub b_key: ObjectKey,
+    pub diffs: Vec<ObjectDiff>,
+}
+
+impl CompleteCompareObjectsResult {
+    pub fn new(
+        diffs: Result<Vec<ObjectDiff>>,
+        a_key: ObjectKey,
+        b_key: ObjectKey,
+    ) -> Result<CompleteCompareObjectsResult> {
+        let diffs = diffs?;
+        Ok(CompleteCompareObjectsResult {
+            a_key,
+            b_key,
+            diffs,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCopyObjectResult {
+    pub dst_key: ObjectKey,
+}
+
+impl CompleteCopyObjectResult {
+    pub fn new(result: Result<()>, dst_key: ObjectKey) -> Result<CompleteCopyObjectResult> {
+        result?;
+        Ok(CompleteCopyObjectResult { dst_key })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteSyncObjectsResult {
+    pub dst_key: ObjectKey,
+    // keys relative to the prefixes of the objects copied successfully
+    pub keys: Vec<String>,
+    pub failed: Vec<CopyFailure>,
+}
+
+impl CompleteSyncObjectsResult {
+    pub fn new(
+        result: Result<Vec<CopyFailure>>,
+        dst_key: ObjectKey,
+        keys: Vec<String>,
+    ) -> Result<CompleteSyncObjectsResult> {
+        let failed = result?;
+        let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+        let keys = keys
+            .into_iter()
+            .filter(|key| !failed_keys.contains(key.as_str()))
+            .collect();
+        Ok(CompleteSyncObjectsResult {
+            dst_key,
+            keys,
+            failed,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteFindCleanupObjectsResult {
+    pub object_key: ObjectKey,
+    pub objects: Vec<CleanupObject>,
+}
+
+impl CompleteFindCleanupObjectsResult {
+    pub fn new(
+        objects: Result<Vec<CleanupObject>>,
+        object_key: ObjectKey,
+    ) -> Result<CompleteFindCleanupObjectsResult> {
+        let objects = objects?;
+        Ok(CompleteFindCleanupObjectsResult {
+            object_key,
+            objects,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCheckObjectLockResult {
+    pub bucket: String,
+    pub keys: Vec<String>,
+    // subset of the keys that cannot be deleted
+    pub locked: Vec<LockedObject>,
+}
+
+impl CompleteCheckObjectLockResult {
+    pub fn new(
+        locked: Result<Vec<LockedObject>>,
+        bucket: String,
+        keys: Vec<String>,
+    ) -> Result<CompleteCheckObjectLockResult> {
+        let locked = locked?;
+        Ok(CompleteCheckObjectLockResult {
+            bucket,
+            keys,
+            locked,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteDeleteObjectsResult {
+    pub bucket: String,
+    // keys of the objects deleted successfully
+    pub keys: Vec<String>,
+    pub failed: Vec<DeleteFailure>,
+}
+
+impl CompleteDeleteObjectsResult {
+    pub fn new(
+        result: Result<Vec<DeleteFailure>>,
+        bucket: String,
+        keys: Vec<String>,
+    ) -> Result<CompleteDeleteObjectsResult> {
+        let failed = result?;
+        let failed_keys: HashSet<&str> = failed.iter().map(|f| f.key.as_str()).collect();
+        let keys = keys
+            .into_iter()
+            .filter(|key| !failed_keys.contains(key.as_str()))
+            .collect();
+        Ok(CompleteDeleteObjectsResult {
+            bucket,
+            keys,
+            failed,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteOpenLocationResult {
+    pub location: S3Location,
+    pub object_items_list: Vec<(ObjectKey, Vec<ObjectItem>)>,
+    pub file_name: Option<String>,
+}
+
+impl CompleteOpenLocationResult {
+    pub fn new(
+        object_items_list: Result<Vec<(ObjectKey, Vec<ObjectItem>)>>,
+        location: S3Location,
+    ) -> Result<CompleteOpenLocationResult> {
+        let object_items_list = object_items_list?;
+        // if the object list of the last path is not loaded, the location points to a file
+        let paths = location.key_paths();
+        let loaded_depth = object_items_list
+            .last()
+            .map(|(key, _)| key.object_path.len())
+            .unwrap_or_default();
+        let file_name = if loaded_depth < paths.len() {
+            paths.last().cloned()
+        } else {
+            None
+        };
+        Ok(CompleteOpenLocationResult {
+            location,
+            object_items_list,
+            file_name,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteLoadInventoryResult {
+    pub bucket: String,
+    pub object_items_list: Vec<(ObjectKey, Vec<ObjectItem>)>,
+    pub count: usize,
+}
+
+impl CompleteLoadInventoryResult {
+    pub fn new(result: Result<InventoryObjects>) -> Result<CompleteLoadInventoryResult> {
+        let InventoryObjects {
+            bucket,
+            object_items_list,
+            count,
+        } = result?;
+        Ok(CompleteLoadInventoryResult {
+            bucket,
+            object_items_list,
+            count,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteAnalyzeAccessLogsResult {
+    pub bucket: String,
+    pub prefix: String,
+    pub summary: AccessLogSummary,
+}
+
+impl CompleteAnalyzeAccessLogsResult {
+    pub fn new(
+        summary: Result<AccessLogSummary>,
+        bucket: String,
+        prefix: String,
+    ) -> Result<CompleteAnalyzeAccessLogsResult> {
+        let summary = summary?;
+        Ok(CompleteAnalyzeAccessLogsResult {
+            bucket,
+            prefix,
+            summary,
+        })
     }
 }
 
@@ -175,6 +1034,8 @@ pub struct CompletePreviewObjectResult {
     pub file_detail: FileDetail,
     pub file_version_id: Option<String>,
     pub path: PathBuf,
+    // only the beginning of the object is loaded
+    pub ranged: bool,
 }
 
 impl CompletePreviewObjectResult {
@@ -183,6 +1044,7 @@ impl CompletePreviewObjectResult {
         file_detail: FileDetail,
         file_version_id: Option<String>,
         path: PathBuf,
+        ranged: bool,
     ) -> Result<CompletePreviewObjectResult> {
         let obj = obj?;
         Ok(CompletePreviewObjectResult {
@@ -190,6 +1052,33 @@ impl CompletePreviewObjectResult {
             file_detail,
             file_version_id,
             path,
+            ranged,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompleteCompareVersionPreviewResult {
+    pub obj: RawObject,
+    pub latest_obj: RawObject,
+    pub file_detail: FileDetail,
+    pub file_version_id: String,
+}
+
+impl CompleteCompareVersionPreviewResult {
+    pub fn new(
+        obj: Result<RawObject>,
+        latest_obj: Result<RawObject>,
+        file_detail: FileDetail,
+        file_version_id: String,
+    ) -> Result<CompleteCompareVersionPreviewResult> {
+        let obj = obj?;
+        let latest_obj = latest_obj?;
+        Ok(CompleteCompareVersionPreviewResult {
+            obj,
+            latest_obj,
+            file_detail,
+            file_version_id,
         })
     }
 }
@@ -219,6 +1108,48 @@ impl Receiver {
     pub fn recv(&self) -> AppEventType {
         self.rx.recv().unwrap()
     }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<AppEventType> {
+        match self.rx.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => panic!("event channel disconnected"),
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<AppEventType> {
+        self.rx.try_recv().ok()
+    }
+}
+
+// Held by the input reader thread while it waits for the terminal input
+static INPUT_READER: Mutex<()> = Mutex::new(());
+static INPUT_READER_PAUSED: AtomicBool = AtomicBool::new(false);
+
+const INPUT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Stops reading the terminal input while f is called,
+// so that f can read the response of a query written to the terminal by itself
+pub fn with_input_reader_paused<T>(f: impl FnOnce() -> T) -> T {
+    INPUT_READER_PAUSED.store(true, Ordering::SeqCst);
+    let guard = INPUT_READER.lock().unwrap_or_else(|e| e.into_inner());
+    let ret = f();
+    drop(guard);
+    INPUT_READER_PAUSED.store(false, Ordering::SeqCst);
+    ret
+}
+
+fn read_input_event() -> Option<std::io::Result<ratatui::crossterm::event::Event>> {
+    if INPUT_READER_PAUSED.load(Ordering::SeqCst) {
+        thread::sleep(INPUT_POLL_TIMEOUT);
+        return None;
+    }
+    let _guard = INPUT_READER.lock().unwrap_or_else(|e| e.into_inner());
+    match ratatui::crossterm::event::poll(INPUT_POLL_TIMEOUT) {
+        Ok(true) => Some(ratatui::crossterm::event::read()),
+        Ok(false) => None,
+        Err(e) => Some(Err(e)),
+    }
 }
 
 pub fn new() -> (Sender, Receiver) {
@@ -228,8 +1159,8 @@ pub fn new() -> (Sender, Receiver) {
 
     let event_tx = tx.clone();
     thread::spawn(move || loop {
-        match ratatui::crossterm::event::read() {
-            Ok(e) => match e {
+        match read_input_event() {
+            Some(Ok(e)) => match e {
                 ratatui::crossterm::event::Event::Key(key) => {
                     event_tx.send(AppEventType::Key(key));
                 }
@@ -238,10 +1169,11 @@ pub fn new() -> (Sender, Receiver) {
                 }
                 _ => {}
             },
-            Err(e) => {
+            Some(Err(e)) => {
                 let e = AppError::new("Failed to read event", e);
                 event_tx.send(AppEventType::NotifyError(e));
             }
+            None => {}
         }
     });
 
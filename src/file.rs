@@ -1,9 +1,11 @@
 use arboard::Clipboard;
-use chrono::Local;
+use chrono::{DateTime, Datelike, Local, Timelike};
+use flate2::{write::GzEncoder, Compression};
+use md5::{Digest, Md5};
 use std::{
     fs::{File, OpenOptions},
-    io::{BufWriter, Write},
-    path::Path,
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
 };
 
 use crate::error::{AppError, Result};
@@ -20,6 +22,198 @@ pub fn save_binary<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
     Ok(())
 }
 
+// The path to download the object to before it is moved to the path, e.g. "dir/file.txt" -> "dir/.file.txt.1.download"
+pub fn download_temp_path<P: AsRef<Path>>(path: P, id: usize) -> PathBuf {
+    let path = path.as_ref();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.download", file_name, id))
+}
+
+// The file removed when dropped unless it is kept,
+// so that a partially downloaded file is not left if the download fails or is cancelled
+#[derive(Debug)]
+pub struct TempFile(Option<PathBuf>);
+
+impl TempFile {
+    pub fn new(path: PathBuf) -> TempFile {
+        TempFile(Some(path))
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.as_deref().unwrap()
+    }
+
+    pub fn keep(mut self) -> PathBuf {
+        self.0.take().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+// Returns the path with a " (n)" suffix added to the file name if the file already exists,
+// e.g. "file.txt" -> "file (1).txt", "archive.tar.gz" -> "archive (1).tar.gz"
+pub fn unique_file_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return path.to_path_buf();
+    };
+    (1..)
+        .map(|n| path.with_file_name(numbered_file_name(&name, n)))
+        .find(|p| !p.exists())
+        .unwrap()
+}
+
+fn numbered_file_name(name: &str, n: usize) -> String {
+    let ext_index = if name.ends_with(".tar.gz") {
+        Some(name.len() - ".tar.gz".len())
+    } else {
+        // ignore the leading dot of hidden files such as ".bashrc"
+        name.rfind('.').filter(|i| *i > 0)
+    };
+    match ext_index {
+        Some(i) => format!("{} ({}){}", &name[..i], n, &name[i..]),
+        None => format!("{} ({})", name, n),
+    }
+}
+
+// Returns the size and the modified time if the file exists
+pub fn local_file_info<P: AsRef<Path>>(path: P) -> Option<(usize, DateTime<Local>)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let modified = metadata.modified().ok()?;
+    Some((metadata.len() as usize, modified.into()))
+}
+
+pub fn file_md5_hex<P: AsRef<Path>>(path: P) -> Result<String> {
+    let mut f = File::open(path).map_err(|e| AppError::new("Failed to open file", e))?;
+    let mut hasher = Md5::new();
+    std::io::copy(&mut f, &mut hasher).map_err(|e| AppError::new("Failed to read file", e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub enum ArchiveWriter {
+    TarGz(tar::Builder<GzEncoder<BufWriter<File>>>),
+    Zip(zip::ZipWriter<BufWriter<File>>),
+}
+
+impl ArchiveWriter {
+    pub fn create_tar_gz<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let writer = create_archive_file(path)?;
+        let encoder = GzEncoder::new(writer, Compression::default());
+        Ok(Self::TarGz(tar::Builder::new(encoder)))
+    }
+
+    pub fn create_zip<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let writer = create_archive_file(path)?;
+        Ok(Self::Zip(zip::ZipWriter::new(writer)))
+    }
+
+    pub fn append(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+        last_modified: DateTime<Local>,
+    ) -> Result<()> {
+        match self {
+            Self::TarGz(builder) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(bytes.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(last_modified.timestamp().max(0) as u64);
+                builder
+                    .append_data(&mut header, name, bytes)
+                    .map_err(|e| AppError::new("Failed to write archive", e))
+            }
+            Self::Zip(writer) => {
+                let mut options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .unix_permissions(0o644)
+                    .large_file(bytes.len() as u64 >= u32::MAX as u64);
+                if let Ok(dt) = zip::DateTime::from_date_and_time(
+                    last_modified.year() as u16,
+                    last_modified.month() as u8,
+                    last_modified.day() as u8,
+                    last_modified.hour() as u8,
+                    last_modified.minute() as u8,
+                    last_modified.second() as u8,
+                ) {
+                    options = options.last_modified_time(dt);
+                }
+                writer
+                    .start_file(name, options)
+                    .map_err(|e| AppError::new("Failed to write archive", e))?;
+                writer
+                    .write_all(bytes)
+                    .map_err(|e| AppError::new("Failed to write archive", e))
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        let writer = match self {
+            Self::TarGz(builder) => builder
+                .into_inner()
+                .and_then(|encoder| encoder.finish())
+                .map_err(|e| AppError::new("Failed to write archive", e))?,
+            Self::Zip(writer) => writer
+                .finish()
+                .map_err(|e| AppError::new("Failed to write archive", e))?,
+        };
+        writer
+            .into_inner()
+            .map_err(|e| AppError::new("Failed to write archive", e.into_error()))
+            .map(|_| ())
+    }
+}
+
+fn create_archive_file<P: AsRef<Path>>(path: P) -> Result<BufWriter<File>> {
+    create_dirs(&path)?;
+    let f = File::create(&path).map_err(|e| AppError::new("Failed to create file", e))?;
+    Ok(BufWriter::new(f))
+}
+
+// The magic numbers are in the first bytes of the file
+const CONTENT_TYPE_DETECTION_BYTES: u64 = 8192;
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+// Detects the Content-Type to upload the file with.
+// The file is read only partially, and the extension is used if the bytes are not recognized.
+pub fn detect_content_type<P: AsRef<Path>>(path: P) -> String {
+    let path = path.as_ref();
+    let mut header = Vec::new();
+    if let Ok(f) = File::open(path) {
+        let _ = f
+            .take(CONTENT_TYPE_DETECTION_BYTES)
+            .read_to_end(&mut header);
+    }
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    content_type_of(name, &header)
+}
+
+fn content_type_of(name: &str, header: &[u8]) -> String {
+    if let Some(t) = infer::get(header) {
+        return t.mime_type().to_string();
+    }
+    mime_guess::from_path(name)
+        .first_raw()
+        .unwrap_or(DEFAULT_CONTENT_TYPE)
+        .to_string()
+}
+
 pub fn save_error_log<P: AsRef<Path>>(path: P, e: &AppError) -> Result<()> {
     create_dirs(&path)?;
 
@@ -43,7 +237,7 @@ pub fn open_or_create_append_file<P: AsRef<Path>>(path: P) -> std::io::Result<Fi
     OpenOptions::new().create(true).append(true).open(path)
 }
 
-fn create_dirs<P: AsRef<Path>>(path: P) -> Result<()> {
+pub fn create_dirs<P: AsRef<Path>>(path: P) -> Result<()> {
     match path.as_ref().parent() {
         Some(path) => std::fs::create_dir_all(path)
             .map_err(|e| AppError::new("Failed to create directories", e)),
@@ -56,3 +250,108 @@ pub fn copy_to_clipboard(value: String) -> Result<()> {
         .and_then(|mut c| c.set_text(value))
         .map_err(|e| AppError::new("Failed to copy to clipboard", e))
 }
+
+pub fn read_clipboard() -> Result<String> {
+    Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .map_err(|e| AppError::new("Failed to read clipboard", e))
+}
+
+// Images are stored as RGBA pixels in the clipboard, so they are encoded as PNG
+pub fn read_clipboard_image() -> Result<Vec<u8>> {
+    let image = Clipboard::new()
+        .and_then(|mut c| c.get_image())
+        .map_err(|e| AppError::new("Failed to read image from clipboard", e))?;
+    encode_png(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+    )
+}
+
+fn encode_png(width: u32, height: u32, rgba: Vec<u8>) -> Result<Vec<u8>> {
+    let image = image::RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| AppError::msg("Invalid image data in clipboard"))?;
+    let mut bytes = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| AppError::new("Failed to encode image", e))?;
+    Ok(bytes)
+}
+
+pub fn clipboard_image_file_name(now: DateTime<Local>) -> String {
+    format!("screenshot-{}.png", now.format("%Y%m%d-%H%M%S"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_encode_png() {
+        let bytes = encode_png(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        assert!(bytes.starts_with(b"\x89PNG"));
+
+        assert!(encode_png(2, 2, vec![0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_clipboard_image_file_name() {
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(
+            clipboard_image_file_name(now),
+            "screenshot-20240102-030405.png"
+        );
+    }
+
+    #[rstest]
+    #[case("file.txt", 1, "file (1).txt")]
+    #[case("file", 2, "file (2)")]
+    #[case("archive.tar.gz", 1, "archive (1).tar.gz")]
+    #[case("my.report.pdf", 1, "my.report (1).pdf")]
+    #[case(".bashrc", 1, ".bashrc (1)")]
+    fn test_numbered_file_name(#[case] name: &str, #[case] n: usize, #[case] expected: &str) {
+        assert_eq!(numbered_file_name(name, n), expected);
+    }
+
+    #[rstest]
+    #[case("image.png", b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR", "image/png")]
+    #[case("image.txt", b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR", "image/png")]
+    #[case("data.json", b"{\"a\": 1}", "application/json")]
+    #[case("style.css", b"body {}", "text/css")]
+    #[case("README", b"hello", "application/octet-stream")]
+    #[case("empty.txt", b"", "text/plain")]
+    fn test_content_type_of(#[case] name: &str, #[case] header: &[u8], #[case] expected: &str) {
+        assert_eq!(content_type_of(name, header), expected);
+    }
+
+    #[test]
+    fn test_download_temp_path() {
+        assert_eq!(
+            download_temp_path("dir/file.txt", 3),
+            PathBuf::from("dir/.file.txt.3.download")
+        );
+    }
+
+    #[test]
+    fn test_temp_file() {
+        let dir = std::env::temp_dir();
+        let removed = dir.join(format!("stu-temp-file-test-{}-a", std::process::id()));
+        let kept = dir.join(format!("stu-temp-file-test-{}-b", std::process::id()));
+        std::fs::write(&removed, b"a").unwrap();
+        std::fs::write(&kept, b"b").unwrap();
+
+        drop(TempFile::new(removed.clone()));
+        assert!(!removed.exists());
+
+        assert_eq!(TempFile::new(kept.clone()).keep(), kept);
+        assert!(kept.exists());
+        std::fs::remove_file(kept).unwrap();
+    }
+}
@@ -1,9 +1,64 @@
+use std::{borrow::Cow, time::Duration};
+
 use chrono::{DateTime, Local};
 
+use crate::util::{is_valid_percent_encoding, percent_decode};
+
 pub fn format_size_byte(size_byte: usize) -> String {
     humansize::format_size_i(size_byte, humansize::BINARY)
 }
 
+// e.g. 12400 -> "12,400"
+pub fn format_count(n: usize) -> String {
+    let s = n.to_string();
+    let mut ret = String::with_capacity(s.len() + s.len() / 3);
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 && (s.len() - i) % 3 == 0 {
+            ret.push(',');
+        }
+        ret.push(c);
+    }
+    ret
+}
+
+// e.g. 3s, 1m 05s, 2h 03m 04s
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+// e.g. 45m, 3h 05m, 12d 03h (less than a minute is shown as 1m)
+pub fn format_remaining(remaining: Duration) -> String {
+    let mins = remaining.as_secs().div_ceil(60).max(1);
+    let (d, h, m) = (mins / (24 * 60), mins / 60 % 24, mins % 60);
+    if d > 0 {
+        format!("{}d {:02}h", d, h)
+    } else if h > 0 {
+        format!("{}h {:02}m", h, m)
+    } else {
+        format!("{}m", m)
+    }
+}
+
+// Decodes percent-encoded characters and '+' (as a space) in a key name for display.
+// The name is returned as is if it is not a valid encoded UTF-8 string.
+pub fn decode_key_name(name: &str) -> Cow<'_, str> {
+    if !name.contains(['%', '+']) || !is_valid_percent_encoding(name) {
+        return Cow::Borrowed(name);
+    }
+    match String::from_utf8(percent_decode(name, true)) {
+        Ok(s) => Cow::Owned(s),
+        Err(_) => Cow::Borrowed(name),
+    }
+}
+
 #[cfg(not(feature = "imggen"))]
 pub fn format_version(version: &str) -> &str {
     version
@@ -23,3 +78,25 @@ pub fn format_datetime(datetime: &DateTime<Local>, format_str: &str) -> String {
 pub fn format_datetime(_datetime: &DateTime<Local>, _: &str) -> String {
     String::from("2024-01-02 13:04:05")
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("a.txt", "a.txt")]
+    #[case("a%20b.txt", "a b.txt")]
+    #[case("a+b%2B.txt", "a b+.txt")]
+    #[case("%E3%81%82.txt", "\u{3042}.txt")]
+    #[case("100%.txt", "100%.txt")]
+    #[case("a%2.txt", "a%2.txt")]
+    #[case("a%+1.txt", "a%+1.txt")]
+    #[case("a%-1%20.txt", "a%-1%20.txt")]
+    #[case("a%zz.txt", "a%zz.txt")]
+    #[case("a%FF.txt", "a%FF.txt")]
+    fn test_decode_key_name(#[case] name: &str, #[case] expected: &str) {
+        assert_eq!(decode_key_name(name), expected);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    config::UploadConfig,
+    error::{AppError, Result},
+    location::S3Location,
+    multipart::{stream_part_size, upload_stream},
+    storage::Storage,
+};
+
+// Uploads everything read from stdin to the location and returns the uploaded size
+pub async fn put_stdin(
+    client: &dyn Storage,
+    location: &S3Location,
+    content_type: Option<&str>,
+    config: &UploadConfig,
+) -> Result<u64> {
+    if location.key.is_empty() || location.key.ends_with('/') {
+        return Err(AppError::msg(
+            "The object key to upload to must be specified",
+        ));
+    }
+    upload_stream(
+        client,
+        tokio::io::stdin(),
+        &location.bucket,
+        &location.key,
+        content_type,
+        stream_part_size(config.part_size_byte()),
+        config.max_retries,
+    )
+    .await
+}
@@ -0,0 +1,466 @@
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "inventory")]
+use std::io::Read;
+
+#[cfg(feature = "inventory")]
+use bytes::Bytes;
+#[cfg(feature = "inventory")]
+use chrono::TimeZone;
+use chrono::{DateTime, Local};
+#[cfg(feature = "inventory")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "inventory")]
+use parquet::{
+    file::reader::{FileReader, SerializedFileReader},
+    record::Field,
+};
+use serde::Deserialize;
+
+#[cfg(feature = "inventory")]
+use crate::util::percent_decode;
+use crate::{
+    error::{AppError, Result},
+    event::{AppEventType, Sender},
+    location::S3Location,
+    object::{BucketItem, ObjectItem, ObjectKey},
+    storage::Storage,
+};
+
+const DELIMITER: &str = "/";
+
+// manifest.json written by S3 Inventory next to the data files
+// https://docs.aws.amazon.com/AmazonS3/latest/userguide/storage-inventory-location.html
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryManifest {
+    pub source_bucket: String,
+    pub file_format: String,
+    // only for CSV, e.g. `Bucket, Key, Size, LastModifiedDate, ETag`
+    #[serde(default)]
+    pub file_schema: String,
+    pub files: Vec<InventoryManifestFile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InventoryManifestFile {
+    pub key: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryFormat {
+    Csv,
+    Parquet,
+}
+
+impl InventoryManifest {
+    pub fn format(&self) -> Result<InventoryFormat> {
+        match self.file_format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(InventoryFormat::Csv),
+            "parquet" => Ok(InventoryFormat::Parquet),
+            format => Err(AppError::msg(format!(
+                "Unsupported inventory format: {}",
+                format
+            ))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InventoryObjects {
+    pub bucket: String,
+    pub object_items_list: Vec<(ObjectKey, Vec<ObjectItem>)>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryEntry {
+    pub key: String,
+    pub size_byte: usize,
+    pub last_modified: DateTime<Local>,
+    pub e_tag: String,
+}
+
+pub async fn load_inventory(
+    client: &dyn Storage,
+    tx: &Sender,
+    location: &S3Location,
+    bucket_items: Vec<BucketItem>,
+) -> Result<InventoryObjects> {
+    let mut manifest_key = location.key.clone();
+    if location.is_dir() {
+        manifest_key.push_str("manifest.json");
+    }
+    let manifest = client
+        .download_object(
+            &location.bucket,
+            &manifest_key,
+            None,
+            None,
+            0,
+            Box::new(|_| {}),
+        )
+        .await?;
+    let manifest = parse_manifest(&manifest.bytes)?;
+    let format = manifest.format()?;
+
+    let Some(bucket) = bucket_items
+        .into_iter()
+        .find(|b| b.name == manifest.source_bucket)
+    else {
+        let msg = format!("Bucket '{}' not found", manifest.source_bucket);
+        return Err(AppError::msg(msg));
+    };
+
+    // the data files are in the same bucket as the manifest
+    let mut entries = Vec::new();
+    for (i, file) in manifest.files.iter().enumerate() {
+        let msg = format!(
+            "Loading inventory ({} out of {})",
+            i + 1,
+            manifest.files.len()
+        );
+        tx.send(AppEventType::NotifyInfo(msg));
+
+        let obj = client
+            .download_object(
+                &location.bucket,
+                &file.key,
+                None,
+                None,
+                file.size,
+                Box::new(|_| {}),
+            )
+            .await?;
+        entries.extend(parse_inventory_file(
+            obj.bytes,
+            format,
+            &manifest.file_schema,
+        )?);
+    }
+
+    let count = entries.len();
+    let object_items_list = build_object_items(&bucket, entries);
+    Ok(InventoryObjects {
+        bucket: bucket.name,
+        object_items_list,
+        count,
+    })
+}
+
+pub fn parse_manifest(bytes: &[u8]) -> Result<InventoryManifest> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| AppError::new("Failed to parse inventory manifest", e))
+}
+
+#[cfg(feature = "inventory")]
+fn parse_inventory_file(
+    bytes: Vec<u8>,
+    format: InventoryFormat,
+    schema: &str,
+) -> Result<Vec<InventoryEntry>> {
+    match format {
+        InventoryFormat::Csv => parse_csv(&bytes, schema),
+        InventoryFormat::Parquet => parse_parquet(bytes),
+    }
+}
+
+#[cfg(not(feature = "inventory"))]
+fn parse_inventory_file(
+    _bytes: Vec<u8>,
+    _format: InventoryFormat,
+    _schema: &str,
+) -> Result<Vec<InventoryEntry>> {
+    Err(AppError::msg(
+        "Inventory is not available (built without the inventory feature)",
+    ))
+}
+
+#[cfg(feature = "inventory")]
+// CSV files are gzipped and have no header row, the columns are described by `fileSchema`
+fn parse_csv(bytes: &[u8], schema: &str) -> Result<Vec<InventoryEntry>> {
+    let mut data = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut data)
+        .map_err(|e| AppError::new("Failed to decompress inventory file", e))?;
+
+    let columns: Vec<&str> = schema.split(',').map(str::trim).collect();
+    let column = |name: &str| columns.iter().position(|c| *c == name);
+    let Some(key_col) = column("Key") else {
+        return Err(AppError::msg("Inventory schema does not contain Key"));
+    };
+    let size_col = column("Size");
+    let last_modified_col = column("LastModifiedDate");
+    let e_tag_col = column("ETag");
+    let is_latest_col = column("IsLatest");
+    let is_delete_marker_col = column("IsDeleteMarker");
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(data.as_slice());
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| AppError::new("Failed to read inventory file", e))?;
+        let get = |col: Option<usize>| col.and_then(|i| record.get(i)).unwrap_or_default();
+
+        // the inventory may include all versions of the objects
+        if get(is_latest_col) == "false" || get(is_delete_marker_col) == "true" {
+            continue;
+        }
+        entries.push(InventoryEntry {
+            // keys are URL-encoded in CSV
+            key: String::from_utf8_lossy(&percent_decode(get(Some(key_col)), true)).into_owned(),
+            size_byte: get(size_col).parse().unwrap_or_default(),
+            last_modified: parse_last_modified(get(last_modified_col)),
+            e_tag: get(e_tag_col).to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(feature = "inventory")]
+fn parse_parquet(bytes: Vec<u8>) -> Result<Vec<InventoryEntry>> {
+    let reader = SerializedFileReader::new(Bytes::from(bytes))
+        .map_err(|e| AppError::new("Failed to read inventory file", e))?;
+    let rows = reader
+        .get_row_iter(None)
+        .map_err(|e| AppError::new("Failed to read inventory file", e))?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let row = row.map_err(|e| AppError::new("Failed to read inventory file", e))?;
+        let mut entry = InventoryEntry {
+            key: String::new(),
+            size_byte: 0,
+            last_modified: epoch(),
+            e_tag: String::new(),
+        };
+        let mut skip = false;
+        for (name, field) in row.get_column_iter() {
+            match (name.as_str(), field) {
+                ("key", Field::Str(s)) => entry.key = s.clone(),
+                ("size", Field::Long(n)) => entry.size_byte = *n as usize,
+                ("last_modified_date", Field::TimestampMillis(ms)) => {
+                    entry.last_modified = Local.timestamp_millis_opt(*ms).unwrap();
+                }
+                ("e_tag", Field::Str(s)) => entry.e_tag = s.clone(),
+                ("is_latest", Field::Bool(false)) | ("is_delete_marker", Field::Bool(true)) => {
+                    skip = true;
+                }
+                _ => {}
+            }
+        }
+        if !skip && !entry.key.is_empty() {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+// Builds the object lists of all the prefixes, in the same order as ListObjectsV2 returns
+pub fn build_object_items(
+    bucket: &BucketItem,
+    mut entries: Vec<InventoryEntry>,
+) -> Vec<(ObjectKey, Vec<ObjectItem>)> {
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let mut dirs: BTreeMap<Vec<String>, BTreeSet<String>> = BTreeMap::new();
+    let mut files: BTreeMap<Vec<String>, Vec<ObjectItem>> = BTreeMap::new();
+    dirs.insert(Vec::new(), BTreeSet::new());
+
+    for entry in entries {
+        let mut paths: Vec<String> = entry.key.split(DELIMITER).map(String::from).collect();
+        let name = paths.pop().unwrap();
+        for i in 0..paths.len() {
+            dirs.entry(paths[..i].to_vec())
+                .or_default()
+                .insert(paths[i].clone());
+        }
+        dirs.entry(paths.clone()).or_default();
+
+        // folder placeholder objects are shown as folders only
+        if name.is_empty() {
+            continue;
+        }
+        let item = ObjectItem::File {
+            name,
+            size_byte: entry.size_byte,
+            last_modified: entry.last_modified,
+            s3_uri: format!("{}{}", bucket.s3_uri, entry.key),
+            arn: format!("{}/{}", bucket.arn, entry.key),
+            virtual_hosted_url: format!("{}{}", bucket.virtual_hosted_url, entry.key),
+            path_style_url: format!("{}{}", bucket.path_style_url, entry.key),
+            e_tag: entry.e_tag.trim_matches('"').to_string(),
+            key: entry.key,
+        };
+        files.entry(paths).or_default().push(item);
+    }
+
+    dirs.into_iter()
+        .map(|(object_path, names)| {
+            let mut items: Vec<ObjectItem> = names
+                .into_iter()
+                .map(|name| {
+                    let mut paths = object_path.clone();
+                    paths.push(name.clone());
+                    let key = format!("{}{}", paths.join(DELIMITER), DELIMITER);
+                    ObjectItem::Dir {
+                        name,
+                        s3_uri: format!("{}{}", bucket.s3_uri, key),
+                        virtual_hosted_url: format!("{}{}", bucket.virtual_hosted_url, key),
+                        path_style_url: format!("{}{}", bucket.path_style_url, key),
+                        key,
+                    }
+                })
+                .collect();
+            items.extend(files.remove(&object_path).unwrap_or_default());
+
+            let object_key = ObjectKey {
+                bucket_name: bucket.name.clone(),
+                object_path,
+            };
+            (object_key, items)
+        })
+        .collect()
+}
+
+#[cfg(feature = "inventory")]
+fn parse_last_modified(s: &str) -> DateTime<Local> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Local))
+        .unwrap_or_else(|_| epoch())
+}
+
+#[cfg(feature = "inventory")]
+fn epoch() -> DateTime<Local> {
+    Local.timestamp_opt(0, 0).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "inventory")]
+    use std::io::Write;
+
+    #[cfg(feature = "inventory")]
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[cfg(feature = "inventory")]
+    fn gzip(s: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(s.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn bucket_item() -> BucketItem {
+        BucketItem {
+            name: "bucket".to_string(),
+            s3_uri: "s3://bucket/".to_string(),
+            arn: "arn:aws:s3:::bucket".to_string(),
+            virtual_hosted_url: "https://bucket.s3.us-east-1.amazonaws.com/".to_string(),
+            path_style_url: "https://s3.us-east-1.amazonaws.com/bucket/".to_string(),
+        }
+    }
+
+    fn entry(key: &str) -> InventoryEntry {
+        InventoryEntry {
+            key: key.to_string(),
+            size_byte: 1,
+            last_modified: DateTime::default(),
+            e_tag: String::new(),
+        }
+    }
+
+    fn names(items: &[ObjectItem]) -> Vec<&str> {
+        items.iter().map(|item| item.name()).collect()
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let json = r#"{
+            "sourceBucket": "bucket",
+            "destinationBucket": "arn:aws:s3:::inventory",
+            "version": "2016-11-30",
+            "creationTimestamp": "1514944800000",
+            "fileFormat": "CSV",
+            "fileSchema": "Bucket, Key, Size, LastModifiedDate, ETag",
+            "files": [
+                {
+                    "key": "bucket/config/data/a.csv.gz",
+                    "size": 2147483647,
+                    "MD5checksum": "f11166069f1990abeb9c97ace9cdfabc"
+                }
+            ]
+        }"#;
+        let manifest = parse_manifest(json.as_bytes()).unwrap();
+        assert_eq!(manifest.source_bucket, "bucket");
+        assert_eq!(manifest.format().unwrap(), InventoryFormat::Csv);
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].key, "bucket/config/data/a.csv.gz");
+    }
+
+    #[cfg(feature = "inventory")]
+    #[test]
+    fn test_parse_csv() {
+        let schema =
+            "Bucket, Key, VersionId, IsLatest, IsDeleteMarker, Size, LastModifiedDate, ETag";
+        let csv = [
+            r#""bucket","a/b+c.txt","v2","true","false","10","2024-01-02T03:04:05.000Z","abc""#,
+            r#""bucket","a/b+c.txt","v1","false","false","8","2024-01-01T03:04:05.000Z","def""#,
+            r#""bucket","d%2Be.txt","v3","true","true","","","""#,
+        ]
+        .join("\n");
+        let entries = parse_inventory_file(gzip(&csv), InventoryFormat::Csv, schema).unwrap();
+        assert_eq!(
+            entries,
+            vec![InventoryEntry {
+                key: "a/b c.txt".to_string(),
+                size_byte: 10,
+                last_modified: parse_last_modified("2024-01-02T03:04:05.000Z"),
+                e_tag: "abc".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_build_object_items() {
+        let entries = vec![
+            entry("z.txt"),
+            entry("a/b/c.txt"),
+            entry("a/x.txt"),
+            entry("d/"),
+            entry("a.txt"),
+        ];
+        let actual = build_object_items(&bucket_item(), entries);
+
+        let keys: Vec<String> = actual
+            .iter()
+            .map(|(key, _)| key.joined_object_path(false))
+            .collect();
+        assert_eq!(keys, vec!["", "a/", "a/b/", "d/"]);
+
+        assert_eq!(names(&actual[0].1), vec!["a", "d", "a.txt", "z.txt"]);
+        assert_eq!(names(&actual[1].1), vec!["b", "x.txt"]);
+        assert_eq!(names(&actual[2].1), vec!["c.txt"]);
+        assert!(actual[3].1.is_empty());
+
+        let ObjectItem::File {
+            key,
+            s3_uri,
+            arn,
+            virtual_hosted_url,
+            ..
+        } = &actual[2].1[0]
+        else {
+            panic!("not a file");
+        };
+        assert_eq!(key, "a/b/c.txt");
+        assert_eq!(s3_uri, "s3://bucket/a/b/c.txt");
+        assert_eq!(arn, "arn:aws:s3:::bucket/a/b/c.txt");
+        assert_eq!(
+            virtual_hosted_url,
+            "https://bucket.s3.us-east-1.amazonaws.com/a/b/c.txt"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use chrono::{DateTime, Local};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
+
+use crate::{download_list::DownloadObjectList, object::DownloadObjectsOptions};
+
+pub type JobId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed(String),
+    Cancelled,
+}
+
+// What the job transfers, kept so that the job can be run again by retrying
+#[derive(Debug, Clone)]
+pub enum JobSpec {
+    DownloadObjects {
+        bucket: String,
+        key: String,
+        objs: Arc<DownloadObjectList>,
+        options: DownloadObjectsOptions,
+        path: PathBuf,
+    },
+    DownloadObject {
+        bucket: String,
+        key: String,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        path: PathBuf,
+    },
+}
+
+impl JobSpec {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobSpec::DownloadObjects { .. } | JobSpec::DownloadObject { .. } => "Download",
+        }
+    }
+
+    pub fn source(&self) -> String {
+        match self {
+            JobSpec::DownloadObjects { bucket, key, .. } => format!("s3://{}/{}", bucket, key),
+            JobSpec::DownloadObject {
+                bucket,
+                key,
+                version_id,
+                ..
+            } => match version_id {
+                Some(version_id) => format!("s3://{}/{} ({})", bucket, key, version_id),
+                None => format!("s3://{}/{}", bucket, key),
+            },
+        }
+    }
+
+    pub fn destination(&self) -> String {
+        match self {
+            JobSpec::DownloadObjects { path, .. } | JobSpec::DownloadObject { path, .. } => {
+                path.to_string_lossy().into()
+            }
+        }
+    }
+
+    fn total(&self) -> JobProgress {
+        match self {
+            JobSpec::DownloadObjects { objs, .. } => JobProgress {
+                count: objs.len(),
+                size_byte: objs.total_size_byte(),
+            },
+            JobSpec::DownloadObject { size_byte, .. } => JobProgress {
+                count: 1,
+                size_byte: *size_byte,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobProgress {
+    pub count: usize,
+    pub size_byte: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: JobId,
+    pub spec: JobSpec,
+    pub status: JobStatus,
+    pub done: JobProgress,
+    pub total: JobProgress,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Job {
+    // The ratio of the transferred bytes, or of the objects if all of them are empty
+    pub fn ratio(&self) -> f64 {
+        if self.total.size_byte > 0 {
+            self.done.size_byte as f64 / self.total.size_byte as f64
+        } else if self.total.count > 0 {
+            self.done.count as f64 / self.total.count as f64
+        } else {
+            1.0
+        }
+    }
+}
+
+// Transfers running in the background, and the finished ones until the app exits.
+// The jobs are not persisted.
+#[derive(Debug, Default)]
+pub struct JobQueue {
+    // the most recently started first
+    jobs: Vec<Job>,
+    handles: HashMap<JobId, AbortHandle>,
+    next_id: JobId,
+}
+
+impl JobQueue {
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn add(&mut self, spec: JobSpec, now: DateTime<Local>) -> JobId {
+        let id = self.next_id;
+        self.next_id += 1;
+        let job = Job {
+            id,
+            total: spec.total(),
+            spec,
+            status: JobStatus::Running,
+            done: JobProgress::default(),
+            updated_at: now,
+        };
+        self.jobs.insert(0, job);
+        id
+    }
+
+    pub fn is_running(&self, id: JobId) -> bool {
+        self.jobs
+            .iter()
+            .any(|j| j.id == id && j.status == JobStatus::Running)
+    }
+
+    pub fn set_handle(&mut self, id: JobId, handle: AbortHandle) {
+        self.handles.insert(id, handle);
+    }
+
+    // Returns false if the job is not running, the progress may arrive after it is cancelled
+    pub fn update_progress(&mut self, id: JobId, done: JobProgress, now: DateTime<Local>) -> bool {
+        match self.running_job_mut(id) {
+            Some(job) => {
+                job.done = done;
+                job.updated_at = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Returns the job only if it was running, so that the result of the cancelled job is ignored
+    pub fn finish(
+        &mut self,
+        id: JobId,
+        status: JobStatus,
+        now: DateTime<Local>,
+    ) -> Option<&mut Job> {
+        self.handles.remove(&id);
+        let job = self.running_job_mut(id)?;
+        job.status = status;
+        job.updated_at = now;
+        Some(job)
+    }
+
+    pub fn cancel(&mut self, id: JobId, now: DateTime<Local>) -> bool {
+        if let Some(handle) = self.handles.remove(&id) {
+            handle.abort();
+        }
+        self.finish(id, JobStatus::Cancelled, now).is_some()
+    }
+
+    // Returns the spec to run again if the job has failed or has been cancelled
+    pub fn retry(&mut self, id: JobId, now: DateTime<Local>) -> Option<JobSpec> {
+        let job = self.jobs.iter_mut().find(|j| j.id == id)?;
+        if !matches!(job.status, JobStatus::Failed(_) | JobStatus::Cancelled) {
+            return None;
+        }
+        job.status = JobStatus::Running;
+        job.total = job.spec.total();
+        job.done = JobProgress::default();
+        job.updated_at = now;
+        Some(job.spec.clone())
+    }
+
+    pub fn cancel_all(&mut self) {
+        for (_, handle) in self.handles.drain() {
+            handle.abort();
+        }
+    }
+
+    fn running_job_mut(&mut self, id: JobId) -> Option<&mut Job> {
+        self.jobs
+            .iter_mut()
+            .find(|j| j.id == id && j.status == JobStatus::Running)
+    }
+}
+
+impl Drop for JobQueue {
+    fn drop(&mut self) {
+        self.cancel_all();
+    }
+}
+
+// Aborts the task when dropped, so that the tasks spawned by a job are cancelled together with it
+#[derive(Debug)]
+pub struct AbortOnDrop<T>(pub JoinHandle<T>);
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::object::DownloadObjectInfo;
+
+    use super::*;
+
+    fn spec(sizes: &[usize]) -> JobSpec {
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let objs = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size_byte)| DownloadObjectInfo {
+                key: format!("dir/{}.txt", i),
+                size_byte: *size_byte,
+                last_modified: now,
+                e_tag: String::new(),
+            });
+        JobSpec::DownloadObjects {
+            bucket: "bucket".into(),
+            key: "dir/".into(),
+            objs: Arc::new(DownloadObjectList::from_objs(objs).unwrap()),
+            options: DownloadObjectsOptions::default(),
+            path: PathBuf::from("/tmp/dir"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_queue() {
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let mut queue = JobQueue::default();
+        let get = |queue: &JobQueue, id: JobId| queue.jobs().iter().find(|j| j.id == id).cloned();
+
+        let a = queue.add(spec(&[10, 30]), now);
+        let b = queue.add(spec(&[]), now);
+        assert_eq!(queue.jobs()[0].id, b);
+        assert!(queue.is_running(a));
+        assert_eq!(get(&queue, b).unwrap().ratio(), 1.0);
+
+        let done = JobProgress {
+            count: 1,
+            size_byte: 10,
+        };
+        assert!(queue.update_progress(a, done, now));
+        assert_eq!(get(&queue, a).unwrap().ratio(), 0.25);
+
+        // running jobs cannot be retried
+        assert!(queue.retry(a, now).is_none());
+
+        let handle = tokio::spawn(std::future::pending::<()>());
+        queue.set_handle(a, handle.abort_handle());
+        assert!(queue.cancel(a, now));
+        assert!(!queue.is_running(a));
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert_eq!(get(&queue, a).unwrap().status, JobStatus::Cancelled);
+        // the result after cancelled is ignored
+        assert!(!queue.update_progress(a, done, now));
+        assert!(queue.finish(a, JobStatus::Completed, now).is_none());
+
+        assert!(queue.retry(a, now).is_some());
+        let job = get(&queue, a).unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+        assert_eq!(job.done, JobProgress::default());
+        assert_eq!(job.total.size_byte, 40);
+
+        assert!(queue.finish(b, JobStatus::Completed, now).is_some());
+        assert!(queue.retry(b, now).is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// Upper limit of the replay count to avoid accidentally queuing a huge number of keys
+const MAX_REPLAY_COUNT: usize = 1000;
+
+#[derive(Debug, Default)]
+pub struct KeyMacro {
+    recording: Option<Vec<KeyEvent>>,
+    recorded: Vec<KeyEvent>,
+    count: Option<usize>,
+    queue: VecDeque<KeyEvent>,
+}
+
+impl KeyMacro {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    pub fn start_recording(&mut self) {
+        self.cancel_replay();
+        self.recording = Some(Vec::new());
+    }
+
+    // Returns the number of recorded keys
+    pub fn stop_recording(&mut self) -> usize {
+        if let Some(keys) = self.recording.take() {
+            self.recorded = keys;
+        }
+        self.recorded.len()
+    }
+
+    // Called for every key passed to the pages
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some(keys) = &mut self.recording {
+            keys.push(key);
+        }
+        self.count = match key.code {
+            KeyCode::Char(c) if key.modifiers == KeyModifiers::NONE && c.is_ascii_digit() => {
+                let n = c.to_digit(10).unwrap() as usize;
+                let count = self.count.unwrap_or(0) * 10 + n;
+                Some(count.min(MAX_REPLAY_COUNT))
+            }
+            _ => None,
+        };
+    }
+
+    // Queues the recorded keys as many times as the count typed just before.
+    // Returns false if there is no macro to replay.
+    pub fn replay(&mut self) -> bool {
+        let count = self.count.take().unwrap_or(1);
+        if self.is_recording() || self.recorded.is_empty() || count == 0 {
+            return false;
+        }
+        for _ in 0..count {
+            self.queue.extend(self.recorded.iter().cloned());
+        }
+        true
+    }
+
+    pub fn next_key(&mut self) -> Option<KeyEvent> {
+        self.queue.pop_front()
+    }
+
+    pub fn cancel_replay(&mut self) {
+        self.queue.clear();
+        self.count = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::from(KeyCode::Char(c))
+    }
+
+    fn replayed_keys(key_macro: &mut KeyMacro) -> Vec<KeyEvent> {
+        std::iter::from_fn(|| key_macro.next_key()).collect()
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut key_macro = KeyMacro::default();
+        assert!(!key_macro.replay());
+
+        key_macro.start_recording();
+        key_macro.record(key('j'));
+        key_macro.record(key('s'));
+        assert!(!key_macro.replay());
+        assert_eq!(key_macro.stop_recording(), 2);
+
+        assert!(key_macro.replay());
+        assert!(key_macro.is_replaying());
+        assert_eq!(replayed_keys(&mut key_macro), vec![key('j'), key('s')]);
+        assert!(!key_macro.is_replaying());
+    }
+
+    #[test]
+    fn test_replay_with_count() {
+        let mut key_macro = KeyMacro::default();
+        key_macro.start_recording();
+        key_macro.record(key('j'));
+        key_macro.stop_recording();
+
+        key_macro.record(key('1'));
+        key_macro.record(key('2'));
+        assert!(key_macro.replay());
+        assert_eq!(replayed_keys(&mut key_macro), vec![key('j'); 12]);
+
+        // count is reset by other keys
+        key_macro.record(key('3'));
+        key_macro.record(key('k'));
+        assert!(key_macro.replay());
+        assert_eq!(replayed_keys(&mut key_macro), vec![key('j')]);
+
+        key_macro.record(key('0'));
+        assert!(!key_macro.replay());
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::{DateTime, Days, Local, NaiveTime, Utc};
+
+const DEFAULT_STORAGE_CLASS: &str = "STANDARD";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LifecycleRule {
+    pub prefix: String,
+    pub tags: Vec<(String, String)>,
+    pub object_size_greater_than: Option<usize>,
+    pub object_size_less_than: Option<usize>,
+    pub transitions: Vec<LifecycleTransition>,
+    pub expiration: Option<LifecycleSchedule>,
+}
+
+impl LifecycleRule {
+    pub fn has_tag_filter(&self) -> bool {
+        !self.tags.is_empty()
+    }
+
+    fn matches(&self, key: &str, tags: &[(String, String)], size_byte: usize) -> bool {
+        key.starts_with(&self.prefix)
+            && self.tags.iter().all(|t| tags.contains(t))
+            && self
+                .object_size_greater_than
+                .map_or(true, |n| size_byte > n)
+            && self.object_size_less_than.map_or(true, |n| size_byte < n)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleTransition {
+    pub schedule: LifecycleSchedule,
+    pub storage_class: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleSchedule {
+    Days(u32),
+    Date(DateTime<Local>),
+}
+
+impl LifecycleSchedule {
+    // https://docs.aws.amazon.com/AmazonS3/latest/userguide/intro-lifecycle-rules.html#intro-lifecycle-rules-number-of-days
+    // The number of days is added to the object creation time and rounded up to the next midnight UTC
+    fn resolve(&self, last_modified: DateTime<Local>) -> DateTime<Local> {
+        match self {
+            LifecycleSchedule::Days(days) => {
+                let dt = last_modified.with_timezone(&Utc) + Days::new(*days as u64);
+                let midnight = dt.date_naive().and_time(NaiveTime::MIN).and_utc();
+                let rounded = if midnight == dt {
+                    midnight
+                } else {
+                    midnight + Days::new(1)
+                };
+                rounded.with_timezone(&Local)
+            }
+            LifecycleSchedule::Date(date) => *date,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleAction {
+    Transition(String),
+    Expire,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleEvent {
+    pub action: LifecycleAction,
+    pub at: DateTime<Local>,
+}
+
+// Returns the upcoming actions for the object in chronological order.
+// Nothing can happen to the object after it expires, so later events are dropped.
+// An empty `storage_class` is treated as STANDARD since HeadObject omits it for STANDARD objects.
+pub fn evaluate_lifecycle_rules(
+    rules: &[LifecycleRule],
+    key: &str,
+    tags: &[(String, String)],
+    size_byte: usize,
+    last_modified: DateTime<Local>,
+    storage_class: &str,
+) -> Vec<LifecycleEvent> {
+    let storage_class = if storage_class.is_empty() {
+        DEFAULT_STORAGE_CLASS
+    } else {
+        storage_class
+    };
+
+    let mut events: Vec<LifecycleEvent> = Vec::new();
+    for rule in rules.iter().filter(|r| r.matches(key, tags, size_byte)) {
+        for transition in &rule.transitions {
+            if transition.storage_class == storage_class {
+                continue;
+            }
+            events.push(LifecycleEvent {
+                action: LifecycleAction::Transition(transition.storage_class.clone()),
+                at: transition.schedule.resolve(last_modified),
+            });
+        }
+        if let Some(expiration) = &rule.expiration {
+            events.push(LifecycleEvent {
+                action: LifecycleAction::Expire,
+                at: expiration.resolve(last_modified),
+            });
+        }
+    }
+
+    // expiration takes precedence over transitions scheduled at the same time
+    events.sort_by_key(|e| (e.at, e.action != LifecycleAction::Expire));
+    if let Some(i) = events
+        .iter()
+        .position(|e| e.action == LifecycleAction::Expire)
+    {
+        events.truncate(i + 1);
+    }
+    events.dedup();
+    events
+}
+
+pub fn format_lifecycle_event(event: &LifecycleEvent, now: DateTime<Local>) -> String {
+    let action = match &event.action {
+        LifecycleAction::Transition(storage_class) => format!("Transition to {}", storage_class),
+        LifecycleAction::Expire => "Expire".to_string(),
+    };
+    let seconds = (event.at - now).num_seconds();
+    if seconds <= 0 {
+        return format!("{} pending", action);
+    }
+    let days = (seconds + 86399) / 86400;
+    let unit = if days == 1 { "day" } else { "days" };
+    format!("{} in {} {}", action, days, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+            .with_timezone(&Local)
+    }
+
+    fn transition(days: u32, storage_class: &str) -> LifecycleTransition {
+        LifecycleTransition {
+            schedule: LifecycleSchedule::Days(days),
+            storage_class: storage_class.to_string(),
+        }
+    }
+
+    fn tag(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_evaluate_lifecycle_rules() {
+        let rules = vec![
+            LifecycleRule {
+                prefix: "logs/".to_string(),
+                transitions: vec![transition(30, "STANDARD_IA"), transition(90, "GLACIER")],
+                expiration: Some(LifecycleSchedule::Days(365)),
+                ..Default::default()
+            },
+            LifecycleRule {
+                tags: vec![tag("tmp", "true")],
+                expiration: Some(LifecycleSchedule::Days(60)),
+                ..Default::default()
+            },
+            LifecycleRule {
+                object_size_greater_than: Some(1000),
+                transitions: vec![transition(0, "GLACIER_IR")],
+                ..Default::default()
+            },
+        ];
+        let last_modified = utc("2024-01-01 10:00:00");
+
+        let actual = evaluate_lifecycle_rules(&rules, "logs/a.txt", &[], 10, last_modified, "");
+        let expected = vec![
+            LifecycleEvent {
+                action: LifecycleAction::Transition("STANDARD_IA".to_string()),
+                at: utc("2024-02-01 00:00:00"),
+            },
+            LifecycleEvent {
+                action: LifecycleAction::Transition("GLACIER".to_string()),
+                at: utc("2024-04-01 00:00:00"),
+            },
+            LifecycleEvent {
+                action: LifecycleAction::Expire,
+                at: utc("2025-01-01 00:00:00"),
+            },
+        ];
+        assert_eq!(actual, expected);
+
+        // transitions after the expiration and to the current storage class are dropped
+        let tags = vec![tag("tmp", "true"), tag("owner", "foo")];
+        let actual = evaluate_lifecycle_rules(
+            &rules,
+            "logs/a.txt",
+            &tags,
+            10,
+            last_modified,
+            "STANDARD_IA",
+        );
+        let expected = vec![LifecycleEvent {
+            action: LifecycleAction::Expire,
+            at: utc("2024-03-02 00:00:00"),
+        }];
+        assert_eq!(actual, expected);
+
+        let actual = evaluate_lifecycle_rules(&rules, "data/b.bin", &[], 2000, last_modified, "");
+        let expected = vec![LifecycleEvent {
+            action: LifecycleAction::Transition("GLACIER_IR".to_string()),
+            at: utc("2024-01-02 00:00:00"),
+        }];
+        assert_eq!(actual, expected);
+
+        let actual = evaluate_lifecycle_rules(&rules, "data/c.txt", &[], 10, last_modified, "");
+        assert!(actual.is_empty());
+    }
+
+    #[test]
+    fn test_format_lifecycle_event() {
+        let now = utc("2024-01-20 12:00:00");
+        let event = |action, at| LifecycleEvent {
+            action,
+            at: utc(at),
+        };
+
+        let glacier = || LifecycleAction::Transition("GLACIER".to_string());
+        assert_eq!(
+            format_lifecycle_event(&event(glacier(), "2024-02-01 00:00:00"), now),
+            "Transition to GLACIER in 12 days"
+        );
+        assert_eq!(
+            format_lifecycle_event(&event(LifecycleAction::Expire, "2024-01-21 00:00:00"), now),
+            "Expire in 1 day"
+        );
+        assert_eq!(
+            format_lifecycle_event(&event(glacier(), "2024-01-20 00:00:00"), now),
+            "Transition to GLACIER pending"
+        );
+    }
+}
@@ -0,0 +1,861 @@
+use std::{
+    fs,
+    io::SeekFrom,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::{
+    bucket_metrics::BucketMetrics,
+    download_list::DownloadObjectList,
+    error::{AppError, Result},
+    lifecycle::LifecycleRule,
+    multipart::{MultipartUpload, UploadedPart},
+    object::{
+        BucketItem, DeleteFailure, DownloadObjectInfo, FileDetail, FileVersion, FileVersions,
+        FileVersionsMarker, ObjectItem, ObjectMetadata, ObjectSummary, ObjectsPage, RawObject,
+    },
+    storage::{
+        load_objects_in_one_page, multipart_upload_not_supported, BoxFuture, BucketAdmin,
+        ManagementConsole, MultipartUploader, ObjectStore, Presigner, Progress,
+    },
+};
+
+const DELIMITER: char = '/';
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+// Serves a local directory as the storage, for trying the app without any object store.
+//
+// The directories directly under the root are the buckets, and the files under them are the objects.
+// Objects have only one version, no tags and no lifecycle rules.
+// ETags are built from the size and the modified time instead of the content,
+// so that listing does not read the files.
+#[derive(Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Result<LocalStorage> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| AppError::new(format!("Failed to open {}", root.display()), e))?;
+        if !root.is_dir() {
+            return Err(AppError::msg(format!(
+                "Not a directory: {}",
+                root.display()
+            )));
+        }
+        Ok(LocalStorage { root })
+    }
+
+    fn bucket_path(&self, bucket: &str) -> Result<PathBuf> {
+        resolve_path(&self.root, bucket, "")
+    }
+
+    fn object_path(&self, bucket: &str, key: &str) -> Result<PathBuf> {
+        resolve_path(&self.root, bucket, key)
+    }
+
+    fn bucket_item(&self, name: &str) -> BucketItem {
+        let url = file_url(&self.root.join(name));
+        BucketItem {
+            name: name.to_string(),
+            s3_uri: url.clone(),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        }
+    }
+
+    fn file_item(&self, bucket: &str, key: String, metadata: &fs::Metadata) -> ObjectItem {
+        let url = file_url(&self.root.join(bucket).join(&key));
+        ObjectItem::File {
+            name: key.rsplit(DELIMITER).next().unwrap().to_string(),
+            size_byte: metadata.len() as usize,
+            last_modified: modified(metadata),
+            s3_uri: url.clone(),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+            e_tag: e_tag(metadata),
+            key,
+        }
+    }
+
+    fn dir_item(&self, bucket: &str, key: String) -> ObjectItem {
+        let url = file_url(&self.root.join(bucket).join(&key));
+        ObjectItem::Dir {
+            name: key
+                .trim_end_matches(DELIMITER)
+                .rsplit(DELIMITER)
+                .next()
+                .unwrap()
+                .to_string(),
+            s3_uri: url.clone(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+            key,
+        }
+    }
+
+    pub async fn load_all_buckets(&self) -> Result<Vec<BucketItem>> {
+        let mut names: Vec<String> = read_dir(&self.root)?
+            .into_iter()
+            .filter(|(_, metadata)| metadata.is_dir())
+            .map(|(name, _)| name)
+            .collect();
+        if names.is_empty() {
+            return Err(AppError::msg("No buckets found"));
+        }
+        names.sort();
+        Ok(names.iter().map(|name| self.bucket_item(name)).collect())
+    }
+
+    pub async fn load_bucket(&self, name: &str) -> Result<BucketItem> {
+        if !self.bucket_path(name)?.is_dir() {
+            return Err(AppError::msg(format!("Bucket '{}' not found", name)));
+        }
+        Ok(self.bucket_item(name))
+    }
+
+    pub async fn create_bucket(&self, name: &str) -> Result<()> {
+        let path = self.bucket_path(name)?;
+        tokio::fs::create_dir(path)
+            .await
+            .map_err(|e| AppError::new("Failed to create bucket", e))
+    }
+
+    pub async fn delete_bucket(&self, name: &str) -> Result<()> {
+        let path = self.bucket_path(name)?;
+        tokio::fs::remove_dir(path)
+            .await
+            .map_err(|e| AppError::new("Failed to delete bucket", e))
+    }
+
+    // Only the prefixes that end with the delimiter are supported.
+    pub async fn load_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectItem>> {
+        let dir = self.object_path(bucket, prefix)?;
+        let mut entries = read_dir(&dir)?;
+        entries.sort_by(|(n1, _), (n2, _)| n1.cmp(n2));
+
+        let (dirs, files): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(_, metadata)| metadata.is_dir());
+        let dirs = dirs
+            .into_iter()
+            .map(|(name, _)| self.dir_item(bucket, format!("{}{}/", prefix, name)));
+        let files = files.into_iter().map(|(name, metadata)| {
+            self.file_item(bucket, format!("{}{}", prefix, name), &metadata)
+        });
+        Ok(dirs.chain(files).collect())
+    }
+
+    pub async fn list_all_download_objects<F>(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        f: F,
+    ) -> Result<DownloadObjectList>
+    where
+        F: Fn(usize),
+    {
+        let files = self.list_all_files(bucket, prefix)?;
+        f(files.len());
+        let mut objs: Vec<DownloadObjectInfo> = files
+            .into_iter()
+            .map(|(key, metadata)| DownloadObjectInfo {
+                key,
+                size_byte: metadata.len() as usize,
+                last_modified: modified(&metadata),
+                e_tag: e_tag(&metadata),
+            })
+            .collect();
+        objs.sort_by(|a, b| a.key.cmp(&b.key));
+        DownloadObjectList::from_objs(objs)
+    }
+
+    pub async fn list_all_object_summaries(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<Vec<ObjectSummary>> {
+        let files = self.list_all_files(bucket, prefix)?;
+        let objs = files
+            .into_iter()
+            .map(|(key, metadata)| ObjectSummary {
+                key,
+                size_byte: metadata.len() as usize,
+                last_modified: modified(&metadata),
+                e_tag: e_tag(&metadata),
+            })
+            .collect();
+        Ok(objs)
+    }
+
+    // Lists the files under the prefix recursively, sorted by the keys like S3.
+    fn list_all_files(&self, bucket: &str, prefix: &str) -> Result<Vec<(String, fs::Metadata)>> {
+        // the prefix may end in the middle of a name
+        let dir_prefix = prefix.rfind(DELIMITER).map_or("", |i| &prefix[..=i]);
+        let dir = self.object_path(bucket, dir_prefix)?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        let mut dirs = vec![(dir, dir_prefix.to_string())];
+        while let Some((dir, key_prefix)) = dirs.pop() {
+            for (name, metadata) in read_dir(&dir)? {
+                let key = format!("{}{}", key_prefix, name);
+                // skip the names that do not match the rest of the prefix
+                if !key.starts_with(prefix) && !prefix.starts_with(&key) {
+                    continue;
+                }
+                if metadata.is_dir() {
+                    dirs.push((dir.join(&name), format!("{}/", key)));
+                } else if key.starts_with(prefix) {
+                    files.push((key, metadata));
+                }
+            }
+        }
+        files.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        Ok(files)
+    }
+
+    pub async fn object_exists(&self, bucket: &str, key: &str) -> Result<bool> {
+        let path = self.object_path(bucket, key)?;
+        path.try_exists()
+            .map_err(|e| AppError::new("Failed to check object", e))
+    }
+
+    pub async fn load_object_detail(
+        &self,
+        bucket: &str,
+        key: &str,
+        name: &str,
+        size_byte: usize,
+    ) -> Result<FileDetail> {
+        let path = self.object_path(bucket, key)?;
+        let metadata =
+            fs::metadata(&path).map_err(|e| AppError::new("Failed to load object detail", e))?;
+        let content_type = infer::get_from_path(&path)
+            .ok()
+            .flatten()
+            .map_or(DEFAULT_CONTENT_TYPE, |t| t.mime_type())
+            .to_string();
+        let url = file_url(&path);
+        Ok(FileDetail {
+            name: name.to_string(),
+            size_byte,
+            last_modified: modified(&metadata),
+            e_tag: e_tag(&metadata),
+            content_type,
+            storage_class: "".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
+            key: key.to_string(),
+            s3_uri: url.clone(),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        })
+    }
+
+    pub async fn load_object_versions(&self, bucket: &str, key: &str) -> Result<FileVersions> {
+        let path = self.object_path(bucket, key)?;
+        let metadata =
+            fs::metadata(&path).map_err(|e| AppError::new("Failed to load object versions", e))?;
+        let version = FileVersion {
+            version_id: "null".to_string(),
+            size_byte: metadata.len() as usize,
+            last_modified: modified(&metadata),
+            e_tag: e_tag(&metadata),
+            is_latest: true,
+            is_delete_marker: false,
+        };
+        Ok(FileVersions {
+            versions: vec![version],
+            next_marker: None,
+        })
+    }
+
+    pub async fn download_object<F>(&self, bucket: &str, key: &str, f: F) -> Result<RawObject>
+    where
+        F: Fn(usize),
+    {
+        let path = self.object_path(bucket, key)?;
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| AppError::new("Failed to download object", e))?;
+        f(bytes.len());
+        Ok(RawObject { bytes })
+    }
+
+    pub async fn download_object_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        offset: usize,
+        len: usize,
+    ) -> Result<RawObject> {
+        let path = self.object_path(bucket, key)?;
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| AppError::new("Failed to download object", e))?;
+        file.seek(SeekFrom::Start(offset as u64))
+            .await
+            .map_err(|e| AppError::new("Failed to download object", e))?;
+        let mut bytes = Vec::with_capacity(len);
+        file.take(len as u64)
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| AppError::new("Failed to download object", e))?;
+        Ok(RawObject { bytes })
+    }
+
+    pub async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<()> {
+        let src = self.object_path(src_bucket, src_key)?;
+        let dst = self.object_path(dst_bucket, dst_key)?;
+        create_parent_dirs(&dst).map_err(|e| AppError::new("Failed to copy object", e))?;
+        tokio::fs::copy(src, dst)
+            .await
+            .map_err(|e| AppError::new("Failed to copy object", e))?;
+        Ok(())
+    }
+
+    pub async fn put_object(&self, bucket: &str, key: &str, bytes: Vec<u8>) -> Result<()> {
+        let path = self.object_path(bucket, key)?;
+        create_parent_dirs(&path).map_err(|e| AppError::new("Failed to upload object", e))?;
+        tokio::fs::write(path, bytes)
+            .await
+            .map_err(|e| AppError::new("Failed to upload object", e))?;
+        Ok(())
+    }
+
+    pub async fn create_folder(&self, bucket: &str, key: &str) -> Result<ObjectItem> {
+        let path = self.object_path(bucket, key)?;
+        tokio::fs::create_dir_all(path)
+            .await
+            .map_err(|e| AppError::new("Failed to create folder", e))?;
+        Ok(self.dir_item(bucket, key.to_string()))
+    }
+
+    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        let path = self.object_path(bucket, key)?;
+        let result = if key.ends_with(DELIMITER) {
+            // "directory" objects can only be deleted if they are empty, like S3 shows them
+            tokio::fs::remove_dir(path).await
+        } else {
+            tokio::fs::remove_file(path).await
+        };
+        result.map_err(|e| AppError::new("Failed to delete object", e))?;
+        Ok(())
+    }
+
+    pub async fn delete_objects<F>(
+        &self,
+        bucket: &str,
+        keys: &[String],
+        f: F,
+    ) -> Result<Vec<DeleteFailure>>
+    where
+        F: Fn(usize),
+    {
+        let mut failed = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            if let Err(e) = self.delete_object(bucket, key).await {
+                failed.push(DeleteFailure {
+                    key: key.clone(),
+                    error: e.to_string(),
+                });
+            }
+            f(i + 1);
+        }
+        Ok(failed)
+    }
+}
+
+// Keys must stay under the bucket directory
+fn resolve_path(root: &Path, bucket: &str, key: &str) -> Result<PathBuf> {
+    let mut path = root.to_path_buf();
+    for name in std::iter::once(bucket).chain(key.split(DELIMITER)) {
+        if name.is_empty() {
+            continue;
+        }
+        match Path::new(name).components().next() {
+            Some(Component::Normal(_)) if Path::new(name).components().count() == 1 => {
+                path.push(name);
+            }
+            _ => {
+                return Err(AppError::msg(format!(
+                    "Invalid object key: {}/{}",
+                    bucket, key
+                )))
+            }
+        }
+    }
+    Ok(path)
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<(String, fs::Metadata)>> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| AppError::new(format!("Failed to read {}", dir.display()), e))?;
+    let mut ret = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::new("Failed to read directory entry", e))?;
+        // names that are not valid UTF-8 cannot be keys
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        // follow symlinks
+        let metadata = fs::metadata(entry.path())
+            .map_err(|e| AppError::new("Failed to read directory entry", e))?;
+        ret.push((name, metadata));
+    }
+    Ok(ret)
+}
+
+fn create_parent_dirs(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
+
+fn modified(metadata: &fs::Metadata) -> DateTime<Local> {
+    metadata
+        .modified()
+        .map(DateTime::<Local>::from)
+        .unwrap_or_default()
+}
+
+fn e_tag(metadata: &fs::Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", metadata.len(), modified.as_nanos())
+}
+
+fn file_url(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+impl ObjectStore for LocalStorage {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>> {
+        Box::pin(LocalStorage::load_objects(self, bucket, prefix))
+    }
+
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        _token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>> {
+        load_objects_in_one_page(Box::pin(LocalStorage::load_objects(self, bucket, prefix)))
+    }
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>> {
+        Box::pin(LocalStorage::list_all_download_objects(
+            self, bucket, prefix, progress,
+        ))
+    }
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>> {
+        Box::pin(LocalStorage::list_all_object_summaries(
+            self, bucket, prefix,
+        ))
+    }
+
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(LocalStorage::object_exists(self, bucket, key))
+    }
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>> {
+        Box::pin(LocalStorage::load_object_detail(
+            self, bucket, key, name, size_byte,
+        ))
+    }
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>> {
+        Box::pin(LocalStorage::load_object_versions(self, bucket, key))
+    }
+
+    fn load_object_tags<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _version_id: Option<String>,
+        _if_match: Option<String>,
+        _size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(LocalStorage::download_object(self, bucket, key, progress))
+    }
+
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _version_id: Option<String>,
+        _if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(LocalStorage::download_object_range(
+            self, bucket, key, offset, len,
+        ))
+    }
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(LocalStorage::copy_object(
+            self, src_bucket, src_key, dst_bucket, dst_key,
+        ))
+    }
+
+    // The Content-Type is not stored, it is detected from the file when loading the detail
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        _content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(LocalStorage::put_object(self, bucket, key, bytes))
+    }
+
+    fn put_object_tags<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Object tags are not supported for the local file system",
+            ))
+        })
+    }
+
+    fn change_storage_class<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Storage classes are not supported for the local file system",
+            ))
+        })
+    }
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Editing metadata is not supported for the local file system",
+            ))
+        })
+    }
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _on: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Legal hold is not supported for the local file system",
+            ))
+        })
+    }
+
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>> {
+        Box::pin(LocalStorage::create_folder(self, bucket, key))
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(LocalStorage::delete_object(self, bucket, key))
+    }
+
+    fn delete_object_version<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Deleting a version is not supported for the local file system",
+            ))
+        })
+    }
+
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>> {
+        Box::pin(LocalStorage::delete_objects(self, bucket, keys, progress))
+    }
+}
+
+impl BucketAdmin for LocalStorage {
+    fn region(&self) -> &str {
+        "local"
+    }
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>> {
+        Box::pin(LocalStorage::load_all_buckets(self))
+    }
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>> {
+        Box::pin(LocalStorage::load_bucket(self, name))
+    }
+
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        _bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn is_object_lock_enabled<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async { Ok(false) })
+    }
+
+    fn load_bucket_metrics<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Bucket metrics are not supported for the local backend",
+            ))
+        })
+    }
+
+    // The region is ignored, buckets are just directories under the root
+    fn create_bucket<'a>(&'a self, name: &'a str, _region: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(LocalStorage::create_bucket(self, name))
+    }
+
+    fn delete_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(LocalStorage::delete_bucket(self, name))
+    }
+}
+
+impl Presigner for LocalStorage {
+    // There is nothing to sign, the file URL is returned as is
+    fn presign_get_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _version_id: Option<String>,
+        _expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move { Ok(file_url(&self.object_path(bucket, key)?)) })
+    }
+
+    fn presign_put_object<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Presigned PUT URLs are not supported for local storage",
+            ))
+        })
+    }
+}
+
+impl MultipartUploader for LocalStorage {
+    fn supports_multipart_upload(&self) -> bool {
+        false
+    }
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn create_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn upload_part<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _part_number: i32,
+        _bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+}
+
+// Opens the directory in the file manager instead
+impl ManagementConsole for LocalStorage {
+    fn open_management_console_buckets(&self) -> Result<()> {
+        open::that(&self.root).map_err(AppError::error)
+    }
+
+    fn open_management_console_list(&self, bucket: &str, prefix: &str) -> Result<()> {
+        let path = self.object_path(bucket, prefix)?;
+        open::that(path).map_err(AppError::error)
+    }
+
+    fn open_management_console_object(&self, bucket: &str, prefix: &str) -> Result<()> {
+        let path = self.object_path(bucket, prefix)?;
+        open::that(path).map_err(AppError::error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path() {
+        let root = Path::new("/data");
+        assert_eq!(
+            resolve_path(root, "bucket", "a/b.txt").unwrap(),
+            PathBuf::from("/data/bucket/a/b.txt")
+        );
+        assert_eq!(
+            resolve_path(root, "bucket", "a/").unwrap(),
+            PathBuf::from("/data/bucket/a")
+        );
+        assert_eq!(
+            resolve_path(root, "bucket", "").unwrap(),
+            PathBuf::from("/data/bucket")
+        );
+        assert!(resolve_path(root, "bucket", "../other/a.txt").is_err());
+        assert!(resolve_path(root, "..", "a.txt").is_err());
+        assert!(resolve_path(root, "bucket", "a/./b.txt").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_list_objects() {
+        let root = std::env::temp_dir().join(format!("stu-local-test-{}", std::process::id()));
+        for path in ["bucket/a.txt", "bucket/dir/b.txt", "bucket/dir2/c.txt"] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "abc").unwrap();
+        }
+        let storage = LocalStorage::new(root.clone()).unwrap();
+
+        let names: Vec<String> = storage
+            .load_objects("bucket", "")
+            .await
+            .unwrap()
+            .iter()
+            .map(|item| item.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["dir", "dir2", "a.txt"]);
+
+        assert!(storage.object_exists("bucket", "dir/b.txt").await.unwrap());
+        assert!(!storage.object_exists("bucket", "dir/x.txt").await.unwrap());
+
+        let keys: Vec<String> = storage
+            .list_all_object_summaries("bucket", "dir")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|obj| obj.key)
+            .collect();
+        assert_eq!(keys, vec!["dir/b.txt", "dir2/c.txt"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    error::{AppError, Result},
+    util::percent_decode,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl S3Location {
+    fn new(bucket: &str, key: &str) -> Result<Self> {
+        if bucket.is_empty() {
+            return Err(AppError::msg("Bucket name is not specified"));
+        }
+        Ok(Self {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    // Names of the path components of the key, e.g. `a/b/c.txt` -> [a, b, c.txt]
+    pub fn key_paths(&self) -> Vec<String> {
+        self.key
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.key.is_empty() || self.key.ends_with('/')
+    }
+}
+
+// Parses the following formats:
+//
+// - s3://bucket/key
+// - arn:aws:s3:::bucket/key
+// - https://bucket.s3.region.amazonaws.com/key (virtual-hosted style)
+// - https://s3.region.amazonaws.com/bucket/key (path style, also used for custom endpoints)
+// - https://s3.console.aws.amazon.com/s3/buckets/bucket?prefix=key
+// - https://s3.console.aws.amazon.com/s3/object/bucket?prefix=key
+pub fn parse_s3_location(s: &str) -> Result<S3Location> {
+    let s = s.trim();
+    if let Some(rest) = s.strip_prefix("s3://") {
+        let (bucket, key) = split_bucket_and_key(rest);
+        return S3Location::new(bucket, key);
+    }
+    if let Some(rest) = strip_arn_prefix(s) {
+        let (bucket, key) = split_bucket_and_key(rest);
+        return S3Location::new(bucket, key);
+    }
+    if let Some(rest) = s
+        .strip_prefix("https://")
+        .or_else(|| s.strip_prefix("http://"))
+    {
+        return parse_url(rest);
+    }
+    Err(AppError::msg(format!("Unsupported location format: {}", s)))
+}
+
+fn strip_arn_prefix(s: &str) -> Option<&str> {
+    // the partition can be aws, aws-cn, aws-us-gov, etc.
+    let rest = s.strip_prefix("arn:")?;
+    let (_, rest) = rest.split_once(':')?;
+    rest.strip_prefix("s3:::")
+}
+
+fn split_bucket_and_key(s: &str) -> (&str, &str) {
+    s.split_once('/').unwrap_or((s, ""))
+}
+
+fn parse_url(s: &str) -> Result<S3Location> {
+    let (s, query) = match s.split_once(['?', '#']) {
+        Some((s, query)) => (s, query),
+        None => (s, ""),
+    };
+    let (host, path) = s.split_once('/').unwrap_or((s, ""));
+    let host = host.split(':').next().unwrap_or_default();
+
+    if host.ends_with("console.aws.amazon.com") {
+        let bucket = path
+            .strip_prefix("s3/buckets/")
+            .or_else(|| path.strip_prefix("s3/object/"))
+            .ok_or_else(|| AppError::msg(format!("Unsupported console URL: {}", s)))?;
+        let bucket = bucket.trim_end_matches('/');
+        let prefix = query
+            .split('&')
+            .find_map(|param| param.strip_prefix("prefix="))
+            .map(|v| decode(v, true))
+            .unwrap_or_default();
+        return S3Location::new(bucket, &prefix);
+    }
+
+    if let Some(bucket) = virtual_hosted_bucket(host) {
+        return S3Location::new(bucket, &decode(path, false));
+    }
+
+    let (bucket, key) = split_bucket_and_key(path);
+    S3Location::new(bucket, &decode(key, false))
+}
+
+fn virtual_hosted_bucket(host: &str) -> Option<&str> {
+    let host = host.strip_suffix(".amazonaws.com")?;
+    // bucket.s3, bucket.s3.region, bucket.s3-region
+    host.rsplit_once(".s3.")
+        .or_else(|| host.rsplit_once(".s3-"))
+        .map(|(bucket, _)| bucket)
+        .or_else(|| host.strip_suffix(".s3"))
+}
+
+fn decode(s: &str, plus_as_space: bool) -> String {
+    String::from_utf8_lossy(&percent_decode(s, plus_as_space)).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("s3://bucket", "bucket", "")]
+    #[case("s3://bucket/", "bucket", "")]
+    #[case("s3://bucket/a/b/", "bucket", "a/b/")]
+    #[case("s3://bucket/a/b/c.txt", "bucket", "a/b/c.txt")]
+    #[case("  s3://bucket/a b.txt\n", "bucket", "a b.txt")]
+    #[case("arn:aws:s3:::bucket", "bucket", "")]
+    #[case("arn:aws:s3:::bucket/a/b/c.txt", "bucket", "a/b/c.txt")]
+    #[case("arn:aws-cn:s3:::bucket/a/", "bucket", "a/")]
+    #[case("https://bucket.s3.amazonaws.com/a/b.txt", "bucket", "a/b.txt")]
+    #[case(
+        "https://bucket.s3.ap-northeast-1.amazonaws.com/a/b%20c.txt",
+        "bucket",
+        "a/b c.txt"
+    )]
+    #[case(
+        "https://my.bucket.s3-us-west-2.amazonaws.com/a/b.txt?X-Amz-Expires=3600",
+        "my.bucket",
+        "a/b.txt"
+    )]
+    #[case("https://bucket.s3.amazonaws.com/a/100%+1%2", "bucket", "a/100%+1%2")]
+    #[case("https://s3.us-east-1.amazonaws.com/bucket/a/", "bucket", "a/")]
+    #[case("https://s3.amazonaws.com/bucket", "bucket", "")]
+    #[case("http://localhost:4566/bucket/a/b.txt", "bucket", "a/b.txt")]
+    #[case(
+        "https://s3.console.aws.amazon.com/s3/buckets/bucket?region=us-east-1&prefix=a/b/&showversions=false",
+        "bucket",
+        "a/b/"
+    )]
+    #[case(
+        "https://us-east-1.console.aws.amazon.com/s3/object/bucket?region=us-east-1&prefix=a/b+c%2B.txt",
+        "bucket",
+        "a/b c+.txt"
+    )]
+    #[case(
+        "https://s3.console.aws.amazon.com/s3/buckets/bucket/?region=us-east-1",
+        "bucket",
+        ""
+    )]
+    fn test_parse_s3_location(#[case] s: &str, #[case] bucket: &str, #[case] key: &str) {
+        let expected = S3Location {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        };
+        assert_eq!(parse_s3_location(s).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("bucket/a/b.txt")]
+    #[case("s3://")]
+    #[case("s3:///a.txt")]
+    #[case("arn:aws:iam::123456789012:user/foo")]
+    #[case("https://console.aws.amazon.com/ec2/home")]
+    fn test_parse_s3_location_error(#[case] s: &str) {
+        assert!(parse_s3_location(s).is_err());
+    }
+}
@@ -1,34 +1,79 @@
+mod access_log;
 mod app;
+mod aws_profile;
+#[cfg(feature = "azure")]
+mod azure;
+mod bucket_metrics;
+mod bucket_pin;
 mod cache;
 mod client;
 mod color;
+mod command;
 mod config;
 mod constant;
+mod diff;
+mod download_list;
 mod environment;
 mod error;
+mod etag_index;
 mod event;
 mod file;
 mod format;
+mod headless;
+mod inventory;
+mod jobs;
+mod key_macro;
+mod lifecycle;
+mod local;
+mod location;
 mod macros;
+mod metadata;
+mod middleware;
+mod multipart;
 mod object;
+mod outline;
 mod pages;
+mod rename;
+mod report;
 mod run;
+mod schedule;
+mod session_metrics;
+mod sso;
+mod storage;
+mod tagging;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod tls;
 mod util;
+mod watch;
+#[cfg(feature = "webdav")]
+mod webdav;
 mod widget;
 
-use clap::{arg, Parser, ValueEnum};
+use chrono::Local;
+use clap::{Parser, Subcommand, ValueEnum};
 use event::AppEventType;
 use file::open_or_create_append_file;
 use ratatui::{backend::Backend, Terminal};
-use std::sync::Mutex;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 use tokio::spawn;
-use tracing_subscriber::fmt::time::ChronoLocal;
+use tracing_subscriber::{
+    filter::LevelFilter, fmt::time::ChronoLocal, layer::SubscriberExt, util::SubscriberInitExt,
+    Layer,
+};
 
 use crate::app::{App, AppContext};
-use crate::client::Client;
+use crate::client::{Client, RetryCallback, SseCustomerKey, SseCustomerKeys};
 use crate::color::ColorTheme;
 use crate::config::Config;
 use crate::environment::Environment;
+use crate::location::{parse_s3_location, S3Location};
+use crate::middleware::{LoggingMiddleware, MiddlewareChain, ReadOnlyMiddleware};
+use crate::session_metrics::SessionMetrics;
+use crate::storage::{GuardedStorage, SlowRequestCallback, Storage, StorageFactory, StorageTarget};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum PathStyle {
@@ -47,10 +92,64 @@ impl From<PathStyle> for client::AddressingStyle {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StartPageType {
+    Buckets,
+    ObjectList,
+    Preview,
+}
+
+impl From<StartPageType> for app::StartPage {
+    fn from(page: StartPageType) -> Self {
+        match page {
+            StartPageType::Buckets => app::StartPage::Buckets,
+            StartPageType::ObjectList => app::StartPage::ObjectList,
+            StartPageType::Preview => app::StartPage::Preview,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum StorageType {
+    S3,
+    Local,
+    #[cfg(feature = "azure")]
+    Azure,
+    #[cfg(feature = "webdav")]
+    Webdav,
+}
+
+// Settings of the storage backend that are resolved before the terminal is initialized
+enum StorageConfig {
+    S3(SseCustomerKeys),
+    Local(local::LocalStorage),
+    #[cfg(feature = "azure")]
+    Azure(azure::AzureCredentials),
+    #[cfg(feature = "webdav")]
+    WebDav(webdav::WebDavConfig),
+}
+
+#[derive(Clone, Subcommand)]
+enum Command {
+    /// Upload stdin to the location without starting the UI
+    Put {
+        /// Destination of the object (s3:// URI, ARN or object URL)
+        #[arg(value_name = "LOCATION", value_parser = parse_location)]
+        location: S3Location,
+
+        /// Content-Type of the object (detected from the content if omitted)
+        #[arg(long, value_name = "TYPE")]
+        content_type: Option<String>,
+    },
+}
+
 /// STU - S3 Terminal UI
-#[derive(Parser)]
+#[derive(Clone, Parser)]
 #[command(version)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// AWS region
     #[arg(short, long)]
     region: Option<String>,
@@ -67,10 +166,47 @@ struct Args {
     #[arg(short, long, value_name = "NAME")]
     bucket: Option<String>,
 
+    /// Prefix to open at startup in the bucket specified by --bucket
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        requires = "bucket",
+        conflicts_with = "key"
+    )]
+    prefix: Option<String>,
+
+    /// Object key to open at startup in the bucket specified by --bucket
+    #[arg(long, value_name = "KEY", requires = "bucket")]
+    key: Option<String>,
+
+    /// Location to open at startup (s3:// URI, ARN, object URL or console URL)
+    #[arg(long, value_name = "LOCATION", conflicts_with = "bucket", value_parser = parse_location)]
+    open: Option<S3Location>,
+
+    /// Page to show first for the location specified at startup
+    #[arg(long, value_name = "TYPE")]
+    start_page: Option<StartPageType>,
+
+    /// SSE-C key (256-bit key encoded in base64) to access encrypted objects
+    #[arg(long, value_name = "KEY", value_parser = parse_sse_c_key)]
+    sse_c_key: Option<SseCustomerKey>,
+
     /// Path style type for object paths
     #[arg(long, value_name = "TYPE", default_value = "auto")]
     path_style: PathStyle,
 
+    /// Storage backend
+    #[arg(long, value_name = "TYPE", default_value = "s3")]
+    backend: StorageType,
+
+    /// Root directory served by the local backend
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    local_root: PathBuf,
+
+    /// Disable operations that modify objects
+    #[arg(long)]
+    read_only: bool,
+
     /// Enable debug logs
     #[arg(long)]
     debug: bool,
@@ -84,12 +220,87 @@ async fn main() -> anyhow::Result<()> {
     let theme = ColorTheme::default();
     let ctx = AppContext::new(config, env, theme);
 
-    initialize_debug_log(&args, &ctx.config)?;
+    initialize_tracing(&args, &ctx.config)?;
+
+    let storage_config = match args.backend {
+        StorageType::S3 => {
+            let sse_customer_keys =
+                SseCustomerKeys::new(args.sse_c_key.clone(), &ctx.config.sse_c.bucket_keys)
+                    .map_err(|e| anyhow::anyhow!(e.msg))?;
+            StorageConfig::S3(sse_customer_keys)
+        }
+        StorageType::Local => {
+            let storage = local::LocalStorage::new(args.local_root.clone())
+                .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            StorageConfig::Local(storage)
+        }
+        #[cfg(feature = "azure")]
+        StorageType::Azure => {
+            let credentials =
+                azure::AzureCredentials::from_env().map_err(|e| anyhow::anyhow!(e.msg))?;
+            StorageConfig::Azure(credentials)
+        }
+        #[cfg(feature = "webdav")]
+        StorageType::Webdav => {
+            let config = webdav::WebDavConfig::from_env().map_err(|e| anyhow::anyhow!(e.msg))?;
+            StorageConfig::WebDav(config)
+        }
+    };
+
+    if tls::is_customized(&ctx.config.client) {
+        tls::validate(&ctx.config.client).map_err(|e| anyhow::anyhow!(e.msg))?;
+    }
+
+    let location = args.start_location();
+    if args.start_page == Some(StartPageType::Preview)
+        && location.as_ref().map_or(true, |l| l.is_dir())
+    {
+        anyhow::bail!("--start-page preview requires an object specified by --key or --open");
+    }
+
+    let metrics = Arc::new(SessionMetrics::new(Local::now()));
+
+    if let Some(Command::Put {
+        location,
+        content_type,
+    }) = &args.command
+    {
+        let client = build_storage(&args, storage_config, &ctx.config, None, None, metrics).await;
+        let size = headless::put_stdin(
+            client.as_ref(),
+            location,
+            content_type.as_deref(),
+            &ctx.config.upload,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e.msg));
+        #[cfg(feature = "otel")]
+        telemetry::shutdown();
+        let size = size?;
+        eprintln!(
+            "Uploaded {} to s3://{}/{}",
+            format::format_size_byte(size as usize),
+            location.bucket,
+            location.key
+        );
+        return Ok(());
+    }
+
+    let metrics_file_path = ctx.config.metrics_file_path();
+    let metrics_format = ctx.config.metrics.format;
 
     let mut terminal = ratatui::try_init()?;
-    let ret = run(&mut terminal, args, ctx).await;
+    let ret = run(&mut terminal, args, storage_config, ctx, metrics.clone()).await;
 
     ratatui::try_restore()?;
+    #[cfg(feature = "otel")]
+    telemetry::shutdown();
+
+    if let Some(path) = metrics_file_path? {
+        if let Err(e) = metrics.write(&path, metrics_format, Local::now()) {
+            eprintln!("Failed to write metrics to {}: {}", path.display(), e);
+        }
+    }
 
     ret
 }
@@ -97,45 +308,211 @@ async fn main() -> anyhow::Result<()> {
 async fn run<B: Backend>(
     terminal: &mut Terminal<B>,
     args: Args,
+    storage_config: StorageConfig,
     ctx: AppContext,
+    metrics: Arc<SessionMetrics>,
 ) -> anyhow::Result<()> {
     let (tx, rx) = event::new();
     let (width, height) = get_frame_size(terminal);
-    let default_region_fallback = ctx.config.default_region.clone();
+    let config = ctx.config.clone();
 
     let mut app = App::new(ctx, tx.clone(), width, height);
+    if config.client.insecure_skip_tls_verify {
+        tx.send(AppEventType::NotifyWarn(tls::INSECURE_WARNING.into()));
+    }
+
+    let mut middlewares = MiddlewareChain::default().with(LoggingMiddleware);
+    if args.read_only {
+        middlewares = middlewares.with(ReadOnlyMiddleware);
+    }
+
+    let retry_tx = tx.clone();
+    let on_retry: RetryCallback = Arc::new(move |attempt, max_attempts| {
+        let msg = format!("Retrying ({}/{})...", attempt, max_attempts);
+        retry_tx.send(AppEventType::NotifyWarn(msg));
+    });
+    let slow_request_tx = tx.clone();
+    let on_slow_request: SlowRequestCallback = Arc::new(move |count| {
+        slow_request_tx.send(AppEventType::SlowRequests(count));
+    });
+
+    // only the S3 backend has profiles and regions
+    if let StorageConfig::S3(sse_customer_keys) = &storage_config {
+        let factory = build_storage_factory(
+            &args,
+            sse_customer_keys,
+            &config,
+            &on_retry,
+            &on_slow_request,
+            &metrics,
+        );
+        let target = StorageTarget {
+            profile: args.profile.clone(),
+            region: args.region.clone(),
+        };
+        app.set_storage_factory(target, factory);
+    }
 
     spawn(async move {
-        let client = Client::new(
-            args.region,
-            args.endpoint_url,
-            args.profile,
-            default_region_fallback,
-            args.path_style.into(),
+        let client = build_storage(
+            &args,
+            storage_config,
+            &config,
+            Some(on_retry),
+            Some(on_slow_request),
+            metrics,
         )
         .await;
-        tx.send(AppEventType::Initialize(client, args.bucket));
+        let location = args.start_location();
+        let bucket = args.bucket.or(location.as_ref().map(|l| l.bucket.clone()));
+        let start_page = args.start_page.map(Into::into);
+        tx.send(AppEventType::Initialize(
+            client, bucket, location, start_page,
+        ));
     });
 
-    run::run(&mut app, terminal, rx).await?;
+    run::run(&mut app, terminal, rx, middlewares).await?;
 
     Ok(())
 }
 
+impl Args {
+    fn start_location(&self) -> Option<S3Location> {
+        if self.open.is_some() {
+            return self.open.clone();
+        }
+        let bucket = self.bucket.clone()?;
+        let key = match (&self.prefix, &self.key) {
+            (Some(prefix), _) if !prefix.is_empty() && !prefix.ends_with('/') => {
+                format!("{}/", prefix)
+            }
+            (Some(prefix), _) => prefix.clone(),
+            (None, Some(key)) => key.clone(),
+            (None, None) => return None,
+        };
+        Some(S3Location { bucket, key })
+    }
+}
+
+async fn build_storage(
+    args: &Args,
+    storage_config: StorageConfig,
+    config: &Config,
+    on_retry: Option<RetryCallback>,
+    on_slow_request: Option<SlowRequestCallback>,
+    metrics: Arc<SessionMetrics>,
+) -> Arc<dyn Storage> {
+    let client: Arc<dyn Storage> = match storage_config {
+        StorageConfig::S3(sse_customer_keys) => {
+            let client = Client::new(
+                args.region.clone(),
+                args.endpoint_url.clone(),
+                args.profile.clone(),
+                config.default_region.clone(),
+                args.path_style.into(),
+                sse_customer_keys,
+                &config.client,
+                &config.retry,
+                on_retry,
+            )
+            .await;
+            Arc::new(client)
+        }
+        StorageConfig::Local(storage) => Arc::new(storage),
+        #[cfg(feature = "azure")]
+        StorageConfig::Azure(credentials) => {
+            Arc::new(azure::AzureClient::new(credentials, &config.client))
+        }
+        #[cfg(feature = "webdav")]
+        StorageConfig::WebDav(webdav_config) => {
+            Arc::new(webdav::WebDavClient::new(webdav_config, &config.client))
+        }
+    };
+    let storage = GuardedStorage::new(client, args.read_only, metrics);
+    match (config.client.slow_request_threshold(), on_slow_request) {
+        (Some(threshold), Some(callback)) => {
+            Arc::new(storage.slow_request_watcher(threshold, callback))
+        }
+        _ => Arc::new(storage),
+    }
+}
+
+fn build_storage_factory(
+    args: &Args,
+    sse_customer_keys: &SseCustomerKeys,
+    config: &Config,
+    on_retry: &RetryCallback,
+    on_slow_request: &SlowRequestCallback,
+    metrics: &Arc<SessionMetrics>,
+) -> StorageFactory {
+    let args = args.clone();
+    let sse_customer_keys = sse_customer_keys.clone();
+    let config = config.clone();
+    let on_retry = on_retry.clone();
+    let on_slow_request = on_slow_request.clone();
+    let metrics = metrics.clone();
+    StorageFactory::new(move |target| {
+        let args = Args {
+            profile: target.profile,
+            region: target.region,
+            ..args.clone()
+        };
+        let storage_config = StorageConfig::S3(sse_customer_keys.clone());
+        let config = config.clone();
+        let on_retry = on_retry.clone();
+        let on_slow_request = on_slow_request.clone();
+        let metrics = metrics.clone();
+        Box::pin(async move {
+            build_storage(
+                &args,
+                storage_config,
+                &config,
+                Some(on_retry),
+                Some(on_slow_request),
+                metrics,
+            )
+            .await
+        })
+    })
+}
+
+fn parse_sse_c_key(s: &str) -> Result<SseCustomerKey, String> {
+    SseCustomerKey::from_base64(s).map_err(|e| e.msg)
+}
+
+fn parse_location(s: &str) -> Result<S3Location, String> {
+    parse_s3_location(s).map_err(|e| e.msg)
+}
+
 fn get_frame_size<B: Backend>(terminal: &mut Terminal<B>) -> (usize, usize) {
     let size = terminal.get_frame().area();
     (size.width as usize, size.height as usize)
 }
 
-fn initialize_debug_log(args: &Args, config: &Config) -> anyhow::Result<()> {
-    if args.debug {
+// No subscriber is set unless the debug log or the OpenTelemetry export is enabled
+fn initialize_tracing(args: &Args, config: &Config) -> anyhow::Result<()> {
+    #[cfg(feature = "otel")]
+    let telemetry = telemetry::layer()?;
+    #[cfg(not(feature = "otel"))]
+    let telemetry: Option<tracing_subscriber::layer::Identity> = None;
+
+    let debug_log = if args.debug {
         let path = config.debug_log_path()?;
         let file = open_or_create_append_file(path)?;
-        tracing_subscriber::fmt()
+        let layer = tracing_subscriber::fmt::layer()
             .with_ansi(false)
             .with_timer(ChronoLocal::rfc_3339())
-            .with_max_level(tracing::Level::DEBUG)
             .with_writer(Mutex::new(file))
+            .with_filter(LevelFilter::DEBUG);
+        Some(layer)
+    } else {
+        None
+    };
+
+    if telemetry.is_some() || debug_log.is_some() {
+        tracing_subscriber::registry()
+            .with(telemetry)
+            .with(debug_log)
             .init();
     }
     Ok(())
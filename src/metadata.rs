@@ -0,0 +1,86 @@
+use crate::error::{AppError, Result};
+
+const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+
+// Parses a user-defined metadata entry to add in the form of `name=value`.
+// The name is stored in lowercase by S3, and the prefix (`x-amz-meta-`) can be omitted.
+pub fn parse_user_metadata(s: &str) -> Result<(String, String)> {
+    let Some((name, value)) = s.split_once('=') else {
+        return Err(AppError::msg(format!("Invalid metadata: {}", s.trim())));
+    };
+    let name = name.trim().to_ascii_lowercase();
+    let name = name
+        .strip_prefix(USER_METADATA_PREFIX)
+        .unwrap_or(&name)
+        .to_string();
+    let valid_name = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || !valid_name {
+        return Err(AppError::msg(format!("Invalid metadata name: {}", name)));
+    }
+    Ok((name, value.trim().to_string()))
+}
+
+// Sets the value of the metadata, the existing name keeps its position and the new name is appended.
+// Returns the index of the entry.
+pub fn set_user_metadata(
+    metadata: &mut Vec<(String, String)>,
+    name: String,
+    value: String,
+) -> usize {
+    match metadata.iter().position(|(n, _)| *n == name) {
+        Some(i) => {
+            metadata[i].1 = value;
+            i
+        }
+        None => {
+            metadata.push((name, value));
+            metadata.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("foo=bar", "foo", "bar")]
+    #[case(" Foo = bar baz ", "foo", "bar baz")]
+    #[case("x-amz-meta-foo=bar", "foo", "bar")]
+    #[case("foo_bar-1=", "foo_bar-1", "")]
+    #[case("foo=a=b", "foo", "a=b")]
+    fn test_parse_user_metadata(#[case] s: &str, #[case] name: &str, #[case] value: &str) {
+        let actual = parse_user_metadata(s).unwrap();
+        assert_eq!(actual, (name.to_string(), value.to_string()));
+    }
+
+    #[rstest]
+    #[case("foo")]
+    #[case("=bar")]
+    #[case("x-amz-meta-=bar")]
+    #[case("foo bar=baz")]
+    #[case("föo=bar")]
+    fn test_parse_user_metadata_error(#[case] s: &str) {
+        assert!(parse_user_metadata(s).is_err());
+    }
+
+    #[test]
+    fn test_set_user_metadata() {
+        let mut metadata = vec![("a".to_string(), "1".to_string())];
+        let i = set_user_metadata(&mut metadata, "b".into(), "2".into());
+        assert_eq!(i, 1);
+        let i = set_user_metadata(&mut metadata, "a".into(), "3".into());
+        assert_eq!(i, 0);
+        assert_eq!(
+            metadata,
+            vec![
+                ("a".to_string(), "3".to_string()),
+                ("b".to_string(), "2".to_string())
+            ]
+        );
+    }
+}
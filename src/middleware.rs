@@ -0,0 +1,99 @@
+use crate::{error::AppError, event::AppEventType};
+
+// Processes the events before they are dispatched to the app.
+// Cross-cutting concerns such as logging are implemented here instead of in each handler.
+pub trait Middleware {
+    // Returns the event passed to the next middleware, which may be replaced with another event,
+    // or None to drop the event.
+    fn handle(&mut self, event: AppEventType) -> Option<AppEventType>;
+}
+
+#[derive(Default)]
+pub struct MiddlewareChain {
+    middlewares: Vec<Box<dyn Middleware>>,
+}
+
+impl MiddlewareChain {
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    pub fn handle(&mut self, event: AppEventType) -> Option<AppEventType> {
+        self.middlewares
+            .iter_mut()
+            .try_fold(event, |event, middleware| middleware.handle(event))
+    }
+}
+
+pub struct LoggingMiddleware;
+
+impl Middleware for LoggingMiddleware {
+    fn handle(&mut self, event: AppEventType) -> Option<AppEventType> {
+        // ticks are too frequent to be logged
+        // only the name is logged, since the payloads may contain the object contents or presigned URLs
+        if !matches!(event, AppEventType::Tick) {
+            tracing::debug!("event received: {}", event.name());
+        }
+        Some(event)
+    }
+}
+
+// Rejects the events that modify objects in S3.
+pub struct ReadOnlyMiddleware;
+
+impl Middleware for ReadOnlyMiddleware {
+    fn handle(&mut self, event: AppEventType) -> Option<AppEventType> {
+        if event.is_mutating() {
+            tracing::info!("event rejected in read-only mode: {}", event.name());
+            let e = AppError::msg("This operation is not allowed in read-only mode");
+            return Some(AppEventType::NotifyError(e));
+        }
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DropRefresh;
+
+    impl Middleware for DropRefresh {
+        fn handle(&mut self, event: AppEventType) -> Option<AppEventType> {
+            match event {
+                AppEventType::ObjectListRefresh => None,
+                event => Some(event),
+            }
+        }
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let mut chain = MiddlewareChain::default()
+            .with(ReadOnlyMiddleware)
+            .with(DropRefresh);
+
+        let event = chain.handle(AppEventType::StartDeleteObjects(
+            "b".into(),
+            vec!["k".into()],
+        ));
+        assert!(matches!(event, Some(AppEventType::NotifyError(_))));
+
+        let event = chain.handle(AppEventType::ObjectListRefresh);
+        assert!(event.is_none());
+
+        let event = chain.handle(AppEventType::BucketListRefresh);
+        assert!(matches!(event, Some(AppEventType::BucketListRefresh)));
+    }
+
+    #[test]
+    fn test_event_name() {
+        let event = AppEventType::CopyToClipboard(
+            "Presigned URL".into(),
+            "https://bucket.s3.amazonaws.com/key?X-Amz-Signature=secret".into(),
+        );
+        assert_eq!(event.name(), "CopyToClipboard");
+        assert_eq!(AppEventType::Tick.name(), "Tick");
+    }
+}
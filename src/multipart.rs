@@ -0,0 +1,604 @@
+use std::{collections::VecDeque, io::SeekFrom, path::PathBuf, sync::Arc, time::Duration};
+
+use md5::{Digest, Md5};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    spawn,
+};
+
+use crate::{
+    error::{AppError, Result},
+    event::{AppEventType, Sender},
+    file::create_dirs,
+    jobs::AbortOnDrop,
+    storage::{Progress, Storage},
+};
+
+// S3 requires every part except the last one to be at least 5 MiB,
+// and an upload can have at most 10,000 parts
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_PART_COUNT: u64 = 10_000;
+
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub struct MultipartUpload {
+    pub upload_id: String,
+    pub parts: Vec<UploadedPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadedPart {
+    pub part_number: i32,
+    pub e_tag: String,
+    pub size_byte: u64,
+}
+
+// A local file to be uploaded to the key in multiple parts
+#[derive(Debug, Clone)]
+pub struct MultipartUploadTarget {
+    pub bucket: String,
+    pub key: String,
+    pub path: PathBuf,
+    pub size_byte: u64,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartRange {
+    pub part_number: i32,
+    pub offset: u64,
+    pub len: u64,
+}
+
+pub fn plan_parts(size_byte: u64, part_size_byte: u64) -> Vec<PartRange> {
+    let part_size = part_size_byte
+        .max(MIN_PART_SIZE)
+        .max(size_byte.div_ceil(MAX_PART_COUNT));
+    (0..size_byte.div_ceil(part_size).max(1))
+        .map(|i| {
+            let offset = i * part_size;
+            PartRange {
+                part_number: i as i32 + 1,
+                offset,
+                len: part_size.min(size_byte - offset),
+            }
+        })
+        .collect()
+}
+
+// The total size is not known in advance when streaming, so only the minimum is applied
+pub fn stream_part_size(part_size_byte: u64) -> u64 {
+    part_size_byte.max(MIN_PART_SIZE)
+}
+
+// Returns the already uploaded parts that match the plan, which are reused if unchanged locally.
+// Parts uploaded with a different part size do not match the plan and will be uploaded again.
+pub fn reusable_parts(upload: &MultipartUpload, plan: &[PartRange]) -> Vec<UploadedPart> {
+    upload
+        .parts
+        .iter()
+        .filter(|part| {
+            plan.iter()
+                .any(|r| r.part_number == part.part_number && r.len == part.size_byte)
+        })
+        .cloned()
+        .collect()
+}
+
+fn retry_delay(attempt: usize) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt as u32)
+}
+
+// Returns the ETag of the part, or the last error if all the attempts failed
+pub async fn upload_part_with_retries(
+    client: &dyn Storage,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    part_number: i32,
+    bytes: Vec<u8>,
+    max_retries: usize,
+) -> Result<String> {
+    let mut attempt = 0;
+    loop {
+        let result = client
+            .upload_part(bucket, key, upload_id, part_number, bytes.clone())
+            .await;
+        match result {
+            Ok(e_tag) => return Ok(e_tag),
+            Err(e) if attempt < max_retries => {
+                tracing::warn!("Failed to upload part {}, retrying: {}", part_number, e);
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// The ETag of a part is the MD5 of its content, so the parts whose range has been changed locally
+// since they were uploaded are excluded. The ETag is not the MD5 with SSE-KMS or SSE-C,
+// in which case the parts cannot be verified and are uploaded again.
+async fn unchanged_parts(
+    file: &mut File,
+    parts: Vec<UploadedPart>,
+    plan: &[PartRange],
+) -> Result<Vec<UploadedPart>> {
+    let mut unchanged = Vec::new();
+    for part in parts {
+        let Some(range) = plan.iter().find(|r| r.part_number == part.part_number) else {
+            continue;
+        };
+        let bytes = read_range(file, *range).await?;
+        if is_same_content(&part, &bytes) {
+            unchanged.push(part);
+        } else {
+            tracing::info!(
+                "Part {} has been changed, uploading again",
+                part.part_number
+            );
+        }
+    }
+    Ok(unchanged)
+}
+
+fn is_same_content(part: &UploadedPart, bytes: &[u8]) -> bool {
+    let md5 = format!("{:x}", Md5::digest(bytes));
+    part.e_tag.trim_matches('"').eq_ignore_ascii_case(&md5)
+}
+
+async fn read_range(file: &mut File, range: PartRange) -> Result<Vec<u8>> {
+    let mut bytes = vec![0; range.len as usize];
+    file.seek(SeekFrom::Start(range.offset))
+        .await
+        .map_err(|e| AppError::new("Failed to read file", e))?;
+    file.read_exact(&mut bytes)
+        .await
+        .map_err(|e| AppError::new("Failed to read file", e))?;
+    Ok(bytes)
+}
+
+// The upload is not aborted on failure, so that it can be resumed from the uploaded parts later
+pub async fn upload_in_parts(
+    client: Arc<dyn Storage>,
+    tx: &Sender,
+    target: MultipartUploadTarget,
+    upload: Option<MultipartUpload>,
+    part_size_byte: u64,
+    max_retries: usize,
+) -> Result<()> {
+    let MultipartUploadTarget {
+        bucket,
+        key,
+        path,
+        size_byte,
+        content_type,
+    } = target;
+    let plan = plan_parts(size_byte, part_size_byte);
+    let (upload_id, mut parts) = match upload {
+        Some(upload) => {
+            let parts = reusable_parts(&upload, &plan);
+            (upload.upload_id, parts)
+        }
+        None => {
+            let upload_id = client
+                .create_multipart_upload(&bucket, &key, content_type.as_deref())
+                .await?;
+            (upload_id, Vec::new())
+        }
+    };
+    let mut file = File::open(&path)
+        .await
+        .map_err(|e| AppError::new("Failed to read file", e))?;
+    if !parts.is_empty() {
+        tx.send(AppEventType::NotifyInfo("Verifying uploaded parts".into()));
+        parts = unchanged_parts(&mut file, parts, &plan).await?;
+    }
+    let pending: Vec<PartRange> = plan
+        .iter()
+        .filter(|r| !parts.iter().any(|p| p.part_number == r.part_number))
+        .copied()
+        .collect();
+
+    for range in pending {
+        let msg = format!(
+            "Uploading parts ({} out of {})",
+            parts.len() + 1,
+            plan.len()
+        );
+        tx.send(AppEventType::NotifyInfo(msg));
+
+        let bytes = read_range(&mut file, range).await?;
+        let result = upload_part_with_retries(
+            client.as_ref(),
+            &bucket,
+            &key,
+            &upload_id,
+            range.part_number,
+            bytes,
+            max_retries,
+        )
+        .await;
+        let e_tag = result.map_err(|e| {
+            let msg = format!(
+                "Failed to upload part {} of {}, upload the file again to resume: {}",
+                range.part_number,
+                plan.len(),
+                e
+            );
+            AppError::msg(msg)
+        })?;
+        parts.push(UploadedPart {
+            part_number: range.part_number,
+            e_tag,
+            size_byte: range.len,
+        });
+    }
+
+    parts.sort_by_key(|p| p.part_number);
+    client
+        .complete_multipart_upload(&bucket, &key, &upload_id, &parts)
+        .await
+}
+
+// Uploads everything read from the reader and returns the uploaded size.
+// The input is uploaded in multiple parts if it does not fit in a part,
+// and the upload is aborted on failure since the input cannot be read again to resume it.
+pub async fn upload_stream<R: AsyncRead + Unpin>(
+    client: &dyn Storage,
+    mut reader: R,
+    bucket: &str,
+    key: &str,
+    content_type: Option<&str>,
+    part_size: u64,
+    max_retries: usize,
+) -> Result<u64> {
+    let mut first = read_chunk(&mut reader, part_size).await?;
+    if (first.len() as u64) < part_size || !client.supports_multipart_upload() {
+        reader
+            .read_to_end(&mut first)
+            .await
+            .map_err(|e| AppError::new("Failed to read input", e))?;
+        let size = first.len() as u64;
+        client.put_object(bucket, key, first, content_type).await?;
+        return Ok(size);
+    }
+
+    let content_type = content_type
+        .map(String::from)
+        .or_else(|| infer::get(&first).map(|t| t.mime_type().to_string()));
+    let upload_id = client
+        .create_multipart_upload(bucket, key, content_type.as_deref())
+        .await?;
+    let result = upload_parts(
+        client,
+        &mut reader,
+        bucket,
+        key,
+        &upload_id,
+        first,
+        part_size,
+        max_retries,
+    )
+    .await;
+    if result.is_err() {
+        if let Err(e) = client.abort_multipart_upload(bucket, key, &upload_id).await {
+            tracing::warn!("Failed to abort multipart upload {}: {}", upload_id, e);
+        }
+    }
+    result
+}
+
+async fn upload_parts<R: AsyncRead + Unpin>(
+    client: &dyn Storage,
+    reader: &mut R,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    first: Vec<u8>,
+    part_size: u64,
+    max_retries: usize,
+) -> Result<u64> {
+    let mut parts = Vec::new();
+    let mut size = 0;
+    let mut chunk = first;
+    while !chunk.is_empty() {
+        let part_number = parts.len() as i32 + 1;
+        if part_number as u64 > MAX_PART_COUNT {
+            return Err(AppError::msg(
+                "The input is too large, increase upload.part_size_mib in the config",
+            ));
+        }
+        let len = chunk.len() as u64;
+        let e_tag = upload_part_with_retries(
+            client,
+            bucket,
+            key,
+            upload_id,
+            part_number,
+            chunk,
+            max_retries,
+        )
+        .await?;
+        parts.push(UploadedPart {
+            part_number,
+            e_tag,
+            size_byte: len,
+        });
+        size += len;
+
+        chunk = read_chunk(reader, part_size).await?;
+    }
+    client
+        .complete_multipart_upload(bucket, key, upload_id, &parts)
+        .await?;
+    Ok(size)
+}
+
+// Downloads the object by ranges of `part_size` concurrently and writes each part to the file at its offset
+// as soon as it is downloaded, so that the whole object is not held in memory.
+// The progress is reported with the total size of the completed parts.
+pub async fn download_in_parts(
+    client: Arc<dyn Storage>,
+    bucket: &str,
+    key: &str,
+    version_id: Option<String>,
+    if_match: Option<String>,
+    size_byte: usize,
+    part_size: usize,
+    max_concurrent_requests: usize,
+    path: PathBuf,
+    progress: Progress,
+) -> Result<PathBuf> {
+    create_dirs(&path)?;
+    let mut file = File::create(&path)
+        .await
+        .map_err(|e| AppError::new("Failed to create file", e))?;
+    file.set_len(size_byte as u64)
+        .await
+        .map_err(|e| AppError::new("Failed to create file", e))?;
+
+    let mut offsets = (0..size_byte).step_by(part_size.max(1));
+    let mut in_flight = VecDeque::new();
+    let mut downloaded = 0;
+    loop {
+        while in_flight.len() < max_concurrent_requests.max(1) {
+            let Some(offset) = offsets.next() else {
+                break;
+            };
+            let client = client.clone();
+            let bucket = bucket.to_string();
+            let key = key.to_string();
+            let version_id = version_id.clone();
+            let if_match = if_match.clone();
+            let handle = spawn(async move {
+                client
+                    .download_object_range(&bucket, &key, version_id, if_match, offset, part_size)
+                    .await
+            });
+            // the parts in flight are aborted if the download fails or is cancelled
+            in_flight.push_back((AbortOnDrop(handle), offset));
+        }
+        let Some((handle, offset)) = in_flight.pop_front() else {
+            break;
+        };
+
+        let part = handle
+            .await
+            .unwrap_or_else(|e| Err(AppError::new("Failed to download object", e)))?;
+        let len = part_size.min(size_byte - offset);
+        if part.bytes.len() != len {
+            return Err(AppError::msg(format!(
+                "Unexpected size of the part at {}: expected {} bytes, got {} bytes",
+                offset,
+                len,
+                part.bytes.len()
+            )));
+        }
+        file.seek(SeekFrom::Start(offset as u64))
+            .await
+            .map_err(|e| AppError::new("Failed to write file", e))?;
+        file.write_all(&part.bytes)
+            .await
+            .map_err(|e| AppError::new("Failed to write file", e))?;
+        downloaded += len;
+        progress(downloaded);
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::new("Failed to write file", e))?;
+    Ok(path)
+}
+
+// Reads until the chunk is full or the input ends
+async fn read_chunk<R: AsyncRead + Unpin>(reader: &mut R, size: u64) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader
+        .take(size)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(|e| AppError::new("Failed to read input", e))?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::local::LocalStorage;
+
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_plan_parts() {
+        let plan = plan_parts(12 * MIB, 5 * MIB);
+        assert_eq!(
+            plan,
+            vec![
+                PartRange {
+                    part_number: 1,
+                    offset: 0,
+                    len: 5 * MIB
+                },
+                PartRange {
+                    part_number: 2,
+                    offset: 5 * MIB,
+                    len: 5 * MIB
+                },
+                PartRange {
+                    part_number: 3,
+                    offset: 10 * MIB,
+                    len: 2 * MIB
+                },
+            ]
+        );
+
+        // the part size is raised to the minimum
+        let plan = plan_parts(6 * MIB, MIB);
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].len, 5 * MIB);
+
+        // the part size is raised to keep the number of parts within the limit
+        let plan = plan_parts(100_000 * MIB, 5 * MIB);
+        assert_eq!(plan.len(), 10_000);
+        assert_eq!(plan[0].len, 10 * MIB);
+
+        let plan = plan_parts(0, 5 * MIB);
+        assert_eq!(
+            plan,
+            vec![PartRange {
+                part_number: 1,
+                offset: 0,
+                len: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reusable_parts() {
+        let plan = plan_parts(12 * MIB, 5 * MIB);
+        let part = |part_number: i32, size_byte: u64| UploadedPart {
+            part_number,
+            e_tag: format!("etag-{}", part_number),
+            size_byte,
+        };
+        let upload = MultipartUpload {
+            upload_id: "upload-id".into(),
+            parts: vec![
+                part(1, 5 * MIB),
+                part(2, 8 * MIB),
+                part(3, 2 * MIB),
+                part(4, MIB),
+            ],
+        };
+        let actual = reusable_parts(&upload, &plan);
+        assert_eq!(actual, vec![part(1, 5 * MIB), part(3, 2 * MIB)]);
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_parts() {
+        let path = std::env::temp_dir().join(format!("stu-parts-test-{}", std::process::id()));
+        std::fs::write(&path, b"aaaabbbbcc").unwrap();
+
+        let range = |part_number: i32, offset: u64, len: u64| PartRange {
+            part_number,
+            offset,
+            len,
+        };
+        let plan = vec![range(1, 0, 4), range(2, 4, 4), range(3, 8, 2)];
+        let part = |part_number: i32, content: &[u8]| UploadedPart {
+            part_number,
+            e_tag: format!("\"{:x}\"", Md5::digest(content)),
+            size_byte: content.len() as u64,
+        };
+        let parts = vec![part(1, b"aaaa"), part(2, b"bbbb"), part(3, b"cc")];
+
+        let mut file = File::open(&path).await.unwrap();
+        let actual = unchanged_parts(&mut file, parts.clone(), &plan)
+            .await
+            .unwrap();
+        assert_eq!(actual, parts);
+
+        // the content of the second part has been changed, but the size is the same
+        std::fs::write(&path, b"aaaaBBBBcc").unwrap();
+        let mut file = File::open(&path).await.unwrap();
+        let actual = unchanged_parts(&mut file, parts.clone(), &plan)
+            .await
+            .unwrap();
+        assert_eq!(actual, vec![parts[0].clone(), parts[2].clone()]);
+
+        // the ETag which is not the MD5 (e.g. SSE-KMS) cannot be verified
+        let encrypted = vec![UploadedPart {
+            e_tag: "\"not-md5\"".into(),
+            ..parts[0].clone()
+        }];
+        let actual = unchanged_parts(&mut file, encrypted, &plan).await.unwrap();
+        assert!(actual.is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_chunk() {
+        let mut reader: &[u8] = b"abcdefg";
+        assert_eq!(read_chunk(&mut reader, 3).await.unwrap(), b"abc");
+        assert_eq!(read_chunk(&mut reader, 3).await.unwrap(), b"def");
+        assert_eq!(read_chunk(&mut reader, 3).await.unwrap(), b"g");
+        assert!(read_chunk(&mut reader, 3).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_stream_without_multipart_upload() {
+        let root = std::env::temp_dir().join(format!("stu-multipart-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("bucket")).unwrap();
+        let storage = LocalStorage::new(root.clone()).unwrap();
+
+        // the whole input is uploaded at once even if it is larger than a part
+        let reader: &[u8] = b"0123456789";
+        let size = upload_stream(&storage, reader, "bucket", "dir/out.txt", None, 4, 0)
+            .await
+            .unwrap();
+        assert_eq!(size, 10);
+        let content = std::fs::read(root.join("bucket/dir/out.txt")).unwrap();
+        assert_eq!(content, b"0123456789");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_in_parts() {
+        let root = std::env::temp_dir().join(format!("stu-download-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("bucket")).unwrap();
+        std::fs::write(root.join("bucket/file.txt"), b"0123456789").unwrap();
+        let storage: Arc<dyn Storage> = Arc::new(LocalStorage::new(root.clone()).unwrap());
+
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let progress = {
+            let reported = reported.clone();
+            Box::new(move |n| reported.lock().unwrap().push(n))
+        };
+        let path = root.join("out/file.txt");
+        let actual = download_in_parts(
+            storage,
+            "bucket",
+            "file.txt",
+            None,
+            None,
+            10,
+            3,
+            2,
+            path.clone(),
+            progress,
+        )
+        .await
+        .unwrap();
+        assert_eq!(actual, path);
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789");
+        // the progress is the total of the completed parts
+        assert_eq!(*reported.lock().unwrap(), vec![3, 6, 9, 10]);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
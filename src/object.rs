@@ -1,16 +1,19 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     fmt::{self, Debug, Formatter},
 };
 
 use chrono::{DateTime, Local};
+use itsuki::zero_indexed_enum;
+use smart_default::SmartDefault;
 
 #[derive(Clone, Debug)]
 pub struct BucketItem {
     pub name: String,
     pub s3_uri: String,
     pub arn: String,
-    pub object_url: String,
+    pub virtual_hosted_url: String,
+    pub path_style_url: String,
 }
 
 #[derive(Clone, Debug)]
@@ -19,7 +22,8 @@ pub enum ObjectItem {
         name: String,
         key: String,
         s3_uri: String,
-        object_url: String,
+        virtual_hosted_url: String,
+        path_style_url: String,
     },
     File {
         name: String,
@@ -28,7 +32,8 @@ pub enum ObjectItem {
         key: String,
         s3_uri: String,
         arn: String,
-        object_url: String,
+        virtual_hosted_url: String,
+        path_style_url: String,
         e_tag: String,
     },
 }
@@ -41,6 +46,14 @@ impl ObjectItem {
         }
     }
 
+    // the keys of the folders end with '/'
+    pub fn key(&self) -> &str {
+        match self {
+            ObjectItem::Dir { key, .. } => key,
+            ObjectItem::File { key, .. } => key,
+        }
+    }
+
     pub fn size_byte(&self) -> Option<usize> {
         match self {
             ObjectItem::Dir { .. } => None,
@@ -64,10 +77,66 @@ pub struct FileDetail {
     pub e_tag: String,
     pub content_type: String,
     pub storage_class: String,
+    pub cache_control: String,
+    pub content_disposition: String,
+    // user-defined metadata without the prefix (`x-amz-meta-`), sorted by name
+    pub user_metadata: Vec<(String, String)>,
+    // Object Lock mode (`GOVERNANCE` or `COMPLIANCE`), empty if the object is not locked
+    pub object_lock_mode: String,
+    pub object_lock_retain_until: Option<DateTime<Local>>,
+    // None if the bucket does not support Object Lock or the status is not allowed to read
+    pub legal_hold: Option<bool>,
     pub key: String,
     pub s3_uri: String,
     pub arn: String,
-    pub object_url: String,
+    pub virtual_hosted_url: String,
+    pub path_style_url: String,
+}
+
+impl FileDetail {
+    pub fn metadata(&self) -> ObjectMetadata {
+        ObjectMetadata {
+            content_type: self.content_type.clone(),
+            cache_control: self.cache_control.clone(),
+            content_disposition: self.content_disposition.clone(),
+            user_metadata: self.user_metadata.clone(),
+        }
+    }
+
+    // Returns None if neither the retention period nor the legal hold prevents the deletion
+    pub fn locked_object(&self, now: DateTime<Local>) -> Option<LockedObject> {
+        let retain_until = self.object_lock_retain_until.filter(|t| *t > now);
+        let legal_hold = self.legal_hold == Some(true);
+        if retain_until.is_none() && !legal_hold {
+            return None;
+        }
+        Some(LockedObject {
+            key: self.key.clone(),
+            mode: self.object_lock_mode.clone(),
+            retain_until,
+            legal_hold,
+        })
+    }
+}
+
+// An object whose current version cannot be deleted until the lock is released
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedObject {
+    pub key: String,
+    pub mode: String,
+    // None if the retention period has already expired
+    pub retain_until: Option<DateTime<Local>>,
+    pub legal_hold: bool,
+}
+
+// The headers and the user-defined metadata that are replaced at once when editing the metadata.
+// Empty values are not set.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ObjectMetadata {
+    pub content_type: String,
+    pub cache_control: String,
+    pub content_disposition: String,
+    pub user_metadata: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,8 +145,22 @@ pub struct FileVersion {
     pub size_byte: usize,
     pub last_modified: DateTime<Local>,
     pub e_tag: String,
-    #[allow(dead_code)]
     pub is_latest: bool,
+    // a delete marker has no content, the size is 0 and the e_tag is empty
+    pub is_delete_marker: bool,
+}
+
+// Position to resume ListObjectVersions from
+#[derive(Debug, Clone)]
+pub struct FileVersionsMarker {
+    pub key_marker: String,
+    pub version_id_marker: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct FileVersions {
+    pub versions: Vec<FileVersion>,
+    pub next_marker: Option<FileVersionsMarker>,
 }
 
 impl FileVersion {
@@ -85,20 +168,512 @@ impl FileVersion {
         format!("{}?versionId={}", base_file_detail.s3_uri, self.version_id)
     }
 
-    pub fn object_url(&self, base_file_detail: &FileDetail) -> String {
+    pub fn virtual_hosted_url(&self, base_file_detail: &FileDetail) -> String {
+        format!(
+            "{}?versionId={}",
+            base_file_detail.virtual_hosted_url, self.version_id
+        )
+    }
+
+    pub fn path_style_url(&self, base_file_detail: &FileDetail) -> String {
         format!(
             "{}?versionId={}",
-            base_file_detail.object_url, self.version_id
+            base_file_detail.path_style_url, self.version_id
         )
     }
 }
 
+// The ETag is the MD5 digest of the content unless the object was uploaded in multiple parts.
+// A multipart ETag is the MD5 digest of the concatenated part digests followed by the part count,
+// so it cannot be compared with the MD5 digest of the content.
+// (The ETags of the objects encrypted with SSE-KMS or SSE-C are not MD5 digests either,
+// but they cannot be distinguished by their format)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ETagType {
+    Md5,
+    Multipart(usize),
+    Unknown,
+}
+
+impl ETagType {
+    pub fn parse(e_tag: &str) -> ETagType {
+        let e_tag = e_tag.trim_matches('"');
+        let is_md5 = |s: &str| s.len() == 32 && s.chars().all(|c| c.is_ascii_hexdigit());
+        if is_md5(e_tag) {
+            return ETagType::Md5;
+        }
+        match e_tag.split_once('-') {
+            Some((digest, parts)) if is_md5(digest) => match parts.parse() {
+                Ok(parts) => ETagType::Multipart(parts),
+                Err(_) => ETagType::Unknown,
+            },
+            _ => ETagType::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ETagType::Multipart(parts) => format!("ETag (multipart, {} parts)", parts),
+            ETagType::Md5 | ETagType::Unknown => "ETag".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadObjectInfo {
+    pub key: String,
+    pub size_byte: usize,
+    pub last_modified: DateTime<Local>,
+    pub e_tag: String,
+}
+
+impl DownloadObjectInfo {
+    pub fn md5_e_tag(&self) -> Option<&str> {
+        let is_md5 = ETagType::parse(&self.e_tag) == ETagType::Md5;
+        is_md5.then_some(self.e_tag.trim_matches('"'))
+    }
+
+    // Returns the reason if the downloaded file does not match the object.
+    pub fn verify_local_file(&self, size_byte: usize, md5: Option<&str>) -> Option<String> {
+        if size_byte != self.size_byte {
+            return Some(format!(
+                "Size mismatch: expected {} bytes, found {} bytes",
+                self.size_byte, size_byte
+            ));
+        }
+        match (self.md5_e_tag(), md5) {
+            (Some(e_tag), Some(md5)) if !e_tag.eq_ignore_ascii_case(md5) => {
+                Some(format!("ETag mismatch: expected {}, found {}", e_tag, md5))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadFailure {
+    pub obj: DownloadObjectInfo,
+    pub error: String,
+}
+
+#[derive(Debug, Default)]
+pub struct DownloadObjectsOutcome {
+    pub succeeded: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<DownloadFailure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeleteFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TagFailure {
+    pub key: String,
+    pub error: String,
+}
+
+// A page of the object list, the next page is loaded with the token
+#[derive(Debug, Default)]
+pub struct ObjectsPage {
+    pub items: Vec<ObjectItem>,
+    pub next_token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectSummary {
+    pub key: String,
+    pub size_byte: usize,
+    pub last_modified: DateTime<Local>,
+    pub e_tag: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateObjectGroup {
+    pub e_tag: String,
+    pub size_byte: usize,
+    // sorted by last modified, oldest first
+    pub objects: Vec<ObjectSummary>,
+}
+
+impl DuplicateObjectGroup {
+    pub fn reclaimable_size_byte(&self) -> usize {
+        self.size_byte * (self.objects.len() - 1)
+    }
+}
+
+// Groups objects with the same ETag and size.
+// Note that objects with the same content may have different ETags if they were uploaded
+// with multipart upload using different part sizes, so they are not detected.
+pub fn find_duplicate_objects(objs: Vec<ObjectSummary>) -> Vec<DuplicateObjectGroup> {
+    let mut map: HashMap<(String, usize), Vec<ObjectSummary>> = HashMap::new();
+    // "directory" objects are not duplicates of each other
+    for obj in objs.into_iter().filter(|obj| !obj.key.ends_with('/')) {
+        map.entry((obj.e_tag.clone(), obj.size_byte))
+            .or_default()
+            .push(obj);
+    }
+
+    let mut groups: Vec<DuplicateObjectGroup> = map
+        .into_iter()
+        .filter(|(_, objects)| objects.len() > 1)
+        .map(|((e_tag, size_byte), mut objects)| {
+            objects.sort_by(|a, b| {
+                a.last_modified
+                    .cmp(&b.last_modified)
+                    .then_with(|| a.key.cmp(&b.key))
+            });
+            DuplicateObjectGroup {
+                e_tag,
+                size_byte,
+                objects,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.reclaimable_size_byte()
+            .cmp(&a.reclaimable_size_byte())
+            .then_with(|| a.objects[0].key.cmp(&b.objects[0].key))
+    });
+    groups
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupObjectType {
+    // "directory" object with nothing but other empty "directory" objects under it
+    EmptyFolder,
+    ZeroByteObject,
+}
+
+impl CleanupObjectType {
+    pub fn name(&self) -> &str {
+        match self {
+            CleanupObjectType::EmptyFolder => "Empty folder",
+            CleanupObjectType::ZeroByteObject => "Zero-byte object",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CleanupObject {
+    pub key: String,
+    pub object_type: CleanupObjectType,
+    pub last_modified: DateTime<Local>,
+}
+
+pub fn find_cleanup_objects(objs: &[ObjectSummary]) -> Vec<CleanupObject> {
+    let mut file_keys: Vec<&str> = objs
+        .iter()
+        .map(|obj| obj.key.as_str())
+        .filter(|key| !key.ends_with('/'))
+        .collect();
+    file_keys.sort();
+    let has_file_under = |prefix: &str| {
+        let i = file_keys.partition_point(|key| *key < prefix);
+        file_keys.get(i).is_some_and(|key| key.starts_with(prefix))
+    };
+
+    let mut cleanup_objs: Vec<CleanupObject> = objs
+        .iter()
+        .filter_map(|obj| {
+            let object_type = if obj.key.ends_with('/') {
+                if has_file_under(&obj.key) {
+                    return None;
+                }
+                CleanupObjectType::EmptyFolder
+            } else if obj.size_byte == 0 {
+                CleanupObjectType::ZeroByteObject
+            } else {
+                return None;
+            };
+            Some(CleanupObject {
+                key: obj.key.clone(),
+                object_type,
+                last_modified: obj.last_modified,
+            })
+        })
+        .collect();
+    cleanup_objs.sort_by(|a, b| a.key.cmp(&b.key));
+    cleanup_objs
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectDiffType {
+    OnlyA,
+    OnlyB,
+    Different,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectDiff {
+    // relative to the compared prefixes
+    pub key: String,
+    pub diff_type: ObjectDiffType,
+    pub a: Option<ObjectSummary>,
+    pub b: Option<ObjectSummary>,
+}
+
+// Compares the objects under two prefixes by their keys relative to each prefix.
+// Objects are considered different if the size or the ETag differs.
+// As with duplicates, the same content uploaded with different multipart settings is reported as different.
+pub fn compare_objects(
+    a_objs: Vec<ObjectSummary>,
+    a_prefix: &str,
+    b_objs: Vec<ObjectSummary>,
+    b_prefix: &str,
+) -> Vec<ObjectDiff> {
+    let relative = |objs: Vec<ObjectSummary>, prefix: &str| -> HashMap<String, ObjectSummary> {
+        objs.into_iter()
+            .filter(|obj| !obj.key.ends_with('/'))
+            .map(|obj| {
+                let key = obj.key.strip_prefix(prefix).unwrap_or(&obj.key).to_string();
+                (key, obj)
+            })
+            .collect()
+    };
+    let mut a_map = relative(a_objs, a_prefix);
+    let b_map = relative(b_objs, b_prefix);
+
+    let mut diffs: Vec<ObjectDiff> = Vec::new();
+    for (key, b) in b_map {
+        let diff = match a_map.remove(&key) {
+            None => ObjectDiff {
+                key,
+                diff_type: ObjectDiffType::OnlyB,
+                a: None,
+                b: Some(b),
+            },
+            Some(a) if a.size_byte != b.size_byte || a.e_tag != b.e_tag => ObjectDiff {
+                key,
+                diff_type: ObjectDiffType::Different,
+                a: Some(a),
+                b: Some(b),
+            },
+            Some(_) => continue,
+        };
+        diffs.push(diff);
+    }
+    diffs.extend(a_map.into_iter().map(|(key, a)| ObjectDiff {
+        key,
+        diff_type: ObjectDiffType::OnlyA,
+        a: Some(a),
+        b: None,
+    }));
+    diffs.sort_by(|a, b| a.key.cmp(&b.key));
+    diffs
+}
+
+// Inserts the folder in the order ListObjectsV2 returns, where the folders come first sorted by name.
+// Returns the index of the inserted folder, or None if the folder already exists.
+pub fn insert_dir_item(items: &mut Vec<ObjectItem>, item: ObjectItem) -> Option<usize> {
+    let dirs = items
+        .iter()
+        .take_while(|i| matches!(i, ObjectItem::Dir { .. }))
+        .count();
+    match items[..dirs].binary_search_by(|i| i.name().cmp(item.name())) {
+        Ok(_) => None,
+        Err(pos) => {
+            items.insert(pos, item);
+            Some(pos)
+        }
+    }
+}
+
+pub const MAX_CONCURRENT_REQUESTS_LIMIT: usize = 32;
+
+// Objects larger than this cannot be copied with a single CopyObject request
+pub const COPY_OBJECT_MAX_SIZE_BYTE: usize = 5 * 1024 * 1024 * 1024;
+
+// Storage classes that objects can be transitioned to by copying them onto themselves
+#[derive(Default)]
+#[zero_indexed_enum]
+pub enum StorageClassType {
+    #[default]
+    Standard,
+    IntelligentTiering,
+    StandardIa,
+    OnezoneIa,
+    GlacierIr,
+    Glacier,
+    DeepArchive,
+}
+
+impl StorageClassType {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Standard => "STANDARD",
+            Self::IntelligentTiering => "INTELLIGENT_TIERING",
+            Self::StandardIa => "STANDARD_IA",
+            Self::OnezoneIa => "ONEZONE_IA",
+            Self::GlacierIr => "GLACIER_IR",
+            Self::Glacier => "GLACIER",
+            Self::DeepArchive => "DEEP_ARCHIVE",
+        }
+    }
+
+    // Smaller objects are charged as if they were this size
+    pub fn min_billable_size_byte(&self) -> usize {
+        match self {
+            Self::StandardIa | Self::OnezoneIa | Self::GlacierIr => 128 * 1024,
+            _ => 0,
+        }
+    }
+
+    // Objects deleted or transitioned earlier are charged for the remaining days
+    pub fn min_storage_days(&self) -> usize {
+        match self {
+            Self::Standard | Self::IntelligentTiering => 0,
+            Self::StandardIa | Self::OnezoneIa => 30,
+            Self::GlacierIr | Self::Glacier => 90,
+            Self::DeepArchive => 180,
+        }
+    }
+
+    pub fn requires_restore(&self) -> bool {
+        matches!(self, Self::Glacier | Self::DeepArchive)
+    }
+
+    pub fn billable_size_byte(&self, sizes: impl Iterator<Item = usize>) -> usize {
+        sizes.map(|s| s.max(self.min_billable_size_byte())).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, SmartDefault)]
+pub struct DownloadObjectsOptions {
+    pub output_type: DownloadOutputType,
+    pub skip_type: DownloadSkipType,
+    pub layout_type: DownloadLayoutType,
+    #[default = 1]
+    pub max_concurrent_requests: usize,
+}
+
+#[derive(Default)]
+#[zero_indexed_enum]
+pub enum DownloadOutputType {
+    #[default]
+    Files,
+    TarGz,
+    Zip,
+}
+
+impl DownloadOutputType {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Files => "Files",
+            Self::TarGz => "Archive (.tar.gz)",
+            Self::Zip => "Archive (.zip)",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Files => "",
+            Self::TarGz => ".tar.gz",
+            Self::Zip => ".zip",
+        }
+    }
+}
+
+#[derive(Default)]
+#[zero_indexed_enum]
+pub enum DownloadSkipType {
+    #[default]
+    Overwrite,
+    SkipSameSize,
+    NewerOnly,
+}
+
+impl DownloadSkipType {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Overwrite => "Overwrite",
+            Self::SkipSameSize => "Skip if same size",
+            Self::NewerOnly => "Download only if newer",
+        }
+    }
+
+    // `local` is the size and the modified time of the existing local file
+    pub fn should_skip(
+        &self,
+        obj: &DownloadObjectInfo,
+        local: Option<(usize, DateTime<Local>)>,
+    ) -> bool {
+        match (self, local) {
+            (_, None) | (Self::Overwrite, _) => false,
+            (Self::SkipSameSize, Some((size_byte, _))) => size_byte == obj.size_byte,
+            (Self::NewerOnly, Some((_, last_modified))) => obj.last_modified <= last_modified,
+        }
+    }
+}
+
+#[derive(Default)]
+#[zero_indexed_enum]
+pub enum DownloadLayoutType {
+    #[default]
+    PreserveStructure,
+    Flatten,
+}
+
+impl DownloadLayoutType {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::PreserveStructure => "Preserve structure",
+            Self::Flatten => "Flatten",
+        }
+    }
+
+    // Returns the path relative to the download directory for the object under the prefix.
+    // The objects are passed one by one in order, and `used` keeps the paths returned so far
+    // so that colliding file names are renamed like `file (1).txt` when flattening.
+    pub fn relative_path(&self, prefix: &str, key: &str, used: &mut HashSet<String>) -> String {
+        match self {
+            Self::PreserveStructure => key.strip_prefix(prefix).unwrap_or(key).to_string(),
+            Self::Flatten => {
+                let name = key.rsplit('/').next().unwrap_or_default();
+                let mut path = name.to_string();
+                let mut n = 1;
+                while used.contains(&path) {
+                    path = numbered_file_name(name, n);
+                    n += 1;
+                }
+                used.insert(path.clone());
+                path
+            }
+        }
+    }
+}
+
+fn numbered_file_name(name: &str, n: usize) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{} ({}).{}", stem, n, ext),
+        _ => format!("{} ({})", name, n),
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AppObjects {
     bucket_items: Vec<BucketItem>,
     object_items_map: HashMap<ObjectKey, Vec<ObjectItem>>,
     detail_map: HashMap<ObjectKey, FileDetail>,
-    versions_map: HashMap<ObjectKey, Vec<FileVersion>>,
+    versions_map: HashMap<ObjectKey, FileVersions>,
+    // recently previewed objects, the most recently used one is at the front
+    preview_cache: VecDeque<(PreviewCacheKey, RawObject)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PreviewCacheKey {
+    key: ObjectKey,
+    version_id: Option<String>,
+    // the cached object is not used if the object has been overwritten
+    e_tag: String,
 }
 
 impl AppObjects {
@@ -122,7 +697,7 @@ impl AppObjects {
         self.detail_map.get(key)
     }
 
-    pub fn get_object_versions(&self, key: &ObjectKey) -> Option<&Vec<FileVersion>> {
+    pub fn get_object_versions(&self, key: &ObjectKey) -> Option<&FileVersions> {
         self.versions_map.get(key)
     }
 
@@ -130,14 +705,59 @@ impl AppObjects {
         self.detail_map.insert(key, detail);
     }
 
-    pub fn set_object_versions(&mut self, key: ObjectKey, versions: Vec<FileVersion>) {
+    pub fn set_object_versions(&mut self, key: ObjectKey, versions: FileVersions) {
         self.versions_map.insert(key, versions);
     }
 
+    pub fn append_object_versions(&mut self, key: ObjectKey, versions: FileVersions) {
+        let current = self.versions_map.entry(key).or_default();
+        current.versions.extend(versions.versions);
+        current.next_marker = versions.next_marker;
+    }
+
+    pub fn get_preview_object(
+        &mut self,
+        key: &ObjectKey,
+        version_id: Option<&str>,
+        e_tag: &str,
+    ) -> Option<RawObject> {
+        let i = self.preview_cache.iter().position(|(k, _)| {
+            &k.key == key && k.version_id.as_deref() == version_id && k.e_tag == e_tag
+        })?;
+        let entry = self.preview_cache.remove(i)?;
+        let obj = entry.1.clone();
+        self.preview_cache.push_front(entry);
+        Some(obj)
+    }
+
+    // `capacity` is the number of objects to keep, and 0 disables caching
+    pub fn set_preview_object(
+        &mut self,
+        key: ObjectKey,
+        version_id: Option<String>,
+        e_tag: String,
+        obj: RawObject,
+        capacity: usize,
+    ) {
+        let cache_key = PreviewCacheKey {
+            key,
+            version_id,
+            e_tag,
+        };
+        self.preview_cache
+            .retain(|(k, _)| k.key != cache_key.key || k.version_id != cache_key.version_id);
+        if capacity == 0 {
+            return;
+        }
+        self.preview_cache.push_front((cache_key, obj));
+        self.preview_cache.truncate(capacity);
+    }
+
     pub fn clear_object_items_under(&mut self, key: &ObjectKey) {
         self.object_items_map.retain(|k, _| !k.has_prefix(key));
         self.detail_map.retain(|k, _| !k.has_prefix(key));
         self.versions_map.retain(|k, _| !k.has_prefix(key));
+        self.preview_cache.retain(|(k, _)| !k.key.has_prefix(key));
     }
 
     pub fn clear_all(&mut self) {
@@ -145,6 +765,7 @@ impl AppObjects {
         self.object_items_map.clear();
         self.detail_map.clear();
         self.versions_map.clear();
+        self.preview_cache.clear();
     }
 }
 
@@ -215,6 +836,35 @@ mod tests {
         assert_eq!(key.has_prefix(&prefix), expected);
     }
 
+    #[rstest]
+    #[case(Some("2024-01-03 00:00:00"), None, true, false)]
+    #[case(Some("2024-01-01 00:00:00"), None, false, false)]
+    #[case(Some("2024-01-01 00:00:00"), Some(true), false, true)]
+    #[case(None, Some(false), false, false)]
+    #[case(None, None, false, false)]
+    fn test_file_detail_locked_object(
+        #[case] retain_until: Option<&str>,
+        #[case] legal_hold: Option<bool>,
+        #[case] retained: bool,
+        #[case] held: bool,
+    ) {
+        let now = parse_datetime("2024-01-02 00:00:00");
+        let detail = FileDetail {
+            object_lock_mode: "GOVERNANCE".to_string(),
+            object_lock_retain_until: retain_until.map(parse_datetime),
+            legal_hold,
+            ..file_detail()
+        };
+        let actual = detail.locked_object(now);
+        if retained || held {
+            let locked = actual.unwrap();
+            assert_eq!(locked.retain_until.is_some(), retained);
+            assert_eq!(locked.legal_hold, held);
+        } else {
+            assert!(actual.is_none());
+        }
+    }
+
     #[test]
     fn test_clear_object_items_under() {
         let mut app_objects = AppObjects::default();
@@ -253,6 +903,331 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[rstest]
+    #[case(DownloadSkipType::Overwrite, None, false)]
+    #[case(DownloadSkipType::Overwrite, Some((10, "2024-01-02 00:00:00")), false)]
+    #[case(DownloadSkipType::SkipSameSize, None, false)]
+    #[case(DownloadSkipType::SkipSameSize, Some((10, "2024-01-01 00:00:00")), true)]
+    #[case(DownloadSkipType::SkipSameSize, Some((20, "2024-01-02 00:00:00")), false)]
+    #[case(DownloadSkipType::NewerOnly, None, false)]
+    #[case(DownloadSkipType::NewerOnly, Some((20, "2024-01-02 00:00:00")), true)]
+    #[case(DownloadSkipType::NewerOnly, Some((20, "2024-01-01 12:00:00")), true)]
+    #[case(DownloadSkipType::NewerOnly, Some((10, "2024-01-01 00:00:00")), false)]
+    fn test_download_skip_type_should_skip(
+        #[case] skip_type: DownloadSkipType,
+        #[case] local: Option<(usize, &str)>,
+        #[case] expected: bool,
+    ) {
+        let obj = DownloadObjectInfo {
+            key: "a/b.txt".to_string(),
+            size_byte: 10,
+            last_modified: parse_datetime("2024-01-01 12:00:00"),
+            e_tag: "".to_string(),
+        };
+        let local = local.map(|(size_byte, s)| (size_byte, parse_datetime(s)));
+        assert_eq!(skip_type.should_skip(&obj, local), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "\"0cc175b9c0f1b6a831c399e269772661\"",
+        1,
+        Some("0cc175b9c0f1b6a831c399e269772661"),
+        None
+    )]
+    #[case("\"0cc175b9c0f1b6a831c399e269772661\"", 1, Some("92eb5ffee6ae2fec3ad71c777531578f"), Some("ETag mismatch: expected 0cc175b9c0f1b6a831c399e269772661, found 92eb5ffee6ae2fec3ad71c777531578f"))]
+    #[case(
+        "\"0cc175b9c0f1b6a831c399e269772661\"",
+        0,
+        None,
+        Some("Size mismatch: expected 1 bytes, found 0 bytes")
+    )]
+    #[case(
+        "\"0cc175b9c0f1b6a831c399e269772661-2\"",
+        1,
+        Some("92eb5ffee6ae2fec3ad71c777531578f"),
+        None
+    )]
+    fn test_download_object_info_verify_local_file(
+        #[case] e_tag: &str,
+        #[case] size_byte: usize,
+        #[case] md5: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        let obj = DownloadObjectInfo {
+            key: "a/b.txt".to_string(),
+            size_byte: 1,
+            last_modified: parse_datetime("2024-01-01 12:00:00"),
+            e_tag: e_tag.to_string(),
+        };
+        let actual = obj.verify_local_file(size_byte, md5);
+        assert_eq!(actual.as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("0cc175b9c0f1b6a831c399e269772661", ETagType::Md5)]
+    #[case("\"0cc175b9c0f1b6a831c399e269772661\"", ETagType::Md5)]
+    #[case("\"0cc175b9c0f1b6a831c399e269772661-24\"", ETagType::Multipart(24))]
+    #[case("0cc175b9c0f1b6a831c399e269772661-x", ETagType::Unknown)]
+    #[case("bef684de-a260-48a4-8178-8a535ecccadb", ETagType::Unknown)]
+    #[case("", ETagType::Unknown)]
+    fn test_e_tag_type_parse(#[case] e_tag: &str, #[case] expected: ETagType) {
+        assert_eq!(ETagType::parse(e_tag), expected);
+    }
+
+    #[test]
+    fn test_download_layout_type_relative_path() {
+        let keys = [
+            "x/a.txt",
+            "x/y/a.txt",
+            "x/y/b",
+            "x/z/a.txt",
+            "x/z/b",
+            "x/z/.c",
+            "x/.c",
+        ];
+        let relative_paths = |layout_type: DownloadLayoutType| {
+            let mut used = HashSet::new();
+            keys.iter()
+                .map(|key| layout_type.relative_path("x/", key, &mut used))
+                .collect::<Vec<_>>()
+        };
+
+        let actual = relative_paths(DownloadLayoutType::PreserveStructure);
+        let expected = vec!["a.txt", "y/a.txt", "y/b", "z/a.txt", "z/b", "z/.c", ".c"];
+        assert_eq!(actual, expected);
+
+        let actual = relative_paths(DownloadLayoutType::Flatten);
+        let expected = vec![
+            "a.txt",
+            "a (1).txt",
+            "b",
+            "a (2).txt",
+            "b (1)",
+            ".c",
+            ".c (1)",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_find_duplicate_objects() {
+        let summary =
+            |key: &str, size_byte: usize, e_tag: &str, last_modified: &str| ObjectSummary {
+                key: key.to_string(),
+                size_byte,
+                last_modified: parse_datetime(last_modified),
+                e_tag: e_tag.to_string(),
+            };
+        let objs = vec![
+            summary("a", 10, "x", "2024-01-03 00:00:00"),
+            summary("b", 10, "x", "2024-01-01 00:00:00"),
+            summary("c", 10, "y", "2024-01-01 00:00:00"),
+            summary("d", 100, "z", "2024-01-01 00:00:00"),
+            summary("e", 100, "z", "2024-01-01 00:00:00"),
+            summary("f", 20, "x", "2024-01-01 00:00:00"),
+            summary("g", 10, "x", "2024-01-02 00:00:00"),
+            summary("h/", 0, "empty", "2024-01-01 00:00:00"),
+            summary("i/", 0, "empty", "2024-01-01 00:00:00"),
+        ];
+
+        let groups = find_duplicate_objects(objs);
+        let actual: Vec<(usize, Vec<&str>)> = groups
+            .iter()
+            .map(|g| {
+                let keys = g.objects.iter().map(|o| o.key.as_str()).collect();
+                (g.reclaimable_size_byte(), keys)
+            })
+            .collect();
+        assert_eq!(
+            actual,
+            vec![(100, vec!["d", "e"]), (20, vec!["b", "g", "a"])]
+        );
+    }
+
+    #[test]
+    fn test_find_cleanup_objects() {
+        let objs: Vec<ObjectSummary> = [
+            ("a/", 0),
+            ("a/b/", 0),
+            ("a/b/c/", 0),
+            ("a/d/", 0),
+            ("a/d/file.txt", 10),
+            ("a/d/empty.txt", 0),
+            ("a/e", 0),
+            ("ab.txt", 5),
+            ("f/", 0),
+        ]
+        .iter()
+        .map(|(key, size_byte)| ObjectSummary {
+            key: key.to_string(),
+            size_byte: *size_byte,
+            last_modified: parse_datetime("2024-01-01 00:00:00"),
+            e_tag: "x".to_string(),
+        })
+        .collect();
+
+        let cleanup_objs = find_cleanup_objects(&objs);
+        let actual: Vec<(&str, CleanupObjectType)> = cleanup_objs
+            .iter()
+            .map(|obj| (obj.key.as_str(), obj.object_type))
+            .collect();
+        let expected = vec![
+            ("a/b/", CleanupObjectType::EmptyFolder),
+            ("a/b/c/", CleanupObjectType::EmptyFolder),
+            ("a/d/empty.txt", CleanupObjectType::ZeroByteObject),
+            ("a/e", CleanupObjectType::ZeroByteObject),
+            ("f/", CleanupObjectType::EmptyFolder),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_compare_objects() {
+        let summaries = |objs: &[(&str, usize, &str)]| -> Vec<ObjectSummary> {
+            objs.iter()
+                .map(|(key, size_byte, e_tag)| ObjectSummary {
+                    key: key.to_string(),
+                    size_byte: *size_byte,
+                    last_modified: parse_datetime("2024-01-01 00:00:00"),
+                    e_tag: e_tag.to_string(),
+                })
+                .collect()
+        };
+        let a_objs = summaries(&[
+            ("x/", 0, "d"),
+            ("x/a.txt", 10, "a"),
+            ("x/b.txt", 10, "b"),
+            ("x/c/d.txt", 10, "d"),
+            ("x/e.txt", 10, "e"),
+        ]);
+        let b_objs = summaries(&[
+            ("y/z/a.txt", 10, "a"),
+            ("y/z/b.txt", 10, "bb"),
+            ("y/z/c/d.txt", 20, "d"),
+            ("y/z/f.txt", 10, "f"),
+        ]);
+
+        let diffs = compare_objects(a_objs, "x/", b_objs, "y/z/");
+        let actual: Vec<(&str, ObjectDiffType)> = diffs
+            .iter()
+            .map(|diff| (diff.key.as_str(), diff.diff_type))
+            .collect();
+        let expected = vec![
+            ("b.txt", ObjectDiffType::Different),
+            ("c/d.txt", ObjectDiffType::Different),
+            ("e.txt", ObjectDiffType::OnlyA),
+            ("f.txt", ObjectDiffType::OnlyB),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_insert_dir_item() {
+        let dir = |name: &str| ObjectItem::Dir {
+            name: name.to_string(),
+            key: format!("{}/", name),
+            s3_uri: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
+        };
+        let file = ObjectItem::File {
+            name: "a.txt".to_string(),
+            size_byte: 0,
+            last_modified: parse_datetime("2024-01-01 00:00:00"),
+            key: "a.txt".to_string(),
+            s3_uri: "".to_string(),
+            arn: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
+            e_tag: "".to_string(),
+        };
+        let mut items = vec![dir("a"), dir("c"), file];
+
+        assert_eq!(insert_dir_item(&mut items, dir("b")), Some(1));
+        assert_eq!(insert_dir_item(&mut items, dir("d")), Some(3));
+        assert_eq!(insert_dir_item(&mut items, dir("c")), None);
+
+        let names: Vec<&str> = items.iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["a", "b", "c", "d", "a.txt"]);
+    }
+
+    #[test]
+    fn test_storage_class_type_billable_size_byte() {
+        let sizes = [10, 200 * 1024];
+        assert_eq!(
+            StorageClassType::Standard.billable_size_byte(sizes.into_iter()),
+            10 + 200 * 1024
+        );
+        assert_eq!(
+            StorageClassType::StandardIa.billable_size_byte(sizes.into_iter()),
+            128 * 1024 + 200 * 1024
+        );
+    }
+
+    #[test]
+    fn test_preview_cache() {
+        let mut objects = AppObjects::default();
+        let obj = |s: &str| RawObject {
+            bytes: s.as_bytes().to_vec(),
+        };
+        let get = |objects: &mut AppObjects, name: &str, e_tag: &str| {
+            objects
+                .get_preview_object(&object_key("b", &[name]), None, e_tag)
+                .map(|o| String::from_utf8(o.bytes).unwrap())
+        };
+
+        objects.set_preview_object(object_key("b", &["x"]), None, "1".into(), obj("x"), 2);
+        objects.set_preview_object(object_key("b", &["y"]), None, "1".into(), obj("y"), 2);
+        assert_eq!(get(&mut objects, "x", "1").as_deref(), Some("x"));
+        assert_eq!(get(&mut objects, "x", "2"), None);
+
+        // y is the least recently used
+        objects.set_preview_object(object_key("b", &["z"]), None, "1".into(), obj("z"), 2);
+        assert_eq!(get(&mut objects, "y", "1"), None);
+        assert_eq!(get(&mut objects, "x", "1").as_deref(), Some("x"));
+        assert_eq!(get(&mut objects, "z", "1").as_deref(), Some("z"));
+
+        // the object has been overwritten
+        objects.set_preview_object(object_key("b", &["z"]), None, "2".into(), obj("z2"), 2);
+        assert_eq!(get(&mut objects, "z", "1"), None);
+        assert_eq!(get(&mut objects, "z", "2").as_deref(), Some("z2"));
+        assert_eq!(get(&mut objects, "x", "1").as_deref(), Some("x"));
+
+        objects.clear_object_items_under(&object_key("b", &[]));
+        assert_eq!(get(&mut objects, "x", "1"), None);
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+
+    fn file_detail() -> FileDetail {
+        FileDetail {
+            name: "a.txt".to_string(),
+            size_byte: 1024,
+            last_modified: parse_datetime("2024-01-01 00:00:00"),
+            e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
+            content_type: "text/plain".to_string(),
+            storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
+            key: "a.txt".to_string(),
+            s3_uri: "s3://bucket-1/a.txt".to_string(),
+            arn: "arn:aws:s3:::bucket-1/a.txt".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/a.txt"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/a.txt".to_string(),
+        }
+    }
+
     fn object_key(bucket_name: &str, object_path: &[&str]) -> ObjectKey {
         ObjectKey {
             bucket_name: bucket_name.to_string(),
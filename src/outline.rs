@@ -0,0 +1,599 @@
+use std::collections::HashMap;
+
+use crate::util::extension_from_file_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineFormat {
+    Yaml,
+    Toml,
+    Ini,
+}
+
+impl OutlineFormat {
+    pub fn from_file_name(file_name: &str) -> Option<OutlineFormat> {
+        match extension_from_file_name(file_name).to_lowercase().as_str() {
+            "yaml" | "yml" => Some(OutlineFormat::Yaml),
+            "toml" => Some(OutlineFormat::Toml),
+            "ini" | "cfg" => Some(OutlineFormat::Ini),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+// The structure of a config file, built line by line without validating the syntax,
+// so that the part of a large object which has been loaded can also be handled.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outline {
+    // the key path of each line, None for the blank and comment lines
+    paths: Vec<Option<String>>,
+    // the foldable ranges of the lines (both inclusive, 0-based)
+    sections: Vec<(usize, usize)>,
+}
+
+impl Outline {
+    pub fn parse(format: OutlineFormat, s: &str) -> Outline {
+        let mut outline = match format {
+            OutlineFormat::Yaml => parse_yaml(s),
+            OutlineFormat::Toml => parse_toml(s),
+            OutlineFormat::Ini => parse_ini(s),
+        };
+        outline.sections.retain(|(start, end)| start < end);
+        outline.sections.sort();
+        outline.sections.dedup();
+        outline
+    }
+
+    pub fn key_path(&self, line: usize) -> Option<&str> {
+        self.paths.get(line).and_then(|p| p.as_deref())
+    }
+
+    // The largest section starting at the line
+    pub fn section_starting_at(&self, line: usize) -> Option<(usize, usize)> {
+        self.sections
+            .iter()
+            .filter(|(start, _)| *start == line)
+            .max_by_key(|(_, end)| *end)
+            .copied()
+    }
+
+    // The section starting at the line, or the innermost section containing the line
+    pub fn section_at(&self, line: usize) -> Option<(usize, usize)> {
+        self.section_starting_at(line).or_else(|| {
+            self.sections
+                .iter()
+                .filter(|(start, end)| *start <= line && line <= *end)
+                .min_by_key(|(start, end)| end - start)
+                .copied()
+        })
+    }
+}
+
+fn format_path(segments: &[Segment]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                if key.is_empty() || key.contains(['.', ' ', '[', ']', '"']) {
+                    path.push_str(&format!("\"{}\"", key.replace('"', "\\\"")));
+                } else {
+                    path.push_str(key);
+                }
+            }
+            Segment::Index(i) => {
+                path.push_str(&format!("[{}]", i));
+            }
+        }
+    }
+    path
+}
+
+fn is_comment(trimmed: &str, markers: &[char]) -> bool {
+    trimmed.starts_with(markers)
+}
+
+#[derive(Debug)]
+struct YamlFrame {
+    indent: usize,
+    segment: Segment,
+    start: usize,
+    end: usize,
+    is_item: bool,
+    // the number of the list items in it
+    items: usize,
+    // the following more indented lines are the content of a literal or folded scalar
+    block_scalar: bool,
+}
+
+fn parse_yaml(s: &str) -> Outline {
+    let mut outline = Outline::default();
+    let mut stack: Vec<YamlFrame> = Vec::new();
+    let mut root_items = 0;
+
+    let close = |stack: &mut Vec<YamlFrame>, outline: &mut Outline| {
+        if let Some(frame) = stack.pop() {
+            outline.sections.push((frame.start, frame.end));
+        }
+    };
+
+    for (i, line) in s.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        let in_block_scalar = stack
+            .last()
+            .is_some_and(|f| f.block_scalar && indent > f.indent);
+        if in_block_scalar && !trimmed.is_empty() {
+            stack.iter_mut().for_each(|f| f.end = i);
+            outline.paths.push(Some(yaml_path(&stack)));
+            continue;
+        }
+
+        if trimmed.is_empty() || is_comment(trimmed, &['#']) {
+            outline.paths.push(None);
+            continue;
+        }
+
+        if trimmed == "---" || trimmed.starts_with("--- ") || trimmed == "..." {
+            // a new document
+            while !stack.is_empty() {
+                close(&mut stack, &mut outline);
+            }
+            root_items = 0;
+            outline.paths.push(None);
+            continue;
+        }
+
+        let (item, rest, rest_indent) = match trimmed.strip_prefix('-') {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => {
+                let rest = rest.trim_start();
+                (true, rest, line.len() - rest.len())
+            }
+            _ => (false, trimmed, indent),
+        };
+
+        if item {
+            // the items can be at the same indentation as the key of the list
+            while stack
+                .last()
+                .is_some_and(|f| f.indent > indent || (f.indent == indent && f.is_item))
+            {
+                close(&mut stack, &mut outline);
+            }
+            let items = match stack.last_mut() {
+                Some(parent) => &mut parent.items,
+                None => &mut root_items,
+            };
+            let index = *items;
+            *items += 1;
+            stack.push(YamlFrame {
+                indent,
+                segment: Segment::Index(index),
+                start: i,
+                end: i,
+                is_item: true,
+                items: 0,
+                block_scalar: false,
+            });
+        }
+
+        if let Some((key, value)) = split_yaml_key(rest) {
+            while stack.last().is_some_and(|f| f.indent >= rest_indent) {
+                close(&mut stack, &mut outline);
+            }
+            let value = value.split(" #").next().unwrap_or_default().trim();
+            stack.push(YamlFrame {
+                indent: rest_indent,
+                segment: Segment::Key(key),
+                start: i,
+                end: i,
+                is_item: false,
+                items: 0,
+                block_scalar: value.starts_with(['|', '>']),
+            });
+        }
+        // otherwise, a scalar item or a continuation of the multi-line value
+
+        stack.iter_mut().for_each(|f| f.end = i);
+        outline.paths.push(Some(yaml_path(&stack)));
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut outline);
+    }
+    outline
+}
+
+fn yaml_path(stack: &[YamlFrame]) -> String {
+    let segments: Vec<Segment> = stack.iter().map(|f| f.segment.clone()).collect();
+    format_path(&segments)
+}
+
+// e.g. `key: value`, `"quoted key":`, returns the key and the rest
+fn split_yaml_key(s: &str) -> Option<(String, &str)> {
+    if s.starts_with(['{', '[', '?', '&', '*', '!', '|', '>']) {
+        return None;
+    }
+    let (key, rest) = if let Some(quote) = s.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = s[1..].find(quote)? + 1;
+        (s[1..end].to_string(), &s[end + 1..])
+    } else {
+        let pos = s
+            .char_indices()
+            .find(|(i, c)| *c == ':' && s[i + 1..].chars().next().map_or(true, |c| c == ' '))
+            .map(|(i, _)| i)?;
+        let key = s[..pos].trim();
+        if key.contains(" #") {
+            return None;
+        }
+        (key.to_string(), &s[pos..])
+    };
+    let value = rest.trim_start().strip_prefix(':')?;
+    if !(value.is_empty() || value.starts_with(' ')) {
+        return None;
+    }
+    Some((key, value))
+}
+
+fn parse_toml(s: &str) -> Outline {
+    let mut outline = Outline::default();
+    let mut table: Vec<Segment> = Vec::new();
+    let mut array_counts: HashMap<String, usize> = HashMap::new();
+    // the header line of the current table and the last line of its content
+    let mut section: Option<(usize, usize)> = None;
+    let mut last_path: Option<String> = None;
+    // the delimiter of the multi-line string which is not closed yet
+    let mut multiline: Option<&str> = None;
+    // the depth of the brackets of the multi-line array or inline table
+    let mut depth = 0;
+
+    for (i, line) in s.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(delimiter) = multiline {
+            if trimmed.matches(delimiter).count() % 2 == 1 {
+                multiline = None;
+            }
+            section = section.map(|(start, _)| (start, i));
+            outline.paths.push(last_path.clone());
+            continue;
+        }
+
+        if trimmed.is_empty() || is_comment(trimmed, &['#']) {
+            outline.paths.push(None);
+            continue;
+        }
+
+        if depth > 0 {
+            depth += bracket_depth(trimmed);
+            section = section.map(|(start, _)| (start, i));
+            outline.paths.push(last_path.clone());
+            continue;
+        }
+
+        let header = trimmed
+            .strip_prefix("[[")
+            .and_then(|s| s.split_once("]]"))
+            .map(|(name, _)| (name, true))
+            .or_else(|| {
+                trimmed
+                    .strip_prefix('[')
+                    .and_then(|s| s.split_once(']'))
+                    .map(|(name, _)| (name, false))
+            });
+        if let Some((name, array)) = header {
+            if let Some(section) = section.take() {
+                outline.sections.push(section);
+            }
+            table = split_dotted_key(name);
+            if array {
+                let count = array_counts.entry(name.trim().to_string()).or_default();
+                table.push(Segment::Index(*count));
+                *count += 1;
+            }
+            section = Some((i, i));
+            last_path = Some(format_path(&table));
+            outline.paths.push(last_path.clone());
+            continue;
+        }
+
+        if let Some((key, value)) = split_unquoted(trimmed, '=') {
+            let mut segments = table.clone();
+            segments.extend(split_dotted_key(key));
+            last_path = Some(format_path(&segments));
+            multiline = ["\"\"\"", "'''"]
+                .into_iter()
+                .find(|d| value.matches(d).count() % 2 == 1);
+            depth = bracket_depth(value).max(0);
+        }
+
+        section = section.map(|(start, _)| (start, i));
+        outline.paths.push(last_path.clone());
+    }
+    if let Some(section) = section {
+        outline.sections.push(section);
+    }
+    outline
+}
+
+// e.g. `a."b.c" . d` => [a, b.c, d]
+fn split_dotted_key(s: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let (key, next) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => match rest[1..].find(quote) {
+                Some(end) => (&rest[1..end + 1], &rest[end + 2..]),
+                None => (&rest[1..], ""),
+            },
+            _ => match rest.find('.') {
+                Some(pos) => (rest[..pos].trim(), &rest[pos..]),
+                None => (rest.trim(), ""),
+            },
+        };
+        segments.push(Segment::Key(key.to_string()));
+        rest = next.trim_start();
+        rest = rest.strip_prefix('.').unwrap_or(rest).trim_start();
+    }
+    segments
+}
+
+// The number of the opened brackets minus the closed ones, which are not in the quotes
+fn bracket_depth(s: &str) -> i32 {
+    let mut quote = None;
+    let mut depth = 0;
+    for c in s.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, '#') => break,
+            _ => {}
+        }
+    }
+    depth
+}
+
+// Splits at the first separator which is not in the quotes
+fn split_unquoted(s: &str, separator: char) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, c) if c == separator => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_ini(s: &str) -> Outline {
+    let mut outline = Outline::default();
+    let mut name: Option<String> = None;
+    let mut section: Option<(usize, usize)> = None;
+    let mut last_path: Option<String> = None;
+
+    for (i, line) in s.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || is_comment(trimmed, &['#', ';']) {
+            outline.paths.push(None);
+            continue;
+        }
+
+        let header = trimmed
+            .strip_prefix('[')
+            .and_then(|s| s.split_once(']'))
+            .map(|(name, _)| name.trim());
+        if let Some(header) = header {
+            if let Some(section) = section.take() {
+                outline.sections.push(section);
+            }
+            let segments = [Segment::Key(header.to_string())];
+            name = Some(header.to_string());
+            section = Some((i, i));
+            last_path = Some(format_path(&segments));
+            outline.paths.push(last_path.clone());
+            continue;
+        }
+
+        // the indented lines are the continuation of the previous value
+        let is_continuation = line.starts_with([' ', '\t']) && last_path.is_some();
+        if !is_continuation {
+            let key = trimmed
+                .split_once(['=', ':'])
+                .map_or(trimmed, |(key, _)| key)
+                .trim();
+            let mut segments: Vec<Segment> = name.iter().cloned().map(Segment::Key).collect();
+            segments.push(Segment::Key(key.to_string()));
+            last_path = Some(format_path(&segments));
+        }
+
+        section = section.map(|(start, _)| (start, i));
+        outline.paths.push(last_path.clone());
+    }
+    if let Some(section) = section {
+        outline.sections.push(section);
+    }
+    outline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(outline: &Outline) -> Vec<Option<&str>> {
+        (0..outline.paths.len())
+            .map(|i| outline.key_path(i))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let s = r#"apiVersion: apps/v1
+metadata:
+  name: web
+  labels:
+    app.kubernetes.io/name: web
+
+spec:
+  containers:
+  - name: nginx
+    ports:
+      - containerPort: 80
+      - containerPort: 443
+  - name: sidecar
+    command: |
+      echo: hello
+      exit 0
+# comment
+---
+- a
+- b: 1
+"#;
+        let outline = Outline::parse(OutlineFormat::Yaml, s);
+        assert_eq!(
+            paths(&outline),
+            [
+                Some("apiVersion"),
+                Some("metadata"),
+                Some("metadata.name"),
+                Some("metadata.labels"),
+                Some("metadata.labels.\"app.kubernetes.io/name\""),
+                None,
+                Some("spec"),
+                Some("spec.containers"),
+                Some("spec.containers[0].name"),
+                Some("spec.containers[0].ports"),
+                Some("spec.containers[0].ports[0].containerPort"),
+                Some("spec.containers[0].ports[1].containerPort"),
+                Some("spec.containers[1].name"),
+                Some("spec.containers[1].command"),
+                Some("spec.containers[1].command"),
+                Some("spec.containers[1].command"),
+                None,
+                None,
+                Some("[0]"),
+                Some("[1].b"),
+            ]
+        );
+        assert_eq!(
+            outline.sections,
+            [
+                (1, 4),
+                (3, 4),
+                (6, 15),
+                (7, 15),
+                (8, 11),
+                (9, 11),
+                (12, 15),
+                (13, 15)
+            ]
+        );
+        assert_eq!(outline.section_at(8), Some((8, 11)));
+        assert_eq!(outline.section_at(10), Some((9, 11)));
+        assert_eq!(outline.section_at(0), None);
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let s = r#"title = "example"
+
+[server]
+host = "localhost"
+"tls.enabled" = true
+
+[[plugins]]
+name = "a"
+args = [
+  ["--verbose"],
+]
+
+[[plugins]]
+name = "b"
+description = """
+multi-line
+"""
+[a.b]
+c.d = 1
+"#;
+        let outline = Outline::parse(OutlineFormat::Toml, s);
+        assert_eq!(
+            paths(&outline),
+            [
+                Some("title"),
+                None,
+                Some("server"),
+                Some("server.host"),
+                Some("server.\"tls.enabled\""),
+                None,
+                Some("plugins[0]"),
+                Some("plugins[0].name"),
+                Some("plugins[0].args"),
+                Some("plugins[0].args"),
+                Some("plugins[0].args"),
+                None,
+                Some("plugins[1]"),
+                Some("plugins[1].name"),
+                Some("plugins[1].description"),
+                Some("plugins[1].description"),
+                Some("plugins[1].description"),
+                Some("a.b"),
+                Some("a.b.c.d"),
+            ]
+        );
+        assert_eq!(outline.sections, [(2, 4), (6, 10), (12, 16), (17, 18)]);
+    }
+
+    #[test]
+    fn test_parse_ini() {
+        let s = r#"; comment
+root = 1
+[core]
+editor = vim
+  --wait
+[remote "origin"]
+url: https://example.com
+"#;
+        let outline = Outline::parse(OutlineFormat::Ini, s);
+        assert_eq!(
+            paths(&outline),
+            [
+                None,
+                Some("root"),
+                Some("core"),
+                Some("core.editor"),
+                Some("core.editor"),
+                Some("\"remote \\\"origin\\\"\""),
+                Some("\"remote \\\"origin\\\"\".url"),
+            ]
+        );
+        assert_eq!(outline.sections, [(2, 4), (5, 6)]);
+    }
+
+    #[test]
+    fn test_outline_format_from_file_name() {
+        assert_eq!(
+            OutlineFormat::from_file_name("a/b/config.YML"),
+            Some(OutlineFormat::Yaml)
+        );
+        assert_eq!(
+            OutlineFormat::from_file_name("Cargo.toml"),
+            Some(OutlineFormat::Toml)
+        );
+        assert_eq!(
+            OutlineFormat::from_file_name("setup.cfg"),
+            Some(OutlineFormat::Ini)
+        );
+        assert_eq!(OutlineFormat::from_file_name("data.json"), None);
+    }
+}
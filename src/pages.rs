@@ -1,10 +1,19 @@
 pub mod page;
 
+pub mod access_log_summary;
 pub mod bucket_list;
+pub mod bucket_metrics;
+pub mod cleanup_objects;
+pub mod compare_objects;
+pub mod duplicate_objects;
 pub mod help;
 pub mod initializing;
 pub mod object_detail;
 pub mod object_list;
+pub mod object_local_compare;
 pub mod object_preview;
+pub mod object_version_compare;
+pub mod transfer_list;
+pub mod watch_status;
 
 mod util;
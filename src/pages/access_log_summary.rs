@@ -0,0 +1,283 @@
+use std::rc::Rc;
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, ListItem, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    access_log::AccessLogSummary,
+    app::AppContext,
+    color::ColorTheme,
+    event::{AppEventType, Sender},
+    format::{format_count, format_size_byte},
+    pages::util::{build_helps, build_short_helps},
+    widget::{ScrollList, ScrollListState},
+};
+
+#[derive(Debug)]
+pub struct AccessLogSummaryPage {
+    bucket: String,
+    prefix: String,
+    summary: AccessLogSummary,
+    rows: Vec<AccessLogSummaryRow>,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessLogSummaryRow {
+    Section(usize),
+    Entry(usize, usize),
+}
+
+impl AccessLogSummaryPage {
+    pub fn new(
+        bucket: String,
+        prefix: String,
+        summary: AccessLogSummary,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let rows = build_rows(&summary);
+        let list_state = ScrollListState::new(rows.len());
+        Self {
+            bucket,
+            prefix,
+            summary,
+            rows,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('j') => {
+                self.list_state.select_next();
+            }
+            key_code_char!('k') => {
+                self.list_state.select_prev();
+            }
+            key_code_char!('g') => {
+                self.list_state.select_first();
+            }
+            key_code_char!('G') => {
+                self.list_state.select_last();
+            }
+            key_code_char!('f') => {
+                self.list_state.select_next_page();
+            }
+            key_code_char!('b') => {
+                self.list_state.select_prev_page();
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).split(area);
+
+        let summary = &self.summary;
+        let mut totals = format!(
+            "{} files, {} requests, {} errors, {} sent",
+            format_count(summary.file_count),
+            format_count(summary.request_count),
+            format_count(summary.error_count()),
+            format_size_byte(summary.bytes_sent),
+        );
+        if summary.invalid_line_count > 0 {
+            totals.push_str(&format!(
+                ", {} invalid lines",
+                format_count(summary.invalid_line_count)
+            ));
+        }
+        let period = match (&summary.first_time, &summary.last_time) {
+            (Some(first), Some(last)) => format!("  ({} - {} UTC)", first, last),
+            _ => String::new(),
+        };
+        let header = Paragraph::new(vec![
+            Line::from(vec![
+                format!("s3://{}/{}", self.bucket, self.prefix).into(),
+                period.fg(self.ctx.theme.divider),
+            ]),
+            Line::from(totals.bold()),
+        ])
+        .block(Block::bordered().padding(Padding::horizontal(1)))
+        .fg(self.ctx.theme.fg);
+        f.render_widget(header, chunks[0]);
+
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+        let show_item_count = (chunks[1].height as usize).saturating_sub(2 /* border */);
+        let list_items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, row)| {
+                build_list_item(
+                    *row,
+                    &self.summary,
+                    idx + offset == selected,
+                    &self.ctx.theme,
+                )
+            })
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, chunks[1], &mut self.list_state);
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["j/k"], "Select item"),
+            (&["g/G"], "Go to top/bottom"),
+            (&["f"], "Scroll page forward"),
+            (&["b"], "Scroll page backward"),
+            (&["Backspace"], "Go back to object list"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["j/k"], "Select", 2),
+            (&["Backspace"], "Go back", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+}
+
+fn build_rows(summary: &AccessLogSummary) -> Vec<AccessLogSummaryRow> {
+    summary
+        .sections
+        .iter()
+        .enumerate()
+        .flat_map(|(si, (_, entries))| {
+            let entries = (0..entries.len()).map(move |ei| AccessLogSummaryRow::Entry(si, ei));
+            std::iter::once(AccessLogSummaryRow::Section(si)).chain(entries)
+        })
+        .collect()
+}
+
+fn build_list_item<'a>(
+    row: AccessLogSummaryRow,
+    summary: &'a AccessLogSummary,
+    selected: bool,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let line = match row {
+        AccessLogSummaryRow::Section(si) => {
+            let (section, entries) = &summary.sections[si];
+            if entries.is_empty() {
+                Line::from(vec![
+                    format!(" {}", section.str()).bold(),
+                    "  (none)".fg(theme.divider),
+                ])
+            } else {
+                Line::from(format!(" {}", section.str()).bold())
+            }
+        }
+        AccessLogSummaryRow::Entry(si, ei) => {
+            let (name, count) = &summary.sections[si].1[ei];
+            Line::from(format!("   {:>8}  {}", format_count(*count), name))
+        }
+    };
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(line).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{access_log::AccessLogSection, event};
+
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(70, 12))?;
+
+        let entries = |es: &[(&str, usize)]| -> Vec<(String, usize)> {
+            es.iter().map(|(n, c)| (n.to_string(), *c)).collect()
+        };
+        let summary = AccessLogSummary {
+            file_count: 2,
+            request_count: 3,
+            invalid_line_count: 0,
+            bytes_sent: 2048,
+            first_time: Some("2024-01-01 00:00:00".to_string()),
+            last_time: Some("2024-01-02 00:00:00".to_string()),
+            sections: vec![
+                (
+                    AccessLogSection::Operations,
+                    entries(&[("REST.GET.OBJECT", 2), ("REST.PUT.OBJECT", 1)]),
+                ),
+                (AccessLogSection::Keys, entries(&[("a.txt", 3)])),
+                (AccessLogSection::Errors, Vec::new()),
+            ],
+        };
+        let mut page =
+            AccessLogSummaryPage::new("bucket".to_string(), "logs/".to_string(), summary, ctx, tx);
+        let area = Rect::new(0, 0, 70, 12);
+        terminal.draw(|f| page.render(f, area))?;
+
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(70)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        #[rustfmt::skip]
+        let expected = [
+            "┌────────────────────────────────────────────────────────────────────┐",
+            "│ s3://bucket/logs/  (2024-01-01 00:00:00 - 2024-01-02 00:00:00 UTC) │",
+            "│ 2 files, 3 requests, 0 errors, 2 KiB sent                          │",
+            "└────────────────────────────────────────────────────────────────────┘",
+            "┌───────────────────────────────────────────────────────────── 1 / 6 ┐",
+            "│  Operations                                                        │",
+            "│           2  REST.GET.OBJECT                                       │",
+            "│           1  REST.PUT.OBJECT                                       │",
+            "│  Top keys                                                          │",
+            "│           3  a.txt                                                 │",
+            "│  Errors  (none)                                                    │",
+            "└────────────────────────────────────────────────────────────────────┘",
+        ];
+        assert_eq!(lines, expected);
+
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
-use std::rc::Rc;
+use std::{collections::HashSet, rc::Rc};
 
 use laurier::{highlight::highlight_matched_text, key_code, key_code_char};
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent},
     layout::Rect,
-    style::Style,
+    style::{Style, Stylize},
     text::Line,
     widgets::ListItem,
     Frame,
@@ -13,19 +13,60 @@ use ratatui::{
 use crate::{
     app::AppContext,
     color::ColorTheme,
+    error::AppError,
     event::{AppEventType, Sender},
     object::{BucketItem, ObjectKey},
     pages::util::{build_helps, build_short_helps},
     widget::{
-        BucketListSortDialog, BucketListSortDialogState, BucketListSortType, CopyDetailDialog,
-        CopyDetailDialogState, InputDialog, InputDialogState, ScrollList, ScrollListState,
+        BucketListSortDialog, BucketListSortDialogState, BucketListSortType, ConfirmDialog,
+        CopyDetailDialog, CopyDetailDialogState, InputDialog, InputDialogState, ScrollList,
+        ScrollListState,
     },
 };
 
+// The regions that can be selected when creating a bucket or switching the region, in addition to the current region
+pub const BUCKET_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "af-south-1",
+    "ap-east-1",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-northeast-3",
+    "ap-south-1",
+    "ap-south-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-southeast-3",
+    "ap-southeast-4",
+    "ca-central-1",
+    "ca-west-1",
+    "eu-central-1",
+    "eu-central-2",
+    "eu-north-1",
+    "eu-south-1",
+    "eu-south-2",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "il-central-1",
+    "me-central-1",
+    "me-south-1",
+    "sa-east-1",
+];
+
 #[derive(Debug)]
 pub struct BucketListPage {
     bucket_items: Vec<BucketItem>,
     view_indices: Vec<usize>,
+    // listed above the other buckets regardless of the sort order
+    pinned_buckets: HashSet<String>,
+    // whether the buckets matching `filters.hidden` are shown
+    show_hidden: bool,
+    // the current region comes first
+    regions: Vec<String>,
 
     view_state: ViewState,
 
@@ -43,22 +84,39 @@ enum ViewState {
     FilterDialog,
     SortDialog,
     CopyDetailDialog(Box<CopyDetailDialogState>),
+    CreateBucketDialog(InputDialogState),
+    CreateBucketRegionDialog(String, usize),
+    DeleteBucketConfirmDialog,
 }
 
 impl BucketListPage {
-    pub fn new(bucket_items: Vec<BucketItem>, ctx: Rc<AppContext>, tx: Sender) -> Self {
+    pub fn new(
+        bucket_items: Vec<BucketItem>,
+        region: &str,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
         let items_len = bucket_items.len();
-        let view_indices = (0..items_len).collect();
-        Self {
+        let view_indices = Vec::new();
+        let regions = std::iter::once(region)
+            .chain(BUCKET_REGIONS.iter().copied().filter(|r| *r != region))
+            .map(String::from)
+            .collect();
+        let mut page = Self {
             bucket_items,
             view_indices,
+            pinned_buckets: HashSet::new(),
+            show_hidden: false,
+            regions,
             view_state: ViewState::Default,
             list_state: ScrollListState::new(items_len),
             filter_input_state: InputDialogState::default(),
             sort_dialog_state: BucketListSortDialogState::default(),
             ctx,
             tx,
-        }
+        };
+        page.filter_view_indices();
+        page
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) {
@@ -95,9 +153,22 @@ impl BucketListPage {
                 key_code_char!('R') if self.non_empty() => {
                     self.tx.send(AppEventType::BucketListRefresh);
                 }
+                key_code_char!('p') => {
+                    self.tx.send(AppEventType::PasteLocation);
+                }
                 key_code_char!('x') if self.non_empty() => {
                     self.tx.send(AppEventType::BucketListOpenManagementConsole);
                 }
+                key_code_char!('.') => {
+                    self.tx.send(AppEventType::ToggleShowHidden);
+                }
+                key_code_char!('P') if self.non_empty() => {
+                    self.tx.send(AppEventType::BucketListTogglePin);
+                }
+                key_code_char!('m') if self.non_empty() => {
+                    let bucket = self.current_selected_item().clone();
+                    self.tx.send(AppEventType::StartLoadBucketMetrics(bucket));
+                }
                 key_code_char!('/') => {
                     self.open_filter_dialog();
                 }
@@ -107,6 +178,18 @@ impl BucketListPage {
                 key_code_char!('r') => {
                     self.open_copy_detail_dialog();
                 }
+                key_code_char!('N') => {
+                    self.open_create_bucket_dialog();
+                }
+                key_code_char!('d') if self.non_empty() => {
+                    self.open_delete_bucket_confirm_dialog();
+                }
+                key_code_char!('a') => {
+                    self.tx.send(AppEventType::OpenProfileSwitcher);
+                }
+                key_code_char!('z') => {
+                    self.tx.send(AppEventType::OpenRegionSwitcher);
+                }
                 key_code_char!('?') => {
                     self.tx.send(AppEventType::OpenHelp);
                 }
@@ -164,6 +247,42 @@ impl BucketListPage {
                 }
                 _ => {}
             },
+            ViewState::CreateBucketDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_create_bucket_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let name = state.input().trim().to_string();
+                    self.open_create_bucket_region_dialog(name);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::CreateBucketRegionDialog(_, ref mut region) => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_create_bucket_dialog();
+                }
+                key_code_char!('j') => {
+                    *region = (*region + 1) % self.regions.len();
+                }
+                key_code_char!('k') => {
+                    *region = (*region + self.regions.len() - 1) % self.regions.len();
+                }
+                key_code_char!('y') => {
+                    self.create_bucket();
+                }
+                _ => {}
+            },
+            ViewState::DeleteBucketConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_delete_bucket_confirm_dialog();
+                }
+                key_code_char!('y') => {
+                    self.delete_bucket();
+                }
+                _ => {}
+            },
         }
     }
 
@@ -174,6 +293,7 @@ impl BucketListPage {
         let list_items = build_list_items(
             &self.bucket_items,
             &self.view_indices,
+            &self.pinned_buckets,
             self.filter_input_state.input(),
             &self.ctx.theme,
             offset,
@@ -205,6 +325,32 @@ impl BucketListPage {
             let copy_detail_dialog = CopyDetailDialog::default().theme(&self.ctx.theme);
             f.render_stateful_widget(copy_detail_dialog, area, state);
         }
+
+        if let ViewState::CreateBucketDialog(state) = &mut self.view_state {
+            let create_bucket_dialog = InputDialog::default()
+                .title("New bucket")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(create_bucket_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::CreateBucketRegionDialog(name, region) = &self.view_state {
+            let lines = build_create_bucket_dialog_lines(name, &self.regions, *region);
+            let dialog = ConfirmDialog::new("Create bucket", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+
+        if let ViewState::DeleteBucketConfirmDialog = self.view_state {
+            let lines = vec![
+                format!("Delete bucket '{}'?", self.current_selected_item().name),
+                "  Only empty buckets can be deleted".into(),
+            ];
+            let dialog = ConfirmDialog::new("Delete", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
     }
 
     pub fn helps(&self) -> Vec<String> {
@@ -222,7 +368,15 @@ impl BucketListPage {
                         (&["o"], "Sort bucket list"),
                         (&["r"], "Open copy dialog"),
                         (&["R"], "Refresh bucket list"),
+                        (&["p"], "Open location in clipboard"),
                         (&["x"], "Open management console in browser"),
+                        (&["m"], "Show bucket metrics"),
+                        (&["P"], "Pin/Unpin bucket"),
+                        (&["."], "Toggle hidden buckets"),
+                        (&["N"], "Create bucket"),
+                        (&["d"], "Delete bucket"),
+                        (&["a"], "Switch AWS profile"),
+                        (&["z"], "Switch region"),
                     ]
                 } else {
                     &[
@@ -237,7 +391,15 @@ impl BucketListPage {
                         (&["o"], "Sort bucket list"),
                         (&["r"], "Open copy dialog"),
                         (&["R"], "Refresh bucket list"),
+                        (&["p"], "Open location in clipboard"),
                         (&["x"], "Open management console in browser"),
+                        (&["m"], "Show bucket metrics"),
+                        (&["P"], "Pin/Unpin bucket"),
+                        (&["."], "Toggle hidden buckets"),
+                        (&["N"], "Create bucket"),
+                        (&["d"], "Delete bucket"),
+                        (&["a"], "Switch AWS profile"),
+                        (&["z"], "Switch region"),
                     ]
                 }
             }
@@ -258,6 +420,22 @@ impl BucketListPage {
                 (&["j/k"], "Select item"),
                 (&["Enter"], "Copy selected value to clipboard"),
             ],
+            ViewState::CreateBucketDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close new bucket dialog"),
+                (&["Enter"], "Select region"),
+            ],
+            ViewState::CreateBucketRegionDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["j/k"], "Select region"),
+                (&["y"], "Create bucket"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+            ViewState::DeleteBucketConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Delete bucket"),
+                (&["n", "Esc"], "Cancel"),
+            ],
         };
         build_helps(helps)
     }
@@ -306,6 +484,13 @@ impl BucketListPage {
                 (&["Enter"], "Copy", 1),
                 (&["?"], "Help", 0),
             ],
+            ViewState::CreateBucketDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Next", 1)],
+            ViewState::CreateBucketRegionDialog(_, _) => &[
+                (&["j/k"], "Select", 3),
+                (&["y"], "Create", 1),
+                (&["n"], "Cancel", 2),
+            ],
+            ViewState::DeleteBucketConfirmDialog => &[(&["y"], "Delete", 1), (&["n"], "Cancel", 2)],
         };
         build_short_helps(helps)
     }
@@ -366,6 +551,51 @@ impl BucketListPage {
         self.view_state = ViewState::Default;
     }
 
+    fn open_create_bucket_dialog(&mut self) {
+        self.view_state = ViewState::CreateBucketDialog(InputDialogState::default());
+    }
+
+    fn open_create_bucket_region_dialog(&mut self, name: String) {
+        if name.is_empty() {
+            return;
+        }
+        // the other rules are checked by S3
+        if name.contains('/') {
+            let e = AppError::msg("Bucket name must not contain '/'");
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        }
+        self.view_state = ViewState::CreateBucketRegionDialog(name, 0);
+    }
+
+    fn close_create_bucket_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn create_bucket(&mut self) {
+        let ViewState::CreateBucketRegionDialog(name, region) = &self.view_state else {
+            return;
+        };
+        let name = name.clone();
+        let region = self.regions[*region].clone();
+        self.close_create_bucket_dialog();
+        self.tx.send(AppEventType::CreateBucket(name, region));
+    }
+
+    fn open_delete_bucket_confirm_dialog(&mut self) {
+        self.view_state = ViewState::DeleteBucketConfirmDialog;
+    }
+
+    fn close_delete_bucket_confirm_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn delete_bucket(&mut self) {
+        let name = self.current_selected_item().name.clone();
+        self.close_delete_bucket_confirm_dialog();
+        self.tx.send(AppEventType::DeleteBucket(name));
+    }
+
     fn apply_filter(&mut self) {
         self.view_state = ViewState::Default;
 
@@ -380,10 +610,12 @@ impl BucketListPage {
 
     fn filter_view_indices(&mut self) {
         let filter = self.filter_input_state.input();
+        let hidden = &self.ctx.config.filters;
         self.view_indices = self
             .bucket_items
             .iter()
             .enumerate()
+            .filter(|(_, item)| self.show_hidden || !hidden.is_hidden_bucket(&item.name))
             .filter(|(_, item)| item.name.contains(filter))
             .map(|(idx, _)| idx)
             .collect();
@@ -428,6 +660,34 @@ impl BucketListPage {
                     .sort_by(|a, b| items[*b].name.cmp(&items[*a].name));
             }
         }
+        // stable, so the pinned buckets keep the selected order among themselves
+        self.view_indices
+            .sort_by_key(|i| !self.pinned_buckets.contains(&items[*i].name));
+    }
+
+    pub fn set_pinned_buckets(&mut self, names: &[String]) {
+        self.pinned_buckets = names.iter().cloned().collect();
+        self.sort_view_indices();
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        if self.show_hidden == show_hidden {
+            return;
+        }
+        self.show_hidden = show_hidden;
+        let selected = self
+            .non_empty()
+            .then(|| self.current_selected_item().name.clone());
+        self.filter_view_indices();
+        // keep the selection unless the selected bucket is hidden
+        let index = selected.and_then(|name| {
+            self.view_indices
+                .iter()
+                .position(|&i| self.bucket_items[i].name == name)
+        });
+        if let Some(index) = index {
+            self.list_state.select_index(index);
+        }
     }
 
     pub fn current_selected_item(&self) -> &BucketItem {
@@ -450,6 +710,20 @@ impl BucketListPage {
         })
     }
 
+    pub fn select_bucket(&mut self, name: &str) {
+        let find = |page: &Self| {
+            page.view_indices
+                .iter()
+                .position(|&i| page.bucket_items[i].name == name)
+        };
+        if find(self).is_none() {
+            self.reset_filter();
+        }
+        if let Some(index) = find(self) {
+            self.list_state.select_index(index);
+        }
+    }
+
     pub fn current_selected_object_key(&self) -> ObjectKey {
         let item = self.current_selected_item();
         ObjectKey {
@@ -458,14 +732,30 @@ impl BucketListPage {
         }
     }
 
-    fn non_empty(&self) -> bool {
+    pub fn non_empty(&self) -> bool {
         !self.view_indices.is_empty()
     }
 }
 
+fn build_create_bucket_dialog_lines(name: &str, regions: &[String], region: usize) -> Vec<String> {
+    let mut lines = vec![
+        format!("Create bucket '{}'?", name),
+        format!("  Region: {} (j/k to change)", regions[region]),
+    ];
+    if region != 0 {
+        // only the buckets in the current region are listed
+        lines.push(format!(
+            "  The bucket will not be listed (current region: {})",
+            regions[0]
+        ));
+    }
+    lines
+}
+
 fn build_list_items<'a>(
     current_items: &'a [BucketItem],
     view_indices: &'a [usize],
+    pinned_buckets: &HashSet<String>,
     filter: &'a str,
     theme: &'a ColorTheme,
     offset: usize,
@@ -481,7 +771,8 @@ fn build_list_items<'a>(
         .enumerate()
         .map(|(idx, item)| {
             let selected = idx + offset == selected;
-            build_list_item(&item.name, selected, filter, theme)
+            let pinned = pinned_buckets.contains(&item.name);
+            build_list_item(&item.name, selected, pinned, filter, theme)
         })
         .collect()
 }
@@ -489,10 +780,11 @@ fn build_list_items<'a>(
 fn build_list_item<'a>(
     name: &'a str,
     selected: bool,
+    pinned: bool,
     filter: &'a str,
     theme: &'a ColorTheme,
 ) -> ListItem<'a> {
-    let line = if filter.is_empty() {
+    let mut line = if filter.is_empty() {
         Line::from(vec![" ".into(), name.into(), " ".into()])
     } else {
         let i = name.find(filter).unwrap();
@@ -506,6 +798,10 @@ fn build_list_item<'a>(
         Line::from(spans)
     };
 
+    if pinned {
+        line.spans[0] = "*".fg(theme.list_marked);
+    }
+
     let style = if selected {
         Style::default()
             .bg(theme.list_selected_bg)
@@ -534,7 +830,7 @@ mod tests {
                 .into_iter()
                 .map(bucket_item)
                 .collect();
-            let mut page = BucketListPage::new(items, ctx, tx);
+            let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
             let area = Rect::new(0, 0, 30, 10);
             page.render(f, area);
         })?;
@@ -571,7 +867,7 @@ mod tests {
             let items = (0..16)
                 .map(|i| bucket_item(&format!("bucket{}", i + 1)))
                 .collect();
-            let mut page = BucketListPage::new(items, ctx, tx);
+            let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
             let area = Rect::new(0, 0, 30, 10);
             page.render(f, area);
         })?;
@@ -609,7 +905,7 @@ mod tests {
             .into_iter()
             .map(bucket_item)
             .collect();
-        let mut page = BucketListPage::new(items, ctx, tx);
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
         let area = Rect::new(0, 0, 30, 10);
 
         page.handle_key(KeyEvent::from(KeyCode::Char('/')));
@@ -686,7 +982,7 @@ mod tests {
             .into_iter()
             .map(bucket_item)
             .collect();
-        let mut page = BucketListPage::new(items, ctx, tx);
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
         let area = Rect::new(0, 0, 30, 10);
 
         page.handle_key(KeyEvent::from(KeyCode::Char('o')));
@@ -731,7 +1027,7 @@ mod tests {
             .into_iter()
             .map(bucket_item)
             .collect();
-        let mut page = BucketListPage::new(items, ctx, tx);
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
 
         page.handle_key(KeyEvent::from(KeyCode::Char('/')));
         page.handle_key(KeyEvent::from(KeyCode::Char('b')));
@@ -766,7 +1062,7 @@ mod tests {
             .into_iter()
             .map(bucket_item)
             .collect();
-        let mut page = BucketListPage::new(items, ctx, tx);
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
 
         page.handle_key(KeyEvent::from(KeyCode::Char('o')));
 
@@ -798,7 +1094,7 @@ mod tests {
             .into_iter()
             .map(bucket_item)
             .collect();
-        let mut page = BucketListPage::new(items, ctx, tx);
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
 
         page.handle_key(KeyEvent::from(KeyCode::Char('/')));
         page.handle_key(KeyEvent::from(KeyCode::Char('b')));
@@ -831,6 +1127,143 @@ mod tests {
         assert_eq!(page.view_indices, vec![0, 4]);
     }
 
+    #[test]
+    fn test_pin_items() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        let items = ["foo", "bar", "baz", "qux", "foobar"]
+            .into_iter()
+            .map(bucket_item)
+            .collect();
+        let mut page = BucketListPage::new(items, "us-east-1", ctx, tx);
+        page.set_pinned_buckets(&["qux".to_string(), "bar".to_string()]);
+
+        assert_eq!(page.view_indices, vec![1, 3, 0, 2, 4]);
+
+        let area = Rect::new(0, 0, 30, 10);
+        terminal.draw(|f| page.render(f, area))?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌───────────────────── 1 / 5 ┐",
+            "│ *bar                       │",
+            "│ *qux                       │",
+            "│  foo                       │",
+            "│  baz                       │",
+            "│  foobar                    │",
+            "│                            │",
+            "│                            │",
+            "│                            │",
+            "└────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            (2..28, [1]) => bg: Color::Cyan, fg: Color::Black,
+            ([2], [1, 2]) => fg: Color::Yellow,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('o')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select NameDesc
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        assert_eq!(page.view_indices, vec![3, 1, 4, 0, 2]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('b')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        assert_eq!(page.view_indices, vec![1, 4, 2]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('P')));
+        let events: Vec<AppEventType> = std::iter::from_fn(|| rx.try_recv())
+            .filter(|e| matches!(e, AppEventType::BucketListTogglePin))
+            .collect();
+        assert_eq!(events.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hidden_items() {
+        let (tx, _) = event::new();
+
+        let items = ["foo", "cdk-assets", "bar", "cdk-staging"]
+            .into_iter()
+            .map(bucket_item)
+            .collect();
+        let mut ctx = AppContext::default();
+        ctx.config.filters.hidden = vec!["cdk-*".to_string()];
+        let mut page = BucketListPage::new(items, "us-east-1", Rc::new(ctx), tx);
+
+        assert_eq!(page.view_indices, vec![0, 2]);
+
+        let mut terminal = setup_terminal().unwrap();
+        terminal
+            .draw(|f| page.render(f, Rect::new(0, 0, 30, 10)))
+            .unwrap();
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.set_show_hidden(true);
+
+        assert_eq!(page.view_indices, vec![0, 1, 2, 3]);
+        assert_eq!(page.current_selected_item().name, "bar");
+
+        page.set_show_hidden(false);
+
+        assert_eq!(page.view_indices, vec![0, 2]);
+        assert_eq!(page.current_selected_item().name, "bar");
+    }
+
+    #[test]
+    fn test_create_and_delete_bucket() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+
+        let items = ["foo", "bar"].into_iter().map(bucket_item).collect();
+        let mut page = BucketListPage::new(items, "ap-northeast-1", ctx, tx);
+        let mut terminal = setup_terminal().unwrap();
+        terminal
+            .draw(|f| page.render(f, Rect::new(0, 0, 30, 10)))
+            .unwrap();
+        assert_eq!(page.regions[0], "ap-northeast-1");
+        assert_eq!(page.regions[1], "us-east-1");
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('N')));
+        for c in "new-bucket".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(
+            page.view_state,
+            ViewState::CreateBucketRegionDialog(_, 0)
+        ));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('d')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let events: Vec<AppEventType> = std::iter::from_fn(|| rx.try_recv())
+            .filter(|e| {
+                matches!(
+                    e,
+                    AppEventType::CreateBucket(..) | AppEventType::DeleteBucket(..)
+                )
+            })
+            .collect();
+        assert!(matches!(
+            &events[..],
+            [AppEventType::CreateBucket(name, region), AppEventType::DeleteBucket(deleted)]
+                if name == "new-bucket" && region == "us-east-1" && deleted == "bar"
+        ));
+    }
+
     fn setup_terminal() -> std::io::Result<Terminal<TestBackend>> {
         let backend = TestBackend::new(30, 10);
         let mut terminal = Terminal::new(backend)?;
@@ -843,7 +1276,8 @@ mod tests {
             name: name.to_string(),
             s3_uri: "".to_string(),
             arn: "".to_string(),
-            object_url: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
         }
     }
 }
@@ -0,0 +1,232 @@
+use std::rc::Rc;
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Padding, Paragraph, Sparkline},
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    bucket_metrics::{BucketMetrics, MetricPoint, BUCKET_METRICS_PERIOD_DAYS},
+    event::{AppEventType, Sender},
+    format::{format_count, format_size_byte},
+    object::BucketItem,
+    pages::util::{build_helps, build_short_helps},
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug)]
+pub struct BucketMetricsPage {
+    bucket: BucketItem,
+    metrics: BucketMetrics,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+impl BucketMetricsPage {
+    pub fn new(
+        bucket: BucketItem,
+        metrics: BucketMetrics,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self {
+            bucket,
+            metrics,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('R') => {
+                self.tx
+                    .send(AppEventType::StartLoadBucketMetrics(self.bucket.clone()));
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let header = Paragraph::new(format!(
+            "{} (last {} days)",
+            self.bucket.s3_uri, BUCKET_METRICS_PERIOD_DAYS
+        ))
+        .block(Block::bordered().padding(Padding::horizontal(1)))
+        .fg(self.ctx.theme.fg);
+        f.render_widget(header, chunks[0]);
+
+        let size_format = |v: f64| format_size_byte(v as usize);
+        self.render_metric(f, chunks[1], "Size", &self.metrics.size_bytes, size_format);
+
+        let count_format = |v: f64| format_count(v as usize);
+        self.render_metric(
+            f,
+            chunks[2],
+            "Objects",
+            &self.metrics.object_count,
+            count_format,
+        );
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["R"], "Reload metrics"),
+            (&["Backspace"], "Go back to bucket list"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["R"], "Reload", 2),
+            (&["Backspace"], "Go back", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+}
+
+impl BucketMetricsPage {
+    fn render_metric(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        title: &str,
+        points: &[MetricPoint],
+        format_value: impl Fn(f64) -> String,
+    ) {
+        let block = Block::bordered()
+            .title(format!(" {} ", title))
+            .padding(Padding::horizontal(1))
+            .fg(self.ctx.theme.fg);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).split(inner);
+
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            let line = Line::from("No data").fg(self.ctx.theme.divider);
+            f.render_widget(line, chunks[0]);
+            return;
+        };
+
+        let min = points.iter().map(|p| p.value).fold(f64::MAX, f64::min);
+        let max = points.iter().map(|p| p.value).fold(f64::MIN, f64::max);
+        let summary = Line::from(vec![
+            format!("Latest: {}", format_value(last.value)).bold(),
+            format!(
+                "  Min: {}  Max: {}  ({} - {})",
+                format_value(min),
+                format_value(max),
+                first.timestamp.format(DATE_FORMAT),
+                last.timestamp.format(DATE_FORMAT),
+            )
+            .fg(self.ctx.theme.divider),
+        ]);
+        f.render_widget(summary, chunks[0]);
+
+        // the last points are shown if there are more points than the width
+        let width = chunks[1].width as usize;
+        let data: Vec<u64> = points
+            .iter()
+            .skip(points.len().saturating_sub(width))
+            .map(|p| p.value as u64)
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .fg(self.ctx.theme.status_info);
+        f.render_widget(sparkline, chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event;
+
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(70, 13))?;
+
+        let point = |day: u32, value: f64| MetricPoint {
+            timestamp: Local.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            value,
+        };
+        let metrics = BucketMetrics {
+            size_bytes: vec![point(1, 1024.0), point(2, 2048.0), point(3, 4096.0)],
+            object_count: Vec::new(),
+        };
+        let mut page = BucketMetricsPage::new(bucket_item(), metrics, ctx, tx);
+        let area = Rect::new(0, 0, 70, 13);
+        terminal.draw(|f| page.render(f, area))?;
+
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(70)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        #[rustfmt::skip]
+        let expected = [
+            "┌────────────────────────────────────────────────────────────────────┐",
+            "│ s3://bucket/ (last 30 days)                                        │",
+            "└────────────────────────────────────────────────────────────────────┘",
+            "┌ Size ──────────────────────────────────────────────────────────────┐",
+            "│ Latest: 4 KiB  Min: 1 KiB  Max: 4 KiB  (2024-01-01 - 2024-01-03)   │",
+            "│   █                                                                │",
+            "│ ▄██                                                                │",
+            "└────────────────────────────────────────────────────────────────────┘",
+            "┌ Objects ───────────────────────────────────────────────────────────┐",
+            "│ No data                                                            │",
+            "│                                                                    │",
+            "│                                                                    │",
+            "└────────────────────────────────────────────────────────────────────┘",
+        ];
+        assert_eq!(lines, expected);
+
+        Ok(())
+    }
+
+    fn bucket_item() -> BucketItem {
+        BucketItem {
+            name: "bucket".to_string(),
+            s3_uri: "s3://bucket/".to_string(),
+            arn: "arn:aws:s3:::bucket".to_string(),
+            virtual_hosted_url: "https://bucket.s3.us-east-1.amazonaws.com/".to_string(),
+            path_style_url: "https://s3.us-east-1.amazonaws.com/bucket/".to_string(),
+        }
+    }
+}
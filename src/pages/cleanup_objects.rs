@@ -0,0 +1,395 @@
+use std::{collections::HashSet, rc::Rc};
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::ListItem,
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    color::ColorTheme,
+    config::UiConfig,
+    event::{AppEventType, Sender},
+    format::format_datetime,
+    object::{CleanupObject, CleanupObjectType, ObjectKey},
+    pages::util::{build_delete_cost_line, build_helps, build_short_helps},
+    widget::{ConfirmDialog, ScrollList, ScrollListState},
+};
+
+const TYPE_WIDTH: usize = 16; // "Zero-byte object".len()
+
+#[derive(Debug)]
+pub struct CleanupObjectsPage {
+    object_key: ObjectKey,
+    objects: Vec<CleanupObject>,
+    checked: Vec<bool>,
+
+    view_state: ViewState,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+#[derive(Debug)]
+enum ViewState {
+    Default,
+    DeleteConfirmDialog,
+}
+
+impl CleanupObjectsPage {
+    pub fn new(
+        object_key: ObjectKey,
+        objects: Vec<CleanupObject>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let checked = vec![true; objects.len()];
+        let list_state = ScrollListState::new(objects.len());
+        Self {
+            object_key,
+            objects,
+            checked,
+            view_state: ViewState::Default,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match self.view_state {
+            ViewState::Default => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.tx.send(AppEventType::Quit);
+                }
+                key_code!(KeyCode::Backspace) => {
+                    self.tx.send(AppEventType::CloseCurrentPage);
+                }
+                key_code_char!('j') if self.non_empty() => {
+                    self.list_state.select_next();
+                }
+                key_code_char!('k') if self.non_empty() => {
+                    self.list_state.select_prev();
+                }
+                key_code_char!('g') if self.non_empty() => {
+                    self.list_state.select_first();
+                }
+                key_code_char!('G') if self.non_empty() => {
+                    self.list_state.select_last();
+                }
+                key_code_char!('f') if self.non_empty() => {
+                    self.list_state.select_next_page();
+                }
+                key_code_char!('b') if self.non_empty() => {
+                    self.list_state.select_prev_page();
+                }
+                key_code_char!(' ') if self.non_empty() => {
+                    self.toggle_checked();
+                }
+                key_code_char!('a') if self.non_empty() => {
+                    self.toggle_all_checked();
+                }
+                key_code_char!('d') if self.checked.contains(&true) => {
+                    self.view_state = ViewState::DeleteConfirmDialog;
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::DeleteConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.view_state = ViewState::Default;
+                }
+                key_code_char!('y') => {
+                    self.view_state = ViewState::Default;
+                    let keys = self.checked_objects().map(|obj| obj.key.clone()).collect();
+                    let bucket = self.object_key.bucket_name.clone();
+                    self.tx.send(AppEventType::StartDeleteObjects(bucket, keys));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+
+        let show_item_count = (area.height as usize) - 2 /* border */;
+        let prefix = self.object_key.joined_object_path(false);
+        let list_items: Vec<ListItem> = self
+            .objects
+            .iter()
+            .zip(&self.checked)
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, (obj, checked))| {
+                build_list_item(
+                    obj,
+                    *checked,
+                    &prefix,
+                    idx + offset == selected,
+                    area,
+                    &self.ctx.config.ui,
+                    &self.ctx.theme,
+                )
+            })
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, area, &mut self.list_state);
+
+        if let ViewState::DeleteConfirmDialog = self.view_state {
+            let count = |t: CleanupObjectType| {
+                self.checked_objects()
+                    .filter(|obj| obj.object_type == t)
+                    .count()
+            };
+            let empty_folders = count(CleanupObjectType::EmptyFolder);
+            let zero_byte_objects = count(CleanupObjectType::ZeroByteObject);
+            let mut lines = vec![
+                format!("Delete {} objects?", empty_folders + zero_byte_objects),
+                format!("  Empty folders: {}", empty_folders),
+                format!("  Zero-byte objects: {}", zero_byte_objects),
+            ];
+            lines.extend(build_delete_cost_line(
+                &self.ctx.config.cost,
+                empty_folders + zero_byte_objects,
+            ));
+            let dialog = ConfirmDialog::new("Delete", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["j/k"], "Select item"),
+                (&["g/G"], "Go to top/bottom"),
+                (&["f"], "Scroll page forward"),
+                (&["b"], "Scroll page backward"),
+                (&["Space"], "Toggle selected item"),
+                (&["a"], "Toggle all items"),
+                (&["d"], "Delete checked objects"),
+                (&["Backspace"], "Go back to object list"),
+            ],
+            ViewState::DeleteConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Delete objects"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+        };
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc"], "Quit", 0),
+                (&["j/k"], "Select", 3),
+                (&["Space"], "Toggle", 2),
+                (&["d"], "Delete", 1),
+                (&["Backspace"], "Go back", 4),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::DeleteConfirmDialog => &[(&["y"], "Delete", 1), (&["n"], "Cancel", 2)],
+        };
+        build_short_helps(helps)
+    }
+}
+
+impl CleanupObjectsPage {
+    fn toggle_checked(&mut self) {
+        let i = self.list_state.selected;
+        self.checked[i] = !self.checked[i];
+        if i < self.objects.len() - 1 {
+            self.list_state.select_next();
+        }
+    }
+
+    fn toggle_all_checked(&mut self) {
+        let all_checked = self.checked.iter().all(|c| *c);
+        self.checked.fill(!all_checked);
+    }
+
+    fn checked_objects(&self) -> impl Iterator<Item = &CleanupObject> {
+        self.objects
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, checked)| **checked)
+            .map(|(obj, _)| obj)
+    }
+
+    pub fn object_key(&self) -> &ObjectKey {
+        &self.object_key
+    }
+
+    pub fn remove_objects(&mut self, keys: &[String]) {
+        let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+        let (objects, checked) = self
+            .objects
+            .drain(..)
+            .zip(self.checked.drain(..))
+            .filter(|(obj, _)| !keys.contains(obj.key.as_str()))
+            .unzip();
+        self.objects = objects;
+        self.checked = checked;
+
+        let selected = self.list_state.selected;
+        self.list_state = ScrollListState::new(self.objects.len());
+        if self.non_empty() {
+            self.list_state
+                .select_index(selected.min(self.objects.len() - 1));
+        }
+    }
+
+    fn non_empty(&self) -> bool {
+        !self.objects.is_empty()
+    }
+}
+
+fn build_list_item<'a>(
+    obj: &'a CleanupObject,
+    checked: bool,
+    prefix: &str,
+    selected: bool,
+    area: Rect,
+    ui_config: &UiConfig,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let check = if checked { "[x]" } else { "[ ]" };
+    let key = obj.key.strip_prefix(prefix).unwrap_or(&obj.key);
+    let object_type = obj.object_type.name();
+    let date = format_datetime(&obj.last_modified, &ui_config.object_list.date_format);
+    let date_w: usize = ui_config.object_list.date_width;
+    let key_w: usize = (area.width as usize).saturating_sub(
+        date_w + TYPE_WIDTH + 3 /* check */ + 10 /* spaces */ + 4, /* border + pad */
+    );
+
+    let line = Line::from(vec![
+        format!(" {} ", check).into(),
+        format!("{:<key_w$}    ", key).into(),
+        format!("{:<TYPE_WIDTH$}", object_type).fg(theme.divider),
+        format!("    {:<date_w$} ", date).into(),
+    ]);
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(line).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event, set_cells};
+
+    use super::*;
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(70, 6))?;
+
+        let mut page = CleanupObjectsPage::new(object_key(), objects(), ctx, tx);
+        let area = Rect::new(0, 0, 70, 6);
+        terminal.draw(|f| page.render(f, area))?;
+        page.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+        terminal.draw(|f| page.render(f, area))?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌───────────────────────────────────────────────────────────── 2 / 3 ┐",
+            "│  [ ] a/                    Empty folder        2024-01-01 00:00:00 │",
+            "│  [x] b.txt                 Zero-byte object    2024-01-02 00:00:00 │",
+            "│  [x] c/d/                  Empty folder        2024-01-03 00:00:00 │",
+            "│                                                                    │",
+            "└────────────────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            (2..68, [2]) => bg: Color::Cyan, fg: Color::Black,
+            (29..45, [1, 2, 3]) => fg: Color::DarkGray,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_toggle_and_remove_objects() {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut page = CleanupObjectsPage::new(object_key(), objects(), ctx, tx);
+
+        page.toggle_all_checked();
+        assert_eq!(page.checked, vec![false, false, false]);
+        page.toggle_all_checked();
+        assert_eq!(page.checked, vec![true, true, true]);
+
+        page.checked[1] = false;
+        let keys: Vec<&str> = page.checked_objects().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, vec!["x/a/", "x/c/d/"]);
+
+        page.remove_objects(&["x/a/".to_string(), "x/c/d/".to_string()]);
+        let keys: Vec<&str> = page.objects.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, vec!["x/b.txt"]);
+        assert_eq!(page.checked, vec![false]);
+    }
+
+    fn object_key() -> ObjectKey {
+        ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["x".to_string()],
+        }
+    }
+
+    fn objects() -> Vec<CleanupObject> {
+        let object =
+            |key: &str, object_type: CleanupObjectType, last_modified: &str| CleanupObject {
+                key: key.to_string(),
+                object_type,
+                last_modified: parse_datetime(last_modified),
+            };
+        vec![
+            object(
+                "x/a/",
+                CleanupObjectType::EmptyFolder,
+                "2024-01-01 00:00:00",
+            ),
+            object(
+                "x/b.txt",
+                CleanupObjectType::ZeroByteObject,
+                "2024-01-02 00:00:00",
+            ),
+            object(
+                "x/c/d/",
+                CleanupObjectType::EmptyFolder,
+                "2024-01-03 00:00:00",
+            ),
+        ]
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+}
@@ -0,0 +1,399 @@
+use std::{collections::HashSet, rc::Rc};
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, ListItem, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    color::ColorTheme,
+    event::{AppEventType, Sender},
+    format::format_size_byte,
+    object::{ObjectDiff, ObjectDiffType, ObjectKey, ObjectSummary},
+    pages::util::{build_helps, build_short_helps},
+    widget::{ConfirmDialog, ScrollList, ScrollListState},
+};
+
+const SIZE_WIDTH: usize = 10;
+
+#[derive(Debug)]
+pub struct CompareObjectsPage {
+    a_key: ObjectKey,
+    b_key: ObjectKey,
+    diffs: Vec<ObjectDiff>,
+
+    view_state: ViewState,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+#[derive(Debug)]
+enum ViewState {
+    Default,
+    SyncConfirmDialog,
+}
+
+impl CompareObjectsPage {
+    pub fn new(
+        a_key: ObjectKey,
+        b_key: ObjectKey,
+        diffs: Vec<ObjectDiff>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let list_state = ScrollListState::new(diffs.len());
+        Self {
+            a_key,
+            b_key,
+            diffs,
+            view_state: ViewState::Default,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match self.view_state {
+            ViewState::Default => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.tx.send(AppEventType::Quit);
+                }
+                key_code!(KeyCode::Backspace) => {
+                    self.tx.send(AppEventType::CloseCurrentPage);
+                }
+                key_code_char!('j') if self.non_empty() => {
+                    self.list_state.select_next();
+                }
+                key_code_char!('k') if self.non_empty() => {
+                    self.list_state.select_prev();
+                }
+                key_code_char!('g') if self.non_empty() => {
+                    self.list_state.select_first();
+                }
+                key_code_char!('G') if self.non_empty() => {
+                    self.list_state.select_last();
+                }
+                key_code_char!('f') if self.non_empty() => {
+                    self.list_state.select_next_page();
+                }
+                key_code_char!('b') if self.non_empty() => {
+                    self.list_state.select_prev_page();
+                }
+                key_code_char!('s') if self.sync_targets().next().is_some() => {
+                    self.view_state = ViewState::SyncConfirmDialog;
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::SyncConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.view_state = ViewState::Default;
+                }
+                key_code_char!('y') => {
+                    self.view_state = ViewState::Default;
+                    let keys = self.sync_targets().map(|diff| diff.key.clone()).collect();
+                    self.tx.send(AppEventType::StartSyncObjects(
+                        self.a_key.clone(),
+                        self.b_key.clone(),
+                        keys,
+                    ));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).split(area);
+
+        let count = |t: ObjectDiffType| self.diffs.iter().filter(|d| d.diff_type == t).count();
+        let header_lines = vec![
+            Line::from(format!("A: {}", location_uri(&self.a_key))),
+            Line::from(format!("B: {}", location_uri(&self.b_key))),
+            Line::from(format!(
+                "Only in A: {}, Only in B: {}, Different: {}",
+                count(ObjectDiffType::OnlyA),
+                count(ObjectDiffType::OnlyB),
+                count(ObjectDiffType::Different),
+            ))
+            .fg(self.ctx.theme.divider),
+        ];
+        let header = Paragraph::new(header_lines).block(
+            Block::bordered()
+                .padding(Padding::horizontal(1))
+                .fg(self.ctx.theme.fg),
+        );
+        f.render_widget(header, chunks[0]);
+
+        let list_area = chunks[1];
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+
+        let show_item_count = (list_area.height as usize).saturating_sub(2 /* border */);
+        let list_items: Vec<ListItem> = self
+            .diffs
+            .iter()
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, diff)| {
+                build_list_item(diff, idx + offset == selected, list_area, &self.ctx.theme)
+            })
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        if let ViewState::SyncConfirmDialog = self.view_state {
+            let count =
+                |t: ObjectDiffType| self.sync_targets().filter(|d| d.diff_type == t).count();
+            let total_size: usize = self
+                .sync_targets()
+                .filter_map(|diff| diff.a.as_ref())
+                .map(|obj| obj.size_byte)
+                .sum();
+            let lines = vec![
+                "Copy objects from A to B?".to_string(),
+                format!("  Missing in B: {}", count(ObjectDiffType::OnlyA)),
+                format!("  Different: {}", count(ObjectDiffType::Different)),
+                format!("  Total size: {}", format_size_byte(total_size)),
+            ];
+            let dialog = ConfirmDialog::new("Sync", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["j/k"], "Select item"),
+                (&["g/G"], "Go to top/bottom"),
+                (&["f"], "Scroll page forward"),
+                (&["b"], "Scroll page backward"),
+                (&["s"], "Copy missing and different objects from A to B"),
+                (&["Backspace"], "Go back to object list"),
+            ],
+            ViewState::SyncConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Copy objects"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+        };
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc"], "Quit", 0),
+                (&["j/k"], "Select", 2),
+                (&["s"], "Sync", 1),
+                (&["Backspace"], "Go back", 3),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::SyncConfirmDialog => &[(&["y"], "Copy", 1), (&["n"], "Cancel", 2)],
+        };
+        build_short_helps(helps)
+    }
+}
+
+impl CompareObjectsPage {
+    // Objects only in B are left as they are
+    fn sync_targets(&self) -> impl Iterator<Item = &ObjectDiff> {
+        self.diffs
+            .iter()
+            .filter(|diff| diff.diff_type != ObjectDiffType::OnlyB)
+    }
+
+    pub fn remove_diffs(&mut self, keys: &[String]) {
+        let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+        self.diffs.retain(|diff| !keys.contains(diff.key.as_str()));
+
+        let selected = self.list_state.selected;
+        self.list_state = ScrollListState::new(self.diffs.len());
+        if self.non_empty() {
+            self.list_state
+                .select_index(selected.min(self.diffs.len() - 1));
+        }
+    }
+
+    fn non_empty(&self) -> bool {
+        !self.diffs.is_empty()
+    }
+}
+
+fn location_uri(key: &ObjectKey) -> String {
+    format!("s3://{}/{}", key.bucket_name, key.joined_object_path(false))
+}
+
+fn build_list_item<'a>(
+    diff: &'a ObjectDiff,
+    selected: bool,
+    area: Rect,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let (mark, mark_color) = match diff.diff_type {
+        ObjectDiffType::OnlyA => ("-", theme.status_error),
+        ObjectDiffType::OnlyB => ("+", theme.status_success),
+        ObjectDiffType::Different => ("~", theme.status_warn),
+    };
+    let size = |obj: &Option<ObjectSummary>| match obj {
+        Some(obj) => format_size_byte(obj.size_byte),
+        None => "-".to_string(),
+    };
+    let key_w: usize = (area.width as usize).saturating_sub(
+        SIZE_WIDTH * 2 + 3 /* mark */ + 5 /* spaces */ + 4, /* border + pad */
+    );
+    let line = Line::from(vec![
+        format!(" {} ", mark).fg(mark_color),
+        format!(
+            "{:<key_w$}  {:>SIZE_WIDTH$}  {:>SIZE_WIDTH$} ",
+            diff.key,
+            size(&diff.a),
+            size(&diff.b)
+        )
+        .into(),
+    ]);
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(line).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event, set_cells};
+
+    use super::*;
+    use chrono::Local;
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(60, 10))?;
+
+        let mut page = CompareObjectsPage::new(
+            object_key("bucket-a", &["x"]),
+            object_key("bucket-b", &["y", "z"]),
+            diffs(),
+            ctx,
+            tx,
+        );
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌──────────────────────────────────────────────────────────┐",
+            "│ A: s3://bucket-a/x/                                      │",
+            "│ B: s3://bucket-b/y/z/                                    │",
+            "│ Only in A: 1, Only in B: 1, Different: 1                 │",
+            "└──────────────────────────────────────────────────────────┘",
+            "┌─────────────────────────────────────────────────── 1 / 3 ┐",
+            "│  ~ a.txt                               10 B        20 B  │",
+            "│  - b/c.txt                            1 KiB           -  │",
+            "│  + d.txt                                  -        10 B  │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            (2..42, [3]) => fg: Color::DarkGray,
+            (2..58, [6]) => bg: Color::Cyan, fg: Color::Black,
+            (2..5, [6]) => fg: Color::Yellow,
+            (2..5, [7]) => fg: Color::Red,
+            (2..5, [8]) => fg: Color::Green,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let mut page = CompareObjectsPage::new(
+            object_key("bucket-a", &["x"]),
+            object_key("bucket-b", &["y", "z"]),
+            diffs(),
+            ctx,
+            tx,
+        );
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert!(matches!(page.view_state, ViewState::SyncConfirmDialog));
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartSyncObjects(src_key, dst_key, keys) => {
+                Some((src_key, dst_key, keys))
+            }
+            _ => None,
+        });
+        let (src_key, dst_key, keys) = event.unwrap();
+        assert_eq!(src_key.bucket_name, "bucket-a");
+        assert_eq!(dst_key.bucket_name, "bucket-b");
+        assert_eq!(keys, vec!["a.txt", "b/c.txt"]);
+
+        page.remove_diffs(&keys);
+        let keys: Vec<&str> = page.diffs.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, vec!["d.txt"]);
+    }
+
+    fn object_key(bucket_name: &str, object_path: &[&str]) -> ObjectKey {
+        ObjectKey {
+            bucket_name: bucket_name.to_string(),
+            object_path: object_path.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn diffs() -> Vec<ObjectDiff> {
+        let summary = |key: &str, size_byte: usize| ObjectSummary {
+            key: key.to_string(),
+            size_byte,
+            last_modified: Local::now(),
+            e_tag: "etag".to_string(),
+        };
+        vec![
+            ObjectDiff {
+                key: "a.txt".to_string(),
+                diff_type: ObjectDiffType::Different,
+                a: Some(summary("x/a.txt", 10)),
+                b: Some(summary("y/z/a.txt", 20)),
+            },
+            ObjectDiff {
+                key: "b/c.txt".to_string(),
+                diff_type: ObjectDiffType::OnlyA,
+                a: Some(summary("x/b/c.txt", 1024)),
+                b: None,
+            },
+            ObjectDiff {
+                key: "d.txt".to_string(),
+                diff_type: ObjectDiffType::OnlyB,
+                a: None,
+                b: Some(summary("y/z/d.txt", 10)),
+            },
+        ]
+    }
+}
@@ -0,0 +1,421 @@
+use std::{collections::HashSet, rc::Rc};
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::ListItem,
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    color::ColorTheme,
+    config::UiConfig,
+    event::{AppEventType, Sender},
+    format::{format_datetime, format_size_byte},
+    object::{DuplicateObjectGroup, ObjectKey},
+    pages::util::{build_delete_cost_line, build_helps, build_short_helps},
+    widget::{ConfirmDialog, ScrollList, ScrollListState},
+};
+
+#[derive(Debug)]
+pub struct DuplicateObjectsPage {
+    object_key: ObjectKey,
+    groups: Vec<DuplicateObjectGroup>,
+    rows: Vec<DuplicateObjectsRow>,
+
+    view_state: ViewState,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DuplicateObjectsRow {
+    Group(usize),
+    Object(usize, usize),
+}
+
+#[derive(Debug)]
+enum ViewState {
+    Default,
+    DeleteConfirmDialog,
+}
+
+impl DuplicateObjectsPage {
+    pub fn new(
+        object_key: ObjectKey,
+        groups: Vec<DuplicateObjectGroup>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let rows = build_rows(&groups);
+        let list_state = ScrollListState::new(rows.len());
+        Self {
+            object_key,
+            groups,
+            rows,
+            view_state: ViewState::Default,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match self.view_state {
+            ViewState::Default => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.tx.send(AppEventType::Quit);
+                }
+                key_code!(KeyCode::Backspace) => {
+                    self.tx.send(AppEventType::CloseCurrentPage);
+                }
+                key_code_char!('j') if self.non_empty() => {
+                    self.list_state.select_next();
+                }
+                key_code_char!('k') if self.non_empty() => {
+                    self.list_state.select_prev();
+                }
+                key_code_char!('g') if self.non_empty() => {
+                    self.list_state.select_first();
+                }
+                key_code_char!('G') if self.non_empty() => {
+                    self.list_state.select_last();
+                }
+                key_code_char!('f') if self.non_empty() => {
+                    self.list_state.select_next_page();
+                }
+                key_code_char!('b') if self.non_empty() => {
+                    self.list_state.select_prev_page();
+                }
+                key_code_char!('d') if self.non_empty() => {
+                    self.view_state = ViewState::DeleteConfirmDialog;
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::DeleteConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.view_state = ViewState::Default;
+                }
+                key_code_char!('y') => {
+                    self.view_state = ViewState::Default;
+                    let (_, delete_keys) = self.selected_keep_and_delete_keys();
+                    let bucket = self.object_key.bucket_name.clone();
+                    self.tx
+                        .send(AppEventType::StartDeleteObjects(bucket, delete_keys));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+
+        let show_item_count = (area.height as usize) - 2 /* border */;
+        let prefix = self.object_key.joined_object_path(false);
+        let list_items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, row)| {
+                build_list_item(
+                    *row,
+                    &self.groups,
+                    &prefix,
+                    idx + offset == selected,
+                    area,
+                    &self.ctx.config.ui,
+                    &self.ctx.theme,
+                )
+            })
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, area, &mut self.list_state);
+
+        if let ViewState::DeleteConfirmDialog = self.view_state {
+            let (keep_key, delete_keys) = self.selected_keep_and_delete_keys();
+            let mut lines = vec![
+                format!("Delete {} duplicate objects, keeping:", delete_keys.len()),
+                format!("  {}", keep_key),
+            ];
+            lines.extend(build_delete_cost_line(
+                &self.ctx.config.cost,
+                delete_keys.len(),
+            ));
+            let dialog = ConfirmDialog::new("Delete", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["j/k"], "Select item"),
+                (&["g/G"], "Go to top/bottom"),
+                (&["f"], "Scroll page forward"),
+                (&["b"], "Scroll page backward"),
+                (&["d"], "Delete duplicates except selected (or oldest)"),
+                (&["Backspace"], "Go back to object list"),
+            ],
+            ViewState::DeleteConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Delete objects"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+        };
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = match self.view_state {
+            ViewState::Default => &[
+                (&["Esc"], "Quit", 0),
+                (&["j/k"], "Select", 2),
+                (&["d"], "Delete duplicates", 1),
+                (&["Backspace"], "Go back", 3),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::DeleteConfirmDialog => &[(&["y"], "Delete", 1), (&["n"], "Cancel", 2)],
+        };
+        build_short_helps(helps)
+    }
+}
+
+impl DuplicateObjectsPage {
+    // If an object is selected, it is kept. Otherwise the oldest object in the group is kept.
+    fn selected_keep_and_delete_keys(&self) -> (String, Vec<String>) {
+        let (gi, keep) = match self.rows[self.list_state.selected] {
+            DuplicateObjectsRow::Group(gi) => (gi, 0),
+            DuplicateObjectsRow::Object(gi, oi) => (gi, oi),
+        };
+        let objects = &self.groups[gi].objects;
+        let delete_keys = objects
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != keep)
+            .map(|(_, obj)| obj.key.clone())
+            .collect();
+        (objects[keep].key.clone(), delete_keys)
+    }
+
+    pub fn object_key(&self) -> &ObjectKey {
+        &self.object_key
+    }
+
+    pub fn remove_objects(&mut self, keys: &[String]) {
+        let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+        for group in &mut self.groups {
+            group.objects.retain(|obj| !keys.contains(obj.key.as_str()));
+        }
+        self.groups.retain(|group| group.objects.len() > 1);
+        self.rows = build_rows(&self.groups);
+
+        let selected = self.list_state.selected;
+        self.list_state = ScrollListState::new(self.rows.len());
+        if self.non_empty() {
+            self.list_state
+                .select_index(selected.min(self.rows.len() - 1));
+        }
+    }
+
+    fn non_empty(&self) -> bool {
+        !self.rows.is_empty()
+    }
+}
+
+fn build_rows(groups: &[DuplicateObjectGroup]) -> Vec<DuplicateObjectsRow> {
+    groups
+        .iter()
+        .enumerate()
+        .flat_map(|(gi, group)| {
+            let objects =
+                (0..group.objects.len()).map(move |oi| DuplicateObjectsRow::Object(gi, oi));
+            std::iter::once(DuplicateObjectsRow::Group(gi)).chain(objects)
+        })
+        .collect()
+}
+
+fn build_list_item<'a>(
+    row: DuplicateObjectsRow,
+    groups: &'a [DuplicateObjectGroup],
+    prefix: &str,
+    selected: bool,
+    area: Rect,
+    ui_config: &UiConfig,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let line = match row {
+        DuplicateObjectsRow::Group(gi) => {
+            let group = &groups[gi];
+            let summary = format!(
+                " {} objects, {} each ({} reclaimable)",
+                group.objects.len(),
+                format_size_byte(group.size_byte),
+                format_size_byte(group.reclaimable_size_byte()),
+            );
+            Line::from(vec![
+                summary.bold(),
+                "  ETag: ".fg(theme.divider),
+                group.e_tag.as_str().fg(theme.divider),
+            ])
+        }
+        DuplicateObjectsRow::Object(gi, oi) => {
+            let obj = &groups[gi].objects[oi];
+            let key = obj.key.strip_prefix(prefix).unwrap_or(&obj.key);
+            let date = format_datetime(&obj.last_modified, &ui_config.object_list.date_format);
+            let date_w: usize = ui_config.object_list.date_width;
+            let key_w: usize = (area.width as usize).saturating_sub(
+                date_w + 3 /* indent */ + 5 /* spaces */ + 4, /* border + pad */
+            );
+            Line::from(format!("   {:<key_w$}    {:<date_w$} ", key, date))
+        }
+    };
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(line).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event, object::ObjectSummary, set_cells};
+
+    use super::*;
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::{
+        backend::TestBackend,
+        buffer::Buffer,
+        style::{Color, Modifier},
+        Terminal,
+    };
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(60, 8))?;
+
+        let mut page = DuplicateObjectsPage::new(object_key(), groups(), ctx, tx);
+        let area = Rect::new(0, 0, 60, 8);
+        terminal.draw(|f| page.render(f, area))?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌─────────────────────────────────────────────────── 1 / 6 ┐",
+            "│  2 objects, 1 KiB each (1 KiB reclaimable)  ETag: aaa    │",
+            "│    a.txt                            2024-01-01 00:00:00  │",
+            "│    b/a.txt                          2024-01-02 00:00:00  │",
+            "│  2 objects, 10 B each (10 B reclaimable)  ETag: bbb      │",
+            "│    c.txt                            2024-01-01 00:00:00  │",
+            "│    d.txt                            2024-01-01 00:00:00  │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+            (2..44, [1]) => modifier: Modifier::BOLD,
+            (44..55, [1]) => fg: Color::DarkGray,
+            (2..42, [4]) => modifier: Modifier::BOLD,
+            (42..53, [4]) => fg: Color::DarkGray,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_selected_keep_and_delete_keys() {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        let mut page = DuplicateObjectsPage::new(object_key(), groups(), ctx, tx);
+        terminal
+            .draw(|f| page.render(f, Rect::new(0, 0, 60, 8)))
+            .unwrap();
+
+        // the oldest object is kept if the group is selected
+        let (keep, delete) = page.selected_keep_and_delete_keys();
+        assert_eq!(keep, "x/a.txt");
+        assert_eq!(delete, vec!["x/b/a.txt"]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        let (keep, delete) = page.selected_keep_and_delete_keys();
+        assert_eq!(keep, "x/b/a.txt");
+        assert_eq!(delete, vec!["x/a.txt"]);
+
+        page.remove_objects(&delete);
+        assert_eq!(page.groups.len(), 1);
+        assert_eq!(
+            page.rows,
+            vec![
+                DuplicateObjectsRow::Group(0),
+                DuplicateObjectsRow::Object(0, 0),
+                DuplicateObjectsRow::Object(0, 1),
+            ]
+        );
+        assert_eq!(page.list_state.selected, 2);
+    }
+
+    fn object_key() -> ObjectKey {
+        ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["x".to_string()],
+        }
+    }
+
+    fn groups() -> Vec<DuplicateObjectGroup> {
+        let summary =
+            |key: &str, size_byte: usize, e_tag: &str, last_modified: &str| ObjectSummary {
+                key: key.to_string(),
+                size_byte,
+                last_modified: parse_datetime(last_modified),
+                e_tag: e_tag.to_string(),
+            };
+        vec![
+            DuplicateObjectGroup {
+                e_tag: "aaa".to_string(),
+                size_byte: 1024,
+                objects: vec![
+                    summary("x/a.txt", 1024, "aaa", "2024-01-01 00:00:00"),
+                    summary("x/b/a.txt", 1024, "aaa", "2024-01-02 00:00:00"),
+                ],
+            },
+            DuplicateObjectGroup {
+                e_tag: "bbb".to_string(),
+                size_byte: 10,
+                objects: vec![
+                    summary("x/c.txt", 10, "bbb", "2024-01-01 00:00:00"),
+                    summary("x/d.txt", 10, "bbb", "2024-01-01 00:00:00"),
+                ],
+            },
+        ]
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+}
@@ -158,7 +158,7 @@ impl Widget for Help<'_> {
     }
 }
 
-fn build_help_lines(helps: &[String], max_width: usize) -> Vec<Line> {
+fn build_help_lines(helps: &[String], max_width: usize) -> Vec<Line<'_>> {
     let delimiter = ",  ";
     let word_groups = group_strings_to_fit_width(helps, max_width, delimiter);
     let lines: Vec<Line> = word_groups
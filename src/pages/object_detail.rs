@@ -1,5 +1,6 @@
-use std::rc::Rc;
+use std::{borrow::Cow, rc::Rc, time::Duration};
 
+use chrono::Local;
 use laurier::{key_code, key_code_char};
 use ratatui::{
     buffer::Buffer,
@@ -15,13 +16,18 @@ use crate::{
     app::AppContext,
     color::ColorTheme,
     config::UiConfig,
+    error::AppError,
     event::{AppEventType, Sender},
-    format::{format_datetime, format_size_byte, format_version},
-    object::{FileDetail, FileVersion, ObjectItem, ObjectKey},
+    format::{decode_key_name, format_datetime, format_size_byte, format_version},
+    lifecycle::{format_lifecycle_event, LifecycleEvent},
+    metadata::parse_user_metadata,
+    object::{ETagType, FileDetail, FileVersion, ObjectItem, ObjectKey},
     pages::util::{build_helps, build_short_helps},
     widget::{
-        Bar, CopyDetailDialog, CopyDetailDialogState, Divider, InputDialog, InputDialogState,
-        ScrollLines, ScrollLinesOptions, ScrollLinesState, ScrollList, ScrollListState,
+        parse_presign_expires_in, Bar, ConfirmDialog, CopyDetailDialog, CopyDetailDialogState,
+        Divider, InputDialog, InputDialogState, MetadataDialog, MetadataDialogState, PresignDialog,
+        PresignDialogState, ScrollLines, ScrollLinesOptions, ScrollLinesState, ScrollList,
+        ScrollListState, DEFAULT_PRESIGN_EXPIRES_IN,
     },
 };
 
@@ -29,18 +35,36 @@ use crate::{
 pub struct ObjectDetailPage {
     file_detail: FileDetail,
     file_versions: Vec<FileVersion>,
+    has_more_versions: bool,
+    lifecycle_events: Vec<LifecycleEvent>,
+    // computed by downloading the object, since a multipart ETag is not an MD5 of the content
+    content_md5: Option<String>,
+    // only for display, the raw keys are used for requests and copy actions
+    decode_key_names: bool,
     object_key: ObjectKey,
 
     tab: Tab,
+    version_sort: VersionSortType,
     view_state: ViewState,
 
     object_items: Vec<ObjectItem>,
     list_state: ScrollListState,
+    list_items_cache: Option<ListItemsCache>,
 
     ctx: Rc<AppContext>,
     tx: Sender,
 }
 
+// The list of the parent objects does not change on this page,
+// so the items are rebuilt only when the selection, the offset or the area changes.
+#[derive(Debug)]
+struct ListItemsCache {
+    offset: usize,
+    selected: usize,
+    area: Rect,
+    items: Vec<ListItem<'static>>,
+}
+
 #[derive(Debug)]
 enum Tab {
     Detail(DetailTabState),
@@ -61,6 +85,13 @@ enum ViewState {
     Default,
     SaveDialog(InputDialogState),
     CopyDetailDialog(Box<CopyDetailDialogState>),
+    PresignDialog(PresignDialogState),
+    PresignCustomDialog(InputDialogState),
+    MetadataDialog(Box<MetadataDialogState>),
+    MetadataValueDialog(Box<MetadataDialogState>, InputDialogState),
+    MetadataAddDialog(Box<MetadataDialogState>, InputDialogState),
+    LegalHoldConfirmDialog,
+    DeleteMarkerConfirmDialog,
 }
 
 impl ObjectDetailPage {
@@ -72,15 +103,30 @@ impl ObjectDetailPage {
         ctx: Rc<AppContext>,
         tx: Sender,
     ) -> Self {
-        let detail_tab_state = DetailTabState::new(&file_detail, &ctx.config.ui);
+        let decode_key_names = ctx.config.ui.object_list.decode_key_names;
+        let detail_tab_state = DetailTabState::new(
+            &file_detail,
+            &[],
+            false,
+            &[],
+            None,
+            decode_key_names,
+            &ctx.config.ui,
+        );
         Self {
             file_detail,
             file_versions: Vec::new(),
+            has_more_versions: false,
+            lifecycle_events: Vec::new(),
+            content_md5: None,
+            decode_key_names,
             object_key,
             tab: Tab::Detail(detail_tab_state),
+            version_sort: VersionSortType::default(),
             view_state: ViewState::Default,
             object_items,
             list_state,
+            list_items_cache: None,
             ctx,
             tx,
         }
@@ -124,6 +170,36 @@ impl ObjectDetailPage {
                         state.select_last();
                     }
                 }
+                key_code_char!('f') => {
+                    if let Tab::Version(ref mut state) = self.tab {
+                        state.select_next_page();
+                    }
+                }
+                key_code_char!('b') => {
+                    if let Tab::Version(ref mut state) = self.tab {
+                        state.select_prev_page();
+                    }
+                }
+                key_code_char!('o') => {
+                    if let Tab::Version(_) = self.tab {
+                        self.toggle_version_sort();
+                    }
+                }
+                key_code_char!('m') => {
+                    if let Tab::Version(_) = self.tab {
+                        self.load_more_versions();
+                    }
+                }
+                key_code_char!('c') => {
+                    if let Tab::Version(_) = self.tab {
+                        self.compare_with_latest();
+                    }
+                }
+                key_code_char!('D') => {
+                    if let Tab::Version(_) = self.tab {
+                        self.open_delete_marker_confirm_dialog();
+                    }
+                }
                 key_code_char!('s') => {
                     self.download();
                 }
@@ -136,6 +212,24 @@ impl ObjectDetailPage {
                 key_code_char!('r') => {
                     self.open_copy_detail_dialog();
                 }
+                key_code_char!('P') => {
+                    self.open_presign_dialog();
+                }
+                key_code_char!('e') => {
+                    if let Tab::Detail(_) = self.tab {
+                        self.open_metadata_dialog();
+                    }
+                }
+                key_code_char!('L') => {
+                    if let Tab::Detail(_) = self.tab {
+                        self.open_legal_hold_confirm_dialog();
+                    }
+                }
+                key_code_char!('M') => {
+                    if let Tab::Detail(_) = self.tab {
+                        self.compute_content_md5();
+                    }
+                }
                 key_code_char!('x') => {
                     self.open_management_console();
                 }
@@ -164,8 +258,106 @@ impl ObjectDetailPage {
                     self.close_copy_detail_dialog();
                 }
                 key_code!(KeyCode::Enter) => {
-                    let (name, value) = state.selected_name_and_value();
-                    self.tx.send(AppEventType::CopyToClipboard(name, value));
+                    if let Some((key, version_id)) = state.selected_presigned_url_target() {
+                        let bucket = self.object_key.bucket_name.clone();
+                        let expires_in = DEFAULT_PRESIGN_EXPIRES_IN;
+                        self.tx.send(AppEventType::CopyPresignedUrl(
+                            bucket, key, version_id, expires_in,
+                        ));
+                    } else {
+                        let (name, value) = state.selected_name_and_value();
+                        self.tx.send(AppEventType::CopyToClipboard(name, value));
+                    }
+                }
+                key_code_char!('j') => {
+                    state.select_next();
+                }
+                key_code_char!('k') => {
+                    state.select_prev();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::PresignDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) | key_code!(KeyCode::Backspace) => {
+                    self.close_presign_dialog();
+                }
+                key_code!(KeyCode::Enter) => match state.selected().duration() {
+                    Some(expires_in) => {
+                        self.copy_presigned_url(expires_in);
+                        self.close_presign_dialog();
+                    }
+                    None => {
+                        self.view_state = ViewState::PresignCustomDialog(Default::default());
+                    }
+                },
+                key_code_char!('j') => {
+                    state.select_next();
+                }
+                key_code_char!('k') => {
+                    state.select_prev();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::PresignCustomDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_presign_dialog();
+                }
+                key_code!(KeyCode::Enter) => match parse_presign_expires_in(state.input()) {
+                    Some(expires_in) => {
+                        self.copy_presigned_url(expires_in);
+                        self.close_presign_dialog();
+                    }
+                    None => {
+                        let msg = "Expiry must be like 90s, 30m, 12h or 7d (up to 7 days)";
+                        self.tx.send(AppEventType::NotifyError(AppError::msg(msg)));
+                    }
+                },
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::LegalHoldConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_legal_hold_confirm_dialog();
+                }
+                key_code_char!('y') => {
+                    self.toggle_legal_hold();
+                }
+                _ => {}
+            },
+            ViewState::DeleteMarkerConfirmDialog => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_delete_marker_confirm_dialog();
+                }
+                key_code_char!('y') => {
+                    self.remove_delete_marker();
+                }
+                _ => {}
+            },
+            ViewState::MetadataDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) | key_code!(KeyCode::Backspace) => {
+                    self.close_metadata_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    self.open_metadata_input_dialog(false);
+                }
+                key_code_char!('a') => {
+                    self.open_metadata_input_dialog(true);
+                }
+                key_code_char!('d') => {
+                    state.remove_selected();
+                }
+                key_code_char!('s') => {
+                    self.update_metadata();
                 }
                 key_code_char!('j') => {
                     state.select_next();
@@ -178,23 +370,48 @@ impl ObjectDetailPage {
                 }
                 _ => {}
             },
+            ViewState::MetadataValueDialog(ref mut state, ref mut input_state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_metadata_input_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    state.set_selected_value(input_state.input().trim().into());
+                    self.close_metadata_input_dialog();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {
+                    input_state.handle_key_event(key);
+                }
+            },
+            ViewState::MetadataAddDialog(ref mut state, ref mut input_state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_metadata_input_dialog();
+                }
+                key_code!(KeyCode::Enter) => match parse_user_metadata(input_state.input()) {
+                    Ok((name, value)) => {
+                        state.add_user_metadata(name, value);
+                        self.close_metadata_input_dialog();
+                    }
+                    Err(e) => {
+                        self.tx.send(AppEventType::NotifyError(e));
+                    }
+                },
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {
+                    input_state.handle_key_event(key);
+                }
+            },
         }
     }
 
     pub fn render(&mut self, f: &mut Frame, area: Rect) {
         let chunks = Layout::horizontal(Constraint::from_percentages([50, 50])).split(area);
 
-        let offset = self.list_state.offset;
-        let selected = self.list_state.selected;
-
-        let list_items = build_list_items_from_object_items(
-            &self.object_items,
-            offset,
-            selected,
-            chunks[0],
-            &self.ctx.theme,
-        );
-
+        let list_items = self.list_items(chunks[0]);
         let list = ScrollList::new(list_items).theme(&self.ctx.theme);
         f.render_stateful_widget(list, chunks[0], &mut self.list_state);
 
@@ -234,6 +451,61 @@ impl ObjectDetailPage {
             let copy_detail_dialog = CopyDetailDialog::default().theme(&self.ctx.theme);
             f.render_stateful_widget(copy_detail_dialog, area, state);
         }
+
+        if let ViewState::PresignDialog(state) = &self.view_state {
+            let presign_dialog = PresignDialog::new(*state).theme(&self.ctx.theme);
+            f.render_widget(presign_dialog, area);
+        }
+
+        if let ViewState::PresignCustomDialog(state) = &mut self.view_state {
+            let custom_dialog = InputDialog::default()
+                .title("Expiry (e.g. 30m, 12h, 7d)")
+                .max_width(40)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(custom_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::LegalHoldConfirmDialog = self.view_state {
+            let lines = build_legal_hold_dialog_lines(&self.file_detail);
+            let dialog = ConfirmDialog::new("Legal hold", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+
+        if let ViewState::DeleteMarkerConfirmDialog = self.view_state {
+            if let Some(version) = self.current_selected_version() {
+                let lines = build_delete_marker_dialog_lines(version);
+                let dialog = ConfirmDialog::new("Delete marker", lines).theme(&self.ctx.theme);
+                f.render_widget(dialog, area);
+            }
+        }
+
+        let (metadata_state, input_state, input_title) = match &mut self.view_state {
+            ViewState::MetadataDialog(state) => (state, None, ""),
+            ViewState::MetadataValueDialog(state, input_state) => {
+                let title = state.selected_title();
+                (state, Some(input_state), title)
+            }
+            ViewState::MetadataAddDialog(state, input_state) => {
+                (state, Some(input_state), "Metadata (name=value)")
+            }
+            _ => return,
+        };
+        let metadata_dialog = MetadataDialog::default().theme(&self.ctx.theme);
+        f.render_stateful_widget(metadata_dialog, area, metadata_state);
+
+        if let Some(input_state) = input_state {
+            let input_dialog = InputDialog::default()
+                .title(input_title)
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(input_dialog, area, input_state);
+
+            let (cursor_x, cursor_y) = input_state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
     }
 
     pub fn helps(&self) -> Vec<String> {
@@ -245,6 +517,10 @@ impl ObjectDetailPage {
                     (&["Backspace"], "Close detail panel"),
                     (&["j/k"], "Scroll forward/backward"),
                     (&["r"], "Open copy dialog"),
+                    (&["P"], "Copy presigned URL"),
+                    (&["e"], "Edit metadata"),
+                    (&["L"], "Turn legal hold on/off"),
+                    (&["M"], "Compute MD5 of the content"),
                     (&["s"], "Download object"),
                     (&["S"], "Download object as"),
                     (&["p"], "Preview object"),
@@ -255,11 +531,18 @@ impl ObjectDetailPage {
                     (&["h/l"], "Select tabs"),
                     (&["j/k"], "Select version"),
                     (&["g/G"], "Go to top/bottom"),
+                    (&["f"], "Scroll page forward"),
+                    (&["b"], "Scroll page backward"),
+                    (&["o"], "Toggle sort order (newest/oldest first)"),
+                    (&["m"], "Load more versions"),
                     (&["Backspace"], "Close detail panel"),
                     (&["r"], "Open copy dialog"),
+                    (&["P"], "Copy presigned URL"),
                     (&["s"], "Download object"),
                     (&["S"], "Download object as"),
                     (&["p"], "Preview object"),
+                    (&["c"], "Preview side-by-side with latest version"),
+                    (&["D"], "Remove delete marker"),
                     (&["x"], "Open management console in browser"),
                 ],
             },
@@ -274,6 +557,41 @@ impl ObjectDetailPage {
                 (&["j/k"], "Select item"),
                 (&["Enter"], "Copy selected value to clipboard"),
             ],
+            ViewState::PresignDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace"], "Close presign dialog"),
+                (&["j/k"], "Select expiry"),
+                (&["Enter"], "Copy presigned URL to clipboard"),
+            ],
+            ViewState::PresignCustomDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close presign dialog"),
+                (&["Enter"], "Copy presigned URL to clipboard"),
+            ],
+            ViewState::MetadataDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace"], "Close metadata dialog"),
+                (&["j/k"], "Select item"),
+                (&["Enter"], "Edit selected value"),
+                (&["a"], "Add user-defined metadata"),
+                (&["d"], "Remove selected metadata"),
+                (&["s"], "Save metadata"),
+            ],
+            ViewState::MetadataValueDialog(..) | ViewState::MetadataAddDialog(..) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close input dialog"),
+                (&["Enter"], "Set value"),
+            ],
+            ViewState::LegalHoldConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Turn legal hold on/off"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+            ViewState::DeleteMarkerConfirmDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Remove delete marker"),
+                (&["n", "Esc"], "Cancel"),
+            ],
         };
         build_helps(helps)
     }
@@ -294,6 +612,7 @@ impl ObjectDetailPage {
                     (&["Esc"], "Quit", 0),
                     (&["h/l"], "Select tabs", 3),
                     (&["j/k"], "Select", 5),
+                    (&["o"], "Sort", 6),
                     (&["s/S"], "Download", 1),
                     (&["p"], "Preview", 4),
                     (&["Backspace"], "Close", 2),
@@ -311,6 +630,33 @@ impl ObjectDetailPage {
                 (&["Enter"], "Copy", 1),
                 (&["?"], "Help", 0),
             ],
+            ViewState::PresignDialog(_) => &[
+                (&["Esc"], "Close", 2),
+                (&["j/k"], "Select", 3),
+                (&["Enter"], "Copy", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::PresignCustomDialog(_) => &[
+                (&["Esc"], "Close", 2),
+                (&["Enter"], "Copy", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::MetadataDialog(_) => &[
+                (&["Esc"], "Close", 2),
+                (&["j/k"], "Select", 3),
+                (&["Enter"], "Edit", 1),
+                (&["a"], "Add", 4),
+                (&["d"], "Remove", 5),
+                (&["s"], "Save", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::MetadataValueDialog(..) | ViewState::MetadataAddDialog(..) => &[
+                (&["Esc"], "Close", 2),
+                (&["Enter"], "Set", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::LegalHoldConfirmDialog => &[(&["y"], "Apply", 1), (&["n"], "Cancel", 2)],
+            ViewState::DeleteMarkerConfirmDialog => &[(&["y"], "Remove", 1), (&["n"], "Cancel", 2)],
         };
 
         build_short_helps(helps)
@@ -318,6 +664,34 @@ impl ObjectDetailPage {
 }
 
 impl ObjectDetailPage {
+    fn list_items(&mut self, area: Rect) -> Vec<ListItem<'static>> {
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+        let cache = match self.list_items_cache.take() {
+            Some(cache)
+                if cache.offset == offset && cache.selected == selected && cache.area == area =>
+            {
+                cache
+            }
+            _ => ListItemsCache {
+                offset,
+                selected,
+                area,
+                items: build_list_items_from_object_items(
+                    &self.object_items,
+                    self.decode_key_names,
+                    offset,
+                    selected,
+                    area,
+                    &self.ctx.theme,
+                ),
+            },
+        };
+        let items = cache.items.clone();
+        self.list_items_cache = Some(cache);
+        items
+    }
+
     fn toggle_tab(&mut self) {
         match self.tab {
             Tab::Detail(_) => {
@@ -332,22 +706,102 @@ impl ObjectDetailPage {
     }
 
     pub fn select_detail_tab(&mut self) {
-        self.tab = Tab::Detail(DetailTabState::new(&self.file_detail, &self.ctx.config.ui));
+        self.tab = Tab::Detail(DetailTabState::new(
+            &self.file_detail,
+            &self.file_versions,
+            self.has_more_versions,
+            &self.lifecycle_events,
+            self.content_md5.as_deref(),
+            self.decode_key_names,
+            &self.ctx.config.ui,
+        ));
     }
 
     pub fn select_versions_tab(&mut self) {
         self.tab = Tab::Version(VersionTabState::new(
             &self.file_versions,
+            self.version_sort,
+            self.has_more_versions,
             &self.ctx.config.ui,
         ));
     }
 
+    fn toggle_version_sort(&mut self) {
+        self.version_sort = self.version_sort.toggle();
+        self.select_versions_tab();
+    }
+
+    pub fn set_file_detail(&mut self, file_detail: FileDetail) {
+        self.file_detail = file_detail;
+        if let Tab::Detail(_) = self.tab {
+            self.select_detail_tab();
+        }
+    }
+
     pub fn set_versions(&mut self, versions: Vec<FileVersion>) {
         self.file_versions = versions;
     }
 
+    pub fn append_versions(&mut self, versions: Vec<FileVersion>) {
+        if let Tab::Version(ref mut state) = self.tab {
+            state.append_versions(&versions, &self.ctx.config.ui);
+        }
+        self.file_versions.extend(versions);
+    }
+
+    pub fn set_lifecycle_events(&mut self, events: Vec<LifecycleEvent>) {
+        self.lifecycle_events = events;
+        if let Tab::Detail(_) = self.tab {
+            self.select_detail_tab();
+        }
+    }
+
+    pub fn set_decode_key_names(&mut self, decode: bool) {
+        if self.decode_key_names == decode {
+            return;
+        }
+        self.decode_key_names = decode;
+        self.list_items_cache = None;
+        if let Tab::Detail(_) = self.tab {
+            self.select_detail_tab();
+        }
+    }
+
+    pub fn set_content_md5(&mut self, md5: String) {
+        self.content_md5 = Some(md5);
+        if let Tab::Detail(_) = self.tab {
+            self.select_detail_tab();
+        }
+    }
+
+    pub fn set_has_more_versions(&mut self, has_more: bool) {
+        self.has_more_versions = has_more;
+        if let Tab::Version(ref mut state) = self.tab {
+            state.has_more = has_more;
+        }
+    }
+
+    fn load_more_versions(&self) {
+        if self.has_more_versions {
+            self.tx.send(AppEventType::LoadMoreObjectVersions);
+        }
+    }
+
     fn open_save_dialog(&mut self) {
-        self.view_state = ViewState::SaveDialog(InputDialogState::default());
+        if self.warn_if_delete_marker_selected() {
+            return;
+        }
+        let version_id = self.current_selected_version_id();
+        let name = self.ctx.config.download.file_name(
+            &self.object_key.bucket_name,
+            &self.file_detail.key,
+            &self.file_detail.name,
+            version_id.as_deref(),
+            Local::now(),
+        );
+        let mut dialog_state = InputDialogState::default();
+        dialog_state.set_input(&name);
+        self.view_state = ViewState::SaveDialog(dialog_state);
     }
 
     pub fn close_save_dialog(&mut self) {
@@ -374,7 +828,96 @@ impl ObjectDetailPage {
         self.view_state = ViewState::Default;
     }
 
+    fn open_presign_dialog(&mut self) {
+        if self.warn_if_delete_marker_selected() {
+            return;
+        }
+        self.view_state = ViewState::PresignDialog(PresignDialogState::default());
+    }
+
+    fn close_presign_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn copy_presigned_url(&self, expires_in: Duration) {
+        let bucket = self.object_key.bucket_name.clone();
+        let key = self.file_detail.key.clone();
+        let version_id = self.current_selected_version_id();
+        self.tx.send(AppEventType::CopyPresignedUrl(
+            bucket, key, version_id, expires_in,
+        ));
+    }
+
+    fn open_metadata_dialog(&mut self) {
+        let state = MetadataDialogState::new(self.file_detail.metadata());
+        self.view_state = ViewState::MetadataDialog(Box::new(state));
+    }
+
+    fn close_metadata_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn open_metadata_input_dialog(&mut self, add: bool) {
+        let view_state = std::mem::replace(&mut self.view_state, ViewState::Default);
+        if let ViewState::MetadataDialog(state) = view_state {
+            self.view_state = if add {
+                ViewState::MetadataAddDialog(state, InputDialogState::default())
+            } else {
+                let mut input_state = InputDialogState::default();
+                input_state.set_input(state.selected_value());
+                ViewState::MetadataValueDialog(state, input_state)
+            };
+        }
+    }
+
+    fn close_metadata_input_dialog(&mut self) {
+        let view_state = std::mem::replace(&mut self.view_state, ViewState::Default);
+        if let ViewState::MetadataValueDialog(state, _) | ViewState::MetadataAddDialog(state, _) =
+            view_state
+        {
+            self.view_state = ViewState::MetadataDialog(state);
+        }
+    }
+
+    fn update_metadata(&mut self) {
+        if let ViewState::MetadataDialog(state) = &self.view_state {
+            if state.is_changed() {
+                let map_key = self.object_key.clone();
+                let metadata = state.metadata().clone();
+                self.tx
+                    .send(AppEventType::StartUpdateObjectMetadata(map_key, metadata));
+            }
+        }
+        self.close_metadata_dialog();
+    }
+
+    fn open_legal_hold_confirm_dialog(&mut self) {
+        self.view_state = ViewState::LegalHoldConfirmDialog;
+    }
+
+    fn close_legal_hold_confirm_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn toggle_legal_hold(&mut self) {
+        let map_key = self.object_key.clone();
+        let on = self.file_detail.legal_hold != Some(true);
+        self.tx
+            .send(AppEventType::StartSetObjectLegalHold(map_key, on));
+        self.close_legal_hold_confirm_dialog();
+    }
+
+    fn compute_content_md5(&self) {
+        let file_detail = self.file_detail.clone();
+        let map_key = self.object_key.clone();
+        self.tx
+            .send(AppEventType::StartComputeObjectMd5(file_detail, map_key));
+    }
+
     fn download(&self) {
+        if self.warn_if_delete_marker_selected() {
+            return;
+        }
         let file_detail = self.file_detail.clone();
         let version_id = self.current_selected_version_id();
         self.tx
@@ -397,21 +940,78 @@ impl ObjectDetailPage {
     }
 
     fn preview(&self) {
+        if self.warn_if_delete_marker_selected() {
+            return;
+        }
         let file_detail = self.file_detail.clone();
         let version_id = self.current_selected_version_id();
         self.tx
             .send(AppEventType::OpenPreview(file_detail, version_id));
     }
 
-    fn open_management_console(&self) {
-        self.tx
-            .send(AppEventType::ObjectDetailOpenManagementConsole);
+    fn compare_with_latest(&self) {
+        if self.warn_if_delete_marker_selected() {
+            return;
+        }
+        let Some(version) = self.current_selected_version() else {
+            return;
+        };
+        let file_detail = self.file_detail.clone();
+        self.tx.send(AppEventType::OpenCompareVersionPreview(
+            file_detail,
+            version.clone(),
+        ));
     }
 
-    fn current_selected_version(&self) -> Option<&FileVersion> {
-        match &self.tab {
+    fn open_delete_marker_confirm_dialog(&mut self) {
+        match self.current_selected_version() {
+            Some(version) if version.is_delete_marker => {
+                self.view_state = ViewState::DeleteMarkerConfirmDialog;
+            }
+            Some(_) => {
+                let msg = "The selected version is not a delete marker";
+                self.tx.send(AppEventType::NotifyWarn(msg.into()));
+            }
+            None => {}
+        }
+    }
+
+    fn close_delete_marker_confirm_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn remove_delete_marker(&mut self) {
+        if let Some(version) = self.current_selected_version() {
+            let map_key = self.object_key.clone();
+            self.tx.send(AppEventType::StartRemoveDeleteMarker(
+                map_key,
+                version.clone(),
+            ));
+        }
+        self.close_delete_marker_confirm_dialog();
+    }
+
+    // A delete marker has no content, so it cannot be downloaded or previewed
+    fn warn_if_delete_marker_selected(&self) -> bool {
+        let selected = self
+            .current_selected_version()
+            .is_some_and(|v| v.is_delete_marker);
+        if selected {
+            let msg = "The selected version is a delete marker and has no content";
+            self.tx.send(AppEventType::NotifyWarn(msg.into()));
+        }
+        selected
+    }
+
+    fn open_management_console(&self) {
+        self.tx
+            .send(AppEventType::ObjectDetailOpenManagementConsole);
+    }
+
+    fn current_selected_version(&self) -> Option<&FileVersion> {
+        match &self.tab {
             Tab::Detail(_) => None,
-            Tab::Version(state) => self.file_versions.get(state.selected),
+            Tab::Version(state) => self.file_versions.get(state.selected_version_index()),
         }
     }
 
@@ -425,13 +1025,14 @@ impl ObjectDetailPage {
     }
 }
 
-fn build_list_items_from_object_items<'a>(
-    current_items: &'a [ObjectItem],
+fn build_list_items_from_object_items(
+    current_items: &[ObjectItem],
+    decode_key_names: bool,
     offset: usize,
     selected: usize,
     area: Rect,
     theme: &ColorTheme,
-) -> Vec<ListItem<'a>> {
+) -> Vec<ListItem<'static>> {
     let show_item_count = (area.height as usize) - 2 /* border */;
     current_items
         .iter()
@@ -439,27 +1040,41 @@ fn build_list_items_from_object_items<'a>(
         .take(show_item_count)
         .enumerate()
         .map(|(idx, item)| {
-            build_list_item_from_object_item(idx, item, offset, selected, area, theme)
+            build_list_item_from_object_item(
+                idx,
+                item,
+                decode_key_names,
+                offset,
+                selected,
+                area,
+                theme,
+            )
         })
         .collect()
 }
 
-fn build_list_item_from_object_item<'a>(
+fn build_list_item_from_object_item(
     idx: usize,
-    item: &'a ObjectItem,
+    item: &ObjectItem,
+    decode_key_names: bool,
     offset: usize,
     selected: usize,
     area: Rect,
     theme: &ColorTheme,
-) -> ListItem<'a> {
+) -> ListItem<'static> {
+    let name = if decode_key_names {
+        decode_key_name(item.name())
+    } else {
+        Cow::Borrowed(item.name())
+    };
     let content = match item {
-        ObjectItem::Dir { name, .. } => {
-            let content = format_dir_item(name, area.width);
+        ObjectItem::Dir { .. } => {
+            let content = format_dir_item(&name, area.width);
             let style = Style::default().add_modifier(Modifier::BOLD);
             Span::styled(content, style)
         }
-        ObjectItem::File { name, .. } => {
-            let content = format_file_item(name, area.width);
+        ObjectItem::File { .. } => {
+            let content = format_file_item(&name, area.width);
             let style = Style::default();
             Span::styled(content, style)
         }
@@ -498,43 +1113,186 @@ fn build_tabs(tab: &Tab, theme: &ColorTheme) -> Tabs<'static> {
         .block(Block::default().borders(Borders::BOTTOM))
 }
 
-fn build_detail_content_lines(detail: &FileDetail, ui_config: &UiConfig) -> Vec<Line<'static>> {
-    let details = [
-        ("Name:", &detail.name),
+fn build_detail_content_lines(
+    detail: &FileDetail,
+    versions: &[FileVersion],
+    has_more_versions: bool,
+    lifecycle_events: &[LifecycleEvent],
+    content_md5: Option<&str>,
+    decode_key_names: bool,
+    ui_config: &UiConfig,
+) -> Vec<Line<'static>> {
+    let name = if decode_key_names {
+        decode_key_name(&detail.name)
+    } else {
+        Cow::Borrowed(detail.name.as_str())
+    };
+    let mut details = build_label_value_lines(&[
+        ("Name:", &name),
         ("Size:", &format_size_byte(detail.size_byte)),
+        (
+            "Versions:",
+            &format_versions_summary(versions, has_more_versions),
+        ),
         (
             "Last Modified:",
             &format_datetime(&detail.last_modified, &ui_config.object_detail.date_format),
         ),
-        ("ETag:", &detail.e_tag),
+    ]);
+
+    if !detail.e_tag.is_empty() {
+        let mut lines = vec![
+            Line::from("ETag:".add_modifier(Modifier::BOLD)),
+            Line::from(format!(" {}", detail.e_tag)),
+        ];
+        if let ETagType::Multipart(parts) = ETagType::parse(&detail.e_tag) {
+            let note = format!(
+                " Multipart upload ({} parts), not an MD5 of the content",
+                parts
+            );
+            lines.push(Line::from(note.italic()));
+        }
+        details.push(lines);
+    }
+    if let Some(md5) = content_md5 {
+        details.push(vec![
+            Line::from("Content MD5:".add_modifier(Modifier::BOLD)),
+            Line::from(format!(" {}", md5)),
+        ]);
+    }
+
+    details.extend(build_label_value_lines(&[
         ("Content-Type:", &detail.content_type),
+        ("Cache-Control:", &detail.cache_control),
+        ("Content-Disposition:", &detail.content_disposition),
         ("Storage class:", &detail.storage_class),
-    ]
-    .iter()
-    .filter_map(|(label, value)| {
-        if value.is_empty() {
-            None
-        } else {
-            let lines = vec![
-                Line::from(label.add_modifier(Modifier::BOLD)),
-                Line::from(format!(" {}", value)),
-            ];
-            Some(lines)
-        }
-    })
-    .collect();
+    ]));
+
+    let object_lock_lines = build_object_lock_lines(detail, &ui_config.object_detail.date_format);
+    if !object_lock_lines.is_empty() {
+        let mut lines = vec![Line::from("Object Lock:".add_modifier(Modifier::BOLD))];
+        lines.extend(object_lock_lines);
+        details.push(lines);
+    }
+
+    if !detail.user_metadata.is_empty() {
+        let mut lines = vec![Line::from("Metadata:".add_modifier(Modifier::BOLD))];
+        lines.extend(
+            detail
+                .user_metadata
+                .iter()
+                .map(|(name, value)| Line::from(format!(" {}: {}", name, value))),
+        );
+        details.push(lines);
+    }
+
+    if !lifecycle_events.is_empty() {
+        let now = Local::now();
+        let mut lines = vec![Line::from("Lifecycle:".add_modifier(Modifier::BOLD))];
+        lines.extend(
+            lifecycle_events
+                .iter()
+                .map(|e| Line::from(format!(" {}", format_lifecycle_event(e, now)))),
+        );
+        details.push(lines);
+    }
 
     flatten_with_empty_lines(details)
 }
 
+fn build_label_value_lines(items: &[(&str, &str)]) -> Vec<Vec<Line<'static>>> {
+    items
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(label, value)| {
+            vec![
+                Line::from(label.to_string().add_modifier(Modifier::BOLD)),
+                Line::from(format!(" {}", value)),
+            ]
+        })
+        .collect()
+}
+
+fn build_object_lock_lines(detail: &FileDetail, date_format: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if !detail.object_lock_mode.is_empty() {
+        lines.push(Line::from(format!(" Mode: {}", detail.object_lock_mode)));
+    }
+    if let Some(retain_until) = &detail.object_lock_retain_until {
+        let retain_until = format_datetime(retain_until, date_format);
+        lines.push(Line::from(format!(" Retain until: {}", retain_until)));
+    }
+    if let Some(legal_hold) = detail.legal_hold {
+        let status = if legal_hold { "ON" } else { "OFF" };
+        lines.push(Line::from(format!(" Legal hold: {}", status)));
+    }
+    lines
+}
+
+fn build_legal_hold_dialog_lines(detail: &FileDetail) -> Vec<String> {
+    let action = if detail.legal_hold == Some(true) {
+        "Turn off"
+    } else {
+        "Turn on"
+    };
+    vec![
+        format!("{} legal hold for {}?", action, detail.name),
+        "  The bucket must have Object Lock enabled".to_string(),
+    ]
+}
+
+fn build_delete_marker_dialog_lines(version: &FileVersion) -> Vec<String> {
+    let mut lines = vec![
+        "Permanently remove this delete marker?".to_string(),
+        format!("  Version ID: {}", version.version_id),
+    ];
+    if version.is_latest {
+        lines.push("  The object will be restored to the previous version".to_string());
+    }
+    lines
+}
+
+fn format_versions_summary(versions: &[FileVersion], has_more: bool) -> String {
+    if versions.is_empty() {
+        // versions have not been loaded yet
+        return "".into();
+    }
+    let total_size_byte = versions.iter().map(|v| v.size_byte).sum();
+    // only the loaded versions are counted
+    let more = if has_more { "+" } else { "" };
+    format!(
+        "{}{} ({}{} total)",
+        versions.len(),
+        more,
+        format_size_byte(total_size_byte),
+        more
+    )
+}
+
 #[derive(Debug)]
 struct DetailTabState {
     scroll_lines_state: ScrollLinesState,
 }
 
 impl DetailTabState {
-    fn new(file_detail: &FileDetail, ui_config: &UiConfig) -> Self {
-        let scroll_lines = build_detail_content_lines(file_detail, ui_config);
+    fn new(
+        file_detail: &FileDetail,
+        versions: &[FileVersion],
+        has_more_versions: bool,
+        lifecycle_events: &[LifecycleEvent],
+        content_md5: Option<&str>,
+        decode_key_names: bool,
+        ui_config: &UiConfig,
+    ) -> Self {
+        let scroll_lines = build_detail_content_lines(
+            file_detail,
+            versions,
+            has_more_versions,
+            lifecycle_events,
+            content_md5,
+            decode_key_names,
+            ui_config,
+        );
         let scroll_lines_state =
             ScrollLinesState::new(scroll_lines, ScrollLinesOptions::new(false, true));
         Self { scroll_lines_state }
@@ -561,17 +1319,48 @@ impl StatefulWidget for DetailTab<'_> {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum VersionSortType {
+    // ListObjectVersions returns versions in this order
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+impl VersionSortType {
+    fn toggle(self) -> Self {
+        match self {
+            VersionSortType::NewestFirst => VersionSortType::OldestFirst,
+            VersionSortType::OldestFirst => VersionSortType::NewestFirst,
+        }
+    }
+}
+
 fn build_version_detail_lines(
     versions: &[FileVersion],
+    sort: VersionSortType,
     ui_config: &UiConfig,
 ) -> Vec<Vec<Line<'static>>> {
+    let versions: Box<dyn Iterator<Item = &FileVersion>> = match sort {
+        VersionSortType::NewestFirst => Box::new(versions.iter()),
+        VersionSortType::OldestFirst => Box::new(versions.iter().rev()),
+    };
     versions
-        .iter()
         .map(|v| {
             let version_id = format_version(&v.version_id).to_owned();
             let last_modified =
                 format_datetime(&v.last_modified, &ui_config.object_detail.date_format);
-            let size_byte = format_size_byte(v.size_byte);
+            let third_line = if v.is_delete_marker {
+                Line::from(vec![
+                    "         Type: ".add_modifier(Modifier::BOLD),
+                    "Delete marker".add_modifier(Modifier::ITALIC),
+                ])
+            } else {
+                Line::from(vec![
+                    "         Size: ".add_modifier(Modifier::BOLD),
+                    Span::raw(format_size_byte(v.size_byte)),
+                ])
+            };
             vec![
                 Line::from(vec![
                     "   Version ID: ".add_modifier(Modifier::BOLD),
@@ -581,10 +1370,7 @@ fn build_version_detail_lines(
                     "Last Modified: ".add_modifier(Modifier::BOLD),
                     Span::raw(last_modified),
                 ]),
-                Line::from(vec![
-                    "         Size: ".add_modifier(Modifier::BOLD),
-                    Span::raw(size_byte),
-                ]),
+                third_line,
             ]
         })
         .collect()
@@ -593,20 +1379,53 @@ fn build_version_detail_lines(
 #[derive(Debug, Default)]
 struct VersionTabState {
     lines: Vec<Vec<Line<'static>>>,
+    sort: VersionSortType,
+    has_more: bool,
     selected: usize,
     offset: usize,
     height: usize,
 }
 
 impl VersionTabState {
-    fn new(versions: &[FileVersion], ui_config: &UiConfig) -> Self {
-        let lines = build_version_detail_lines(versions, ui_config);
+    fn new(
+        versions: &[FileVersion],
+        sort: VersionSortType,
+        has_more: bool,
+        ui_config: &UiConfig,
+    ) -> Self {
+        let lines = build_version_detail_lines(versions, sort, ui_config);
         Self {
             lines,
+            sort,
+            has_more,
             ..Default::default()
         }
     }
 
+    // Additionally loaded versions are always older than the current ones
+    fn append_versions(&mut self, versions: &[FileVersion], ui_config: &UiConfig) {
+        let lines = build_version_detail_lines(versions, self.sort, ui_config);
+        match self.sort {
+            VersionSortType::NewestFirst => {
+                self.lines.extend(lines);
+            }
+            VersionSortType::OldestFirst => {
+                let n = lines.len();
+                self.lines.splice(0..0, lines);
+                self.selected += n;
+                self.offset += n;
+            }
+        }
+    }
+
+    // Index in the versions as returned by ListObjectVersions (newest first)
+    fn selected_version_index(&self) -> usize {
+        match self.sort {
+            VersionSortType::NewestFirst => self.selected,
+            VersionSortType::OldestFirst => self.lines.len() - 1 - self.selected,
+        }
+    }
+
     fn select_next(&mut self) {
         if self.selected >= self.lines.len() - 1 {
             return;
@@ -647,20 +1466,47 @@ impl VersionTabState {
 
     fn select_last(&mut self) {
         self.selected = self.lines.len() - 1;
+        self.offset = self.last_offset();
+    }
+
+    fn select_next_page(&mut self) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let n = self.page_size();
+        self.selected = (self.selected + n).min(self.lines.len() - 1);
+        self.offset = (self.offset + n).min(self.last_offset());
+    }
+
+    fn select_prev_page(&mut self) {
+        let n = self.page_size();
+        self.selected = self.selected.saturating_sub(n);
+        self.offset = self.offset.saturating_sub(n);
+    }
 
+    // Number of versions that fit in the area
+    fn page_size(&self) -> usize {
+        let item_height = self
+            .lines
+            .first()
+            .map_or(1, |lines| lines.len() + 1 /* divider */);
+        (self.height / item_height).max(1)
+    }
+
+    // Offset at which the last version is displayed at the bottom of the area
+    fn last_offset(&self) -> usize {
         let mut total_height = 0;
         for (i, lines) in self.lines.iter().enumerate().rev() {
             total_height += lines.len();
             total_height += 1; // divider
 
             if total_height == self.height {
-                self.offset = i;
-                break;
+                return i;
             } else if total_height > self.height {
-                self.offset = i + 1;
-                break;
+                return i + 1;
             }
         }
+        0
     }
 }
 
@@ -707,7 +1553,7 @@ impl StatefulWidget for VersionTab {
             if area.height < lines_count {
                 let version_paragraph = Paragraph::new("⋮").alignment(Alignment::Center);
                 version_paragraph.render(area, buf);
-                break;
+                return;
             }
 
             let divider_area_height = if area.height > lines_count { 1 } else { 0 };
@@ -737,6 +1583,13 @@ impl StatefulWidget for VersionTab {
             }
             version_paragraph.render(chunks[1], buf);
         }
+
+        if state.has_more {
+            let more_paragraph = Paragraph::new("Press m to load more versions")
+                .alignment(Alignment::Center)
+                .fg(self.color.divider);
+            more_paragraph.render(area, buf);
+        }
     }
 }
 
@@ -756,20 +1609,221 @@ fn flatten_with_empty_lines(line_groups: Vec<Vec<Line>>) -> Vec<Line> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{event, set_cells};
+    use crate::{event, lifecycle::LifecycleAction, object::ObjectMetadata, set_cells};
+
+    use super::*;
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::{backend::TestBackend, buffer::Buffer, crossterm::event::KeyCode, Terminal};
+
+    #[test]
+    fn test_render_detail_tab() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let (items, file_detail, _file_versions, object_key) = fixtures();
+            let items_len = items.len();
+            let mut page = ObjectDetailPage::new(
+                file_detail,
+                items,
+                object_key,
+                ScrollListState::new(items_len),
+                ctx,
+                tx,
+            );
+            let area = Rect::new(0, 0, 60, 20);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
+            "│  file1                     ││ Detail │ Version           │",
+            "│  file2                     ││────────────────────────────│",
+            "│  file3                     ││ Name:                      │",
+            "│                            ││  file1                     │",
+            "│                            ││                            │",
+            "│                            ││ Size:                      │",
+            "│                            ││  1.01 KiB                  │",
+            "│                            ││                            │",
+            "│                            ││ Last Modified:             │",
+            "│                            ││  2024-01-02 13:01:02       │",
+            "│                            ││                            │",
+            "│                            ││ ETag:                      │",
+            "│                            ││  bef684de-a260-48a4-8178-8 │",
+            "│                            ││ a535ecccadb                │",
+            "│                            ││                            │",
+            "│                            ││ Content-Type:              │",
+            "│                            ││  text/plain                │",
+            "│                            ││                            │",
+            "└────────────────────────────┘└────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // selected item
+            (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
+            // "Detail" is selected
+            (32..38, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
+            // "Name" label
+            (32..37, [3]) => modifier: Modifier::BOLD,
+            // "Size" label
+            (32..37, [6]) => modifier: Modifier::BOLD,
+            // "Last Modified" label
+            (32..46, [9]) => modifier: Modifier::BOLD,
+            // "ETag" label
+            (32..37, [12]) => modifier: Modifier::BOLD,
+            // "Content-Type" label
+            (32..45, [16]) => modifier: Modifier::BOLD,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_detail_tab_with_config() -> std::io::Result<()> {
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let (items, file_detail, _file_versions, object_key) = fixtures();
+            let items_len = items.len();
+            let mut ctx = AppContext::default();
+            ctx.config.ui.object_detail.date_format = "%Y/%m/%d".to_string();
+            let mut page = ObjectDetailPage::new(
+                file_detail,
+                items,
+                object_key,
+                ScrollListState::new(items_len),
+                Rc::new(ctx),
+                tx,
+            );
+            let area = Rect::new(0, 0, 60, 20);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
+            "│  file1                     ││ Detail │ Version           │",
+            "│  file2                     ││────────────────────────────│",
+            "│  file3                     ││ Name:                      │",
+            "│                            ││  file1                     │",
+            "│                            ││                            │",
+            "│                            ││ Size:                      │",
+            "│                            ││  1.01 KiB                  │",
+            "│                            ││                            │",
+            "│                            ││ Last Modified:             │",
+            "│                            ││  2024/01/02                │",
+            "│                            ││                            │",
+            "│                            ││ ETag:                      │",
+            "│                            ││  bef684de-a260-48a4-8178-8 │",
+            "│                            ││ a535ecccadb                │",
+            "│                            ││                            │",
+            "│                            ││ Content-Type:              │",
+            "│                            ││  text/plain                │",
+            "│                            ││                            │",
+            "└────────────────────────────┘└────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // selected item
+            (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
+            // "Detail" is selected
+            (32..38, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
+            // "Name" label
+            (32..37, [3]) => modifier: Modifier::BOLD,
+            // "Size" label
+            (32..37, [6]) => modifier: Modifier::BOLD,
+            // "Last Modified" label
+            (32..46, [9]) => modifier: Modifier::BOLD,
+            // "ETag" label
+            (32..37, [12]) => modifier: Modifier::BOLD,
+            // "Content-Type" label
+            (32..45, [16]) => modifier: Modifier::BOLD,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_version_tab() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let (items, file_detail, file_versions, object_key) = fixtures();
+            let items_len = items.len();
+            let mut page = ObjectDetailPage::new(
+                file_detail,
+                items,
+                object_key,
+                ScrollListState::new(items_len),
+                ctx,
+                tx,
+            );
+            page.set_versions(file_versions);
+            page.select_versions_tab();
+            let area = Rect::new(0, 0, 60, 20);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
+            "│  file1                     ││ Detail │ Version           │",
+            "│  file2                     ││────────────────────────────│",
+            "│  file3                     ││┃    Version ID: 60f36bc2-0f│",
+            "│                            ││┃ Last Modified: 2024-01-02 │",
+            "│                            ││┃          Size: 1.01 KiB   │",
+            "│                            ││────────────────────────────│",
+            "│                            ││     Version ID: 1c5d3bcc-2b│",
+            "│                            ││  Last Modified: 2024-01-01 │",
+            "│                            ││           Size: 1 KiB      │",
+            "│                            ││────────────────────────────│",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "└────────────────────────────┘└────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // selected item
+            (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
+            // "Version" is selected
+            (41..48, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
+            // "Version ID" label
+            (33..48, [3, 7]) => modifier: Modifier::BOLD,
+            // "Last Modified" label
+            (33..48, [4, 8]) => modifier: Modifier::BOLD,
+            // "Size" label
+            (33..48, [5, 9]) => modifier: Modifier::BOLD,
+            // selected bar
+            ([31], [3, 4, 5]) => fg: Color::Cyan,
+            // divider
+            (31..59, [6, 10]) => fg: Color::DarkGray,
+        }
+
+        terminal.backend().assert_buffer(&expected);
 
-    use super::*;
-    use chrono::{DateTime, Local, NaiveDateTime};
-    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+        Ok(())
+    }
 
     #[test]
-    fn test_render_detail_tab() -> std::io::Result<()> {
+    fn test_render_detail_tab_with_versions() -> std::io::Result<()> {
         let ctx = Rc::default();
         let (tx, _) = event::new();
         let mut terminal = setup_terminal()?;
 
         terminal.draw(|f| {
-            let (items, file_detail, _file_versions, object_key) = fixtures();
+            let (items, file_detail, file_versions, object_key) = fixtures();
             let items_len = items.len();
             let mut page = ObjectDetailPage::new(
                 file_detail,
@@ -779,6 +1833,8 @@ mod tests {
                 ctx,
                 tx,
             );
+            page.set_versions(file_versions);
+            page.select_detail_tab();
             let area = Rect::new(0, 0, 60, 20);
             page.render(f, area);
         })?;
@@ -794,6 +1850,9 @@ mod tests {
             "│                            ││ Size:                      │",
             "│                            ││  1.01 KiB                  │",
             "│                            ││                            │",
+            "│                            ││ Versions:                  │",
+            "│                            ││  2 (2.01 KiB total)        │",
+            "│                            ││                            │",
             "│                            ││ Last Modified:             │",
             "│                            ││  2024-01-02 13:01:02       │",
             "│                            ││                            │",
@@ -801,9 +1860,6 @@ mod tests {
             "│                            ││  bef684de-a260-48a4-8178-8 │",
             "│                            ││ a535ecccadb                │",
             "│                            ││                            │",
-            "│                            ││ Content-Type:              │",
-            "│                            ││  text/plain                │",
-            "│                            ││                            │",
             "└────────────────────────────┘└────────────────────────────┘",
         ]);
         set_cells! { expected =>
@@ -815,12 +1871,12 @@ mod tests {
             (32..37, [3]) => modifier: Modifier::BOLD,
             // "Size" label
             (32..37, [6]) => modifier: Modifier::BOLD,
+            // "Versions" label
+            (32..41, [9]) => modifier: Modifier::BOLD,
             // "Last Modified" label
-            (32..46, [9]) => modifier: Modifier::BOLD,
+            (32..46, [12]) => modifier: Modifier::BOLD,
             // "ETag" label
-            (32..37, [12]) => modifier: Modifier::BOLD,
-            // "Content-Type" label
-            (32..45, [16]) => modifier: Modifier::BOLD,
+            (32..37, [15]) => modifier: Modifier::BOLD,
         }
 
         terminal.backend().assert_buffer(&expected);
@@ -829,24 +1885,27 @@ mod tests {
     }
 
     #[test]
-    fn test_render_detail_tab_with_config() -> std::io::Result<()> {
+    fn test_render_version_tab_oldest_first() -> std::io::Result<()> {
+        let ctx = Rc::default();
         let (tx, _) = event::new();
         let mut terminal = setup_terminal()?;
 
+        let (items, file_detail, file_versions, object_key) = fixtures();
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+        page.set_versions(file_versions);
+        page.select_versions_tab();
+        page.handle_key(KeyEvent::from(KeyCode::Char('o')));
+
+        let area = Rect::new(0, 0, 60, 20);
         terminal.draw(|f| {
-            let (items, file_detail, _file_versions, object_key) = fixtures();
-            let items_len = items.len();
-            let mut ctx = AppContext::default();
-            ctx.config.ui.object_detail.date_format = "%Y/%m/%d".to_string();
-            let mut page = ObjectDetailPage::new(
-                file_detail,
-                items,
-                object_key,
-                ScrollListState::new(items_len),
-                Rc::new(ctx),
-                tx,
-            );
-            let area = Rect::new(0, 0, 60, 20);
             page.render(f, area);
         })?;
 
@@ -855,66 +1914,101 @@ mod tests {
             "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
             "│  file1                     ││ Detail │ Version           │",
             "│  file2                     ││────────────────────────────│",
-            "│  file3                     ││ Name:                      │",
-            "│                            ││  file1                     │",
+            "│  file3                     ││┃    Version ID: 1c5d3bcc-2b│",
+            "│                            ││┃ Last Modified: 2024-01-01 │",
+            "│                            ││┃          Size: 1 KiB      │",
+            "│                            ││────────────────────────────│",
+            "│                            ││     Version ID: 60f36bc2-0f│",
+            "│                            ││  Last Modified: 2024-01-02 │",
+            "│                            ││           Size: 1.01 KiB   │",
+            "│                            ││────────────────────────────│",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
             "│                            ││                            │",
-            "│                            ││ Size:                      │",
-            "│                            ││  1.01 KiB                  │",
             "│                            ││                            │",
-            "│                            ││ Last Modified:             │",
-            "│                            ││  2024/01/02                │",
             "│                            ││                            │",
-            "│                            ││ ETag:                      │",
-            "│                            ││  bef684de-a260-48a4-8178-8 │",
-            "│                            ││ a535ecccadb                │",
             "│                            ││                            │",
-            "│                            ││ Content-Type:              │",
-            "│                            ││  text/plain                │",
             "│                            ││                            │",
             "└────────────────────────────┘└────────────────────────────┘",
         ]);
         set_cells! { expected =>
             // selected item
             (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
-            // "Detail" is selected
-            (32..38, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
-            // "Name" label
-            (32..37, [3]) => modifier: Modifier::BOLD,
-            // "Size" label
-            (32..37, [6]) => modifier: Modifier::BOLD,
+            // "Version" is selected
+            (41..48, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
+            // "Version ID" label
+            (33..48, [3, 7]) => modifier: Modifier::BOLD,
             // "Last Modified" label
-            (32..46, [9]) => modifier: Modifier::BOLD,
-            // "ETag" label
-            (32..37, [12]) => modifier: Modifier::BOLD,
-            // "Content-Type" label
-            (32..45, [16]) => modifier: Modifier::BOLD,
+            (33..48, [4, 8]) => modifier: Modifier::BOLD,
+            // "Size" label
+            (33..48, [5, 9]) => modifier: Modifier::BOLD,
+            // selected bar
+            ([31], [3, 4, 5]) => fg: Color::Cyan,
+            // divider
+            (31..59, [6, 10]) => fg: Color::DarkGray,
         }
 
         terminal.backend().assert_buffer(&expected);
 
+        assert_eq!(
+            page.current_selected_version_id(),
+            Some("1c5d3bcc-2bb3-4cd5-875f-a95a6ae53f65".to_string())
+        );
+
         Ok(())
     }
 
     #[test]
-    fn test_render_version_tab() -> std::io::Result<()> {
+    fn test_version_tab_state_select_page() {
+        let (_, _, file_versions, _) = fixtures();
+        let versions: Vec<FileVersion> = file_versions.iter().cycle().take(7).cloned().collect();
+        let mut state = VersionTabState::new(
+            &versions,
+            VersionSortType::default(),
+            false,
+            &UiConfig::default(),
+        );
+        state.height = 10; // 2 versions per page
+
+        state.select_next_page();
+        assert_eq!((state.selected, state.offset), (2, 2));
+        state.select_next_page();
+        assert_eq!((state.selected, state.offset), (4, 4));
+        state.select_next_page();
+        assert_eq!((state.selected, state.offset), (6, 5));
+        state.select_next_page();
+        assert_eq!((state.selected, state.offset), (6, 5));
+        state.select_prev_page();
+        assert_eq!((state.selected, state.offset), (4, 3));
+        state.select_prev_page();
+        assert_eq!((state.selected, state.offset), (2, 1));
+        state.select_prev_page();
+        assert_eq!((state.selected, state.offset), (0, 0));
+    }
+
+    #[test]
+    fn test_render_version_tab_has_more_versions() -> std::io::Result<()> {
         let ctx = Rc::default();
         let (tx, _) = event::new();
         let mut terminal = setup_terminal()?;
 
+        let (items, file_detail, file_versions, object_key) = fixtures();
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+        page.set_versions(file_versions[..1].to_vec());
+        page.set_has_more_versions(true);
+        page.select_versions_tab();
+
+        let area = Rect::new(0, 0, 60, 20);
         terminal.draw(|f| {
-            let (items, file_detail, file_versions, object_key) = fixtures();
-            let items_len = items.len();
-            let mut page = ObjectDetailPage::new(
-                file_detail,
-                items,
-                object_key,
-                ScrollListState::new(items_len),
-                ctx,
-                tx,
-            );
-            page.set_versions(file_versions);
-            page.select_versions_tab();
-            let area = Rect::new(0, 0, 60, 20);
             page.render(f, area);
         })?;
 
@@ -927,10 +2021,10 @@ mod tests {
             "│                            ││┃ Last Modified: 2024-01-02 │",
             "│                            ││┃          Size: 1.01 KiB   │",
             "│                            ││────────────────────────────│",
-            "│                            ││     Version ID: 1c5d3bcc-2b│",
-            "│                            ││  Last Modified: 2024-01-01 │",
-            "│                            ││           Size: 1 KiB      │",
-            "│                            ││────────────────────────────│",
+            "│                            ││Press m to load more version│",
+            "│                            ││                            │",
+            "│                            ││                            │",
+            "│                            ││                            │",
             "│                            ││                            │",
             "│                            ││                            │",
             "│                            ││                            │",
@@ -947,22 +2041,50 @@ mod tests {
             // "Version" is selected
             (41..48, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
             // "Version ID" label
-            (33..48, [3, 7]) => modifier: Modifier::BOLD,
+            (33..48, [3]) => modifier: Modifier::BOLD,
             // "Last Modified" label
-            (33..48, [4, 8]) => modifier: Modifier::BOLD,
+            (33..48, [4]) => modifier: Modifier::BOLD,
             // "Size" label
-            (33..48, [5, 9]) => modifier: Modifier::BOLD,
+            (33..48, [5]) => modifier: Modifier::BOLD,
             // selected bar
             ([31], [3, 4, 5]) => fg: Color::Cyan,
             // divider
-            (31..59, [6, 10]) => fg: Color::DarkGray,
+            (31..59, [6]) => fg: Color::DarkGray,
+            // load more hint
+            (31..59, 7..19) => fg: Color::DarkGray,
         }
 
         terminal.backend().assert_buffer(&expected);
 
+        page.append_versions(fixtures().2[1..].to_vec());
+        page.set_has_more_versions(false);
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        assert_eq!(
+            page.current_selected_version_id(),
+            Some("1c5d3bcc-2bb3-4cd5-875f-a95a6ae53f65".to_string())
+        );
+
         Ok(())
     }
 
+    #[test]
+    fn test_version_tab_state_append_versions_oldest_first() {
+        let (_, _, file_versions, _) = fixtures();
+        let mut state = VersionTabState::new(
+            &file_versions[..1],
+            VersionSortType::OldestFirst,
+            true,
+            &UiConfig::default(),
+        );
+        assert_eq!(state.selected_version_index(), 0);
+
+        state.append_versions(&file_versions[1..], &UiConfig::default());
+
+        // the selection stays on the same version
+        assert_eq!((state.selected, state.offset), (1, 1));
+        assert_eq!(state.selected_version_index(), 0);
+    }
+
     #[test]
     fn test_render_version_tab_with_config() -> std::io::Result<()> {
         let (tx, _) = event::new();
@@ -1065,7 +2187,7 @@ mod tests {
             "│                            ││ Size:                      │",
             "│                            ││  1.01 KiB                  │",
             "│         ╭Save As───────────────────────────────╮         │",
-            "│         │                                      │         │",
+            "│         │ file1                                │         │",
             "│         ╰──────────────────────────────────────╯ 2       │",
             "│                            ││                            │",
             "│                            ││ ETag:                      │",
@@ -1123,8 +2245,6 @@ mod tests {
         let mut expected = Buffer::with_lines([
             "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
             "│  file1                     ││ Detail │ Version           │",
-            "│  file2                     ││────────────────────────────│",
-            "│  file3                     ││ Name:                      │",
             "│ ╭Copy──────────────────────────────────────────────────╮ │",
             "│ │ Key:                                                 │ │",
             "│ │   file1                                              │ │",
@@ -1132,13 +2252,15 @@ mod tests {
             "│ │   s3://bucket-1/file1                                │ │",
             "│ │ ARN:                                                 │ │",
             "│ │   arn:aws:s3:::bucket-1/file1                        │ │",
-            "│ │ Object URL:                                          │ │",
+            "│ │ Object URL (virtual-hosted):                         │ │",
             "│ │   https://bucket-1.s3.ap-northeast-1.amazonaws.com/f │ │",
+            "│ │ Object URL (path-style):                             │ │",
+            "│ │   https://s3.ap-northeast-1.amazonaws.com/bucket-1/f │ │",
+            "│ │ Presigned URL:                                       │ │",
+            "│ │   (generated when copied)                            │ │",
             "│ │ ETag:                                                │ │",
             "│ │   bef684de-a260-48a4-8178-8a535ecccadb               │ │",
             "│ ╰──────────────────────────────────────────────────────╯ │",
-            "│                            ││ Content-Type:              │",
-            "│                            ││  text/plain                │",
             "│                            ││                            │",
             "└────────────────────────────┘└────────────────────────────┘",
         ]);
@@ -1147,22 +2269,22 @@ mod tests {
             (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
             // "Detail" is selected
             (32..38, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
-            // "Name" label
-            (32..37, [3]) => modifier: Modifier::BOLD,
-            // "Content-Type" label
-            (32..45, [16]) => modifier: Modifier::BOLD,
             // "Key" label
-            (4..8, [5]) => modifier: Modifier::BOLD,
+            (4..8, [3]) => modifier: Modifier::BOLD,
             // "S3 URI" label
-            (4..11, [7]) => modifier: Modifier::BOLD,
+            (4..11, [5]) => modifier: Modifier::BOLD,
             // "ARN" label
-            (4..8, [9]) => modifier: Modifier::BOLD,
-            // "Object URL" label
-            (4..15, [11]) => modifier: Modifier::BOLD,
+            (4..8, [7]) => modifier: Modifier::BOLD,
+            // "Object URL (virtual-hosted)" label
+            (4..32, [9]) => modifier: Modifier::BOLD,
+            // "Object URL (path-style)" label
+            (4..28, [11]) => modifier: Modifier::BOLD,
+            // "Presigned URL" label
+            (4..18, [13]) => modifier: Modifier::BOLD,
             // "ETag" label
-            (4..9, [13]) => modifier: Modifier::BOLD,
+            (4..9, [15]) => modifier: Modifier::BOLD,
             // "Key" is selected
-            (4..56, [5, 6]) => fg: Color::Cyan,
+            (4..56, [3, 4]) => fg: Color::Cyan,
         }
 
         terminal.backend().assert_buffer(&expected);
@@ -1207,8 +2329,6 @@ mod tests {
         let mut expected = Buffer::with_lines([
             "┌───────────────────── 1 / 3 ┐┌────────────────────────────┐",
             "│  file1                     ││ Detail │ Version           │",
-            "│  file2                     ││────────────────────────────│",
-            "│  file3                     ││     Version ID: 60f36bc2-0f│",
             "│ ╭Copy──────────────────────────────────────────────────╮ │",
             "│ │ Key:                                                 │ │",
             "│ │   file1                                              │ │",
@@ -1216,14 +2336,16 @@ mod tests {
             "│ │   s3://bucket-1/file1?versionId=1c5d3bcc-2bb3-4cd5-8 │ │",
             "│ │ ARN:                                                 │ │",
             "│ │   arn:aws:s3:::bucket-1/file1                        │ │",
-            "│ │ Object URL:                                          │ │",
+            "│ │ Object URL (virtual-hosted):                         │ │",
             "│ │   https://bucket-1.s3.ap-northeast-1.amazonaws.com/f │ │",
+            "│ │ Object URL (path-style):                             │ │",
+            "│ │   https://s3.ap-northeast-1.amazonaws.com/bucket-1/f │ │",
+            "│ │ Presigned URL:                                       │ │",
+            "│ │   (generated when copied)                            │ │",
             "│ │ ETag:                                                │ │",
             "│ │   6c5db847-d206-4a27-9723-713e3a6cad86               │ │",
             "│ ╰──────────────────────────────────────────────────────╯ │",
             "│                            ││                            │",
-            "│                            ││                            │",
-            "│                            ││                            │",
             "└────────────────────────────┘└────────────────────────────┘",
         ]);
         set_cells! { expected =>
@@ -1231,20 +2353,22 @@ mod tests {
             (2..28, [1]) => bg: Color::DarkGray, fg: Color::Black,
             // "Version" is selected
             (41..48, [1]) => fg: Color::Cyan, modifier: Modifier::BOLD,
-            // "Version ID" label
-            (33..48, [3]) => modifier: Modifier::BOLD,
             // "Key" label
-            (4..8, [5]) => modifier: Modifier::BOLD,
+            (4..8, [3]) => modifier: Modifier::BOLD,
             // "S3 URI" label
-            (4..11, [7]) => modifier: Modifier::BOLD,
+            (4..11, [5]) => modifier: Modifier::BOLD,
             // "ARN" label
-            (4..8, [9]) => modifier: Modifier::BOLD,
-            // "Object URL" label
-            (4..15, [11]) => modifier: Modifier::BOLD,
+            (4..8, [7]) => modifier: Modifier::BOLD,
+            // "Object URL (virtual-hosted)" label
+            (4..32, [9]) => modifier: Modifier::BOLD,
+            // "Object URL (path-style)" label
+            (4..28, [11]) => modifier: Modifier::BOLD,
+            // "Presigned URL" label
+            (4..18, [13]) => modifier: Modifier::BOLD,
             // "ETag" label
-            (4..9, [13]) => modifier: Modifier::BOLD,
+            (4..9, [15]) => modifier: Modifier::BOLD,
             // "Key" is selected
-            (4..56, [5, 6]) => fg: Color::Cyan,
+            (4..56, [3, 4]) => fg: Color::Cyan,
         }
 
         terminal.backend().assert_buffer(&expected);
@@ -1252,6 +2376,344 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_copy_presigned_url() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let (items, file_detail, _file_versions, object_key) = fixtures();
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('P')));
+        assert!(matches!(page.view_state, ViewState::PresignDialog(_)));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('P')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('k')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('k')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::PresignCustomDialog(_)));
+        for c in "30m".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let events: Vec<(String, String, Duration)> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::CopyPresignedUrl(bucket, key, _, expires_in) => {
+                    Some((bucket, key, expires_in))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    "test-bucket".into(),
+                    "file1".into(),
+                    Duration::from_secs(24 * 60 * 60)
+                ),
+                (
+                    "test-bucket".into(),
+                    "file1".into(),
+                    Duration::from_secs(30 * 60)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_detail_content_lines_with_lifecycle() {
+        let (_, file_detail, _, _) = fixtures();
+        let events = vec![
+            LifecycleEvent {
+                action: LifecycleAction::Transition("GLACIER".to_string()),
+                at: parse_datetime("2024-02-01 00:00:00"),
+            },
+            LifecycleEvent {
+                action: LifecycleAction::Expire,
+                at: parse_datetime("2024-03-01 00:00:00"),
+            },
+        ];
+
+        let lines = build_detail_content_lines(
+            &file_detail,
+            &[],
+            false,
+            &events,
+            None,
+            false,
+            &UiConfig::default(),
+        );
+        let actual: Vec<String> = lines
+            .iter()
+            .rev()
+            .take(3)
+            .rev()
+            .map(|l| l.to_string())
+            .collect();
+        // the dates are in the past, so the actions are pending
+        let expected = vec![
+            "Lifecycle:",
+            " Transition to GLACIER pending",
+            " Expire pending",
+        ];
+        assert_eq!(actual, expected);
+
+        let lines = build_detail_content_lines(
+            &file_detail,
+            &[],
+            false,
+            &[],
+            None,
+            false,
+            &UiConfig::default(),
+        );
+        assert!(lines.iter().all(|l| l.to_string() != "Lifecycle:"));
+    }
+
+    #[test]
+    fn test_build_detail_content_lines_with_metadata() {
+        let (_, mut file_detail, _, _) = fixtures();
+        file_detail.cache_control = "no-cache".to_string();
+        file_detail.user_metadata = vec![("foo".to_string(), "bar".to_string())];
+
+        let lines = build_detail_content_lines(
+            &file_detail,
+            &[],
+            false,
+            &[],
+            None,
+            false,
+            &UiConfig::default(),
+        );
+        let actual: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert!(actual
+            .windows(2)
+            .any(|w| w == ["Cache-Control:", " no-cache"]));
+        assert_eq!(actual[actual.len() - 2..], ["Metadata:", " foo: bar"]);
+    }
+
+    #[test]
+    fn test_build_detail_content_lines_with_object_lock() {
+        let (_, mut file_detail, _, _) = fixtures();
+        file_detail.object_lock_mode = "GOVERNANCE".to_string();
+        file_detail.object_lock_retain_until = Some(parse_datetime("2025-01-02 13:01:02"));
+        file_detail.legal_hold = Some(true);
+
+        let lines = build_detail_content_lines(
+            &file_detail,
+            &[],
+            false,
+            &[],
+            None,
+            false,
+            &UiConfig::default(),
+        );
+        let actual: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            actual[actual.len() - 4..],
+            [
+                "Object Lock:",
+                " Mode: GOVERNANCE",
+                " Retain until: 2025-01-02 13:01:02",
+                " Legal hold: ON",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_detail_content_lines_with_multipart_e_tag() {
+        let (_, mut file_detail, _, _) = fixtures();
+        file_detail.e_tag = "0cc175b9c0f1b6a831c399e269772661-24".to_string();
+
+        let md5 = Some("92eb5ffee6ae2fec3ad71c777531578f");
+        let lines = build_detail_content_lines(
+            &file_detail,
+            &[],
+            false,
+            &[],
+            md5,
+            false,
+            &UiConfig::default(),
+        );
+        let actual: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert!(actual.windows(6).any(|w| w
+            == [
+                "ETag:",
+                " 0cc175b9c0f1b6a831c399e269772661-24",
+                " Multipart upload (24 parts), not an MD5 of the content",
+                "",
+                "Content MD5:",
+                " 92eb5ffee6ae2fec3ad71c777531578f",
+            ]));
+    }
+
+    #[test]
+    fn test_toggle_legal_hold() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let (items, mut file_detail, _file_versions, object_key) = fixtures();
+        file_detail.legal_hold = Some(true);
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('L')));
+        assert!(matches!(page.view_state, ViewState::LegalHoldConfirmDialog));
+        page.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('L')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let events: Vec<bool> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::StartSetObjectLegalHold(_, on) => Some(on),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(events, vec![false]);
+    }
+
+    #[test]
+    fn test_remove_delete_marker() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let (items, file_detail, mut file_versions, object_key) = fixtures();
+        file_versions[0].size_byte = 0;
+        file_versions[0].e_tag = "".to_string();
+        file_versions[0].is_delete_marker = true;
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+        page.set_versions(file_versions);
+        page.select_versions_tab();
+
+        let lines = build_version_detail_lines(
+            &page.file_versions,
+            VersionSortType::NewestFirst,
+            &UiConfig::default(),
+        );
+        assert_eq!(lines[0][2].to_string(), "         Type: Delete marker");
+        assert_eq!(lines[1][2].to_string(), "         Size: 1 KiB");
+
+        // a delete marker has no content
+        page.handle_key(KeyEvent::from(KeyCode::Char('p')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('D')));
+        assert!(matches!(
+            page.view_state,
+            ViewState::DeleteMarkerConfirmDialog
+        ));
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        // the older version is not a delete marker
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('D')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let events: Vec<String> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::StartRemoveDeleteMarker(_, v) => Some(v.version_id),
+                AppEventType::NotifyWarn(msg) => Some(msg),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                "The selected version is a delete marker and has no content".to_string(),
+                "60f36bc2-0f38-47b8-9bf0-e24e334b86d5".to_string(),
+                "The selected version is not a delete marker".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edit_metadata() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let (items, file_detail, _file_versions, object_key) = fixtures();
+        let items_len = items.len();
+        let mut page = ObjectDetailPage::new(
+            file_detail,
+            items,
+            object_key,
+            ScrollListState::new(items_len),
+            ctx,
+            tx,
+        );
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('e')));
+        assert!(matches!(page.view_state, ViewState::MetadataDialog(_)));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(
+            page.view_state,
+            ViewState::MetadataValueDialog(..)
+        ));
+        for c in "no-cache".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::MetadataDialog(_)));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('a')));
+        assert!(matches!(page.view_state, ViewState::MetadataAddDialog(..)));
+        for c in "Foo=bar".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        page.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let events: Vec<ObjectMetadata> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::StartUpdateObjectMetadata(_, metadata) => Some(metadata),
+                _ => None,
+            })
+            .collect();
+        let expected = ObjectMetadata {
+            content_type: "text/plain".into(),
+            cache_control: "no-cache".into(),
+            content_disposition: "".into(),
+            user_metadata: vec![("foo".into(), "bar".into())],
+        };
+        assert_eq!(events, vec![expected]);
+
+        // nothing is sent if the metadata is not changed
+        page.handle_key(KeyEvent::from(KeyCode::Char('e')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert!(std::iter::from_fn(|| rx.try_recv())
+            .all(|e| !matches!(e, AppEventType::StartUpdateObjectMetadata(..))));
+    }
+
     fn setup_terminal() -> std::io::Result<Terminal<TestBackend>> {
         let backend = TestBackend::new(60, 20);
         let mut terminal = Terminal::new(backend)?;
@@ -1279,10 +2741,18 @@ mod tests {
             e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
             content_type: "text/plain".to_string(),
             storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
             key: "file1".to_string(),
             s3_uri: "s3://bucket-1/file1".to_string(),
             arn: "arn:aws:s3:::bucket-1/file1".to_string(),
-            object_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file1".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file1"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/file1".to_string(),
         };
         let file_versions = vec![
             FileVersion {
@@ -1291,6 +2761,7 @@ mod tests {
                 last_modified: parse_datetime("2024-01-02 13:01:02"),
                 e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
                 is_latest: true,
+                is_delete_marker: false,
             },
             FileVersion {
                 version_id: "1c5d3bcc-2bb3-4cd5-875f-a95a6ae53f65".to_string(),
@@ -1298,6 +2769,7 @@ mod tests {
                 last_modified: parse_datetime("2024-01-01 23:59:59"),
                 e_tag: "6c5db847-d206-4a27-9723-713e3a6cad86".to_string(),
                 is_latest: false,
+                is_delete_marker: false,
             },
         ];
         let object_key = ObjectKey {
@@ -1315,7 +2787,8 @@ mod tests {
             key: "".to_string(),
             s3_uri: "".to_string(),
             arn: "".to_string(),
-            object_url: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
             e_tag: "".to_string(),
         }
     }
@@ -1,12 +1,19 @@
-use std::rc::Rc;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    rc::Rc,
+    time::Duration,
+};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
+use itsuki::zero_indexed_enum;
 use laurier::{highlight::highlight_matched_text, key_code, key_code_char};
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent},
     layout::Rect,
     style::{Style, Stylize},
-    text::Line,
+    text::{Line, Span},
     widgets::ListItem,
     Frame,
 };
@@ -14,15 +21,28 @@ use ratatui::{
 use crate::{
     app::AppContext,
     color::ColorTheme,
+    command::{parse_command, Command},
     config::UiConfig,
+    download_list::DownloadObjectList,
+    error::AppError,
+    etag_index::ObjectChange,
     event::{AppEventType, Sender},
-    format::{format_datetime, format_size_byte},
-    object::{ObjectItem, ObjectKey},
-    pages::util::{build_helps, build_short_helps},
+    file::{detect_content_type, unique_file_path},
+    format::{decode_key_name, format_count, format_datetime, format_size_byte},
+    location::parse_s3_location,
+    multipart::{plan_parts, reusable_parts, MultipartUpload, MultipartUploadTarget},
+    object::{
+        insert_dir_item, DownloadFailure, DownloadObjectsOptions, DownloadOutputType, ObjectItem,
+        ObjectKey, StorageClassType, COPY_OBJECT_MAX_SIZE_BYTE,
+    },
+    pages::util::{build_delete_cost_line, build_helps, build_short_helps},
+    tagging::parse_tags,
     widget::{
-        CopyDetailDialog, CopyDetailDialogState, InputDialog, InputDialogState,
-        ObjectListSortDialog, ObjectListSortDialogState, ObjectListSortType, ScrollList,
-        ScrollListState,
+        parse_presign_expires_in, BatchRenameDialog, BatchRenameDialogState, ConfirmDialog,
+        CopyDetailDialog, CopyDetailDialogState, DownloadConfirmDialog, DownloadConfirmDialogState,
+        InputDialog, InputDialogState, ObjectListSortDialog, ObjectListSortDialogState,
+        ObjectListSortType, PresignDialog, PresignDialogState, ScrollList, ScrollListState,
+        DEFAULT_PRESIGN_EXPIRES_IN,
     },
 };
 
@@ -31,12 +51,33 @@ pub struct ObjectListPage {
     object_items: Vec<ObjectItem>,
     object_key: ObjectKey,
     view_indices: Vec<usize>,
+    // rows actually shown in the list, view_indices with group headers when grouped
+    view_rows: Vec<ViewRow>,
+    // indices of object_items, only files can be marked
+    marked: HashSet<usize>,
+    // indices of object_items changed since the last session
+    changes: HashMap<usize, ObjectChange>,
+
+    group_type: ObjectListGroupType,
+    groups: Vec<ObjectListGroup>,
+    // labels of the collapsed groups
+    collapsed_groups: HashSet<String>,
 
     view_state: ViewState,
 
     list_state: ScrollListState,
     filter_input_state: InputDialogState,
+    // the filter which view_indices reflects, it can be behind the input while typing on a large list
+    applied_filter: String,
+    // incremented on each input so that only the last debounced filter is applied
+    filter_generation: usize,
     sort_dialog_state: ObjectListSortDialogState,
+    // only for display, the raw keys are used for requests and copy actions
+    decode_key_names: bool,
+    // whether the objects matching `filters.hidden` are shown
+    show_hidden: bool,
+    // the following pages of the list are still being loaded
+    loading_more: bool,
 
     ctx: Rc<AppContext>,
     tx: Sender,
@@ -48,6 +89,68 @@ enum ViewState {
     FilterDialog,
     SortDialog,
     CopyDetailDialog(Box<CopyDetailDialogState>),
+    DownloadConfirmDialog(Box<DownloadConfirmDialogState>),
+    DownloadFilterDialog(Box<DownloadConfirmDialogState>, InputDialogState),
+    CommandDialog(InputDialogState),
+    UploadDialog(InputDialogState),
+    UploadContentTypeDialog(PathBuf, InputDialogState),
+    NewFolderDialog(InputDialogState),
+    CompareDialog(InputDialogState),
+    CopyToDialog(ObjectKey, InputDialogState),
+    TagDialog(InputDialogState),
+    BatchRenameDialog(Box<BatchRenameDialogState>),
+    DeleteConfirmDialog(Vec<usize>),
+    StorageClassDialog(Vec<usize>, StorageClassType),
+    DownloadRetryDialog(Box<DownloadRetryState>),
+    ResumeUploadDialog(Box<(MultipartUploadTarget, MultipartUpload)>),
+    PresignPutKeyDialog(InputDialogState),
+    PresignPutDialog(String, PresignDialogState),
+    PresignPutCustomDialog(String, InputDialogState),
+}
+
+#[derive(Debug)]
+struct DownloadRetryState {
+    bucket: String,
+    key: String,
+    failed: Vec<DownloadFailure>,
+    options: DownloadObjectsOptions,
+    path: PathBuf,
+}
+
+#[derive(Default)]
+#[zero_indexed_enum]
+enum ObjectListGroupType {
+    #[default]
+    None,
+    Extension,
+    LastModified,
+    Prefix,
+}
+
+impl ObjectListGroupType {
+    fn str(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Extension => "Extension",
+            Self::LastModified => "Last Modified",
+            Self::Prefix => "Prefix",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewRow {
+    // index of groups
+    Group(usize),
+    // index of object_items
+    Item(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ObjectListGroup {
+    label: String,
+    len: usize,
+    collapsed: bool,
 }
 
 impl ObjectListPage {
@@ -58,33 +161,56 @@ impl ObjectListPage {
         tx: Sender,
     ) -> Self {
         let items_len = object_items.len();
-        let view_indices = (0..items_len).collect();
-        Self {
+        let view_indices: Vec<usize> = (0..items_len).collect();
+        let view_rows = view_indices.iter().map(|&i| ViewRow::Item(i)).collect();
+        let mut page = Self {
             object_items,
             object_key,
             view_indices,
+            view_rows,
+            marked: HashSet::new(),
+            changes: HashMap::new(),
+            group_type: ObjectListGroupType::default(),
+            groups: Vec::new(),
+            collapsed_groups: HashSet::new(),
             view_state: ViewState::Default,
             list_state: ScrollListState::new(items_len),
             filter_input_state: InputDialogState::default(),
+            applied_filter: String::new(),
+            filter_generation: 0,
             sort_dialog_state: ObjectListSortDialogState::default(),
+            decode_key_names: ctx.config.ui.object_list.decode_key_names,
+            show_hidden: false,
+            loading_more: false,
             ctx,
             tx,
+        };
+        if !page.ctx.config.filters.hidden.is_empty() {
+            page.filter_view_indices();
         }
+        page
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) {
         match self.view_state {
             ViewState::Default => match key {
                 key_code!(KeyCode::Esc) => {
-                    if self.filter_input_state.input().is_empty() {
+                    if !self.marked.is_empty() {
+                        self.marked.clear();
+                    } else if self.filter_input_state.input().is_empty() {
                         self.tx.send(AppEventType::Quit);
                     } else {
                         self.reset_filter();
                     }
                 }
-                key_code!(KeyCode::Enter) if self.non_empty() => {
-                    self.tx.send(AppEventType::ObjectListMoveDown);
-                }
+                key_code!(KeyCode::Enter) if self.non_empty() => match self.selected_row() {
+                    ViewRow::Group(group) => {
+                        self.toggle_group_collapsed(group);
+                    }
+                    ViewRow::Item(_) => {
+                        self.tx.send(AppEventType::ObjectListMoveDown);
+                    }
+                },
                 key_code!(KeyCode::Backspace) => {
                     self.tx.send(AppEventType::ObjectListMoveUp);
                 }
@@ -115,15 +241,79 @@ impl ObjectListPage {
                 key_code_char!('x') if self.non_empty() => {
                     self.tx.send(AppEventType::ObjectListOpenManagementConsole);
                 }
+                key_code_char!('p') => {
+                    self.tx.send(AppEventType::PasteLocation);
+                }
                 key_code_char!('/') => {
                     self.open_filter_dialog();
                 }
                 key_code_char!('o') => {
                     self.open_sort_dialog();
                 }
-                key_code_char!('r') => {
+                key_code_char!('r') if self.is_item_selected() => {
                     self.open_copy_detail_dialog();
                 }
+                key_code_char!('s') if self.is_item_selected() => {
+                    self.download();
+                }
+                key_code_char!(':') => {
+                    self.open_command_dialog();
+                }
+                key_code_char!('u') => {
+                    self.open_upload_dialog();
+                }
+                key_code_char!('U') => {
+                    self.upload_clipboard_image();
+                }
+                key_code_char!('W') => {
+                    self.start_watch_dir();
+                }
+                key_code_char!('N') => {
+                    self.open_new_folder_dialog();
+                }
+                key_code_char!('P') => {
+                    self.open_presign_put_key_dialog();
+                }
+                key_code_char!('c') => {
+                    self.open_compare_dialog();
+                }
+                key_code_char!('y') if self.is_file_selected() => {
+                    self.open_copy_to_dialog();
+                }
+                key_code_char!(' ') if self.non_empty() => {
+                    self.toggle_mark();
+                }
+                key_code_char!('n') if self.is_item_selected() || !self.marked.is_empty() => {
+                    self.open_batch_rename_dialog();
+                }
+                key_code_char!('d') if self.is_item_selected() || !self.marked.is_empty() => {
+                    self.open_delete_confirm_dialog();
+                }
+                key_code_char!('t') if self.is_item_selected() || !self.marked.is_empty() => {
+                    self.open_tag_dialog();
+                }
+                key_code_char!('S') if self.is_item_selected() || !self.marked.is_empty() => {
+                    self.open_storage_class_dialog();
+                }
+                key_code_char!('v') => {
+                    self.toggle_group_type();
+                }
+                key_code_char!('%') => {
+                    self.tx.send(AppEventType::ToggleDecodeKeyNames);
+                }
+                key_code_char!('.') => {
+                    self.tx.send(AppEventType::ToggleShowHidden);
+                }
+                key_code_char!('D') => {
+                    let object_key = self.object_key.clone();
+                    self.tx
+                        .send(AppEventType::StartFindDuplicateObjects(object_key));
+                }
+                key_code_char!('C') => {
+                    let object_key = self.object_key.clone();
+                    self.tx
+                        .send(AppEventType::StartFindCleanupObjects(object_key));
+                }
                 key_code_char!('?') => {
                     self.tx.send(AppEventType::OpenHelp);
                 }
@@ -141,7 +331,7 @@ impl ObjectListPage {
                 }
                 _ => {
                     self.filter_input_state.handle_key_event(key);
-                    self.filter_view_indices();
+                    self.update_filter_on_input();
                 }
             },
             ViewState::SortDialog => match key {
@@ -167,9 +357,204 @@ impl ObjectListPage {
                     self.close_copy_detail_dialog();
                 }
                 key_code!(KeyCode::Enter) => {
-                    let (name, value) = state.selected_name_and_value();
-                    self.tx.send(AppEventType::CopyToClipboard(name, value));
+                    if let Some((key, version_id)) = state.selected_presigned_url_target() {
+                        let bucket = self.object_key.bucket_name.clone();
+                        let expires_in = DEFAULT_PRESIGN_EXPIRES_IN;
+                        self.tx.send(AppEventType::CopyPresignedUrl(
+                            bucket, key, version_id, expires_in,
+                        ));
+                    } else {
+                        let (name, value) = state.selected_name_and_value();
+                        self.tx.send(AppEventType::CopyToClipboard(name, value));
+                    }
+                }
+                key_code_char!('j') => {
+                    state.select_next();
+                }
+                key_code_char!('k') => {
+                    state.select_prev();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::DownloadConfirmDialog(ref mut state) if state.is_editing_destination() => {
+                match key {
+                    key_code!(KeyCode::Esc) => {
+                        state.cancel_edit_destination();
+                    }
+                    key_code!(KeyCode::Enter) => {
+                        if let Err(e) = state.apply_destination_input() {
+                            self.tx.send(AppEventType::NotifyError(e));
+                        }
+                    }
+                    _ => {
+                        state.handle_destination_key_event(key);
+                    }
+                }
+            }
+            ViewState::DownloadConfirmDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) | key_code!(KeyCode::Backspace) => {
+                    self.close_download_confirm_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let bucket = self.object_key.bucket_name.clone();
+                    let key = state.key().to_string();
+                    let options = state.options();
+                    let path = state.destination_path();
+                    match state.download_objects() {
+                        Ok(objs) if !objs.is_empty() => {
+                            self.tx.send(AppEventType::StartDownloadObjects(
+                                bucket, key, objs, options, path,
+                            ));
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            self.tx.send(AppEventType::NotifyError(e));
+                        }
+                    }
+                }
+                key_code_char!('e') => {
+                    state.start_edit_destination();
+                }
+                key_code_char!('j') => {
+                    state.select_next();
+                }
+                key_code_char!('k') => {
+                    state.select_prev();
+                }
+                key_code_char!('g') => {
+                    state.select_first();
+                }
+                key_code_char!('G') => {
+                    state.select_last();
+                }
+                key_code_char!('h') => {
+                    state.collapse_selected();
+                }
+                key_code_char!('l') => {
+                    state.expand_selected();
+                }
+                key_code_char!(' ') => {
+                    state.toggle_selected();
+                }
+                key_code_char!('/') => {
+                    self.open_download_filter_dialog();
+                }
+                key_code_char!('o') => {
+                    state.toggle_skip_type();
+                }
+                key_code_char!('f') => {
+                    state.toggle_layout_type();
+                }
+                key_code_char!('a') => {
+                    state.toggle_output_type();
+                }
+                key_code_char!('+') => {
+                    state.increase_max_concurrent_requests();
+                }
+                key_code_char!('-') => {
+                    state.decrease_max_concurrent_requests();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            ViewState::DownloadFilterDialog(ref mut state, ref mut input_state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_download_filter_dialog();
+                }
+                key_code!(KeyCode::Enter) => match state.apply_filter(input_state.input()) {
+                    Ok(_) => {
+                        self.close_download_filter_dialog();
+                    }
+                    Err(e) => {
+                        self.tx.send(AppEventType::NotifyError(e));
+                    }
+                },
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {
+                    input_state.handle_key_event(key);
+                }
+            },
+            ViewState::CommandDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_command_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().to_string();
+                    self.execute_command(&input);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::UploadDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_upload_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().trim();
+                    if !input.is_empty() {
+                        let path = PathBuf::from(input);
+                        self.open_upload_content_type_dialog(path);
+                    }
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::UploadContentTypeDialog(ref path, ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_upload_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let bucket = self.object_key.bucket_name.clone();
+                    let prefix = self.object_key.joined_object_path(false);
+                    let input = state.input().trim();
+                    // the type is detected from the bytes if it is cleared
+                    let content_type = (!input.is_empty()).then(|| input.to_string());
+                    self.tx.send(AppEventType::UploadObject(
+                        bucket,
+                        prefix,
+                        path.clone(),
+                        content_type,
+                    ));
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::PresignPutKeyDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_presign_put_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let key = state.input().trim().to_string();
+                    self.open_presign_put_dialog(key);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::PresignPutDialog(ref object_key, ref mut state) => match key {
+                key_code!(KeyCode::Esc) | key_code!(KeyCode::Backspace) => {
+                    self.close_presign_put_dialog();
                 }
+                key_code!(KeyCode::Enter) => match state.selected().duration() {
+                    Some(expires_in) => {
+                        self.copy_presigned_put_url(object_key.clone(), expires_in);
+                    }
+                    None => {
+                        let object_key = object_key.clone();
+                        let state = InputDialogState::default();
+                        self.view_state = ViewState::PresignPutCustomDialog(object_key, state);
+                    }
+                },
                 key_code_char!('j') => {
                     state.select_next();
                 }
@@ -181,6 +566,138 @@ impl ObjectListPage {
                 }
                 _ => {}
             },
+            ViewState::PresignPutCustomDialog(ref object_key, ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_presign_put_dialog();
+                }
+                key_code!(KeyCode::Enter) => match parse_presign_expires_in(state.input()) {
+                    Some(expires_in) => {
+                        self.copy_presigned_put_url(object_key.clone(), expires_in);
+                    }
+                    None => {
+                        let msg = "Expiry must be like 90s, 30m, 12h or 7d (up to 7 days)";
+                        self.tx.send(AppEventType::NotifyError(AppError::msg(msg)));
+                    }
+                },
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::NewFolderDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_new_folder_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().trim().trim_end_matches('/').to_string();
+                    self.create_folder(input);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::CompareDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_compare_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().to_string();
+                    self.compare(&input);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::CopyToDialog(_, ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_copy_to_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().to_string();
+                    self.copy_to(&input);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::TagDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_tag_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().to_string();
+                    self.apply_tags(&input);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::BatchRenameDialog(ref mut state) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_batch_rename_dialog();
+                }
+                key_code!(KeyCode::Tab) => {
+                    state.toggle_input();
+                }
+                key_code!(KeyCode::Enter) => {
+                    if let Some(targets) = state.targets() {
+                        let bucket = self.object_key.bucket_name.clone();
+                        let prefix = self.object_key.joined_object_path(false);
+                        self.tx.send(AppEventType::StartRenameObjects(
+                            bucket,
+                            prefix,
+                            targets.to_vec(),
+                        ));
+                    }
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            ViewState::DeleteConfirmDialog(_) => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_delete_confirm_dialog();
+                }
+                key_code_char!('y') => {
+                    self.delete();
+                }
+                _ => {}
+            },
+            ViewState::StorageClassDialog(_, ref mut storage_class) => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_storage_class_dialog();
+                }
+                key_code_char!('j') => {
+                    *storage_class = storage_class.next();
+                }
+                key_code_char!('k') => {
+                    *storage_class = storage_class.prev();
+                }
+                key_code_char!('y') => {
+                    self.change_storage_class();
+                }
+                _ => {}
+            },
+            ViewState::DownloadRetryDialog(_) => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_download_retry_dialog();
+                }
+                key_code_char!('y') => {
+                    self.retry_download();
+                }
+                _ => {}
+            },
+            ViewState::ResumeUploadDialog(_) => match key {
+                key_code!(KeyCode::Esc) | key_code_char!('n') => {
+                    self.close_resume_upload_dialog();
+                }
+                key_code_char!('y') => {
+                    self.start_multipart_upload(true);
+                }
+                key_code_char!('N') => {
+                    self.start_multipart_upload(false);
+                }
+                _ => {}
+            },
         }
     }
 
@@ -190,8 +707,12 @@ impl ObjectListPage {
 
         let list_items = build_list_items(
             &self.object_items,
-            &self.view_indices,
-            self.filter_input_state.input(),
+            &self.view_rows,
+            &self.groups,
+            &self.marked,
+            &self.changes,
+            &self.applied_filter,
+            self.decode_key_names,
             offset,
             selected,
             area,
@@ -199,7 +720,10 @@ impl ObjectListPage {
             &self.ctx.theme,
         );
 
-        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        let footer = self.loading_more.then_some(" Loading more... ");
+        let list = ScrollList::new(list_items)
+            .footer(footer)
+            .theme(&self.ctx.theme);
         f.render_stateful_widget(list, area, &mut self.list_state);
 
         if let ViewState::FilterDialog = self.view_state {
@@ -223,66 +747,383 @@ impl ObjectListPage {
             let copy_detail_dialog = CopyDetailDialog::default().theme(&self.ctx.theme);
             f.render_stateful_widget(copy_detail_dialog, area, state);
         }
-    }
 
-    pub fn helps(&self) -> Vec<String> {
-        let helps: &[(&[&str], &str)] = match self.view_state {
-            ViewState::Default => {
-                if self.filter_input_state.input().is_empty() {
-                    &[
-                        (&["Esc", "Ctrl-c"], "Quit app"),
-                        (&["j/k"], "Select item"),
-                        (&["g/G"], "Go to top/bottom"),
-                        (&["f"], "Scroll page forward"),
-                        (&["b"], "Scroll page backward"),
-                        (&["Enter"], "Open file or folder"),
-                        (&["Backspace"], "Go back to prev folder"),
-                        (&["~"], "Go back to bucket list"),
-                        (&["/"], "Filter object list"),
-                        (&["o"], "Sort object list"),
-                        (&["r"], "Open copy dialog"),
-                        (&["R"], "Refresh object list"),
-                        (&["x"], "Open management console in browser"),
-                    ]
-                } else {
-                    &[
-                        (&["Ctrl-c"], "Quit app"),
-                        (&["Esc"], "Clear filter"),
-                        (&["j/k"], "Select item"),
-                        (&["g/G"], "Go to top/bottom"),
-                        (&["f"], "Scroll page forward"),
-                        (&["b"], "Scroll page backward"),
-                        (&["Enter"], "Open file or folder"),
-                        (&["Backspace"], "Go back to prev folder"),
-                        (&["~"], "Go back to bucket list"),
-                        (&["/"], "Filter object list"),
-                        (&["o"], "Sort object list"),
-                        (&["r"], "Open copy dialog"),
-                        (&["R"], "Refresh object list"),
-                        (&["x"], "Open management console in browser"),
-                    ]
-                }
-            }
-            ViewState::FilterDialog => &[
-                (&["Ctrl-c"], "Quit app"),
-                (&["Esc"], "Close filter dialog"),
-                (&["Enter"], "Apply filter"),
-            ],
-            ViewState::SortDialog => &[
-                (&["Ctrl-c"], "Quit app"),
-                (&["Esc"], "Close sort dialog"),
-                (&["j/k"], "Select item"),
-                (&["Enter"], "Apply sort"),
-            ],
-            ViewState::CopyDetailDialog(_) => &[
-                (&["Ctrl-c"], "Quit app"),
-                (&["Esc", "Backspace"], "Close copy dialog"),
-                (&["j/k"], "Select item"),
-                (&["Enter"], "Copy selected value to clipboard"),
-            ],
-        };
-        build_helps(helps)
-    }
+        if let ViewState::DownloadConfirmDialog(state) = &mut self.view_state {
+            let download_confirm_dialog = DownloadConfirmDialog::default()
+                .cost_config(&self.ctx.config.cost)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(download_confirm_dialog, area, state);
+
+            if state.is_editing_destination() {
+                let (cursor_x, cursor_y) = state.cursor();
+                f.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+
+        if let ViewState::DownloadFilterDialog(state, input_state) = &mut self.view_state {
+            let download_confirm_dialog = DownloadConfirmDialog::default()
+                .cost_config(&self.ctx.config.cost)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(download_confirm_dialog, area, state);
+
+            let filter_dialog = InputDialog::default()
+                .title("Filter (--include/--exclude)")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(filter_dialog, area, input_state);
+
+            let (cursor_x, cursor_y) = input_state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::CommandDialog(state) = &mut self.view_state {
+            let command_dialog = InputDialog::default()
+                .title("Command")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(command_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::UploadDialog(state) = &mut self.view_state {
+            let upload_dialog = InputDialog::default()
+                .title("Upload file")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(upload_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::UploadContentTypeDialog(_, state) = &mut self.view_state {
+            let content_type_dialog = InputDialog::default()
+                .title("Content-Type")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(content_type_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::NewFolderDialog(state) = &mut self.view_state {
+            let new_folder_dialog = InputDialog::default()
+                .title("New folder")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(new_folder_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::CompareDialog(state) = &mut self.view_state {
+            let compare_dialog = InputDialog::default()
+                .title("Compare with (s3://bucket/prefix)")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(compare_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::CopyToDialog(_, state) = &mut self.view_state {
+            let copy_to_dialog = InputDialog::default()
+                .title("Copy to (s3://bucket/key)")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(copy_to_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::PresignPutKeyDialog(state) = &mut self.view_state {
+            let key_dialog = InputDialog::default()
+                .title("Presigned PUT URL key")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(key_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::PresignPutDialog(_, state) = &self.view_state {
+            let presign_dialog = PresignDialog::new(*state).theme(&self.ctx.theme);
+            f.render_widget(presign_dialog, area);
+        }
+
+        if let ViewState::PresignPutCustomDialog(_, state) = &mut self.view_state {
+            let custom_dialog = InputDialog::default()
+                .title("Expiry (e.g. 30m, 12h, 7d)")
+                .max_width(40)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(custom_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::TagDialog(state) = &mut self.view_state {
+            let tag_dialog = InputDialog::default()
+                .title("Apply tags (key=value, ...)")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(tag_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::BatchRenameDialog(state) = &mut self.view_state {
+            let batch_rename_dialog = BatchRenameDialog::default().theme(&self.ctx.theme);
+            f.render_stateful_widget(batch_rename_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::DeleteConfirmDialog(indices) = &self.view_state {
+            let total_size: usize = indices
+                .iter()
+                .filter_map(|i| self.object_items[*i].size_byte())
+                .sum();
+            let mut lines = vec![
+                format!("Delete {} objects?", format_count(indices.len())),
+                format!("  Total size: {}", format_size_byte(total_size)),
+            ];
+            lines.extend(build_delete_cost_line(&self.ctx.config.cost, indices.len()));
+            let dialog = ConfirmDialog::new("Delete", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+
+        if let ViewState::StorageClassDialog(indices, storage_class) = &self.view_state {
+            let sizes: Vec<usize> = indices
+                .iter()
+                .filter_map(|i| self.object_items[*i].size_byte())
+                .collect();
+            let lines = build_storage_class_dialog_lines(&sizes, storage_class);
+            let dialog = ConfirmDialog::new("Change storage class", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+
+        if let ViewState::DownloadRetryDialog(state) = &self.view_state {
+            let lines = build_download_retry_dialog_lines(&state.failed);
+            let dialog = ConfirmDialog::new("Download failed", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+
+        if let ViewState::ResumeUploadDialog(state) = &self.view_state {
+            let (target, upload) = state.as_ref();
+            let part_size_byte = self.ctx.config.upload.part_size_byte();
+            let lines = build_resume_upload_dialog_lines(target, upload, part_size_byte);
+            let dialog = ConfirmDialog::new("Resume upload", lines).theme(&self.ctx.theme);
+            f.render_widget(dialog, area);
+        }
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = match self.view_state {
+            ViewState::Default => {
+                if self.filter_input_state.input().is_empty() {
+                    &[
+                        (&["Esc", "Ctrl-c"], "Quit app"),
+                        (&["j/k"], "Select item"),
+                        (&["g/G"], "Go to top/bottom"),
+                        (&["f"], "Scroll page forward"),
+                        (&["b"], "Scroll page backward"),
+                        (&["Enter"], "Open file or folder"),
+                        (&["Backspace"], "Go back to prev folder"),
+                        (&["~"], "Go back to bucket list"),
+                        (&["/"], "Filter object list"),
+                        (&["o"], "Sort object list"),
+                        (&["r"], "Open copy dialog"),
+                        (&["s"], "Download folder"),
+                        (&["R"], "Refresh object list"),
+                        (&["p"], "Open location in clipboard"),
+                        (&["x"], "Open management console in browser"),
+                        (&[":"], "Enter command"),
+                        (&["u"], "Upload file"),
+                        (&["U"], "Upload image in clipboard"),
+                        (&["W"], "Watch local directory and upload changes"),
+                        (&["N"], "Create new folder"),
+                        (&["P"], "Copy presigned PUT URL"),
+                        (&["c"], "Compare with another prefix"),
+                        (&["Space"], "Toggle mark"),
+                        (&["n"], "Rename marked objects"),
+                        (&["d"], "Delete marked objects"),
+                        (&["y"], "Copy object to another key"),
+                        (&["t"], "Apply tags to marked objects"),
+                        (&["S"], "Change storage class of marked objects"),
+                        (&["D"], "Find duplicate objects"),
+                        (&["C"], "Find empty folders and zero-byte objects"),
+                        (&["v"], "Switch grouping"),
+                        (&["%"], "Toggle decoding of key names"),
+                        (&["."], "Toggle hidden objects"),
+                    ]
+                } else {
+                    &[
+                        (&["Ctrl-c"], "Quit app"),
+                        (&["Esc"], "Clear filter"),
+                        (&["j/k"], "Select item"),
+                        (&["g/G"], "Go to top/bottom"),
+                        (&["f"], "Scroll page forward"),
+                        (&["b"], "Scroll page backward"),
+                        (&["Enter"], "Open file or folder"),
+                        (&["Backspace"], "Go back to prev folder"),
+                        (&["~"], "Go back to bucket list"),
+                        (&["/"], "Filter object list"),
+                        (&["o"], "Sort object list"),
+                        (&["r"], "Open copy dialog"),
+                        (&["s"], "Download folder"),
+                        (&["R"], "Refresh object list"),
+                        (&["p"], "Open location in clipboard"),
+                        (&["x"], "Open management console in browser"),
+                        (&[":"], "Enter command"),
+                        (&["u"], "Upload file"),
+                        (&["U"], "Upload image in clipboard"),
+                        (&["W"], "Watch local directory and upload changes"),
+                        (&["N"], "Create new folder"),
+                        (&["P"], "Copy presigned PUT URL"),
+                        (&["c"], "Compare with another prefix"),
+                        (&["Space"], "Toggle mark"),
+                        (&["n"], "Rename marked objects"),
+                        (&["d"], "Delete marked objects"),
+                        (&["y"], "Copy object to another key"),
+                        (&["t"], "Apply tags to marked objects"),
+                        (&["S"], "Change storage class of marked objects"),
+                        (&["D"], "Find duplicate objects"),
+                        (&["C"], "Find empty folders and zero-byte objects"),
+                        (&["v"], "Switch grouping"),
+                        (&["%"], "Toggle decoding of key names"),
+                        (&["."], "Toggle hidden objects"),
+                    ]
+                }
+            }
+            ViewState::FilterDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close filter dialog"),
+                (&["Enter"], "Apply filter"),
+            ],
+            ViewState::SortDialog => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close sort dialog"),
+                (&["j/k"], "Select item"),
+                (&["Enter"], "Apply sort"),
+            ],
+            ViewState::CopyDetailDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace"], "Close copy dialog"),
+                (&["j/k"], "Select item"),
+                (&["Enter"], "Copy selected value to clipboard"),
+            ],
+            ViewState::DownloadConfirmDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace"], "Cancel download"),
+                (&["j/k"], "Select item"),
+                (&["g/G"], "Go to top/bottom"),
+                (&["h/l"], "Collapse/Expand folder"),
+                (&["Space"], "Toggle include/exclude"),
+                (&["/"], "Filter objects by patterns"),
+                (&["o"], "Switch handling of existing files"),
+                (&["f"], "Switch flatten/preserve structure"),
+                (&["a"], "Switch files/archive output"),
+                (&["e"], "Edit download destination"),
+                (&["+/-"], "Change concurrent requests"),
+                (&["Enter"], "Download included objects"),
+            ],
+            ViewState::DownloadFilterDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close filter dialog"),
+                (&["Enter"], "Apply filter"),
+            ],
+            ViewState::CommandDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close command dialog"),
+                (&["Enter"], "Execute command"),
+            ],
+            ViewState::UploadDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close upload dialog"),
+                (&["Enter"], "Select Content-Type"),
+            ],
+            ViewState::UploadContentTypeDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close upload dialog"),
+                (&["Enter"], "Upload file with the Content-Type"),
+            ],
+            ViewState::NewFolderDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close new folder dialog"),
+                (&["Enter"], "Create folder"),
+            ],
+            ViewState::PresignPutKeyDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close presign dialog"),
+                (&["Enter"], "Select expiry"),
+            ],
+            ViewState::PresignPutDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace"], "Close presign dialog"),
+                (&["j/k"], "Select expiry"),
+                (&["Enter"], "Copy presigned PUT URL to clipboard"),
+            ],
+            ViewState::PresignPutCustomDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close presign dialog"),
+                (&["Enter"], "Copy presigned PUT URL to clipboard"),
+            ],
+            ViewState::CompareDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close compare dialog"),
+                (&["Enter"], "Compare objects"),
+            ],
+            ViewState::CopyToDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close copy dialog"),
+                (&["Enter"], "Copy object"),
+            ],
+            ViewState::TagDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close tag dialog"),
+                (&["Enter"], "Apply tags"),
+            ],
+            ViewState::BatchRenameDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close rename dialog"),
+                (&["Tab"], "Switch find/replace"),
+                (&["Enter"], "Rename objects"),
+            ],
+            ViewState::DeleteConfirmDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Delete objects"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+            ViewState::StorageClassDialog(_, _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["j/k"], "Select storage class"),
+                (&["y"], "Change storage class"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+            ViewState::DownloadRetryDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Retry failed objects"),
+                (&["n", "Esc"], "Close"),
+            ],
+            ViewState::ResumeUploadDialog(_) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["y"], "Resume upload"),
+                (&["N"], "Upload from the beginning"),
+                (&["n", "Esc"], "Cancel"),
+            ],
+        };
+        build_helps(helps)
+    }
 
     pub fn short_helps(&self) -> Vec<(String, usize)> {
         let helps: &[(&[&str], &str, usize)] = match self.view_state {
@@ -330,6 +1171,59 @@ impl ObjectListPage {
                 (&["Enter"], "Copy", 1),
                 (&["?"], "Help", 0),
             ],
+            ViewState::DownloadConfirmDialog(_) => &[
+                (&["Esc"], "Cancel", 2),
+                (&["j/k"], "Select", 3),
+                (&["Space"], "Toggle", 4),
+                (&["/"], "Filter", 5),
+                (&["o"], "Existing files", 6),
+                (&["e"], "Destination", 7),
+                (&["Enter"], "Download", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::DownloadFilterDialog(_, _) => &[
+                (&["Esc"], "Close", 2),
+                (&["Enter"], "Filter", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::CommandDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Execute", 1)],
+            ViewState::UploadDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Next", 1)],
+            ViewState::UploadContentTypeDialog(_, _) => {
+                &[(&["Esc"], "Close", 2), (&["Enter"], "Upload", 1)]
+            }
+            ViewState::NewFolderDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Create", 1)],
+            ViewState::PresignPutKeyDialog(_) => {
+                &[(&["Esc"], "Close", 2), (&["Enter"], "Select", 1)]
+            }
+            ViewState::PresignPutDialog(_, _) => &[
+                (&["Esc"], "Close", 2),
+                (&["j/k"], "Select", 3),
+                (&["Enter"], "Copy", 1),
+                (&["?"], "Help", 0),
+            ],
+            ViewState::PresignPutCustomDialog(_, _) => {
+                &[(&["Esc"], "Close", 2), (&["Enter"], "Copy", 1)]
+            }
+            ViewState::CompareDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Compare", 1)],
+            ViewState::CopyToDialog(_, _) => &[(&["Esc"], "Close", 2), (&["Enter"], "Copy", 1)],
+            ViewState::TagDialog(_) => &[(&["Esc"], "Close", 2), (&["Enter"], "Apply", 1)],
+            ViewState::BatchRenameDialog(_) => &[
+                (&["Esc"], "Close", 2),
+                (&["Tab"], "Switch", 3),
+                (&["Enter"], "Rename", 1),
+            ],
+            ViewState::DeleteConfirmDialog(_) => &[(&["y"], "Delete", 1), (&["n"], "Cancel", 2)],
+            ViewState::StorageClassDialog(_, _) => &[
+                (&["j/k"], "Select", 3),
+                (&["y"], "Change", 1),
+                (&["n"], "Cancel", 2),
+            ],
+            ViewState::DownloadRetryDialog(_) => &[(&["y"], "Retry", 1), (&["n"], "Close", 2)],
+            ViewState::ResumeUploadDialog(_) => &[
+                (&["y"], "Resume", 1),
+                (&["N"], "Restart", 3),
+                (&["n"], "Cancel", 2),
+            ],
         };
         build_short_helps(helps)
     }
@@ -381,7 +1275,9 @@ impl ObjectListPage {
     }
 
     fn open_copy_detail_dialog(&mut self) {
-        let item = self.current_selected_item();
+        let Some(item) = self.current_selected_item() else {
+            return;
+        };
         let dialog_state = match item {
             ObjectItem::Dir { .. } => CopyDetailDialogState::object_list_dir(item.clone()),
             ObjectItem::File { .. } => CopyDetailDialogState::object_list_file(item.clone()),
@@ -393,69 +1289,652 @@ impl ObjectListPage {
         self.view_state = ViewState::Default;
     }
 
-    fn apply_filter(&mut self) {
+    pub fn open_download_confirm_dialog(&mut self, key: String, objs: DownloadObjectList) {
+        let dir_name = key
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            // downloading from the bucket root
+            .unwrap_or(&self.object_key.bucket_name);
+        let path = self.ctx.config.download_file_path(dir_name);
+        // show the resolved path so that the user can see where the files will be saved
+        let destination = std::path::absolute(&path).unwrap_or(path);
+        let max_concurrent_requests = self.ctx.config.download.max_concurrent_requests;
+        match DownloadConfirmDialogState::new(key, objs, destination, max_concurrent_requests) {
+            Ok(dialog_state) => {
+                self.view_state = ViewState::DownloadConfirmDialog(Box::new(dialog_state));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
+    pub fn close_download_confirm_dialog(&mut self) {
         self.view_state = ViewState::Default;
+    }
 
-        self.filter_view_indices();
+    fn open_download_filter_dialog(&mut self) {
+        if let ViewState::DownloadConfirmDialog(state) =
+            std::mem::replace(&mut self.view_state, ViewState::Default)
+        {
+            let mut input_state = InputDialogState::default();
+            input_state.set_input(state.filter());
+            self.view_state = ViewState::DownloadFilterDialog(state, input_state);
+        }
     }
 
-    fn reset_filter(&mut self) {
-        self.filter_input_state.clear_input();
+    fn close_download_filter_dialog(&mut self) {
+        if let ViewState::DownloadFilterDialog(state, _) =
+            std::mem::replace(&mut self.view_state, ViewState::Default)
+        {
+            self.view_state = ViewState::DownloadConfirmDialog(state);
+        }
+    }
 
-        self.filter_view_indices();
+    pub fn open_download_retry_dialog(
+        &mut self,
+        bucket: String,
+        key: String,
+        failed: Vec<DownloadFailure>,
+        options: DownloadObjectsOptions,
+        path: PathBuf,
+    ) {
+        let state = DownloadRetryState {
+            bucket,
+            key,
+            failed,
+            options,
+            path,
+        };
+        self.view_state = ViewState::DownloadRetryDialog(Box::new(state));
     }
 
-    fn filter_view_indices(&mut self) {
-        let filter = self.filter_input_state.input();
-        self.view_indices = self
-            .object_items
-            .iter()
-            .enumerate()
-            .filter(|(_, item)| item.name().contains(filter))
-            .map(|(idx, _)| idx)
-            .collect();
-        // reset list state
-        self.list_state = ScrollListState::new(self.view_indices.len());
+    pub fn open_resume_upload_dialog(
+        &mut self,
+        target: MultipartUploadTarget,
+        upload: MultipartUpload,
+    ) {
+        self.view_state = ViewState::ResumeUploadDialog(Box::new((target, upload)));
+    }
 
-        self.sort_view_indices();
+    fn close_resume_upload_dialog(&mut self) {
+        self.view_state = ViewState::Default;
     }
 
-    fn apply_sort(&mut self) {
+    // Starts a new upload instead of resuming if `resume` is false,
+    // the parts of the previous upload are left as they are
+    fn start_multipart_upload(&mut self, resume: bool) {
+        if let ViewState::ResumeUploadDialog(state) =
+            std::mem::replace(&mut self.view_state, ViewState::Default)
+        {
+            let (target, upload) = *state;
+            let upload = resume.then_some(upload);
+            self.tx
+                .send(AppEventType::StartMultipartUpload(target, upload));
+        }
+    }
+
+    fn close_download_retry_dialog(&mut self) {
         self.view_state = ViewState::Default;
+    }
 
-        self.sort_view_indices();
+    fn retry_download(&mut self) {
+        if let ViewState::DownloadRetryDialog(state) =
+            std::mem::replace(&mut self.view_state, ViewState::Default)
+        {
+            let DownloadRetryState {
+                bucket,
+                key,
+                failed,
+                options,
+                path,
+            } = *state;
+            let objs = match DownloadObjectList::from_objs(failed.into_iter().map(|f| f.obj)) {
+                Ok(objs) => objs,
+                Err(e) => {
+                    self.tx.send(AppEventType::NotifyError(e));
+                    return;
+                }
+            };
+            // do not overwrite the archive containing the objects downloaded successfully
+            let path = match options.output_type {
+                DownloadOutputType::Files => path,
+                DownloadOutputType::TarGz | DownloadOutputType::Zip => unique_file_path(path),
+            };
+            self.tx.send(AppEventType::StartDownloadObjects(
+                bucket, key, objs, options, path,
+            ));
+        }
     }
 
-    fn select_next_sort_item(&mut self) {
-        self.sort_dialog_state.select_next();
+    fn download(&self) {
+        if let Some(ObjectItem::Dir { key, .. }) = self.current_selected_item() {
+            let bucket = self.object_key.bucket_name.clone();
+            self.tx.send(AppEventType::StartLoadAllDownloadObjectList(
+                bucket,
+                key.clone(),
+            ));
+        }
+    }
 
-        self.sort_view_indices();
+    // only the objects under the prefix are listed, which is useful for huge buckets or folders
+    fn download_prefix(&self, prefix: &str) {
+        let bucket = self.object_key.bucket_name.clone();
+        let key = format!("{}{}", self.object_key.joined_object_path(false), prefix);
+        self.tx
+            .send(AppEventType::StartLoadAllDownloadObjectList(bucket, key));
     }
 
-    fn select_prev_sort_item(&mut self) {
-        self.sort_dialog_state.select_prev();
+    fn open_command_dialog(&mut self) {
+        self.view_state = ViewState::CommandDialog(InputDialogState::default());
+    }
 
-        self.sort_view_indices();
+    fn close_command_dialog(&mut self) {
+        self.view_state = ViewState::Default;
     }
 
-    fn sort_view_indices(&mut self) {
-        let items = &self.object_items;
-        let selected = self.sort_dialog_state.selected();
+    fn open_upload_dialog(&mut self) {
+        self.view_state = ViewState::UploadDialog(InputDialogState::default());
+    }
 
-        match selected {
-            ObjectListSortType::Default => {
-                self.view_indices.sort();
-            }
-            ObjectListSortType::NameAsc => {
-                self.view_indices
-                    .sort_by(|a, b| items[*a].name().cmp(items[*b].name()));
-            }
-            ObjectListSortType::NameDesc => {
-                self.view_indices
-                    .sort_by(|a, b| items[*b].name().cmp(items[*a].name()));
-            }
-            ObjectListSortType::LastModifiedAsc => {
-                self.view_indices
+    fn upload_clipboard_image(&self) {
+        let bucket = self.object_key.bucket_name.clone();
+        let prefix = self.object_key.joined_object_path(false);
+        self.tx
+            .send(AppEventType::UploadClipboardImage(bucket, prefix));
+    }
+
+    fn start_watch_dir(&self) {
+        let bucket = self.object_key.bucket_name.clone();
+        let prefix = self.object_key.joined_object_path(false);
+        self.tx.send(AppEventType::StartWatchDir(bucket, prefix));
+    }
+
+    fn open_upload_content_type_dialog(&mut self, path: PathBuf) {
+        let mut state = InputDialogState::default();
+        state.set_input(&detect_content_type(&path));
+        self.view_state = ViewState::UploadContentTypeDialog(path, state);
+    }
+
+    pub fn close_upload_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn open_presign_put_key_dialog(&mut self) {
+        let key = match self.current_selected_object_key() {
+            Some(object_key) if self.is_file_selected() => object_key.joined_object_path(true),
+            _ => self.object_key.joined_object_path(false),
+        };
+        let mut state = InputDialogState::default();
+        state.set_input(&key);
+        self.view_state = ViewState::PresignPutKeyDialog(state);
+    }
+
+    fn open_presign_put_dialog(&mut self, key: String) {
+        if key.is_empty() {
+            return;
+        }
+        if key.ends_with('/') {
+            let e = AppError::msg("Key must not end with '/'");
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        }
+        self.view_state = ViewState::PresignPutDialog(key, PresignDialogState::default());
+    }
+
+    fn close_presign_put_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn copy_presigned_put_url(&mut self, key: String, expires_in: Duration) {
+        let bucket = self.object_key.bucket_name.clone();
+        self.tx
+            .send(AppEventType::CopyPresignedPutUrl(bucket, key, expires_in));
+        self.close_presign_put_dialog();
+    }
+
+    fn open_new_folder_dialog(&mut self) {
+        self.view_state = ViewState::NewFolderDialog(InputDialogState::default());
+    }
+
+    fn close_new_folder_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn create_folder(&mut self, name: String) {
+        if name.is_empty() {
+            return;
+        }
+        if name.contains('/') {
+            let e = AppError::msg("Folder name must not contain '/'");
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        }
+        self.close_new_folder_dialog();
+
+        let object_key = self.object_key.clone();
+        self.tx.send(AppEventType::CreateFolder(object_key, name));
+    }
+
+    fn open_compare_dialog(&mut self) {
+        self.view_state = ViewState::CompareDialog(InputDialogState::default());
+    }
+
+    fn close_compare_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn compare(&mut self, input: &str) {
+        let location = match parse_s3_location(input) {
+            Ok(location) => location,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        self.close_compare_dialog();
+
+        let a_key = self.object_key.clone();
+        // the location is always treated as a prefix
+        let b_key = ObjectKey {
+            bucket_name: location.bucket.clone(),
+            object_path: location.key_paths(),
+        };
+        self.tx
+            .send(AppEventType::StartCompareObjects(a_key, b_key));
+    }
+
+    fn open_copy_to_dialog(&mut self) {
+        let Some(src_key) = self.current_selected_object_key() else {
+            return;
+        };
+        let mut state = InputDialogState::default();
+        state.set_input(&format!(
+            "s3://{}/{}",
+            src_key.bucket_name,
+            src_key.joined_object_path(true)
+        ));
+        self.view_state = ViewState::CopyToDialog(src_key, state);
+    }
+
+    fn close_copy_to_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn copy_to(&mut self, input: &str) {
+        let ViewState::CopyToDialog(src_key, _) = &self.view_state else {
+            return;
+        };
+        let location = match parse_s3_location(input) {
+            Ok(location) => location,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        let mut object_path = location.key_paths();
+        // copy into the folder with the same name
+        if location.is_dir() {
+            object_path.push(src_key.object_path.last().unwrap().clone());
+        }
+        let dst_key = ObjectKey {
+            bucket_name: location.bucket,
+            object_path,
+        };
+        if dst_key == *src_key {
+            let e = AppError::msg("The destination is the same as the source");
+            self.tx.send(AppEventType::NotifyError(e));
+            return;
+        }
+
+        let src_key = src_key.clone();
+        self.close_copy_to_dialog();
+        self.tx
+            .send(AppEventType::StartCopyObject(src_key, dst_key));
+    }
+
+    fn execute_command(&mut self, input: &str) {
+        let cmd = match parse_command(input) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        self.close_command_dialog();
+
+        match cmd {
+            Command::Download(None) => {
+                if self.is_item_selected() {
+                    self.download();
+                }
+            }
+            Command::Download(Some(prefix)) => {
+                self.download_prefix(&prefix);
+            }
+            Command::Delete => {
+                let e = AppError::msg("Deleting objects is not supported");
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+            Command::Sort(sort_type) => {
+                self.sort_dialog_state.select(sort_type);
+                self.sort_view_indices();
+            }
+            Command::Filter(filter) => {
+                self.filter_input_state.set_input(&filter);
+                self.filter_view_indices();
+            }
+            Command::Goto(location) => {
+                self.tx.send(AppEventType::OpenLocation(location));
+            }
+            Command::Inventory(location) => {
+                self.tx.send(AppEventType::StartLoadInventory(location));
+            }
+            Command::AccessLog(prefix) => {
+                let bucket = self.object_key.bucket_name.clone();
+                let prefix = format!(
+                    "{}{}",
+                    self.object_key.joined_object_path(false),
+                    prefix.unwrap_or_default()
+                );
+                self.tx
+                    .send(AppEventType::StartAnalyzeAccessLogs(bucket, prefix));
+            }
+            Command::Refresh => {
+                self.tx.send(AppEventType::ObjectListRefresh);
+            }
+            Command::Every(minutes, task_type, prefix) => {
+                let object_key = self.object_key.clone();
+                let prefix = format!(
+                    "{}{}",
+                    object_key.joined_object_path(false),
+                    prefix.unwrap_or_default()
+                );
+                self.tx.send(AppEventType::ScheduleTask(
+                    task_type, object_key, prefix, minutes,
+                ));
+            }
+            Command::EveryOff => {
+                self.tx.send(AppEventType::CancelScheduledTasks);
+            }
+            Command::Quit => {
+                self.tx.send(AppEventType::Quit);
+            }
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        if let ViewRow::Item(i) = self.selected_row() {
+            if let ObjectItem::File { .. } = self.object_items[i] {
+                if !self.marked.remove(&i) {
+                    self.marked.insert(i);
+                }
+            }
+        }
+        if self.list_state.selected < self.view_rows.len() - 1 {
+            self.select_next();
+        }
+    }
+
+    // The marked files, or the selected file if nothing is marked
+    fn marked_or_selected_file_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.marked.iter().copied().collect();
+        if indices.is_empty() {
+            if let ViewRow::Item(i) = self.selected_row() {
+                indices.push(i);
+            }
+        }
+        indices.sort();
+        indices.retain(|i| matches!(self.object_items[*i], ObjectItem::File { .. }));
+        indices
+    }
+
+    // Renames the marked files, or the selected file if nothing is marked
+    fn open_batch_rename_dialog(&mut self) {
+        let names: Vec<String> = self
+            .marked_or_selected_file_indices()
+            .into_iter()
+            .map(|i| self.object_items[i].name().to_string())
+            .collect();
+        if names.is_empty() {
+            return;
+        }
+        let existing_names = self
+            .object_items
+            .iter()
+            .filter(|item| matches!(item, ObjectItem::File { .. }))
+            .map(|item| item.name().to_string())
+            .collect();
+
+        let dialog_state = BatchRenameDialogState::new(names, existing_names);
+        self.view_state = ViewState::BatchRenameDialog(Box::new(dialog_state));
+    }
+
+    pub fn close_batch_rename_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn open_tag_dialog(&mut self) {
+        if self.marked_or_selected_file_indices().is_empty() {
+            return;
+        }
+        self.view_state = ViewState::TagDialog(InputDialogState::default());
+    }
+
+    fn close_tag_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn apply_tags(&mut self, input: &str) {
+        let tags = match parse_tags(input) {
+            Ok(tags) => tags,
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+                return;
+            }
+        };
+        self.close_tag_dialog();
+
+        let bucket = self.object_key.bucket_name.clone();
+        let prefix = self.object_key.joined_object_path(false);
+        let keys = self
+            .marked_or_selected_file_indices()
+            .into_iter()
+            .map(|i| format!("{}{}", prefix, self.object_items[i].name()))
+            .collect();
+        self.tx
+            .send(AppEventType::StartApplyObjectTags(bucket, keys, tags));
+    }
+
+    fn open_delete_confirm_dialog(&mut self) {
+        let indices = self.marked_or_selected_file_indices();
+        if indices.is_empty() {
+            return;
+        }
+        self.view_state = ViewState::DeleteConfirmDialog(indices);
+    }
+
+    fn close_delete_confirm_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn delete(&mut self) {
+        let ViewState::DeleteConfirmDialog(indices) = &self.view_state else {
+            return;
+        };
+        let bucket = self.object_key.bucket_name.clone();
+        let prefix = self.object_key.joined_object_path(false);
+        let keys = indices
+            .iter()
+            .map(|i| format!("{}{}", prefix, self.object_items[*i].name()))
+            .collect();
+        self.close_delete_confirm_dialog();
+        self.tx.send(AppEventType::StartDeleteObjects(bucket, keys));
+    }
+
+    fn open_storage_class_dialog(&mut self) {
+        let indices = self.marked_or_selected_file_indices();
+        if indices.is_empty() {
+            return;
+        }
+        self.view_state = ViewState::StorageClassDialog(indices, StorageClassType::default());
+    }
+
+    fn close_storage_class_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn change_storage_class(&mut self) {
+        let ViewState::StorageClassDialog(indices, storage_class) = &self.view_state else {
+            return;
+        };
+        let storage_class = *storage_class;
+        let bucket = self.object_key.bucket_name.clone();
+        let prefix = self.object_key.joined_object_path(false);
+        let keys = indices
+            .iter()
+            .map(|i| format!("{}{}", prefix, self.object_items[*i].name()))
+            .collect();
+        self.close_storage_class_dialog();
+        self.tx.send(AppEventType::StartChangeStorageClass(
+            bucket,
+            keys,
+            storage_class,
+        ));
+    }
+
+    fn toggle_group_type(&mut self) {
+        let selected_item = match self.view_rows.get(self.list_state.selected) {
+            Some(ViewRow::Item(i)) => Some(*i),
+            _ => None,
+        };
+
+        self.group_type = self.group_type.next();
+        self.collapsed_groups.clear();
+        self.update_view_rows();
+
+        // keep the selected item selected after regrouping
+        let index = selected_item
+            .and_then(|i| self.view_rows.iter().position(|r| *r == ViewRow::Item(i)))
+            .unwrap_or_default();
+        self.list_state.select_index(index);
+
+        let msg = format!("Group by: {}", self.group_type.str());
+        self.tx.send(AppEventType::NotifyInfo(msg));
+    }
+
+    fn toggle_group_collapsed(&mut self, group: usize) {
+        let label = &self.groups[group].label;
+        if !self.collapsed_groups.remove(label) {
+            self.collapsed_groups.insert(label.clone());
+        }
+        self.update_view_rows();
+    }
+
+    fn update_view_rows(&mut self) {
+        let today = Local::now().date_naive();
+        let (groups, view_rows) = build_view_rows(
+            &self.object_items,
+            &self.view_indices,
+            self.group_type,
+            &self.collapsed_groups,
+            today,
+        );
+        self.groups = groups;
+        self.view_rows = view_rows;
+        self.list_state.set_total(self.view_rows.len());
+    }
+
+    fn apply_filter(&mut self) {
+        self.view_state = ViewState::Default;
+
+        self.filter_view_indices();
+    }
+
+    fn reset_filter(&mut self) {
+        self.filter_input_state.clear_input();
+
+        self.filter_view_indices();
+    }
+
+    // Filtering a large list on every keystroke makes typing lag,
+    // so the filter is applied after the input has stopped for a while.
+    fn update_filter_on_input(&mut self) {
+        if self.object_items.len() < FILTER_DEBOUNCE_MIN_ITEMS {
+            self.filter_view_indices();
+            return;
+        }
+        self.filter_generation += 1;
+        self.tx.send(AppEventType::ObjectListDebounceFilter(
+            self.filter_generation,
+        ));
+    }
+
+    pub fn apply_debounced_filter(&mut self, generation: usize) {
+        // the filter has been changed again, or already applied by Enter/Esc
+        if generation != self.filter_generation {
+            return;
+        }
+        self.filter_view_indices();
+    }
+
+    fn filter_view_indices(&mut self) {
+        // invalidate the pending debounced filter since the latest input is applied here
+        self.filter_generation += 1;
+
+        let filter = self.filter_input_state.input();
+        self.view_indices = self
+            .object_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| self.is_shown(item, filter))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.applied_filter = filter.to_string();
+        // reset list state
+        self.list_state = ScrollListState::new(self.view_indices.len());
+
+        self.sort_view_indices();
+    }
+
+    fn apply_sort(&mut self) {
+        self.view_state = ViewState::Default;
+
+        self.sort_view_indices();
+    }
+
+    fn select_next_sort_item(&mut self) {
+        self.sort_dialog_state.select_next();
+
+        self.sort_view_indices();
+    }
+
+    fn select_prev_sort_item(&mut self) {
+        self.sort_dialog_state.select_prev();
+
+        self.sort_view_indices();
+    }
+
+    fn sort_view_indices(&mut self) {
+        let items = &self.object_items;
+        let selected = self.sort_dialog_state.selected();
+
+        match selected {
+            ObjectListSortType::Default => {
+                self.view_indices.sort();
+            }
+            ObjectListSortType::NameAsc => {
+                self.view_indices
+                    .sort_by(|a, b| items[*a].name().cmp(items[*b].name()));
+            }
+            ObjectListSortType::NameDesc => {
+                self.view_indices
+                    .sort_by(|a, b| items[*b].name().cmp(items[*a].name()));
+            }
+            ObjectListSortType::LastModifiedAsc => {
+                self.view_indices
                     .sort_by(|a, b| items[*a].last_modified().cmp(&items[*b].last_modified()));
             }
             ObjectListSortType::LastModifiedDesc => {
@@ -471,26 +1950,120 @@ impl ObjectListPage {
                     .sort_by(|a, b| items[*b].size_byte().cmp(&items[*a].size_byte()));
             }
         }
+
+        self.update_view_rows();
+    }
+
+    // None if a group header is selected
+    pub fn current_selected_item(&self) -> Option<&ObjectItem> {
+        match self.view_rows.get(self.list_state.selected) {
+            Some(ViewRow::Item(i)) => self.object_items.get(*i),
+            Some(ViewRow::Group(_)) | None => None,
+        }
     }
 
-    pub fn current_selected_item(&self) -> &ObjectItem {
-        let i = self
-            .view_indices
-            .get(self.list_state.selected)
-            .unwrap_or_else(|| {
-                panic!(
-                    "selected view index {} is out of range {}",
-                    self.list_state.selected,
-                    self.view_indices.len()
-                )
-            });
-        self.object_items.get(*i).unwrap_or_else(|| {
-            panic!(
-                "selected index {} is out of range {}",
-                i,
-                self.object_items.len()
-            )
-        })
+    pub fn select_item(&mut self, name: &str, is_file: bool) {
+        let find = |page: &Self| {
+            page.view_indices.iter().copied().find(|&i| {
+                let item = &page.object_items[i];
+                item.name() == name && matches!(item, ObjectItem::File { .. }) == is_file
+            })
+        };
+        if find(self).is_none() {
+            self.reset_filter();
+        }
+        let Some(i) = find(self) else {
+            return;
+        };
+        if !self.view_rows.contains(&ViewRow::Item(i)) {
+            // the item is hidden in a collapsed group
+            let today = Local::now().date_naive();
+            let (_, label) = group_key(&self.object_items[i], self.group_type, today);
+            self.collapsed_groups.remove(&label);
+            self.update_view_rows();
+        }
+        if let Some(index) = self.view_rows.iter().position(|r| *r == ViewRow::Item(i)) {
+            self.list_state.select_index(index);
+        }
+    }
+
+    pub fn add_dir_item(&mut self, item: ObjectItem) {
+        let name = item.name().to_string();
+        if let Some(pos) = insert_dir_item(&mut self.object_items, item) {
+            // marks and changes refer to the items by index
+            let shift = |i: usize| if i >= pos { i + 1 } else { i };
+            self.marked = self.marked.iter().map(|&i| shift(i)).collect();
+            self.changes = self.changes.drain().map(|(i, c)| (shift(i), c)).collect();
+            self.filter_view_indices();
+        }
+        self.select_item(&name, false);
+    }
+
+    pub fn set_object_changes(&mut self, changes: HashMap<usize, ObjectChange>) {
+        self.changes = changes;
+    }
+
+    pub fn set_loading_more(&mut self, loading_more: bool) {
+        self.loading_more = loading_more;
+    }
+
+    pub fn is_loading_more(&self) -> bool {
+        self.loading_more
+    }
+
+    // The items of the following pages are added to the end, keeping the selected item selected.
+    pub fn append_objects(&mut self, items: Vec<ObjectItem>) {
+        let selected_item = match self.view_rows.get(self.list_state.selected) {
+            Some(ViewRow::Item(i)) => Some(*i),
+            _ => None,
+        };
+
+        let start = self.object_items.len();
+        self.object_items.extend(items);
+        let shown: Vec<usize> = (start..self.object_items.len())
+            .filter(|&i| self.is_shown(&self.object_items[i], &self.applied_filter))
+            .collect();
+        self.view_indices.extend(shown);
+        self.sort_view_indices();
+
+        let index = selected_item
+            .and_then(|i| self.view_rows.iter().position(|r| *r == ViewRow::Item(i)))
+            .unwrap_or_default();
+        self.list_state.select_index(index);
+    }
+
+    pub fn all_object_items(&self) -> &[ObjectItem] {
+        &self.object_items
+    }
+
+    pub fn set_decode_key_names(&mut self, decode: bool) {
+        if self.decode_key_names == decode {
+            return;
+        }
+        self.decode_key_names = decode;
+        // the filter is matched against the displayed names
+        if !self.applied_filter.is_empty() {
+            self.filter_view_indices();
+        }
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        if self.show_hidden == show_hidden {
+            return;
+        }
+        self.show_hidden = show_hidden;
+        self.filter_view_indices();
+    }
+
+    // the filter is matched against the displayed names
+    fn is_shown(&self, item: &ObjectItem, filter: &str) -> bool {
+        let hidden = !self.show_hidden
+            && self
+                .ctx
+                .config
+                .filters
+                .is_hidden_object(&self.object_key.bucket_name, item.key());
+        !hidden && display_name(item, self.decode_key_names).contains(filter)
     }
 
     pub fn current_dir_object_key(&self) -> &ObjectKey {
@@ -498,37 +2071,233 @@ impl ObjectListPage {
         &self.object_key
     }
 
-    pub fn current_selected_object_key(&self) -> ObjectKey {
-        let item = self.current_selected_item();
+    // None if a group header is selected
+    pub fn current_selected_object_key(&self) -> Option<ObjectKey> {
+        let item = self.current_selected_item()?;
         let mut object_path = self.object_key.object_path.clone();
         object_path.push(item.name().to_string());
-        ObjectKey {
+        Some(ObjectKey {
             bucket_name: self.object_key.bucket_name.clone(),
             object_path,
-        }
+        })
     }
 
+    // group headers are not included, the list is shown as is in the object detail page
     pub fn object_list(&self) -> Vec<ObjectItem> {
-        self.view_indices
+        self.view_rows
             .iter()
-            .map(|&original_idx| &self.object_items[original_idx])
-            .cloned()
+            .filter_map(|row| match row {
+                ViewRow::Item(i) => Some(self.object_items[*i].clone()),
+                ViewRow::Group(_) => None,
+            })
             .collect()
     }
 
     pub fn list_state(&self) -> ScrollListState {
-        self.list_state
-    }
+        if self.group_type == ObjectListGroupType::None {
+            return self.list_state;
+        }
+        let items_before_selected = self.view_rows[..self.list_state.selected]
+            .iter()
+            .filter(|row| matches!(row, ViewRow::Item(_)))
+            .count();
+        let mut list_state = ScrollListState::new(self.object_list().len());
+        list_state.select_index(items_before_selected);
+        list_state
+    }
+
+    fn selected_row(&self) -> ViewRow {
+        self.view_rows[self.list_state.selected]
+    }
+
+    pub fn is_item_selected(&self) -> bool {
+        matches!(
+            self.view_rows.get(self.list_state.selected),
+            Some(ViewRow::Item(_))
+        )
+    }
+
+    fn is_file_selected(&self) -> bool {
+        match self.view_rows.get(self.list_state.selected) {
+            Some(ViewRow::Item(i)) => matches!(self.object_items[*i], ObjectItem::File { .. }),
+            _ => false,
+        }
+    }
 
     fn non_empty(&self) -> bool {
-        !self.view_indices.is_empty()
+        !self.view_rows.is_empty()
+    }
+}
+
+// Groups are ordered by `group_key`, and the items in each group keep the order of `view_indices`
+fn build_view_rows(
+    items: &[ObjectItem],
+    view_indices: &[usize],
+    group_type: ObjectListGroupType,
+    collapsed_groups: &HashSet<String>,
+    today: NaiveDate,
+) -> (Vec<ObjectListGroup>, Vec<ViewRow>) {
+    if group_type == ObjectListGroupType::None {
+        let rows = view_indices.iter().map(|&i| ViewRow::Item(i)).collect();
+        return (Vec::new(), rows);
+    }
+
+    let mut grouped: BTreeMap<(usize, String), Vec<usize>> = BTreeMap::new();
+    for &i in view_indices {
+        let key = group_key(&items[i], group_type, today);
+        grouped.entry(key).or_default().push(i);
+    }
+
+    let mut groups = Vec::new();
+    let mut rows = Vec::new();
+    for ((_, label), indices) in grouped {
+        let collapsed = collapsed_groups.contains(&label);
+        rows.push(ViewRow::Group(groups.len()));
+        if !collapsed {
+            rows.extend(indices.iter().map(|&i| ViewRow::Item(i)));
+        }
+        groups.push(ObjectListGroup {
+            label,
+            len: indices.len(),
+            collapsed,
+        });
+    }
+    (groups, rows)
+}
+
+// Returns (order, label) of the group the item belongs to, folders always come first
+fn group_key(
+    item: &ObjectItem,
+    group_type: ObjectListGroupType,
+    today: NaiveDate,
+) -> (usize, String) {
+    let ObjectItem::File {
+        name,
+        last_modified,
+        ..
+    } = item
+    else {
+        return (0, "Folders".to_string());
+    };
+    match group_type {
+        ObjectListGroupType::None => (0, String::new()),
+        ObjectListGroupType::Extension => match name.rsplit_once('.') {
+            Some((base, ext)) if !base.is_empty() && !ext.is_empty() => (1, format!("*.{}", ext)),
+            _ => (2, "No extension".to_string()),
+        },
+        ObjectListGroupType::LastModified => {
+            let (order, label) = match (today - last_modified.date_naive()).num_days() {
+                ..=0 => (1, "Today"),
+                1 => (2, "Yesterday"),
+                2..=6 => (3, "Last 7 days"),
+                7..=29 => (4, "Last 30 days"),
+                _ => (5, "Older"),
+            };
+            (order, label.to_string())
+        }
+        ObjectListGroupType::Prefix => match name.find(['-', '_', '.']) {
+            Some(i) if i > 0 => (1, format!("{}*", &name[..=i])),
+            _ => (2, "Other".to_string()),
+        },
+    }
+}
+
+const MAX_DOWNLOAD_RETRY_DIALOG_FAILURES: usize = 5;
+
+// the list with fewer items than this is filtered immediately on each input
+const FILTER_DEBOUNCE_MIN_ITEMS: usize = 10_000;
+pub const FILTER_DEBOUNCE_DELAY: Duration = Duration::from_millis(150);
+
+fn build_download_retry_dialog_lines(failed: &[DownloadFailure]) -> Vec<String> {
+    let mut lines = vec![format!("{} objects failed to download:", failed.len())];
+    lines.extend(
+        failed
+            .iter()
+            .take(MAX_DOWNLOAD_RETRY_DIALOG_FAILURES)
+            .map(|f| format!("  {}: {}", f.obj.key, f.error)),
+    );
+    if failed.len() > MAX_DOWNLOAD_RETRY_DIALOG_FAILURES {
+        let rest = failed.len() - MAX_DOWNLOAD_RETRY_DIALOG_FAILURES;
+        lines.push(format!("  ... and {} more", rest));
     }
+    lines.push("".into());
+    lines.push("Retry failed objects?".into());
+    lines
+}
+
+fn build_resume_upload_dialog_lines(
+    target: &MultipartUploadTarget,
+    upload: &MultipartUpload,
+    part_size_byte: u64,
+) -> Vec<String> {
+    let plan = plan_parts(target.size_byte, part_size_byte);
+    let parts = reusable_parts(upload, &plan);
+    let uploaded_size: u64 = parts.iter().map(|p| p.size_byte).sum();
+    vec![
+        format!("An unfinished upload of {} was found.", target.key),
+        format!(
+            "  Uploaded: {} of {} parts ({} / {})",
+            format_count(parts.len()),
+            format_count(plan.len()),
+            format_size_byte(uploaded_size as usize),
+            format_size_byte(target.size_byte as usize),
+        ),
+        "".into(),
+        "Resume the upload? (N to upload from the beginning)".into(),
+    ]
+}
+
+fn build_storage_class_dialog_lines(
+    sizes: &[usize],
+    storage_class: &StorageClassType,
+) -> Vec<String> {
+    let total_size: usize = sizes.iter().sum();
+    let mut lines = vec![
+        format!(
+            "Change storage class of {} objects?",
+            format_count(sizes.len())
+        ),
+        format!("  Storage class: {} (j/k to change)", storage_class.str()),
+        format!("  Total size: {}", format_size_byte(total_size)),
+    ];
+    if storage_class.min_billable_size_byte() > 0 {
+        let billable_size = storage_class.billable_size_byte(sizes.iter().copied());
+        lines.push(format!(
+            "  Billable size: {} (minimum {} per object)",
+            format_size_byte(billable_size),
+            format_size_byte(storage_class.min_billable_size_byte())
+        ));
+    }
+    if storage_class.min_storage_days() > 0 {
+        lines.push(format!(
+            "  Minimum storage duration: {} days",
+            storage_class.min_storage_days()
+        ));
+    }
+    if storage_class.requires_restore() {
+        lines.push("  Objects must be restored before they can be read".into());
+    }
+    let too_large = sizes
+        .iter()
+        .filter(|s| **s > COPY_OBJECT_MAX_SIZE_BYTE)
+        .count();
+    if too_large > 0 {
+        lines.push(format!(
+            "  {} objects are larger than 5 GiB and will fail",
+            format_count(too_large)
+        ));
+    }
+    lines
 }
 
 fn build_list_items<'a>(
     current_items: &'a [ObjectItem],
-    view_indices: &'a [usize],
+    view_rows: &[ViewRow],
+    groups: &[ObjectListGroup],
+    marked: &HashSet<usize>,
+    changes: &HashMap<usize, ObjectChange>,
     filter: &'a str,
+    decode_key_names: bool,
     offset: usize,
     selected: usize,
     area: Rect,
@@ -536,42 +2305,73 @@ fn build_list_items<'a>(
     theme: &ColorTheme,
 ) -> Vec<ListItem<'a>> {
     let show_item_count = (area.height as usize) - 2 /* border */;
-    view_indices
+    view_rows
         .iter()
-        .map(|&original_idx| &current_items[original_idx])
         .skip(offset)
         .take(show_item_count)
         .enumerate()
-        .map(|(idx, item)| {
-            build_list_item(
-                item,
+        .map(|(idx, row)| match *row {
+            ViewRow::Group(group) => {
+                build_group_list_item(&groups[group], idx + offset == selected, theme)
+            }
+            ViewRow::Item(original_idx) => build_list_item(
+                &current_items[original_idx],
                 idx + offset == selected,
+                marked.contains(&original_idx),
+                changes.get(&original_idx).copied(),
                 filter,
+                decode_key_names,
                 area,
                 ui_config,
                 theme,
-            )
+            ),
         })
         .collect()
 }
 
+fn build_group_list_item<'a>(
+    group: &ObjectListGroup,
+    selected: bool,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let icon = if group.collapsed { "▸" } else { "▾" };
+    let line = Line::from(vec![
+        " ".into(),
+        icon.into(),
+        " ".into(),
+        group.label.clone().bold(),
+        format!(" ({})", group.len).into(),
+    ]);
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(line).style(style)
+}
+
 fn build_list_item<'a>(
     item: &'a ObjectItem,
     selected: bool,
+    marked: bool,
+    change: Option<ObjectChange>,
     filter: &'a str,
+    decode_key_names: bool,
     area: Rect,
     ui_config: &UiConfig,
     theme: &ColorTheme,
 ) -> ListItem<'a> {
-    let line = match item {
-        ObjectItem::Dir { name, .. } => build_object_dir_line(name, filter, theme),
+    let name = display_name(item, decode_key_names);
+    let mut line = match item {
+        ObjectItem::Dir { .. } => build_object_dir_line(name, filter, theme),
         ObjectItem::File {
-            name,
             size_byte,
             last_modified,
             ..
         } => build_object_file_line(
-            name,
+            &name,
             *size_byte,
             last_modified,
             filter,
@@ -581,19 +2381,40 @@ fn build_list_item<'a>(
         ),
     };
 
+    if marked {
+        line.spans[0] = "*".into();
+    } else if let Some(change) = change {
+        let indicator = match change {
+            ObjectChange::New => "+",
+            ObjectChange::Modified => "~",
+        };
+        line.spans[0] = indicator.fg(theme.list_changed);
+    }
+
     let style = if selected {
         Style::default()
             .bg(theme.list_selected_bg)
             .fg(theme.list_selected_fg)
+    } else if marked {
+        Style::default().fg(theme.list_marked)
     } else {
         Style::default()
     };
     ListItem::new(line).style(style)
 }
 
-fn build_object_dir_line<'a>(name: &'a str, filter: &'a str, theme: &ColorTheme) -> Line<'a> {
+fn display_name(item: &ObjectItem, decode_key_names: bool) -> Cow<'_, str> {
+    if decode_key_names {
+        decode_key_name(item.name())
+    } else {
+        Cow::Borrowed(item.name())
+    }
+}
+
+fn build_object_dir_line<'a>(name: Cow<'a, str>, filter: &'a str, theme: &ColorTheme) -> Line<'a> {
     if filter.is_empty() {
-        Line::from(vec![" ".into(), name.bold(), "/".bold(), " ".into()])
+        let name = Span::styled(name, Style::default().bold());
+        Line::from(vec![" ".into(), name, "/".bold(), " ".into()])
     } else {
         let i = name.find(filter).unwrap();
         let mut spans = highlight_matched_text(name)
@@ -609,7 +2430,7 @@ fn build_object_dir_line<'a>(name: &'a str, filter: &'a str, theme: &ColorTheme)
 }
 
 fn build_object_file_line<'a>(
-    name: &'a str,
+    name: &str,
     size_byte: usize,
     last_modified: &'a DateTime<Local>,
     filter: &'a str,
@@ -623,148 +2444,1069 @@ fn build_object_file_line<'a>(
     let size_w: usize = 10;
     let name_w: usize = (width as usize) - date_w - size_w - 10 /* spaces */ - 4 /* border + pad */;
 
-    let name = format!("{:<name_w$}", name, name_w = name_w);
-    let date = format!("{:<date_w$}", date, date_w = date_w);
-    let size = format!("{:>size_w$}", size, size_w = size_w);
+    let name = format!("{:<name_w$}", name, name_w = name_w);
+    let date = format!("{:<date_w$}", date, date_w = date_w);
+    let size = format!("{:>size_w$}", size, size_w = size_w);
+
+    if filter.is_empty() {
+        Line::from(vec![
+            " ".into(),
+            name.into(),
+            "    ".into(),
+            date.into(),
+            "    ".into(),
+            size.into(),
+            " ".into(),
+        ])
+    } else {
+        let i = name.find(filter).unwrap();
+        let mut spans = highlight_matched_text(name)
+            .matched_range(i, i + filter.chars().count())
+            .not_matched_style(Style::default())
+            .matched_style(Style::default().fg(theme.list_filter_match))
+            .into_spans();
+        spans.insert(0, " ".into());
+        spans.push("    ".into());
+        spans.push(date.into());
+        spans.push("    ".into());
+        spans.push(size.into());
+        spans.push(" ".into());
+        Line::from(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event, multipart::UploadedPart, object::DownloadObjectInfo, schedule::ScheduledTaskType,
+        set_cells,
+    };
+
+    use super::*;
+    use chrono::NaiveDateTime;
+    use ratatui::{
+        backend::TestBackend,
+        buffer::Buffer,
+        style::{Color, Modifier},
+        Terminal,
+    };
+
+    #[test]
+    fn test_render_without_scroll() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let items = vec![
+                object_dir_item("dir1"),
+                object_dir_item("dir2"),
+                object_file_item("file1", 1024 + 10, "2024-01-02 13:01:02"),
+                object_file_item("file2", 1024 * 999, "2023-12-31 09:00:00"),
+            ];
+            let object_key = ObjectKey {
+                bucket_name: "test-bucket".to_string(),
+                object_path: vec!["path".to_string(), "to".to_string()],
+            };
+            let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+            let area = Rect::new(0, 0, 60, 10);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌─────────────────────────────────────────────────── 1 / 4 ┐",
+            "│  dir1/                                                   │",
+            "│  dir2/                                                   │",
+            "│  file1                2024-01-02 13:01:02      1.01 KiB  │",
+            "│  file2                2023-12-31 09:00:00       999 KiB  │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // dir items
+            (3..8, [1, 2]) => modifier: Modifier::BOLD,
+            // selected item
+            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_scroll() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let items = (0..32)
+                .map(|i| object_file_item(&format!("file{}", i + 1), 1024, "2024-01-02 13:01:02"))
+                .collect();
+            let object_key = ObjectKey {
+                bucket_name: "test-bucket".to_string(),
+                object_path: vec!["path".to_string(), "to".to_string()],
+            };
+            let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+            let area = Rect::new(0, 0, 60, 10);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+                "┌─────────────────────────────────────────────────  1 / 32 ┐",
+                "│  file1                2024-01-02 13:01:02         1 KiB ││",
+                "│  file2                2024-01-02 13:01:02         1 KiB ││",
+                "│  file3                2024-01-02 13:01:02         1 KiB  │",
+                "│  file4                2024-01-02 13:01:02         1 KiB  │",
+                "│  file5                2024-01-02 13:01:02         1 KiB  │",
+                "│  file6                2024-01-02 13:01:02         1 KiB  │",
+                "│  file7                2024-01-02 13:01:02         1 KiB  │",
+                "│  file8                2024-01-02 13:01:02         1 KiB  │",
+                "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // selected item
+            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_config() -> std::io::Result<()> {
+        let (tx, _) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        terminal.draw(|f| {
+            let items = vec![
+                object_dir_item("dir1"),
+                object_dir_item("dir2"),
+                object_file_item("file1", 1024 + 10, "2024-01-02 13:01:02"),
+                object_file_item("file2", 1024 * 999, "2023-12-31 09:00:00"),
+            ];
+            let object_key = ObjectKey {
+                bucket_name: "test-bucket".to_string(),
+                object_path: vec!["path".to_string(), "to".to_string()],
+            };
+            let mut ctx = AppContext::default();
+            ctx.config.ui.object_list.date_format = "%Y/%m/%d".to_string();
+            ctx.config.ui.object_list.date_width = 10;
+            let mut page = ObjectListPage::new(items, object_key, Rc::new(ctx), tx);
+            let area = Rect::new(0, 0, 60, 10);
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌─────────────────────────────────────────────────── 1 / 4 ┐",
+            "│  dir1/                                                   │",
+            "│  dir2/                                                   │",
+            "│  file1                         2024/01/02      1.01 KiB  │",
+            "│  file2                         2023/12/31       999 KiB  │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // dir items
+            (3..8, [1, 2]) => modifier: Modifier::BOLD,
+            // selected item
+            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_items() {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let items = vec![
+            object_dir_item("rid"),
+            object_file_item("file", 1024, "2024-01-02 13:01:02"),
+            object_dir_item("dir"),
+            object_file_item("xyz", 1024 * 1024, "2023-12-31 23:59:59"),
+            object_file_item("abc", 0, "-2000-01-01 00:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string(), "to".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('o')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select NameAsc
+
+        assert_eq!(page.view_indices, vec![4, 2, 1, 0, 3]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select NameDesc
+
+        assert_eq!(page.view_indices, vec![3, 0, 1, 2, 4]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select LastModifiedAsc
+
+        assert_eq!(page.view_indices, vec![0, 2, 4, 3, 1]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select LastModifiedDesc
+
+        assert_eq!(page.view_indices, vec![1, 3, 4, 0, 2]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select SizeAsc
+
+        assert_eq!(page.view_indices, vec![0, 2, 4, 1, 3]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select SizeDesc
+
+        assert_eq!(page.view_indices, vec![3, 1, 4, 0, 2]);
+    }
+
+    #[test]
+    fn test_debounce_filter_on_large_list() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = (0..FILTER_DEBOUNCE_MIN_ITEMS)
+            .map(|i| object_dir_item(&format!("dir{:05}", i)))
+            .collect();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        for c in "9999".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+
+        // not filtered until the debounced event arrives
+        assert_eq!(page.view_indices.len(), FILTER_DEBOUNCE_MIN_ITEMS);
+        let generations: Vec<usize> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::ObjectListDebounceFilter(generation) => Some(generation),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(generations.len(), 4);
+
+        // the outdated one is ignored
+        page.apply_debounced_filter(generations[0]);
+        assert_eq!(page.view_indices.len(), FILTER_DEBOUNCE_MIN_ITEMS);
+
+        page.apply_debounced_filter(generations[3]);
+        assert_eq!(page.view_indices, vec![9999]);
+        assert_eq!(page.applied_filter, "9999");
+    }
+
+    #[test]
+    fn test_append_objects() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend)?;
+        let area = Rect::new(0, 0, 60, 6);
+
+        let items = vec![
+            object_dir_item("dir1"),
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        page.set_loading_more(true);
+        terminal.draw(|f| page.render(f, area))?;
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('1')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        terminal.draw(|f| page.render(f, area))?;
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+
+        page.append_objects(vec![
+            object_dir_item("dir2"),
+            object_file_item("file2", 2048, "2024-01-02 13:01:02"),
+            object_file_item("file10", 2048, "2024-01-02 13:01:02"),
+        ]);
+
+        // the filter is applied to the appended items, and the selection is kept
+        assert_eq!(page.view_indices, vec![0, 1, 4]);
+        assert_eq!(page.current_selected_item().unwrap().name(), "file1");
+        assert_eq!(page.all_object_items().len(), 5);
+
+        terminal.draw(|f| page.render(f, area))?;
+        let bottom: String = (0..20)
+            .map(|x| terminal.backend().buffer()[(x, 5)].symbol().to_string())
+            .collect();
+        assert_eq!(bottom, "└ Loading more... ──");
+
+        page.set_loading_more(false);
+        terminal.draw(|f| page.render(f, area))?;
+        let bottom: String = (0..20)
+            .map(|x| terminal.backend().buffer()[(x, 5)].symbol().to_string())
+            .collect();
+        assert_eq!(bottom, "└───────────────────");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_decoded_key_names() {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let items = vec![
+            object_dir_item("a%20dir"),
+            object_file_item("my+file%E3%81%82.txt", 1024, "2024-01-02 13:01:02"),
+            object_file_item("broken%ZZ file", 1024, "2024-01-02 13:01:02"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        page.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(page.view_indices, vec![2]);
+
+        page.set_decode_key_names(true);
+        assert_eq!(page.view_indices, vec![0, 1, 2]);
+        let names: Vec<String> = page
+            .object_items
+            .iter()
+            .map(|item| display_name(item, true).into_owned())
+            .collect();
+        assert_eq!(names, vec!["a dir", "my fileあ.txt", "broken%ZZ file"]);
+
+        // the raw keys are kept
+        assert_eq!(
+            page.current_selected_object_key().unwrap().object_path,
+            vec!["a%20dir".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_hidden_objects() {
+        let (tx, _) = event::new();
+        let mut items = vec![
+            object_dir_item("_temporary"),
+            object_dir_item("data"),
+            object_file_item("_SUCCESS", 0, "2024-01-02 13:01:02"),
+            object_file_item("a.txt", 1024, "2024-01-02 13:01:02"),
+        ];
+        for item in items.iter_mut() {
+            match item {
+                ObjectItem::Dir { name, key, .. } => *key = format!("{}/", name),
+                ObjectItem::File { name, key, .. } => *key = name.clone(),
+            }
+        }
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut ctx = AppContext::default();
+        ctx.config.filters.hidden = vec!["*/_temporary/".to_string(), "*/_SUCCESS".to_string()];
+        let mut page = ObjectListPage::new(items, object_key, Rc::new(ctx), tx);
+        assert_eq!(page.view_indices, vec![1, 3]);
+
+        page.set_show_hidden(true);
+        assert_eq!(page.view_indices, vec![0, 1, 2, 3]);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        page.handle_key(KeyEvent::from(KeyCode::Char('a')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(page.view_indices, vec![0, 1, 3]);
+
+        page.set_show_hidden(false);
+        assert_eq!(page.view_indices, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_execute_commands() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![
+            object_dir_item("rid"),
+            object_file_item("file", 1024, "2024-01-02 13:01:02"),
+            object_dir_item("dir"),
+            object_file_item("xyz", 1024 * 1024, "2023-12-31 23:59:59"),
+            object_file_item("abc", 0, "-2000-01-01 00:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string(), "to".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        let execute = |page: &mut ObjectListPage, cmd: &str| {
+            page.handle_key(KeyEvent::from(KeyCode::Char(':')));
+            for c in cmd.chars() {
+                page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+            }
+            page.handle_key(KeyEvent::from(KeyCode::Enter));
+        };
+
+        execute(&mut page, "sort size desc");
+        assert_eq!(page.view_indices, vec![3, 1, 4, 0, 2]);
+
+        execute(&mut page, "filter i");
+        assert_eq!(page.view_indices, vec![1, 0, 2]);
+
+        execute(&mut page, "filter");
+        assert_eq!(page.view_indices, vec![3, 1, 4, 0, 2]);
+
+        execute(&mut page, "download logs/2024-");
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartLoadAllDownloadObjectList(bucket, key) => Some((bucket, key)),
+            _ => None,
+        });
+        let expected = ("test-bucket".to_string(), "path/to/logs/2024-".to_string());
+        assert_eq!(event, Some(expected));
+
+        execute(&mut page, "every 5 download logs/");
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::ScheduleTask(task_type, _, prefix, minutes) => {
+                Some((task_type, prefix, minutes))
+            }
+            _ => None,
+        });
+        let expected = (ScheduledTaskType::Download, "path/to/logs/".to_string(), 5);
+        assert_eq!(event, Some(expected));
+
+        // the dialog stays open if the command is invalid
+        execute(&mut page, "sort foo");
+        assert!(matches!(page.view_state, ViewState::CommandDialog(_)));
+    }
+
+    #[test]
+    fn test_mark_and_rename() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        let items = vec![
+            object_dir_item("dir1"),
+            object_file_item("file1", 1024 + 10, "2024-01-02 13:01:02"),
+            object_file_item("file2", 1024 * 999, "2023-12-31 09:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string(), "to".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
+
+        // dirs cannot be marked
+        for c in [' ', ' ', ' '] {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(page.marked, HashSet::from([1, 2]));
+        terminal.draw(|f| page.render(f, area))?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌─────────────────────────────────────────────────── 3 / 3 ┐",
+            "│  dir1/                                                   │",
+            "│ *file1                2024-01-02 13:01:02      1.01 KiB  │",
+            "│ *file2                2023-12-31 09:00:00       999 KiB  │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "│                                                          │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // dir items
+            (3..8, [1]) => modifier: Modifier::BOLD,
+            // marked item
+            (2..58, [2]) => fg: Color::Yellow,
+            // selected item
+            (2..58, [3]) => bg: Color::Cyan, fg: Color::Black,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        for c in "^file".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Tab));
+        for c in "doc".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartRenameObjects(bucket, prefix, targets) => {
+                Some((bucket, prefix, targets))
+            }
+            _ => None,
+        });
+        let (bucket, prefix, targets) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(prefix, "path/to/");
+        let targets: Vec<(&str, &str)> = targets
+            .iter()
+            .map(|t| (t.from.as_str(), t.to.as_str()))
+            .collect();
+        assert_eq!(targets, vec![("file1", "doc1"), ("file2", "doc2")]);
+
+        // Esc clears the marks before quitting
+        page.close_batch_rename_dialog();
+        page.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert!(page.marked.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_failed_download() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![object_dir_item("dir1")];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        let failure = |key: &str| DownloadFailure {
+            obj: DownloadObjectInfo {
+                key: key.to_string(),
+                size_byte: 0,
+                last_modified: Local::now(),
+                e_tag: "".to_string(),
+            },
+            error: "Failed to download object".to_string(),
+        };
+        let failed: Vec<DownloadFailure> =
+            (1..=6).map(|i| failure(&format!("dir1/f{i}"))).collect();
+
+        let lines = build_download_retry_dialog_lines(&failed);
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "6 objects failed to download:");
+        assert_eq!(lines[1], "  dir1/f1: Failed to download object");
+        assert_eq!(lines[6], "  ... and 1 more");
+
+        page.open_download_retry_dialog(
+            "test-bucket".to_string(),
+            "dir1/".to_string(),
+            failed[..2].to_vec(),
+            DownloadObjectsOptions::default(),
+            PathBuf::from("/tmp/dir1"),
+        );
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartDownloadObjects(bucket, key, objs, _, path) => {
+                Some((bucket, key, objs, path))
+            }
+            _ => None,
+        });
+        let (bucket, key, objs, path) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(key, "dir1/");
+        let keys: Vec<String> = objs.iter().unwrap().map(|o| o.unwrap().key).collect();
+        assert_eq!(keys, vec!["dir1/f1", "dir1/f2"]);
+        assert_eq!(path, PathBuf::from("/tmp/dir1"));
+    }
+
+    #[test]
+    fn test_upload_file() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![object_file_item("file1", 1024, "2024-01-02 13:01:02")];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string(), "to".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('u')));
+        assert!(matches!(page.view_state, ViewState::UploadDialog(_)));
+        for c in "/tmp/a.txt".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        // the detected type can be overridden
+        let ViewState::UploadContentTypeDialog(_, state) = &mut page.view_state else {
+            panic!("content type dialog is not open");
+        };
+        assert_eq!(state.input(), "text/plain");
+        state.set_input("text/csv");
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::UploadObject(bucket, prefix, path, content_type) => {
+                Some((bucket, prefix, path, content_type))
+            }
+            _ => None,
+        });
+        let (bucket, prefix, path, content_type) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(prefix, "path/to/");
+        assert_eq!(path, PathBuf::from("/tmp/a.txt"));
+        assert_eq!(content_type.as_deref(), Some("text/csv"));
+    }
+
+    #[test]
+    fn test_copy_presigned_put_url() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![object_file_item("file1", 1024, "2024-01-02 13:01:02")];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string(), "to".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let mut terminal = setup_terminal()?;
+        terminal.draw(|f| page.render(f, Rect::new(0, 0, 60, 10)))?;
+
+        // the selected file is used as the initial key
+        page.handle_key(KeyEvent::from(KeyCode::Char('P')));
+        let ViewState::PresignPutKeyDialog(state) = &mut page.view_state else {
+            panic!("key dialog is not open");
+        };
+        assert_eq!(state.input(), "path/to/file1");
+        state.set_input("path/to/new.txt");
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::PresignPutDialog(_, _)));
+        page.handle_key(KeyEvent::from(KeyCode::Char('k')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::CopyPresignedPutUrl(bucket, key, expires_in) => {
+                Some((bucket, key, expires_in))
+            }
+            _ => None,
+        });
+        let (bucket, key, expires_in) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(key, "path/to/new.txt");
+        assert_eq!(expires_in, Duration::from_secs(15 * 60));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_clipboard_image() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["images".to_string()],
+        };
+        let mut page = ObjectListPage::new(Vec::new(), object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('U')));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::UploadClipboardImage(bucket, prefix) => Some((bucket, prefix)),
+            _ => None,
+        });
+        assert_eq!(
+            event,
+            Some(("test-bucket".to_string(), "images/".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_start_watch_dir() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["inbox".to_string()],
+        };
+        let mut page = ObjectListPage::new(Vec::new(), object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('W')));
 
-    if filter.is_empty() {
-        Line::from(vec![
-            " ".into(),
-            name.into(),
-            "    ".into(),
-            date.into(),
-            "    ".into(),
-            size.into(),
-            " ".into(),
-        ])
-    } else {
-        let i = name.find(filter).unwrap();
-        let mut spans = highlight_matched_text(name)
-            .matched_range(i, i + filter.chars().count())
-            .not_matched_style(Style::default())
-            .matched_style(Style::default().fg(theme.list_filter_match))
-            .into_spans();
-        spans.insert(0, " ".into());
-        spans.push("    ".into());
-        spans.push(date.into());
-        spans.push("    ".into());
-        spans.push(size.into());
-        spans.push(" ".into());
-        Line::from(spans)
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartWatchDir(bucket, prefix) => Some((bucket, prefix)),
+            _ => None,
+        });
+        assert_eq!(
+            event,
+            Some(("test-bucket".to_string(), "inbox/".to_string()))
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{event, set_cells};
+    #[test]
+    fn test_resume_upload() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![object_file_item("file1", 1024, "2024-01-02 13:01:02")];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
 
-    use super::*;
-    use chrono::NaiveDateTime;
-    use ratatui::{
-        backend::TestBackend,
-        buffer::Buffer,
-        style::{Color, Modifier},
-        Terminal,
-    };
+        let mib = 1024 * 1024;
+        let target = MultipartUploadTarget {
+            bucket: "test-bucket".into(),
+            key: "path/large.bin".into(),
+            path: PathBuf::from("/tmp/large.bin"),
+            size_byte: 20 * mib,
+            content_type: None,
+        };
+        let upload = MultipartUpload {
+            upload_id: "upload-id".into(),
+            parts: vec![UploadedPart {
+                part_number: 1,
+                e_tag: "etag-1".into(),
+                size_byte: 8 * mib,
+            }],
+        };
+        assert_eq!(
+            build_resume_upload_dialog_lines(&target, &upload, 8 * mib),
+            vec![
+                "An unfinished upload of path/large.bin was found.",
+                "  Uploaded: 1 of 3 parts (8 MiB / 20 MiB)",
+                "",
+                "Resume the upload? (N to upload from the beginning)",
+            ]
+        );
+
+        page.open_resume_upload_dialog(target.clone(), upload.clone());
+        page.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        page.open_resume_upload_dialog(target.clone(), upload.clone());
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        page.open_resume_upload_dialog(target, upload);
+        page.handle_key(KeyEvent::from(KeyCode::Char('N')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let upload_ids: Vec<Option<String>> = std::iter::from_fn(|| rx.try_recv())
+            .filter_map(|e| match e {
+                AppEventType::StartMultipartUpload(target, upload) => {
+                    assert_eq!(target.key, "path/large.bin");
+                    Some(upload.map(|u| u.upload_id))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(upload_ids, vec![Some("upload-id".to_string()), None]);
+    }
 
     #[test]
-    fn test_render_without_scroll() -> std::io::Result<()> {
+    fn test_delete_marked_objects() -> std::io::Result<()> {
         let ctx = Rc::default();
-        let (tx, _) = event::new();
+        let (tx, rx) = event::new();
         let mut terminal = setup_terminal()?;
 
-        terminal.draw(|f| {
-            let items = vec![
-                object_dir_item("dir1"),
-                object_dir_item("dir2"),
-                object_file_item("file1", 1024 + 10, "2024-01-02 13:01:02"),
-                object_file_item("file2", 1024 * 999, "2023-12-31 09:00:00"),
-            ];
-            let object_key = ObjectKey {
-                bucket_name: "test-bucket".to_string(),
-                object_path: vec!["path".to_string(), "to".to_string()],
-            };
-            let mut page = ObjectListPage::new(items, object_key, ctx, tx);
-            let area = Rect::new(0, 0, 60, 10);
-            page.render(f, area);
-        })?;
+        let items = vec![
+            object_dir_item("dir1"),
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+            object_file_item("file2", 2048, "2023-12-31 09:00:00"),
+            object_file_item("file3", 10, "2023-12-31 09:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
+
+        for c in [' ', ' ', ' ', 'd'] {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        terminal.draw(|f| page.render(f, area))?;
 
         #[rustfmt::skip]
         let mut expected = Buffer::with_lines([
-            "┌─────────────────────────────────────────────────── 1 / 4 ┐",
+            "┌─────────────────────────────────────────────────── 4 / 4 ┐",
             "│  dir1/                                                   │",
-            "│  dir2/                                                   │",
-            "│  file1                2024-01-02 13:01:02      1.01 KiB  │",
-            "│  file2                2023-12-31 09:00:00       999 KiB  │",
-            "│                                                          │",
-            "│                                                          │",
-            "│                                                          │",
+            "│ *file1    ╭Delete───────────────────────────╮     1 KiB  │",
+            "│ *file2    │ Delete 2 objects?               │     2 KiB  │",
+            "│  file3    │   Total size: 3 KiB             │      10 B  │",
+            "│           │                                 │            │",
+            "│           │ Press y to confirm, n to cancel │            │",
+            "│           ╰─────────────────────────────────╯            │",
             "│                                                          │",
             "└──────────────────────────────────────────────────────────┘",
         ]);
         set_cells! { expected =>
             // dir items
-            (3..8, [1, 2]) => modifier: Modifier::BOLD,
+            (3..8, [1]) => modifier: Modifier::BOLD,
+            // marked items
+            (2..11, [2, 3]) => fg: Color::Yellow,
+            (48..58, [2, 3]) => fg: Color::Yellow,
             // selected item
-            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+            (2..11, [4]) => bg: Color::Cyan, fg: Color::Black,
+            (48..58, [4]) => bg: Color::Cyan, fg: Color::Black,
+            // confirm dialog
+            (14..45, [6]) => fg: Color::DarkGray,
         }
 
         terminal.backend().assert_buffer(&expected);
 
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartDeleteObjects(bucket, keys) => Some((bucket, keys)),
+            _ => None,
+        });
+        let (bucket, keys) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(keys, vec!["path/file1", "path/file2"]);
+
         Ok(())
     }
 
     #[test]
-    fn test_render_with_scroll() -> std::io::Result<()> {
+    fn test_apply_tags() {
         let ctx = Rc::default();
-        let (tx, _) = event::new();
+        let (tx, rx) = event::new();
+        let items = vec![
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+            object_file_item("file2", 2048, "2023-12-31 09:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('t')));
+        assert!(matches!(page.view_state, ViewState::TagDialog(_)));
+        for c in "env=prod, team=a".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartApplyObjectTags(bucket, keys, tags) => Some((bucket, keys, tags)),
+            _ => None,
+        });
+        let (bucket, keys, tags) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(keys, vec!["path/file1"]);
+        assert_eq!(
+            tags,
+            vec![
+                ("env".to_string(), "prod".to_string()),
+                ("team".to_string(), "a".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_change_storage_class() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
         let mut terminal = setup_terminal()?;
 
-        terminal.draw(|f| {
-            let items = (0..32)
-                .map(|i| object_file_item(&format!("file{}", i + 1), 1024, "2024-01-02 13:01:02"))
-                .collect();
-            let object_key = ObjectKey {
-                bucket_name: "test-bucket".to_string(),
-                object_path: vec!["path".to_string(), "to".to_string()],
-            };
-            let mut page = ObjectListPage::new(items, object_key, ctx, tx);
-            let area = Rect::new(0, 0, 60, 10);
-            page.render(f, area);
-        })?;
+        let items = vec![
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+            object_file_item("file2", 1024 * 1024, "2023-12-31 09:00:00"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
 
-        #[rustfmt::skip]
-        let mut expected = Buffer::with_lines([
-                "┌─────────────────────────────────────────────────  1 / 32 ┐",
-                "│  file1                2024-01-02 13:01:02         1 KiB ││",
-                "│  file2                2024-01-02 13:01:02         1 KiB ││",
-                "│  file3                2024-01-02 13:01:02         1 KiB  │",
-                "│  file4                2024-01-02 13:01:02         1 KiB  │",
-                "│  file5                2024-01-02 13:01:02         1 KiB  │",
-                "│  file6                2024-01-02 13:01:02         1 KiB  │",
-                "│  file7                2024-01-02 13:01:02         1 KiB  │",
-                "│  file8                2024-01-02 13:01:02         1 KiB  │",
-                "└──────────────────────────────────────────────────────────┘",
-        ]);
-        set_cells! { expected =>
-            // selected item
-            (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+        for c in [' ', ' ', 'S', 'j', 'j'] {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
         }
+        let ViewState::StorageClassDialog(indices, storage_class) = &page.view_state else {
+            panic!("storage class dialog is not opened");
+        };
+        assert_eq!(indices, &vec![0, 1]);
+        assert_eq!(*storage_class, StorageClassType::StandardIa);
+
+        let lines = build_storage_class_dialog_lines(&[1024, 1024 * 1024], storage_class);
+        assert_eq!(
+            lines,
+            vec![
+                "Change storage class of 2 objects?",
+                "  Storage class: STANDARD_IA (j/k to change)",
+                "  Total size: 1.00 MiB",
+                "  Billable size: 1.12 MiB (minimum 128 KiB per object)",
+                "  Minimum storage duration: 30 days",
+            ]
+        );
 
-        terminal.backend().assert_buffer(&expected);
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartChangeStorageClass(bucket, keys, storage_class) => {
+                Some((bucket, keys, storage_class))
+            }
+            _ => None,
+        });
+        let (bucket, keys, storage_class) = event.unwrap();
+        assert_eq!(bucket, "test-bucket");
+        assert_eq!(keys, vec!["path/file1", "path/file2"]);
+        assert_eq!(storage_class, StorageClassType::StandardIa);
 
         Ok(())
     }
 
     #[test]
-    fn test_render_with_config() -> std::io::Result<()> {
-        let (tx, _) = event::new();
+    fn test_copy_to() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        let items = vec![
+            object_dir_item("dir1"),
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
+
+        // not a file
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        for c in ['j', 'y'] {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        let ViewState::CopyToDialog(_, state) = &mut page.view_state else {
+            panic!("copy to dialog is not opened");
+        };
+        assert_eq!(state.input(), "s3://test-bucket/path/file1");
+
+        // the same key as the source
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::CopyToDialog(_, _)));
+
+        let ViewState::CopyToDialog(_, state) = &mut page.view_state else {
+            panic!("copy to dialog is not opened");
+        };
+        state.set_input("s3://other-bucket/backup/");
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartCopyObject(src_key, dst_key) => Some((src_key, dst_key)),
+            _ => None,
+        });
+        let (src_key, dst_key) = event.unwrap();
+        assert_eq!(src_key.bucket_name, "test-bucket");
+        assert_eq!(src_key.joined_object_path(true), "path/file1");
+        assert_eq!(dst_key.bucket_name, "other-bucket");
+        assert_eq!(dst_key.joined_object_path(true), "backup/file1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![object_file_item("file1", 1024, "2024-01-02 13:01:02")];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('c')));
+        assert!(matches!(page.view_state, ViewState::CompareDialog(_)));
+        for c in "s3://other-bucket/path/to".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::StartCompareObjects(a_key, b_key) => Some((a_key, b_key)),
+            _ => None,
+        });
+        let (a_key, b_key) = event.unwrap();
+        assert_eq!(a_key.joined_object_path(false), "path/");
+        assert_eq!(b_key.bucket_name, "other-bucket");
+        assert_eq!(b_key.joined_object_path(false), "path/to/");
+    }
+
+    #[test]
+    fn test_create_folder() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![
+            object_dir_item("dir1"),
+            object_dir_item("dir3"),
+            object_file_item("file1", 1024, "2024-01-02 13:01:02"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec!["path".to_string()],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        page.handle_key(KeyEvent::from(KeyCode::Char('G')));
+        page.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('N')));
+        assert!(matches!(page.view_state, ViewState::NewFolderDialog(_)));
+        for c in "dir2/".chars() {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+
+        let event = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::CreateFolder(object_key, name) => Some((object_key, name)),
+            _ => None,
+        });
+        let (object_key, name) = event.unwrap();
+        assert_eq!(object_key.joined_object_path(false), "path/");
+        assert_eq!(name, "dir2");
+
+        page.add_dir_item(object_dir_item("dir2"));
+        let names: Vec<String> = page
+            .object_list()
+            .iter()
+            .map(|item| item.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["dir1", "dir2", "dir3", "file1"]);
+        assert_eq!(page.current_selected_item().unwrap().name(), "dir2");
+        // the marked file is still marked after its index moved
+        assert_eq!(page.marked, HashSet::from([3]));
+    }
+
+    #[test]
+    fn test_render_with_changes() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
         let mut terminal = setup_terminal()?;
 
         terminal.draw(|f| {
             let items = vec![
                 object_dir_item("dir1"),
-                object_dir_item("dir2"),
                 object_file_item("file1", 1024 + 10, "2024-01-02 13:01:02"),
                 object_file_item("file2", 1024 * 999, "2023-12-31 09:00:00"),
             ];
@@ -772,21 +3514,22 @@ mod tests {
                 bucket_name: "test-bucket".to_string(),
                 object_path: vec!["path".to_string(), "to".to_string()],
             };
-            let mut ctx = AppContext::default();
-            ctx.config.ui.object_list.date_format = "%Y/%m/%d".to_string();
-            ctx.config.ui.object_list.date_width = 10;
-            let mut page = ObjectListPage::new(items, object_key, Rc::new(ctx), tx);
+            let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+            page.set_object_changes(HashMap::from([
+                (1, ObjectChange::New),
+                (2, ObjectChange::Modified),
+            ]));
             let area = Rect::new(0, 0, 60, 10);
             page.render(f, area);
         })?;
 
         #[rustfmt::skip]
         let mut expected = Buffer::with_lines([
-            "┌─────────────────────────────────────────────────── 1 / 4 ┐",
+            "┌─────────────────────────────────────────────────── 1 / 3 ┐",
             "│  dir1/                                                   │",
-            "│  dir2/                                                   │",
-            "│  file1                         2024/01/02      1.01 KiB  │",
-            "│  file2                         2023/12/31       999 KiB  │",
+            "│ +file1                2024-01-02 13:01:02      1.01 KiB  │",
+            "│ ~file2                2023-12-31 09:00:00       999 KiB  │",
+            "│                                                          │",
             "│                                                          │",
             "│                                                          │",
             "│                                                          │",
@@ -795,9 +3538,11 @@ mod tests {
         ]);
         set_cells! { expected =>
             // dir items
-            (3..8, [1, 2]) => modifier: Modifier::BOLD,
+            (3..8, [1]) => modifier: Modifier::BOLD,
             // selected item
             (2..58, [1]) => bg: Color::Cyan, fg: Color::Black,
+            // change indicators
+            ([2], [2, 3]) => fg: Color::Green,
         }
 
         terminal.backend().assert_buffer(&expected);
@@ -806,46 +3551,133 @@ mod tests {
     }
 
     #[test]
-    fn test_sort_items() {
+    fn test_group_items() -> std::io::Result<()> {
         let ctx = Rc::default();
-        let (tx, _) = event::new();
+        let (tx, _rx) = event::new();
+        let mut terminal = setup_terminal()?;
+
         let items = vec![
-            object_dir_item("rid"),
-            object_file_item("file", 1024, "2024-01-02 13:01:02"),
-            object_dir_item("dir"),
-            object_file_item("xyz", 1024 * 1024, "2023-12-31 23:59:59"),
-            object_file_item("abc", 0, "-2000-01-01 00:00:00"),
+            object_file_item("a.txt", 1024, "2024-01-02 13:01:02"),
+            object_dir_item("dir1"),
+            object_file_item("README", 1024, "2024-01-02 13:01:02"),
+            object_file_item("c.txt", 1024, "2024-01-02 13:01:02"),
         ];
         let object_key = ObjectKey {
             bucket_name: "test-bucket".to_string(),
             object_path: vec!["path".to_string(), "to".to_string()],
         };
         let mut page = ObjectListPage::new(items, object_key, ctx, tx);
+        let area = Rect::new(0, 0, 60, 10);
+        terminal.draw(|f| page.render(f, area))?;
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('o')));
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select NameAsc
-
-        assert_eq!(page.view_indices, vec![4, 2, 1, 0, 3]);
+        page.handle_key(KeyEvent::from(KeyCode::Char('v'))); // group by extension
+        terminal.draw(|f| page.render(f, area))?;
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select NameDesc
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌─────────────────────────────────────────────────── 4 / 7 ┐",
+            "│  ▾ Folders (1)                                           │",
+            "│  dir1/                                                   │",
+            "│  ▾ *.txt (2)                                             │",
+            "│  a.txt                2024-01-02 13:01:02         1 KiB  │",
+            "│  c.txt                2024-01-02 13:01:02         1 KiB  │",
+            "│  ▾ No extension (1)                                      │",
+            "│  README               2024-01-02 13:01:02         1 KiB  │",
+            "│                                                          │",
+            "└──────────────────────────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            // group headers
+            (5..12, [1]) => modifier: Modifier::BOLD,
+            (5..10, [3]) => modifier: Modifier::BOLD,
+            (5..17, [6]) => modifier: Modifier::BOLD,
+            // dir items
+            (3..8, [2]) => modifier: Modifier::BOLD,
+            // selected item
+            (2..58, [4]) => bg: Color::Cyan, fg: Color::Black,
+        }
 
-        assert_eq!(page.view_indices, vec![3, 0, 1, 2, 4]);
+        terminal.backend().assert_buffer(&expected);
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select LastModifiedAsc
+        // Enter on a group header collapses the group instead of opening it
+        page.handle_key(KeyEvent::from(KeyCode::Char('k')));
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            page.object_list()
+                .iter()
+                .map(|item| item.name())
+                .collect::<Vec<_>>(),
+            vec!["dir1", "README"]
+        );
+        assert!(!page.is_item_selected());
 
-        assert_eq!(page.view_indices, vec![0, 2, 4, 3, 1]);
+        // selecting a hidden item expands its group
+        page.select_item("c.txt", true);
+        assert_eq!(page.current_selected_item().unwrap().name(), "c.txt");
+        assert_eq!(page.list_state().selected, 2);
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select LastModifiedDesc
+        // back to the flat list
+        for _ in 0..3 {
+            page.handle_key(KeyEvent::from(KeyCode::Char('v')));
+        }
+        assert_eq!(page.current_selected_item().unwrap().name(), "c.txt");
+        assert_eq!(page.view_rows.len(), 4);
 
-        assert_eq!(page.view_indices, vec![1, 3, 4, 0, 2]);
+        Ok(())
+    }
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select SizeAsc
+    #[test]
+    fn test_keys_on_group_header() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let items = vec![
+            object_dir_item("dir1"),
+            object_file_item("a.txt", 1024, "2024-01-02 13:01:02"),
+        ];
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![],
+        };
+        let mut page = ObjectListPage::new(items, object_key, ctx, tx);
 
-        assert_eq!(page.view_indices, vec![0, 2, 4, 1, 3]);
+        page.handle_key(KeyEvent::from(KeyCode::Char('v'))); // group by extension
+        page.handle_key(KeyEvent::from(KeyCode::Char('g')));
+        assert!(page.current_selected_item().is_none());
+        assert!(page.current_selected_object_key().is_none());
 
-        page.handle_key(KeyEvent::from(KeyCode::Char('j'))); // select SizeDesc
+        // nothing is opened from a group header
+        for c in ['s', 'r', 'y'] {
+            page.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        page.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(page.view_state, ViewState::Default));
+        assert!(std::iter::from_fn(|| rx.try_recv()).all(|e| !matches!(
+            e,
+            AppEventType::ObjectListMoveDown | AppEventType::StartLoadAllDownloadObjectList(..)
+        )));
+
+        // the group is collapsed instead
+        assert_eq!(
+            page.object_list()
+                .iter()
+                .map(|item| item.name())
+                .collect::<Vec<_>>(),
+            vec!["a.txt"]
+        );
+    }
 
-        assert_eq!(page.view_indices, vec![3, 1, 4, 0, 2]);
+    #[test]
+    fn test_group_key_last_modified() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let label = |last_modified: &str| {
+            let item = object_file_item("a", 0, last_modified);
+            group_key(&item, ObjectListGroupType::LastModified, today).1
+        };
+        assert_eq!(label("2024-01-31 00:00:00"), "Today");
+        assert_eq!(label("2024-01-30 23:59:59"), "Yesterday");
+        assert_eq!(label("2024-01-25 00:00:00"), "Last 7 days");
+        assert_eq!(label("2024-01-02 00:00:00"), "Last 30 days");
+        assert_eq!(label("2023-12-31 00:00:00"), "Older");
     }
 
     fn setup_terminal() -> std::io::Result<Terminal<TestBackend>> {
@@ -867,7 +3699,8 @@ mod tests {
             name: name.to_string(),
             key: "".to_string(),
             s3_uri: "".to_string(),
-            object_url: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
         }
     }
 
@@ -879,7 +3712,8 @@ mod tests {
             key: "".to_string(),
             s3_uri: "".to_string(),
             arn: "".to_string(),
-            object_url: "".to_string(),
+            virtual_hosted_url: "".to_string(),
+            path_style_url: "".to_string(),
             e_tag: "".to_string(),
         }
     }
@@ -0,0 +1,253 @@
+use std::{path::PathBuf, rc::Rc};
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    widgets::Block,
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    event::{AppEventType, Sender},
+    object::{FileDetail, RawObject},
+    pages::util::{build_helps, build_short_helps},
+    widget::{ScrollLines, TextPreviewState},
+};
+
+#[derive(Debug)]
+pub struct ObjectLocalComparePage {
+    file_detail: FileDetail,
+    local_path: PathBuf,
+
+    // object on the left, local file on the right
+    object_state: TextPreviewState,
+    local_state: TextPreviewState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+impl ObjectLocalComparePage {
+    pub fn new(
+        file_detail: FileDetail,
+        object: RawObject,
+        local_path: PathBuf,
+        local_object: RawObject,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let (object_state, local_state, msg) = TextPreviewState::new_diff_pair(
+            &file_detail.name,
+            &object,
+            &local_object,
+            ctx.config.preview.highlight,
+            &ctx.config.preview.highlight_theme,
+            &ctx.theme,
+        );
+        if let Some(msg) = msg {
+            tx.send(AppEventType::NotifyWarn(msg));
+        }
+
+        Self {
+            file_detail,
+            local_path,
+            object_state,
+            local_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('j') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_forward());
+            }
+            key_code_char!('k') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_backward());
+            }
+            key_code_char!('f') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_page_forward());
+            }
+            key_code_char!('b') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_page_backward());
+            }
+            key_code_char!('g') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_to_top());
+            }
+            key_code_char!('G') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_to_end());
+            }
+            key_code_char!('h') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_left());
+            }
+            key_code_char!('l') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_right());
+            }
+            key_code_char!('n') => {
+                self.for_each_state(|state| state.scroll_lines_state.toggle_number());
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::horizontal(Constraint::from_percentages([50, 50])).split(area);
+
+        let object_title = format!("Preview [{}]", self.file_detail.name);
+        let object_preview = ScrollLines::default()
+            .block(Block::bordered().title(object_title))
+            .theme(&self.ctx.theme);
+        f.render_stateful_widget(
+            object_preview,
+            chunks[0],
+            &mut self.object_state.scroll_lines_state,
+        );
+
+        let local_title = format!("Local [{}]", self.local_path.display());
+        let local_preview = ScrollLines::default()
+            .block(Block::bordered().title(local_title))
+            .theme(&self.ctx.theme);
+        f.render_stateful_widget(
+            local_preview,
+            chunks[1],
+            &mut self.local_state.scroll_lines_state,
+        );
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["j/k"], "Scroll forward/backward"),
+            (&["f/b"], "Scroll page forward/backward"),
+            (&["g/G"], "Scroll to top/end"),
+            (&["h/l"], "Scroll left/right"),
+            (&["n"], "Toggle number"),
+            (&["Backspace"], "Close comparison"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["j/k"], "Scroll", 2),
+            (&["g/G"], "Top/End", 3),
+            (&["Backspace"], "Close", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+}
+
+impl ObjectLocalComparePage {
+    // scroll both sides together, they are aligned line by line
+    fn for_each_state(&mut self, f: impl Fn(&mut TextPreviewState)) {
+        f(&mut self.object_state);
+        f(&mut self.local_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event, set_cells};
+
+    use super::*;
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+
+    fn object(ss: &[&str]) -> RawObject {
+        RawObject {
+            bytes: ss.join("\n").as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_render_aligned() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend)?;
+
+        let object = object(&["foo", "bar", "baz", "qux"]);
+        let local_object = object_local();
+        let mut page = ObjectLocalComparePage::new(
+            file_detail(),
+            object,
+            PathBuf::from("a.txt"),
+            local_object,
+            ctx,
+            tx,
+        );
+
+        let area = Rect::new(0, 0, 60, 6);
+        terminal.draw(|f| {
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌Preview [a.txt]─────────────┐┌Local [a.txt]───────────────┐",
+            "│ 1 foo                      ││ 1 foo                      │",
+            "│ 2 bar                      ││ 2 BAZ                      │",
+            "│ 3 baz                      ││                            │",
+            "│ 4 qux                      ││ 3 qux                      │",
+            "└────────────────────────────┘└────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            ([2], 1..5) => fg: Color::DarkGray,
+            ([32], [1, 2, 4]) => fg: Color::DarkGray,
+            (4..7, [2, 3]) => bg: Color::Indexed(52),
+            (34..37, [2]) => bg: Color::Indexed(22),
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    fn object_local() -> RawObject {
+        object(&["foo", "BAZ", "qux"])
+    }
+
+    fn file_detail() -> FileDetail {
+        FileDetail {
+            name: "a.txt".to_string(),
+            size_byte: 1024,
+            last_modified: parse_datetime("2024-01-02 13:01:02"),
+            e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
+            content_type: "text/plain".to_string(),
+            storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
+            key: "a.txt".to_string(),
+            s3_uri: "s3://bucket-1/a.txt".to_string(),
+            arn: "arn:aws:s3:::bucket-1/a.txt".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/a.txt"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/a.txt".to_string(),
+        }
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+}
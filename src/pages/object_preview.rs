@@ -1,9 +1,9 @@
-use std::rc::Rc;
+use std::{path::Path, rc::Rc};
 
 use laurier::{key_code, key_code_char};
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent},
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     Frame,
 };
 
@@ -14,22 +14,35 @@ use crate::{
     object::{FileDetail, ObjectKey, RawObject},
     pages::util::{build_helps, build_short_helps},
     widget::{
-        self, ImagePreview, ImagePreviewState, InputDialog, InputDialogState, TextPreview,
-        TextPreviewState,
+        self, build_highlighted_html, is_binary, ImagePreview, ImagePreviewState, InputDialog,
+        InputDialogState, MagicInfo, MagicPanel, ScrollLinesOptions, TextPreview, TextPreviewState,
+        TextStats, TextStatsDialog,
     },
 };
 
+const NOT_OUTLINED_MSG: &str = "Only YAML, TOML and INI files have sections and key paths";
+
 #[derive(Debug)]
 pub struct ObjectPreviewPage {
     preview_type: PreviewType,
 
     file_detail: FileDetail,
     file_version_id: Option<String>,
+    // only the beginning of a large object is loaded at first, see `preview.range_size_mib`
     object: RawObject,
+    partial: bool,
+    loading_more: bool,
+    // shown from the objects previewed recently, without downloading
+    cached: bool,
     path: String,
     object_key: ObjectKey,
 
     view_state: ViewState,
+    // the raw bytes of an image, built when it is shown for the first time
+    hexdump: Option<TextPreviewState>,
+    show_hexdump: bool,
+    // what the magic number tells, shown with the hexdump
+    magic: Option<MagicInfo>,
 
     ctx: Rc<AppContext>,
     tx: Sender,
@@ -39,6 +52,8 @@ pub struct ObjectPreviewPage {
 enum PreviewType {
     Text(TextPreviewState),
     Image(ImagePreviewState),
+    // the hexdump of the object which is neither a text nor an image
+    Binary(TextPreviewState),
 }
 
 #[derive(Debug, Default)]
@@ -46,6 +61,8 @@ enum ViewState {
     #[default]
     Default,
     SaveDialog(InputDialogState),
+    CompareLocalDialog(InputDialogState),
+    StatsDialog(TextStats),
 }
 
 impl ObjectPreviewPage {
@@ -60,32 +77,46 @@ impl ObjectPreviewPage {
     ) -> Self {
         let preview_type = if infer::is_image(&object.bytes) {
             let (state, msg) =
-                ImagePreviewState::new(&object.bytes, ctx.env.image_picker.clone().into());
+                ImagePreviewState::new(&object.bytes, ctx.env.image_picker().clone().into());
             if let Some(msg) = msg {
                 tx.send(AppEventType::NotifyWarn(msg));
             }
             PreviewType::Image(state)
+        } else if is_binary(&object.bytes) {
+            PreviewType::Binary(TextPreviewState::new_hexdump(&object))
         } else {
             let (state, msg) = TextPreviewState::new(
                 &file_detail,
                 &object,
                 ctx.config.preview.highlight,
                 &ctx.config.preview.highlight_theme,
+                ScrollLinesOptions::default().wrap_options(
+                    ctx.config.preview.word_wrap,
+                    ctx.config.preview.hanging_indent,
+                ),
             );
             if let Some(msg) = msg {
                 tx.send(AppEventType::NotifyWarn(msg));
             }
             PreviewType::Text(state)
         };
+        let magic = matches!(preview_type, PreviewType::Binary(_))
+            .then(|| MagicInfo::new(&object.bytes, &file_detail.name, &file_detail.content_type));
 
         Self {
             preview_type,
             object,
+            partial: false,
+            loading_more: false,
+            cached: false,
             file_detail,
             file_version_id,
             path,
             object_key,
             view_state: ViewState::Default,
+            hexdump: None,
+            show_hexdump: false,
+            magic,
             ctx,
             tx,
         }
@@ -136,6 +167,111 @@ impl ObjectPreviewPage {
                 key_code_char!('S') => {
                     self.open_save_dialog();
                 }
+                key_code_char!('E') => {
+                    self.export_html();
+                }
+                key_code_char!('D') => {
+                    self.open_compare_local_dialog();
+                }
+                key_code_char!('R') => {
+                    self.tx.send(AppEventType::PreviewRefresh);
+                }
+                key_code_char!('i') => {
+                    self.open_stats_dialog();
+                }
+                key_code_char!('z') => {
+                    self.toggle_fold();
+                }
+                key_code_char!('Z') => {
+                    state.unfold_all();
+                }
+                key_code_char!('y') => {
+                    self.copy_key_path();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
+            (ViewState::Default, PreviewType::Image(_)) if self.show_hexdump => {
+                let state = self.hexdump.as_mut().unwrap();
+                match key {
+                    key_code!(KeyCode::Esc) => {
+                        self.tx.send(AppEventType::Quit);
+                    }
+                    key_code!(KeyCode::Backspace) => {
+                        self.tx.send(AppEventType::CloseCurrentPage);
+                    }
+                    key_code_char!('j') => {
+                        state.scroll_lines_state.scroll_forward();
+                    }
+                    key_code_char!('k') => {
+                        state.scroll_lines_state.scroll_backward();
+                    }
+                    key_code_char!('f') => {
+                        state.scroll_lines_state.scroll_page_forward();
+                    }
+                    key_code_char!('b') => {
+                        state.scroll_lines_state.scroll_page_backward();
+                    }
+                    key_code_char!('g') => {
+                        state.scroll_lines_state.scroll_to_top();
+                    }
+                    key_code_char!('G') => {
+                        state.scroll_lines_state.scroll_to_end();
+                    }
+                    key_code_char!('x') => {
+                        self.toggle_hexdump();
+                    }
+                    key_code_char!('R') => {
+                        self.tx.send(AppEventType::PreviewRefresh);
+                    }
+                    key_code_char!('s') => {
+                        self.download();
+                    }
+                    key_code_char!('S') => {
+                        self.open_save_dialog();
+                    }
+                    key_code_char!('?') => {
+                        self.tx.send(AppEventType::OpenHelp);
+                    }
+                    _ => {}
+                }
+            }
+            (ViewState::Default, PreviewType::Binary(state)) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.tx.send(AppEventType::Quit);
+                }
+                key_code!(KeyCode::Backspace) => {
+                    self.tx.send(AppEventType::CloseCurrentPage);
+                }
+                key_code_char!('j') => {
+                    state.scroll_lines_state.scroll_forward();
+                }
+                key_code_char!('k') => {
+                    state.scroll_lines_state.scroll_backward();
+                }
+                key_code_char!('f') => {
+                    state.scroll_lines_state.scroll_page_forward();
+                }
+                key_code_char!('b') => {
+                    state.scroll_lines_state.scroll_page_backward();
+                }
+                key_code_char!('g') => {
+                    state.scroll_lines_state.scroll_to_top();
+                }
+                key_code_char!('G') => {
+                    state.scroll_lines_state.scroll_to_end();
+                }
+                key_code_char!('R') => {
+                    self.tx.send(AppEventType::PreviewRefresh);
+                }
+                key_code_char!('s') => {
+                    self.download();
+                }
+                key_code_char!('S') => {
+                    self.open_save_dialog();
+                }
                 key_code_char!('?') => {
                     self.tx.send(AppEventType::OpenHelp);
                 }
@@ -155,6 +291,12 @@ impl ObjectPreviewPage {
                     self.open_save_dialog();
                     self.disable_image_render();
                 }
+                key_code_char!('x') => {
+                    self.toggle_hexdump();
+                }
+                key_code_char!('R') => {
+                    self.tx.send(AppEventType::PreviewRefresh);
+                }
                 key_code_char!('?') => {
                     self.tx.send(AppEventType::OpenHelp);
                 }
@@ -177,6 +319,30 @@ impl ObjectPreviewPage {
                     state.handle_key_event(key);
                 }
             },
+            (ViewState::CompareLocalDialog(state), _) => match key {
+                key_code!(KeyCode::Esc) => {
+                    self.close_compare_local_dialog();
+                }
+                key_code!(KeyCode::Enter) => {
+                    let input = state.input().into();
+                    self.compare_local(input);
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {
+                    state.handle_key_event(key);
+                }
+            },
+            (ViewState::StatsDialog(_), _) => match key {
+                key_code!(KeyCode::Esc) | key_code!(KeyCode::Backspace) | key_code_char!('i') => {
+                    self.close_stats_dialog();
+                }
+                key_code_char!('?') => {
+                    self.tx.send(AppEventType::OpenHelp);
+                }
+                _ => {}
+            },
         }
     }
 
@@ -187,14 +353,60 @@ impl ObjectPreviewPage {
                     self.file_detail.name.as_str(),
                     self.file_version_id.as_deref(),
                     &self.ctx.theme,
-                );
+                )
+                .partial(
+                    self.partial,
+                    self.object.bytes.len(),
+                    self.file_detail.size_byte,
+                )
+                .cached(self.cached);
                 f.render_stateful_widget(preview, area, state);
+
+                // load the rest before the end is reached
+                let near_end =
+                    state.scroll_lines_state.remaining_lines() <= area.height as usize * 2;
+                if self.partial && near_end && !self.loading_more {
+                    self.loading_more = true;
+                    self.tx.send(AppEventType::LoadMorePreview);
+                }
+            }
+            PreviewType::Binary(ref mut state) => {
+                let preview = TextPreview::new(
+                    self.file_detail.name.as_str(),
+                    self.file_version_id.as_deref(),
+                    &self.ctx.theme,
+                )
+                .partial(
+                    self.partial,
+                    self.object.bytes.len(),
+                    self.file_detail.size_byte,
+                )
+                .cached(self.cached);
+                render_hexdump(f, area, preview, state, self.magic.as_ref(), &self.ctx);
+
+                let near_end =
+                    state.scroll_lines_state.remaining_lines() <= area.height as usize * 2;
+                if self.partial && near_end && !self.loading_more {
+                    self.loading_more = true;
+                    self.tx.send(AppEventType::LoadMorePreview);
+                }
+            }
+            PreviewType::Image(_) if self.show_hexdump => {
+                let state = self.hexdump.as_mut().unwrap();
+                let preview = TextPreview::new(
+                    self.file_detail.name.as_str(),
+                    self.file_version_id.as_deref(),
+                    &self.ctx.theme,
+                )
+                .cached(self.cached);
+                render_hexdump(f, area, preview, state, self.magic.as_ref(), &self.ctx);
             }
             PreviewType::Image(ref mut state) => {
                 let preview = ImagePreview::new(
                     self.file_detail.name.as_str(),
                     self.file_version_id.as_deref(),
-                );
+                )
+                .cached(self.cached);
                 f.render_stateful_widget(preview, area, state);
             }
         }
@@ -209,6 +421,22 @@ impl ObjectPreviewPage {
             let (cursor_x, cursor_y) = state.cursor();
             f.set_cursor_position((cursor_x, cursor_y));
         }
+
+        if let ViewState::CompareLocalDialog(state) = &mut self.view_state {
+            let compare_dialog = InputDialog::default()
+                .title("Compare with local file")
+                .max_width(60)
+                .theme(&self.ctx.theme);
+            f.render_stateful_widget(compare_dialog, area, state);
+
+            let (cursor_x, cursor_y) = state.cursor();
+            f.set_cursor_position((cursor_x, cursor_y));
+        }
+
+        if let ViewState::StatsDialog(stats) = &self.view_state {
+            let stats_dialog = TextStatsDialog::new(stats, self.partial).theme(&self.ctx.theme);
+            f.render_widget(stats_dialog, area);
+        }
     }
 
     pub fn helps(&self) -> Vec<String> {
@@ -224,18 +452,56 @@ impl ObjectPreviewPage {
                 (&["Backspace"], "Close preview"),
                 (&["s"], "Download object"),
                 (&["S"], "Download object as"),
+                (&["E"], "Export preview as HTML"),
+                (&["D"], "Compare with local file"),
+                (&["R"], "Reload if changed"),
+                (&["i"], "Show text stats"),
+                (&["z/Z"], "Toggle fold/Unfold all (YAML/TOML/INI)"),
+                (&["y"], "Copy key path (YAML/TOML/INI)"),
+            ],
+            (ViewState::Default, PreviewType::Image(_)) if self.show_hexdump => &[
+                (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["j/k"], "Scroll forward/backward"),
+                (&["f/b"], "Scroll page forward/backward"),
+                (&["g/G"], "Scroll to top/end"),
+                (&["x"], "Show image"),
+                (&["Backspace"], "Close preview"),
+                (&["s"], "Download object"),
+                (&["S"], "Download object as"),
+                (&["R"], "Reload if changed"),
+            ],
+            (ViewState::Default, PreviewType::Binary(_)) => &[
+                (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["j/k"], "Scroll forward/backward"),
+                (&["f/b"], "Scroll page forward/backward"),
+                (&["g/G"], "Scroll to top/end"),
+                (&["Backspace"], "Close preview"),
+                (&["s"], "Download object"),
+                (&["S"], "Download object as"),
+                (&["R"], "Reload if changed"),
             ],
             (ViewState::Default, PreviewType::Image(_)) => &[
                 (&["Esc", "Ctrl-c"], "Quit app"),
+                (&["x"], "Show hexdump"),
                 (&["Backspace"], "Close preview"),
                 (&["s"], "Download object"),
                 (&["S"], "Download object as"),
+                (&["R"], "Reload if changed"),
             ],
             (ViewState::SaveDialog(_), _) => &[
                 (&["Ctrl-c"], "Quit app"),
                 (&["Esc"], "Close save dialog"),
                 (&["Enter"], "Download object"),
             ],
+            (ViewState::CompareLocalDialog(_), _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc"], "Close compare dialog"),
+                (&["Enter"], "Compare with local file"),
+            ],
+            (ViewState::StatsDialog(_), _) => &[
+                (&["Ctrl-c"], "Quit app"),
+                (&["Esc", "Backspace", "i"], "Close stats dialog"),
+            ],
         };
 
         build_helps(helps)
@@ -251,8 +517,25 @@ impl ObjectPreviewPage {
                 (&["Backspace"], "Close", 1),
                 (&["?"], "Help", 0),
             ],
+            (ViewState::Default, PreviewType::Image(_)) if self.show_hexdump => &[
+                (&["Esc"], "Quit", 0),
+                (&["j/k"], "Scroll", 3),
+                (&["x"], "Image", 2),
+                (&["s/S"], "Download", 4),
+                (&["Backspace"], "Close", 1),
+                (&["?"], "Help", 0),
+            ],
+            (ViewState::Default, PreviewType::Binary(_)) => &[
+                (&["Esc"], "Quit", 0),
+                (&["j/k"], "Scroll", 2),
+                (&["g/G"], "Top/End", 4),
+                (&["s/S"], "Download", 3),
+                (&["Backspace"], "Close", 1),
+                (&["?"], "Help", 0),
+            ],
             (ViewState::Default, PreviewType::Image(_)) => &[
                 (&["Esc"], "Quit", 0),
+                (&["x"], "Hexdump", 3),
                 (&["s/S"], "Download", 2),
                 (&["Backspace"], "Close", 1),
                 (&["?"], "Help", 0),
@@ -262,6 +545,12 @@ impl ObjectPreviewPage {
                 (&["Enter"], "Download", 1),
                 (&["?"], "Help", 0),
             ],
+            (ViewState::CompareLocalDialog(_), _) => &[
+                (&["Esc"], "Close", 2),
+                (&["Enter"], "Compare", 1),
+                (&["?"], "Help", 0),
+            ],
+            (ViewState::StatsDialog(_), _) => &[(&["Esc"], "Close", 1), (&["?"], "Help", 0)],
         };
 
         build_short_helps(helps)
@@ -277,6 +566,50 @@ impl ObjectPreviewPage {
         self.view_state = ViewState::Default;
     }
 
+    fn open_compare_local_dialog(&mut self) {
+        self.view_state = ViewState::CompareLocalDialog(InputDialogState::default());
+    }
+
+    fn close_compare_local_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    // counted from the loaded bytes without downloading again
+    fn open_stats_dialog(&mut self) {
+        self.view_state = ViewState::StatsDialog(TextStats::new(&self.object.bytes));
+    }
+
+    fn close_stats_dialog(&mut self) {
+        self.view_state = ViewState::Default;
+    }
+
+    fn toggle_fold(&mut self) {
+        if let PreviewType::Text(state) = &mut self.preview_type {
+            if !state.is_foldable() {
+                self.tx
+                    .send(AppEventType::NotifyWarn(NOT_OUTLINED_MSG.into()));
+            } else if !state.toggle_fold() {
+                let msg = "No section to fold at the top line".to_string();
+                self.tx.send(AppEventType::NotifyWarn(msg));
+            }
+        }
+    }
+
+    fn copy_key_path(&self) {
+        if let PreviewType::Text(state) = &self.preview_type {
+            if !state.is_foldable() {
+                self.tx
+                    .send(AppEventType::NotifyWarn(NOT_OUTLINED_MSG.into()));
+            } else if let Some(path) = state.current_key_path() {
+                let (name, value) = ("key path".to_string(), path.to_string());
+                self.tx.send(AppEventType::CopyToClipboard(name, value));
+            } else {
+                let msg = "No key at the top line".to_string();
+                self.tx.send(AppEventType::NotifyWarn(msg));
+            }
+        }
+    }
+
     pub fn enable_image_render(&mut self) {
         if let PreviewType::Image(state) = &mut self.preview_type {
             state.set_render(true);
@@ -289,11 +622,39 @@ impl ObjectPreviewPage {
         }
     }
 
+    // Switches between the image and the hexdump of the same bytes, e.g. for a corrupt image
+    fn toggle_hexdump(&mut self) {
+        self.show_hexdump = !self.show_hexdump;
+        if self.show_hexdump {
+            if self.hexdump.is_none() {
+                self.hexdump = Some(TextPreviewState::new_hexdump(&self.object));
+                self.magic = Some(MagicInfo::new(
+                    &self.object.bytes,
+                    &self.file_detail.name,
+                    &self.file_detail.content_type,
+                ));
+            }
+            self.disable_image_render();
+        } else {
+            self.enable_image_render();
+        }
+    }
+
     pub fn is_image_preview(&self) -> bool {
         matches!(self.preview_type, PreviewType::Image(_))
     }
 
     fn download(&self) {
+        if self.partial {
+            // the whole object is downloaded with the same file name
+            let file_name = Path::new(&self.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into())
+                .unwrap_or_default();
+            self.download_as(file_name);
+            return;
+        }
+
         // object has been already downloaded, so send completion event to save file
         let obj = self.object.clone();
         let path = self.path.clone();
@@ -315,9 +676,129 @@ impl ObjectPreviewPage {
         ));
     }
 
+    fn compare_local(&mut self, input: String) {
+        let input: String = input.trim().into();
+        if input.is_empty() {
+            return;
+        }
+        self.close_compare_local_dialog();
+        if self.warn_if_partial() {
+            return;
+        }
+
+        let file_detail = self.file_detail.clone();
+        let obj = self.object.clone();
+        self.tx.send(AppEventType::PreviewCompareLocalFile(
+            file_detail,
+            obj,
+            input,
+        ));
+    }
+
+    fn export_html(&self) {
+        if self.warn_if_partial() {
+            return;
+        }
+        let html = build_highlighted_html(
+            &self.object,
+            &self.file_detail.name,
+            &self.ctx.config.preview.highlight_theme,
+        );
+        match html {
+            Ok(html) => {
+                let file_name = format!("{}.html", self.file_detail.name);
+                let path = self.ctx.config.download_file_path(&file_name);
+                self.tx.send(AppEventType::PreviewExportHtml(html, path));
+            }
+            Err(e) => {
+                self.tx.send(AppEventType::NotifyError(e));
+            }
+        }
+    }
+
     pub fn current_object_key(&self) -> &ObjectKey {
         &self.object_key
     }
+
+    pub fn file_detail(&self) -> &FileDetail {
+        &self.file_detail
+    }
+
+    pub fn file_version_id(&self) -> &Option<String> {
+        &self.file_version_id
+    }
+
+    pub fn loaded_size(&self) -> usize {
+        self.object.bytes.len()
+    }
+
+    pub fn set_cached(&mut self) {
+        self.cached = true;
+    }
+
+    // The object has been loaded only up to `preview.range_size_mib`
+    pub fn set_ranged(&mut self) {
+        self.partial = self.has_more(self.object.bytes.len());
+    }
+
+    // A range shorter than requested is the end of the object.
+    // The size of the detail is not of the selected version, so it is not compared for versions.
+    fn has_more(&self, last_range_size: usize) -> bool {
+        last_range_size >= self.ctx.config.preview.range_size_byte()
+            && (self.file_version_id.is_some() || self.loaded_size() < self.file_detail.size_byte)
+    }
+
+    fn warn_if_partial(&self) -> bool {
+        if self.partial {
+            let msg = "The whole object has not been loaded yet".to_string();
+            self.tx.send(AppEventType::NotifyWarn(msg));
+        }
+        self.partial
+    }
+
+    pub fn append_object(&mut self, object: RawObject) {
+        let range_size = object.bytes.len();
+        self.object.bytes.extend(object.bytes);
+        self.partial = self.has_more(range_size);
+        match &mut self.preview_type {
+            PreviewType::Text(state) => {
+                state.set_object(
+                    &self.file_detail,
+                    &self.object,
+                    self.ctx.config.preview.highlight,
+                    &self.ctx.config.preview.highlight_theme,
+                );
+            }
+            PreviewType::Binary(state) => {
+                state.set_hexdump_object(&self.object);
+            }
+            PreviewType::Image(_) => {}
+        }
+        self.loading_more = false;
+    }
+}
+
+// The panel of the file type is shown above the hexdump if there is enough space
+fn render_hexdump(
+    f: &mut Frame,
+    area: Rect,
+    preview: TextPreview,
+    state: &mut TextPreviewState,
+    magic: Option<&MagicInfo>,
+    ctx: &AppContext,
+) {
+    let panel_height = magic.map(MagicInfo::height).unwrap_or_default();
+    match magic {
+        Some(magic) if area.height >= panel_height * 2 => {
+            let chunks = Layout::vertical([Constraint::Length(panel_height), Constraint::Min(0)])
+                .split(area);
+            f.render_widget(MagicPanel::new(magic).theme(&ctx.theme), chunks[0]);
+            f.render_stateful_widget(preview, chunks[1], state);
+        }
+        _ => {
+            f.render_stateful_widget(preview, area, state);
+        }
+    }
 }
 
 impl From<ImagePicker> for widget::ImagePicker {
@@ -491,6 +972,194 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn test_load_more_ranged_preview() -> std::io::Result<()> {
+        let ctx: Rc<AppContext> = Rc::default();
+        let (tx, rx) = event::new();
+        let mut terminal = setup_terminal()?;
+        let load_more_count = || {
+            std::iter::from_fn(|| rx.try_recv())
+                .filter(|e| matches!(e, AppEventType::LoadMorePreview))
+                .count()
+        };
+
+        const MIB: usize = 1024 * 1024;
+        let line = |c: &str| format!("{}\n", c.repeat(63));
+        let file_detail = FileDetail {
+            size_byte: 2 * MIB + 10,
+            ..file_detail()
+        };
+        let object = RawObject {
+            bytes: line("a").repeat(MIB / 64).into_bytes(),
+        };
+        let file_path = "file.txt".to_string();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![file_path.clone()],
+        };
+        let mut page =
+            ObjectPreviewPage::new(file_detail, None, object, file_path, object_key, ctx, tx);
+        page.set_ranged();
+
+        // not loaded until the end is approached
+        terminal.draw(|f| page.render(f, f.area()))?;
+        assert_eq!(load_more_count(), 0);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('G')));
+        terminal.draw(|f| page.render(f, f.area()))?;
+        terminal.draw(|f| page.render(f, f.area()))?;
+        assert_eq!(load_more_count(), 1);
+
+        page.append_object(RawObject {
+            bytes: line("b").repeat(MIB / 64).into_bytes(),
+        });
+        assert_eq!(page.loaded_size(), 2 * MIB);
+        assert!(page.partial);
+
+        page.append_object(RawObject {
+            bytes: "c".repeat(10).into_bytes(),
+        });
+        assert_eq!(page.loaded_size(), 2 * MIB + 10);
+        assert!(!page.partial);
+
+        terminal.draw(|f| page.render(f, f.area()))?;
+        assert_eq!(load_more_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_preview() {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let object = object(&["Hello, world!"]);
+        let file_path = "file.txt".to_string();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![file_path.clone()],
+        };
+        let mut page =
+            ObjectPreviewPage::new(file_detail(), None, object, file_path, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('R')));
+        let refreshed =
+            std::iter::from_fn(|| rx.try_recv()).any(|e| matches!(e, AppEventType::PreviewRefresh));
+        assert!(refreshed);
+    }
+
+    #[test]
+    fn test_fold_and_copy_key_path() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, rx) = event::new();
+        let mut terminal = setup_terminal()?;
+
+        let preview = [
+            "kind: Pod",
+            "metadata:",
+            "  name: web",
+            "  labels:",
+            "    app: web",
+            "spec:",
+            "  replicas: 1",
+        ];
+        let object = object(&preview);
+        let file_path = "pod.yaml".to_string();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![file_path.clone()],
+        };
+        let file_detail = FileDetail {
+            name: file_path.clone(),
+            ..file_detail()
+        };
+        let mut page =
+            ObjectPreviewPage::new(file_detail, None, object, file_path, object_key, ctx, tx);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        terminal.draw(|f| page.render(f, f.area()))?;
+        page.handle_key(KeyEvent::from(KeyCode::Char('z')));
+        terminal.draw(|f| page.render(f, f.area()))?;
+
+        #[rustfmt::skip]
+        let expected = [
+            "┌Preview [pod.yaml]──────────┐",
+            "│ 2 metadata: ... (3 lines)  │",
+            "│ 6 spec:                    │",
+            "│ 7   replicas: 1            │",
+            "│                            │",
+            "│                            │",
+            "│                            │",
+            "│                            │",
+            "│                            │",
+            "└────────────────────────────┘",
+        ];
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+
+        page.handle_key(KeyEvent::from(KeyCode::Char('y')));
+        let copied = std::iter::from_fn(|| rx.try_recv()).find_map(|e| match e {
+            AppEventType::CopyToClipboard(_, value) => Some(value),
+            _ => None,
+        });
+        assert_eq!(copied.as_deref(), Some("metadata"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_binary_with_magic_panel() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(82, 12))?;
+
+        let object = RawObject {
+            bytes: b"PK\x03\x04\x14\x00\x00\x00".to_vec(),
+        };
+        let file_path = "report.pdf".to_string();
+        let object_key = ObjectKey {
+            bucket_name: "test-bucket".to_string(),
+            object_path: vec![file_path.clone()],
+        };
+        let file_detail = FileDetail {
+            name: file_path.clone(),
+            content_type: "application/pdf".to_string(),
+            ..file_detail()
+        };
+        let mut page =
+            ObjectPreviewPage::new(file_detail, None, object, file_path, object_key, ctx, tx);
+        terminal.draw(|f| page.render(f, f.area()))?;
+
+        #[rustfmt::skip]
+        let expected = [
+            "┌File type───────────────────────────────────────────────────────────────────────┐",
+            "│ Format        ZIP (archive)                                                    │",
+            "│ MIME type     application/zip                                                  │",
+            "│ Extension     .zip (the name has .pdf)                                         │",
+            "│ Content-Type  application/pdf (does not match)                                 │",
+            "└────────────────────────────────────────────────────────────────────────────────┘",
+            "┌Preview [report.pdf]────────────────────────────────────────────────────────────┐",
+            "│ 00000000  50 4b 03 04 14 00 00 00                           |PK......|         │",
+            "│                                                                                │",
+            "│                                                                                │",
+            "│                                                                                │",
+            "└────────────────────────────────────────────────────────────────────────────────┘",
+        ];
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+
+        Ok(())
+    }
+
     fn setup_terminal() -> std::io::Result<Terminal<TestBackend>> {
         let backend = TestBackend::new(30, 10);
         let mut terminal = Terminal::new(backend)?;
@@ -506,10 +1175,18 @@ mod tests {
             e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
             content_type: "text/plain".to_string(),
             storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
             key: "file.txt".to_string(),
             s3_uri: "s3://bucket-1/file.txt".to_string(),
             arn: "arn:aws:s3:::bucket-1/file.txt".to_string(),
-            object_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file.txt".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file.txt"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/file.txt".to_string(),
         }
     }
 }
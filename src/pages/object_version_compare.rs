@@ -0,0 +1,248 @@
+use std::rc::Rc;
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    event::{AppEventType, Sender},
+    object::{FileDetail, RawObject},
+    pages::util::{build_helps, build_short_helps},
+    widget::{ScrollLinesOptions, TextPreview, TextPreviewState},
+};
+
+#[derive(Debug)]
+pub struct ObjectVersionComparePage {
+    file_detail: FileDetail,
+    file_version_id: String,
+
+    // selected version on the left, latest version on the right
+    version_state: TextPreviewState,
+    latest_state: TextPreviewState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+impl ObjectVersionComparePage {
+    pub fn new(
+        file_detail: FileDetail,
+        file_version_id: String,
+        object: RawObject,
+        latest_object: RawObject,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let build_state = |object: &RawObject| {
+            TextPreviewState::new(
+                &file_detail,
+                object,
+                ctx.config.preview.highlight,
+                &ctx.config.preview.highlight_theme,
+                ScrollLinesOptions::default().wrap_options(
+                    ctx.config.preview.word_wrap,
+                    ctx.config.preview.hanging_indent,
+                ),
+            )
+        };
+        let (version_state, msg) = build_state(&object);
+        let (latest_state, _) = build_state(&latest_object);
+        if let Some(msg) = msg {
+            tx.send(AppEventType::NotifyWarn(msg));
+        }
+
+        Self {
+            file_detail,
+            file_version_id,
+            version_state,
+            latest_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('j') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_forward());
+            }
+            key_code_char!('k') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_backward());
+            }
+            key_code_char!('f') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_page_forward());
+            }
+            key_code_char!('b') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_page_backward());
+            }
+            key_code_char!('g') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_to_top());
+            }
+            key_code_char!('G') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_to_end());
+            }
+            key_code_char!('h') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_left());
+            }
+            key_code_char!('l') => {
+                self.for_each_state(|state| state.scroll_lines_state.scroll_right());
+            }
+            key_code_char!('w') => {
+                self.for_each_state(|state| state.scroll_lines_state.toggle_wrap());
+            }
+            key_code_char!('n') => {
+                self.for_each_state(|state| state.scroll_lines_state.toggle_number());
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::horizontal(Constraint::from_percentages([50, 50])).split(area);
+
+        let version_preview = TextPreview::new(
+            self.file_detail.name.as_str(),
+            Some(self.file_version_id.as_str()),
+            &self.ctx.theme,
+        );
+        f.render_stateful_widget(version_preview, chunks[0], &mut self.version_state);
+
+        let latest_preview =
+            TextPreview::new(self.file_detail.name.as_str(), None, &self.ctx.theme);
+        f.render_stateful_widget(latest_preview, chunks[1], &mut self.latest_state);
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["j/k"], "Scroll forward/backward"),
+            (&["f/b"], "Scroll page forward/backward"),
+            (&["g/G"], "Scroll to top/end"),
+            (&["h/l"], "Scroll left/right"),
+            (&["w"], "Toggle wrap"),
+            (&["n"], "Toggle number"),
+            (&["Backspace"], "Close preview"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["j/k"], "Scroll", 2),
+            (&["g/G"], "Top/End", 3),
+            (&["Backspace"], "Close", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+}
+
+impl ObjectVersionComparePage {
+    // scroll both previews together
+    fn for_each_state(&mut self, f: impl Fn(&mut TextPreviewState)) {
+        f(&mut self.version_state);
+        f(&mut self.latest_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{event, set_cells};
+
+    use super::*;
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+
+    fn object(ss: &[&str]) -> RawObject {
+        RawObject {
+            bytes: ss.join("\n").as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_render_scroll_together() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _) = event::new();
+        let backend = TestBackend::new(80, 6);
+        let mut terminal = Terminal::new(backend)?;
+
+        let latest_object = object(&["foo", "bar", "BAZ", "qux", "quux", "corge"]);
+        let object = object(&["foo", "bar", "baz", "qux", "quux", "corge"]);
+        let mut page = ObjectVersionComparePage::new(
+            file_detail(),
+            "v1".to_string(),
+            object,
+            latest_object,
+            ctx,
+            tx,
+        );
+        page.handle_key(KeyEvent::from(KeyCode::Char('j')));
+
+        let area = Rect::new(0, 0, 80, 6);
+        terminal.draw(|f| {
+            page.render(f, area);
+        })?;
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌Preview [a.txt (Version ID: v1)]──────┐┌Preview [a.txt]───────────────────────┐",
+            "│ 2 bar                                ││ 2 bar                                │",
+            "│ 3 baz                                ││ 3 BAZ                                │",
+            "│ 4 qux                                ││ 4 qux                                │",
+            "│ 5 quux                               ││ 5 quux                               │",
+            "└──────────────────────────────────────┘└──────────────────────────────────────┘",
+        ]);
+        set_cells! { expected =>
+            ([2], 1..5) => fg: Color::DarkGray,
+            ([42], 1..5) => fg: Color::DarkGray,
+        }
+
+        terminal.backend().assert_buffer(&expected);
+
+        Ok(())
+    }
+
+    fn file_detail() -> FileDetail {
+        FileDetail {
+            name: "a.txt".to_string(),
+            size_byte: 1024,
+            last_modified: parse_datetime("2024-01-02 13:01:02"),
+            e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
+            content_type: "text/plain".to_string(),
+            storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
+            key: "a.txt".to_string(),
+            s3_uri: "s3://bucket-1/a.txt".to_string(),
+            arn: "arn:aws:s3:::bucket-1/a.txt".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/a.txt"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/a.txt".to_string(),
+        }
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+}
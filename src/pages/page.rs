@@ -1,72 +1,147 @@
-use std::rc::Rc;
+use std::{fmt::Debug, path::PathBuf, rc::Rc};
 
 use ratatui::{crossterm::event::KeyEvent, layout::Rect, Frame};
 
 use crate::{
+    access_log::AccessLogSummary,
     app::AppContext,
+    bucket_metrics::BucketMetrics,
     event::Sender,
-    object::{BucketItem, FileDetail, ObjectItem, ObjectKey, RawObject},
+    jobs::Job,
+    object::{
+        BucketItem, CleanupObject, DuplicateObjectGroup, FileDetail, ObjectDiff, ObjectItem,
+        ObjectKey, RawObject,
+    },
     pages::{
-        bucket_list::BucketListPage, help::HelpPage, initializing::InitializingPage,
-        object_detail::ObjectDetailPage, object_list::ObjectListPage,
-        object_preview::ObjectPreviewPage,
+        access_log_summary::AccessLogSummaryPage, bucket_list::BucketListPage,
+        bucket_metrics::BucketMetricsPage, cleanup_objects::CleanupObjectsPage,
+        compare_objects::CompareObjectsPage, duplicate_objects::DuplicateObjectsPage,
+        help::HelpPage, initializing::InitializingPage, object_detail::ObjectDetailPage,
+        object_list::ObjectListPage, object_local_compare::ObjectLocalComparePage,
+        object_preview::ObjectPreviewPage, object_version_compare::ObjectVersionComparePage,
+        transfer_list::TransferListPage, watch_status::WatchStatusPage,
     },
+    watch::WatchUpload,
     widget::ScrollListState,
 };
 
-#[derive(Debug)]
-pub enum Page {
-    Initializing(Box<InitializingPage>),
-    BucketList(Box<BucketListPage>),
-    ObjectList(Box<ObjectListPage>),
-    ObjectDetail(Box<ObjectDetailPage>),
-    ObjectPreview(Box<ObjectPreviewPage>),
-    Help(Box<HelpPage>),
+// Common interface of the pages.
+// The methods are implemented as inherent methods of each page, and the trait just delegates to them.
+pub trait PageView: Debug {
+    fn handle_key(&mut self, key: KeyEvent);
+    fn render(&mut self, f: &mut Frame, area: Rect);
+    fn helps(&self) -> Vec<String>;
+    fn short_helps(&self) -> Vec<(String, usize)>;
+}
+
+// Pages that can be taken out of the `Page` enum by type, see `Page::get` and `Page::get_mut`.
+pub trait PageVariant: PageView + Sized {
+    fn from_page(page: &Page) -> Option<&Self>;
+    fn from_page_mut(page: &mut Page) -> Option<&mut Self>;
+}
+
+// Defines the `Page` enum, and implements `PageView` and `PageVariant` for each page.
+// To add a new page, add a line here and implement `handle_key`, `render`, `helps` and `short_helps` on it.
+macro_rules! define_pages {
+    ($($variant:ident($page:ty),)*) => {
+        #[derive(Debug)]
+        pub enum Page {
+            $($variant(Box<$page>),)*
+        }
+
+        impl Page {
+            fn view(&self) -> &dyn PageView {
+                match self {
+                    $(Page::$variant(page) => page.as_ref(),)*
+                }
+            }
+
+            fn view_mut(&mut self) -> &mut dyn PageView {
+                match self {
+                    $(Page::$variant(page) => page.as_mut(),)*
+                }
+            }
+        }
+
+        $(
+            impl PageView for $page {
+                fn handle_key(&mut self, key: KeyEvent) {
+                    <$page>::handle_key(self, key)
+                }
+
+                fn render(&mut self, f: &mut Frame, area: Rect) {
+                    <$page>::render(self, f, area)
+                }
+
+                fn helps(&self) -> Vec<String> {
+                    <$page>::helps(self)
+                }
+
+                fn short_helps(&self) -> Vec<(String, usize)> {
+                    <$page>::short_helps(self)
+                }
+            }
+
+            impl PageVariant for $page {
+                fn from_page(page: &Page) -> Option<&Self> {
+                    match page {
+                        Page::$variant(page) => Some(page),
+                        _ => None,
+                    }
+                }
+
+                fn from_page_mut(page: &mut Page) -> Option<&mut Self> {
+                    match page {
+                        Page::$variant(page) => Some(page),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+define_pages! {
+    Initializing(InitializingPage),
+    BucketList(BucketListPage),
+    ObjectList(ObjectListPage),
+    ObjectDetail(ObjectDetailPage),
+    ObjectPreview(ObjectPreviewPage),
+    ObjectVersionCompare(ObjectVersionComparePage),
+    ObjectLocalCompare(ObjectLocalComparePage),
+    DuplicateObjects(DuplicateObjectsPage),
+    CleanupObjects(CleanupObjectsPage),
+    CompareObjects(CompareObjectsPage),
+    BucketMetrics(BucketMetricsPage),
+    AccessLogSummary(AccessLogSummaryPage),
+    WatchStatus(WatchStatusPage),
+    TransferList(TransferListPage),
+    Help(HelpPage),
 }
 
 impl Page {
     pub fn handle_key(&mut self, key: KeyEvent) {
-        match self {
-            Page::Initializing(page) => page.handle_key(key),
-            Page::BucketList(page) => page.handle_key(key),
-            Page::ObjectList(page) => page.handle_key(key),
-            Page::ObjectDetail(page) => page.handle_key(key),
-            Page::ObjectPreview(page) => page.handle_key(key),
-            Page::Help(page) => page.handle_key(key),
-        }
+        self.view_mut().handle_key(key)
     }
 
     pub fn render(&mut self, f: &mut Frame, area: Rect) {
-        match self {
-            Page::Initializing(page) => page.render(f, area),
-            Page::BucketList(page) => page.render(f, area),
-            Page::ObjectList(page) => page.render(f, area),
-            Page::ObjectDetail(page) => page.render(f, area),
-            Page::ObjectPreview(page) => page.render(f, area),
-            Page::Help(page) => page.render(f, area),
-        }
+        self.view_mut().render(f, area)
     }
 
     pub fn helps(&self) -> Vec<String> {
-        match self {
-            Page::Initializing(page) => page.helps(),
-            Page::BucketList(page) => page.helps(),
-            Page::ObjectList(page) => page.helps(),
-            Page::ObjectDetail(page) => page.helps(),
-            Page::ObjectPreview(page) => page.helps(),
-            Page::Help(page) => page.helps(),
-        }
+        self.view().helps()
     }
 
     pub fn short_helps(&self) -> Vec<(String, usize)> {
-        match self {
-            Page::Initializing(page) => page.short_helps(),
-            Page::BucketList(page) => page.short_helps(),
-            Page::ObjectList(page) => page.short_helps(),
-            Page::ObjectDetail(page) => page.short_helps(),
-            Page::ObjectPreview(page) => page.short_helps(),
-            Page::Help(page) => page.short_helps(),
-        }
+        self.view().short_helps()
+    }
+
+    pub fn get<P: PageVariant>(&self) -> Option<&P> {
+        P::from_page(self)
+    }
+
+    pub fn get_mut<P: PageVariant>(&mut self) -> Option<&mut P> {
+        P::from_page_mut(self)
     }
 }
 
@@ -75,8 +150,13 @@ impl Page {
         Self::Initializing(Box::new(InitializingPage::new(ctx, tx)))
     }
 
-    pub fn of_bucket_list(bucket_items: Vec<BucketItem>, ctx: Rc<AppContext>, tx: Sender) -> Self {
-        Self::BucketList(Box::new(BucketListPage::new(bucket_items, ctx, tx)))
+    pub fn of_bucket_list(
+        bucket_items: Vec<BucketItem>,
+        region: &str,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::BucketList(Box::new(BucketListPage::new(bucket_items, region, ctx, tx)))
     }
 
     pub fn of_object_list(
@@ -131,63 +211,146 @@ impl Page {
         )))
     }
 
-    pub fn of_help(helps: Vec<String>, ctx: Rc<AppContext>, tx: Sender) -> Self {
-        Self::Help(Box::new(HelpPage::new(helps, ctx, tx)))
+    pub fn of_object_version_compare(
+        file_detail: FileDetail,
+        file_version_id: String,
+        object: RawObject,
+        latest_object: RawObject,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::ObjectVersionCompare(Box::new(ObjectVersionComparePage::new(
+            file_detail,
+            file_version_id,
+            object,
+            latest_object,
+            ctx,
+            tx,
+        )))
     }
 
-    pub fn as_bucket_list(&self) -> &BucketListPage {
-        match self {
-            Self::BucketList(page) => page,
-            page => panic!("Page is not BucketList: {:?}", page),
-        }
+    pub fn of_object_local_compare(
+        file_detail: FileDetail,
+        object: RawObject,
+        local_path: PathBuf,
+        local_object: RawObject,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::ObjectLocalCompare(Box::new(ObjectLocalComparePage::new(
+            file_detail,
+            object,
+            local_path,
+            local_object,
+            ctx,
+            tx,
+        )))
     }
 
-    pub fn as_object_list(&self) -> &ObjectListPage {
-        match self {
-            Self::ObjectList(page) => page,
-            page => panic!("Page is not ObjectList: {:?}", page),
-        }
+    pub fn of_duplicate_objects(
+        object_key: ObjectKey,
+        groups: Vec<DuplicateObjectGroup>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::DuplicateObjects(Box::new(DuplicateObjectsPage::new(
+            object_key, groups, ctx, tx,
+        )))
     }
 
-    pub fn as_object_detail(&self) -> &ObjectDetailPage {
-        match self {
-            Self::ObjectDetail(page) => page,
-            page => panic!("Page is not ObjectDetail: {:?}", page),
-        }
+    pub fn of_access_log_summary(
+        bucket: String,
+        prefix: String,
+        summary: AccessLogSummary,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::AccessLogSummary(Box::new(AccessLogSummaryPage::new(
+            bucket, prefix, summary, ctx, tx,
+        )))
     }
 
-    pub fn as_mut_object_detail(&mut self) -> &mut ObjectDetailPage {
-        match self {
-            Self::ObjectDetail(page) => &mut *page,
-            page => panic!("Page is not ObjectDetail: {:?}", page),
-        }
+    pub fn of_watch_status(
+        dir: PathBuf,
+        bucket: String,
+        prefix: String,
+        uploads: Vec<WatchUpload>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::WatchStatus(Box::new(WatchStatusPage::new(
+            dir, bucket, prefix, uploads, ctx, tx,
+        )))
     }
 
-    pub fn as_object_preview(&self) -> &ObjectPreviewPage {
-        match self {
-            Self::ObjectPreview(page) => page,
-            page => panic!("Page is not ObjectPreview: {:?}", page),
-        }
+    pub fn of_transfer_list(jobs: Vec<Job>, ctx: Rc<AppContext>, tx: Sender) -> Self {
+        Self::TransferList(Box::new(TransferListPage::new(jobs, ctx, tx)))
     }
 
-    pub fn as_mut_object_preview(&mut self) -> &mut ObjectPreviewPage {
-        match self {
-            Self::ObjectPreview(page) => &mut *page,
-            page => panic!("Page is not ObjectPreview: {:?}", page),
-        }
+    pub fn of_cleanup_objects(
+        object_key: ObjectKey,
+        objects: Vec<CleanupObject>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::CleanupObjects(Box::new(CleanupObjectsPage::new(
+            object_key, objects, ctx, tx,
+        )))
+    }
+
+    pub fn of_compare_objects(
+        a_key: ObjectKey,
+        b_key: ObjectKey,
+        diffs: Vec<ObjectDiff>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::CompareObjects(Box::new(CompareObjectsPage::new(
+            a_key, b_key, diffs, ctx, tx,
+        )))
+    }
+
+    pub fn of_bucket_metrics(
+        bucket: BucketItem,
+        metrics: BucketMetrics,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        Self::BucketMetrics(Box::new(BucketMetricsPage::new(bucket, metrics, ctx, tx)))
+    }
+
+    pub fn of_help(helps: Vec<String>, ctx: Rc<AppContext>, tx: Sender) -> Self {
+        Self::Help(Box::new(HelpPage::new(helps, ctx, tx)))
     }
 }
 
+// Identifies a page in the stack, so that the result of a background load can be delivered
+// to the page which started it even if other pages have been pushed or popped in the meantime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageId(usize);
+
+#[derive(Debug)]
+struct PageEntry {
+    id: PageId,
+    page: Page,
+    // the number of the background loads started from the page
+    loading: usize,
+}
+
 #[derive(Debug)]
 pub struct PageStack {
-    stack: Vec<Page>,
+    stack: Vec<PageEntry>,
+    next_id: usize,
 }
 
 impl PageStack {
     pub fn new(ctx: Rc<AppContext>, tx: Sender) -> PageStack {
-        PageStack {
-            stack: vec![Page::of_initializing(ctx, tx)],
-        }
+        let mut page_stack = PageStack {
+            stack: Vec::new(),
+            next_id: 0,
+        };
+        page_stack.push(Page::of_initializing(ctx, tx));
+        page_stack
     }
 
     pub fn len(&self) -> usize {
@@ -195,11 +358,17 @@ impl PageStack {
     }
 
     pub fn push(&mut self, page: Page) {
-        self.stack.push(page);
+        let id = PageId(self.next_id);
+        self.next_id += 1;
+        self.stack.push(PageEntry {
+            id,
+            page,
+            loading: 0,
+        });
     }
 
     pub fn pop(&mut self) -> Page {
-        self.stack.pop().unwrap()
+        self.stack.pop().unwrap().page
     }
 
     pub fn clear(&mut self) {
@@ -207,14 +376,137 @@ impl PageStack {
     }
 
     pub fn current_page(&self) -> &Page {
-        self.stack.last().unwrap()
+        &self.stack.last().unwrap().page
     }
 
     pub fn current_page_mut(&mut self) -> &mut Page {
-        self.stack.last_mut().unwrap()
+        &mut self.stack.last_mut().unwrap().page
+    }
+
+    // None if the current page is not of the type, e.g. the page has been closed before the event arrives
+    pub fn current<P: PageVariant>(&self) -> Option<&P> {
+        self.current_page().get()
+    }
+
+    pub fn current_mut<P: PageVariant>(&mut self) -> Option<&mut P> {
+        self.current_page_mut().get_mut()
+    }
+
+    pub fn current_page_id(&self) -> PageId {
+        self.stack.last().unwrap().id
     }
 
-    pub fn iter(&self) -> std::slice::Iter<Page> {
-        self.stack.iter()
+    pub fn get_mut(&mut self, id: PageId) -> Option<&mut Page> {
+        self.stack
+            .iter_mut()
+            .find(|entry| entry.id == id)
+            .map(|entry| &mut entry.page)
+    }
+
+    pub fn is_current_page(&self, id: PageId) -> bool {
+        self.current_page_id() == id
+    }
+
+    // Replaces the page with the given id, keeping its position and the loading state.
+    // Returns false if the page has already been removed from the stack.
+    pub fn replace(&mut self, id: PageId, page: Page) -> bool {
+        match self.stack.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                entry.page = page;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn start_loading(&mut self, id: PageId) {
+        if let Some(entry) = self.stack.iter_mut().find(|entry| entry.id == id) {
+            entry.loading += 1;
+        }
+    }
+
+    // The page may have been removed while loading, in which case nothing happens.
+    pub fn finish_loading(&mut self, id: PageId) {
+        if let Some(entry) = self.stack.iter_mut().find(|entry| entry.id == id) {
+            entry.loading = entry.loading.saturating_sub(1);
+        }
+    }
+
+    pub fn is_current_page_loading(&self) -> bool {
+        self.stack.last().unwrap().loading > 0
+    }
+
+    pub fn is_any_page_loading(&self) -> bool {
+        self.stack.iter().any(|entry| entry.loading > 0)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Page> {
+        self.stack.iter().map(|entry| &entry.page)
+    }
+
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Page> {
+        self.stack.iter_mut().map(|entry| &mut entry.page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event;
+
+    use super::*;
+
+    #[test]
+    fn test_page_stack_loading() {
+        let ctx: Rc<AppContext> = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut page_stack = PageStack::new(Rc::clone(&ctx), tx.clone());
+
+        page_stack.push(Page::of_help(vec![], Rc::clone(&ctx), tx.clone()));
+        let id = page_stack.current_page_id();
+        page_stack.start_loading(id);
+        assert!(page_stack.is_current_page_loading());
+
+        // loading of the other page is not shown on the current page
+        page_stack.push(Page::of_help(vec![], Rc::clone(&ctx), tx.clone()));
+        assert!(!page_stack.is_current_page_loading());
+        assert!(page_stack.is_any_page_loading());
+        assert!(!page_stack.is_current_page(id));
+
+        page_stack.pop();
+        assert!(page_stack.is_current_page(id));
+        assert!(page_stack.replace(id, Page::of_help(vec![], Rc::clone(&ctx), tx.clone())));
+        assert!(page_stack.is_current_page_loading());
+        page_stack.finish_loading(id);
+        assert!(!page_stack.is_any_page_loading());
+
+        // the page has been removed while loading
+        page_stack.pop();
+        page_stack.finish_loading(id);
+        assert!(!page_stack.replace(id, Page::of_help(vec![], ctx, tx)));
+    }
+
+    #[test]
+    fn test_page_get() {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut page = Page::of_help(vec![], ctx, tx);
+
+        assert!(page.get::<HelpPage>().is_some());
+        assert!(page.get::<ObjectListPage>().is_none());
+        assert!(page.get_mut::<HelpPage>().is_some());
+        assert!(page.get_mut::<BucketListPage>().is_none());
+    }
+
+    #[test]
+    fn test_page_stack_current() {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut page_stack = PageStack::new(Rc::clone(&ctx), tx.clone());
+        assert!(page_stack.current::<InitializingPage>().is_some());
+        assert!(page_stack.current::<ObjectListPage>().is_none());
+
+        page_stack.push(Page::of_help(vec![], ctx, tx));
+        assert!(page_stack.current_mut::<HelpPage>().is_some());
+        assert!(page_stack.current_mut::<InitializingPage>().is_none());
     }
 }
@@ -0,0 +1,286 @@
+use std::rc::Rc;
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, ListItem, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    color::ColorTheme,
+    event::{AppEventType, Sender},
+    format::format_size_byte,
+    jobs::{Job, JobStatus},
+    pages::util::{build_helps, build_short_helps},
+    widget::{ScrollList, ScrollListState},
+};
+
+const PROGRESS_BAR_WIDTH: usize = 10;
+
+#[derive(Debug)]
+pub struct TransferListPage {
+    jobs: Vec<Job>,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+impl TransferListPage {
+    pub fn new(jobs: Vec<Job>, ctx: Rc<AppContext>, tx: Sender) -> Self {
+        let list_state = ScrollListState::new(jobs.len());
+        Self {
+            jobs,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('j') => {
+                self.list_state.select_next();
+            }
+            key_code_char!('k') => {
+                self.list_state.select_prev();
+            }
+            key_code_char!('g') => {
+                self.list_state.select_first();
+            }
+            key_code_char!('G') => {
+                self.list_state.select_last();
+            }
+            key_code_char!('f') => {
+                self.list_state.select_next_page();
+            }
+            key_code_char!('b') => {
+                self.list_state.select_prev_page();
+            }
+            key_code_char!('x') => {
+                if let Some(job) = self.current_selected_job() {
+                    self.tx.send(AppEventType::CancelJob(job.id));
+                }
+            }
+            key_code_char!('r') => {
+                if let Some(job) = self.current_selected_job() {
+                    self.tx.send(AppEventType::RetryJob(job.id));
+                }
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).split(area);
+
+        let count = |f: fn(&JobStatus) -> bool| self.jobs.iter().filter(|j| f(&j.status)).count();
+        let totals = format!(
+            "{} running, {} completed, {} failed, {} cancelled",
+            count(|s| matches!(s, JobStatus::Running)),
+            count(|s| matches!(s, JobStatus::Completed)),
+            count(|s| matches!(s, JobStatus::Failed(_))),
+            count(|s| matches!(s, JobStatus::Cancelled)),
+        );
+        let header = Paragraph::new(Line::from(totals.bold()))
+            .block(
+                Block::bordered()
+                    .title("Transfers")
+                    .padding(Padding::horizontal(1)),
+            )
+            .fg(self.ctx.theme.fg);
+        f.render_widget(header, chunks[0]);
+
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+        let show_item_count = (chunks[1].height as usize).saturating_sub(2 /* border */);
+        let list_items: Vec<ListItem> = self
+            .jobs
+            .iter()
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, job)| build_list_item(job, idx + offset == selected, &self.ctx.theme))
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, chunks[1], &mut self.list_state);
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["j/k"], "Select item"),
+            (&["g/G"], "Go to top/bottom"),
+            (&["f"], "Scroll page forward"),
+            (&["b"], "Scroll page backward"),
+            (&["x"], "Cancel transfer"),
+            (&["r"], "Retry transfer"),
+            (&["Backspace"], "Close transfers"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["j/k"], "Select", 4),
+            (&["x"], "Cancel", 2),
+            (&["r"], "Retry", 3),
+            (&["Backspace"], "Close", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+
+    pub fn set_jobs(&mut self, jobs: Vec<Job>) {
+        self.list_state.set_total(jobs.len());
+        self.jobs = jobs;
+    }
+
+    fn current_selected_job(&self) -> Option<&Job> {
+        self.jobs.get(self.list_state.selected)
+    }
+}
+
+fn build_list_item<'a>(job: &'a Job, selected: bool, theme: &ColorTheme) -> ListItem<'a> {
+    let (status, color, error) = match &job.status {
+        JobStatus::Running => ("Running", theme.status_info, None),
+        JobStatus::Completed => ("Completed", theme.status_success, None),
+        JobStatus::Failed(e) => ("Failed", theme.status_error, Some(e)),
+        JobStatus::Cancelled => ("Cancelled", theme.status_warn, None),
+    };
+    let ratio = job.ratio();
+    let filled = ((ratio * PROGRESS_BAR_WIDTH as f64) as usize).min(PROGRESS_BAR_WIDTH);
+    let mut spans = vec![
+        Span::raw(format!(" {} ", job.updated_at.format("%H:%M:%S"))),
+        Span::raw(format!("{:<9}", status)).fg(color),
+        Span::raw(format!(" {} ", job.spec.kind())),
+        Span::raw("━".repeat(filled)).fg(color),
+        Span::raw("─".repeat(PROGRESS_BAR_WIDTH - filled)).fg(theme.divider),
+        Span::raw(format!(" {:>3}%", (ratio * 100.0) as usize)),
+        Span::raw(format!(
+            "  {}/{} ({}/{})",
+            job.done.count,
+            job.total.count,
+            format_size_byte(job.done.size_byte),
+            format_size_byte(job.total.size_byte),
+        )),
+        Span::raw(format!(
+            "  {} -> {}",
+            job.spec.source(),
+            job.spec.destination()
+        )),
+    ];
+    if let Some(error) = error {
+        spans.push(Span::raw(format!("  {}", error)).fg(theme.divider));
+    }
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(Line::from(spans)).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use chrono::{Local, TimeZone};
+
+    use crate::{
+        download_list::DownloadObjectList,
+        event,
+        jobs::{JobProgress, JobSpec},
+        object::{DownloadObjectInfo, DownloadObjectsOptions},
+    };
+
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(100, 8))?;
+
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 13, 1, 2).unwrap();
+        let job = |id: usize, key: &str, status: JobStatus, done: usize| {
+            let objs = (0..4).map(|i| DownloadObjectInfo {
+                key: format!("{}{}.txt", key, i),
+                size_byte: 1024,
+                last_modified: now,
+                e_tag: String::new(),
+            });
+            Job {
+                id,
+                spec: JobSpec::DownloadObjects {
+                    bucket: "bucket".into(),
+                    key: key.into(),
+                    objs: Arc::new(DownloadObjectList::from_objs(objs).unwrap()),
+                    options: DownloadObjectsOptions::default(),
+                    path: PathBuf::from(format!("/tmp/{}", key)),
+                },
+                status,
+                done: JobProgress {
+                    count: done,
+                    size_byte: done * 1024,
+                },
+                total: JobProgress {
+                    count: 4,
+                    size_byte: 4096,
+                },
+                updated_at: now,
+            }
+        };
+        let jobs = vec![
+            job(2, "c/", JobStatus::Running, 1),
+            job(1, "b/", JobStatus::Failed("1 failed".into()), 3),
+            job(0, "a/", JobStatus::Completed, 4),
+        ];
+        let mut page = TransferListPage::new(jobs, ctx, tx);
+        let area = Rect::new(0, 0, 100, 8);
+        terminal.draw(|f| page.render(f, area))?;
+
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(100)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        #[rustfmt::skip]
+        let expected = [
+            "┌Transfers─────────────────────────────────────────────────────────────────────────────────────────┐",
+            "│ 1 running, 1 completed, 1 failed, 0 cancelled                                                    │",
+            "└──────────────────────────────────────────────────────────────────────────────────────────────────┘",
+            "┌─────────────────────────────────────────────────────────────────────────────────────────── 1 / 3 ┐",
+            "│  13:01:02 Running   Download ━━────────  25%  1/4 (1 KiB/4 KiB)  s3://bucket/c/ -> /tmp/c/       │",
+            "│  13:01:02 Failed    Download ━━━━━━━───  75%  3/4 (3 KiB/4 KiB)  s3://bucket/b/ -> /tmp/b/  1 fa │",
+            "│  13:01:02 Completed Download ━━━━━━━━━━ 100%  4/4 (4 KiB/4 KiB)  s3://bucket/a/ -> /tmp/a/       │",
+            "└──────────────────────────────────────────────────────────────────────────────────────────────────┘",
+        ];
+        assert_eq!(lines, expected);
+
+        Ok(())
+    }
+}
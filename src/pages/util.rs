@@ -1,3 +1,11 @@
+use crate::config::CostConfig;
+
+pub fn build_delete_cost_line(cost_config: &CostConfig, key_count: usize) -> Option<String> {
+    cost_config
+        .estimate_delete_cost(key_count)
+        .map(|cost| format!("  Estimated cost: ${:.2} (DeleteObjects requests)", cost))
+}
+
 pub fn build_helps(helps: &[(&[&str], &str)]) -> Vec<String> {
     helps
         .iter()
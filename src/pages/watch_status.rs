@@ -0,0 +1,258 @@
+use std::{path::PathBuf, rc::Rc};
+
+use laurier::{key_code, key_code_char};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, ListItem, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    app::AppContext,
+    color::ColorTheme,
+    event::{AppEventType, Sender},
+    format::format_size_byte,
+    pages::util::{build_helps, build_short_helps},
+    watch::{WatchUpload, WatchUploadStatus},
+    widget::{ScrollList, ScrollListState},
+};
+
+#[derive(Debug)]
+pub struct WatchStatusPage {
+    dir: PathBuf,
+    bucket: String,
+    prefix: String,
+    uploads: Vec<WatchUpload>,
+
+    list_state: ScrollListState,
+
+    ctx: Rc<AppContext>,
+    tx: Sender,
+}
+
+impl WatchStatusPage {
+    pub fn new(
+        dir: PathBuf,
+        bucket: String,
+        prefix: String,
+        uploads: Vec<WatchUpload>,
+        ctx: Rc<AppContext>,
+        tx: Sender,
+    ) -> Self {
+        let list_state = ScrollListState::new(uploads.len());
+        Self {
+            dir,
+            bucket,
+            prefix,
+            uploads,
+            list_state,
+            ctx,
+            tx,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key {
+            key_code!(KeyCode::Esc) => {
+                self.tx.send(AppEventType::Quit);
+            }
+            key_code!(KeyCode::Backspace) => {
+                self.tx.send(AppEventType::CloseCurrentPage);
+            }
+            key_code_char!('j') => {
+                self.list_state.select_next();
+            }
+            key_code_char!('k') => {
+                self.list_state.select_prev();
+            }
+            key_code_char!('g') => {
+                self.list_state.select_first();
+            }
+            key_code_char!('G') => {
+                self.list_state.select_last();
+            }
+            key_code_char!('f') => {
+                self.list_state.select_next_page();
+            }
+            key_code_char!('b') => {
+                self.list_state.select_prev_page();
+            }
+            key_code_char!('x') => {
+                self.tx.send(AppEventType::StopWatchDir);
+            }
+            key_code_char!('?') => {
+                self.tx.send(AppEventType::OpenHelp);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).split(area);
+
+        let count = |f: fn(&WatchUploadStatus) -> bool| {
+            self.uploads.iter().filter(|u| f(&u.status)).count()
+        };
+        let totals = format!(
+            "{} pending, {} uploading, {} completed, {} failed",
+            count(|s| matches!(s, WatchUploadStatus::Pending)),
+            count(|s| matches!(s, WatchUploadStatus::Uploading)),
+            count(|s| matches!(s, WatchUploadStatus::Completed(_))),
+            count(|s| matches!(s, WatchUploadStatus::Failed(_))),
+        );
+        let header = Paragraph::new(vec![
+            Line::from(vec![
+                format!("{}", self.dir.display()).into(),
+                " -> ".fg(self.ctx.theme.divider),
+                format!("s3://{}/{}", self.bucket, self.prefix).into(),
+            ]),
+            Line::from(totals.bold()),
+        ])
+        .block(Block::bordered().padding(Padding::horizontal(1)))
+        .fg(self.ctx.theme.fg);
+        f.render_widget(header, chunks[0]);
+
+        let offset = self.list_state.offset;
+        let selected = self.list_state.selected;
+        let show_item_count = (chunks[1].height as usize).saturating_sub(2 /* border */);
+        let list_items: Vec<ListItem> = self
+            .uploads
+            .iter()
+            .skip(offset)
+            .take(show_item_count)
+            .enumerate()
+            .map(|(idx, upload)| build_list_item(upload, idx + offset == selected, &self.ctx.theme))
+            .collect();
+
+        let list = ScrollList::new(list_items).theme(&self.ctx.theme);
+        f.render_stateful_widget(list, chunks[1], &mut self.list_state);
+    }
+
+    pub fn helps(&self) -> Vec<String> {
+        let helps: &[(&[&str], &str)] = &[
+            (&["Esc", "Ctrl-c"], "Quit app"),
+            (&["j/k"], "Select item"),
+            (&["g/G"], "Go to top/bottom"),
+            (&["f"], "Scroll page forward"),
+            (&["b"], "Scroll page backward"),
+            (&["x"], "Stop watching"),
+            (&["Backspace"], "Go back (keep watching)"),
+        ];
+        build_helps(helps)
+    }
+
+    pub fn short_helps(&self) -> Vec<(String, usize)> {
+        let helps: &[(&[&str], &str, usize)] = &[
+            (&["Esc"], "Quit", 0),
+            (&["j/k"], "Select", 3),
+            (&["x"], "Stop", 2),
+            (&["Backspace"], "Go back", 1),
+            (&["?"], "Help", 0),
+        ];
+        build_short_helps(helps)
+    }
+
+    pub fn set_uploads(&mut self, uploads: Vec<WatchUpload>) {
+        self.list_state.set_total(uploads.len());
+        self.uploads = uploads;
+    }
+}
+
+fn build_list_item<'a>(
+    upload: &'a WatchUpload,
+    selected: bool,
+    theme: &ColorTheme,
+) -> ListItem<'a> {
+    let (status, color, detail) = match &upload.status {
+        WatchUploadStatus::Pending => ("Pending", theme.status_info, String::new()),
+        WatchUploadStatus::Uploading => ("Uploading", theme.status_info, String::new()),
+        WatchUploadStatus::Completed(size_byte) => (
+            "Completed",
+            theme.status_success,
+            format_size_byte(*size_byte as usize),
+        ),
+        WatchUploadStatus::Failed(e) => ("Failed", theme.status_error, e.clone()),
+    };
+    let mut spans = vec![
+        Span::raw(format!(" {} ", upload.updated_at.format("%H:%M:%S"))),
+        Span::raw(format!("{:<9}", status)).fg(color),
+        Span::raw(format!("  {}", upload.key)),
+    ];
+    if !detail.is_empty() {
+        spans.push(Span::raw(format!("  {}", detail)).fg(theme.divider));
+    }
+
+    let style = if selected {
+        Style::default()
+            .bg(theme.list_selected_bg)
+            .fg(theme.list_selected_fg)
+    } else {
+        Style::default()
+    };
+    ListItem::new(Line::from(spans)).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::event;
+
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_render() -> std::io::Result<()> {
+        let ctx = Rc::default();
+        let (tx, _rx) = event::new();
+        let mut terminal = Terminal::new(TestBackend::new(60, 9))?;
+
+        let upload = |key: &str, status: WatchUploadStatus| WatchUpload {
+            path: PathBuf::from(format!("/watch/{}", key)),
+            key: format!("inbox/{}", key),
+            status,
+            updated_at: Local.with_ymd_and_hms(2024, 1, 2, 13, 1, 2).unwrap(),
+        };
+        let uploads = vec![
+            upload("c.txt", WatchUploadStatus::Pending),
+            upload("b.txt", WatchUploadStatus::Completed(2048)),
+            upload("a.txt", WatchUploadStatus::Failed("Access Denied".into())),
+        ];
+        let mut page = WatchStatusPage::new(
+            PathBuf::from("/watch"),
+            "bucket".to_string(),
+            "inbox/".to_string(),
+            uploads,
+            ctx,
+            tx,
+        );
+        let area = Rect::new(0, 0, 60, 9);
+        terminal.draw(|f| page.render(f, area))?;
+
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(60)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        #[rustfmt::skip]
+        let expected = [
+            "┌──────────────────────────────────────────────────────────┐",
+            "│ /watch -> s3://bucket/inbox/                             │",
+            "│ 1 pending, 0 uploading, 1 completed, 1 failed            │",
+            "└──────────────────────────────────────────────────────────┘",
+            "┌─────────────────────────────────────────────────── 1 / 3 ┐",
+            "│  13:01:02 Pending    inbox/c.txt                         │",
+            "│  13:01:02 Completed  inbox/b.txt  2 KiB                  │",
+            "│  13:01:02 Failed     inbox/a.txt  Access Denied          │",
+            "└──────────────────────────────────────────────────────────┘",
+        ];
+        assert_eq!(lines, expected);
+
+        Ok(())
+    }
+}
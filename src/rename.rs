@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::error::{AppError, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameTarget {
+    pub from: String,
+    pub to: String,
+}
+
+// Builds the list of renames by applying the regex substitution to each name.
+// Names are relative to the current prefix, and names that do not change are excluded.
+// `existing_names` are the names of the files loaded in the current prefix, used to detect overwrites
+// early. The objects which are not loaded are checked when each object is renamed.
+// Renaming to the name of another renamed object is also rejected since copies run one by one.
+pub fn build_rename_targets(
+    names: &[&str],
+    existing_names: &[&str],
+    pattern: &str,
+    replacement: &str,
+) -> Result<Vec<RenameTarget>> {
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+    let re = Regex::new(pattern).map_err(|e| AppError::new("Invalid pattern", e))?;
+
+    let targets: Vec<RenameTarget> = names
+        .iter()
+        .map(|name| RenameTarget {
+            from: name.to_string(),
+            to: re.replace_all(name, replacement).into_owned(),
+        })
+        .filter(|t| t.from != t.to)
+        .collect();
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    for t in &targets {
+        if t.to.is_empty() || t.to.ends_with('/') || t.to.starts_with('/') {
+            return Err(AppError::msg(format!(
+                "Invalid new name: {} -> {}",
+                t.from, t.to
+            )));
+        }
+        if !seen.insert(t.to.as_str()) {
+            return Err(AppError::msg(format!("Duplicate new name: {}", t.to)));
+        }
+        if existing_names.contains(&t.to.as_str()) {
+            return Err(AppError::msg(format!("Object already exists: {}", t.to)));
+        }
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn target(from: &str, to: &str) -> RenameTarget {
+        RenameTarget {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    #[test]
+    fn test_build_rename_targets() {
+        let names = ["a.txt", "b.txt", "c.csv"];
+        let actual = build_rename_targets(&names, &names, r"\.txt$", ".md").unwrap();
+        assert_eq!(
+            actual,
+            vec![target("a.txt", "a.md"), target("b.txt", "b.md")]
+        );
+
+        // prefix and suffix with capture groups
+        let actual = build_rename_targets(&names, &names, r"^(.+)\.(\w+)$", "old_$1.$2").unwrap();
+        assert_eq!(
+            actual,
+            vec![
+                target("a.txt", "old_a.txt"),
+                target("b.txt", "old_b.txt"),
+                target("c.csv", "old_c.csv"),
+            ]
+        );
+
+        let names = ["a", "b"];
+        let actual = build_rename_targets(&names, &names, "^(a|b)$", "${1}x").unwrap();
+        assert_eq!(actual, vec![target("a", "ax"), target("b", "bx")]);
+
+        let actual = build_rename_targets(&names, &names, "", "x").unwrap();
+        assert!(actual.is_empty());
+    }
+
+    #[rstest]
+    #[case(&["a.txt"], &["a.txt"], "(", "x")]
+    #[case(&["a.txt"], &["a.txt"], "a.txt", "")]
+    #[case(&["a.txt"], &["a.txt"], "a.txt", "dir/")]
+    #[case(&["a.txt", "b.txt"], &["a.txt", "b.txt"], r"^\w", "c")]
+    #[case(&["a.txt"], &["a.txt", "b.txt"], "a", "b")]
+    #[case(&["a", "b"], &["a", "b"], "^a$|^b$", "b")]
+    #[case(&["a", "ab"], &["a", "ab"], "^a", "ab")]
+    fn test_build_rename_targets_error(
+        #[case] names: &[&str],
+        #[case] existing_names: &[&str],
+        #[case] pattern: &str,
+        #[case] replacement: &str,
+    ) {
+        assert!(build_rename_targets(names, existing_names, pattern, replacement).is_err());
+    }
+}
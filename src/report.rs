@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    error::{AppError, Result},
+    file::{save_binary, unique_file_path},
+    object::{CopyFailure, DeleteFailure, DownloadFailure, TagFailure},
+};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BulkOperation {
+    Download,
+    Delete,
+    Sync,
+    Tag,
+    #[serde(rename = "storage-class")]
+    StorageClass,
+}
+
+#[derive(Debug, Serialize)]
+struct BulkOperationFailure {
+    key: String,
+    error: String,
+}
+
+// Result of a bulk operation written as JSON,
+// so that the failed keys can be processed by other tools afterwards.
+#[derive(Debug, Serialize)]
+pub struct BulkOperationReport {
+    operation: BulkOperation,
+    bucket: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination: Option<PathBuf>,
+    #[serde(serialize_with = "serialize_datetime")]
+    completed_at: DateTime<Local>,
+    succeeded: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+    failed: Vec<BulkOperationFailure>,
+}
+
+impl BulkOperationReport {
+    pub fn download(
+        bucket: &str,
+        destination: &Path,
+        succeeded: &[String],
+        skipped: &[String],
+        failed: &[DownloadFailure],
+    ) -> BulkOperationReport {
+        let failed = failed
+            .iter()
+            .map(|f| BulkOperationFailure {
+                key: f.obj.key.clone(),
+                error: f.error.clone(),
+            })
+            .collect();
+        BulkOperationReport {
+            operation: BulkOperation::Download,
+            bucket: bucket.into(),
+            destination: Some(destination.into()),
+            completed_at: Local::now(),
+            succeeded: succeeded.to_vec(),
+            skipped: skipped.to_vec(),
+            failed,
+        }
+    }
+
+    pub fn delete(
+        bucket: &str,
+        succeeded: &[String],
+        failed: &[DeleteFailure],
+    ) -> BulkOperationReport {
+        let failed = failed
+            .iter()
+            .map(|f| BulkOperationFailure {
+                key: f.key.clone(),
+                error: f.error.clone(),
+            })
+            .collect();
+        BulkOperationReport {
+            operation: BulkOperation::Delete,
+            bucket: bucket.into(),
+            destination: None,
+            completed_at: Local::now(),
+            succeeded: succeeded.to_vec(),
+            skipped: Vec::new(),
+            failed,
+        }
+    }
+
+    // `bucket` and the keys are of the destination
+    pub fn sync(bucket: &str, succeeded: &[String], failed: &[CopyFailure]) -> BulkOperationReport {
+        let failed = failed
+            .iter()
+            .map(|f| BulkOperationFailure {
+                key: f.key.clone(),
+                error: f.error.clone(),
+            })
+            .collect();
+        BulkOperationReport {
+            operation: BulkOperation::Sync,
+            bucket: bucket.into(),
+            destination: None,
+            completed_at: Local::now(),
+            succeeded: succeeded.to_vec(),
+            skipped: Vec::new(),
+            failed,
+        }
+    }
+
+    pub fn tag(bucket: &str, succeeded: &[String], failed: &[TagFailure]) -> BulkOperationReport {
+        let failed = failed
+            .iter()
+            .map(|f| BulkOperationFailure {
+                key: f.key.clone(),
+                error: f.error.clone(),
+            })
+            .collect();
+        BulkOperationReport {
+            operation: BulkOperation::Tag,
+            bucket: bucket.into(),
+            destination: None,
+            completed_at: Local::now(),
+            succeeded: succeeded.to_vec(),
+            skipped: Vec::new(),
+            failed,
+        }
+    }
+
+    pub fn storage_class(
+        bucket: &str,
+        succeeded: &[String],
+        failed: &[CopyFailure],
+    ) -> BulkOperationReport {
+        let failed = failed
+            .iter()
+            .map(|f| BulkOperationFailure {
+                key: f.key.clone(),
+                error: f.error.clone(),
+            })
+            .collect();
+        BulkOperationReport {
+            operation: BulkOperation::StorageClass,
+            bucket: bucket.into(),
+            destination: None,
+            completed_at: Local::now(),
+            succeeded: succeeded.to_vec(),
+            skipped: Vec::new(),
+            failed,
+        }
+    }
+
+    // Saves the report as `<operation>-<timestamp>.json` in `dir` and returns the path.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| AppError::new("Failed to serialize report", e))?;
+        let name = format!(
+            "{}-{}.json",
+            self.operation.str(),
+            self.completed_at.format("%Y%m%d-%H%M%S")
+        );
+        let path = unique_file_path(dir.join(name));
+        save_binary(&path, &json)?;
+        Ok(path)
+    }
+}
+
+impl BulkOperation {
+    fn str(&self) -> &'static str {
+        match self {
+            BulkOperation::Download => "download",
+            BulkOperation::Delete => "delete",
+            BulkOperation::Sync => "sync",
+            BulkOperation::Tag => "tag",
+            BulkOperation::StorageClass => "storage-class",
+        }
+    }
+}
+
+fn serialize_datetime<S: Serializer>(
+    dt: &DateTime<Local>,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_str(&dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_bulk_operation_report_json() {
+        let failed = vec![DeleteFailure {
+            key: "dir/b.txt".to_string(),
+            error: "AccessDenied".to_string(),
+        }];
+        let mut report = BulkOperationReport::delete("bucket", &["dir/a.txt".to_string()], &failed);
+        report.completed_at = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let actual: serde_json::Value = serde_json::to_value(&report).unwrap();
+        let expected = serde_json::json!({
+            "operation": "delete",
+            "bucket": "bucket",
+            "completed_at": report.completed_at.to_rfc3339(),
+            "succeeded": ["dir/a.txt"],
+            "failed": [{ "key": "dir/b.txt", "error": "AccessDenied" }],
+        });
+        assert_eq!(actual, expected);
+    }
+}
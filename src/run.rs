@@ -1,23 +1,38 @@
 use laurier::{key_code, key_code_char};
 use ratatui::{backend::Backend, crossterm::event::KeyCode, Terminal};
-use std::io::Result;
+use std::{io::Result, time::Duration};
 
 use crate::{
     app::{App, Notification},
     event::{AppEventType, Receiver},
+    middleware::MiddlewareChain,
     pages::page::Page,
 };
 
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
 pub async fn run<B: Backend>(
     app: &mut App,
     terminal: &mut Terminal<B>,
     rx: Receiver,
+    mut middlewares: MiddlewareChain,
 ) -> Result<()> {
     loop {
         terminal.draw(|f| app.render(f))?;
 
-        let event = rx.recv();
-        tracing::debug!("event received: {:?}", event);
+        let event = match rx.try_recv() {
+            Some(event) => event,
+            None => match app.next_macro_key() {
+                Some(key) => AppEventType::Key(key),
+                None if app.loading() => {
+                    rx.recv_timeout(TICK_INTERVAL).unwrap_or(AppEventType::Tick)
+                }
+                None => rx.recv(),
+            },
+        };
+        let Some(event) = middlewares.handle(event) else {
+            continue;
+        };
 
         match event {
             AppEventType::Quit => {
@@ -30,7 +45,10 @@ pub async fn run<B: Backend>(
                 }
 
                 if app.loading() {
-                    // Ignore key inputs while loading (except quit)
+                    if matches!(key, key_code!(KeyCode::Esc)) {
+                        app.cancel_loading();
+                    }
+                    // Ignore key inputs while loading (except quit and cancel)
                     continue;
                 }
 
@@ -56,13 +74,69 @@ pub async fn run<B: Backend>(
                     continue;
                 }
 
+                if matches!(key, key_code_char!('q', Ctrl)) {
+                    app.toggle_macro_recording();
+                    continue;
+                }
+
+                if matches!(key, key_code_char!('r', Ctrl)) {
+                    app.replay_macro();
+                    continue;
+                }
+
+                app.record_macro_key(key);
+
+                if app.is_sso_login_confirm_open() {
+                    app.handle_sso_login_confirm_key(key);
+                    continue;
+                }
+
+                if app.is_object_lock_warning_open() {
+                    app.handle_object_lock_warning_key(key);
+                    continue;
+                }
+
+                if app.is_bucket_switcher_open() {
+                    app.handle_bucket_switcher_key(key);
+                    continue;
+                }
+
+                if app.is_profile_switcher_open() {
+                    app.handle_profile_switcher_key(key);
+                    continue;
+                }
+
+                if app.is_region_switcher_open() {
+                    app.handle_region_switcher_key(key);
+                    continue;
+                }
+
+                if matches!(key, key_code_char!('p', Ctrl)) {
+                    app.open_bucket_switcher();
+                    continue;
+                }
+
+                if matches!(key, key_code_char!('t', Ctrl)) {
+                    app.open_transfer_list();
+                    continue;
+                }
+
                 app.page_stack.current_page_mut().handle_key(key);
             }
             AppEventType::Resize(width, height) => {
                 app.resize(width, height);
             }
-            AppEventType::Initialize(client, bucket) => {
-                app.initialize(client, bucket);
+            AppEventType::Tick => {
+                // nothing to do, the screen is redrawn on every event
+            }
+            AppEventType::LoadingTransferProgress(current, total) => {
+                app.update_loading_transfer_progress(current, total);
+            }
+            AppEventType::SlowRequests(count) => {
+                app.set_slow_requests(count);
+            }
+            AppEventType::Initialize(client, bucket, location, start_page) => {
+                app.initialize(client, bucket, location, start_page);
             }
             AppEventType::CompleteInitialize(result) => {
                 app.complete_initialize(result);
@@ -76,26 +150,47 @@ pub async fn run<B: Backend>(
             AppEventType::LoadObjects => {
                 app.load_objects();
             }
-            AppEventType::CompleteLoadObjects(result) => {
-                app.complete_load_objects(result);
+            AppEventType::CompleteLoadObjects(page_id, result) => {
+                app.complete_load_objects(page_id, result);
+            }
+            AppEventType::AppendLoadedObjects(page_id, result) => {
+                app.append_loaded_objects(page_id, result);
             }
             AppEventType::ReloadObjects => {
                 app.reload_objects();
             }
-            AppEventType::CompleteReloadObjects(result) => {
-                app.complete_reload_objects(result);
+            AppEventType::CompleteReloadObjects(page_id, result) => {
+                app.complete_reload_objects(page_id, result);
             }
             AppEventType::LoadObjectDetail => {
                 app.load_object_detail();
             }
-            AppEventType::CompleteLoadObjectDetail(result) => {
-                app.complete_load_object_detail(result);
+            AppEventType::CompleteLoadObjectDetail(page_id, result) => {
+                app.complete_load_object_detail(page_id, result);
+            }
+            AppEventType::LoadObjectLifecycle => {
+                app.load_object_lifecycle();
+            }
+            AppEventType::CompleteLoadObjectLifecycle(result) => {
+                app.complete_load_object_lifecycle(result);
+            }
+            AppEventType::StartComputeObjectMd5(file_detail, map_key) => {
+                app.start_compute_object_md5(file_detail, map_key);
+            }
+            AppEventType::CompleteComputeObjectMd5(result) => {
+                app.complete_compute_object_md5(result);
             }
             AppEventType::LoadObjectVersions => {
                 app.load_object_versions();
             }
-            AppEventType::CompleteLoadObjectVersions(result) => {
-                app.complete_load_object_versions(result);
+            AppEventType::CompleteLoadObjectVersions(page_id, result) => {
+                app.complete_load_object_versions(page_id, result);
+            }
+            AppEventType::LoadMoreObjectVersions => {
+                app.load_more_object_versions();
+            }
+            AppEventType::CompleteLoadMoreObjectVersions(page_id, result) => {
+                app.complete_load_more_object_versions(page_id, result);
             }
             AppEventType::DownloadObject(file_detail, version_id) => {
                 app.download_object(file_detail, version_id);
@@ -103,8 +198,146 @@ pub async fn run<B: Backend>(
             AppEventType::DownloadObjectAs(file_detail, input, version_id) => {
                 app.download_object_as(file_detail, input, version_id);
             }
-            AppEventType::CompleteDownloadObject(result) => {
-                app.complete_download_object(result);
+            AppEventType::CompleteDownloadObject(id, result) => {
+                app.complete_download_object(id, result);
+            }
+            AppEventType::StartLoadAllDownloadObjectList(bucket, key) => {
+                app.start_load_all_download_object_list(bucket, key);
+            }
+            AppEventType::LoadAllDownloadObjectListProgress(count) => {
+                app.load_all_download_object_list_progress(count);
+            }
+            AppEventType::CompleteLoadAllDownloadObjectList(result) => {
+                app.complete_load_all_download_object_list(result);
+            }
+            AppEventType::StartDownloadObjects(bucket, key, objs, options, path) => {
+                app.start_download_objects(bucket, key, objs, options, path);
+            }
+            AppEventType::CompleteDownloadObjects(id, result) => {
+                app.complete_download_objects(id, result);
+            }
+            AppEventType::JobProgress(id, progress) => {
+                app.update_job_progress(id, progress);
+            }
+            AppEventType::CancelJob(id) => {
+                app.cancel_job(id);
+            }
+            AppEventType::RetryJob(id) => {
+                app.retry_job(id);
+            }
+            AppEventType::StartRenameObjects(bucket, prefix, targets) => {
+                app.start_rename_objects(bucket, prefix, targets);
+            }
+            AppEventType::CompleteRenameObjects(result) => {
+                app.complete_rename_objects(result);
+            }
+            AppEventType::UploadObject(bucket, prefix, path, content_type) => {
+                app.upload_object(bucket, prefix, path, content_type);
+            }
+            AppEventType::CompleteUploadObject(result) => {
+                app.complete_upload_object(result);
+            }
+            AppEventType::CompleteFindMultipartUpload(result) => {
+                app.complete_find_multipart_upload(result);
+            }
+            AppEventType::StartMultipartUpload(target, upload) => {
+                app.start_multipart_upload(target, upload);
+            }
+            AppEventType::UploadClipboardImage(bucket, prefix) => {
+                app.upload_clipboard_image(bucket, prefix);
+            }
+            AppEventType::CompleteUploadClipboardImage(result) => {
+                app.complete_upload_clipboard_image(result);
+            }
+            AppEventType::StartWatchDir(bucket, prefix) => {
+                app.start_watch_dir(bucket, prefix);
+            }
+            AppEventType::StopWatchDir => {
+                app.stop_watch_dir();
+            }
+            AppEventType::WatchFileChanged(path) => {
+                app.watch_file_changed(path);
+            }
+            AppEventType::StartWatchUpload(path) => {
+                app.start_watch_upload(path);
+            }
+            AppEventType::CompleteWatchUpload(path, result) => {
+                app.complete_watch_upload(path, result);
+            }
+            AppEventType::CreateFolder(object_key, name) => {
+                app.create_folder(object_key, name);
+            }
+            AppEventType::CompleteCreateFolder(result) => {
+                app.complete_create_folder(result);
+            }
+            AppEventType::StartApplyObjectTags(bucket, keys, tags) => {
+                app.start_apply_object_tags(bucket, keys, tags);
+            }
+            AppEventType::CompleteApplyObjectTags(result) => {
+                app.complete_apply_object_tags(result);
+            }
+            AppEventType::StartChangeStorageClass(bucket, keys, storage_class) => {
+                app.start_change_storage_class(bucket, keys, storage_class);
+            }
+            AppEventType::CompleteChangeStorageClass(result) => {
+                app.complete_change_storage_class(result);
+            }
+            AppEventType::StartUpdateObjectMetadata(map_key, metadata) => {
+                app.start_update_object_metadata(map_key, metadata);
+            }
+            AppEventType::CompleteUpdateObjectMetadata(result) => {
+                app.complete_update_object_metadata(result);
+            }
+            AppEventType::StartSetObjectLegalHold(map_key, on) => {
+                app.start_set_object_legal_hold(map_key, on);
+            }
+            AppEventType::CompleteSetObjectLegalHold(result) => {
+                app.complete_set_object_legal_hold(result);
+            }
+            AppEventType::StartRemoveDeleteMarker(map_key, version) => {
+                app.start_remove_delete_marker(map_key, version);
+            }
+            AppEventType::CompleteRemoveDeleteMarker(result) => {
+                app.complete_remove_delete_marker(result);
+            }
+            AppEventType::StartFindDuplicateObjects(object_key) => {
+                app.start_find_duplicate_objects(object_key);
+            }
+            AppEventType::CompleteFindDuplicateObjects(result) => {
+                app.complete_find_duplicate_objects(result);
+            }
+            AppEventType::StartFindCleanupObjects(object_key) => {
+                app.start_find_cleanup_objects(object_key);
+            }
+            AppEventType::CompleteFindCleanupObjects(result) => {
+                app.complete_find_cleanup_objects(result);
+            }
+            AppEventType::StartCompareObjects(a_key, b_key) => {
+                app.start_compare_objects(a_key, b_key);
+            }
+            AppEventType::CompleteCompareObjects(result) => {
+                app.complete_compare_objects(result);
+            }
+            AppEventType::StartCopyObject(src_key, dst_key) => {
+                app.start_copy_object(src_key, dst_key);
+            }
+            AppEventType::CompleteCopyObject(result) => {
+                app.complete_copy_object(result);
+            }
+            AppEventType::StartSyncObjects(src_key, dst_key, keys) => {
+                app.start_sync_objects(src_key, dst_key, keys);
+            }
+            AppEventType::CompleteSyncObjects(result) => {
+                app.complete_sync_objects(result);
+            }
+            AppEventType::StartDeleteObjects(bucket, keys) => {
+                app.start_delete_objects(bucket, keys);
+            }
+            AppEventType::CompleteCheckObjectLock(result) => {
+                app.complete_check_object_lock(result);
+            }
+            AppEventType::CompleteDeleteObjects(result) => {
+                app.complete_delete_objects(result);
             }
             AppEventType::PreviewObject(file_detail, version_id) => {
                 app.preview_object(file_detail, version_id);
@@ -112,6 +345,18 @@ pub async fn run<B: Backend>(
             AppEventType::CompletePreviewObject(result) => {
                 app.complete_preview_object(result);
             }
+            AppEventType::LoadMorePreview => {
+                app.load_more_preview();
+            }
+            AppEventType::AppendPreviewObject(page_id, result) => {
+                app.append_preview_object(page_id, result);
+            }
+            AppEventType::CompareVersionPreview(file_detail, version) => {
+                app.compare_version_preview(file_detail, version);
+            }
+            AppEventType::CompleteCompareVersionPreview(result) => {
+                app.complete_compare_version_preview(result);
+            }
             AppEventType::BucketListMoveDown => {
                 app.bucket_list_move_down();
             }
@@ -127,6 +372,18 @@ pub async fn run<B: Backend>(
             AppEventType::ObjectListRefresh => {
                 app.object_list_refresh();
             }
+            AppEventType::ToggleDecodeKeyNames => {
+                app.toggle_decode_key_names();
+            }
+            AppEventType::ToggleShowHidden => {
+                app.toggle_show_hidden();
+            }
+            AppEventType::ObjectListDebounceFilter(generation) => {
+                app.object_list_debounce_filter(generation);
+            }
+            AppEventType::ObjectListApplyDebouncedFilter(generation) => {
+                app.object_list_apply_debounced_filter(generation);
+            }
             AppEventType::BackToBucketList => {
                 app.back_to_bucket_list();
             }
@@ -136,12 +393,21 @@ pub async fn run<B: Backend>(
             AppEventType::OpenPreview(file_detail, version_id) => {
                 app.open_preview(file_detail, version_id);
             }
+            AppEventType::OpenCompareVersionPreview(file_detail, version) => {
+                app.open_compare_version_preview(file_detail, version);
+            }
             AppEventType::DetailDownloadObject(file_detail, version_id) => {
                 app.detail_download_object(file_detail, version_id);
             }
             AppEventType::DetailDownloadObjectAs(file_detail, input, version_id) => {
                 app.detail_download_object_as(file_detail, input, version_id);
             }
+            AppEventType::PreviewExportHtml(html, path) => {
+                app.preview_export_html(html, path);
+            }
+            AppEventType::PreviewCompareLocalFile(file_detail, obj, input) => {
+                app.preview_compare_local_file(file_detail, obj, input);
+            }
             AppEventType::PreviewDownloadObject(obj, path) => {
                 app.preview_download_object(obj, path);
             }
@@ -151,9 +417,51 @@ pub async fn run<B: Backend>(
             AppEventType::PreviewRerenderImage => {
                 app.preview_rerender_image();
             }
+            AppEventType::PreviewRefresh => {
+                app.preview_refresh();
+            }
+            AppEventType::CompletePreviewRefresh(page_id, result) => {
+                app.complete_preview_refresh(page_id, result);
+            }
             AppEventType::BucketListOpenManagementConsole => {
                 app.bucket_list_open_management_console();
             }
+            AppEventType::BucketListTogglePin => {
+                app.bucket_list_toggle_pin();
+            }
+            AppEventType::OpenProfileSwitcher => {
+                app.open_profile_switcher();
+            }
+            AppEventType::OpenRegionSwitcher => {
+                app.open_region_switcher();
+            }
+            AppEventType::CompleteSwitchStorage(target, client) => {
+                app.complete_switch_storage(target, client);
+            }
+            AppEventType::SsoLoginPrompt(prompt) => {
+                app.sso_login_prompt(prompt);
+            }
+            AppEventType::CompleteSsoLogin(result) => {
+                app.complete_sso_login(result);
+            }
+            AppEventType::StartLoadBucketMetrics(bucket) => {
+                app.start_load_bucket_metrics(bucket);
+            }
+            AppEventType::CompleteLoadBucketMetrics(result) => {
+                app.complete_load_bucket_metrics(result);
+            }
+            AppEventType::CreateBucket(name, region) => {
+                app.create_bucket(name, region);
+            }
+            AppEventType::CompleteCreateBucket(result) => {
+                app.complete_create_bucket(result);
+            }
+            AppEventType::DeleteBucket(name) => {
+                app.delete_bucket(name);
+            }
+            AppEventType::CompleteDeleteBucket(result) => {
+                app.complete_delete_bucket(result);
+            }
             AppEventType::ObjectListOpenManagementConsole => {
                 app.object_list_open_management_console();
             }
@@ -169,6 +477,45 @@ pub async fn run<B: Backend>(
             AppEventType::CopyToClipboard(name, value) => {
                 app.copy_to_clipboard(name, value);
             }
+            AppEventType::CopyPresignedUrl(bucket, key, version_id, expires_in) => {
+                app.copy_presigned_url(bucket, key, version_id, expires_in);
+            }
+            AppEventType::CopyPresignedPutUrl(bucket, key, expires_in) => {
+                app.copy_presigned_put_url(bucket, key, expires_in);
+            }
+            AppEventType::PasteLocation => {
+                app.paste_location();
+            }
+            AppEventType::OpenLocation(location) => {
+                app.open_location(location);
+            }
+            AppEventType::CompleteOpenLocation(result) => {
+                app.complete_open_location(result);
+            }
+            AppEventType::StartLoadInventory(location) => {
+                app.start_load_inventory(location);
+            }
+            AppEventType::CompleteLoadInventory(result) => {
+                app.complete_load_inventory(result);
+            }
+            AppEventType::StartAnalyzeAccessLogs(bucket, prefix) => {
+                app.start_analyze_access_logs(bucket, prefix);
+            }
+            AppEventType::CompleteAnalyzeAccessLogs(result) => {
+                app.complete_analyze_access_logs(result);
+            }
+            AppEventType::ScheduleTask(task_type, object_key, prefix, minutes) => {
+                app.schedule_task(task_type, object_key, prefix, minutes);
+            }
+            AppEventType::CancelScheduledTasks => {
+                app.cancel_scheduled_tasks();
+            }
+            AppEventType::RunScheduledTask(id) => {
+                app.run_scheduled_task(id);
+            }
+            AppEventType::CompleteScheduledTask(id, result) => {
+                app.complete_scheduled_task(id, result);
+            }
             AppEventType::NotifyInfo(msg) => {
                 app.info_notification(msg);
             }
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use tokio::{spawn, task::AbortHandle};
+
+use crate::{
+    event::{AppEventType, Sender},
+    object::{DownloadFailure, ObjectItem, ObjectKey},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledTaskType {
+    // reload the object list of the folder
+    Refresh,
+    // download the objects under the prefix that do not exist locally with the same size
+    Download,
+}
+
+impl ScheduledTaskType {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Refresh => "refresh",
+            Self::Download => "download",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ScheduledTaskOutcome {
+    Refreshed(Vec<ObjectItem>),
+    Downloaded {
+        downloaded: usize,
+        skipped: usize,
+        failed: Vec<DownloadFailure>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ScheduledTask {
+    pub id: usize,
+    pub task_type: ScheduledTaskType,
+    // the folder where the task is scheduled
+    pub object_key: ObjectKey,
+    pub prefix: String,
+    pub interval_minutes: u64,
+    // the previous run is not finished, the next one is skipped
+    pub running: bool,
+    handle: AbortHandle,
+}
+
+impl ScheduledTask {
+    pub fn s3_uri(&self) -> String {
+        format!("s3://{}/{}", self.object_key.bucket_name, self.prefix)
+    }
+}
+
+// Tasks are repeated while the app is running, they are not persisted
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    tasks: Vec<ScheduledTask>,
+    next_id: usize,
+}
+
+impl Scheduler {
+    // The task runs immediately, and then every `interval_minutes`
+    pub fn add(
+        &mut self,
+        task_type: ScheduledTaskType,
+        object_key: ObjectKey,
+        prefix: String,
+        interval_minutes: u64,
+        tx: Sender,
+    ) -> &ScheduledTask {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let period = Duration::from_secs(interval_minutes * 60);
+        let handle = spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                tx.send(AppEventType::RunScheduledTask(id));
+            }
+        });
+
+        self.tasks.push(ScheduledTask {
+            id,
+            task_type,
+            object_key,
+            prefix,
+            interval_minutes,
+            running: false,
+            handle: handle.abort_handle(),
+        });
+        self.tasks.last().unwrap()
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut ScheduledTask> {
+        self.tasks.iter_mut().find(|t| t.id == id)
+    }
+
+    // Returns the number of the cancelled tasks
+    pub fn cancel_all(&mut self) -> usize {
+        let n = self.tasks.len();
+        for task in self.tasks.drain(..) {
+            task.handle.abort();
+        }
+        n
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.cancel_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scheduler() {
+        let (tx, rx) = event::new();
+        let mut scheduler = Scheduler::default();
+        let key = ObjectKey {
+            bucket_name: "bucket".into(),
+            object_path: vec!["logs".into()],
+        };
+
+        let task = scheduler.add(ScheduledTaskType::Refresh, key, "logs/".into(), 5, tx);
+        assert_eq!(task.s3_uri(), "s3://bucket/logs/");
+        let id = task.id;
+
+        // the first run is triggered immediately
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let triggered = std::iter::from_fn(|| rx.try_recv())
+            .any(|e| matches!(e, AppEventType::RunScheduledTask(i) if i == id));
+        assert!(triggered);
+
+        assert!(scheduler.get_mut(id).is_some());
+        assert_eq!(scheduler.cancel_all(), 1);
+        assert!(scheduler.get_mut(id).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::config::MetricsFormat;
+
+// Counts the storage requests of the session, written to a file on quit if `metrics.format` is set
+#[derive(Debug)]
+pub struct SessionMetrics {
+    started_at: DateTime<Local>,
+    counters: Mutex<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    operations: BTreeMap<&'static str, OperationMetrics>,
+    downloaded_bytes: usize,
+    uploaded_bytes: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct OperationMetrics {
+    pub requests: usize,
+    pub errors: usize,
+    // the sum of the elapsed time, so it can exceed the session duration with concurrent requests
+    pub duration_seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsSnapshot {
+    // RFC 3339
+    pub started_at: String,
+    pub start_time_seconds: i64,
+    pub duration_seconds: f64,
+    pub downloaded_bytes: usize,
+    pub uploaded_bytes: usize,
+    pub operations: BTreeMap<&'static str, OperationMetrics>,
+}
+
+impl SessionMetrics {
+    pub fn new(started_at: DateTime<Local>) -> SessionMetrics {
+        SessionMetrics {
+            started_at,
+            counters: Mutex::default(),
+        }
+    }
+
+    pub fn record(&self, operation: &'static str, elapsed: Duration, ok: bool) {
+        let mut counters = self.counters.lock().unwrap();
+        let metrics = counters.operations.entry(operation).or_default();
+        metrics.requests += 1;
+        if !ok {
+            metrics.errors += 1;
+        }
+        metrics.duration_seconds += elapsed.as_secs_f64();
+    }
+
+    pub fn add_downloaded_bytes(&self, n: usize) {
+        self.counters.lock().unwrap().downloaded_bytes += n;
+    }
+
+    pub fn add_uploaded_bytes(&self, n: usize) {
+        self.counters.lock().unwrap().uploaded_bytes += n;
+    }
+
+    pub fn snapshot(&self, now: DateTime<Local>) -> MetricsSnapshot {
+        let counters = self.counters.lock().unwrap();
+        let duration = (now - self.started_at).to_std().unwrap_or_default();
+        MetricsSnapshot {
+            started_at: self.started_at.to_rfc3339(),
+            start_time_seconds: self.started_at.timestamp(),
+            duration_seconds: duration.as_secs_f64(),
+            downloaded_bytes: counters.downloaded_bytes,
+            uploaded_bytes: counters.uploaded_bytes,
+            operations: counters.operations.clone(),
+        }
+    }
+
+    pub fn write(
+        &self,
+        path: &Path,
+        format: MetricsFormat,
+        now: DateTime<Local>,
+    ) -> anyhow::Result<()> {
+        let snapshot = self.snapshot(now);
+        let content = match format {
+            MetricsFormat::None => return Ok(()),
+            MetricsFormat::Prometheus => snapshot.to_prometheus(),
+            MetricsFormat::Json => serde_json::to_string_pretty(&snapshot)? + "\n",
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl MetricsSnapshot {
+    // The text exposition format, which can be collected by the textfile collector of node_exporter
+    pub fn to_prometheus(&self) -> String {
+        let mut s = String::new();
+        let mut metric =
+            |name: &str, metric_type: &str, help: &str, values: Vec<(String, String)>| {
+                let _ = writeln!(s, "# HELP {} {}", name, help);
+                let _ = writeln!(s, "# TYPE {} {}", name, metric_type);
+                for (labels, value) in values {
+                    let _ = writeln!(s, "{}{} {}", name, labels, value);
+                }
+            };
+        let per_operation = |f: fn(&OperationMetrics) -> String| {
+            self.operations
+                .iter()
+                .map(|(name, m)| (format!("{{operation=\"{}\"}}", name), f(m)))
+                .collect()
+        };
+        let single = |value: String| vec![(String::new(), value)];
+
+        metric(
+            "stu_session_start_time_seconds",
+            "gauge",
+            "Start time of the session since unix epoch in seconds.",
+            single(self.start_time_seconds.to_string()),
+        );
+        metric(
+            "stu_session_duration_seconds",
+            "gauge",
+            "Duration of the session in seconds.",
+            single(self.duration_seconds.to_string()),
+        );
+        metric(
+            "stu_requests_total",
+            "counter",
+            "Number of storage requests per operation.",
+            per_operation(|m| m.requests.to_string()),
+        );
+        metric(
+            "stu_request_errors_total",
+            "counter",
+            "Number of failed storage requests per operation.",
+            per_operation(|m| m.errors.to_string()),
+        );
+        metric(
+            "stu_request_duration_seconds_total",
+            "counter",
+            "Total elapsed time of storage requests per operation in seconds.",
+            per_operation(|m| m.duration_seconds.to_string()),
+        );
+        metric(
+            "stu_downloaded_bytes_total",
+            "counter",
+            "Number of bytes downloaded.",
+            single(self.downloaded_bytes.to_string()),
+        );
+        metric(
+            "stu_uploaded_bytes_total",
+            "counter",
+            "Number of bytes uploaded.",
+            single(self.uploaded_bytes.to_string()),
+        );
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_session_metrics() {
+        let started_at = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let metrics = SessionMetrics::new(started_at);
+        metrics.record("download_object", Duration::from_millis(500), true);
+        metrics.record("download_object", Duration::from_millis(250), false);
+        metrics.record("load_objects", Duration::from_secs(1), true);
+        metrics.add_downloaded_bytes(1024);
+        metrics.add_uploaded_bytes(10);
+
+        let snapshot = metrics.snapshot(started_at + chrono::Duration::seconds(90));
+        let text = snapshot.to_prometheus();
+        let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        let expected = [
+            format!("stu_session_start_time_seconds {}", started_at.timestamp()),
+            "stu_session_duration_seconds 90".to_string(),
+            "stu_requests_total{operation=\"download_object\"} 2".to_string(),
+            "stu_requests_total{operation=\"load_objects\"} 1".to_string(),
+            "stu_request_errors_total{operation=\"download_object\"} 1".to_string(),
+            "stu_request_errors_total{operation=\"load_objects\"} 0".to_string(),
+            "stu_request_duration_seconds_total{operation=\"download_object\"} 0.75".to_string(),
+            "stu_request_duration_seconds_total{operation=\"load_objects\"} 1".to_string(),
+            "stu_downloaded_bytes_total 1024".to_string(),
+            "stu_uploaded_bytes_total 10".to_string(),
+        ];
+        assert_eq!(lines, expected);
+        assert!(text.contains("# TYPE stu_requests_total counter\n"));
+
+        let json: serde_json::Value = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["duration_seconds"], 90.0);
+        assert_eq!(json["downloaded_bytes"], 1024);
+        assert_eq!(json["operations"]["download_object"]["requests"], 2);
+        assert_eq!(json["operations"]["download_object"]["errors"], 1);
+    }
+}
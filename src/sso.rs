@@ -0,0 +1,192 @@
+use std::{error::Error, process::Stdio};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+    process::Command,
+};
+
+use crate::error::{AppError, Result};
+
+// The messages of the errors returned when the cached SSO token cannot be used.
+// The SDK does not expose the error types, so they are detected by the messages.
+const EXPIRED_SESSION_MESSAGES: &[&str] = &[
+    "the SSO token has expired and cannot be refreshed",
+    "failed to load the cached SSO token",
+    // returned by GetRoleCredentials if the token has been expired or revoked
+    "Session token not found or invalid",
+];
+
+pub fn is_sso_session_expired(e: &AppError) -> bool {
+    let Some(cause) = &e.cause else {
+        return false;
+    };
+    // the messages of the inner errors are not always included in the outer ones
+    let mut messages = vec![format!("{:?}", cause)];
+    let mut source: Option<&dyn Error> = Some(cause.as_ref());
+    while let Some(e) = source {
+        messages.push(e.to_string());
+        source = e.source();
+    }
+    messages
+        .iter()
+        .any(|m| EXPIRED_SESSION_MESSAGES.iter().any(|s| m.contains(s)))
+}
+
+// The instructions printed by `aws sso login`, which are shown while waiting for the login.
+// Only the URL is printed in the authorization code flow, and the code is also printed in the device flow.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SsoLoginPrompt {
+    pub url: Option<String>,
+    pub code: Option<String>,
+}
+
+impl SsoLoginPrompt {
+    // Returns true if the line has updated the prompt
+    fn update(&mut self, line: &str) -> bool {
+        let line = line.trim();
+        if self.url.is_none() && line.starts_with("https://") {
+            self.url = Some(line.to_string());
+            return true;
+        }
+        if self.code.is_none() && is_user_code(line) {
+            self.code = Some(line.to_string());
+            return true;
+        }
+        false
+    }
+}
+
+// e.g. "ABCD-EFGH"
+fn is_user_code(s: &str) -> bool {
+    match s.split_once('-') {
+        Some((a, b)) => [a, b].iter().all(|p| {
+            p.len() == 4
+                && p.chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        }),
+        None => false,
+    }
+}
+
+// Runs `aws sso login` until the login is completed in the browser.
+// The process is killed if the returned future is dropped (cancelled).
+pub async fn sso_login(profile: Option<String>, on_prompt: impl Fn(SsoLoginPrompt)) -> Result<()> {
+    let mut command = Command::new("aws");
+    command.args(["sso", "login"]);
+    if let Some(profile) = &profile {
+        command.args(["--profile", profile]);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::new("Failed to run aws sso login", e))?;
+
+    // both streams are read at once, otherwise the process blocks if the stderr pipe is full
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let read_stdout = async {
+        let mut prompt = SsoLoginPrompt::default();
+        if let Some(stdout) = stdout {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if prompt.update(&line) {
+                    on_prompt(prompt.clone());
+                }
+            }
+        }
+    };
+    let read_stderr = async {
+        let mut buf = String::new();
+        if let Some(mut s) = stderr {
+            let _ = s.read_to_string(&mut buf).await;
+        }
+        buf
+    };
+    let ((), stderr) = tokio::join!(read_stdout, read_stderr);
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| AppError::new("Failed to run aws sso login", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        let msg = match stderr.trim() {
+            "" => format!("aws sso login failed ({})", status),
+            s => format!("aws sso login failed: {}", s.lines().last().unwrap_or(s)),
+        };
+        Err(AppError::msg(msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestError(&'static str, Option<Box<TestError>>);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Error for TestError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_deref().map(|e| e as &(dyn Error + 'static))
+        }
+    }
+
+    #[test]
+    fn test_is_sso_session_expired() {
+        let inner = TestError("the SSO token has expired and cannot be refreshed", None);
+        let e = AppError::new(
+            "Failed to load buckets",
+            TestError("dispatch failure", Some(Box::new(inner))),
+        );
+        assert!(is_sso_session_expired(&e));
+
+        let e = AppError::new(
+            "Failed to load buckets",
+            TestError("dispatch failure", None),
+        );
+        assert!(!is_sso_session_expired(&e));
+
+        let e = AppError::msg("the SSO token has expired and cannot be refreshed");
+        assert!(!is_sso_session_expired(&e));
+    }
+
+    #[test]
+    fn test_sso_login_prompt_update() {
+        let output = [
+            "Attempting to automatically open the SSO authorization page in your default browser.",
+            "If the browser does not open or you wish to use a different device to authorize this request, open the following URL:",
+            "",
+            "https://device.sso.us-east-1.amazonaws.com/",
+            "",
+            "Then enter the code:",
+            "",
+            "WDKC-QRXJ",
+            "Successfully logged into Start URL: https://example.awsapps.com/start",
+        ];
+        let mut prompt = SsoLoginPrompt::default();
+        let updated: Vec<bool> = output.iter().map(|line| prompt.update(line)).collect();
+        assert_eq!(
+            updated,
+            [false, false, false, true, false, false, false, true, false]
+        );
+        assert_eq!(
+            prompt,
+            SsoLoginPrompt {
+                url: Some("https://device.sso.us-east-1.amazonaws.com/".into()),
+                code: Some("WDKC-QRXJ".into()),
+            }
+        );
+    }
+}
@@ -0,0 +1,1308 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tracing::{field::Empty, Instrument};
+
+use crate::{
+    bucket_metrics::BucketMetrics,
+    client::Client,
+    download_list::DownloadObjectList,
+    error::{AppError, Result},
+    lifecycle::LifecycleRule,
+    multipart::{MultipartUpload, UploadedPart},
+    object::{
+        BucketItem, DeleteFailure, FileDetail, FileVersions, FileVersionsMarker, ObjectItem,
+        ObjectMetadata, ObjectSummary, ObjectsPage, RawObject,
+    },
+    session_metrics::SessionMetrics,
+};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// Called with the number of bytes downloaded or the number of objects listed so far.
+pub type Progress = Box<dyn Fn(usize) + Send>;
+
+// Called with the number of the requests running longer than the threshold, whenever it changes.
+pub type SlowRequestCallback = Arc<dyn Fn(usize) + Send + Sync>;
+
+// The AWS profile and region to build the storage with, None if resolved from the environment.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageTarget {
+    pub profile: Option<String>,
+    pub region: Option<String>,
+}
+
+// Builds the storage for the given target, so that the profile and the region can be switched without restarting.
+#[derive(Clone)]
+pub struct StorageFactory {
+    build: Arc<dyn Fn(StorageTarget) -> BoxFuture<'static, Arc<dyn Storage>> + Send + Sync>,
+}
+
+impl StorageFactory {
+    pub fn new(
+        build: impl Fn(StorageTarget) -> BoxFuture<'static, Arc<dyn Storage>> + Send + Sync + 'static,
+    ) -> StorageFactory {
+        StorageFactory {
+            build: Arc::new(build),
+        }
+    }
+
+    pub fn build(&self, target: StorageTarget) -> BoxFuture<'static, Arc<dyn Storage>> {
+        (self.build)(target)
+    }
+}
+
+impl Debug for StorageFactory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StorageFactory")
+    }
+}
+
+// The app accesses the storage only through these traits,
+// so that other implementations (mocks, decorators, other backends) can be used instead of `Client`.
+// The methods return boxed futures to keep the traits object safe.
+
+pub trait ObjectStore {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>>;
+
+    // Loads a page of the object list, so that the list can be shown before all pages are loaded.
+    // The token is the one returned with the previous page.
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>>;
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>>;
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>>;
+
+    // Whether the object exists, which is not an error if it does not
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>>;
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>>;
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>>;
+
+    fn load_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>>;
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>>;
+
+    // The bytes of `len` from `offset`, which are fewer at the end of the object
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>>;
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    // The Content-Type is detected from the bytes if it is not specified
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    // Replaces all the tags of the object
+    fn put_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn change_storage_class<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        on: bool,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    // `key` ends with the delimiter, the created folder is returned to be shown in the list
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>>;
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    fn delete_object_version<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    // `progress` is called with the number of processed keys
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>>;
+}
+
+pub trait BucketAdmin {
+    fn region(&self) -> &str;
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>>;
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>>;
+
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>>;
+
+    // Objects in the bucket can be locked only if this is true
+    fn is_object_lock_enabled<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<bool>>;
+
+    // Size and object count history of the bucket
+    fn load_bucket_metrics<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>>;
+
+    fn create_bucket<'a>(&'a self, name: &'a str, region: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    // Fails if the bucket is not empty
+    fn delete_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+pub trait Presigner {
+    fn presign_get_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>>;
+
+    // Anyone with the URL can upload to the key until it expires
+    fn presign_put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>>;
+}
+
+// Large files are uploaded in parts so that an interrupted upload can be resumed later
+pub trait MultipartUploader {
+    fn supports_multipart_upload(&self) -> bool;
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>>;
+
+    // Returns the upload id
+    fn create_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>>;
+
+    // Returns the ETag of the part
+    fn upload_part<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        part_number: i32,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>>;
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>>;
+
+    // Deletes the uploaded parts, used when the upload cannot be resumed
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+pub trait ManagementConsole {
+    fn open_management_console_buckets(&self) -> Result<()>;
+
+    fn open_management_console_list(&self, bucket: &str, prefix: &str) -> Result<()>;
+
+    fn open_management_console_object(&self, bucket: &str, prefix: &str) -> Result<()>;
+}
+
+pub trait Storage:
+    ObjectStore + BucketAdmin + Presigner + MultipartUploader + ManagementConsole + Debug + Send + Sync
+{
+}
+
+impl<T> Storage for T where
+    T: ObjectStore
+        + BucketAdmin
+        + Presigner
+        + MultipartUploader
+        + ManagementConsole
+        + Debug
+        + Send
+        + Sync
+{
+}
+
+// For the backends that list all objects at once
+pub fn load_objects_in_one_page(
+    items: BoxFuture<'_, Result<Vec<ObjectItem>>>,
+) -> BoxFuture<'_, Result<ObjectsPage>> {
+    Box::pin(async move {
+        let items = items.await?;
+        Ok(ObjectsPage {
+            items,
+            next_token: None,
+        })
+    })
+}
+
+// For the backends that upload the whole file at once
+pub fn multipart_upload_not_supported<'a, T: Send + 'a>() -> BoxFuture<'a, Result<T>> {
+    Box::pin(async { Err(AppError::msg("Multipart uploads are not supported")) })
+}
+
+impl ObjectStore for Client {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>> {
+        Box::pin(Client::load_objects(self, bucket, prefix))
+    }
+
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>> {
+        Box::pin(Client::load_objects_page(self, bucket, prefix, token))
+    }
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>> {
+        Box::pin(Client::list_all_download_objects(
+            self, bucket, prefix, progress,
+        ))
+    }
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>> {
+        Box::pin(Client::list_all_object_summaries(self, bucket, prefix))
+    }
+
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(Client::object_exists(self, bucket, key))
+    }
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>> {
+        Box::pin(Client::load_object_detail(
+            self, bucket, key, name, size_byte,
+        ))
+    }
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>> {
+        Box::pin(Client::load_object_versions(self, bucket, key, marker))
+    }
+
+    fn load_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(Client::load_object_tags(self, bucket, key))
+    }
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(Client::download_object(
+            self, bucket, key, version_id, if_match, size_byte, progress,
+        ))
+    }
+
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(Client::download_object_range(
+            self, bucket, key, version_id, if_match, offset, len,
+        ))
+    }
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::copy_object(
+            self, src_bucket, src_key, dst_bucket, dst_key,
+        ))
+    }
+
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::put_object(self, bucket, key, bytes, content_type))
+    }
+
+    fn put_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::put_object_tags(self, bucket, key, tags))
+    }
+
+    fn change_storage_class<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::change_storage_class(
+            self,
+            bucket,
+            key,
+            storage_class,
+        ))
+    }
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::update_object_metadata(self, bucket, key, metadata))
+    }
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        on: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::set_object_legal_hold(self, bucket, key, on))
+    }
+
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>> {
+        Box::pin(Client::create_folder(self, bucket, key))
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::delete_object(self, bucket, key))
+    }
+
+    fn delete_object_version<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::delete_object_version(self, bucket, key, version_id))
+    }
+
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>> {
+        Box::pin(Client::delete_objects(self, bucket, keys, progress))
+    }
+}
+
+impl BucketAdmin for Client {
+    fn region(&self) -> &str {
+        Client::region(self)
+    }
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>> {
+        Box::pin(Client::load_all_buckets(self))
+    }
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>> {
+        Box::pin(Client::load_bucket(self, name))
+    }
+
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>> {
+        Box::pin(Client::load_lifecycle_rules(self, bucket))
+    }
+
+    fn is_object_lock_enabled<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(Client::is_object_lock_enabled(self, bucket))
+    }
+
+    fn load_bucket_metrics<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>> {
+        Box::pin(Client::load_bucket_metrics(self, bucket))
+    }
+
+    fn create_bucket<'a>(&'a self, name: &'a str, region: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::create_bucket(self, name, region))
+    }
+
+    fn delete_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::delete_bucket(self, name))
+    }
+}
+
+impl Presigner for Client {
+    fn presign_get_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(Client::presign_get_object(
+            self, bucket, key, version_id, expires_in,
+        ))
+    }
+
+    fn presign_put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(Client::presign_put_object(self, bucket, key, expires_in))
+    }
+}
+
+impl MultipartUploader for Client {
+    fn supports_multipart_upload(&self) -> bool {
+        true
+    }
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>> {
+        Box::pin(Client::find_multipart_upload(self, bucket, key))
+    }
+
+    fn create_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(Client::create_multipart_upload(
+            self,
+            bucket,
+            key,
+            content_type,
+        ))
+    }
+
+    fn upload_part<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        part_number: i32,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(Client::upload_part(
+            self,
+            bucket,
+            key,
+            upload_id,
+            part_number,
+            bytes,
+        ))
+    }
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::complete_multipart_upload(
+            self, bucket, key, upload_id, parts,
+        ))
+    }
+
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(Client::abort_multipart_upload(self, bucket, key, upload_id))
+    }
+}
+
+impl ManagementConsole for Client {
+    fn open_management_console_buckets(&self) -> Result<()> {
+        Client::open_management_console_buckets(self)
+    }
+
+    fn open_management_console_list(&self, bucket: &str, prefix: &str) -> Result<()> {
+        Client::open_management_console_list(self, bucket, prefix)
+    }
+
+    fn open_management_console_object(&self, bucket: &str, prefix: &str) -> Result<()> {
+        Client::open_management_console_object(self, bucket, prefix)
+    }
+}
+
+// Decorates a storage with the cross-cutting concerns:
+//
+// - logs the elapsed time of each request
+// - counts the requests and the transferred bytes for the session metrics
+// - counts the slow requests to indicate them
+// - rejects the operations that modify objects in read-only mode
+//
+// Retries are not added here since the AWS SDK already retries the requests.
+#[derive(Debug)]
+pub struct GuardedStorage {
+    inner: Arc<dyn Storage>,
+    read_only: bool,
+    metrics: Arc<SessionMetrics>,
+    slow_requests: Option<SlowRequestWatcher>,
+}
+
+impl GuardedStorage {
+    pub fn new(
+        inner: Arc<dyn Storage>,
+        read_only: bool,
+        metrics: Arc<SessionMetrics>,
+    ) -> GuardedStorage {
+        GuardedStorage {
+            inner,
+            read_only,
+            metrics,
+            slow_requests: None,
+        }
+    }
+
+    pub fn slow_request_watcher(
+        mut self,
+        threshold: Duration,
+        callback: SlowRequestCallback,
+    ) -> GuardedStorage {
+        self.slow_requests = Some(SlowRequestWatcher {
+            threshold,
+            count: AtomicUsize::new(0),
+            callback,
+        });
+        self
+    }
+
+    // Each operation is recorded as a span, which is exported if OpenTelemetry is enabled
+    async fn timed<T>(&self, name: &'static str, f: impl Future<Output = Result<T>>) -> Result<T> {
+        let span = tracing::info_span!(
+            "storage",
+            otel.name = name,
+            otel.status_code = Empty,
+            error.message = Empty,
+        );
+        #[cfg(feature = "otel")]
+        let f = crate::telemetry::with_span_context(f, &span);
+
+        let start = std::time::Instant::now();
+        let f = f.instrument(span.clone());
+        let result = match &self.slow_requests {
+            Some(watcher) => watcher.watch(f).await,
+            None => f.await,
+        };
+        if let Err(e) = &result {
+            span.record("otel.status_code", "ERROR");
+            span.record("error.message", e.msg.as_str());
+        }
+        tracing::debug!(
+            "{} finished in {:?} (ok: {})",
+            name,
+            start.elapsed(),
+            result.is_ok()
+        );
+        self.metrics.record(name, start.elapsed(), result.is_ok());
+        result
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            Err(AppError::msg(
+                "This operation is not allowed in read-only mode",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct SlowRequestWatcher {
+    threshold: Duration,
+    count: AtomicUsize,
+    callback: SlowRequestCallback,
+}
+
+impl Debug for SlowRequestWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SlowRequestWatcher {{ threshold: {:?} }}",
+            self.threshold
+        )
+    }
+}
+
+impl SlowRequestWatcher {
+    async fn watch<T>(&self, f: impl Future<Output = T>) -> T {
+        tokio::pin!(f);
+        tokio::select! {
+            ret = &mut f => return ret,
+            _ = tokio::time::sleep(self.threshold) => {}
+        }
+        let _slow = SlowRequest::new(self);
+        f.await
+    }
+}
+
+// Counted as slow until dropped, so that the cancelled requests are not counted anymore
+struct SlowRequest<'a>(&'a SlowRequestWatcher);
+
+impl<'a> SlowRequest<'a> {
+    fn new(watcher: &'a SlowRequestWatcher) -> SlowRequest<'a> {
+        let count = watcher.count.fetch_add(1, Ordering::SeqCst) + 1;
+        (watcher.callback)(count);
+        SlowRequest(watcher)
+    }
+}
+
+impl Drop for SlowRequest<'_> {
+    fn drop(&mut self) {
+        let count = self.0.count.fetch_sub(1, Ordering::SeqCst) - 1;
+        (self.0.callback)(count);
+    }
+}
+
+impl ObjectStore for GuardedStorage {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>> {
+        Box::pin(self.timed("load_objects", self.inner.load_objects(bucket, prefix)))
+    }
+
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>> {
+        Box::pin(self.timed(
+            "load_objects_page",
+            self.inner.load_objects_page(bucket, prefix, token),
+        ))
+    }
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>> {
+        Box::pin(
+            self.timed(
+                "list_all_download_objects",
+                self.inner
+                    .list_all_download_objects(bucket, prefix, progress),
+            ),
+        )
+    }
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>> {
+        Box::pin(self.timed(
+            "list_all_object_summaries",
+            self.inner.list_all_object_summaries(bucket, prefix),
+        ))
+    }
+
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(self.timed("object_exists", self.inner.object_exists(bucket, key)))
+    }
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>> {
+        Box::pin(self.timed(
+            "load_object_detail",
+            self.inner.load_object_detail(bucket, key, name, size_byte),
+        ))
+    }
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>> {
+        Box::pin(self.timed(
+            "load_object_versions",
+            self.inner.load_object_versions(bucket, key, marker),
+        ))
+    }
+
+    fn load_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(self.timed("load_object_tags", self.inner.load_object_tags(bucket, key)))
+    }
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(async move {
+            self.timed(
+                "download_object",
+                self.inner
+                    .download_object(bucket, key, version_id, if_match, size_byte, progress),
+            )
+            .await
+            .inspect(|obj| self.metrics.add_downloaded_bytes(obj.bytes.len()))
+        })
+    }
+
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(async move {
+            self.timed(
+                "download_object_range",
+                self.inner
+                    .download_object_range(bucket, key, version_id, if_match, offset, len),
+            )
+            .await
+            .inspect(|obj| self.metrics.add_downloaded_bytes(obj.bytes.len()))
+        })
+    }
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "copy_object",
+                self.inner
+                    .copy_object(src_bucket, src_key, dst_bucket, dst_key),
+            )
+            .await
+        })
+    }
+
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            let len = bytes.len();
+            self.timed(
+                "put_object",
+                self.inner.put_object(bucket, key, bytes, content_type),
+            )
+            .await
+            .inspect(|_| self.metrics.add_uploaded_bytes(len))
+        })
+    }
+
+    fn put_object_tags<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "put_object_tags",
+                self.inner.put_object_tags(bucket, key, tags),
+            )
+            .await
+        })
+    }
+
+    fn change_storage_class<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "change_storage_class",
+                self.inner.change_storage_class(bucket, key, storage_class),
+            )
+            .await
+        })
+    }
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "update_object_metadata",
+                self.inner.update_object_metadata(bucket, key, metadata),
+            )
+            .await
+        })
+    }
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        on: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "set_object_legal_hold",
+                self.inner.set_object_legal_hold(bucket, key, on),
+            )
+            .await
+        })
+    }
+
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed("create_folder", self.inner.create_folder(bucket, key))
+                .await
+        })
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed("delete_object", self.inner.delete_object(bucket, key))
+                .await
+        })
+    }
+
+    fn delete_object_version<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "delete_object_version",
+                self.inner.delete_object_version(bucket, key, version_id),
+            )
+            .await
+        })
+    }
+
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "delete_objects",
+                self.inner.delete_objects(bucket, keys, progress),
+            )
+            .await
+        })
+    }
+}
+
+impl BucketAdmin for GuardedStorage {
+    fn region(&self) -> &str {
+        self.inner.region()
+    }
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>> {
+        Box::pin(self.timed("load_all_buckets", self.inner.load_all_buckets()))
+    }
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>> {
+        Box::pin(self.timed("load_bucket", self.inner.load_bucket(name)))
+    }
+
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>> {
+        Box::pin(self.timed(
+            "load_lifecycle_rules",
+            self.inner.load_lifecycle_rules(bucket),
+        ))
+    }
+
+    fn is_object_lock_enabled<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(self.timed(
+            "is_object_lock_enabled",
+            self.inner.is_object_lock_enabled(bucket),
+        ))
+    }
+
+    fn load_bucket_metrics<'a>(&'a self, bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>> {
+        Box::pin(self.timed(
+            "load_bucket_metrics",
+            self.inner.load_bucket_metrics(bucket),
+        ))
+    }
+
+    fn create_bucket<'a>(&'a self, name: &'a str, region: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed("create_bucket", self.inner.create_bucket(name, region))
+                .await
+        })
+    }
+
+    fn delete_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed("delete_bucket", self.inner.delete_bucket(name))
+                .await
+        })
+    }
+}
+
+impl Presigner for GuardedStorage {
+    fn presign_get_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        version_id: Option<String>,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        self.inner
+            .presign_get_object(bucket, key, version_id, expires_in)
+    }
+
+    // Generating the URL does not modify anything, but it allows others to upload
+    fn presign_put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.inner.presign_put_object(bucket, key, expires_in).await
+        })
+    }
+}
+
+impl MultipartUploader for GuardedStorage {
+    fn supports_multipart_upload(&self) -> bool {
+        self.inner.supports_multipart_upload()
+    }
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>> {
+        Box::pin(self.timed(
+            "find_multipart_upload",
+            self.inner.find_multipart_upload(bucket, key),
+        ))
+    }
+
+    fn create_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "create_multipart_upload",
+                self.inner
+                    .create_multipart_upload(bucket, key, content_type),
+            )
+            .await
+        })
+    }
+
+    fn upload_part<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        part_number: i32,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            let len = bytes.len();
+            self.timed(
+                "upload_part",
+                self.inner
+                    .upload_part(bucket, key, upload_id, part_number, bytes),
+            )
+            .await
+            .inspect(|_| self.metrics.add_uploaded_bytes(len))
+        })
+    }
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+        parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "complete_multipart_upload",
+                self.inner
+                    .complete_multipart_upload(bucket, key, upload_id, parts),
+            )
+            .await
+        })
+    }
+
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.check_writable()?;
+            self.timed(
+                "abort_multipart_upload",
+                self.inner.abort_multipart_upload(bucket, key, upload_id),
+            )
+            .await
+        })
+    }
+}
+
+impl ManagementConsole for GuardedStorage {
+    fn open_management_console_buckets(&self) -> Result<()> {
+        self.inner.open_management_console_buckets()
+    }
+
+    fn open_management_console_list(&self, bucket: &str, prefix: &str) -> Result<()> {
+        self.inner.open_management_console_list(bucket, prefix)
+    }
+
+    fn open_management_console_object(&self, bucket: &str, prefix: &str) -> Result<()> {
+        self.inner.open_management_console_object(bucket, prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slow_request_watcher() {
+        let counts = Arc::new(Mutex::new(Vec::new()));
+        let watcher = {
+            let counts = counts.clone();
+            SlowRequestWatcher {
+                threshold: Duration::from_millis(20),
+                count: AtomicUsize::new(0),
+                callback: Arc::new(move |n| counts.lock().unwrap().push(n)),
+            }
+        };
+
+        // fast requests are not counted
+        assert_eq!(watcher.watch(async { 1 }).await, 1);
+        assert!(counts.lock().unwrap().is_empty());
+
+        let slow = |ms| async move {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            ms
+        };
+        let (a, b) = tokio::join!(watcher.watch(slow(60)), watcher.watch(slow(100)));
+        assert_eq!((a, b), (60, 100));
+        assert_eq!(*counts.lock().unwrap(), vec![1, 2, 1, 0]);
+
+        // cancelled requests are not counted anymore
+        counts.lock().unwrap().clear();
+        let _ = tokio::time::timeout(Duration::from_millis(50), watcher.watch(slow(1000))).await;
+        assert_eq!(*counts.lock().unwrap(), vec![1, 0]);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::{AppError, Result};
+
+// Parses the tags to apply in the form of `key=value`, separated by commas.
+// The value can be empty, but the key cannot.
+pub fn parse_tags(s: &str) -> Result<Vec<(String, String)>> {
+    let mut tags: Vec<(String, String)> = Vec::new();
+    for tag in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let Some((key, value)) = tag.split_once('=') else {
+            return Err(AppError::msg(format!("Invalid tag: {}", tag)));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key.is_empty() {
+            return Err(AppError::msg(format!("Invalid tag: {}", tag)));
+        }
+        if tags.iter().any(|(k, _)| k == key) {
+            return Err(AppError::msg(format!("Duplicate tag key: {}", key)));
+        }
+        tags.push((key.to_string(), value.to_string()));
+    }
+    if tags.is_empty() {
+        return Err(AppError::msg("No tags specified"));
+    }
+    Ok(tags)
+}
+
+// Sets the tags to the current tags of an object.
+// The existing keys keep their positions and the new keys are appended.
+pub fn merge_tags(
+    current: &[(String, String)],
+    tags: &[(String, String)],
+) -> Vec<(String, String)> {
+    let mut merged = current.to_vec();
+    for (key, value) in tags {
+        match merged.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.clone(),
+            None => merged.push((key.clone(), value.clone())),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn tags(tags: &[(&str, &str)]) -> Vec<(String, String)> {
+        tags.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("a=1", &[("a", "1")])]
+    #[case(" a = 1 , b=, ", &[("a", "1"), ("b", "")])]
+    #[case("a=b=c", &[("a", "b=c")])]
+    fn test_parse_tags(#[case] s: &str, #[case] expected: &[(&str, &str)]) {
+        assert_eq!(parse_tags(s).unwrap(), tags(expected));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("a")]
+    #[case("=1")]
+    #[case("a=1,a=2")]
+    fn test_parse_tags_error(#[case] s: &str) {
+        assert!(parse_tags(s).is_err());
+    }
+
+    #[test]
+    fn test_merge_tags() {
+        let current = tags(&[("a", "1"), ("b", "2")]);
+        let actual = merge_tags(&current, &tags(&[("c", "3"), ("a", "x")]));
+        assert_eq!(actual, tags(&[("a", "x"), ("b", "2"), ("c", "3")]));
+    }
+}
@@ -0,0 +1,100 @@
+use std::{collections::HashMap, env, future::Future, sync::OnceLock};
+
+use aws_sdk_s3::{
+    config::{
+        interceptors::BeforeTransmitInterceptorContextMut, ConfigBag, Intercept, RuntimeComponents,
+    },
+    error::BoxError,
+};
+use opentelemetry::{
+    global,
+    trace::{FutureExt, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator, runtime, trace::TracerProvider, Resource,
+};
+use tracing::Level;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{filter::Targets, Layer, Registry};
+
+const ENDPOINT_ENV_VARS: &[&str] = &[
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+];
+
+static PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+pub type TelemetryLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+// Returns the layer which exports the spans of the storage operations over OTLP/HTTP,
+// or None if no endpoint is configured by the standard environment variables.
+pub fn layer() -> anyhow::Result<Option<TelemetryLayer>> {
+    if !ENDPOINT_ENV_VARS.iter().any(|v| env::var_os(v).is_some()) {
+        return Ok(None);
+    }
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()?;
+    let service_name = env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "stu".into());
+    let resource = Resource::default().merge(&Resource::new([
+        KeyValue::new("service.name", service_name),
+        KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ]));
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(resource)
+        .build();
+    let tracer = provider.tracer("stu");
+    let _ = PROVIDER.set(provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    // the spans of the SDK are too detailed, only the storage operations are exported
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(Targets::new().with_target("stu", Level::INFO));
+    Ok(Some(Box::new(layer)))
+}
+
+// Flushes the spans which have not been exported yet
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to export traces: {}", e);
+        }
+    }
+}
+
+// Makes the trace context of the span current while the future is polled,
+// so that it is propagated to the requests sent inside regardless of the spans of the SDK
+pub fn with_span_context<F: Future>(f: F, span: &tracing::Span) -> impl Future<Output = F::Output> {
+    f.with_context(span.context())
+}
+
+// Adds the `traceparent` header to the S3 requests,
+// so that the gateway-side traces can be correlated with the spans of stu
+#[derive(Debug)]
+pub struct TraceContextInterceptor;
+
+impl Intercept for TraceContextInterceptor {
+    fn name(&self) -> &'static str {
+        "TraceContextInterceptor"
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let mut fields = HashMap::new();
+        let cx = opentelemetry::Context::current();
+        global::get_text_map_propagator(|propagator| propagator.inject_context(&cx, &mut fields));
+        let headers = context.request_mut().headers_mut();
+        for (key, value) in fields {
+            headers.insert(key, value);
+        }
+        Ok(())
+    }
+}
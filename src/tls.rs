@@ -0,0 +1,146 @@
+use std::{fs::File, io::BufReader, sync::Arc, time::SystemTime};
+
+use hyper::client::HttpConnector;
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, ClientConfig as TlsClientConfig, RootCertStore, ServerName,
+};
+
+use crate::{
+    config::ClientConfig,
+    error::{AppError, Result},
+};
+
+pub type HttpsConnector = hyper_rustls::HttpsConnector<HttpConnector>;
+
+pub const INSECURE_WARNING: &str =
+    "TLS certificate verification is disabled (client.insecure_skip_tls_verify)";
+
+// Whether the connector differs from the default one of the AWS SDK
+pub fn is_customized(config: &ClientConfig) -> bool {
+    config.ca_bundle().is_some() || config.insecure_skip_tls_verify
+}
+
+// Checked at startup, so that the invalid config is reported before the app starts
+pub fn validate(config: &ClientConfig) -> Result<()> {
+    build_tls_config(config).map(|_| ())
+}
+
+// Falls back to the native roots if the config is invalid, which is checked by `validate` at startup
+pub fn https_connector(config: &ClientConfig) -> HttpsConnector {
+    let builder = hyper_rustls::HttpsConnectorBuilder::new();
+    let builder = match build_tls_config(config) {
+        Ok(tls_config) => builder.with_tls_config(tls_config),
+        Err(e) => {
+            tracing::error!("Failed to build TLS config: {:?}", e);
+            builder.with_native_roots()
+        }
+    };
+    builder.https_or_http().enable_http1().build()
+}
+
+fn build_tls_config(config: &ClientConfig) -> Result<TlsClientConfig> {
+    if config.insecure_skip_tls_verify && config.ca_bundle().is_some() {
+        return Err(AppError::msg(
+            "client.ca_bundle cannot be used with client.insecure_skip_tls_verify",
+        ));
+    }
+
+    let builder = TlsClientConfig::builder().with_safe_defaults();
+    if config.insecure_skip_tls_verify {
+        tracing::warn!("{}", INSECURE_WARNING);
+        let tls_config = builder
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+            .with_no_client_auth();
+        return Ok(tls_config);
+    }
+
+    let mut roots = RootCertStore::empty();
+    if let Some(path) = config.ca_bundle() {
+        let certs = load_ca_bundle(path)?;
+        let (valid, _) = roots.add_parsable_certificates(&certs);
+        if valid == 0 {
+            let msg = format!("No valid certificate found in the CA bundle: {}", path);
+            return Err(AppError::msg(msg));
+        }
+    }
+    // the native roots are still trusted with a custom CA, e.g. for STS and SSO endpoints
+    let native_certs = rustls_native_certs::load_native_certs()
+        .map_err(|e| AppError::new("Failed to load the native root certificates", e))?;
+    let native_certs: Vec<Vec<u8>> = native_certs.into_iter().map(|c| c.0).collect();
+    roots.add_parsable_certificates(&native_certs);
+    if roots.is_empty() {
+        return Err(AppError::msg("No root certificate found"));
+    }
+    Ok(builder.with_root_certificates(roots).with_no_client_auth())
+}
+
+fn load_ca_bundle(path: &str) -> Result<Vec<Vec<u8>>> {
+    let file = File::open(path)
+        .map_err(|e| AppError::new(format!("Failed to open the CA bundle: {}", path), e))?;
+    rustls_pemfile::certs(&mut BufReader::new(file))
+        .map_err(|e| AppError::new(format!("Failed to read the CA bundle: {}", path), e))
+}
+
+struct NoCertificateVerification;
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn client_config(ca_bundle: Option<&str>, insecure: bool) -> ClientConfig {
+        ClientConfig {
+            ca_bundle: ca_bundle.unwrap_or_default().to_string(),
+            insecure_skip_tls_verify: insecure,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_customized() {
+        assert!(!is_customized(&client_config(None, false)));
+        assert!(is_customized(&client_config(Some("ca.pem"), false)));
+        assert!(is_customized(&client_config(None, true)));
+    }
+
+    #[test]
+    fn test_validate_insecure() {
+        assert!(validate(&client_config(None, true)).is_ok());
+
+        let e = validate(&client_config(Some("ca.pem"), true)).unwrap_err();
+        assert_eq!(
+            e.msg,
+            "client.ca_bundle cannot be used with client.insecure_skip_tls_verify"
+        );
+    }
+
+    #[test]
+    fn test_validate_ca_bundle_errors() {
+        let e = validate(&client_config(Some("/no/such/ca.pem"), false)).unwrap_err();
+        assert_eq!(e.msg, "Failed to open the CA bundle: /no/such/ca.pem");
+
+        let path = std::env::temp_dir().join(format!("stu-test-ca-{}.pem", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "not a certificate").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        let result = load_ca_bundle(&path_str);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.unwrap().is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use percent_encoding::percent_decode_str;
+
 pub fn prune_strings_to_fit_width(
     words_with_priority: &[(String, usize)],
     max_width: usize,
@@ -69,14 +71,81 @@ pub fn digits(n: usize) -> usize {
     c
 }
 
+// Supports `*` (any sequence including `/`) and `?` (any single character),
+// same as the --include/--exclude patterns of `aws s3 cp`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = star {
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            star = Some((star_pi, star_ti + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+// Returns a score if all characters of `query` appear in `text` in order, ignoring case.
+// Consecutive matches and matches at the beginning of a word are scored higher.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    for q in query.to_lowercase().chars() {
+        let i = (next..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if i > 0 && i == next {
+            score += 2;
+        }
+        if i == 0 || matches!(text[i - 1], '-' | '_' | '.' | '/') {
+            score += 3;
+        }
+        next = i + 1;
+    }
+    Some(score)
+}
+
 pub fn extension_from_file_name(filename: &str) -> String {
     filename
         .split('.')
-        .last()
+        .next_back()
         .map(|s| s.to_string())
         .unwrap_or_default()
 }
 
+// Decodes the percent-encoded string, with `+` as a space if `plus_as_space` (form encoding).
+// A `%` not followed by two hex digits is kept as it is.
+pub fn percent_decode(s: &str, plus_as_space: bool) -> Vec<u8> {
+    if plus_as_space && s.contains('+') {
+        percent_decode_str(&s.replace('+', " ")).collect()
+    } else {
+        percent_decode_str(s).collect()
+    }
+}
+
+// Whether every `%` in the string is followed by two hex digits
+pub fn is_valid_percent_encoding(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.iter().enumerate().all(|(i, b)| {
+        *b != b'%'
+            || bytes
+                .get(i + 1..i + 3)
+                .is_some_and(|h| h.iter().all(u8::is_ascii_hexdigit))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -156,4 +225,54 @@ mod tests {
         assert_eq!(extension_from_file_name("a.txt"), "txt");
         assert_eq!(extension_from_file_name("a.gif.txt"), "txt");
     }
+
+    #[rstest]
+    #[case("*", "a/b/c.txt", true)]
+    #[case("*.txt", "a/b/c.txt", true)]
+    #[case("*.txt", "a/b/c.log", false)]
+    #[case("a/*", "a/b/c.txt", true)]
+    #[case("b/*", "a/b/c.txt", false)]
+    #[case("a/?/c.txt", "a/b/c.txt", true)]
+    #[case("a/?/c.txt", "a/bb/c.txt", false)]
+    #[case("*b*", "a/b/c.txt", true)]
+    #[case("c.txt", "a/b/c.txt", false)]
+    #[case("", "", true)]
+    #[case("**", "", true)]
+    fn test_glob_match(#[case] pattern: &str, #[case] text: &str, #[case] expected: bool) {
+        assert_eq!(glob_match(pattern, text), expected);
+    }
+
+    #[rstest]
+    #[case("", "bucket", Some(0))]
+    #[case("bkt", "bucket", Some(6))]
+    #[case("BUC", "my-bucket", Some(10))]
+    #[case("mb", "my-bucket", Some(8))]
+    #[case("tb", "bucket", None)]
+    #[case("bucket1", "bucket", None)]
+    fn test_fuzzy_match(#[case] query: &str, #[case] text: &str, #[case] expected: Option<usize>) {
+        assert_eq!(fuzzy_match(query, text), expected);
+    }
+
+    #[rstest]
+    #[case("a%20b", false, "a b")]
+    #[case("a+b%2B", false, "a+b+")]
+    #[case("a+b%2B", true, "a b+")]
+    #[case("a%2", false, "a%2")]
+    #[case("a%+1", false, "a%+1")]
+    #[case("a%-1", true, "a%-1")]
+    #[case("a%zz%41", false, "a%zzA")]
+    fn test_percent_decode(#[case] s: &str, #[case] plus_as_space: bool, #[case] expected: &str) {
+        assert_eq!(percent_decode(s, plus_as_space), expected.as_bytes());
+    }
+
+    #[rstest]
+    #[case("abc", true)]
+    #[case("a%20b%2f", true)]
+    #[case("a%2", false)]
+    #[case("a%+1", false)]
+    #[case("a%-1", false)]
+    #[case("100%", false)]
+    fn test_is_valid_percent_encoding(#[case] s: &str, #[case] expected: bool) {
+        assert_eq!(is_valid_percent_encoding(s), expected);
+    }
 }
@@ -0,0 +1,256 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use notify::{
+    event::ModifyKind, recommended_watcher, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+use crate::{
+    error::{AppError, Result},
+    event::{AppEventType, Sender},
+};
+
+// A changed file is uploaded after this delay, so that a file being written is uploaded once
+pub const WATCH_UPLOAD_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchUploadStatus {
+    Pending,
+    Uploading,
+    Completed(u64),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchUpload {
+    pub path: PathBuf,
+    pub key: String,
+    pub status: WatchUploadStatus,
+    pub updated_at: DateTime<Local>,
+}
+
+pub struct WatchSession {
+    pub dir: PathBuf,
+    pub bucket: String,
+    pub prefix: String,
+    // the most recently changed first
+    uploads: Vec<WatchUpload>,
+    // files changed again while being uploaded, they are uploaded again after the current upload
+    changed_while_uploading: HashSet<PathBuf>,
+    // the directory is watched while this is alive
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl std::fmt::Debug for WatchSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchSession")
+            .field("dir", &self.dir)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("uploads", &self.uploads)
+            .finish()
+    }
+}
+
+impl WatchSession {
+    // Starts watching the directory recursively, the changed files are notified with `WatchFileChanged`
+    pub fn start(dir: PathBuf, bucket: String, prefix: String, tx: Sender) -> Result<Self> {
+        let mut watcher =
+            recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+                Ok(event) if is_content_change(&event.kind) => {
+                    for path in event.paths {
+                        tx.send(AppEventType::WatchFileChanged(path));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to watch directory: {}", e),
+            })
+            .map_err(|e| AppError::new("Failed to watch directory", e))?;
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .map_err(|e| AppError::new("Failed to watch directory", e))?;
+
+        let mut session = Self::new(dir, bucket, prefix);
+        session._watcher = Some(watcher);
+        Ok(session)
+    }
+
+    fn new(dir: PathBuf, bucket: String, prefix: String) -> Self {
+        Self {
+            dir,
+            bucket,
+            prefix,
+            uploads: Vec::new(),
+            changed_while_uploading: HashSet::new(),
+            _watcher: None,
+        }
+    }
+
+    pub fn uploads(&self) -> &[WatchUpload] {
+        &self.uploads
+    }
+
+    // Returns true if the upload of the file should be scheduled.
+    // The file which is already pending is not scheduled again.
+    pub fn file_changed(&mut self, path: &Path, now: DateTime<Local>) -> bool {
+        if !path.is_file() {
+            return false;
+        }
+        let Some(key) = build_watch_key(&self.dir, &self.prefix, path) else {
+            return false;
+        };
+        match self.uploads.iter().find(|u| u.path == path) {
+            Some(upload) if upload.status == WatchUploadStatus::Pending => false,
+            Some(upload) if upload.status == WatchUploadStatus::Uploading => {
+                self.changed_while_uploading.insert(path.to_path_buf());
+                false
+            }
+            _ => {
+                self.update(path, key, WatchUploadStatus::Pending, now);
+                true
+            }
+        }
+    }
+
+    // Returns the key to upload the file to
+    pub fn start_upload(&mut self, path: &Path, now: DateTime<Local>) -> Option<String> {
+        let key = self.uploads.iter().find(|u| u.path == path)?.key.clone();
+        self.update(path, key.clone(), WatchUploadStatus::Uploading, now);
+        Some(key)
+    }
+
+    // Returns true if the file should be uploaded again since it was changed during the upload
+    pub fn complete_upload(
+        &mut self,
+        path: &Path,
+        result: Result<u64>,
+        now: DateTime<Local>,
+    ) -> bool {
+        let Some(key) = self
+            .uploads
+            .iter()
+            .find(|u| u.path == path)
+            .map(|u| u.key.clone())
+        else {
+            return false;
+        };
+        let status = match result {
+            Ok(size_byte) => WatchUploadStatus::Completed(size_byte),
+            Err(e) => WatchUploadStatus::Failed(e.to_string()),
+        };
+        self.update(path, key.clone(), status, now);
+
+        if self.changed_while_uploading.remove(path) {
+            self.update(path, key, WatchUploadStatus::Pending, now);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn update(
+        &mut self,
+        path: &Path,
+        key: String,
+        status: WatchUploadStatus,
+        now: DateTime<Local>,
+    ) {
+        self.uploads.retain(|u| u.path != path);
+        self.uploads.insert(
+            0,
+            WatchUpload {
+                path: path.to_path_buf(),
+                key,
+                status,
+                updated_at: now,
+            },
+        );
+    }
+}
+
+fn is_content_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    }
+}
+
+// The key is the path relative to the watched directory under the prefix, separated by slashes
+fn build_watch_key(dir: &Path, prefix: &str, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let mut names = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => names.push(name.to_str()?),
+            _ => return None,
+        }
+    }
+    if names.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", prefix, names.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/watch/a.txt", Some("up/a.txt"))]
+    #[case("/watch/dir/b.txt", Some("up/dir/b.txt"))]
+    #[case("/other/a.txt", None)]
+    #[case("/watch", None)]
+    fn test_build_watch_key(#[case] path: &str, #[case] expected: Option<&str>) {
+        let actual = build_watch_key(Path::new("/watch"), "up/", Path::new(path));
+        assert_eq!(actual.as_deref(), expected);
+    }
+
+    #[test]
+    fn test_watch_session() {
+        let dir = std::env::temp_dir().join(format!("stu-watch-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt");
+        std::fs::write(&path, "a").unwrap();
+        let now = Local.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let mut session = WatchSession::new(dir.clone(), "bucket".into(), "up/".into());
+        let status = |session: &WatchSession| session.uploads()[0].status.clone();
+
+        assert!(session.file_changed(&path, now));
+        assert_eq!(session.uploads()[0].key, "up/a.txt");
+        assert_eq!(status(&session), WatchUploadStatus::Pending);
+        // the pending file is not scheduled twice
+        assert!(!session.file_changed(&path, now));
+
+        assert_eq!(
+            session.start_upload(&path, now).as_deref(),
+            Some("up/a.txt")
+        );
+        assert_eq!(status(&session), WatchUploadStatus::Uploading);
+        // the file changed during the upload is uploaded again
+        assert!(!session.file_changed(&path, now));
+        assert!(session.complete_upload(&path, Ok(1), now));
+        assert_eq!(status(&session), WatchUploadStatus::Pending);
+
+        session.start_upload(&path, now);
+        assert!(!session.complete_upload(&path, Err(AppError::msg("error")), now));
+        assert!(matches!(status(&session), WatchUploadStatus::Failed(_)));
+        assert!(session.file_changed(&path, now));
+
+        // directories and removed files are ignored
+        assert!(!session.file_changed(&dir, now));
+        assert!(!session.file_changed(&dir.join("b.txt"), now));
+        assert_eq!(session.uploads().len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1145 @@
+use std::{fmt, time::Duration};
+
+use aws_smithy_types::base64;
+use chrono::{DateTime, Local};
+use hyper::{
+    body::HttpBody, client::HttpConnector, Body, Client as HttpClient, Method, Request, Response,
+    StatusCode,
+};
+use hyper_rustls::HttpsConnector;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use quick_xml::{events::Event, Reader};
+
+use crate::{
+    bucket_metrics::BucketMetrics,
+    config::ClientConfig,
+    download_list::DownloadObjectList,
+    error::{AppError, Result},
+    lifecycle::LifecycleRule,
+    multipart::{MultipartUpload, UploadedPart},
+    object::{
+        BucketItem, DeleteFailure, DownloadObjectInfo, FileDetail, FileVersion, FileVersions,
+        FileVersionsMarker, ObjectItem, ObjectMetadata, ObjectSummary, ObjectsPage, RawObject,
+    },
+    storage::{
+        load_objects_in_one_page, multipart_upload_not_supported, BoxFuture, BucketAdmin,
+        ManagementConsole, MultipartUploader, ObjectStore, Presigner, Progress,
+    },
+    tls,
+};
+
+const DELIMITER: char = '/';
+const URL_ENV: &str = "STU_WEBDAV_URL";
+const USERNAME_ENV: &str = "STU_WEBDAV_USERNAME";
+const PASSWORD_ENV: &str = "STU_WEBDAV_PASSWORD";
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+    <d:getetag/>
+    <d:getcontenttype/>
+  </d:prop>
+</d:propfind>"#;
+
+// Everything except the unreserved characters and the `/` separators
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct WebDavConfig {
+    // e.g. https://example.com/remote.php/dav/files/user
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl fmt::Debug for WebDavConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebDavConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .finish()
+    }
+}
+
+impl WebDavConfig {
+    pub fn from_env() -> Result<WebDavConfig> {
+        let Ok(url) = std::env::var(URL_ENV) else {
+            return Err(AppError::msg(format!("{} must be set", URL_ENV)));
+        };
+        Ok(WebDavConfig {
+            url: url.trim_end_matches('/').to_string(),
+            username: std::env::var(USERNAME_ENV).ok(),
+            password: std::env::var(PASSWORD_ENV).ok(),
+        })
+    }
+
+    // path of the url, without the trailing slash
+    fn base_path(&self) -> &str {
+        url_path(&self.url)
+    }
+}
+
+#[derive(Debug)]
+enum WebDavError {
+    Status(StatusCode),
+    Http(hyper::Error),
+    Request(hyper::http::Error),
+    Xml(quick_xml::Error),
+}
+
+impl fmt::Display for WebDavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebDavError::Status(status) => write!(f, "Unexpected response: {}", status),
+            WebDavError::Http(e) => e.fmt(f),
+            WebDavError::Request(e) => e.fmt(f),
+            WebDavError::Xml(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for WebDavError {}
+
+impl From<hyper::Error> for WebDavError {
+    fn from(e: hyper::Error) -> Self {
+        WebDavError::Http(e)
+    }
+}
+
+impl From<hyper::http::Error> for WebDavError {
+    fn from(e: hyper::http::Error) -> Self {
+        WebDavError::Request(e)
+    }
+}
+
+impl From<quick_xml::Error> for WebDavError {
+    fn from(e: quick_xml::Error) -> Self {
+        WebDavError::Xml(e)
+    }
+}
+
+// A resource in a PROPFIND response
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DavEntry {
+    // decoded path
+    path: String,
+    is_collection: bool,
+    size_byte: usize,
+    last_modified: String,
+    e_tag: String,
+    content_type: String,
+}
+
+// Client for WebDAV servers, which maps the top-level collections to buckets
+// and the resources under them to objects.
+// Objects have only one version, no tags and no lifecycle rules.
+pub struct WebDavClient {
+    http: HttpClient<HttpsConnector<HttpConnector>>,
+    config: WebDavConfig,
+}
+
+impl fmt::Debug for WebDavClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebDavClient")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl WebDavClient {
+    pub fn new(config: WebDavConfig, client_config: &ClientConfig) -> WebDavClient {
+        let connector = tls::https_connector(client_config);
+        let http = HttpClient::builder().build(connector);
+        WebDavClient { http, config }
+    }
+
+    // {url}/{bucket}/{key}
+    fn url(&self, bucket: &str, key: &str) -> String {
+        let path = if bucket.is_empty() {
+            "".to_string()
+        } else {
+            format!("/{}/{}", bucket, key)
+        };
+        format!(
+            "{}{}",
+            self.config.url,
+            utf8_percent_encode(&path, PATH_ENCODE_SET)
+        )
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Body,
+    ) -> std::result::Result<Response<Body>, WebDavError> {
+        let mut builder = Request::builder().method(method).uri(url);
+        for (name, value) in headers {
+            builder = builder.header(*name, value);
+        }
+        if let Some(username) = &self.config.username {
+            let credentials = format!(
+                "{}:{}",
+                username,
+                self.config.password.as_deref().unwrap_or_default()
+            );
+            let authorization = format!("Basic {}", base64::encode(credentials));
+            builder = builder.header("authorization", authorization);
+        }
+        let response = self.http.request(builder.body(body)?).await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(WebDavError::Status(response.status()))
+        }
+    }
+
+    async fn propfind(
+        &self,
+        bucket: &str,
+        key: &str,
+        depth: &str,
+    ) -> std::result::Result<Vec<DavEntry>, WebDavError> {
+        let method = Method::from_bytes(b"PROPFIND").unwrap();
+        let headers = [
+            ("depth", depth.to_string()),
+            ("content-type", "application/xml".to_string()),
+        ];
+        let url = self.url(bucket, key);
+        let response = self
+            .send(method, &url, &headers, Body::from(PROPFIND_BODY))
+            .await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        parse_multistatus(&body)
+    }
+
+    // Lists the direct children of the collection, except itself.
+    // The keys of the collections end with the delimiter.
+    async fn list_children(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> std::result::Result<Vec<(String, DavEntry)>, WebDavError> {
+        let entries = self.propfind(bucket, prefix, "1").await?;
+        let base = format!("{}/{}/", self.config.base_path(), bucket);
+        let children = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let key = entry.path.strip_prefix(&base)?.trim_end_matches(DELIMITER);
+                if key.len() <= prefix.trim_end_matches(DELIMITER).len() {
+                    // the collection itself
+                    return None;
+                }
+                let key = if entry.is_collection {
+                    format!("{}/", key)
+                } else {
+                    key.to_string()
+                };
+                Some((key, entry))
+            })
+            .collect();
+        Ok(children)
+    }
+
+    // Lists the resources under the prefix recursively with Depth: 1,
+    // since many servers do not allow Depth: infinity.
+    async fn list_all_files<F>(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        f: F,
+    ) -> Result<Vec<(String, DavEntry)>>
+    where
+        F: Fn(usize),
+    {
+        // the prefix may end in the middle of a name
+        let dir_prefix = prefix.rfind(DELIMITER).map_or("", |i| &prefix[..=i]);
+
+        let mut files = Vec::new();
+        let mut dirs = vec![dir_prefix.to_string()];
+        while let Some(dir) = dirs.pop() {
+            let children = self
+                .list_children(bucket, &dir)
+                .await
+                .map_err(|e| AppError::new("Failed to list objects", e))?;
+            for (key, entry) in children {
+                if !key.starts_with(prefix) && !prefix.starts_with(&key) {
+                    continue;
+                }
+                if entry.is_collection {
+                    dirs.push(key);
+                } else if key.starts_with(prefix) {
+                    files.push((key, entry));
+                }
+            }
+            f(files.len());
+        }
+        files.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        Ok(files)
+    }
+
+    fn bucket_item(&self, name: &str) -> BucketItem {
+        let url = self.url(name, "");
+        BucketItem {
+            name: name.to_string(),
+            s3_uri: url.clone(),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        }
+    }
+
+    fn object_item(&self, bucket: &str, key: String, entry: DavEntry) -> ObjectItem {
+        let url = self.url(bucket, &key);
+        if entry.is_collection {
+            ObjectItem::Dir {
+                name: key
+                    .trim_end_matches(DELIMITER)
+                    .rsplit(DELIMITER)
+                    .next()
+                    .unwrap()
+                    .to_string(),
+                s3_uri: url.clone(),
+                virtual_hosted_url: url.clone(),
+                path_style_url: url,
+                key,
+            }
+        } else {
+            ObjectItem::File {
+                name: key.rsplit(DELIMITER).next().unwrap().to_string(),
+                size_byte: entry.size_byte,
+                last_modified: parse_http_date(&entry.last_modified),
+                s3_uri: url.clone(),
+                arn: "".to_string(),
+                virtual_hosted_url: url.clone(),
+                path_style_url: url,
+                e_tag: entry.e_tag.trim_matches('"').to_string(),
+                key,
+            }
+        }
+    }
+
+    pub async fn load_all_buckets(&self) -> Result<Vec<BucketItem>> {
+        let entries = self
+            .propfind("", "", "1")
+            .await
+            .map_err(|e| AppError::new("Failed to load buckets", e))?;
+        let base = format!("{}/", self.config.base_path());
+        let mut names: Vec<String> = entries
+            .into_iter()
+            .filter(|entry| entry.is_collection)
+            .filter_map(|entry| {
+                let name = entry.path.strip_prefix(&base)?.trim_end_matches(DELIMITER);
+                (!name.is_empty()).then(|| name.to_string())
+            })
+            .collect();
+        if names.is_empty() {
+            return Err(AppError::msg("No buckets found"));
+        }
+        names.sort();
+        Ok(names.iter().map(|name| self.bucket_item(name)).collect())
+    }
+
+    pub async fn load_bucket(&self, name: &str) -> Result<BucketItem> {
+        let entries = self
+            .propfind(name, "", "0")
+            .await
+            .map_err(|e| AppError::new(format!("Failed to load bucket '{}'", name), e))?;
+        if !entries.iter().any(|entry| entry.is_collection) {
+            return Err(AppError::msg(format!("Bucket '{}' not found", name)));
+        }
+        Ok(self.bucket_item(name))
+    }
+
+    pub async fn load_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectItem>> {
+        let mut children = self
+            .list_children(bucket, prefix)
+            .await
+            .map_err(|e| AppError::new("Failed to load objects", e))?;
+        children.sort_by(|(k1, e1), (k2, e2)| {
+            e2.is_collection
+                .cmp(&e1.is_collection)
+                .then_with(|| k1.cmp(k2))
+        });
+        Ok(children
+            .into_iter()
+            .map(|(key, entry)| self.object_item(bucket, key, entry))
+            .collect())
+    }
+
+    pub async fn list_all_download_objects<F>(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        f: F,
+    ) -> Result<DownloadObjectList>
+    where
+        F: Fn(usize),
+    {
+        let files = self.list_all_files(bucket, prefix, f).await?;
+        let mut objs: Vec<DownloadObjectInfo> = files
+            .into_iter()
+            .map(|(key, entry)| DownloadObjectInfo {
+                key,
+                size_byte: entry.size_byte,
+                last_modified: parse_http_date(&entry.last_modified),
+                e_tag: entry.e_tag,
+            })
+            .collect();
+        objs.sort_by(|a, b| a.key.cmp(&b.key));
+        DownloadObjectList::from_objs(objs)
+    }
+
+    pub async fn list_all_object_summaries(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<Vec<ObjectSummary>> {
+        let files = self.list_all_files(bucket, prefix, |_| {}).await?;
+        Ok(files
+            .into_iter()
+            .map(|(key, entry)| ObjectSummary {
+                key,
+                size_byte: entry.size_byte,
+                last_modified: parse_http_date(&entry.last_modified),
+                e_tag: entry.e_tag.trim_matches('"').to_string(),
+            })
+            .collect())
+    }
+
+    async fn load_entry(&self, bucket: &str, key: &str) -> Result<DavEntry> {
+        let entries = self
+            .propfind(bucket, key, "0")
+            .await
+            .map_err(|e| AppError::new("Failed to load object detail", e))?;
+        entries
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::msg("Failed to load object detail"))
+    }
+
+    pub async fn object_exists(&self, bucket: &str, key: &str) -> Result<bool> {
+        match self.propfind(bucket, key, "0").await {
+            Ok(entries) => Ok(!entries.is_empty()),
+            Err(WebDavError::Status(StatusCode::NOT_FOUND)) => Ok(false),
+            Err(e) => Err(AppError::new("Failed to check object", e)),
+        }
+    }
+
+    pub async fn load_object_detail(
+        &self,
+        bucket: &str,
+        key: &str,
+        name: &str,
+        size_byte: usize,
+    ) -> Result<FileDetail> {
+        let entry = self.load_entry(bucket, key).await?;
+        let url = self.url(bucket, key);
+        let content_type = if entry.content_type.is_empty() {
+            DEFAULT_CONTENT_TYPE.to_string()
+        } else {
+            entry.content_type
+        };
+        Ok(FileDetail {
+            name: name.to_string(),
+            size_byte,
+            last_modified: parse_http_date(&entry.last_modified),
+            e_tag: entry.e_tag.trim_matches('"').to_string(),
+            content_type,
+            storage_class: "".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
+            key: key.to_string(),
+            s3_uri: url.clone(),
+            arn: "".to_string(),
+            virtual_hosted_url: url.clone(),
+            path_style_url: url,
+        })
+    }
+
+    pub async fn load_object_versions(&self, bucket: &str, key: &str) -> Result<FileVersions> {
+        let entry = self.load_entry(bucket, key).await?;
+        let version = FileVersion {
+            version_id: "null".to_string(),
+            size_byte: entry.size_byte,
+            last_modified: parse_http_date(&entry.last_modified),
+            e_tag: entry.e_tag.trim_matches('"').to_string(),
+            is_latest: true,
+            is_delete_marker: false,
+        };
+        Ok(FileVersions {
+            versions: vec![version],
+            next_marker: None,
+        })
+    }
+
+    pub async fn download_object<F>(
+        &self,
+        bucket: &str,
+        key: &str,
+        if_match: Option<String>,
+        size_byte: usize,
+        f: F,
+    ) -> Result<RawObject>
+    where
+        F: Fn(usize),
+    {
+        let headers: Vec<(&str, String)> = if_match.map(|e| ("if-match", e)).into_iter().collect();
+        let url = self.url(bucket, key);
+        let result = self.send(Method::GET, &url, &headers, Body::empty()).await;
+        let output = result.map_err(convert_download_error)?;
+
+        let mut bytes: Vec<u8> = Vec::with_capacity(size_byte);
+        let mut body = output.into_body();
+        let mut reported = 0;
+        while let Some(buf) = body.data().await {
+            let buf = buf.map_err(|e| AppError::new("Failed to collect body", e))?;
+            bytes.extend_from_slice(&buf);
+
+            // suppress too many calls
+            if bytes.len() - reported >= 1024 * 1024 {
+                f(bytes.len());
+                reported = bytes.len();
+            }
+        }
+
+        Ok(RawObject { bytes })
+    }
+
+    // The whole content is returned from the beginning if the server does not support Range,
+    // so the body is read only up to the end of the range in that case.
+    pub async fn download_object_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> Result<RawObject> {
+        let range = format!("bytes={}-{}", offset, offset + len.max(1) - 1);
+        let mut headers = vec![("range", range)];
+        headers.extend(if_match.map(|e| ("if-match", e)));
+        let url = self.url(bucket, key);
+        let result = self.send(Method::GET, &url, &headers, Body::empty()).await;
+        let output = result.map_err(convert_download_error)?;
+
+        let skip = if output.status() == StatusCode::PARTIAL_CONTENT {
+            0
+        } else {
+            offset
+        };
+        let mut bytes: Vec<u8> = Vec::with_capacity(len);
+        let mut body = output.into_body();
+        while let Some(buf) = body.data().await {
+            let buf = buf.map_err(|e| AppError::new("Failed to collect body", e))?;
+            bytes.extend_from_slice(&buf);
+            if bytes.len() >= skip + len {
+                break;
+            }
+        }
+        bytes.truncate(skip + len);
+        bytes.drain(..skip.min(bytes.len()));
+
+        Ok(RawObject { bytes })
+    }
+
+    pub async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<()> {
+        let method = Method::from_bytes(b"COPY").unwrap();
+        let headers = [
+            ("destination", self.url(dst_bucket, dst_key)),
+            ("overwrite", "T".to_string()),
+        ];
+        let url = self.url(src_bucket, src_key);
+        let result = self.send(method, &url, &headers, Body::empty()).await;
+        result.map_err(|e| AppError::new("Failed to copy object", e))?;
+        Ok(())
+    }
+
+    pub async fn put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<()> {
+        let url = self.url(bucket, key);
+        let content_type = content_type
+            .map(String::from)
+            .or_else(|| infer::get(&bytes).map(|t| t.mime_type().to_string()));
+        let headers: Vec<(&str, String)> = content_type
+            .into_iter()
+            .map(|t| ("Content-Type", t))
+            .collect();
+        let result = self
+            .send(Method::PUT, &url, &headers, Body::from(bytes))
+            .await;
+        result.map_err(|e| AppError::new("Failed to upload object", e))?;
+        Ok(())
+    }
+
+    pub async fn create_folder(&self, bucket: &str, key: &str) -> Result<ObjectItem> {
+        let url = self.url(bucket, key);
+        let method = Method::from_bytes(b"MKCOL").unwrap();
+        let result = self.send(method, &url, &[], Body::empty()).await;
+        result.map_err(|e| AppError::new("Failed to create folder", e))?;
+        let entry = DavEntry {
+            path: key.to_string(),
+            is_collection: true,
+            size_byte: 0,
+            last_modified: String::new(),
+            e_tag: String::new(),
+            content_type: String::new(),
+        };
+        Ok(self.object_item(bucket, key.to_string(), entry))
+    }
+
+    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        let url = self.url(bucket, key);
+        let result = self.send(Method::DELETE, &url, &[], Body::empty()).await;
+        result.map_err(|e| AppError::new("Failed to delete object", e))?;
+        Ok(())
+    }
+
+    pub async fn delete_objects<F>(
+        &self,
+        bucket: &str,
+        keys: &[String],
+        f: F,
+    ) -> Result<Vec<DeleteFailure>>
+    where
+        F: Fn(usize),
+    {
+        let mut failed = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            if let Err(e) = self.delete_object(bucket, key).await {
+                failed.push(DeleteFailure {
+                    key: key.clone(),
+                    error: e.to_string(),
+                });
+            }
+            f(i + 1);
+        }
+        Ok(failed)
+    }
+}
+
+// path of the url without the scheme and the host, and the trailing slash
+fn url_path(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.find('/')
+        .map_or("", |i| &rest[i..])
+        .trim_end_matches('/')
+}
+
+fn parse_http_date(s: &str) -> DateTime<Local> {
+    DateTime::parse_from_rfc2822(s)
+        .map(|dt| dt.with_timezone(&Local))
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DavProp {
+    Href,
+    ContentLength,
+    LastModified,
+    ETag,
+    ContentType,
+}
+
+// Parses a 207 Multi-Status response.
+// Elements are matched by the local names since servers use different namespace prefixes.
+fn parse_multistatus(xml: &[u8]) -> std::result::Result<Vec<DavEntry>, WebDavError> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut entries = Vec::new();
+    let mut entry: Option<DavEntry> = None;
+    let mut prop: Option<DavProp> = None;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"response" => entry = Some(DavEntry::default()),
+                b"href" => prop = Some(DavProp::Href),
+                b"getcontentlength" => prop = Some(DavProp::ContentLength),
+                b"getlastmodified" => prop = Some(DavProp::LastModified),
+                b"getetag" => prop = Some(DavProp::ETag),
+                b"getcontenttype" => prop = Some(DavProp::ContentType),
+                b"collection" => {
+                    if let Some(entry) = entry.as_mut() {
+                        entry.is_collection = true;
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"collection" => {
+                if let Some(entry) = entry.as_mut() {
+                    entry.is_collection = true;
+                }
+            }
+            Event::Text(e) => {
+                if let (Some(entry), Some(prop)) = (entry.as_mut(), prop) {
+                    let text = e.unescape()?.trim().to_string();
+                    match prop {
+                        DavProp::Href => {
+                            entry.path = percent_decode_str(url_path_keep_slash(&text))
+                                .decode_utf8_lossy()
+                                .to_string()
+                        }
+                        DavProp::ContentLength => entry.size_byte = text.parse().unwrap_or(0),
+                        DavProp::LastModified => entry.last_modified = text,
+                        DavProp::ETag => entry.e_tag = text,
+                        DavProp::ContentType => entry.content_type = text,
+                    }
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"response" => entries.extend(entry.take()),
+                _ => prop = None,
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(entries)
+}
+
+// hrefs may be absolute URLs or absolute paths
+fn url_path_keep_slash(href: &str) -> &str {
+    match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => href,
+    }
+}
+
+fn convert_download_error(e: WebDavError) -> AppError {
+    match e {
+        WebDavError::Status(StatusCode::PRECONDITION_FAILED) => {
+            AppError::msg("Object has changed since you viewed it, please reload and try again")
+        }
+        e => AppError::new("Failed to download object", e),
+    }
+}
+
+impl ObjectStore for WebDavClient {
+    fn load_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectItem>>> {
+        Box::pin(WebDavClient::load_objects(self, bucket, prefix))
+    }
+
+    fn load_objects_page<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        _token: Option<String>,
+    ) -> BoxFuture<'a, Result<ObjectsPage>> {
+        load_objects_in_one_page(Box::pin(WebDavClient::load_objects(self, bucket, prefix)))
+    }
+
+    fn list_all_download_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<DownloadObjectList>> {
+        Box::pin(WebDavClient::list_all_download_objects(
+            self, bucket, prefix, progress,
+        ))
+    }
+
+    fn list_all_object_summaries<'a>(
+        &'a self,
+        bucket: &'a str,
+        prefix: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ObjectSummary>>> {
+        Box::pin(WebDavClient::list_all_object_summaries(
+            self, bucket, prefix,
+        ))
+    }
+
+    fn object_exists<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(WebDavClient::object_exists(self, bucket, key))
+    }
+
+    fn load_object_detail<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        name: &'a str,
+        size_byte: usize,
+    ) -> BoxFuture<'a, Result<FileDetail>> {
+        Box::pin(WebDavClient::load_object_detail(
+            self, bucket, key, name, size_byte,
+        ))
+    }
+
+    fn load_object_versions<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _marker: Option<FileVersionsMarker>,
+    ) -> BoxFuture<'a, Result<FileVersions>> {
+        Box::pin(WebDavClient::load_object_versions(self, bucket, key))
+    }
+
+    fn load_object_tags<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn download_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _version_id: Option<String>,
+        if_match: Option<String>,
+        size_byte: usize,
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(WebDavClient::download_object(
+            self, bucket, key, if_match, size_byte, progress,
+        ))
+    }
+
+    fn download_object_range<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        _version_id: Option<String>,
+        if_match: Option<String>,
+        offset: usize,
+        len: usize,
+    ) -> BoxFuture<'a, Result<RawObject>> {
+        Box::pin(WebDavClient::download_object_range(
+            self, bucket, key, if_match, offset, len,
+        ))
+    }
+
+    fn copy_object<'a>(
+        &'a self,
+        src_bucket: &'a str,
+        src_key: &'a str,
+        dst_bucket: &'a str,
+        dst_key: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(WebDavClient::copy_object(
+            self, src_bucket, src_key, dst_bucket, dst_key,
+        ))
+    }
+
+    fn put_object<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(WebDavClient::put_object(
+            self,
+            bucket,
+            key,
+            bytes,
+            content_type,
+        ))
+    }
+
+    fn put_object_tags<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _tags: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Err(AppError::msg("Object tags are not supported for WebDAV")) })
+    }
+
+    fn change_storage_class<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _storage_class: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Storage classes are not supported for WebDAV",
+            ))
+        })
+    }
+
+    fn update_object_metadata<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _metadata: &'a ObjectMetadata,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Editing metadata is not supported for WebDAV",
+            ))
+        })
+    }
+
+    fn set_object_legal_hold<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _on: bool,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Err(AppError::msg("Legal hold is not supported for WebDAV")) })
+    }
+
+    fn create_folder<'a>(
+        &'a self,
+        bucket: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<ObjectItem>> {
+        Box::pin(WebDavClient::create_folder(self, bucket, key))
+    }
+
+    fn delete_object<'a>(&'a self, bucket: &'a str, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(WebDavClient::delete_object(self, bucket, key))
+    }
+
+    fn delete_object_version<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _version_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Deleting a version is not supported for WebDAV",
+            ))
+        })
+    }
+
+    fn delete_objects<'a>(
+        &'a self,
+        bucket: &'a str,
+        keys: &'a [String],
+        progress: Progress,
+    ) -> BoxFuture<'a, Result<Vec<DeleteFailure>>> {
+        Box::pin(WebDavClient::delete_objects(self, bucket, keys, progress))
+    }
+}
+
+impl BucketAdmin for WebDavClient {
+    fn region(&self) -> &str {
+        &self.config.url
+    }
+
+    fn load_all_buckets(&self) -> BoxFuture<'_, Result<Vec<BucketItem>>> {
+        Box::pin(WebDavClient::load_all_buckets(self))
+    }
+
+    fn load_bucket<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<BucketItem>> {
+        Box::pin(WebDavClient::load_bucket(self, name))
+    }
+
+    fn load_lifecycle_rules<'a>(
+        &'a self,
+        _bucket: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<LifecycleRule>>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn is_object_lock_enabled<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async { Ok(false) })
+    }
+
+    fn load_bucket_metrics<'a>(&'a self, _bucket: &'a str) -> BoxFuture<'a, Result<BucketMetrics>> {
+        Box::pin(async { Err(AppError::msg("Bucket metrics are not supported for WebDAV")) })
+    }
+
+    fn create_bucket<'a>(&'a self, _name: &'a str, _region: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Creating buckets is not supported for WebDAV",
+            ))
+        })
+    }
+
+    fn delete_bucket<'a>(&'a self, _name: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async {
+            Err(AppError::msg(
+                "Deleting buckets is not supported for WebDAV",
+            ))
+        })
+    }
+}
+
+impl Presigner for WebDavClient {
+    fn presign_get_object<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _version_id: Option<String>,
+        _expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async { Err(AppError::msg("Presigned URLs are not supported for WebDAV")) })
+    }
+
+    fn presign_put_object<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _expires_in: Duration,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async { Err(AppError::msg("Presigned URLs are not supported for WebDAV")) })
+    }
+}
+
+impl MultipartUploader for WebDavClient {
+    fn supports_multipart_upload(&self) -> bool {
+        false
+    }
+
+    fn find_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<MultipartUpload>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn create_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _content_type: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn upload_part<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _part_number: i32,
+        _bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<String>> {
+        multipart_upload_not_supported()
+    }
+
+    fn complete_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+        _parts: &'a [UploadedPart],
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+
+    fn abort_multipart_upload<'a>(
+        &'a self,
+        _bucket: &'a str,
+        _key: &'a str,
+        _upload_id: &'a str,
+    ) -> BoxFuture<'a, Result<()>> {
+        multipart_upload_not_supported()
+    }
+}
+
+// Many servers provide a web interface at the same URLs
+impl ManagementConsole for WebDavClient {
+    fn open_management_console_buckets(&self) -> Result<()> {
+        open::that(&self.config.url).map_err(AppError::error)
+    }
+
+    fn open_management_console_list(&self, bucket: &str, prefix: &str) -> Result<()> {
+        open::that(self.url(bucket, prefix)).map_err(AppError::error)
+    }
+
+    fn open_management_console_object(&self, bucket: &str, prefix: &str) -> Result<()> {
+        open::that(self.url(bucket, prefix)).map_err(AppError::error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/bucket/dir/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getlastmodified>Mon, 27 Jan 2025 10:00:00 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://example.com/dav/bucket/dir/a%20b.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>123</d:getcontentlength>
+        <d:getlastmodified>Mon, 27 Jan 2025 10:00:00 GMT</d:getlastmodified>
+        <d:getetag>"abc"</d:getetag>
+        <d:getcontenttype>text/plain</d:getcontenttype>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+        let actual = parse_multistatus(xml.as_bytes()).unwrap();
+        let expected = vec![
+            DavEntry {
+                path: "/dav/bucket/dir/".to_string(),
+                is_collection: true,
+                last_modified: "Mon, 27 Jan 2025 10:00:00 GMT".to_string(),
+                ..Default::default()
+            },
+            DavEntry {
+                path: "/dav/bucket/dir/a b.txt".to_string(),
+                is_collection: false,
+                size_byte: 123,
+                last_modified: "Mon, 27 Jan 2025 10:00:00 GMT".to_string(),
+                e_tag: "\"abc\"".to_string(),
+                content_type: "text/plain".to_string(),
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(url_path("https://example.com"), "");
+        assert_eq!(url_path("https://example.com/"), "");
+        assert_eq!(
+            url_path("https://example.com/remote.php/dav/"),
+            "/remote.php/dav"
+        );
+    }
+}
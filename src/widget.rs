@@ -1,27 +1,47 @@
 mod bar;
+mod batch_rename_dialog;
+mod bucket_switcher_dialog;
 mod common;
+mod confirm_dialog;
 mod copy_detail_dialog;
 mod dialog;
 mod divider;
+mod download_confirm_dialog;
 mod header;
 mod image_preview;
 mod input_dialog;
 mod loading_dialog;
+mod magic_panel;
+mod metadata_dialog;
+mod presign_dialog;
+mod progress;
 mod scroll;
 mod scroll_lines;
 mod scroll_list;
 mod sort_list_dialog;
 mod status;
+mod switcher_dialog;
 mod text_preview;
+mod text_stats_dialog;
 
 pub use bar::Bar;
+pub use batch_rename_dialog::{BatchRenameDialog, BatchRenameDialogState};
+pub use bucket_switcher_dialog::{BucketSwitcherDialog, BucketSwitcherDialogState};
+pub use confirm_dialog::ConfirmDialog;
 pub use copy_detail_dialog::{CopyDetailDialog, CopyDetailDialogState};
 pub use dialog::Dialog;
 pub use divider::Divider;
+pub use download_confirm_dialog::{DownloadConfirmDialog, DownloadConfirmDialogState};
 pub use header::Header;
 pub use image_preview::{ImagePicker, ImagePreview, ImagePreviewState};
 pub use input_dialog::{InputDialog, InputDialogState};
 pub use loading_dialog::LoadingDialog;
+pub use magic_panel::{is_binary, MagicInfo, MagicPanel};
+pub use metadata_dialog::{MetadataDialog, MetadataDialogState};
+pub use presign_dialog::{
+    parse_presign_expires_in, PresignDialog, PresignDialogState, DEFAULT_PRESIGN_EXPIRES_IN,
+};
+pub use progress::ProgressBar;
 pub use scroll::ScrollBar;
 pub use scroll_lines::{ScrollLines, ScrollLinesOptions, ScrollLinesState};
 pub use scroll_list::{ScrollList, ScrollListState};
@@ -30,4 +50,6 @@ pub use sort_list_dialog::{
     ObjectListSortDialogState, ObjectListSortType,
 };
 pub use status::{Status, StatusType};
-pub use text_preview::{TextPreview, TextPreviewState};
+pub use switcher_dialog::{SwitcherDialog, SwitcherDialogState};
+pub use text_preview::{build_highlighted_html, TextPreview, TextPreviewState};
+pub use text_stats_dialog::{TextStats, TextStatsDialog};
@@ -0,0 +1,296 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyEvent,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{block::Title, Block, BorderType, Padding, Paragraph, StatefulWidget, WidgetRef},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+    color::ColorTheme,
+    error::Result,
+    rename::{build_rename_targets, RenameTarget},
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const LABEL_WIDTH: usize = 9;
+const MAX_PREVIEW_LINES: usize = 10;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum BatchRenameInputType {
+    #[default]
+    Pattern,
+    Replacement,
+}
+
+#[derive(Debug)]
+pub struct BatchRenameDialogState {
+    names: Vec<String>,
+    existing_names: Vec<String>,
+    pattern: Input,
+    replacement: Input,
+    selected: BatchRenameInputType,
+    targets: Result<Vec<RenameTarget>>,
+    cursor: (u16, u16),
+}
+
+impl BatchRenameDialogState {
+    pub fn new(names: Vec<String>, existing_names: Vec<String>) -> Self {
+        Self {
+            names,
+            existing_names,
+            pattern: Input::default(),
+            replacement: Input::default(),
+            selected: BatchRenameInputType::default(),
+            targets: Ok(Vec::new()),
+            cursor: (0, 0),
+        }
+    }
+
+    // Returns None if there is nothing to rename or the pattern is invalid
+    pub fn targets(&self) -> Option<&[RenameTarget]> {
+        match &self.targets {
+            Ok(targets) if !targets.is_empty() => Some(targets),
+            _ => None,
+        }
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    pub fn toggle_input(&mut self) {
+        self.selected = match self.selected {
+            BatchRenameInputType::Pattern => BatchRenameInputType::Replacement,
+            BatchRenameInputType::Replacement => BatchRenameInputType::Pattern,
+        };
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) {
+        let event = &ratatui::crossterm::event::Event::Key(key);
+        match self.selected {
+            BatchRenameInputType::Pattern => self.pattern.handle_event(event),
+            BatchRenameInputType::Replacement => self.replacement.handle_event(event),
+        };
+        self.update_targets();
+    }
+
+    fn update_targets(&mut self) {
+        let names: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        let existing_names: Vec<&str> = self.existing_names.iter().map(String::as_str).collect();
+        self.targets = build_rename_targets(
+            &names,
+            &existing_names,
+            self.pattern.value(),
+            self.replacement.value(),
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+struct BatchRenameDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+    disabled: Color,
+    error: Color,
+}
+
+impl BatchRenameDialogColor {
+    fn new(theme: &ColorTheme) -> BatchRenameDialogColor {
+        BatchRenameDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+            disabled: theme.divider,
+            error: theme.status_error,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BatchRenameDialog {
+    color: BatchRenameDialogColor,
+}
+
+impl BatchRenameDialog {
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = BatchRenameDialogColor::new(theme);
+        self
+    }
+}
+
+impl StatefulWidget for BatchRenameDialog {
+    type State = BatchRenameDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let dialog_width = (area.width - 4).min(70);
+        let input_max_width = (dialog_width as usize).saturating_sub(4 + LABEL_WIDTH);
+
+        let pattern_line = self.build_input_line(
+            "Find:",
+            &state.pattern,
+            state.selected == BatchRenameInputType::Pattern,
+            input_max_width,
+        );
+        let replacement_line = self.build_input_line(
+            "Replace:",
+            &state.replacement,
+            state.selected == BatchRenameInputType::Replacement,
+            input_max_width,
+        );
+        let mut lines = vec![pattern_line, replacement_line, Line::raw("")];
+        lines.extend(self.build_preview_lines(&state.targets));
+
+        let dialog_height = (lines.len() as u16 + 2).min(area.height - 2);
+        let dialog_area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let title = Title::from(format!("Rename {} objects", state.names.len()));
+        let dialog_content = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .bg(self.color.bg)
+                .fg(self.color.block)
+                .padding(Padding::horizontal(1)),
+        );
+        let dialog = Dialog::new(Box::new(dialog_content), self.color.bg);
+        dialog.render_ref(dialog_area, buf);
+
+        // update cursor position
+        let (input, row) = match state.selected {
+            BatchRenameInputType::Pattern => (&state.pattern, 1),
+            BatchRenameInputType::Replacement => (&state.replacement, 2),
+        };
+        let cursor_x = dialog_area.x
+            + 2
+            + LABEL_WIDTH as u16
+            + input.visual_cursor().min(input_max_width) as u16;
+        let cursor_y = dialog_area.y + row;
+        state.cursor = (cursor_x, cursor_y);
+    }
+}
+
+impl BatchRenameDialog {
+    fn build_input_line<'a>(
+        &self,
+        label: &'a str,
+        input: &'a Input,
+        selected: bool,
+        input_max_width: usize,
+    ) -> Line<'a> {
+        let label = format!("{:<LABEL_WIDTH$}", label);
+        let label = if selected {
+            label.fg(self.color.selected).bold()
+        } else {
+            label.fg(self.color.text)
+        };
+        // show the last `input_max_width` characters of the input
+        let input_start_index = input.visual_cursor().saturating_sub(input_max_width);
+        let input_view: &str = &input.value()[input_start_index..];
+        Line::from(vec![label, input_view.fg(self.color.text)])
+    }
+
+    fn build_preview_lines<'a>(&self, targets: &'a Result<Vec<RenameTarget>>) -> Vec<Line<'a>> {
+        let targets = match targets {
+            Ok(targets) => targets,
+            Err(e) => return vec![Line::from(e.msg.as_str().fg(self.color.error))],
+        };
+        if targets.is_empty() {
+            return vec![Line::from("No objects to rename".fg(self.color.disabled))];
+        }
+
+        let mut lines: Vec<Line> = targets
+            .iter()
+            .take(MAX_PREVIEW_LINES)
+            .map(|t| {
+                Line::from(vec![
+                    Span::raw(t.from.as_str()).fg(self.color.text),
+                    " -> ".fg(self.color.disabled),
+                    Span::raw(t.to.as_str()).fg(self.color.text),
+                ])
+            })
+            .collect();
+        if targets.len() > MAX_PREVIEW_LINES {
+            let msg = format!("... and {} more", targets.len() - MAX_PREVIEW_LINES);
+            lines.push(Line::from(msg.fg(self.color.disabled)));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{crossterm::event::KeyCode, style::Modifier};
+
+    use crate::set_cells;
+
+    use super::*;
+
+    fn type_str(state: &mut BatchRenameDialogState, s: &str) {
+        for c in s.chars() {
+            state.handle_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_render_batch_rename_dialog() {
+        let theme = ColorTheme::default();
+        let names = vec!["a.txt".to_string(), "b.txt".to_string()];
+        let existing_names = vec!["a.txt".to_string(), "b.txt".to_string(), "c".to_string()];
+        let mut state = BatchRenameDialogState::new(names, existing_names);
+        let dialog = BatchRenameDialog::default().theme(&theme);
+
+        type_str(&mut state, r"\.txt$");
+        state.toggle_input();
+        type_str(&mut state, ".md");
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 10));
+        dialog.render(buf.area, &mut buf, &mut state);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                                        ",
+            "  ╭Rename 2 objects──────────────────╮  ",
+            "  │ Find:    \\.txt$                  │  ",
+            "  │ Replace: .md                     │  ",
+            "  │                                  │  ",
+            "  │ a.txt -> a.md                    │  ",
+            "  │ b.txt -> b.md                    │  ",
+            "  ╰──────────────────────────────────╯  ",
+            "                                        ",
+            "                                        ",
+        ]);
+        set_cells! { expected =>
+            (4..13, [3]) => fg: Color::Cyan, modifier: Modifier::BOLD,
+            ([9, 10, 11, 12], [5, 6]) => fg: Color::DarkGray,
+        }
+
+        assert_eq!(buf, expected);
+        assert_eq!(state.cursor(), (16, 3));
+
+        let targets = state.targets().unwrap();
+        assert_eq!(targets.len(), 2);
+    }
+
+    #[test]
+    fn test_batch_rename_dialog_state_error() {
+        let names = vec!["a.txt".to_string()];
+        let existing_names = vec!["a.txt".to_string(), "b.txt".to_string()];
+        let mut state = BatchRenameDialogState::new(names, existing_names);
+        assert!(state.targets().is_none());
+
+        type_str(&mut state, "a");
+        state.toggle_input();
+        type_str(&mut state, "b");
+        assert!(state.targets().is_none());
+
+        state.handle_key_event(KeyEvent::from(KeyCode::Backspace));
+        type_str(&mut state, "c");
+        assert_eq!(state.targets().unwrap()[0].to, "c.txt");
+    }
+}
@@ -0,0 +1,249 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyEvent,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, BorderType, Padding, Paragraph, StatefulWidget, WidgetRef},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+    color::ColorTheme,
+    util::fuzzy_match,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const MAX_LIST_HEIGHT: usize = 10;
+const MAX_DIALOG_WIDTH: u16 = 60;
+
+#[derive(Debug, Default)]
+pub struct BucketSwitcherDialogState {
+    names: Vec<String>,
+    input: Input,
+    filtered_indices: Vec<usize>,
+    selected: usize,
+    offset: usize,
+    cursor: (u16, u16),
+}
+
+impl BucketSwitcherDialogState {
+    pub fn new(names: Vec<String>) -> Self {
+        let filtered_indices = (0..names.len()).collect();
+        Self {
+            names,
+            filtered_indices,
+            ..Default::default()
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) {
+        let event = &ratatui::crossterm::event::Event::Key(key);
+        if self.input.handle_event(event).is_some_and(|c| c.value) {
+            self.update_filter();
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.filtered_indices.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_name(&self) -> Option<&str> {
+        self.filtered_indices
+            .get(self.selected)
+            .map(|i| self.names[*i].as_str())
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    // better matches come first, ties keep the original order of the bucket list
+    fn update_filter(&mut self) {
+        let query = self.input.value();
+        let mut scored: Vec<(usize, usize)> = self
+            .names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| fuzzy_match(query, name).map(|score| (i, score)))
+            .collect();
+        scored.sort_by(|(_, s1), (_, s2)| s2.cmp(s1));
+        self.filtered_indices = scored.into_iter().map(|(i, _)| i).collect();
+        self.selected = 0;
+        self.offset = 0;
+    }
+}
+
+#[derive(Debug, Default)]
+struct BucketSwitcherDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+    divider: Color,
+}
+
+impl BucketSwitcherDialogColor {
+    fn new(theme: &ColorTheme) -> BucketSwitcherDialogColor {
+        BucketSwitcherDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+            divider: theme.divider,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BucketSwitcherDialog {
+    color: BucketSwitcherDialogColor,
+}
+
+impl BucketSwitcherDialog {
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = BucketSwitcherDialogColor::new(theme);
+        self
+    }
+}
+
+impl StatefulWidget for BucketSwitcherDialog {
+    type State = BucketSwitcherDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let list_height = state.names.len().clamp(1, MAX_LIST_HEIGHT);
+        let dialog_width = (area.width - 4).min(MAX_DIALOG_WIDTH);
+        let dialog_height = (list_height as u16 + 4/* border + input + divider */).min(area.height);
+        let dialog_area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let list_height = (dialog_height as usize).saturating_sub(4).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + list_height {
+            state.offset = state.selected + 1 - list_height;
+        }
+
+        // show the last `input_max_width` characters of the input
+        let input_max_width = (dialog_width - 4) as usize;
+        let input_start_index = state.input.visual_cursor().saturating_sub(input_max_width);
+        let input_view: &str = &state.input.value()[input_start_index..];
+
+        let mut lines = vec![
+            Line::from(input_view.fg(self.color.text)),
+            Line::from("─".repeat(input_max_width).fg(self.color.divider)),
+        ];
+        if state.filtered_indices.is_empty() {
+            lines.push(Line::from("No matching buckets".fg(self.color.divider)));
+        }
+        lines.extend(
+            state
+                .filtered_indices
+                .iter()
+                .enumerate()
+                .skip(state.offset)
+                .take(list_height)
+                .map(|(i, name_index)| {
+                    let name = state.names[*name_index].as_str();
+                    if i == state.selected {
+                        Line::from(name.fg(self.color.selected))
+                    } else {
+                        Line::from(name.fg(self.color.text))
+                    }
+                }),
+        );
+
+        let title = Title::from("Switch bucket");
+        let dialog_content = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .bg(self.color.bg)
+                .fg(self.color.block)
+                .padding(Padding::horizontal(1)),
+        );
+        let dialog = Dialog::new(Box::new(dialog_content), self.color.bg);
+        dialog.render_ref(dialog_area, buf);
+
+        // update cursor position
+        let cursor_x = dialog_area.x + state.input.visual_cursor().min(input_max_width) as u16 + 2;
+        let cursor_y = dialog_area.y + 1;
+        state.cursor = (cursor_x, cursor_y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+
+    use crate::set_cells;
+
+    use super::*;
+
+    fn state() -> BucketSwitcherDialogState {
+        let names = ["foo-bucket", "bar-bucket", "logs", "baz"];
+        BucketSwitcherDialogState::new(names.into_iter().map(String::from).collect())
+    }
+
+    #[test]
+    fn test_bucket_switcher_dialog_state_filter() {
+        let mut state = state();
+        assert_eq!(state.selected_name(), Some("foo-bucket"));
+
+        for c in "bk".chars() {
+            state.handle_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(state.selected_name(), Some("foo-bucket"));
+        state.select_next();
+        assert_eq!(state.selected_name(), Some("bar-bucket"));
+        state.select_next();
+        assert_eq!(state.selected_name(), Some("bar-bucket"));
+
+        state.handle_key_event(KeyEvent::from(KeyCode::Backspace));
+        for c in "az".chars() {
+            state.handle_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(state.selected_name(), Some("baz"));
+
+        state.handle_key_event(KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(state.selected_name(), None);
+    }
+
+    #[test]
+    fn test_render_bucket_switcher_dialog() {
+        let theme = ColorTheme::default();
+        let mut state = state();
+        state.handle_key_event(KeyEvent::from(KeyCode::Char('b')));
+        state.select_next();
+        let dialog = BucketSwitcherDialog::default().theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 10));
+        dialog.render(buf.area, &mut buf, &mut state);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                              ",
+            "  ╭Switch bucket───────────╮  ",
+            "  │ b                      │  ",
+            "  │ ────────────────────── │  ",
+            "  │ foo-bucket             │  ",
+            "  │ bar-bucket             │  ",
+            "  │ baz                    │  ",
+            "  │                        │  ",
+            "  ╰────────────────────────╯  ",
+            "                              ",
+        ]);
+        set_cells! { expected =>
+            (4..26, [3]) => fg: Color::DarkGray,
+            (4..14, [5]) => fg: Color::Cyan,
+        }
+
+        assert_eq!(buf, expected);
+        assert_eq!(state.cursor(), (5, 2));
+    }
+}
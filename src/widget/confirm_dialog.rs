@@ -0,0 +1,124 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, BorderType, Padding, Paragraph, Widget, WidgetRef},
+};
+
+use crate::{
+    color::ColorTheme,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const HELP_MESSAGE: &str = "Press y to confirm, n to cancel";
+
+#[derive(Debug, Default)]
+struct ConfirmDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    help: Color,
+}
+
+impl ConfirmDialogColor {
+    fn new(theme: &ColorTheme) -> ConfirmDialogColor {
+        ConfirmDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            help: theme.status_help,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ConfirmDialog {
+    title: String,
+    lines: Vec<String>,
+    color: ConfirmDialogColor,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: impl Into<String>, lines: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            ..Default::default()
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = ConfirmDialogColor::new(theme);
+        self
+    }
+}
+
+impl Widget for ConfirmDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let content_width = self
+            .lines
+            .iter()
+            .map(|l| l.chars().count())
+            .chain([HELP_MESSAGE.len(), self.title.chars().count()])
+            .max()
+            .unwrap_or_default() as u16;
+        let dialog_width = (content_width + 4).min(area.width - 4);
+        let dialog_height = (self.lines.len() as u16 + 4).min(area.height - 2);
+        let dialog_area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let mut lines: Vec<Line> = self
+            .lines
+            .into_iter()
+            .map(|l| Line::from(l.fg(self.color.text)))
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::from(HELP_MESSAGE.fg(self.color.help)));
+
+        let title = Title::from(self.title);
+        let dialog_content = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .bg(self.color.bg)
+                .fg(self.color.block)
+                .padding(Padding::horizontal(1)),
+        );
+        let dialog = Dialog::new(Box::new(dialog_content), self.color.bg);
+        dialog.render_ref(dialog_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::set_cells;
+
+    use super::*;
+
+    #[test]
+    fn test_render_confirm_dialog() {
+        let theme = ColorTheme::default();
+        let lines = vec!["Delete 2 objects?".to_string(), "  a/b.txt".to_string()];
+        let dialog = ConfirmDialog::new("Delete", lines).theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 8));
+        dialog.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                                        ",
+            "  ╭Delete───────────────────────────╮   ",
+            "  │ Delete 2 objects?               │   ",
+            "  │   a/b.txt                       │   ",
+            "  │                                 │   ",
+            "  │ Press y to confirm, n to cancel │   ",
+            "  ╰─────────────────────────────────╯   ",
+            "                                        ",
+        ]);
+        set_cells! { expected =>
+            (4..35, [5]) => fg: Color::DarkGray,
+        }
+
+        assert_eq!(buf, expected);
+    }
+}
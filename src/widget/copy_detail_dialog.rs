@@ -11,17 +11,20 @@ use ratatui::{
 
 use crate::{
     color::ColorTheme,
-    object::{BucketItem, FileDetail, FileVersion, ObjectItem},
+    object::{BucketItem, ETagType, FileDetail, FileVersion, ObjectItem},
     widget::{common::calc_centered_dialog_rect, Dialog},
 };
 
+const PRESIGNED_URL_PLACEHOLDER: &str = "(generated when copied)";
+
 #[derive(Default)]
 #[zero_indexed_enum]
 enum BucketListItemType {
     #[default]
     S3Uri,
     Arn,
-    ObjectUrl,
+    VirtualHostedUrl,
+    PathStyleUrl,
 }
 
 impl BucketListItemType {
@@ -29,7 +32,11 @@ impl BucketListItemType {
         let (name, value) = match self {
             Self::S3Uri => ("S3 URI", &bucket_item.s3_uri),
             Self::Arn => ("ARN", &bucket_item.arn),
-            Self::ObjectUrl => ("Object URL", &bucket_item.object_url),
+            Self::VirtualHostedUrl => (
+                "Object URL (virtual-hosted)",
+                &bucket_item.virtual_hosted_url,
+            ),
+            Self::PathStyleUrl => ("Object URL (path-style)", &bucket_item.path_style_url),
         };
         (name.into(), value.into())
     }
@@ -42,7 +49,9 @@ enum ObjectListFileItemType {
     Key,
     S3Uri,
     Arn,
-    ObjectUrl,
+    VirtualHostedUrl,
+    PathStyleUrl,
+    PresignedUrl,
     Etag,
 }
 
@@ -54,15 +63,20 @@ impl ObjectListFileItemType {
                 key,
                 s3_uri,
                 arn,
-                object_url,
+                virtual_hosted_url,
+                path_style_url,
                 e_tag,
                 ..
             } => match self {
-                Self::Key => ("Key", key),
-                Self::S3Uri => ("S3 URI", s3_uri),
-                Self::Arn => ("ARN", arn),
-                Self::ObjectUrl => ("Object URL", object_url),
-                Self::Etag => ("ETag", e_tag),
+                Self::Key => ("Key", key.as_str()),
+                Self::S3Uri => ("S3 URI", s3_uri.as_str()),
+                Self::Arn => ("ARN", arn.as_str()),
+                Self::VirtualHostedUrl => {
+                    ("Object URL (virtual-hosted)", virtual_hosted_url.as_str())
+                }
+                Self::PathStyleUrl => ("Object URL (path-style)", path_style_url.as_str()),
+                Self::PresignedUrl => ("Presigned URL", PRESIGNED_URL_PLACEHOLDER),
+                Self::Etag => return (ETagType::parse(e_tag).label(), e_tag.clone()),
             },
         };
         (name.into(), value.into())
@@ -75,7 +89,8 @@ enum ObjectListDirItemType {
     #[default]
     Key,
     S3Uri,
-    ObjectUrl,
+    VirtualHostedUrl,
+    PathStyleUrl,
 }
 
 impl ObjectListDirItemType {
@@ -84,12 +99,14 @@ impl ObjectListDirItemType {
             ObjectItem::Dir {
                 key,
                 s3_uri,
-                object_url,
+                virtual_hosted_url,
+                path_style_url,
                 ..
             } => match self {
                 Self::Key => ("Key", key),
                 Self::S3Uri => ("S3 URI", s3_uri),
-                Self::ObjectUrl => ("Object URL", object_url),
+                Self::VirtualHostedUrl => ("Object URL (virtual-hosted)", virtual_hosted_url),
+                Self::PathStyleUrl => ("Object URL (path-style)", path_style_url),
             },
             ObjectItem::File { .. } => unreachable!(),
         };
@@ -104,18 +121,31 @@ enum ObjectDetailItemType {
     Key,
     S3Uri,
     Arn,
-    ObjectUrl,
+    VirtualHostedUrl,
+    PathStyleUrl,
+    PresignedUrl,
     Etag,
 }
 
 impl ObjectDetailItemType {
     fn name_and_value(&self, file_detail: &FileDetail) -> (String, String) {
         let (name, value) = match self {
-            Self::Key => ("Key", &file_detail.key),
-            Self::S3Uri => ("S3 URI", &file_detail.s3_uri),
-            Self::Arn => ("ARN", &file_detail.arn),
-            Self::ObjectUrl => ("Object URL", &file_detail.object_url),
-            Self::Etag => ("ETag", &file_detail.e_tag),
+            Self::Key => ("Key", file_detail.key.as_str()),
+            Self::S3Uri => ("S3 URI", file_detail.s3_uri.as_str()),
+            Self::Arn => ("ARN", file_detail.arn.as_str()),
+            Self::VirtualHostedUrl => (
+                "Object URL (virtual-hosted)",
+                file_detail.virtual_hosted_url.as_str(),
+            ),
+            Self::PathStyleUrl => (
+                "Object URL (path-style)",
+                file_detail.path_style_url.as_str(),
+            ),
+            Self::PresignedUrl => ("Presigned URL", PRESIGNED_URL_PLACEHOLDER),
+            Self::Etag => {
+                let label = ETagType::parse(&file_detail.e_tag).label();
+                return (label, file_detail.e_tag.clone());
+            }
         };
         (name.into(), value.into())
     }
@@ -128,7 +158,9 @@ enum ObjectVersionItemType {
     Key,
     S3Uri,
     Arn,
-    ObjectUrl,
+    VirtualHostedUrl,
+    PathStyleUrl,
+    PresignedUrl,
     Etag,
 }
 
@@ -139,13 +171,24 @@ impl ObjectVersionItemType {
         file_version: &FileVersion,
     ) -> (String, String) {
         let (name, value) = match self {
-            Self::Key => ("Key", &file_detail.key),
-            Self::S3Uri => ("S3 URI", &file_version.s3_uri(file_detail)),
-            Self::Arn => ("ARN", &file_detail.arn),
-            Self::ObjectUrl => ("Object URL", &file_version.object_url(file_detail)),
-            Self::Etag => ("ETag", &file_version.e_tag),
+            Self::Key => ("Key", file_detail.key.clone()),
+            Self::S3Uri => ("S3 URI", file_version.s3_uri(file_detail)),
+            Self::Arn => ("ARN", file_detail.arn.clone()),
+            Self::VirtualHostedUrl => (
+                "Object URL (virtual-hosted)",
+                file_version.virtual_hosted_url(file_detail),
+            ),
+            Self::PathStyleUrl => (
+                "Object URL (path-style)",
+                file_version.path_style_url(file_detail),
+            ),
+            Self::PresignedUrl => ("Presigned URL", PRESIGNED_URL_PLACEHOLDER.into()),
+            Self::Etag => {
+                let label = ETagType::parse(&file_version.e_tag).label();
+                return (label, file_version.e_tag.clone());
+            }
         };
-        (name.into(), value.into())
+        (name.into(), value)
     }
 }
 
@@ -223,6 +266,29 @@ impl CopyDetailDialogState {
         }
     }
 
+    // Presigned URLs are not generated until they are actually copied,
+    // so the key and version id are returned instead of the value
+    pub fn selected_presigned_url_target(&self) -> Option<(String, Option<String>)> {
+        match self {
+            Self::ObjectDetail(ObjectDetailItemType::PresignedUrl, file_detail) => {
+                Some((file_detail.key.clone(), None))
+            }
+            Self::ObjectVersion(ObjectVersionItemType::PresignedUrl, file_detail, file_version) => {
+                Some((
+                    file_detail.key.clone(),
+                    Some(file_version.version_id.clone()),
+                ))
+            }
+            Self::ObjectListFile(ObjectListFileItemType::PresignedUrl, object_item) => {
+                match object_item {
+                    ObjectItem::File { key, .. } => Some((key.clone(), None)),
+                    ObjectItem::Dir { .. } => unreachable!(),
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn name_and_value_vec(&self) -> Vec<(String, String)> {
         match self {
             Self::BucketList(_, bucket_item) => BucketListItemType::vars_array()
@@ -361,8 +427,6 @@ mod tests {
 
         #[rustfmt::skip]
         let mut expected = Buffer::with_lines([
-            "                                        ",
-            "                                        ",
             "                                        ",
             "                                        ",
             "  ╭Copy──────────────────────────────╮  ",
@@ -372,29 +436,35 @@ mod tests {
             "  │   s3://bucket-1/file.txt         │  ",
             "  │ ARN:                             │  ",
             "  │   arn:aws:s3:::bucket-1/file.txt │  ",
-            "  │ Object URL:                      │  ",
+            "  │ Object URL (virtual-hosted):     │  ",
             "  │   https://bucket-1.s3.ap-northea │  ",
+            "  │ Object URL (path-style):         │  ",
+            "  │   https://s3.ap-northeast-1.amaz │  ",
+            "  │ Presigned URL:                   │  ",
+            "  │   (generated when copied)        │  ",
             "  │ ETag:                            │  ",
             "  │   bef684de-a260-48a4-8178-8a535e │  ",
             "  ╰──────────────────────────────────╯  ",
             "                                        ",
             "                                        ",
-            "                                        ",
-            "                                        ",
         ]);
         set_cells! { expected =>
             // "Key" is bold
-            (4..8, [5]) => modifier: Modifier::BOLD,
+            (4..8, [3]) => modifier: Modifier::BOLD,
             // "S3 URI" is bold
-            (4..11, [7]) => modifier: Modifier::BOLD,
+            (4..11, [5]) => modifier: Modifier::BOLD,
             // "ARN" is bold
-            (4..8, [9]) => modifier: Modifier::BOLD,
-            // "Object URL" is bold
-            (4..15, [11]) => modifier: Modifier::BOLD,
+            (4..8, [7]) => modifier: Modifier::BOLD,
+            // "Object URL (virtual-hosted)" is bold
+            (4..32, [9]) => modifier: Modifier::BOLD,
+            // "Object URL (path-style)" is bold
+            (4..28, [11]) => modifier: Modifier::BOLD,
+            // "Presigned URL" is bold
+            (4..18, [13]) => modifier: Modifier::BOLD,
             // "ETag" is bold
-            (4..9, [13]) => modifier: Modifier::BOLD,
+            (4..9, [15]) => modifier: Modifier::BOLD,
             // selected item
-            (4..36, [5, 6]) => fg: Color::Cyan,
+            (4..36, [3, 4]) => fg: Color::Cyan,
         }
 
         assert_eq!(buf, expected);
@@ -408,10 +478,18 @@ mod tests {
             e_tag: "bef684de-a260-48a4-8178-8a535ecccadb".to_string(),
             content_type: "text/plain".to_string(),
             storage_class: "STANDARD".to_string(),
+            cache_control: "".to_string(),
+            content_disposition: "".to_string(),
+            user_metadata: Vec::new(),
+            object_lock_mode: String::new(),
+            object_lock_retain_until: None,
+            legal_hold: None,
             key: "file.txt".to_string(),
             s3_uri: "s3://bucket-1/file.txt".to_string(),
             arn: "arn:aws:s3:::bucket-1/file.txt".to_string(),
-            object_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file.txt".to_string(),
+            virtual_hosted_url: "https://bucket-1.s3.ap-northeast-1.amazonaws.com/file.txt"
+                .to_string(),
+            path_style_url: "https://s3.ap-northeast-1.amazonaws.com/bucket-1/file.txt".to_string(),
         }
     }
 
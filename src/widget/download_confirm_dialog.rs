@@ -0,0 +1,830 @@
+use std::{ops::Range, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyEvent,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::Line,
+    widgets::{
+        block::Title, Block, BorderType, List, ListItem, Padding, Paragraph, StatefulWidget,
+        WidgetRef,
+    },
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+    color::ColorTheme,
+    config::CostConfig,
+    download_list::DownloadObjectList,
+    error::{AppError, Result},
+    format::format_size_byte,
+    object::{DownloadObjectsOptions, DownloadOutputType, MAX_CONCURRENT_REQUESTS_LIMIT},
+    util::glob_match,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const DESTINATION_LABEL: &str = "Destination: ";
+
+// Only the folders keep their names, the names of the files are read from the list when rendered
+// since there can be as many rows as the objects.
+#[derive(Debug)]
+enum TreeRowKind {
+    Dir {
+        name: String,
+        objs_range: Range<usize>,
+    },
+    File {
+        objs_index: usize,
+    },
+}
+
+#[derive(Debug)]
+struct TreeRow {
+    depth: usize,
+    kind: TreeRowKind,
+}
+
+#[derive(Debug)]
+pub struct DownloadConfirmDialogState {
+    key: String,
+    objs: DownloadObjectList,
+    excluded: Vec<bool>,
+    filter: String,
+    options: DownloadObjectsOptions,
+    // without the extension of the archive
+    destination: String,
+    destination_input: Option<Input>,
+
+    rows: Vec<TreeRow>,
+    collapsed: Vec<bool>,
+    view_indices: Vec<usize>,
+
+    selected: usize,
+    offset: usize,
+    cursor: (u16, u16),
+}
+
+impl DownloadConfirmDialogState {
+    // The objects must be in the order of the keys
+    pub fn new(
+        key: String,
+        objs: DownloadObjectList,
+        destination: PathBuf,
+        max_concurrent_requests: usize,
+    ) -> Result<Self> {
+        let rows = build_tree_rows(&key, &objs)?;
+        let excluded = vec![false; objs.len()];
+        let collapsed = vec![false; rows.len()];
+        let view_indices = (0..rows.len()).collect();
+        Ok(Self {
+            key,
+            objs,
+            excluded,
+            filter: String::new(),
+            options: DownloadObjectsOptions {
+                max_concurrent_requests: max_concurrent_requests
+                    .clamp(1, MAX_CONCURRENT_REQUESTS_LIMIT),
+                ..Default::default()
+            },
+            destination: destination.to_string_lossy().into(),
+            destination_input: None,
+            rows,
+            collapsed,
+            view_indices,
+            selected: 0,
+            offset: 0,
+            cursor: (0, 0),
+        })
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.view_indices.len() {
+            self.selected += 1;
+        } else {
+            self.selected = 0;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        } else {
+            self.selected = self.view_indices.len().saturating_sub(1);
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = self.view_indices.len().saturating_sub(1);
+    }
+
+    pub fn toggle_selected(&mut self) {
+        let Some(&row_index) = self.view_indices.get(self.selected) else {
+            return;
+        };
+        match &self.rows[row_index].kind {
+            TreeRowKind::Dir { objs_range, .. } => {
+                let all_included = self.excluded[objs_range.clone()].iter().all(|e| !*e);
+                self.excluded[objs_range.clone()].fill(all_included);
+            }
+            TreeRowKind::File { objs_index } => {
+                self.excluded[*objs_index] = !self.excluded[*objs_index];
+            }
+        }
+    }
+
+    pub fn collapse_selected(&mut self) {
+        self.set_selected_collapsed(true);
+    }
+
+    pub fn expand_selected(&mut self) {
+        self.set_selected_collapsed(false);
+    }
+
+    fn set_selected_collapsed(&mut self, collapsed: bool) {
+        let Some(&row_index) = self.view_indices.get(self.selected) else {
+            return;
+        };
+        if let TreeRowKind::Dir { .. } = self.rows[row_index].kind {
+            self.collapsed[row_index] = collapsed;
+            self.update_view_indices();
+            // the selected row itself is always visible
+            self.selected = self
+                .view_indices
+                .iter()
+                .position(|i| *i == row_index)
+                .unwrap_or_default();
+        }
+    }
+
+    fn update_view_indices(&mut self) {
+        let mut view_indices = Vec::new();
+        let mut collapsed_depth: Option<usize> = None;
+        for (i, row) in self.rows.iter().enumerate() {
+            if let Some(depth) = collapsed_depth {
+                if row.depth > depth {
+                    continue;
+                }
+                collapsed_depth = None;
+            }
+            if self.collapsed[i] {
+                collapsed_depth = Some(row.depth);
+            }
+            view_indices.push(i);
+        }
+        self.view_indices = view_indices;
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn options(&self) -> DownloadObjectsOptions {
+        self.options
+    }
+
+    pub fn destination_path(&self) -> PathBuf {
+        let extension = self.options.output_type.extension();
+        PathBuf::from(format!("{}{}", self.destination, extension))
+    }
+
+    pub fn is_editing_destination(&self) -> bool {
+        self.destination_input.is_some()
+    }
+
+    pub fn start_edit_destination(&mut self) {
+        self.destination_input = Some(Input::new(self.destination.clone()));
+    }
+
+    pub fn cancel_edit_destination(&mut self) {
+        self.destination_input = None;
+    }
+
+    pub fn apply_destination_input(&mut self) -> Result<()> {
+        let Some(input) = &self.destination_input else {
+            return Ok(());
+        };
+        let destination = input.value().trim();
+        if destination.is_empty() {
+            return Err(AppError::msg("Destination must not be empty"));
+        }
+        self.destination = destination.trim_end_matches('/').to_string();
+        self.destination_input = None;
+        Ok(())
+    }
+
+    pub fn handle_destination_key_event(&mut self, key: KeyEvent) {
+        if let Some(input) = &mut self.destination_input {
+            let event = &ratatui::crossterm::event::Event::Key(key);
+            input.handle_event(event);
+        }
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    pub fn toggle_skip_type(&mut self) {
+        self.options.skip_type = self.options.skip_type.next();
+    }
+
+    pub fn toggle_layout_type(&mut self) {
+        self.options.layout_type = self.options.layout_type.next();
+    }
+
+    pub fn increase_max_concurrent_requests(&mut self) {
+        let n = self.options.max_concurrent_requests + 1;
+        self.options.max_concurrent_requests = n.min(MAX_CONCURRENT_REQUESTS_LIMIT);
+    }
+
+    pub fn decrease_max_concurrent_requests(&mut self) {
+        let n = self.options.max_concurrent_requests - 1;
+        self.options.max_concurrent_requests = n.max(1);
+    }
+
+    pub fn toggle_output_type(&mut self) {
+        self.options.output_type = self.options.output_type.next();
+    }
+
+    // The filter is the same format as the options of `aws s3 cp`, e.g. `--exclude * --include *.txt`.
+    // Patterns are matched against the keys relative to the target prefix, and later ones take precedence.
+    pub fn apply_filter(&mut self, filter: &str) -> Result<()> {
+        let rules = parse_filter_rules(filter)?;
+        let mut excluded = Vec::with_capacity(self.objs.len());
+        for obj in self.objs.iter()? {
+            let obj = obj?;
+            let relative_key = obj.key.strip_prefix(&self.key).unwrap_or(&obj.key);
+            let included = rules
+                .iter()
+                .rev()
+                .find(|(_, pattern)| glob_match(pattern, relative_key))
+                .map_or(true, |(include, _)| *include);
+            excluded.push(!included);
+        }
+        self.excluded = excluded;
+        self.filter = filter.trim().to_string();
+        Ok(())
+    }
+
+    pub fn download_objects(&self) -> Result<DownloadObjectList> {
+        self.objs.filter(|i| !self.excluded[i])
+    }
+
+    fn included_count_and_size(&self, range: Range<usize>) -> (usize, usize) {
+        self.objs.size_bytes()[range.clone()]
+            .iter()
+            .zip(self.excluded[range].iter())
+            .filter(|(_, excluded)| !**excluded)
+            .fold((0, 0), |(count, size), (size_byte, _)| {
+                (count + 1, size + size_byte)
+            })
+    }
+}
+
+fn parse_filter_rules(filter: &str) -> Result<Vec<(bool, &str)>> {
+    let mut rules = Vec::new();
+    let mut tokens = filter.split_whitespace();
+    while let Some(token) = tokens.next() {
+        let include = match token {
+            "--include" => true,
+            "--exclude" => false,
+            _ => return Err(AppError::msg(format!("Invalid filter option: {}", token))),
+        };
+        let pattern = tokens
+            .next()
+            .ok_or_else(|| AppError::msg(format!("Pattern is not specified for {}", token)))?;
+        rules.push((include, pattern));
+    }
+    Ok(rules)
+}
+
+// The objects are read from the list one by one, and the objects under a folder are
+// in a contiguous range since they are in the order of the keys.
+fn build_tree_rows(key: &str, objs: &DownloadObjectList) -> Result<Vec<TreeRow>> {
+    let mut rows: Vec<TreeRow> = Vec::new();
+    // (dir name, index of the row in rows)
+    let mut dir_stack: Vec<(String, usize)> = Vec::new();
+
+    for (i, obj) in objs.iter()?.enumerate() {
+        let obj = obj?;
+        let relative_key = obj.key.strip_prefix(key).unwrap_or(&obj.key);
+        let mut paths: Vec<&str> = relative_key.split('/').collect();
+        paths.pop();
+
+        let common = dir_stack
+            .iter()
+            .zip(paths.iter())
+            .take_while(|((name, _), path)| name == *path)
+            .count();
+        for (_, row_index) in dir_stack.drain(common..) {
+            close_dir_row(&mut rows[row_index], i);
+        }
+        for path in &paths[common..] {
+            let depth = dir_stack.len();
+            dir_stack.push((path.to_string(), rows.len()));
+            rows.push(TreeRow {
+                depth,
+                kind: TreeRowKind::Dir {
+                    name: path.to_string(),
+                    objs_range: i..i,
+                },
+            });
+        }
+
+        rows.push(TreeRow {
+            depth: dir_stack.len(),
+            kind: TreeRowKind::File { objs_index: i },
+        });
+    }
+    for (_, row_index) in dir_stack {
+        close_dir_row(&mut rows[row_index], objs.len());
+    }
+
+    Ok(rows)
+}
+
+fn close_dir_row(row: &mut TreeRow, end: usize) {
+    if let TreeRowKind::Dir { objs_range, .. } = &mut row.kind {
+        objs_range.end = end;
+    }
+}
+
+#[derive(Debug, Default)]
+struct DownloadConfirmDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+    excluded: Color,
+    cost: Color,
+}
+
+impl DownloadConfirmDialogColor {
+    fn new(theme: &ColorTheme) -> Self {
+        Self {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+            excluded: theme.divider,
+            cost: theme.status_warn,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DownloadConfirmDialog {
+    cost_config: CostConfig,
+    color: DownloadConfirmDialogColor,
+}
+
+impl DownloadConfirmDialog {
+    pub fn cost_config(mut self, cost_config: &CostConfig) -> Self {
+        self.cost_config = cost_config.clone();
+        self
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = DownloadConfirmDialogColor::new(theme);
+        self
+    }
+}
+
+impl StatefulWidget for DownloadConfirmDialog {
+    type State = DownloadConfirmDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let dialog_width = (area.width - 4).min(80);
+        let (summary_lines, destination_row) =
+            self.build_summary_lines(state, dialog_width.saturating_sub(4) as usize);
+        let summary_height = summary_lines.len() as u16 + 1;
+
+        let max_list_height = area
+            .height
+            .saturating_sub(2 /* border */ + summary_height + 2);
+        let list_height = (state.view_indices.len() as u16).min(max_list_height);
+        let dialog_height = list_height + summary_height + 2 /* border */;
+        let area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let list_height = list_height as usize;
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + list_height {
+            state.offset = state.selected + 1 - list_height;
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Title::from("Download"))
+            .bg(self.color.bg)
+            .fg(self.color.block)
+            .padding(Padding::horizontal(1));
+        let inner_area = block.inner(area);
+        let chunks = Layout::vertical([Constraint::Length(summary_height), Constraint::Min(0)])
+            .split(inner_area);
+
+        let summary = Paragraph::new(summary_lines).fg(self.color.text);
+
+        let list_width = chunks[1].width as usize;
+        let list_items: Vec<ListItem> = state
+            .view_indices
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(list_height)
+            .map(|(i, row_index)| self.build_list_item(state, *row_index, i, list_width))
+            .collect();
+        let list = List::new(list_items);
+
+        let dialog = Dialog::new(Box::new(block), self.color.bg);
+        dialog.render_ref(area, buf);
+        summary.render_ref(chunks[0], buf);
+        list.render_ref(chunks[1], buf);
+
+        // update cursor position
+        if let Some(input) = &state.destination_input {
+            let input_max_width =
+                (chunks[0].width as usize).saturating_sub(DESTINATION_LABEL.len());
+            let cursor_x = chunks[0].x
+                + DESTINATION_LABEL.len() as u16
+                + input.visual_cursor().min(input_max_width) as u16;
+            let cursor_y = chunks[0].y + destination_row as u16;
+            state.cursor = (cursor_x, cursor_y);
+        }
+    }
+}
+
+impl DownloadConfirmDialog {
+    // Returns the lines and the row index of the destination line
+    fn build_summary_lines<'a>(
+        &self,
+        state: &DownloadConfirmDialogState,
+        width: usize,
+    ) -> (Vec<Line<'a>>, usize) {
+        let (count, size) = state.included_count_and_size(0..state.objs.len());
+        let mut summary = format!("{} objects ({})", count, format_size_byte(size));
+        let excluded_count = state.objs.len() - count;
+        if excluded_count > 0 {
+            summary.push_str(&format!(", {} excluded", excluded_count));
+        }
+
+        let mut lines = vec![Line::from(summary.add_modifier(Modifier::BOLD))];
+        if let Some(cost) = self.cost_config.estimate_download_cost(count, size) {
+            let cost = format!(
+                "Estimated cost: ${:.2} (GET requests and data transfer)",
+                cost
+            );
+            lines.push(Line::from(cost.fg(self.color.cost)));
+        }
+
+        let destination_row = lines.len();
+        lines.push(self.build_destination_line(state, width));
+
+        let options = &state.options;
+        lines.extend([Line::from(format!("Output: {}", options.output_type.str()))]);
+        // existing files are not checked when writing into an archive
+        if options.output_type == DownloadOutputType::Files {
+            lines.push(Line::from(format!(
+                "Existing files: {}",
+                options.skip_type.str()
+            )));
+        }
+        lines.push(Line::from(format!("Layout: {}", options.layout_type.str())));
+        lines.push(Line::from(format!(
+            "Concurrent requests: {}",
+            options.max_concurrent_requests
+        )));
+        if !state.filter.is_empty() {
+            lines.push(Line::from(format!("Filter: {}", state.filter)));
+        }
+        (lines, destination_row)
+    }
+
+    fn build_destination_line<'a>(
+        &self,
+        state: &DownloadConfirmDialogState,
+        width: usize,
+    ) -> Line<'a> {
+        match &state.destination_input {
+            Some(input) => {
+                // show the last characters of the input so that the cursor is always visible
+                let input_max_width = width.saturating_sub(DESTINATION_LABEL.len());
+                let input_start_index = input.visual_cursor().saturating_sub(input_max_width);
+                let input_view: String = input.value().chars().skip(input_start_index).collect();
+                Line::from(vec![
+                    DESTINATION_LABEL.fg(self.color.selected).bold(),
+                    input_view.fg(self.color.text),
+                ])
+            }
+            None => {
+                let path = state.destination_path().to_string_lossy().to_string();
+                Line::from(format!("{}{}", DESTINATION_LABEL, path))
+            }
+        }
+    }
+
+    fn build_list_item<'a>(
+        &self,
+        state: &DownloadConfirmDialogState,
+        row_index: usize,
+        i: usize,
+        width: usize,
+    ) -> ListItem<'a> {
+        let row = &state.rows[row_index];
+        let indent = "  ".repeat(row.depth);
+        let (check, name, size_byte, excluded) = match &row.kind {
+            TreeRowKind::Dir { name, objs_range } => {
+                let (count, size) = state.included_count_and_size(objs_range.clone());
+                let check = if count == objs_range.len() {
+                    "[x]"
+                } else if count == 0 {
+                    "[ ]"
+                } else {
+                    "[-]"
+                };
+                let marker = if state.collapsed[row_index] {
+                    "▸"
+                } else {
+                    "▾"
+                };
+                let name = format!("{}{} {}/", indent, marker, name);
+                (check, name, size, count == 0)
+            }
+            TreeRowKind::File { objs_index } => {
+                let excluded = state.excluded[*objs_index];
+                let check = if excluded { "[ ]" } else { "[x]" };
+                // the name is left empty if the list cannot be read
+                let file_name = state
+                    .objs
+                    .get(*objs_index)
+                    .map(|obj| obj.key.rsplit('/').next().unwrap_or_default().to_string())
+                    .unwrap_or_default();
+                let name = format!("{}  {}", indent, file_name);
+                (check, name, state.objs.size_bytes()[*objs_index], excluded)
+            }
+        };
+
+        let size_width: usize = 10;
+        let size = format!("{:>size_width$}", format_size_byte(size_byte));
+        let name_width = width.saturating_sub(check.len() + size_width + 2 /* spaces */);
+        let name = if name.chars().count() > name_width {
+            let mut name: String = name.chars().take(name_width.saturating_sub(2)).collect();
+            name.push_str("..");
+            name
+        } else {
+            name
+        };
+        let line = format!("{} {:<name_width$} {}", check, name, size);
+
+        let item = ListItem::new(Line::from(line));
+        if i == state.selected {
+            item.fg(self.color.selected)
+        } else if excluded {
+            item.fg(self.color.excluded)
+        } else {
+            item.fg(self.color.text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveDateTime};
+    use ratatui::crossterm::event::KeyCode;
+
+    use crate::{object::DownloadObjectInfo, set_cells};
+
+    use super::*;
+
+    #[test]
+    fn test_render_download_confirm_dialog() {
+        let theme = ColorTheme::default();
+        let mut state = state();
+        let dialog = DownloadConfirmDialog::default().theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 17));
+        dialog.render(buf.area, &mut buf, &mut state);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                                        ",
+            "  ╭Download──────────────────────────╮  ",
+            "  │ 5 objects (2.13 KiB)             │  ",
+            "  │ Destination: /d/x                │  ",
+            "  │ Output: Files                    │  ",
+            "  │ Existing files: Overwrite        │  ",
+            "  │ Layout: Preserve structure       │  ",
+            "  │ Concurrent requests: 4           │  ",
+            "  │                                  │  ",
+            "  │ [x] ▾ a/                2.01 KiB │  ",
+            "  │ [x]   ▾ b/                 1 KiB │  ",
+            "  │ [x]       file3.txt        1 KiB │  ",
+            "  │ [x]     file1.txt          1 KiB │  ",
+            "  │ [x]     file2.txt           10 B │  ",
+            "  │ [x]   file4.txt            100 B │  ",
+            "  ╰──────────────────────────────────╯  ",
+            "                                        ",
+        ]);
+        set_cells! { expected =>
+            (4..24, [2]) => modifier: Modifier::BOLD,
+            (4..36, [9]) => fg: Color::Cyan,
+        }
+
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_render_download_confirm_dialog_with_cost() {
+        let theme = ColorTheme::default();
+        let mut state = state();
+        let cost_config = CostConfig {
+            display_threshold: 0.0,
+            ..Default::default()
+        };
+        let dialog = DownloadConfirmDialog::default()
+            .cost_config(&cost_config)
+            .theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 12));
+        dialog.render(buf.area, &mut buf, &mut state);
+
+        let line: String = buf.content[40 * 3 + 4..40 * 3 + 36]
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert_eq!(line, "Estimated cost: $0.00 (GET reque");
+        assert_eq!(buf.content[40 * 3 + 4].fg, Color::Yellow);
+    }
+
+    #[test]
+    fn test_download_confirm_dialog_state_toggle() {
+        let mut state = state();
+
+        // exclude "a/"
+        state.toggle_selected();
+        assert_eq!(download_keys(&state), vec!["x/file4.txt", "x/file5.txt"]);
+
+        // include "a/b/file3.txt"
+        state.select_next();
+        state.select_next();
+        state.toggle_selected();
+        assert_eq!(
+            download_keys(&state),
+            vec!["x/a/b/file3.txt", "x/file4.txt", "x/file5.txt"]
+        );
+
+        // include all under "a/" because "a/" is partially included
+        state.select_first();
+        state.toggle_selected();
+        assert_eq!(download_keys(&state).len(), 5);
+    }
+
+    #[test]
+    fn test_download_confirm_dialog_state_max_concurrent_requests() {
+        let mut state = state();
+        assert_eq!(state.options().max_concurrent_requests, 4);
+
+        for _ in 0..4 {
+            state.decrease_max_concurrent_requests();
+        }
+        assert_eq!(state.options().max_concurrent_requests, 1);
+
+        for _ in 0..40 {
+            state.increase_max_concurrent_requests();
+        }
+        assert_eq!(state.options().max_concurrent_requests, 32);
+    }
+
+    #[test]
+    fn test_download_confirm_dialog_state_collapse() {
+        let mut state = state();
+
+        state.select_next();
+        // collapse "a/b/"
+        state.collapse_selected();
+        assert_eq!(state.view_indices, vec![0, 1, 3, 4, 5, 6]);
+        assert_eq!(state.selected, 1);
+
+        state.select_first();
+        // collapse "a/"
+        state.collapse_selected();
+        assert_eq!(state.view_indices, vec![0, 5, 6]);
+
+        // expand "a/" (and "a/b/" is still collapsed)
+        state.expand_selected();
+        assert_eq!(state.view_indices, vec![0, 1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_download_confirm_dialog_state_apply_filter() {
+        let mut state = state();
+
+        state.apply_filter("--exclude a/*").unwrap();
+        assert_eq!(download_keys(&state), vec!["x/file4.txt", "x/file5.txt"]);
+
+        state
+            .apply_filter("--exclude * --include *1.txt --include *5.txt")
+            .unwrap();
+        assert_eq!(download_keys(&state), vec!["x/a/file1.txt", "x/file5.txt"]);
+
+        let theme = ColorTheme::default();
+        let dialog = DownloadConfirmDialog::default().theme(&theme);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 10));
+        dialog.render(buf.area, &mut buf, &mut state);
+        assert_eq!(
+            buf.content[40 * 7 + 4..40 * 7 + 30]
+                .iter()
+                .map(|c| c.symbol())
+                .collect::<String>(),
+            "Filter: --exclude * --incl"
+        );
+
+        state.apply_filter("").unwrap();
+        assert_eq!(download_keys(&state).len(), 5);
+
+        assert!(state.apply_filter("--exclude").is_err());
+        assert!(state.apply_filter("*.txt").is_err());
+    }
+
+    #[test]
+    fn test_download_confirm_dialog_state_edit_destination() {
+        let mut state = state();
+        assert_eq!(state.destination_path(), PathBuf::from("/d/x"));
+
+        state.start_edit_destination();
+        assert!(state.is_editing_destination());
+        for c in "yz/".chars() {
+            state.handle_destination_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+
+        let theme = ColorTheme::default();
+        let dialog = DownloadConfirmDialog::default().theme(&theme);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 16));
+        dialog.render(buf.area, &mut buf, &mut state);
+        assert_eq!(state.cursor(), (24, 3));
+
+        state.apply_destination_input().unwrap();
+        assert!(!state.is_editing_destination());
+        state.toggle_output_type();
+        assert_eq!(state.destination_path(), PathBuf::from("/d/xyz.tar.gz"));
+
+        // the destination is not changed when editing is canceled or the input is empty
+        state.start_edit_destination();
+        state.handle_destination_key_event(KeyEvent::from(KeyCode::Char('w')));
+        state.cancel_edit_destination();
+        assert_eq!(state.destination_path(), PathBuf::from("/d/xyz.tar.gz"));
+
+        state.start_edit_destination();
+        for _ in 0..6 {
+            state.handle_destination_key_event(KeyEvent::from(KeyCode::Backspace));
+        }
+        assert!(state.apply_destination_input().is_err());
+        assert!(state.is_editing_destination());
+    }
+
+    fn state() -> DownloadConfirmDialogState {
+        let objs = vec![
+            download_object_info("x/a/b/file3.txt", 1024),
+            download_object_info("x/a/file1.txt", 1024),
+            download_object_info("x/a/file2.txt", 10),
+            download_object_info("x/file4.txt", 100),
+            download_object_info("x/file5.txt", 20),
+        ];
+        let objs = DownloadObjectList::from_objs(objs).unwrap();
+        DownloadConfirmDialogState::new("x/".to_string(), objs, PathBuf::from("/d/x"), 4).unwrap()
+    }
+
+    fn download_keys(state: &DownloadConfirmDialogState) -> Vec<String> {
+        state
+            .download_objects()
+            .unwrap()
+            .iter()
+            .unwrap()
+            .map(|obj| obj.unwrap().key)
+            .collect()
+    }
+
+    fn download_object_info(key: &str, size_byte: usize) -> DownloadObjectInfo {
+        DownloadObjectInfo {
+            key: key.to_string(),
+            size_byte,
+            last_modified: parse_datetime("2024-01-02 13:01:02"),
+            e_tag: "".to_string(),
+        }
+    }
+
+    fn parse_datetime(s: &str) -> DateTime<Local> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+    }
+}
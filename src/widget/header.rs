@@ -2,6 +2,7 @@ use ratatui::{
     buffer::Buffer,
     layout::{Margin, Rect},
     style::{Color, Stylize},
+    text::Line,
     widgets::{Block, Padding, Paragraph, Widget},
 };
 
@@ -11,6 +12,7 @@ use crate::{color::ColorTheme, constant::APP_NAME, util::prune_strings_to_fit_wi
 struct HeaderColor {
     block: Color,
     text: Color,
+    warn: Color,
 }
 
 impl HeaderColor {
@@ -18,6 +20,7 @@ impl HeaderColor {
         HeaderColor {
             block: theme.fg,
             text: theme.fg,
+            warn: theme.status_warn,
         }
     }
 }
@@ -25,6 +28,10 @@ impl HeaderColor {
 #[derive(Debug, Default)]
 pub struct Header {
     breadcrumb: Vec<String>,
+    // whether the current page is loading something in the background
+    loading: bool,
+    // whether the TLS certificates are not verified
+    insecure: bool,
     color: HeaderColor,
 }
 
@@ -36,6 +43,16 @@ impl Header {
         }
     }
 
+    pub fn loading(mut self, loading: bool) -> Self {
+        self.loading = loading;
+        self
+    }
+
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
     pub fn theme(mut self, theme: &ColorTheme) -> Self {
         self.color = HeaderColor::new(theme);
         self
@@ -51,6 +68,8 @@ impl Widget for Header {
 impl Header {
     const DELIMITER: &'static str = " / ";
     const ELLIPSIS: &'static str = "...";
+    const LOADING: &'static str = "Loading...";
+    const INSECURE: &'static str = " TLS VERIFICATION DISABLED ";
 
     fn render_header(self, area: Rect, buf: &mut Buffer) {
         let inner_area = area.inner(Margin::new(1, 1));
@@ -59,14 +78,22 @@ impl Header {
 
         let block_color = self.color.block;
         let text_color = self.color.text;
+        let loading = self.loading;
+        let insecure = self.insecure;
+        let warn_color = self.color.warn;
         let current_key_str = self.build_current_key_str(max_width).fg(text_color);
 
-        let paragraph = Paragraph::new(current_key_str).block(
-            Block::bordered()
-                .title(APP_NAME)
-                .fg(block_color)
-                .padding(pad),
-        );
+        let mut block = Block::bordered()
+            .title(APP_NAME)
+            .fg(block_color)
+            .padding(pad);
+        if insecure {
+            block = block.title(Line::from(Self::INSECURE.fg(warn_color).bold()).centered());
+        }
+        if loading {
+            block = block.title(Line::from(Self::LOADING).right_aligned());
+        }
+        let paragraph = Paragraph::new(current_key_str).block(block);
 
         paragraph.render(area, buf);
     }
@@ -101,6 +128,8 @@ impl Header {
 
 #[cfg(test)]
 mod tests {
+    use ratatui::style::Style;
+
     use super::*;
 
     #[test]
@@ -143,6 +172,44 @@ mod tests {
         assert_eq!(buf, expected);
     }
 
+    #[test]
+    fn test_render_header_loading() {
+        let theme = ColorTheme::default();
+        let breadcrumb = vec!["bucket".to_string()];
+        let header = Header::new(breadcrumb).loading(true).theme(&theme);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30 + 4, 3));
+        header.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "┌STU───────────────────Loading...┐",
+            "│ bucket                         │",
+            "└────────────────────────────────┘",
+        ]);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_render_header_insecure() {
+        let theme = ColorTheme::default();
+        let breadcrumb = vec!["bucket".to_string()];
+        let header = Header::new(breadcrumb).insecure(true).theme(&theme);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 46 + 4, 3));
+        header.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "┌STU─────── TLS VERIFICATION DISABLED ───────────┐",
+            "│ bucket                                         │",
+            "└────────────────────────────────────────────────┘",
+        ]);
+        expected.set_style(
+            Rect::new(11, 0, 27, 1),
+            Style::default().fg(Color::Yellow).bold(),
+        );
+        assert_eq!(buf, expected);
+    }
+
     #[test]
     fn test_render_header_empty() {
         let theme = ColorTheme::default();
@@ -3,10 +3,13 @@ use std::{
     io::Cursor,
 };
 
-use image::{DynamicImage, ImageReader};
+use image::{
+    imageops::FilterType, metadata::Orientation, DynamicImage, ImageDecoder, ImageReader, RgbImage,
+};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::Color,
     widgets::{Block, Padding, StatefulWidget, Widget},
 };
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
@@ -15,10 +18,19 @@ use crate::format::format_version;
 
 pub struct ImagePreviewState {
     protocol: Option<StatefulProtocol>,
+    // used instead of the protocol if the terminal does not support any graphics protocol
+    fallback: Option<FallbackImage>,
     // to control image rendering when dialogs are overlapped...
     render: bool,
 }
 
+// The decoded image drawn with colored half blocks, each cell shows two vertical pixels
+struct FallbackImage {
+    image: DynamicImage,
+    // the image resized for the last rendered area, to avoid resizing on every render
+    resized: Option<(Rect, RgbImage)>,
+}
+
 impl Debug for ImagePreviewState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "ImagePreviewState")
@@ -33,21 +45,31 @@ pub enum ImagePicker {
 
 impl ImagePreviewState {
     pub fn new(bytes: &[u8], image_picker: ImagePicker) -> (Self, Option<String>) {
-        match build_image_protocol(bytes, image_picker) {
-            Ok(protocol) => {
-                let state = ImagePreviewState {
-                    protocol: Some(protocol),
-                    render: true,
-                };
-                (state, None)
-            }
-            Err(e) => {
-                let state = ImagePreviewState {
-                    protocol: None,
-                    render: true,
-                };
-                (state, Some(e))
-            }
+        let mut state = ImagePreviewState {
+            protocol: None,
+            fallback: None,
+            render: true,
+        };
+        match image_picker {
+            ImagePicker::Ok(picker) => match decode_image(bytes) {
+                Ok(img) => {
+                    state.protocol = Some(picker.new_resize_protocol(img));
+                    (state, None)
+                }
+                Err(e) => (state, Some(e)),
+            },
+            ImagePicker::Error(e) => match decode_image(bytes) {
+                Ok(image) => {
+                    state.fallback = Some(FallbackImage {
+                        image,
+                        resized: None,
+                    });
+                    let msg = format!("Showing a coarse preview: failed to create picker: {e}");
+                    (state, Some(msg))
+                }
+                Err(e) => (state, Some(e)),
+            },
+            ImagePicker::Disabled => (state, Some("Image preview is disabled".into())),
         }
     }
 
@@ -56,22 +78,60 @@ impl ImagePreviewState {
     }
 }
 
-fn build_image_protocol(
-    bytes: &[u8],
-    image_picker: ImagePicker,
-) -> Result<StatefulProtocol, String> {
-    match image_picker {
-        ImagePicker::Ok(picker) => {
-            let reader = ImageReader::new(Cursor::new(bytes))
-                .with_guessed_format()
-                .map_err(|e| format!("Failed to guess image format: {e}"))?;
-            let img: DynamicImage = reader
-                .decode()
-                .map_err(|e| format!("Failed to decode image: {e}"))?;
-            Ok(picker.new_resize_protocol(img))
+// The image is rotated by the EXIF orientation, e.g. photos taken on phones are stored sideways
+fn decode_image(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to guess image format: {e}"))?;
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| format!("Failed to decode image: {e}"))?;
+    // the image is shown as is if the orientation cannot be read
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut img =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode image: {e}"))?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+impl FallbackImage {
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let resized = match &self.resized {
+            Some((resized_area, resized)) if *resized_area == area => resized,
+            _ => {
+                // the aspect ratio is kept, the pixels are roughly square as the cells are about 1:2
+                let resized = self
+                    .image
+                    .resize(
+                        area.width as u32,
+                        area.height as u32 * 2,
+                        FilterType::Triangle,
+                    )
+                    .to_rgb8();
+                &self.resized.insert((area, resized)).1
+            }
+        };
+
+        let pixel = |x: u32, y: u32| {
+            resized
+                .get_pixel_checked(x, y)
+                .map(|p| Color::Rgb(p[0], p[1], p[2]))
+        };
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let Some(top) = pixel(x as u32, y as u32 * 2) else {
+                    continue;
+                };
+                let cell = &mut buf[(area.x + x, area.y + y)];
+                cell.set_symbol("▀").set_fg(top);
+                if let Some(bottom) = pixel(x as u32, y as u32 * 2 + 1) {
+                    cell.set_bg(bottom);
+                }
+            }
         }
-        ImagePicker::Error(e) => Err(format!("Failed to create picker: {e}")),
-        ImagePicker::Disabled => Err("Image preview is disabled".into()),
     }
 }
 
@@ -79,6 +139,7 @@ fn build_image_protocol(
 pub struct ImagePreview<'a> {
     file_name: &'a str,
     file_version_id: Option<&'a str>,
+    cached: bool,
 }
 
 impl<'a> ImagePreview<'a> {
@@ -86,8 +147,14 @@ impl<'a> ImagePreview<'a> {
         Self {
             file_name,
             file_version_id,
+            cached: false,
         }
     }
+
+    pub fn cached(mut self, cached: bool) -> Self {
+        self.cached = cached;
+        self
+    }
 }
 
 impl StatefulWidget for ImagePreview<'_> {
@@ -103,6 +170,11 @@ impl StatefulWidget for ImagePreview<'_> {
         } else {
             format!("Preview [{}]", self.file_name)
         };
+        let title = if self.cached {
+            format!("{} (cached)", title)
+        } else {
+            title
+        };
         let block = Block::bordered().padding(Padding::uniform(1)).title(title);
         let image_area = block.inner(area);
 
@@ -112,7 +184,88 @@ impl StatefulWidget for ImagePreview<'_> {
             if let Some(protocol) = &mut state.protocol {
                 let image = StatefulImage::default();
                 image.render(image_area, buf, protocol);
+            } else if let Some(fallback) = &mut state.fallback {
+                fallback.render(image_area, buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn test_render_fallback() {
+        // 4x4 image, the top half is red and the bottom half is blue
+        let img = RgbImage::from_fn(4, 4, |_, y| {
+            if y < 2 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
             }
+        });
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+
+        let picker = ImagePicker::Error("no protocol".into());
+        let (mut state, msg) = ImagePreviewState::new(&bytes, picker);
+        assert!(msg.unwrap().starts_with("Showing a coarse preview"));
+
+        // the image area is 4x2 cells, which is 4x4 pixels
+        let area = Rect::new(0, 0, 8, 6);
+        let mut buf = Buffer::empty(area);
+        ImagePreview::new("a.png", None).render(area, &mut buf, &mut state);
+
+        let red = Color::Rgb(255, 0, 0);
+        let blue = Color::Rgb(0, 0, 255);
+        for x in 2..6 {
+            let top = &buf[(x, 2)];
+            assert_eq!((top.symbol(), top.fg, top.bg), ("▀", red, red));
+            let bottom = &buf[(x, 3)];
+            assert_eq!((bottom.symbol(), bottom.fg, bottom.bg), ("▀", blue, blue));
         }
     }
+
+    #[test]
+    fn test_decode_image_with_exif_orientation() {
+        let img = RgbImage::from_fn(4, 2, |x, _| {
+            if x < 2 {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 0])
+            }
+        });
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .unwrap();
+
+        // APP1 segment with the orientation 6 (rotated 90 degrees clockwise)
+        #[rustfmt::skip]
+        let exif: &[u8] = &[
+            0xFF, 0xE1, 0x00, 0x22,
+            b'E', b'x', b'i', b'f', 0x00, 0x00,
+            b'M', b'M', 0x00, 0x2A, 0x00, 0x00, 0x00, 0x08,
+            0x00, 0x01,
+            0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x06, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        // insert after SOI
+        let bytes = [&jpeg[..2], exif, &jpeg[2..]].concat();
+
+        let actual = decode_image(&bytes).unwrap();
+        assert_eq!((actual.width(), actual.height()), (2, 4));
+        // the left (white) half becomes the top
+        let pixel = |x, y| actual.to_luma8().get_pixel(x, y)[0];
+        assert!(pixel(0, 0) > 128);
+        assert!(pixel(0, 3) < 128);
+
+        let actual = decode_image(&jpeg).unwrap();
+        assert_eq!((actual.width(), actual.height()), (4, 2));
+    }
 }
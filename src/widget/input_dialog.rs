@@ -23,6 +23,10 @@ impl InputDialogState {
         self.input.value()
     }
 
+    pub fn set_input(&mut self, input: &str) {
+        self.input = Input::new(input.into());
+    }
+
     pub fn clear_input(&mut self) {
         self.input.reset();
     }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
@@ -8,6 +10,7 @@ use ratatui::{
 
 use crate::{
     color::ColorTheme,
+    format::format_elapsed,
     widget::{common::calc_centered_dialog_rect, Dialog},
 };
 
@@ -30,10 +33,27 @@ impl LoadingDialogColor {
 
 #[derive(Debug, Default)]
 pub struct LoadingDialog {
+    operation: String,
+    elapsed: Duration,
+    // lines shown below the loading message, such as the progress
+    details: Vec<String>,
     color: LoadingDialogColor,
 }
 
 impl LoadingDialog {
+    pub fn new(operation: impl Into<String>, elapsed: Duration) -> Self {
+        LoadingDialog {
+            operation: operation.into(),
+            elapsed,
+            ..Default::default()
+        }
+    }
+
+    pub fn details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
     pub fn theme(mut self, theme: &ColorTheme) -> Self {
         self.color = LoadingDialogColor::new(theme);
         self
@@ -42,10 +62,30 @@ impl LoadingDialog {
 
 impl Widget for LoadingDialog {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let area = calc_centered_dialog_rect(area, 30, 5);
+        let msg = format!("{} {}...", self.spinner(), self.operation);
+        let elapsed = format!("{} elapsed", format_elapsed(self.elapsed));
 
-        let text = Line::from(Self::MSG.fg(self.color.text).add_modifier(Modifier::BOLD));
-        let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
+        let max_width = self
+            .details
+            .iter()
+            .chain([&msg, &elapsed])
+            .map(|d| d.chars().count())
+            .max()
+            .unwrap_or(0);
+        let dialog_width = (max_width as u16 + 4).clamp(30, area.width);
+        let dialog_height = (self.details.len() as u16 + 6).min(area.height);
+        let area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let mut lines = vec![
+            Line::from(msg.fg(self.color.text).add_modifier(Modifier::BOLD)),
+            Line::from(elapsed.fg(self.color.text)),
+        ];
+        lines.extend(
+            self.details
+                .into_iter()
+                .map(|d| Line::from(d.fg(self.color.text))),
+        );
+        let paragraph = Paragraph::new(lines).alignment(Alignment::Center).block(
             Block::bordered()
                 .border_type(BorderType::Rounded)
                 .padding(Padding::vertical(1))
@@ -58,5 +98,80 @@ impl Widget for LoadingDialog {
 }
 
 impl LoadingDialog {
-    const MSG: &'static str = "Loading...";
+    const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    const SPINNER_INTERVAL_MILLIS: u128 = 100;
+
+    // the frame is derived from the elapsed time, so the dialog itself has no state to advance
+    fn spinner(&self) -> char {
+        let frame = self.elapsed.as_millis() / Self::SPINNER_INTERVAL_MILLIS;
+        Self::SPINNER[frame as usize % Self::SPINNER.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::set_cells;
+
+    use super::*;
+
+    #[test]
+    fn test_render_loading_dialog() {
+        let theme = ColorTheme::default();
+        let dialog =
+            LoadingDialog::new("Loading buckets", Duration::from_millis(3250)).theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 8));
+        dialog.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                                        ",
+            "     ╭────────────────────────────╮     ",
+            "     │                            │     ",
+            "     │    ⠹ Loading buckets...    │     ",
+            "     │         3s elapsed         │     ",
+            "     │                            │     ",
+            "     ╰────────────────────────────╯     ",
+            "                                        ",
+        ]);
+        set_cells! { expected =>
+            (10..30, [3]) => modifier: Modifier::BOLD,
+        }
+
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_render_loading_dialog_with_details() {
+        let theme = ColorTheme::default();
+        let details = vec![
+            "12,400 objects found so far...".to_string(),
+            "Press Esc to cancel".to_string(),
+        ];
+        let dialog = LoadingDialog::new("Listing objects", Duration::from_secs(75))
+            .details(details)
+            .theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 10));
+        dialog.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                                        ",
+            "   ╭────────────────────────────────╮   ",
+            "   │                                │   ",
+            "   │      ⠋ Listing objects...      │   ",
+            "   │         1m 15s elapsed         │   ",
+            "   │ 12,400 objects found so far... │   ",
+            "   │       Press Esc to cancel      │   ",
+            "   │                                │   ",
+            "   ╰────────────────────────────────╯   ",
+            "                                        ",
+        ]);
+        set_cells! { expected =>
+            (10..30, [3]) => modifier: Modifier::BOLD,
+        }
+
+        assert_eq!(buf, expected);
+    }
 }
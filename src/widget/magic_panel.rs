@@ -0,0 +1,303 @@
+use infer::MatcherType;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, Padding, Paragraph, Widget},
+};
+
+use crate::color::ColorTheme;
+
+// The content types which do not tell the format, so they are not considered as mislabeled
+const GENERIC_CONTENT_TYPES: &[&str] = &["application/octet-stream", "binary/octet-stream"];
+
+// The bytes to check for a binary, the same as git
+const BINARY_CHECK_SIZE: usize = 8000;
+
+// Whether the object should be shown as a hexdump instead of a text.
+// The formats detected by the magic number are binary, except the text ones like HTML and XML.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if let Some(t) = infer::get(bytes) {
+        return t.matcher_type() != MatcherType::Text;
+    }
+    bytes.iter().take(BINARY_CHECK_SIZE).any(|b| *b == 0)
+}
+
+// What the magic number of the object tells, compared with its name and Content-Type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagicInfo {
+    // None if no known signature is found
+    detected: Option<DetectedType>,
+    name_extension: Option<String>,
+    content_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DetectedType {
+    kind: &'static str,
+    mime_type: &'static str,
+    extension: &'static str,
+}
+
+impl MagicInfo {
+    pub fn new(bytes: &[u8], file_name: &str, content_type: &str) -> MagicInfo {
+        let detected = infer::get(bytes).map(|t| DetectedType {
+            kind: kind_name(t.matcher_type()),
+            mime_type: t.mime_type(),
+            extension: t.extension(),
+        });
+        let name_extension = file_name
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_lowercase())
+            .filter(|ext| !ext.is_empty() && !ext.contains('/'));
+        MagicInfo {
+            detected,
+            name_extension,
+            content_type: content_type.to_string(),
+        }
+    }
+
+    // e.g. `.jpeg` for `image/jpeg` is not a mismatch even if the detected extension is `jpg`
+    fn extension_mismatch(&self) -> bool {
+        let Some(detected) = &self.detected else {
+            return false;
+        };
+        match &self.name_extension {
+            Some(ext) => {
+                ext != detected.extension
+                    && !mime_guess::from_ext(ext)
+                        .iter_raw()
+                        .any(|m| m == detected.mime_type)
+            }
+            None => true,
+        }
+    }
+
+    fn content_type_mismatch(&self) -> bool {
+        let Some(detected) = &self.detected else {
+            return false;
+        };
+        let essence = self
+            .content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        !essence.is_empty()
+            && !GENERIC_CONTENT_TYPES.contains(&essence)
+            && !essence.eq_ignore_ascii_case(detected.mime_type)
+    }
+
+    // The name and the value of each line, and whether it is a mismatch
+    fn items(&self) -> Vec<(&'static str, String, bool)> {
+        let mut items = Vec::new();
+        match &self.detected {
+            Some(detected) => {
+                let format = format!("{} ({})", detected.extension.to_uppercase(), detected.kind);
+                items.push(("Format", format, false));
+                items.push(("MIME type", detected.mime_type.to_string(), false));
+                let extension = match (&self.name_extension, self.extension_mismatch()) {
+                    (Some(ext), true) => {
+                        format!(".{} (the name has .{})", detected.extension, ext)
+                    }
+                    (None, true) => format!(".{} (the name has no extension)", detected.extension),
+                    (_, false) => format!(".{}", detected.extension),
+                };
+                items.push(("Extension", extension, self.extension_mismatch()));
+            }
+            None => {
+                items.push(("Format", "Unknown (no known signature)".into(), false));
+            }
+        }
+        let content_type = match self.content_type.as_str() {
+            "" => "-".to_string(),
+            s if self.content_type_mismatch() => format!("{} (does not match)", s),
+            s => s.to_string(),
+        };
+        items.push(("Content-Type", content_type, self.content_type_mismatch()));
+        items
+    }
+
+    pub fn height(&self) -> u16 {
+        self.items().len() as u16 + 2 /* border */
+    }
+}
+
+fn kind_name(matcher_type: MatcherType) -> &'static str {
+    match matcher_type {
+        MatcherType::App => "application",
+        MatcherType::Archive => "archive",
+        MatcherType::Audio => "audio",
+        MatcherType::Book => "book",
+        MatcherType::Doc => "document",
+        MatcherType::Font => "font",
+        MatcherType::Image => "image",
+        MatcherType::Text => "text",
+        MatcherType::Video => "video",
+        MatcherType::Custom => "custom",
+    }
+}
+
+#[derive(Debug, Default)]
+struct MagicPanelColor {
+    block: Color,
+    name: Color,
+    value: Color,
+    mismatch: Color,
+}
+
+impl MagicPanelColor {
+    fn new(theme: &ColorTheme) -> MagicPanelColor {
+        MagicPanelColor {
+            block: theme.fg,
+            name: theme.fg,
+            value: theme.fg,
+            mismatch: theme.status_warn,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MagicPanel<'a> {
+    info: &'a MagicInfo,
+    color: MagicPanelColor,
+}
+
+impl<'a> MagicPanel<'a> {
+    pub fn new(info: &'a MagicInfo) -> Self {
+        Self {
+            info,
+            color: MagicPanelColor::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = MagicPanelColor::new(theme);
+        self
+    }
+}
+
+impl Widget for MagicPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .info
+            .items()
+            .into_iter()
+            .map(|(name, value, mismatch)| {
+                let value_color = if mismatch {
+                    self.color.mismatch
+                } else {
+                    self.color.value
+                };
+                Line::from(vec![
+                    format!("{:<14}", name).fg(self.color.name),
+                    value.fg(value_color),
+                ])
+            })
+            .collect();
+
+        let title = Title::from("File type");
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .fg(self.color.block)
+                    .padding(Padding::horizontal(1)),
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+    const ZIP: &[u8] = b"PK\x03\x04\x14\x00\x00\x00";
+
+    #[rstest]
+    #[case(PNG, true)]
+    #[case(ZIP, true)]
+    #[case(b"<?xml version=\"1.0\"?><a/>", false)]
+    #[case(b"hello\nworld\n", false)]
+    #[case(b"\x01\x02\x00\x03", true)]
+    fn test_is_binary(#[case] bytes: &[u8], #[case] expected: bool) {
+        assert_eq!(is_binary(bytes), expected);
+    }
+
+    #[rstest]
+    #[case("image.png", "image/png", false, false)]
+    #[case("image.PNG", "", false, false)]
+    #[case("image.bin", "application/octet-stream", true, false)]
+    #[case("image", "image/jpeg", true, true)]
+    #[case("dir.v1/image", "image/png; charset=binary", true, false)]
+    fn test_magic_info_mismatch(
+        #[case] file_name: &str,
+        #[case] content_type: &str,
+        #[case] extension_mismatch: bool,
+        #[case] content_type_mismatch: bool,
+    ) {
+        let info = MagicInfo::new(PNG, file_name, content_type);
+        assert_eq!(info.extension_mismatch(), extension_mismatch);
+        assert_eq!(info.content_type_mismatch(), content_type_mismatch);
+    }
+
+    #[test]
+    fn test_magic_info_jpeg_extension() {
+        let jpeg = b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00";
+        let info = MagicInfo::new(jpeg, "photo.jpeg", "image/jpeg");
+        assert!(!info.extension_mismatch());
+    }
+
+    #[test]
+    fn test_render_magic_panel() {
+        let theme = ColorTheme::default();
+        let info = MagicInfo::new(ZIP, "report.pdf", "application/pdf");
+        let panel = MagicPanel::new(&info).theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 54, info.height()));
+        panel.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let expected = [
+            "┌File type───────────────────────────────────────────┐",
+            "│ Format        ZIP (archive)                        │",
+            "│ MIME type     application/zip                      │",
+            "│ Extension     .zip (the name has .pdf)             │",
+            "│ Content-Type  application/pdf (does not match)     │",
+            "└────────────────────────────────────────────────────┘",
+        ];
+        let lines: Vec<String> = buf
+            .content()
+            .chunks(54)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn test_render_magic_panel_unknown() {
+        let info = MagicInfo::new(b"\x01\x02\x00\x03", "data", "");
+        let panel = MagicPanel::new(&info);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 46, info.height()));
+        panel.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let expected = [
+            "┌File type───────────────────────────────────┐",
+            "│ Format        Unknown (no known signature) │",
+            "│ Content-Type  -                            │",
+            "└────────────────────────────────────────────┘",
+        ];
+        let lines: Vec<String> = buf
+            .content()
+            .chunks(46)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+}
@@ -0,0 +1,245 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Stylize},
+    text::{Line, Span},
+    widgets::{
+        block::Title, Block, BorderType, List, ListItem, Padding, StatefulWidget, WidgetRef,
+    },
+};
+
+use crate::{
+    color::ColorTheme,
+    metadata::set_user_metadata,
+    object::ObjectMetadata,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+// Content-Type, Cache-Control and Content-Disposition are listed before the user-defined metadata
+const HEADER_COUNT: usize = 3;
+
+#[derive(Debug)]
+pub struct MetadataDialogState {
+    metadata: ObjectMetadata,
+    original: ObjectMetadata,
+    selected: usize,
+}
+
+impl MetadataDialogState {
+    pub fn new(metadata: ObjectMetadata) -> Self {
+        Self {
+            original: metadata.clone(),
+            metadata,
+            selected: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        HEADER_COUNT + self.metadata.user_metadata.len()
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = (self.selected + self.len() - 1) % self.len();
+    }
+
+    pub fn selected_title(&self) -> &'static str {
+        match self.selected {
+            0 => "Content-Type",
+            1 => "Cache-Control",
+            2 => "Content-Disposition",
+            _ => "Metadata value",
+        }
+    }
+
+    pub fn selected_value(&self) -> &str {
+        match self.selected {
+            0 => &self.metadata.content_type,
+            1 => &self.metadata.cache_control,
+            2 => &self.metadata.content_disposition,
+            i => &self.metadata.user_metadata[i - HEADER_COUNT].1,
+        }
+    }
+
+    pub fn set_selected_value(&mut self, value: String) {
+        match self.selected {
+            0 => self.metadata.content_type = value,
+            1 => self.metadata.cache_control = value,
+            2 => self.metadata.content_disposition = value,
+            i => self.metadata.user_metadata[i - HEADER_COUNT].1 = value,
+        }
+    }
+
+    pub fn add_user_metadata(&mut self, name: String, value: String) {
+        let i = set_user_metadata(&mut self.metadata.user_metadata, name, value);
+        self.selected = HEADER_COUNT + i;
+    }
+
+    // The user-defined metadata is removed, and the header is cleared
+    pub fn remove_selected(&mut self) {
+        if self.selected < HEADER_COUNT {
+            self.set_selected_value(String::new());
+        } else {
+            self.metadata
+                .user_metadata
+                .remove(self.selected - HEADER_COUNT);
+            self.selected = self.selected.min(self.len() - 1);
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.metadata != self.original
+    }
+
+    pub fn metadata(&self) -> &ObjectMetadata {
+        &self.metadata
+    }
+
+    fn items(&self) -> Vec<(String, &str)> {
+        let headers = [
+            (
+                "Content-Type".to_string(),
+                self.metadata.content_type.as_str(),
+            ),
+            (
+                "Cache-Control".to_string(),
+                self.metadata.cache_control.as_str(),
+            ),
+            (
+                "Content-Disposition".to_string(),
+                self.metadata.content_disposition.as_str(),
+            ),
+        ];
+        let user_metadata = self
+            .metadata
+            .user_metadata
+            .iter()
+            .map(|(name, value)| (format!("x-amz-meta-{}", name), value.as_str()));
+        headers.into_iter().chain(user_metadata).collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetadataDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+}
+
+impl MetadataDialogColor {
+    fn new(theme: &ColorTheme) -> MetadataDialogColor {
+        MetadataDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MetadataDialog {
+    color: MetadataDialogColor,
+}
+
+impl MetadataDialog {
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = MetadataDialogColor::new(theme);
+        self
+    }
+}
+
+impl StatefulWidget for MetadataDialog {
+    type State = MetadataDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let list_items: Vec<ListItem> = state
+            .items()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let line = Line::from(vec![
+                    Span::raw(format!("{}: ", name)).add_modifier(Modifier::BOLD),
+                    Span::raw(value.to_string()),
+                ]);
+                let item = ListItem::new(line);
+                if i == state.selected {
+                    item.fg(self.color.selected)
+                } else {
+                    item.fg(self.color.text)
+                }
+            })
+            .collect();
+
+        let dialog_width = (area.width - 4).min(70);
+        let dialog_height = (list_items.len() as u16 + 2/* border */).min(area.height - 4);
+        let area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let title = if state.is_changed() {
+            Title::from("Metadata (modified)")
+        } else {
+            Title::from("Metadata")
+        };
+        let list = List::new(list_items).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .padding(Padding::horizontal(1))
+                .bg(self.color.bg)
+                .fg(self.color.block),
+        );
+        let dialog = Dialog::new(Box::new(list), self.color.bg);
+        dialog.render_ref(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ObjectMetadata {
+        ObjectMetadata {
+            content_type: "text/plain".into(),
+            cache_control: "".into(),
+            content_disposition: "".into(),
+            user_metadata: vec![("foo".into(), "bar".into())],
+        }
+    }
+
+    #[test]
+    fn test_edit_metadata() {
+        let mut state = MetadataDialogState::new(metadata());
+        assert!(!state.is_changed());
+
+        state.select_next();
+        assert_eq!(state.selected_title(), "Cache-Control");
+        state.set_selected_value("no-cache".into());
+
+        state.add_user_metadata("baz".into(), "qux".into());
+        assert_eq!(state.selected_value(), "qux");
+
+        state.select_prev();
+        assert_eq!(state.selected_value(), "bar");
+        state.remove_selected();
+        assert_eq!(state.selected_value(), "qux");
+
+        state.select_next();
+        assert_eq!(state.selected_title(), "Content-Type");
+        state.remove_selected();
+
+        assert!(state.is_changed());
+        assert_eq!(
+            state.metadata(),
+            &ObjectMetadata {
+                content_type: "".into(),
+                cache_control: "no-cache".into(),
+                content_disposition: "".into(),
+                user_metadata: vec![("baz".into(), "qux".into())],
+            }
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use itsuki::zero_indexed_enum;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, BorderType, List, ListItem, Padding, Widget, WidgetRef},
+};
+
+use crate::{
+    color::ColorTheme,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+pub const DEFAULT_PRESIGN_EXPIRES_IN: Duration = Duration::from_secs(60 * 60);
+
+// SigV4 presigned URLs can be valid for at most 7 days
+const MAX_PRESIGN_EXPIRES_IN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Default)]
+#[zero_indexed_enum]
+pub enum PresignExpiry {
+    Minutes15,
+    #[default]
+    Hour1,
+    Hours24,
+    Custom,
+}
+
+impl PresignExpiry {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Minutes15 => "15 minutes",
+            Self::Hour1 => "1 hour",
+            Self::Hours24 => "24 hours",
+            Self::Custom => "Custom...",
+        }
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Self::Minutes15 => Some(Duration::from_secs(15 * 60)),
+            Self::Hour1 => Some(DEFAULT_PRESIGN_EXPIRES_IN),
+            Self::Hours24 => Some(Duration::from_secs(24 * 60 * 60)),
+            Self::Custom => None,
+        }
+    }
+}
+
+/// Parses an expiry such as `90s`, `30m`, `12h` or `7d` (a bare number is treated as seconds).
+pub fn parse_presign_expires_in(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (num, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let n: u64 = num.parse().ok()?;
+    let secs = match unit.trim() {
+        "s" => n,
+        "m" => n.checked_mul(60)?,
+        "h" => n.checked_mul(60 * 60)?,
+        "d" => n.checked_mul(24 * 60 * 60)?,
+        _ => return None,
+    };
+    let duration = Duration::from_secs(secs);
+    (secs > 0 && duration <= MAX_PRESIGN_EXPIRES_IN).then_some(duration)
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PresignDialogState {
+    selected: PresignExpiry,
+}
+
+impl PresignDialogState {
+    pub fn select_next(&mut self) {
+        self.selected = self.selected.next();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.prev();
+    }
+
+    pub fn selected(&self) -> PresignExpiry {
+        self.selected
+    }
+}
+
+#[derive(Debug, Default)]
+struct PresignDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+}
+
+impl PresignDialogColor {
+    fn new(theme: &ColorTheme) -> PresignDialogColor {
+        PresignDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+        }
+    }
+}
+
+pub struct PresignDialog {
+    state: PresignDialogState,
+    color: PresignDialogColor,
+}
+
+impl PresignDialog {
+    pub fn new(state: PresignDialogState) -> Self {
+        Self {
+            state,
+            color: PresignDialogColor::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = PresignDialogColor::new(theme);
+        self
+    }
+}
+
+impl Widget for PresignDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let list_items: Vec<ListItem> = PresignExpiry::vars_vec()
+            .iter()
+            .map(|expiry| {
+                let item = ListItem::new(Line::raw(expiry.str()));
+                if *expiry == self.state.selected {
+                    item.fg(self.color.selected)
+                } else {
+                    item.fg(self.color.text)
+                }
+            })
+            .collect();
+
+        let dialog_width = (area.width - 4).min(30);
+        let dialog_height = list_items.len() as u16 + 2 /* border */;
+        let area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let title = Title::from("Presigned URL expiry");
+        let list = List::new(list_items).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .padding(Padding::horizontal(1))
+                .bg(self.color.bg)
+                .fg(self.color.block),
+        );
+        let dialog = Dialog::new(Box::new(list), self.color.bg);
+        dialog.render_ref(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("90s", Some(90))]
+    #[case("30m", Some(30 * 60))]
+    #[case(" 12h ", Some(12 * 60 * 60))]
+    #[case("7d", Some(7 * 24 * 60 * 60))]
+    #[case("600", Some(600))]
+    #[case("8d", None)]
+    #[case("0m", None)]
+    #[case("1w", None)]
+    #[case("h", None)]
+    #[case("", None)]
+    fn test_parse_presign_expires_in(#[case] s: &str, #[case] expected: Option<u64>) {
+        let actual = parse_presign_expires_in(s);
+        assert_eq!(actual, expected.map(Duration::from_secs));
+    }
+}
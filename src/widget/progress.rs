@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::Line,
+    widgets::{Block, LineGauge, Padding, Widget},
+};
+
+use crate::{
+    color::ColorTheme,
+    format::{format_elapsed, format_size_byte},
+};
+
+#[derive(Debug, Default)]
+struct ProgressBarColor {
+    fg: Color,
+    filled: Color,
+    unfilled: Color,
+}
+
+impl ProgressBarColor {
+    fn new(theme: &ColorTheme) -> Self {
+        ProgressBarColor {
+            fg: theme.fg,
+            filled: theme.status_info,
+            unfilled: theme.divider,
+        }
+    }
+}
+
+// Shows the progress of a transfer in the footer, with the speed and the estimated time remaining
+#[derive(Debug)]
+pub struct ProgressBar<'a> {
+    name: &'a str,
+    current: usize,
+    total: usize,
+    elapsed: Duration,
+    color: ProgressBarColor,
+}
+
+impl<'a> ProgressBar<'a> {
+    pub fn new(name: &'a str, current: usize, total: usize, elapsed: Duration) -> Self {
+        ProgressBar {
+            name,
+            current,
+            total,
+            elapsed,
+            color: ProgressBarColor::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = ProgressBarColor::new(theme);
+        self
+    }
+
+    fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.current as f64 / self.total as f64).clamp(0.0, 1.0)
+    }
+
+    // bytes per second, None until anything is transferred
+    fn speed(&self) -> Option<f64> {
+        let secs = self.elapsed.as_secs_f64();
+        (self.current > 0 && secs > 0.0).then(|| self.current as f64 / secs)
+    }
+
+    fn detail(&self) -> String {
+        let size = format!(
+            "{} / {}",
+            format_size_byte(self.current),
+            format_size_byte(self.total)
+        );
+        match self.speed() {
+            Some(speed) => {
+                let remaining = self.total.saturating_sub(self.current) as f64 / speed;
+                format!(
+                    "{}  {}/s  ETA {}",
+                    size,
+                    format_size_byte(speed as usize),
+                    format_elapsed(Duration::from_secs_f64(remaining.ceil()))
+                )
+            }
+            None => format!("{}  -/s  ETA -", size),
+        }
+    }
+}
+
+impl Widget for ProgressBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().padding(Padding::horizontal(2));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let name = format!("{} ", self.name);
+        let detail = format!("  {}", self.detail());
+        let [name_area, gauge_area, detail_area] = Layout::horizontal([
+            Constraint::Length(name.len() as u16),
+            Constraint::Fill(1),
+            Constraint::Length(detail.len() as u16),
+        ])
+        .areas(inner);
+
+        Line::from(name.fg(self.color.fg)).render(name_area, buf);
+        let gauge = LineGauge::default()
+            .ratio(self.ratio())
+            .label(format!("{:>3}%", (self.ratio() * 100.0) as usize))
+            .line_set(symbols::line::THICK)
+            .style(Style::default().fg(self.color.fg))
+            .filled_style(Style::default().fg(self.color.filled))
+            .unfilled_style(Style::default().fg(self.color.unfilled));
+        gauge.render(gauge_area, buf);
+        Line::from(detail.fg(self.color.fg)).render(detail_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mib = 1024 * 1024;
+        let theme = ColorTheme::default();
+        let progress =
+            ProgressBar::new("Loading preview", 3 * mib, 8 * mib, Duration::from_secs(3))
+                .theme(&theme);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 2));
+        progress.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "  Loading preview  37% ━━━━━━━━━━━━━━━━━━━━━━━  3 MiB / 8 MiB  1 MiB/s  ETA 5s  ",
+            "                                                                                ",
+        ]);
+        let lines = |buf: &Buffer| -> Vec<String> {
+            buf.content()
+                .chunks(80)
+                .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+                .collect()
+        };
+        assert_eq!(lines(&buf), lines(&expected));
+        // the gauge is filled up to 37%
+        assert_eq!(buf[(23, 0)].fg, theme.status_info);
+        assert_eq!(buf[(31, 0)].fg, theme.divider);
+    }
+
+    #[test]
+    fn test_detail_before_transferred() {
+        let progress = ProgressBar::new("Computing MD5", 0, 1024, Duration::ZERO);
+        assert_eq!(progress.detail(), "0 B / 1 KiB  -/s  ETA -");
+        assert_eq!(progress.ratio(), 0.0);
+    }
+}
@@ -2,9 +2,10 @@ use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
-    text::Line,
-    widgets::{block::BlockExt, Block, Borders, Padding, Paragraph, StatefulWidget, Widget, Wrap},
+    text::{Line, Span},
+    widgets::{block::BlockExt, Block, Borders, Padding, Paragraph, StatefulWidget, Widget},
 };
+use textwrap::core::display_width;
 
 use crate::{color::ColorTheme, util::digits};
 
@@ -26,11 +27,26 @@ enum ScrollEvent {
 pub struct ScrollLinesOptions {
     pub number: bool,
     pub wrap: bool,
+    // break lines at word boundaries instead of at the exact width
+    pub word_wrap: bool,
+    // indent continuation lines to the leading whitespace of the original line
+    pub hanging_indent: bool,
 }
 
 impl ScrollLinesOptions {
     pub fn new(number: bool, wrap: bool) -> Self {
-        Self { number, wrap }
+        Self {
+            number,
+            wrap,
+            word_wrap: true,
+            hanging_indent: false,
+        }
+    }
+
+    pub fn wrap_options(mut self, word_wrap: bool, hanging_indent: bool) -> Self {
+        self.word_wrap = word_wrap;
+        self.hanging_indent = hanging_indent;
+        self
     }
 }
 
@@ -40,11 +56,18 @@ impl Default for ScrollLinesOptions {
     }
 }
 
+// Lines longer than this are split into multiple lines in advance,
+// since rendering (and wrapping) a very long line such as minified JS on every frame is too slow.
+const LONG_LINE_CHUNK_WIDTH: usize = 2000;
+
 #[derive(Debug, Default)]
 pub struct ScrollLinesState {
     lines: Vec<Line<'static>>,
+    // the original line number of each line, None for the continuation of a split long line
+    line_numbers: Vec<Option<usize>>,
     max_digits: usize,
-    max_line_width: usize,
+    // calculated only when it is needed for horizontal scrolling
+    max_line_width: Option<usize>,
     v_offset: usize,
     h_offset: usize,
     options: ScrollLinesOptions,
@@ -54,17 +77,83 @@ pub struct ScrollLinesState {
 impl ScrollLinesState {
     pub fn new(lines: Vec<Line<'static>>, options: ScrollLinesOptions) -> Self {
         let max_digits = digits(lines.len());
-        let max_line_width = lines.iter().map(Line::width).max().unwrap_or_default();
+        let (lines, line_numbers) = split_long_lines(lines);
+
+        Self {
+            lines,
+            line_numbers,
+            max_digits,
+            options,
+            ..Default::default()
+        }
+    }
+
+    // Lines with the given line numbers, None is shown without the number.
+    // Long lines are not split so that the lines correspond to the numbers.
+    pub fn with_line_numbers(
+        lines: Vec<Line<'static>>,
+        line_numbers: Vec<Option<usize>>,
+        options: ScrollLinesOptions,
+    ) -> Self {
+        let max_digits = digits(line_numbers.iter().flatten().max().copied().unwrap_or(0));
 
         Self {
             lines,
+            line_numbers,
             max_digits,
-            max_line_width,
             options,
             ..Default::default()
         }
     }
 
+    // The scroll position and the options are kept
+    pub fn set_lines(&mut self, lines: Vec<Line<'static>>) {
+        self.max_digits = digits(lines.len());
+        (self.lines, self.line_numbers) = split_long_lines(lines);
+        self.max_line_width = None;
+        self.v_offset = self.v_offset.min(self.lines.len().saturating_sub(1));
+    }
+
+    // Same as `set_lines`, but with the given line numbers like `with_line_numbers`
+    pub fn set_lines_with_line_numbers(
+        &mut self,
+        lines: Vec<Line<'static>>,
+        line_numbers: Vec<Option<usize>>,
+    ) {
+        self.max_digits = digits(line_numbers.iter().flatten().max().copied().unwrap_or(0));
+        self.lines = lines;
+        self.line_numbers = line_numbers;
+        self.max_line_width = None;
+        self.v_offset = self.v_offset.min(self.lines.len().saturating_sub(1));
+    }
+
+    // The original line number of the line at the top of the view
+    pub fn current_line_number(&self) -> Option<usize> {
+        self.line_numbers
+            .iter()
+            .take(self.v_offset + 1)
+            .rev()
+            .find_map(|n| *n)
+    }
+
+    // Scrolls so that the line with the number is at the top of the view
+    pub fn scroll_to_line_number(&mut self, number: usize) {
+        if let Some(i) = self.line_numbers.iter().position(|n| *n == Some(number)) {
+            self.v_offset = i;
+        }
+    }
+
+    // The number of lines from the top of the view to the end
+    pub fn remaining_lines(&self) -> usize {
+        self.lines.len().saturating_sub(self.v_offset)
+    }
+
+    fn max_line_width(&mut self) -> usize {
+        *self
+            .max_line_width
+            .get_or_insert_with(|| self.lines.iter().map(Line::width).max().unwrap_or_default())
+    }
+
     pub fn scroll_forward(&mut self) {
         self.scroll_event = ScrollEvent::Forward;
     }
@@ -169,7 +258,8 @@ impl StatefulWidget for ScrollLines {
             show_lines_count,
             self.color.line_number,
         );
-        let lines_paragraph = build_lines_paragraph(state, show_lines_count, self.color.block);
+        let lines_paragraph =
+            build_lines_paragraph(state, text_area_width, show_lines_count, self.color.block);
 
         self.block.map(|b| b.fg(self.color.block)).render(area, buf);
         line_numbers_paragraph.render(chunks[0], buf);
@@ -182,27 +272,28 @@ fn build_line_numbers_paragraph(
     text_area_width: usize,
     show_lines_count: usize,
     line_number_color: Color,
-) -> Paragraph {
-    // may not be correct because the wrap of the text is calculated separately...
+) -> Paragraph<'_> {
     let line_heights = wrapped_line_width_iter(
         &state.lines,
         state.v_offset,
         text_area_width,
         show_lines_count,
-        state.options.wrap,
+        &state.options,
     );
-    let lines_count = state.lines.len();
-    let line_numbers_content: Vec<Line> = ((state.v_offset + 1)..)
+    let line_numbers_content: Vec<Line> = state
+        .line_numbers
+        .iter()
+        .skip(state.v_offset)
         .zip(line_heights)
-        .flat_map(|(line, line_height)| {
-            if line > lines_count {
-                vec![Line::raw("")]
-            } else {
-                let line_number = format!("{:>width$}", line, width = state.max_digits);
-                let number_line: Line = line_number.fg(line_number_color).into();
-                let empty_lines = (0..(line_height - 1)).map(|_| Line::raw(""));
-                std::iter::once(number_line).chain(empty_lines).collect()
-            }
+        .flat_map(|(line_number, line_height)| {
+            let number_line: Line = match line_number {
+                Some(n) => format!("{:>width$}", n, width = state.max_digits)
+                    .fg(line_number_color)
+                    .into(),
+                None => Line::raw(""),
+            };
+            let empty_lines = (0..(line_height - 1)).map(|_| Line::raw(""));
+            std::iter::once(number_line).chain(empty_lines)
         })
         .take(show_lines_count)
         .collect();
@@ -216,16 +307,23 @@ fn build_line_numbers_paragraph(
 
 fn build_lines_paragraph(
     state: &ScrollLinesState,
+    text_area_width: usize,
     show_lines_count: usize,
     block_color: Color,
-) -> Paragraph {
-    let lines_content: Vec<Line> = state
+) -> Paragraph<'_> {
+    let lines = state
         .lines
         .iter()
         .skip(state.v_offset)
-        .take(show_lines_count)
-        .cloned()
-        .collect();
+        .take(show_lines_count);
+    let lines_content: Vec<Line> = if state.options.wrap {
+        lines
+            .flat_map(|line| wrap_line(line, text_area_width, &state.options))
+            .take(show_lines_count)
+            .collect()
+    } else {
+        lines.cloned().collect()
+    };
 
     let lines_paragraph = Paragraph::new(lines_content).block(
         Block::default()
@@ -235,7 +333,7 @@ fn build_lines_paragraph(
     );
 
     if state.options.wrap {
-        lines_paragraph.wrap(Wrap { trim: false })
+        lines_paragraph
     } else {
         lines_paragraph.scroll((0, state.h_offset as u16))
     }
@@ -260,7 +358,7 @@ fn handle_scroll_events(state: &mut ScrollLinesState, width: usize, height: usiz
                 state.v_offset,
                 width,
                 height,
-                state.options.wrap,
+                &state.options,
             );
             let mut add_offset = 0;
             let mut total_h = 0;
@@ -287,7 +385,7 @@ fn handle_scroll_events(state: &mut ScrollLinesState, width: usize, height: usiz
                 state.v_offset,
                 width,
                 height,
-                state.options.wrap,
+                &state.options,
             );
             let mut sub_offset = 0;
             let mut total_h = 0;
@@ -315,7 +413,7 @@ fn handle_scroll_events(state: &mut ScrollLinesState, width: usize, height: usiz
             state.v_offset = state.lines.len().saturating_sub(1);
         }
         ScrollEvent::Right => {
-            if state.h_offset < state.max_line_width.saturating_sub(1) {
+            if state.h_offset < state.max_line_width().saturating_sub(1) {
                 state.h_offset = state.h_offset.saturating_add(1);
             }
         }
@@ -329,22 +427,36 @@ fn handle_scroll_events(state: &mut ScrollLinesState, width: usize, height: usiz
     state.scroll_event = ScrollEvent::None;
 }
 
+fn split_long_lines(lines: Vec<Line<'static>>) -> (Vec<Line<'static>>, Vec<Option<usize>>) {
+    let options = ScrollLinesOptions::new(false, true).wrap_options(false, false);
+    let mut split_lines = Vec::with_capacity(lines.len());
+    let mut line_numbers = Vec::with_capacity(lines.len());
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.width() > LONG_LINE_CHUNK_WIDTH {
+            let chunks = wrap_line(&line, LONG_LINE_CHUNK_WIDTH, &options);
+            line_numbers.push(Some(i + 1));
+            line_numbers.extend(std::iter::repeat(None).take(chunks.len() - 1));
+            split_lines.extend(chunks);
+        } else {
+            split_lines.push(line);
+            line_numbers.push(Some(i + 1));
+        }
+    }
+    (split_lines, line_numbers)
+}
+
 fn wrapped_line_width_iter<'a>(
     lines: &'a [Line],
     offset: usize,
     width: usize,
     height: usize,
-    wrap: bool,
+    options: &'a ScrollLinesOptions,
 ) -> impl Iterator<Item = usize> + 'a {
-    lines.iter().skip(offset).take(height).map(move |line| {
-        if wrap {
-            let line_str = line_to_string(line);
-            let lines = textwrap::wrap(&line_str, width);
-            lines.len()
-        } else {
-            1
-        }
-    })
+    lines
+        .iter()
+        .skip(offset)
+        .take(height)
+        .map(move |line| wrapped_line_height(line, width, options))
 }
 
 fn wrapped_reversed_line_width_iter<'a>(
@@ -352,33 +464,116 @@ fn wrapped_reversed_line_width_iter<'a>(
     offset: usize,
     width: usize,
     height: usize,
-    wrap: bool,
+    options: &'a ScrollLinesOptions,
 ) -> impl Iterator<Item = usize> + 'a {
     lines
         .iter()
         .take(offset)
         .rev()
         .take(height)
-        .map(move |line| {
-            if wrap {
-                let line_str = line_to_string(line);
-                let lines = textwrap::wrap(&line_str, width);
-                lines.len()
-            } else {
-                1
-            }
-        })
+        .map(move |line| wrapped_line_height(line, width, options))
+}
+
+fn wrapped_line_height(line: &Line, width: usize, options: &ScrollLinesOptions) -> usize {
+    if options.wrap {
+        wrap_line(line, width, options).len()
+    } else {
+        1
+    }
 }
 
-fn line_to_string(line: &Line) -> String {
-    line.styled_graphemes(Style::default())
-        .map(|g| g.symbol)
-        .collect::<Vec<&str>>()
-        .concat()
+// The same function is used to render the lines and to calculate the heights of the line numbers,
+// so that the line numbers always match the wrapped lines.
+fn wrap_line<'a>(line: &Line<'a>, width: usize, options: &ScrollLinesOptions) -> Vec<Line<'a>> {
+    if width == 0 || line.width() <= width {
+        return vec![line.clone()];
+    }
+
+    let graphemes: Vec<(&str, Style)> = line
+        .styled_graphemes(Style::default())
+        .map(|g| (g.symbol, g.style))
+        .collect();
+    let is_whitespace = |s: &str| s.chars().all(char::is_whitespace);
+
+    let indent = if options.hanging_indent {
+        let indent_width: usize = graphemes
+            .iter()
+            .take_while(|(s, _)| is_whitespace(s))
+            .map(|(s, _)| display_width(s))
+            .sum();
+        // leave enough space for the content
+        indent_width.min(width / 2)
+    } else {
+        0
+    };
+
+    // the width from each grapheme to the end of the word containing it
+    let mut rest_word_widths = vec![0; graphemes.len() + 1];
+    for (i, (symbol, _)) in graphemes.iter().enumerate().rev() {
+        if !is_whitespace(symbol) {
+            rest_word_widths[i] = display_width(symbol) + rest_word_widths[i + 1];
+        }
+    }
+
+    let mut rows: Vec<Vec<(&str, Style)>> = Vec::new();
+    let mut row: Vec<(&str, Style)> = Vec::new();
+    let mut row_width = 0;
+    let mut available_width = width;
+    // the position in the row just after the last whitespace
+    let mut break_position: Option<usize> = None;
+
+    for (j, (symbol, style)) in graphemes.into_iter().enumerate() {
+        let w = display_width(symbol);
+        if row_width + w > available_width && !row.is_empty() {
+            available_width = width - indent;
+            let mut carry = Vec::new();
+            if options.word_wrap && !is_whitespace(symbol) {
+                if let Some(i) = break_position {
+                    // words longer than the line are split at the exact width
+                    let carry_width: usize = row[i..].iter().map(|(s, _)| display_width(s)).sum();
+                    if carry_width + rest_word_widths[j] <= available_width {
+                        carry = row.split_off(i);
+                    }
+                }
+            }
+            rows.push(std::mem::replace(&mut row, carry));
+            row_width = row.iter().map(|(s, _)| display_width(s)).sum();
+            break_position = None;
+            if options.word_wrap && is_whitespace(symbol) {
+                // the whitespace at the break is not displayed
+                continue;
+            }
+        }
+        row.push((symbol, style));
+        row_width += w;
+        if options.word_wrap && is_whitespace(symbol) {
+            break_position = Some(row.len());
+        }
+    }
+    rows.push(row);
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut spans: Vec<Span> = Vec::new();
+            if i > 0 && indent > 0 {
+                spans.push(Span::raw(" ".repeat(indent)));
+            }
+            for (symbol, style) in row {
+                match spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push_str(symbol),
+                    _ => spans.push(Span::styled(symbol.to_string(), style)),
+                }
+            }
+            Line::from(spans)
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use crate::set_cells;
 
     use super::*;
@@ -707,6 +902,80 @@ mod tests {
         assert_eq!(buf, expected);
     }
 
+    #[rstest]
+    #[case("aaa bbb ccc", 8, false, false, &["aaa bbb ", "ccc"])]
+    #[case("aaaa bbbb cccc", 8, false, false, &["aaaa bbb", "b cccc"])]
+    #[case("aaaa bbbb cccc", 8, true, false, &["aaaa ", "bbbb ", "cccc"])]
+    #[case("aaaaaaaaaaaa bb", 8, true, false, &["aaaaaaaa", "aaaa bb"])]
+    #[case("    aaaa bbbb", 8, false, true, &["    aaaa", "     bbb", "    b"])]
+    #[case("    aaaa bbbb cc", 10, true, true, &["    aaaa ", "    bbbb ", "    cc"])]
+    #[case("          aaaaaa", 8, false, true, &["        ", "      aa", "    aaaa"])]
+    fn test_wrap_line(
+        #[case] line: &str,
+        #[case] width: usize,
+        #[case] word_wrap: bool,
+        #[case] hanging_indent: bool,
+        #[case] expected: &[&str],
+    ) {
+        let options = ScrollLinesOptions::new(false, true).wrap_options(word_wrap, hanging_indent);
+        let actual: Vec<String> = wrap_line(&Line::raw(line), width, &options)
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wrap_line_keeps_styles() {
+        let line = Line::from(vec!["aaa ".red(), "bbb".blue()]);
+        let options = ScrollLinesOptions::new(false, true).wrap_options(true, false);
+        let actual = wrap_line(&line, 5, &options);
+        let expected = vec![
+            Line::from(vec!["aaa ".red()]),
+            Line::from(vec!["bbb".blue()]),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_scroll_lines_state_split_long_lines() {
+        let long_line = "a".repeat(LONG_LINE_CHUNK_WIDTH * 2 + 10);
+        let lines = vec![Line::raw("aaa"), Line::raw(long_line), Line::raw("bbb")];
+        let mut state = ScrollLinesState::new(lines, ScrollLinesOptions::default());
+
+        let widths: Vec<usize> = state.lines.iter().map(Line::width).collect();
+        assert_eq!(
+            widths,
+            vec![3, LONG_LINE_CHUNK_WIDTH, LONG_LINE_CHUNK_WIDTH, 10, 3]
+        );
+        assert_eq!(
+            state.line_numbers,
+            vec![Some(1), Some(2), None, None, Some(3)]
+        );
+        assert_eq!(state.max_digits, 1);
+        assert_eq!(state.max_line_width, None);
+        assert_eq!(state.max_line_width(), LONG_LINE_CHUNK_WIDTH);
+
+        state.scroll_forward();
+        render_scroll_lines(&mut state);
+        state.scroll_forward();
+        let buf = render_scroll_lines(&mut state);
+
+        // the continuation of the split line has no line number
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "┌TITLE─────────────┐",
+            "│   aaaaaaaaaaaaaa │",
+            "│   aaaaaaaaaaaaaa │",
+            "│   aaaaaaaaaaaaaa │",
+            "│   aaaaaaaaaaaaaa │",
+            "│   aaaaaaaaaaaaaa │",
+            "└──────────────────┘",
+        ]);
+
+        assert_eq!(buf, expected);
+    }
+
     fn state(number: bool, wrap: bool) -> ScrollLinesState {
         let lines: Vec<Line> = [
             "aaa bbb ccc ddd",
@@ -730,7 +999,7 @@ mod tests {
         .cloned()
         .map(Line::raw)
         .collect();
-        let options = ScrollLinesOptions { number, wrap };
+        let options = ScrollLinesOptions::new(number, wrap);
         ScrollLinesState::new(lines, options)
     }
 
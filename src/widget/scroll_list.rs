@@ -2,6 +2,7 @@ use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Margin, Rect},
     style::{Color, Stylize},
+    text::Line,
     widgets::{Block, List, ListItem, Padding, StatefulWidget, Widget},
 };
 
@@ -101,6 +102,32 @@ impl ScrollListState {
         self.offset = 0;
     }
 
+    pub fn select_index(&mut self, index: usize) {
+        if index >= self.total {
+            return;
+        }
+        self.selected = index;
+        if index < self.offset || index >= self.offset + self.height {
+            self.offset = index;
+        }
+    }
+
+    // Keeps the selected index as far as possible when the number of items changes
+    pub fn set_total(&mut self, total: usize) {
+        self.total = total;
+        if total == 0 {
+            self.selected = 0;
+            self.offset = 0;
+            return;
+        }
+        if self.selected >= total {
+            self.selected = total - 1;
+        }
+        if self.offset > self.selected {
+            self.offset = self.selected;
+        }
+    }
+
     pub fn select_last(&mut self) {
         if self.total == 0 {
             return;
@@ -130,17 +157,25 @@ impl ScrollListColor {
 #[derive(Debug)]
 pub struct ScrollList<'a> {
     items: Vec<ListItem<'a>>,
+    footer: Option<&'a str>,
     color: ScrollListColor,
 }
 
-impl ScrollList<'_> {
+impl<'a> ScrollList<'a> {
     pub fn new(items: Vec<ListItem>) -> ScrollList {
         ScrollList {
             items,
+            footer: None,
             color: Default::default(),
         }
     }
 
+    // Shown at the bottom left of the border
+    pub fn footer(mut self, footer: Option<&'a str>) -> Self {
+        self.footer = footer;
+        self
+    }
+
     pub fn theme(mut self, theme: &ColorTheme) -> Self {
         self.color = ScrollListColor::new(theme);
         self
@@ -154,13 +189,15 @@ impl StatefulWidget for ScrollList<'_> {
         state.height = area.height as usize - 2 /* border */;
 
         let title = format_list_count(state.total, state.selected);
-        let list = List::new(self.items).block(
-            Block::bordered()
-                .title(title)
-                .title_alignment(Alignment::Right)
-                .padding(Padding::horizontal(1))
-                .fg(self.color.block),
-        );
+        let mut block = Block::bordered()
+            .title(title)
+            .title_alignment(Alignment::Right)
+            .padding(Padding::horizontal(1))
+            .fg(self.color.block);
+        if let Some(footer) = self.footer {
+            block = block.title_bottom(Line::from(footer).left_aligned());
+        }
+        let list = List::new(self.items).block(block);
         Widget::render(list, area, buf);
 
         let area = area.inner(Margin::new(2, 1));
@@ -131,6 +131,10 @@ impl ObjectListSortDialogState {
         self.selected = ObjectListSortType::Default;
     }
 
+    pub fn select(&mut self, sort_type: ObjectListSortType) {
+        self.selected = sort_type;
+    }
+
     pub fn selected(&self) -> ObjectListSortType {
         self.selected
     }
@@ -0,0 +1,190 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, BorderType, Padding, Paragraph, StatefulWidget, WidgetRef},
+};
+
+use crate::{
+    color::ColorTheme,
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const MAX_LIST_HEIGHT: usize = 10;
+const MAX_DIALOG_WIDTH: u16 = 50;
+
+#[derive(Debug, Default)]
+pub struct SwitcherDialogState {
+    names: Vec<String>,
+    // the item used now, which is marked in the list
+    current: Option<usize>,
+    selected: usize,
+    offset: usize,
+}
+
+impl SwitcherDialogState {
+    pub fn new(names: Vec<String>, current: Option<&str>) -> Self {
+        let current = current.and_then(|c| names.iter().position(|n| n == c));
+        Self {
+            names,
+            current,
+            selected: current.unwrap_or_default(),
+            offset: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.names.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_name(&self) -> Option<&str> {
+        self.names.get(self.selected).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SwitcherDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    selected: Color,
+}
+
+impl SwitcherDialogColor {
+    fn new(theme: &ColorTheme) -> SwitcherDialogColor {
+        SwitcherDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            selected: theme.dialog_selected,
+        }
+    }
+}
+
+// Selects one of the items like profiles or regions, without filtering unlike the bucket switcher
+#[derive(Debug)]
+pub struct SwitcherDialog<'a> {
+    title: &'a str,
+    color: SwitcherDialogColor,
+}
+
+impl<'a> SwitcherDialog<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            color: SwitcherDialogColor::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = SwitcherDialogColor::new(theme);
+        self
+    }
+}
+
+impl StatefulWidget for SwitcherDialog<'_> {
+    type State = SwitcherDialogState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let list_height = state.names.len().clamp(1, MAX_LIST_HEIGHT);
+        let dialog_width = (area.width - 4).min(MAX_DIALOG_WIDTH);
+        let dialog_height = (list_height as u16 + 2/* border */).min(area.height);
+        let dialog_area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let list_height = (dialog_height as usize).saturating_sub(2).max(1);
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + list_height {
+            state.offset = state.selected + 1 - list_height;
+        }
+
+        let lines: Vec<Line> = state
+            .names
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(list_height)
+            .map(|(i, name)| {
+                let mark = if state.current == Some(i) { "* " } else { "  " };
+                let line = Line::from(format!("{}{}", mark, name));
+                if i == state.selected {
+                    line.fg(self.color.selected)
+                } else {
+                    line.fg(self.color.text)
+                }
+            })
+            .collect();
+
+        let title = Title::from(self.title);
+        let dialog_content = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .bg(self.color.bg)
+                .fg(self.color.block)
+                .padding(Padding::horizontal(1)),
+        );
+        let dialog = Dialog::new(Box::new(dialog_content), self.color.bg);
+        dialog.render_ref(dialog_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::set_cells;
+
+    use super::*;
+
+    fn state(current: Option<&str>) -> SwitcherDialogState {
+        let names = ["default", "dev", "prod"];
+        SwitcherDialogState::new(names.into_iter().map(String::from).collect(), current)
+    }
+
+    #[test]
+    fn test_switcher_dialog_state_select() {
+        let mut dev = state(Some("dev"));
+        assert_eq!(dev.selected_name(), Some("dev"));
+        dev.select_next();
+        dev.select_next();
+        assert_eq!(dev.selected_name(), Some("prod"));
+
+        let mut unknown = state(None);
+        assert_eq!(unknown.selected_name(), Some("default"));
+        unknown.select_prev();
+        assert_eq!(unknown.selected_name(), Some("default"));
+    }
+
+    #[test]
+    fn test_render_switcher_dialog() {
+        let theme = ColorTheme::default();
+        let mut state = state(Some("dev"));
+        state.select_next();
+        let dialog = SwitcherDialog::new("Switch profile").theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 7));
+        dialog.render(buf.area, &mut buf, &mut state);
+
+        #[rustfmt::skip]
+        let mut expected = Buffer::with_lines([
+            "                              ",
+            "  ╭Switch profile──────────╮  ",
+            "  │   default              │  ",
+            "  │ * dev                  │  ",
+            "  │   prod                 │  ",
+            "  ╰────────────────────────╯  ",
+            "                              ",
+        ]);
+        set_cells! { expected =>
+            (4..10, [4]) => fg: Color::Cyan,
+        }
+
+        assert_eq!(buf, expected);
+    }
+}
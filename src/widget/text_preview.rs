@@ -1,14 +1,18 @@
+use std::collections::BTreeSet;
+
 use ansi_to_tui::IntoText;
 use once_cell::sync::Lazy;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::{Color, Stylize},
     text::Line,
     widgets::{Block, StatefulWidget},
 };
 use syntect::{
     easy::HighlightLines,
-    highlighting::ThemeSet,
+    highlighting::{Theme, ThemeSet},
+    html::highlighted_html_for_string,
     parsing::SyntaxSet,
     util::{as_24_bit_terminal_escaped, LinesWithEndings},
 };
@@ -16,8 +20,11 @@ use syntect::{
 use crate::{
     color::ColorTheme,
     config::Config,
-    format::format_version,
+    diff::{diff_lines, DiffRow, DiffRowType},
+    error::{AppError, Result},
+    format::{format_size_byte, format_version},
     object::{FileDetail, RawObject},
+    outline::{Outline, OutlineFormat},
     util::extension_from_file_name,
     widget::{ScrollLines, ScrollLinesOptions, ScrollLinesState},
 };
@@ -47,6 +54,53 @@ static USER_THEME_SET: Lazy<ThemeSet> = Lazy::new(|| {
 #[derive(Debug)]
 pub struct TextPreviewState {
     pub scroll_lines_state: ScrollLinesState,
+    // only for the config files such as YAML
+    folding: Option<Folding>,
+}
+
+#[derive(Debug)]
+struct Folding {
+    outline: Outline,
+    // all the lines including the folded ones
+    lines: Vec<Line<'static>>,
+    // the start lines of the folded sections
+    folds: BTreeSet<usize>,
+}
+
+impl Folding {
+    fn new(file_name: &str, s: &str, lines: &[Line<'static>]) -> Option<Folding> {
+        let format = OutlineFormat::from_file_name(file_name)?;
+        Some(Folding {
+            outline: Outline::parse(format, s),
+            lines: lines.to_vec(),
+            folds: BTreeSet::new(),
+        })
+    }
+
+    // The lines except the folded ones, and their original line numbers
+    fn visible_lines(&self) -> (Vec<Line<'static>>, Vec<Option<usize>>) {
+        let mut lines = Vec::new();
+        let mut line_numbers = Vec::new();
+        let mut i = 0;
+        while i < self.lines.len() {
+            let mut line = self.lines[i].clone();
+            line_numbers.push(Some(i + 1));
+            let folded = self
+                .folds
+                .contains(&i)
+                .then(|| self.outline.section_starting_at(i))
+                .flatten();
+            if let Some((start, end)) = folded {
+                line.push_span(format!(" ... ({} lines)", end - start).dim());
+                lines.push(line);
+                i = end + 1;
+            } else {
+                lines.push(line);
+                i += 1;
+            }
+        }
+        (lines, line_numbers)
+    }
 }
 
 impl TextPreviewState {
@@ -55,30 +109,258 @@ impl TextPreviewState {
         object: &RawObject,
         highlight: bool,
         highlight_theme_name: &str,
+        scroll_lines_options: ScrollLinesOptions,
     ) -> (Self, Option<String>) {
-        let mut warn_msg = None;
+        let s = to_preview_string(&object.bytes);
+        let (lines, warn_msg) =
+            build_preview_lines(&s, &file_detail.name, highlight, highlight_theme_name);
+        let folding = Folding::new(&file_detail.name, &s, &lines);
+        let scroll_lines_state = ScrollLinesState::new(lines, scroll_lines_options);
 
+        let state = Self {
+            scroll_lines_state,
+            folding,
+        };
+        (state, warn_msg)
+    }
+
+    // Shows the raw bytes, e.g. of an image which cannot be decoded
+    pub fn new_hexdump(object: &RawObject) -> Self {
+        let lines = build_hexdump_lines(&object.bytes);
+        let scroll_lines_state =
+            ScrollLinesState::new(lines, ScrollLinesOptions::new(false, false));
+        Self {
+            scroll_lines_state,
+            folding: None,
+        }
+    }
+
+    // Same as `set_object`, but for the hexdump
+    pub fn set_hexdump_object(&mut self, object: &RawObject) {
+        let lines = build_hexdump_lines(&object.bytes);
+        self.scroll_lines_state.set_lines(lines);
+    }
+
+    // Replaces the content keeping the scroll position, e.g. when the rest of the object is loaded
+    pub fn set_object(
+        &mut self,
+        file_detail: &FileDetail,
+        object: &RawObject,
+        highlight: bool,
+        highlight_theme_name: &str,
+    ) {
         let s = to_preview_string(&object.bytes);
+        let (lines, _) =
+            build_preview_lines(&s, &file_detail.name, highlight, highlight_theme_name);
+        match (
+            self.folding.take(),
+            Folding::new(&file_detail.name, &s, &lines),
+        ) {
+            (Some(old), Some(mut folding)) if !old.folds.is_empty() => {
+                // the sections may have been extended by the loaded lines
+                folding.folds = old.folds;
+                self.folding = Some(folding);
+                self.update_folded_lines();
+            }
+            (_, folding) => {
+                self.folding = folding;
+                self.scroll_lines_state.set_lines(lines);
+            }
+        }
+    }
 
-        let lines: Vec<Line<'static>> =
-            match build_highlighted_lines(&s, &file_detail.name, highlight, highlight_theme_name) {
-                Ok(lines) => lines,
-                Err(msg) => {
-                    // If there is an error, display the original text
+    pub fn is_foldable(&self) -> bool {
+        self.folding.is_some()
+    }
+
+    // Folds the section at the top of the view, or unfolds it if it has been folded.
+    // Returns false if there is no section to fold.
+    pub fn toggle_fold(&mut self) -> bool {
+        let Some(line) = self.current_line() else {
+            return false;
+        };
+        let Some(folding) = self.folding.as_mut() else {
+            return false;
+        };
+        if !folding.folds.remove(&line) {
+            match folding.outline.section_at(line) {
+                Some((start, _)) => {
+                    folding.folds.insert(start);
+                }
+                None => return false,
+            }
+        }
+        self.update_folded_lines();
+        true
+    }
+
+    pub fn unfold_all(&mut self) {
+        if let Some(folding) = self.folding.as_mut() {
+            folding.folds.clear();
+            self.update_folded_lines();
+        }
+    }
+
+    // The key path of the line at the top of the view, e.g. `spec.containers[0].name`
+    pub fn current_key_path(&self) -> Option<&str> {
+        let line = self.current_line()?;
+        self.folding.as_ref()?.outline.key_path(line)
+    }
+
+    // 0-based index of the original line at the top of the view
+    fn current_line(&self) -> Option<usize> {
+        self.scroll_lines_state
+            .current_line_number()
+            .map(|n| n.saturating_sub(1))
+    }
+
+    fn update_folded_lines(&mut self) {
+        let Some(folding) = self.folding.as_ref() else {
+            return;
+        };
+        let current = self.current_line();
+        if folding.folds.is_empty() {
+            self.scroll_lines_state.set_lines(folding.lines.clone());
+        } else {
+            let (lines, line_numbers) = folding.visible_lines();
+            self.scroll_lines_state
+                .set_lines_with_line_numbers(lines, line_numbers);
+        }
+        // keep the top line, or the start of the section which it has been folded into
+        if let Some(line) = current {
+            let line = folding
+                .folds
+                .iter()
+                .filter_map(|start| folding.outline.section_starting_at(*start))
+                .filter(|(start, end)| *start <= line && line <= *end)
+                .map(|(start, _)| start)
+                .min()
+                .unwrap_or(line);
+            self.scroll_lines_state.scroll_to_line_number(line + 1);
+        }
+    }
+
+    // Builds the states of the both sides of the side-by-side diff.
+    // The lines are aligned by blank lines so that the both sides can be scrolled together,
+    // and the lines which differ are highlighted with the background color.
+    pub fn new_diff_pair(
+        file_name: &str,
+        left: &RawObject,
+        right: &RawObject,
+        highlight: bool,
+        highlight_theme_name: &str,
+        theme: &ColorTheme,
+    ) -> (Self, Self, Option<String>) {
+        let left_s = to_preview_string(&left.bytes);
+        let right_s = to_preview_string(&right.bytes);
+        let left_plain: Vec<&str> = left_s.lines().collect();
+        let right_plain: Vec<&str> = right_s.lines().collect();
+        let rows = diff_lines(&left_plain, &right_plain);
+
+        let mut warn_msg = None;
+        let mut build_lines = |s: &str| {
+            build_highlighted_lines(s, file_name, highlight, highlight_theme_name).unwrap_or_else(
+                |msg| {
                     if let Some(msg) = msg {
                         warn_msg = Some(msg);
                     }
                     s.lines().map(drop_control_chars).map(Line::raw).collect()
-                }
-            };
+                },
+            )
+        };
+        let left_lines = build_lines(&left_s);
+        let right_lines = build_lines(&right_s);
 
-        let scroll_lines_state = ScrollLinesState::new(lines, ScrollLinesOptions::default());
+        let build_state = |lines: Vec<Line<'static>>, index: fn(&DiffRow) -> Option<usize>, bg| {
+            let (lines, line_numbers) = align_diff_lines(lines, &rows, index, bg);
+            let options = ScrollLinesOptions::new(true, false);
+            let scroll_lines_state =
+                ScrollLinesState::with_line_numbers(lines, line_numbers, options);
+            Self {
+                scroll_lines_state,
+                folding: None,
+            }
+        };
+        let left_state = build_state(left_lines, |row| row.left, theme.preview_diff_removed_bg);
+        let right_state = build_state(right_lines, |row| row.right, theme.preview_diff_added_bg);
+        (left_state, right_state, warn_msg)
+    }
+}
 
-        let state = Self { scroll_lines_state };
-        (state, warn_msg)
+fn build_preview_lines(
+    s: &str,
+    file_name: &str,
+    highlight: bool,
+    highlight_theme_name: &str,
+) -> (Vec<Line<'static>>, Option<String>) {
+    match build_highlighted_lines(s, file_name, highlight, highlight_theme_name) {
+        Ok(lines) => (lines, None),
+        // If there is an error, display the original text
+        Err(msg) => (
+            s.lines().map(drop_control_chars).map(Line::raw).collect(),
+            msg,
+        ),
     }
 }
 
+const HEXDUMP_BYTES_PER_LINE: usize = 16;
+
+// e.g. "00000010  00 00 00 0d 49 48 44 52  00 00 00 01 00 00 00 01  |....IHDR........|"
+fn build_hexdump_lines(bytes: &[u8]) -> Vec<Line<'static>> {
+    bytes
+        .chunks(HEXDUMP_BYTES_PER_LINE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = (0..HEXDUMP_BYTES_PER_LINE)
+                .map(|j| match chunk.get(j) {
+                    Some(b) => format!("{:02x}", b),
+                    None => "  ".into(),
+                })
+                .collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            let half = HEXDUMP_BYTES_PER_LINE / 2;
+            Line::raw(format!(
+                "{:08x}  {}  {}  |{}|",
+                i * HEXDUMP_BYTES_PER_LINE,
+                hex[..half].join(" "),
+                hex[half..].join(" "),
+                ascii
+            ))
+        })
+        .collect()
+}
+
+fn align_diff_lines(
+    lines: Vec<Line<'static>>,
+    rows: &[DiffRow],
+    index: fn(&DiffRow) -> Option<usize>,
+    bg: Color,
+) -> (Vec<Line<'static>>, Vec<Option<usize>>) {
+    rows.iter()
+        .map(|row| match index(row) {
+            Some(i) => {
+                let line = lines.get(i).cloned().unwrap_or_default();
+                let line = if row.row_type == DiffRowType::Equal {
+                    line
+                } else {
+                    line.bg(bg)
+                };
+                (line, Some(i + 1))
+            }
+            None => (Line::default(), None),
+        })
+        .unzip()
+}
+
 fn to_preview_string(bytes: &[u8]) -> String {
     let s: String = String::from_utf8_lossy(bytes).into();
     // tab is not rendered correctly, so replace it
@@ -99,7 +381,7 @@ fn build_highlighted_lines(
     file_name: &str,
     highlight: bool,
     highlight_theme_name: &str,
-) -> Result<Vec<Line<'static>>, Option<String>> {
+) -> std::result::Result<Vec<Line<'static>>, Option<String>> {
     if !highlight {
         return Err(None);
     }
@@ -111,14 +393,10 @@ fn build_highlighted_lines(
             let msg = format!("No syntax definition found for `.{}`", extension);
             Some(msg)
         })?;
-    let theme = &DEFAULT_THEME_SET
-        .themes
-        .get(highlight_theme_name)
-        .or_else(|| USER_THEME_SET.themes.get(highlight_theme_name))
-        .ok_or_else(|| {
-            let msg = format!("Theme `{}` not found", highlight_theme_name);
-            Some(msg)
-        })?;
+    let theme = find_theme(highlight_theme_name).ok_or_else(|| {
+        let msg = format!("Theme `{}` not found", highlight_theme_name);
+        Some(msg)
+    })?;
     let mut h = HighlightLines::new(syntax, theme);
     let s = LinesWithEndings::from(s)
         .map(|line| {
@@ -131,10 +409,53 @@ fn build_highlighted_lines(
     Ok(s.into_text().unwrap().into_iter().collect())
 }
 
+fn find_theme(name: &str) -> Option<&'static Theme> {
+    DEFAULT_THEME_SET
+        .themes
+        .get(name)
+        .or_else(|| USER_THEME_SET.themes.get(name))
+}
+
+// Builds a standalone HTML document of the preview highlighted in the same way as the preview.
+// Files without a syntax definition are exported as plain text with the theme colors.
+pub fn build_highlighted_html(
+    object: &RawObject,
+    file_name: &str,
+    highlight_theme_name: &str,
+) -> Result<String> {
+    let s = to_preview_string(&object.bytes);
+
+    let extension = extension_from_file_name(file_name);
+    let syntax = SYNTAX_SET
+        .find_syntax_by_extension(&extension)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let theme = find_theme(highlight_theme_name)
+        .ok_or_else(|| AppError::msg(format!("Theme `{}` not found", highlight_theme_name)))?;
+    let body = highlighted_html_for_string(&s, &SYNTAX_SET, syntax, theme)
+        .map_err(|e| AppError::new("Failed to highlight preview", e))?;
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(file_name),
+        body
+    );
+    Ok(html)
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[derive(Debug)]
 pub struct TextPreview<'a> {
     file_name: &'a str,
     file_version_id: Option<&'a str>,
+    // loaded and total size, if only the beginning of the object is loaded
+    partial: Option<(usize, usize)>,
+    cached: bool,
 
     theme: &'a ColorTheme,
 }
@@ -148,9 +469,23 @@ impl<'a> TextPreview<'a> {
         Self {
             file_name,
             file_version_id,
+            partial: None,
+            cached: false,
             theme,
         }
     }
+
+    pub fn partial(mut self, partial: bool, loaded_size: usize, total_size: usize) -> Self {
+        if partial {
+            self.partial = Some((loaded_size, total_size));
+        }
+        self
+    }
+
+    pub fn cached(mut self, cached: bool) -> Self {
+        self.cached = cached;
+        self
+    }
 }
 
 impl StatefulWidget for TextPreview<'_> {
@@ -166,9 +501,82 @@ impl StatefulWidget for TextPreview<'_> {
         } else {
             format!("Preview [{}]", self.file_name)
         };
+        let title = match self.partial {
+            Some((loaded, total)) => format!(
+                "{} ({} / {} loaded)",
+                title,
+                format_size_byte(loaded),
+                format_size_byte(total)
+            ),
+            None => title,
+        };
+        let title = if self.cached {
+            format!("{} (cached)", title)
+        } else {
+            title
+        };
         ScrollLines::default()
             .block(Block::bordered().title(title))
             .theme(self.theme)
             .render(area, buf, &mut state.scroll_lines_state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_diff_lines() {
+        let rows = diff_lines(&["a", "b", "c"], &["a", "c", "d"]);
+        let bg = Color::Red;
+
+        let lines = vec![Line::raw("a"), Line::raw("b"), Line::raw("c")];
+        let (lines, line_numbers) = align_diff_lines(lines, &rows, |row| row.left, bg);
+        let texts: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        let bgs: Vec<Option<Color>> = lines.iter().map(|l| l.style.bg).collect();
+        assert_eq!(texts, ["a", "b", "c", ""]);
+        assert_eq!(line_numbers, [Some(1), Some(2), Some(3), None]);
+        assert_eq!(bgs, [None, Some(bg), None, None]);
+
+        let lines = vec![Line::raw("a"), Line::raw("c"), Line::raw("d")];
+        let (lines, line_numbers) = align_diff_lines(lines, &rows, |row| row.right, bg);
+        let texts: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(texts, ["a", "", "c", "d"]);
+        assert_eq!(line_numbers, [Some(1), None, Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_build_hexdump_lines() {
+        let bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x01";
+        let lines = build_hexdump_lines(bytes);
+        let texts: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            texts,
+            [
+                "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|",
+                "00000010  00 01                                             |..|",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_highlighted_html() {
+        let object = RawObject {
+            bytes: b"fn main() {}\n".to_vec(),
+        };
+        let html = build_highlighted_html(&object, "<main>.rs", "base16-ocean.dark").unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>&lt;main&gt;.rs</title>"));
+        assert!(html.contains("<span style=\"color:#b48ead;\">fn </span>"));
+
+        let object = RawObject {
+            bytes: b"a < b".to_vec(),
+        };
+        let html = build_highlighted_html(&object, "file.unknown", "base16-ocean.dark").unwrap();
+        assert!(html.contains("a &lt; b"));
+
+        assert!(build_highlighted_html(&object, "file.txt", "no-such-theme").is_err());
+    }
+}
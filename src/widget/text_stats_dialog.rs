@@ -0,0 +1,255 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{block::Title, Block, BorderType, Padding, Paragraph, Widget, WidgetRef},
+};
+
+use crate::{
+    color::ColorTheme,
+    format::{format_count, format_size_byte},
+    widget::{common::calc_centered_dialog_rect, Dialog},
+};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextStats {
+    lines: usize,
+    words: usize,
+    bytes: usize,
+    // the number of the characters and the line number (1-based)
+    longest_line: (usize, usize),
+    encoding: &'static str,
+    line_endings: String,
+}
+
+impl TextStats {
+    pub fn new(bytes: &[u8]) -> TextStats {
+        let s = String::from_utf8_lossy(bytes);
+        let longest_line = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (line.chars().count(), i + 1))
+            // the first one of the longest lines
+            .fold((0, 0), |max, cur| if cur.0 > max.0 { cur } else { max });
+        TextStats {
+            lines: s.lines().count(),
+            words: s.split_whitespace().count(),
+            bytes: bytes.len(),
+            longest_line,
+            encoding: detect_encoding(bytes),
+            line_endings: detect_line_endings(bytes),
+        }
+    }
+}
+
+fn detect_encoding(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(UTF8_BOM) {
+        return "UTF-8 (BOM)";
+    }
+    if bytes.starts_with(UTF16LE_BOM) {
+        return "UTF-16LE (BOM)";
+    }
+    if bytes.starts_with(UTF16BE_BOM) {
+        return "UTF-16BE (BOM)";
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(s) if s.is_ascii() => "ASCII",
+        Ok(_) => "UTF-8",
+        // the loaded range may end in the middle of a character
+        Err(e) if e.error_len().is_none() => "UTF-8",
+        Err(_) => "Unknown (not valid UTF-8)",
+    }
+}
+
+fn detect_line_endings(bytes: &[u8]) -> String {
+    let crlf = bytes.windows(2).filter(|w| w == b"\r\n").count();
+    let lf = bytes.iter().filter(|b| **b == b'\n').count() - crlf;
+    match (lf, crlf) {
+        (0, 0) => "None".into(),
+        (_, 0) => "LF".into(),
+        (0, _) => "CRLF".into(),
+        (lf, crlf) => format!(
+            "Mixed (LF: {}, CRLF: {})",
+            format_count(lf),
+            format_count(crlf)
+        ),
+    }
+}
+
+#[derive(Debug, Default)]
+struct TextStatsDialogColor {
+    bg: Color,
+    block: Color,
+    text: Color,
+    note: Color,
+}
+
+impl TextStatsDialogColor {
+    fn new(theme: &ColorTheme) -> TextStatsDialogColor {
+        TextStatsDialogColor {
+            bg: theme.bg,
+            block: theme.fg,
+            text: theme.fg,
+            note: theme.status_help,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TextStatsDialog<'a> {
+    stats: &'a TextStats,
+    // only the beginning of the object is loaded
+    partial: bool,
+    color: TextStatsDialogColor,
+}
+
+impl<'a> TextStatsDialog<'a> {
+    pub fn new(stats: &'a TextStats, partial: bool) -> Self {
+        Self {
+            stats,
+            partial,
+            color: TextStatsDialogColor::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: &ColorTheme) -> Self {
+        self.color = TextStatsDialogColor::new(theme);
+        self
+    }
+}
+
+impl Widget for TextStatsDialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let stats = self.stats;
+        let (longest_len, longest_number) = stats.longest_line;
+        let items = [
+            ("Lines", format_count(stats.lines)),
+            ("Words", format_count(stats.words)),
+            (
+                "Bytes",
+                format!(
+                    "{} ({})",
+                    format_count(stats.bytes),
+                    format_size_byte(stats.bytes)
+                ),
+            ),
+            (
+                "Longest line",
+                format!(
+                    "{} chars (line {})",
+                    format_count(longest_len),
+                    format_count(longest_number)
+                ),
+            ),
+            ("Encoding", stats.encoding.to_string()),
+            ("Line endings", stats.line_endings.clone()),
+        ];
+        let mut lines: Vec<Line> = items
+            .into_iter()
+            .map(|(name, value)| Line::from(format!("{:<14}{}", name, value).fg(self.color.text)))
+            .collect();
+        if self.partial {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(
+                "Only the loaded part is counted".fg(self.color.note),
+            ));
+        }
+
+        let content_width = lines.iter().map(Line::width).max().unwrap_or_default() as u16;
+        let dialog_width = (content_width + 4).min(area.width - 4);
+        let dialog_height = (lines.len() as u16 + 2).min(area.height);
+        let dialog_area = calc_centered_dialog_rect(area, dialog_width, dialog_height);
+
+        let title = Title::from("Stats");
+        let dialog_content = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .bg(self.color.bg)
+                .fg(self.color.block)
+                .padding(Padding::horizontal(1)),
+        );
+        let dialog = Dialog::new(Box::new(dialog_content), self.color.bg);
+        dialog.render_ref(dialog_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_text_stats() {
+        let stats =
+            TextStats::new("hello world\r\nこんにちは\r\n\r\nfoo bar baz qux\r\n".as_bytes());
+        assert_eq!(
+            stats,
+            TextStats {
+                lines: 4,
+                words: 7,
+                bytes: 49,
+                longest_line: (15, 4),
+                encoding: "UTF-8",
+                line_endings: "CRLF".into(),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(b"abc", "ASCII")]
+    #[case(b"\xEF\xBB\xBFabc", "UTF-8 (BOM)")]
+    #[case(b"\xFF\xFEa\x00", "UTF-16LE (BOM)")]
+    #[case(b"\xE3\x81\x82\xE3\x81", "UTF-8")]
+    #[case(b"\xFFabc", "Unknown (not valid UTF-8)")]
+    fn test_detect_encoding(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(detect_encoding(bytes), expected);
+    }
+
+    #[rstest]
+    #[case(b"abc", "None")]
+    #[case(b"a\nb\n", "LF")]
+    #[case(b"a\r\nb", "CRLF")]
+    #[case(b"a\r\nb\nc\n", "Mixed (LF: 2, CRLF: 1)")]
+    fn test_detect_line_endings(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(detect_line_endings(bytes), expected);
+    }
+
+    #[test]
+    fn test_render_text_stats_dialog() {
+        let theme = ColorTheme::default();
+        let stats = TextStats::new(b"foo bar\nbaz\n");
+        let dialog = TextStatsDialog::new(&stats, true).theme(&theme);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 12));
+        dialog.render(buf.area, &mut buf);
+
+        #[rustfmt::skip]
+        let expected = [
+            "                                        ",
+            "  ╭Stats────────────────────────────╮   ",
+            "  │ Lines         2                 │   ",
+            "  │ Words         3                 │   ",
+            "  │ Bytes         12 (12 B)         │   ",
+            "  │ Longest line  7 chars (line 1)  │   ",
+            "  │ Encoding      ASCII             │   ",
+            "  │ Line endings  LF                │   ",
+            "  │                                 │   ",
+            "  │ Only the loaded part is counted │   ",
+            "  ╰─────────────────────────────────╯   ",
+            "                                        ",
+        ];
+        let lines: Vec<String> = buf
+            .content()
+            .chunks(40)
+            .map(|cells| cells.iter().map(|c| c.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+}